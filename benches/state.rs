@@ -0,0 +1,166 @@
+//! 행사 당일 요청마다 실행되는 상태 연산의 성능을 측정합니다.
+//!
+//! ```sh
+//! cargo bench --bench state
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use gj_stamptour::{
+    config::Config,
+    progress::{record_completions, user_progress},
+    state::{CompletionList, Stamp, StampHistory, StampIdList, StampUserInfo, User, UserList},
+    storage::{from_database, to_database, DatabaseFormat},
+};
+use std::collections::HashMap;
+
+const STAMPS: usize = 30;
+const USERS: usize = 5000;
+
+fn stamp_id(index: usize) -> String {
+    format!("booth-{}", index)
+}
+
+fn user_id(index: usize) -> String {
+    format!("user-{}", index)
+}
+
+fn entry(user: usize) -> StampUserInfo {
+    StampUserInfo {
+        user_name: format!("방문객 {}", user),
+        user_id: user_id(user),
+        timestamp: chrono::Utc::now().to_string(),
+        seq: 0,
+    }
+}
+
+fn stamp_id_list() -> StampIdList {
+    StampIdList {
+        stamp_id_list: (0..STAMPS)
+            .map(|index| {
+                let stamp = Stamp {
+                    stampId: stamp_id(index),
+                    stampName: format!("스템프 {}", index),
+                    stampLocation: format!("{}번 부스", index),
+                    ..Default::default()
+                };
+                (stamp.stampId.clone(), stamp)
+            })
+            .collect(),
+    }
+}
+
+/// 유저마다 스템프를 3분의 1쯤 모은 행사 중반의 상태를 만듭니다.
+fn populated() -> (UserList, StampHistory) {
+    let mut user_list = UserList::default();
+    let mut history: HashMap<String, Vec<StampUserInfo>> = HashMap::new();
+    for user in 0..USERS {
+        user_list.insert(&mut User {
+            user_name: format!("방문객 {}", user),
+            user_id: user_id(user),
+            team_code: None,
+            user_code: None,
+        });
+        for stamp in (user % 3..STAMPS).step_by(3) {
+            history
+                .entry(stamp_id(stamp))
+                .or_default()
+                .push(entry(user));
+        }
+    }
+    (user_list, StampHistory::from(history))
+}
+
+fn register_user(c: &mut Criterion) {
+    let (user_list, _) = populated();
+    c.bench_function("register user", |b| {
+        b.iter_batched_ref(
+            || user_list.clone(),
+            |user_list| {
+                let mut user = User {
+                    user_name: "새 방문객".to_string(),
+                    user_id: user_id(USERS),
+                    team_code: None,
+                    user_code: None,
+                };
+                user_list.insert(&mut user);
+                black_box(user.user_code)
+            },
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+fn record_stamp(c: &mut Criterion) {
+    let config = Config::default();
+    let stamp_id_list = stamp_id_list();
+    let (_, stamp_history) = populated();
+    let mut completions = CompletionList::default();
+    let mut user = 0;
+    // `/stamp/` 처리와 같이 수량 제한을 확인하며 기록한 뒤 완주 등급을 갱신
+    c.bench_function("record stamp", |b| {
+        b.iter(|| {
+            user = (user + 1) % USERS;
+            let stamp_id = stamp_id((user + 1) % STAMPS);
+            stamp_history.push_if(&stamp_id, entry(user), |issued| issued < usize::MAX);
+            black_box(record_completions(
+                &config,
+                &stamp_id_list,
+                &stamp_history,
+                &mut completions,
+                &user_id(user),
+            ))
+        })
+    });
+}
+
+fn progress_lookup(c: &mut Criterion) {
+    let config = Config::default();
+    let stamp_id_list = stamp_id_list();
+    let (_, stamp_history) = populated();
+    c.bench_function("progress lookup", |b| {
+        b.iter(|| {
+            black_box(user_progress(
+                &config,
+                &stamp_id_list,
+                &stamp_history,
+                black_box("user-1234"),
+                "방문객 1234",
+            ))
+        })
+    });
+}
+
+fn serialize_history(c: &mut Criterion) {
+    let (_, stamp_history) = populated();
+    let mut group = c.benchmark_group("serialize history");
+    group.sample_size(20);
+    for format in DatabaseFormat::ALL {
+        group.bench_function(format.extension(), |b| {
+            b.iter(|| to_database("stamp_status", &stamp_history, format).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn deserialize_history(c: &mut Criterion) {
+    let (_, stamp_history) = populated();
+    let mut group = c.benchmark_group("deserialize history");
+    group.sample_size(20);
+    for format in DatabaseFormat::ALL {
+        let contents = to_database("stamp_status", &stamp_history, format).unwrap();
+        group.bench_function(format.extension(), |b| {
+            b.iter(|| from_database::<StampHistory>(black_box(&contents)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    register_user,
+    record_stamp,
+    progress_lookup,
+    serialize_history,
+    deserialize_history
+);
+criterion_main!(benches);
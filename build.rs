@@ -0,0 +1,5 @@
+fn main() {
+    // 개찰구 기기용 gRPC 서비스 코드 생성 (protoc를 따로 설치하지 않아도 되도록 내장된 protoc 사용)
+    std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path().unwrap());
+    tonic_build::compile_protos("proto/stamptour.proto").unwrap();
+}
@@ -0,0 +1,42 @@
+//! `/admin` 요청 본문과 관리자 명령 인수 해석을 검사합니다.
+
+#![no_main]
+
+use chrono::{NaiveDate, Utc};
+use gj_stamptour::{
+    announcement::parse_announce,
+    handlers::{parse_reset_user, parse_revoke},
+    partner::parse_partner_create,
+    state::{Command, StampIdList},
+};
+use libfuzzer_sys::fuzz_target;
+use std::collections::BTreeMap;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(command) = serde_json::from_slice::<Command>(data) else {
+        return;
+    };
+    let command = command.command;
+
+    if let Some(args) = command.strip_prefix("revoke ") {
+        if let Some((user, stamp_id, reason)) = parse_revoke(args) {
+            assert!(!user.is_empty() && !stamp_id.is_empty() && !reason.is_empty());
+        }
+    } else if let Some(args) = command.strip_prefix("reset user ") {
+        if let Some((user, reason)) = parse_reset_user(args) {
+            assert!(!user.is_empty() && !reason.is_empty());
+        }
+    } else if let Some(args) = command.strip_prefix("announce ") {
+        if let Some(announcement) = parse_announce(args, Utc::now()) {
+            assert!(!announcement.message.is_empty());
+        }
+    } else if let Some(args) = command.strip_prefix("partner create ") {
+        // 스템프가 없으므로 형식이 맞아도 항상 실패해야 함
+        let stamp_id_list = StampIdList {
+            stamp_id_list: BTreeMap::new(),
+        };
+        assert!(parse_partner_create(args, &stamp_id_list).is_err());
+    } else if let Some(date) = command.strip_prefix("daily report") {
+        let _ = date.trim().parse::<NaiveDate>();
+    }
+});
@@ -0,0 +1,17 @@
+//! `/check` 요청의 쿼리 문자열 해석을 검사합니다.
+
+#![no_main]
+
+use gj_stamptour::handlers::parse_check_query;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|query: &str| {
+    let parsed = parse_check_query(query);
+
+    // 스템프 ID가 비어 있지 않으면 쿼리 어딘가에 `s` 매개변수가 있어야 함
+    if !parsed.s.is_empty() {
+        assert!(query.contains('='));
+    }
+    // 같은 쿼리는 항상 같은 결과
+    assert_eq!(parse_check_query(query), parsed);
+});
@@ -0,0 +1,25 @@
+//! 요청 경로를 `resources` 폴더 안의 파일 경로로 바꾸는 함수를 검사합니다.
+
+#![no_main]
+
+use gj_stamptour::storage::{html_file_name, public_resource_path, PUBLIC_FOLDERS};
+use libfuzzer_sys::fuzz_target;
+use std::path::{Component, Path};
+
+fuzz_target!(|relative: &str| {
+    // 허용한 경로는 `resources` 밖이나 공개하지 않는 폴더를 가리키지 않아야 함
+    if let Some(path) = public_resource_path(relative) {
+        assert!(path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_))));
+        assert!(PUBLIC_FOLDERS
+            .iter()
+            .any(|folder| path.starts_with(folder)));
+    }
+
+    // HTML 파일 이름은 `resources/html` 바로 아래의 파일 하나만 가리켜야 함
+    if let Some(file) = html_file_name(relative) {
+        assert_eq!(Path::new(&file).components().count(), 1);
+        assert!(public_resource_path(&format!("html/{}", file)).is_some());
+    }
+});
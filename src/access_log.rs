@@ -0,0 +1,193 @@
+use actix_web::{
+    body::{BodySize, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    http::header::{REFERER, USER_AGENT},
+    middleware::Next,
+    web::Data,
+    HttpRequest,
+};
+use chrono::{DateTime, Local, Utc};
+use log::{error, warn};
+use std::{net::IpAddr, path::Path, sync::Mutex};
+
+use crate::config::Config;
+use crate::lock::MutexExt;
+use crate::logging::RotatingFile;
+
+/// Combined Log Format으로 접근 로그를 기록하는 구조체입니다.
+/// `log.access_file`이 설정되지 않았으면 아무것도 기록하지 않습니다.
+#[derive(Default)]
+pub struct AccessLog {
+    file: Option<Mutex<RotatingFile>>,
+    trusted_proxies: Vec<IpAddr>,
+}
+
+impl AccessLog {
+    /// 설정에 따라 접근 로그 파일을 엽니다. 파일을 열 수 없으면 오류를 남기고 기록하지 않습니다.
+    pub fn new(config: &Config) -> Self {
+        let file = config.log.access_file.as_ref().and_then(|path| {
+            RotatingFile::open(&config.log, Path::new(path))
+                .map_err(|e| error!("Cannot open access log {} : {}", path, e))
+                .ok()
+                .map(Mutex::new)
+        });
+        AccessLog {
+            file,
+            trusted_proxies: trusted_proxies(&config.server.trusted_proxies),
+        }
+    }
+
+    fn write(&self, line: &str) {
+        let Some(file) = &self.file else {
+            return;
+        };
+        if let Err(e) = file.lock_or_recover().write_at(line.as_bytes(), Utc::now()) {
+            error!("Failed to write access log : {}", e);
+        }
+    }
+}
+
+/// `server.trusted_proxies`의 주소를 읽습니다. 잘못된 주소는 경고를 남기고 무시합니다.
+fn trusted_proxies(addresses: &[String]) -> Vec<IpAddr> {
+    addresses
+        .iter()
+        .filter_map(|address| {
+            address
+                .parse()
+                .map_err(|_| warn!("Ignoring invalid trusted proxy address {}", address))
+                .ok()
+        })
+        .collect()
+}
+
+/// 요청을 보낸 방문객의 IP를 찾는 함수입니다.
+///
+/// 연결한 쪽이 `trusted_proxies`에 있거나 유닉스 소켓으로 연결된 리버스 프록시이면 `X-Forwarded-For`를
+/// 오른쪽부터 읽어 신뢰하지 않는 첫 주소를 사용합니다. 그 밖에는 연결한 주소를 그대로 사용하므로,
+/// 방문객이 직접 보낸 `X-Forwarded-For`로 IP를 속일 수 없습니다.
+///
+/// # Returns
+///
+/// IP 주소를 반환합니다. 알 수 없으면 `-`를 반환합니다.
+pub fn client_ip(req: &HttpRequest, trusted_proxies: &[IpAddr]) -> String {
+    let peer = req.peer_addr().map(|addr| addr.ip());
+    if peer.is_some_and(|ip| !trusted_proxies.contains(&ip)) {
+        return peer.unwrap().to_string();
+    }
+
+    let forwarded: Vec<IpAddr> = req
+        .headers()
+        .get_all("x-forwarded-for")
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|address| address.trim().parse().ok())
+        .collect();
+    forwarded
+        .iter()
+        .rev()
+        .find(|ip| !trusted_proxies.contains(ip))
+        .or(forwarded.first())
+        .or(peer.as_ref())
+        .map_or_else(|| "-".to_string(), |ip| ip.to_string())
+}
+
+/// 헤더 값을 큰따옴표 안에 넣을 수 있도록 바꿉니다. 값이 없으면 `-`를 반환합니다.
+fn quoted(value: Option<&str>) -> String {
+    match value {
+        Some(value) if !value.is_empty() => value.replace('\\', "\\\\").replace('"', "\\\""),
+        _ => "-".to_string(),
+    }
+}
+
+/// Combined Log Format 한 줄을 만드는 함수입니다.
+///
+/// # Arguments
+///
+/// * `client_ip` - 방문객 IP입니다.
+/// * `request_line` - `GET /check?s=a HTTP/1.1`과 같은 요청 줄입니다.
+/// * `bytes` - 응답 본문 크기입니다. 알 수 없거나 0이면 `-`로 기록합니다.
+///
+/// # Example
+///
+/// ```rust
+/// use chrono::{FixedOffset, TimeZone};
+/// use gj_stamptour::access_log::combined_line;
+///
+/// let time = FixedOffset::east_opt(9 * 3600).unwrap().with_ymd_and_hms(2024, 10, 5, 13, 5, 9).unwrap();
+/// assert_eq!(
+///     combined_line("203.0.113.7", "GET / HTTP/1.1", 200, Some(512), None, Some("Mozilla/5.0"), time),
+///     "203.0.113.7 - - [05/Oct/2024:13:05:09 +0900] \"GET / HTTP/1.1\" 200 512 \"-\" \"Mozilla/5.0\"\n"
+/// );
+/// ```
+pub fn combined_line<Tz: chrono::TimeZone>(
+    client_ip: &str,
+    request_line: &str,
+    status: u16,
+    bytes: Option<u64>,
+    referer: Option<&str>,
+    user_agent: Option<&str>,
+    time: DateTime<Tz>,
+) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    format!(
+        "{} - - [{}] \"{}\" {} {} \"{}\" \"{}\"\n",
+        client_ip,
+        time.format("%d/%b/%Y:%H:%M:%S %z"),
+        quoted(Some(request_line)),
+        status,
+        bytes
+            .filter(|bytes| *bytes > 0)
+            .map_or_else(|| "-".to_string(), |bytes| bytes.to_string()),
+        quoted(referer),
+        quoted(user_agent),
+    )
+}
+
+/// 요청마다 접근 로그를 한 줄씩 남기는 미들웨어입니다. URL 접두사를 제거하기 전의 원래 주소를 기록하도록
+/// 가장 바깥쪽에 둡니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// App::new()
+///     .wrap(from_fn(strip_base_path))
+///     .wrap(from_fn(log_access))
+///     .configure(|cfg| state.register(cfg));
+/// ```
+pub async fn log_access(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let access_log = req.app_data::<Data<AccessLog>>().cloned();
+    let Some(access_log) = access_log.filter(|access_log| access_log.file.is_some()) else {
+        return next.call(req).await;
+    };
+    let request_line = format!("{} {} {:?}", req.method(), req.uri(), req.version());
+    let client_ip = client_ip(req.request(), &access_log.trusted_proxies);
+    let header = |req: &ServiceRequest, name| {
+        req.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+    };
+    let (referer, user_agent) = (header(&req, REFERER), header(&req, USER_AGENT));
+
+    let res = next.call(req).await?;
+
+    let bytes = match res.response().body().size() {
+        BodySize::Sized(bytes) => Some(bytes),
+        _ => None,
+    };
+    access_log.write(&combined_line(
+        &client_ip,
+        &request_line,
+        res.status().as_u16(),
+        bytes,
+        referer.as_deref(),
+        user_agent.as_deref(),
+        Local::now(),
+    ));
+    Ok(res)
+}
@@ -0,0 +1,231 @@
+use chrono::NaiveTime;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::report::parse_timestamp;
+use crate::state::{AchievementRecord, CompletionList, StampHistory, StampIdList};
+
+/// `resources/config.json`의 `achievements` 항목 하나입니다. 스템프를 찍을 때마다 조건을 확인하여
+/// 새로 달성한 업적을 유저별로 기록합니다.
+///
+/// # Example
+///
+/// ```json
+/// { "achievement_id": "night-owl", "name": "올빼미", "rule": "stamped_between", "after": "20:00" }
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AchievementConfig {
+    pub achievement_id: String,
+    /// 진행 상황과 완주 페이지에 표시하는 업적 이름입니다.
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(flatten)]
+    pub rule: AchievementRule,
+}
+
+/// 업적을 달성하는 조건입니다. `rule`에 조건 종류를 적습니다.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum AchievementRule {
+    /// 완주 등급을 먼저 달성한 `limit`명에게 주는 업적입니다. `tier_name`이 없으면 가장 높은 등급을 기준으로 합니다.
+    FirstFinishers {
+        #[serde(default)]
+        tier_name: Option<String>,
+        limit: usize,
+    },
+    /// `stamp_ids`의 스템프(예: 먹거리 부스)를 모두 모은 유저에게 주는 업적입니다.
+    AllOf { stamp_ids: Vec<String> },
+    /// 서버 시간대 기준 `after`(`HH:MM`)부터 `before`까지 스템프를 찍은 유저에게 주는 업적입니다.
+    /// `before`가 없으면 자정까지이며, `before`가 `after`보다 이르면 자정을 넘는 시간대로 봅니다.
+    /// `stamp_ids`가 비어있으면 모든 스템프를 확인합니다.
+    StampedBetween {
+        after: String,
+        #[serde(default)]
+        before: Option<String>,
+        #[serde(default)]
+        stamp_ids: Vec<String>,
+    },
+}
+
+/// `/progress`와 완주 페이지에 표시하는 달성한 업적입니다.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EarnedAchievement {
+    pub achievement_id: String,
+    pub name: String,
+    pub description: String,
+    pub timestamp: String,
+}
+
+/// `HH:MM` 형식의 시각을 읽습니다.
+fn parse_time(time: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(time.trim(), "%H:%M").ok()
+}
+
+/// 시각이 `after`부터 `before` 전까지의 시간대에 있는지 확인합니다. `before`가 `after`보다 이르면 자정을 넘는 시간대입니다.
+///
+/// # Example
+///
+/// ```rust
+/// use chrono::NaiveTime;
+/// use gj_stamptour::achievements::in_time_window;
+///
+/// let time = |h| NaiveTime::from_hms_opt(h, 0, 0).unwrap();
+/// assert!(in_time_window(time(21), time(20), None));
+/// assert!(!in_time_window(time(19), time(20), None));
+/// assert!(in_time_window(time(1), time(20), Some(time(2))));
+/// assert!(!in_time_window(time(3), time(20), Some(time(2))));
+/// ```
+pub fn in_time_window(time: NaiveTime, after: NaiveTime, before: Option<NaiveTime>) -> bool {
+    match before {
+        None => time >= after,
+        Some(before) if before > after => time >= after && time < before,
+        Some(before) => time >= after || time < before,
+    }
+}
+
+/// 유저가 업적의 조건을 만족하는지 확인합니다.
+fn earned(
+    rule: &AchievementRule,
+    achievement_id: &str,
+    config: &Config,
+    stamp_id_list: &StampIdList,
+    stamp_history: &StampHistory,
+    completions: &CompletionList,
+    user_id: &str,
+) -> bool {
+    match rule {
+        AchievementRule::FirstFinishers { tier_name, limit } => {
+            let tier_name = match tier_name {
+                Some(tier_name) => tier_name.clone(),
+                None => match config.tiers(stamp_id_list.visible_count()).pop() {
+                    Some((tier_name, _)) => tier_name,
+                    None => return false,
+                },
+            };
+            let finished = completions
+                .completions
+                .get(user_id)
+                .is_some_and(|records| records.iter().any(|record| record.tier_name == tier_name));
+            let holders = completions
+                .achievements
+                .values()
+                .filter(|records| {
+                    records
+                        .iter()
+                        .any(|record| record.achievement_id == achievement_id)
+                })
+                .count();
+            finished && holders < *limit
+        }
+        AchievementRule::AllOf { stamp_ids } => {
+            let collected = stamp_history.collected_by(user_id);
+            !stamp_ids.is_empty()
+                && stamp_ids
+                    .iter()
+                    .all(|stamp_id| collected.contains(stamp_id))
+        }
+        AchievementRule::StampedBetween {
+            after,
+            before,
+            stamp_ids,
+        } => {
+            let Some(after) = parse_time(after) else {
+                return false;
+            };
+            let before = before.as_deref().and_then(parse_time);
+            stamp_history.to_map().iter().any(|(stamp_id, entries)| {
+                (stamp_ids.is_empty() || stamp_ids.contains(stamp_id))
+                    && entries
+                        .iter()
+                        .filter(|entry| entry.user_id == user_id)
+                        .filter_map(|entry| parse_timestamp(&entry.timestamp))
+                        .any(|timestamp| in_time_window(timestamp.time(), after, before))
+            })
+        }
+    }
+}
+
+/// 스템프가 기록될 때 새로 달성한 업적을 완주 기록에 추가하는 함수입니다.
+/// 완주 등급을 기준으로 하는 업적이 있으므로 `record_completions` 다음에 같은 락 안에서 호출합니다.
+///
+/// # Arguments
+///
+/// * `config` - 업적이 정의된 행사 설정입니다.
+/// * `stamp_id_list` - 전체 스템프 목록입니다.
+/// * `stamp_history` - 방금 스템프가 기록된 스템프 기록입니다.
+/// * `completions` - 유저별 완주 기록입니다. 업적 기록도 여기에 저장합니다.
+/// * `user_id` - 스템프를 찍은 유저 ID입니다.
+///
+/// # Returns
+///
+/// 이번에 새로 달성한 업적 이름 목록을 반환합니다.
+pub fn record_achievements(
+    config: &Config,
+    stamp_id_list: &StampIdList,
+    stamp_history: &StampHistory,
+    completions: &mut CompletionList,
+    user_id: &str,
+) -> Vec<String> {
+    let timestamp = chrono::prelude::Utc::now().to_string();
+    let mut reached = Vec::new();
+    for achievement in config.achievements.iter() {
+        let recorded = completions
+            .achievements
+            .get(user_id)
+            .is_some_and(|records| {
+                records
+                    .iter()
+                    .any(|record| record.achievement_id == achievement.achievement_id)
+            });
+        if recorded
+            || !earned(
+                &achievement.rule,
+                &achievement.achievement_id,
+                config,
+                stamp_id_list,
+                stamp_history,
+                completions,
+                user_id,
+            )
+        {
+            continue;
+        }
+        completions
+            .achievements
+            .entry(user_id.to_string())
+            .or_default()
+            .push(AchievementRecord {
+                achievement_id: achievement.achievement_id.clone(),
+                timestamp: timestamp.clone(),
+            });
+        reached.push(achievement.name.clone());
+    }
+    reached
+}
+
+/// 유저가 달성한 업적을 달성한 순서대로 반환합니다. 설정에서 지운 업적은 제외합니다.
+pub fn user_achievements(
+    config: &Config,
+    completions: &CompletionList,
+    user_id: &str,
+) -> Vec<EarnedAchievement> {
+    completions
+        .achievements
+        .get(user_id)
+        .into_iter()
+        .flatten()
+        .filter_map(|record| {
+            let achievement = config
+                .achievements
+                .iter()
+                .find(|achievement| achievement.achievement_id == record.achievement_id)?;
+            Some(EarnedAchievement {
+                achievement_id: achievement.achievement_id.clone(),
+                name: achievement.name.clone(),
+                description: achievement.description.clone(),
+                timestamp: record.timestamp.clone(),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,494 @@
+use actix_web::{get, web::Path, HttpResponse};
+use log::{error, info, warn};
+use openssl::{
+    asn1::Asn1Time,
+    ec::EcKey,
+    error::ErrorStack,
+    hash::MessageDigest,
+    pkey::{PKey, Private},
+    sha::sha256,
+    stack::Stack,
+    x509::{extension::SubjectAlternativeName, X509NameBuilder, X509ReqBuilder, X509},
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{cmp::Ordering, fs, path::Path as FsPath, sync::Arc, time::Duration};
+
+use crate::crypto::encode_base64_url;
+use crate::error::AppError;
+use crate::push::{p256, public_key, sign_es256};
+use crate::scheduler;
+use crate::storage::resource_path;
+use crate::tls::{certified_key, CertResolver, TlsConfig};
+
+/// HTTP-01 인증 토큰을 보관하는 `resources` 하위 폴더입니다.
+///
+/// `tls.acme`를 설정하면 서버의 ACME 클라이언트가 인증 중에 토큰 파일을 만들고 지웁니다.
+/// 외부 ACME 클라이언트를 사용할 때는 `certbot certonly --webroot -w resources ...`처럼
+/// `resources` 폴더를 웹루트로 지정하면 됩니다.
+pub const CHALLENGE_FOLDER: &str = ".well-known/acme-challenge";
+
+/// 인증서 만료일을 확인하는 주기입니다.
+const RENEWAL_CHECK_PERIOD: Duration = Duration::from_secs(12 * 60 * 60);
+
+/// 인증과 주문 상태를 다시 확인하기 전에 기다리는 시간과 최대 확인 횟수입니다.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+const POLL_ATTEMPTS: usize = 30;
+
+/// `resources/config.json`의 `tls.acme` 항목입니다. 설정하면 서버가 ACME(RFC 8555)로 인증서를 직접 발급받고,
+/// 만료가 가까워지면 갱신하여 서버를 다시 시작하지 않고 새 인증서로 바꿉니다.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct AcmeConfig {
+    /// ACME 디렉터리 주소입니다. 기본값은 Let's Encrypt입니다.
+    pub directory_url: String,
+    /// 인증서에 넣을 도메인입니다. 첫 번째 도메인이 인증서의 CN이 됩니다.
+    pub domains: Vec<String>,
+    /// 만료 안내를 받을 연락처 이메일입니다.
+    pub contact_email: Option<String>,
+    /// ACME 계정 키 파일입니다. 없으면 새로 만듭니다.
+    pub account_key_file: String,
+    /// 만료까지 남은 기간이 이 일수보다 짧으면 인증서를 갱신합니다.
+    pub renew_before_days: u32,
+}
+
+impl Default for AcmeConfig {
+    fn default() -> Self {
+        AcmeConfig {
+            directory_url: "https://acme-v02.api.letsencrypt.org/directory".to_string(),
+            domains: Vec::new(),
+            contact_email: None,
+            account_key_file: "resources/tls/account.pem".to_string(),
+            renew_before_days: 30,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Directory {
+    new_nonce: String,
+    new_account: String,
+    new_order: String,
+}
+
+#[derive(Deserialize)]
+struct Order {
+    status: String,
+    #[serde(default)]
+    authorizations: Vec<String>,
+    finalize: String,
+    certificate: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Authorization {
+    status: String,
+    challenges: Vec<Challenge>,
+}
+
+#[derive(Deserialize)]
+struct Challenge {
+    #[serde(rename = "type")]
+    kind: String,
+    url: String,
+    token: String,
+}
+
+/// ACME 토큰은 base64url 문자만 사용합니다.
+fn is_valid_token(token: &str) -> bool {
+    !token.is_empty()
+        && token
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Let's Encrypt 등의 HTTP-01 인증 요청에 토큰 파일 내용을 반환하는 비동기 함수입니다.
+///
+/// # Arguments
+///
+/// * `token` - 인증 서버가 요청한 토큰입니다.
+///
+/// # Returns
+///
+/// `resources/.well-known/acme-challenge/{token}` 파일이 있으면 내용을 담은 200 OK 응답이, 없거나 토큰 형식이 틀리면 404 응답이 반환됩니다.
+#[get("/.well-known/acme-challenge/{token}")]
+pub async fn handle_acme_challenge(token: Path<String>) -> Result<HttpResponse, AppError> {
+    if !is_valid_token(&token) {
+        return Err(AppError::NotFound);
+    }
+
+    let key_authorization = async_std::fs::read_to_string(resource_path(CHALLENGE_FOLDER, &token))
+        .await
+        .map_err(|_| AppError::NotFound)?;
+    info!("ACME challenge {} answered", token);
+    Ok(HttpResponse::Ok()
+        .content_type("text/plain")
+        .body(key_authorization))
+}
+
+/// ACME 계정 키를 읽습니다. 파일이 없으면 새 P-256 키를 만들어 저장합니다.
+pub fn load_account_key(path: &str) -> Result<EcKey<Private>, String> {
+    if let Ok(pem) = fs::read(path) {
+        return EcKey::private_key_from_pem(&pem)
+            .map_err(|e| format!("Invalid ACME account key {}: {}", path, e));
+    }
+    let key = p256()
+        .and_then(|group| EcKey::generate(&group))
+        .map_err(|e| e.to_string())?;
+    let pem = key.private_key_to_pem().map_err(|e| e.to_string())?;
+    write_file(path, &pem)?;
+    info!("Created a new ACME account key at {}", path);
+    Ok(key)
+}
+
+/// 계정 키의 JWK(RFC 7517)입니다.
+fn jwk(key: &EcKey<Private>) -> Value {
+    let point = public_key(key);
+    json!({
+        "crv": "P-256",
+        "kty": "EC",
+        "x": encode_base64_url(&point[1..33]),
+        "y": encode_base64_url(&point[33..]),
+    })
+}
+
+/// HTTP-01 인증 토큰 파일에 적는 키 인증 값(`토큰.JWK 지문`)입니다. (RFC 8555 8.1)
+pub fn key_authorization(token: &str, key: &EcKey<Private>) -> String {
+    // JWK 지문(RFC 7638)은 키 이름 순으로 정렬한 공백 없는 JSON의 SHA-256이며,
+    // serde_json의 객체는 키 이름 순으로 직렬화됩니다.
+    let thumbprint = sha256(jwk(key).to_string().as_bytes());
+    format!("{}.{}", token, encode_base64_url(&thumbprint))
+}
+
+/// 인증서 서명 요청(CSR)을 DER 형식으로 만듭니다.
+fn csr(domains: &[String], key: &PKey<Private>) -> Result<Vec<u8>, ErrorStack> {
+    let mut name = X509NameBuilder::new()?;
+    name.append_entry_by_text("CN", &domains[0])?;
+    let mut builder = X509ReqBuilder::new()?;
+    builder.set_subject_name(&name.build())?;
+    builder.set_pubkey(key)?;
+    let mut alt_names = SubjectAlternativeName::new();
+    for domain in domains {
+        alt_names.dns(domain);
+    }
+    let mut extensions = Stack::new()?;
+    extensions.push(alt_names.build(&builder.x509v3_context(None))?)?;
+    builder.add_extensions(&extensions)?;
+    builder.sign(key, MessageDigest::sha256())?;
+    builder.build().to_der()
+}
+
+fn write_file(path: &str, contents: &[u8]) -> Result<(), String> {
+    if let Some(parent) = FsPath::new(path).parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("{}: {}", path, e))?;
+    }
+    fs::write(path, contents).map_err(|e| format!("{}: {}", path, e))
+}
+
+/// 요청마다 계정 키로 서명(JWS)하고 재사용 방지 값(nonce)을 이어받는 ACME 클라이언트입니다.
+struct AcmeClient {
+    client: reqwest::Client,
+    directory: Directory,
+    key: EcKey<Private>,
+    kid: Option<String>,
+    nonce: Option<String>,
+}
+
+impl AcmeClient {
+    async fn new(directory_url: &str, key: EcKey<Private>) -> Result<Self, String> {
+        let client = reqwest::Client::new();
+        let directory = client
+            .get(directory_url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| format!("Failed to fetch ACME directory: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("Invalid ACME directory: {}", e))?;
+        Ok(AcmeClient {
+            client,
+            directory,
+            key,
+            kid: None,
+            nonce: None,
+        })
+    }
+
+    async fn nonce(&mut self) -> Result<String, String> {
+        if let Some(nonce) = self.nonce.take() {
+            return Ok(nonce);
+        }
+        let response = self
+            .client
+            .head(&self.directory.new_nonce)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to get ACME nonce: {}", e))?;
+        replay_nonce(&response).ok_or("ACME server did not return a nonce".to_string())
+    }
+
+    /// 요청 본문을 JWS로 서명합니다. 계정을 만들기 전에는 공개 키(`jwk`)를, 만든 뒤에는 계정 주소(`kid`)를 넣습니다.
+    fn sign(&self, url: &str, nonce: &str, payload: Option<&Value>) -> Result<Value, String> {
+        let mut protected = json!({ "alg": "ES256", "nonce": nonce, "url": url });
+        match &self.kid {
+            Some(kid) => protected["kid"] = json!(kid),
+            None => protected["jwk"] = jwk(&self.key),
+        }
+        let protected = encode_base64_url(protected.to_string().as_bytes());
+        // 내용 없이 조회하는 요청(POST-as-GET)은 payload가 빈 문자열
+        let payload = payload
+            .map(|payload| encode_base64_url(payload.to_string().as_bytes()))
+            .unwrap_or_default();
+        let signature = sign_es256(&self.key, format!("{}.{}", protected, payload).as_bytes())
+            .map_err(|e| format!("Failed to sign ACME request: {}", e))?;
+        Ok(json!({
+            "protected": protected,
+            "payload": payload,
+            "signature": encode_base64_url(&signature),
+        }))
+    }
+
+    /// 서명한 POST 요청을 보냅니다. 서버가 nonce를 거절하면(`badNonce`) 새 nonce로 한 번 더 보냅니다.
+    async fn post(
+        &mut self,
+        url: &str,
+        payload: Option<&Value>,
+    ) -> Result<reqwest::Response, String> {
+        let mut retried = false;
+        loop {
+            let nonce = self.nonce().await?;
+            let body = self.sign(url, &nonce, payload)?;
+            let response = self
+                .client
+                .post(url)
+                .header("Content-Type", "application/jose+json")
+                .body(body.to_string())
+                .send()
+                .await
+                .map_err(|e| format!("ACME request to {} failed: {}", url, e))?;
+            self.nonce = replay_nonce(&response);
+            if response.status().is_success() {
+                return Ok(response);
+            }
+
+            let status = response.status();
+            let problem: Value = response.json().await.unwrap_or_default();
+            if !retried && problem["type"] == "urn:ietf:params:acme:error:badNonce" {
+                retried = true;
+                continue;
+            }
+            return Err(format!(
+                "ACME request to {} failed ({}): {}",
+                url, status, problem
+            ));
+        }
+    }
+
+    async fn post_json<T: DeserializeOwned>(
+        &mut self,
+        url: &str,
+        payload: Option<&Value>,
+    ) -> Result<T, String> {
+        self.post(url, payload)
+            .await?
+            .json()
+            .await
+            .map_err(|e| format!("Invalid ACME response from {}: {}", url, e))
+    }
+
+    /// 인증이나 주문이 처리 중이 아닐 때까지 상태를 다시 확인합니다.
+    async fn wait_for(&mut self, url: &str) -> Result<Value, String> {
+        for _ in 0..POLL_ATTEMPTS {
+            let object: Value = self.post_json(url, None).await?;
+            match object["status"].as_str() {
+                Some("pending" | "processing") => actix_rt::time::sleep(POLL_INTERVAL).await,
+                Some("invalid") => {
+                    return Err(format!("ACME object {} is invalid: {}", url, object))
+                }
+                _ => return Ok(object),
+            }
+        }
+        Err(format!("Timed out waiting for ACME object {}", url))
+    }
+
+    /// HTTP-01 인증을 진행합니다. 인증 서버가 토큰 파일을 가져가는 동안만 파일을 둡니다.
+    async fn authorize(&mut self, url: &str) -> Result<(), String> {
+        let authorization: Authorization = self.post_json(url, None).await?;
+        if authorization.status == "valid" {
+            return Ok(());
+        }
+        let challenge = authorization
+            .challenges
+            .into_iter()
+            .find(|challenge| challenge.kind == "http-01")
+            .ok_or("ACME server did not offer an HTTP-01 challenge")?;
+        if !is_valid_token(&challenge.token) {
+            return Err(format!("Invalid ACME challenge token: {}", challenge.token));
+        }
+
+        let token_path = resource_path(CHALLENGE_FOLDER, &challenge.token);
+        write_file(
+            &token_path.to_string_lossy(),
+            key_authorization(&challenge.token, &self.key).as_bytes(),
+        )?;
+        let result = async {
+            self.post(&challenge.url, Some(&json!({}))).await?;
+            self.wait_for(url).await
+        }
+        .await;
+        let _ = fs::remove_file(&token_path);
+        result.map(|_| ())
+    }
+}
+
+fn replay_nonce(response: &reqwest::Response) -> Option<String> {
+    response
+        .headers()
+        .get("Replay-Nonce")
+        .and_then(|nonce| nonce.to_str().ok())
+        .map(str::to_string)
+}
+
+/// ACME 서버에서 인증서를 발급받는 함수입니다.
+///
+/// # Returns
+///
+/// 발급된 인증서 체인과 새로 만든 개인 키를 PEM 형식으로 반환합니다.
+pub async fn issue(config: &AcmeConfig) -> Result<(Vec<u8>, Vec<u8>), String> {
+    if config.domains.is_empty() {
+        return Err("tls.acme.domains is empty".to_string());
+    }
+    let account_key = load_account_key(&config.account_key_file)?;
+    let mut client = AcmeClient::new(&config.directory_url, account_key).await?;
+
+    // 계정 등록 (이미 등록된 키이면 기존 계정 주소를 돌려받음)
+    let mut account = json!({ "termsOfServiceAgreed": true });
+    if let Some(email) = &config.contact_email {
+        account["contact"] = json!([format!("mailto:{}", email)]);
+    }
+    let new_account = client.directory.new_account.clone();
+    let response = client.post(&new_account, Some(&account)).await?;
+    let kid = response
+        .headers()
+        .get("Location")
+        .and_then(|location| location.to_str().ok())
+        .ok_or("ACME server did not return an account URL")?;
+    client.kid = Some(kid.to_string());
+
+    // 주문 생성 후 도메인마다 HTTP-01 인증
+    let identifiers: Vec<Value> = config
+        .domains
+        .iter()
+        .map(|domain| json!({ "type": "dns", "value": domain }))
+        .collect();
+    let new_order = client.directory.new_order.clone();
+    let response = client
+        .post(&new_order, Some(&json!({ "identifiers": identifiers })))
+        .await?;
+    let order_url = response
+        .headers()
+        .get("Location")
+        .and_then(|location| location.to_str().ok())
+        .ok_or("ACME server did not return an order URL")?
+        .to_string();
+    let order: Order = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid ACME order: {}", e))?;
+    for authorization in &order.authorizations {
+        client.authorize(authorization).await?;
+    }
+
+    // 인증서용 키로 서명 요청을 보내고 발급을 기다림
+    let key = p256()
+        .and_then(|group| EcKey::generate(&group))
+        .and_then(PKey::from_ec_key)
+        .map_err(|e| e.to_string())?;
+    let csr = csr(&config.domains, &key).map_err(|e| format!("Failed to create CSR: {}", e))?;
+    client
+        .post(
+            &order.finalize,
+            Some(&json!({ "csr": encode_base64_url(&csr) })),
+        )
+        .await?;
+    let order: Order = serde_json::from_value(client.wait_for(&order_url).await?)
+        .map_err(|e| format!("Invalid ACME order: {}", e))?;
+    let certificate_url = match (order.status.as_str(), order.certificate) {
+        ("valid", Some(url)) => url,
+        (status, _) => return Err(format!("ACME order was not issued (status: {})", status)),
+    };
+    let certificate = client
+        .post(&certificate_url, None)
+        .await?
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to download certificate: {}", e))?;
+    let key_pem = key.private_key_to_pem_pkcs8().map_err(|e| e.to_string())?;
+    Ok((certificate.to_vec(), key_pem))
+}
+
+/// 인증서를 새로 받아야 하는지 확인합니다. 인증서가 없거나 읽을 수 없거나,
+/// 만료까지 `renew_before_days`일보다 적게 남았으면 `true`를 반환합니다.
+pub fn needs_renewal(cert_file: &str, renew_before_days: u32) -> bool {
+    let Some(certificate) = fs::read(cert_file)
+        .ok()
+        .and_then(|pem| X509::from_pem(&pem).ok())
+    else {
+        return true;
+    };
+    Asn1Time::days_from_now(renew_before_days)
+        .and_then(|threshold| certificate.not_after().compare(&threshold))
+        .map_or(true, |ordering| ordering == Ordering::Less)
+}
+
+/// 인증서가 만료에 가까우면 새로 발급받아 파일에 저장하고, 이후의 TLS 연결에서 새 인증서를 사용하도록 바꿉니다.
+///
+/// # Returns
+///
+/// 인증서를 갱신하면 `Ok(true)`, 아직 갱신할 필요가 없으면 `Ok(false)`, 발급에 실패하면 오류 메시지를 반환합니다.
+pub async fn renew(tls: &TlsConfig, resolver: &CertResolver) -> Result<bool, String> {
+    let Some(acme) = &tls.acme else {
+        return Ok(false);
+    };
+    if !needs_renewal(&tls.cert_file, acme.renew_before_days) {
+        return Ok(false);
+    }
+
+    info!(
+        "Requesting a certificate for {} from {}",
+        acme.domains.join(", "),
+        acme.directory_url
+    );
+    let (cert_pem, key_pem) = issue(acme).await?;
+    let certified = certified_key(&cert_pem, &key_pem)?;
+    write_file(&tls.key_file, &key_pem)?;
+    write_file(&tls.cert_file, &cert_pem)?;
+    resolver.set(certified);
+    info!("Certificate for {} renewed", acme.domains.join(", "));
+    Ok(true)
+}
+
+/// `tls.acme`가 설정된 경우 인증서 만료일을 주기적으로 확인하여 갱신하도록 등록합니다.
+/// 첫 확인은 등록 직후에 이루어지므로 인증서가 없으면 서버가 시작되자마자 발급을 요청합니다.
+pub fn schedule_renewal(tls: TlsConfig, resolver: Arc<CertResolver>) {
+    if tls.acme.is_none() {
+        return;
+    }
+    scheduler::every("acme renewal", RENEWAL_CHECK_PERIOD, move || {
+        let (tls, resolver) = (tls.clone(), resolver.clone());
+        actix_rt::spawn(async move {
+            match renew(&tls, &resolver).await {
+                Ok(_) => {}
+                Err(e) if resolver.current().is_some() => {
+                    warn!(
+                        "Failed to renew certificate, keeping the current one: {}",
+                        e
+                    )
+                }
+                Err(e) => error!("Failed to issue certificate: {}", e),
+            }
+        });
+    });
+}
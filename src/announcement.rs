@@ -0,0 +1,106 @@
+use actix_web::{routes, web::Data, HttpRequest, HttpResponse};
+use chrono::{DateTime, Duration, Utc};
+use std::sync::Mutex;
+
+use crate::assets::inject_assets;
+use crate::lock::MutexExt;
+use crate::staging::inject_banner;
+use crate::state::Announcement;
+use crate::template::{escape_html, render};
+
+impl Announcement {
+    /// 공지가 아직 만료되지 않았는지 확인합니다. 만료 시각을 읽을 수 없으면 만료된 것으로 처리합니다.
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        DateTime::parse_from_rfc3339(&self.expires_at).is_ok_and(|expires_at| now < expires_at)
+    }
+}
+
+/// `announce <분> <메시지>` 관리자 명령을 해석하여 공지를 생성하는 함수입니다.
+///
+/// # Arguments
+///
+/// * `args` - `announce ` 뒤에 오는 문자열입니다.
+/// * `now` - 만료 시각 계산의 기준 시각입니다.
+///
+/// # Returns
+///
+/// 공지 유지 시간(분)과 메시지가 모두 있으면 `Some(Announcement)`, 형식이 틀리면 `None`이 반환됩니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let announcement = parse_announce("90 7번 부스는 5시에 종료됩니다", Utc::now()).unwrap();
+/// assert_eq!(announcement.message, "7번 부스는 5시에 종료됩니다");
+/// ```
+pub fn parse_announce(args: &str, now: DateTime<Utc>) -> Option<Announcement> {
+    let (minutes, message) = args.trim().split_once(' ')?;
+    let minutes: i64 = minutes.parse().ok().filter(|minutes| *minutes > 0)?;
+    let message = message.trim();
+    if message.is_empty() {
+        return None;
+    }
+    // 표현할 수 없을 만큼 먼 만료 시각은 형식 오류로 취급
+    let expires_at = now.checked_add_signed(Duration::try_minutes(minutes)?)?;
+
+    Some(Announcement {
+        message: message.to_string(),
+        expires_at: expires_at.to_rfc3339(),
+    })
+}
+
+/// 현재 표시 중인 공지를 반환합니다. 만료된 공지는 반환하지 않습니다.
+pub fn active_announcement(announcement: &Mutex<Option<Announcement>>) -> Option<Announcement> {
+    announcement
+        .lock_or_recover()
+        .clone()
+        .filter(|announcement| announcement.is_active(Utc::now()))
+}
+
+/// HTML 페이지의 `%ANNOUNCEMENT%` 자리표시자를 현재 공지로 치환하는 함수입니다.
+/// 공지가 없거나 만료되었으면 빈 문자열로 치환합니다. `%ASSET:경로%`와 `%BASE_PATH%` 자리표시자도 함께 치환하고,
+/// 시험 운영 중이면 `TEST MODE` 띠를 넣습니다.
+///
+/// # Arguments
+///
+/// * `req` - 공지 상태를 앱 데이터에서 꺼내기 위한 `HttpRequest`입니다.
+/// * `html` - 치환할 HTML 문자열입니다.
+pub fn inject(req: &HttpRequest, html: String) -> String {
+    inject_banner(req, inject_announcement(req, inject_assets(req, html)))
+}
+
+/// `%ANNOUNCEMENT%` 자리표시자만 현재 공지로 치환하는 함수입니다. (`inject` 참고)
+pub fn inject_announcement(req: &HttpRequest, html: String) -> String {
+    if !html.contains("%ANNOUNCEMENT%") {
+        return html;
+    }
+
+    let message = req
+        .app_data::<Data<Mutex<Option<Announcement>>>>()
+        .and_then(|announcement| active_announcement(announcement))
+        .map(|announcement| escape_html(&announcement.message))
+        .unwrap_or_default();
+    render(&html, &[("ANNOUNCEMENT", &message)])
+}
+
+/// 표시 중인 공지가 있는지 확인합니다. 공지를 복사하지 않으므로 요청마다 호출해도 됩니다.
+pub fn is_announcing(req: &HttpRequest) -> bool {
+    req.app_data::<Data<Mutex<Option<Announcement>>>>()
+        .is_some_and(|announcement| {
+            announcement
+                .lock_or_recover()
+                .as_ref()
+                .is_some_and(|announcement| announcement.is_active(Utc::now()))
+        })
+}
+
+/// 현재 공지를 JSON으로 반환하는 비동기 함수입니다.
+///
+/// # Returns
+///
+/// 표시 중인 공지가 있으면 `Announcement`를, 없으면 `null`을 담은 200 OK 응답이 반환됩니다.
+#[routes]
+#[get("/api/v1/announcement")]
+#[get("/api/announcement")]
+pub async fn handle_announcement(announcement: Data<Mutex<Option<Announcement>>>) -> HttpResponse {
+    HttpResponse::Ok().json(active_announcement(&announcement))
+}
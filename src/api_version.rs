@@ -0,0 +1,98 @@
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    http::{
+        header::{HeaderName, HeaderValue, LINK},
+        StatusCode,
+    },
+    middleware::Next,
+};
+
+use crate::base_path::prefixed;
+
+/// 현재 JSON API의 경로 접두사입니다. 이 아래의 응답 형식은 같은 버전 안에서 호환성을 유지합니다.
+pub const API_V1: &str = "/api/v1";
+
+/// `/api/v1` 도입 이전의 JSON API 경로입니다. `/`로 끝나는 항목은 그 아래의 모든 경로를 뜻합니다.
+/// 예전 스템프 목록 파일 주소(`/api/stampList.json`)는 원본 파일 형식을 유지하는 별도 주소이므로 포함하지 않습니다.
+const LEGACY_PATHS: [&str; 13] = [
+    "/login",
+    "/progress",
+    "/me/delete",
+    "/api/sync",
+    "/api/partner/stamp",
+    "/api/leaderboard",
+    "/api/stamps",
+    "/api/announcement",
+    "/api/event",
+    "/api/stats/",
+    "/api/teams/",
+    "/api/staff/",
+    "/api/admin/",
+];
+
+/// `/api/v1` 도입 이전의 JSON API 경로를 새 경로로 바꾸는 함수입니다. 이전 경로가 아니면 `None`을 반환합니다.
+///
+/// # Example
+///
+/// ```rust
+/// use gj_stamptour::api_version::successor_path;
+///
+/// assert_eq!(successor_path("/api/teams/ABC234").as_deref(), Some("/api/v1/teams/ABC234"));
+/// assert_eq!(successor_path("/login").as_deref(), Some("/api/v1/login"));
+/// assert_eq!(successor_path("/api/v1/login"), None);
+/// assert_eq!(successor_path("/api/stampList.json"), None);
+/// assert_eq!(successor_path("/check"), None);
+/// ```
+pub fn successor_path(path: &str) -> Option<String> {
+    let is_legacy = LEGACY_PATHS.iter().any(|legacy| {
+        if legacy.ends_with('/') {
+            path.starts_with(legacy)
+        } else {
+            path == *legacy
+        }
+    });
+    if !is_legacy {
+        return None;
+    }
+    Some(match path.strip_prefix("/api") {
+        Some(rest) => format!("{}{}", API_V1, rest),
+        None => format!("{}{}", API_V1, path),
+    })
+}
+
+/// 이전 경로로 들어온 JSON API 요청의 응답에 `Deprecation`과 새 경로를 알려주는 `Link` 헤더를 붙이는 미들웨어입니다.
+/// 이전 경로는 각 핸들러에 함께 등록되어 있으므로 응답 내용은 새 경로와 같습니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// App::new()
+///     .wrap(actix_web::middleware::from_fn(mark_legacy_path))
+///     .configure(routes);
+/// ```
+pub async fn mark_legacy_path(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let successor = successor_path(req.path());
+    let mut res = next.call(req).await?;
+
+    if let Some(successor) = successor {
+        if res.status() != StatusCode::NOT_FOUND {
+            let link = format!(
+                "<{}>; rel=\"successor-version\"",
+                prefixed(res.request(), &successor)
+            );
+            let headers = res.headers_mut();
+            headers.insert(
+                HeaderName::from_static("deprecation"),
+                HeaderValue::from_static("true"),
+            );
+            if let Ok(link) = HeaderValue::from_str(&link) {
+                headers.insert(LINK, link);
+            }
+        }
+    }
+    Ok(res)
+}
@@ -0,0 +1,144 @@
+use actix_web::{web::Data, HttpRequest};
+use log::{info, warn};
+use std::{collections::HashMap, fs, path::Path};
+
+use crate::base_path::base_path;
+use crate::state::Reloadable;
+use crate::template::render;
+
+/// 지문이 붙은 파일에 사용하는 캐시 정책입니다. 내용이 바뀌면 파일 이름도 바뀌므로 1년 동안 재검증하지 않습니다.
+pub const IMMUTABLE_CACHE: &str = "public, max-age=31536000, immutable";
+
+/// 지문을 붙이지 않는 폴더입니다. HTML과 데이터베이스, 인증서 발급용 토큰은 항상 새로 읽어야 합니다.
+const EXCLUDED_FOLDERS: [&str; 3] = ["html", "database", ".well-known"];
+
+/// 정적 파일의 원래 경로와 내용 해시를 붙인 경로(예: `css/style.1a2b3c4d.css`)의 대응표입니다.
+#[derive(Debug, Default, Clone)]
+pub struct AssetManifest {
+    /// 원래 경로 → 지문이 붙은 경로입니다.
+    fingerprinted: HashMap<String, String>,
+    /// 지문이 붙은 경로 → 원래 경로입니다.
+    originals: HashMap<String, String>,
+}
+
+impl AssetManifest {
+    /// `resources` 하위 폴더의 정적 파일을 모두 읽어 대응표를 만드는 함수입니다.
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - `resources` 폴더 경로입니다.
+    ///
+    /// # Returns
+    ///
+    /// 읽을 수 있는 파일의 지문을 담은 `AssetManifest`를 반환합니다. 폴더가 없으면 빈 대응표를 반환합니다.
+    pub fn build(root: &Path) -> Self {
+        let mut manifest = AssetManifest::default();
+        let Ok(folders) = fs::read_dir(root) else {
+            warn!("Asset directory {} not found", root.display());
+            return manifest;
+        };
+
+        for folder in folders.flatten().filter(|entry| entry.path().is_dir()) {
+            let folder_name = folder.file_name().to_string_lossy().to_string();
+            if !EXCLUDED_FOLDERS.contains(&folder_name.as_str()) {
+                manifest.add_dir(&folder.path(), &folder_name);
+            }
+        }
+        info!(
+            "Asset manifest built ({} files)",
+            manifest.fingerprinted.len()
+        );
+        manifest
+    }
+
+    /// 폴더 안의 파일을 하위 폴더까지 모두 대응표에 추가합니다.
+    fn add_dir(&mut self, dir: &Path, folder: &str) {
+        for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if entry.path().is_dir() {
+                self.add_dir(&entry.path(), &format!("{folder}/{name}"));
+            } else if let Ok(contents) = fs::read(entry.path()) {
+                self.insert(folder, &name, &contents);
+            }
+        }
+    }
+
+    /// 파일 하나의 지문을 계산하여 대응표에 추가합니다.
+    pub fn insert(&mut self, folder: &str, file: &str, contents: &[u8]) {
+        let hash = format!("{:08x}", crc32fast::hash(contents));
+        let hashed_file = match file.rsplit_once('.') {
+            Some((stem, extension)) => format!("{stem}.{hash}.{extension}"),
+            None => format!("{file}.{hash}"),
+        };
+        self.fingerprinted.insert(
+            format!("{folder}/{file}"),
+            format!("{folder}/{hashed_file}"),
+        );
+        self.originals.insert(
+            format!("{folder}/{hashed_file}"),
+            format!("{folder}/{file}"),
+        );
+    }
+
+    /// 원래 경로(예: `css/style.css`)에 해당하는 URL을 반환합니다. 대응표에 없으면 원래 경로를 그대로 사용합니다.
+    pub fn url(&self, asset: &str) -> String {
+        let asset = asset.trim_start_matches('/');
+        format!(
+            "/{}",
+            self.fingerprinted
+                .get(asset)
+                .map(String::as_str)
+                .unwrap_or(asset)
+        )
+    }
+
+    /// 지문이 붙은 경로(예: `css/style.1a2b3c4d.css`)에 해당하는 원래 경로를 반환합니다.
+    pub fn original(&self, asset: &str) -> Option<&str> {
+        self.originals.get(asset).map(String::as_str)
+    }
+}
+
+/// HTML의 `%ASSET:경로%` 자리표시자를 지문이 붙은 URL로 치환하는 함수입니다.
+///
+/// # Arguments
+///
+/// * `html` - 치환할 HTML 문자열입니다.
+/// * `manifest` - 정적 파일 대응표입니다.
+/// * `base_path` - 주소 앞에 붙일 URL 접두사입니다. 없으면 빈 문자열입니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// // <link rel="stylesheet" href="%ASSET:css/style.css%">
+/// let html = rewrite_asset_urls(html, &manifest, "");
+/// // <link rel="stylesheet" href="/css/style.1a2b3c4d.css">
+/// ```
+pub fn rewrite_asset_urls(html: &str, manifest: &AssetManifest, base_path: &str) -> String {
+    let mut output = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find("%ASSET:") {
+        let after = &rest[start + "%ASSET:".len()..];
+        let Some(end) = after.find('%') else {
+            break;
+        };
+        output.push_str(&rest[..start]);
+        output.push_str(base_path);
+        output.push_str(&manifest.url(&after[..end]));
+        rest = &after[end + 1..];
+    }
+    output.push_str(rest);
+    output
+}
+
+/// 요청의 앱 데이터에 등록된 대응표로 HTML의 에셋 주소를 치환합니다.
+/// URL 접두사가 설정되어 있으면 에셋 주소와 `%BASE_PATH%` 자리표시자에 접두사를 붙입니다.
+pub fn inject_assets(req: &HttpRequest, html: String) -> String {
+    let base_path = base_path(req);
+    let html = match req.app_data::<Data<Reloadable<AssetManifest>>>() {
+        Some(manifest) if html.contains("%ASSET:") => {
+            rewrite_asset_urls(&html, &manifest.get(), &base_path)
+        }
+        _ => html,
+    };
+    render(&html, &[("BASE_PATH", &base_path)])
+}
@@ -0,0 +1,575 @@
+use chrono::{DateTime, Local, Utc};
+use log::{error, info, warn};
+use openssl::{hash::MessageDigest, pkey::PKey, sha::sha256, sign::Signer};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::state::{CompletionList, StampHistory, TeamList, UserList};
+use crate::storage::{
+    checksum_path, from_database, verify_checksum, write_with_checksum, DatabaseFormat,
+};
+
+/// 스냅샷 파일 이름에 붙는 시각 형식입니다. (예: `stamp_status-2024-10-05T14:00.json`)
+pub const SNAPSHOT_FORMAT: &str = "%Y-%m-%dT%H:%M";
+
+/// `resources/config.json`의 `backup` 항목입니다.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct BackupConfig {
+    /// 스냅샷을 저장할 디렉터리입니다.
+    pub dir: String,
+    /// 데이터베이스 파일별로 남겨둘 최신 스냅샷 수입니다. 0이면 지우지 않습니다.
+    pub keep: usize,
+    /// 스냅샷을 함께 올릴 S3 호환 저장소 설정입니다. 없으면 로컬에만 저장합니다.
+    pub s3: Option<S3Config>,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        BackupConfig {
+            dir: "resources/backups".to_string(),
+            keep: 24,
+            s3: None,
+        }
+    }
+}
+
+/// S3 호환 저장소(AWS S3, MinIO, Cloudflare R2 등) 설정입니다. 경로 방식(`{endpoint}/{bucket}/{key}`)으로 업로드합니다.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct S3Config {
+    /// `https://s3.ap-northeast-2.amazonaws.com`과 같은 저장소 주소입니다.
+    pub endpoint: String,
+    pub bucket: String,
+    #[serde(default = "default_region")]
+    pub region: String,
+    pub access_key: String,
+    pub secret_key: String,
+    /// 객체 키 앞에 붙일 경로입니다. (예: `stamptour/`)
+    #[serde(default)]
+    pub prefix: String,
+}
+
+fn default_region() -> String {
+    "us-east-1".to_string()
+}
+
+/// 데이터베이스 파일 이름과 시각으로 스냅샷 파일 이름을 만듭니다. 확장자는 저장 형식을 따릅니다.
+pub fn snapshot_name(file_name: &str, timestamp: &str, format: DatabaseFormat) -> String {
+    format!("{}-{}.{}", file_name, timestamp, format.extension())
+}
+
+/// 디렉터리에 있는 스냅샷 파일 경로를 찾습니다. 어느 형식으로도 없으면 JSON 경로를 반환합니다.
+fn snapshot_path(dir: &Path, file_name: &str, timestamp: &str) -> PathBuf {
+    DatabaseFormat::ALL
+        .into_iter()
+        .map(|format| dir.join(snapshot_name(file_name, timestamp, format)))
+        .find(|path| path.exists())
+        .unwrap_or_else(|| dir.join(snapshot_name(file_name, timestamp, DatabaseFormat::Json)))
+}
+
+/// 디렉터리에 있는 `file_name`의 스냅샷 시각 목록을 오래된 순서로 반환합니다.
+pub fn list_snapshots(dir: &Path, file_name: &str) -> Vec<String> {
+    let prefix = format!("{}-", file_name);
+    let mut timestamps: Vec<String> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter_map(|name| {
+            let (timestamp, extension) = name.strip_prefix(&prefix)?.rsplit_once('.')?;
+            if !DatabaseFormat::ALL
+                .iter()
+                .any(|format| format.extension() == extension)
+            {
+                return None;
+            }
+            // `stamp_status`와 `stamp_status_old`처럼 앞부분이 같은 다른 파일은 제외
+            chrono::NaiveDateTime::parse_from_str(timestamp, SNAPSHOT_FORMAT)
+                .is_ok()
+                .then(|| timestamp.to_string())
+        })
+        .collect();
+    timestamps.sort();
+    timestamps.dedup();
+    timestamps
+}
+
+/// 최신 스냅샷 `keep`개만 남기고 오래된 스냅샷과 체크섬 파일을 지우는 함수입니다.
+///
+/// # Returns
+///
+/// 지운 스냅샷 파일 경로 목록을 반환합니다. 체크섬 파일은 목록에 넣지 않습니다.
+pub fn prune_snapshots(dir: &Path, file_name: &str, keep: usize) -> io::Result<Vec<PathBuf>> {
+    let timestamps = list_snapshots(dir, file_name);
+    if keep == 0 || timestamps.len() <= keep {
+        return Ok(Vec::new());
+    }
+
+    let mut removed = Vec::new();
+    for timestamp in timestamps[..timestamps.len() - keep].iter() {
+        for format in DatabaseFormat::ALL {
+            let path = dir.join(snapshot_name(file_name, timestamp, format));
+            match fs::remove_file(checksum_path(&path)) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+            if path.exists() {
+                fs::remove_file(&path)?;
+                removed.push(path);
+            }
+        }
+    }
+    Ok(removed)
+}
+
+/// 데이터베이스 파일들의 스냅샷을 체크섬 파일(`.sha256`)과 함께 저장하고 오래된 스냅샷을 정리하는 함수입니다.
+///
+/// # Arguments
+///
+/// * `config` - 백업 설정입니다.
+/// * `files` - `(데이터베이스 파일 이름, 저장 형식의 내용)` 목록입니다. 확장자는 내용으로 판별한 형식을 따릅니다.
+/// * `now` - 스냅샷 이름에 사용할 시각입니다.
+///
+/// # Returns
+///
+/// 저장한 스냅샷 파일 경로 목록과 지운 스냅샷 수를 반환합니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let (saved, removed) = write_snapshot(&config.backup, &[("stamp_status", json)], Local::now())?;
+/// ```
+pub fn write_snapshot(
+    config: &BackupConfig,
+    files: &[(&str, Vec<u8>)],
+    now: DateTime<Local>,
+) -> io::Result<(Vec<PathBuf>, usize)> {
+    let dir = PathBuf::from(&config.dir);
+    let timestamp = now.format(SNAPSHOT_FORMAT).to_string();
+    fs::create_dir_all(&dir)?;
+
+    let mut saved = Vec::new();
+    let mut removed = 0;
+    for (file_name, contents) in files {
+        let path = dir.join(snapshot_name(
+            file_name,
+            &timestamp,
+            DatabaseFormat::detect(contents),
+        ));
+        write_with_checksum(&path, contents)?;
+        saved.push(path);
+        removed += prune_snapshots(&dir, file_name, config.keep)?.len();
+    }
+    info!("Backup {} saved to {}", timestamp, dir.display());
+    Ok((saved, removed))
+}
+
+/// 스냅샷에서 불러온 참가자 진행 상황입니다. 공지, 투어 상태, 감사 기록은 되돌리지 않습니다.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub stamp_history: StampHistory,
+    /// 스냅샷 파일이 없으면 현재 값을 유지합니다.
+    pub user_list: Option<UserList>,
+    pub completions: Option<CompletionList>,
+    pub teams: Option<TeamList>,
+}
+
+/// 스냅샷 파일 하나를 읽고 체크섬을 확인합니다. 파일이 없으면 `None`을 반환합니다.
+fn read_snapshot_file<T: DeserializeOwned>(
+    dir: &Path,
+    file_name: &str,
+    timestamp: &str,
+) -> Result<Option<T>, String> {
+    let path = snapshot_path(dir, file_name, timestamp);
+    match fs::read(&path) {
+        Ok(contents) => verify_checksum(&path, &contents)
+            .and_then(|_| from_database(&contents))
+            .map(Some)
+            .map_err(|e| format!("{}: {}", path.display(), e)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("{}: {}", path.display(), e)),
+    }
+}
+
+/// `timestamp` 시각의 스냅샷을 읽는 함수입니다.
+///
+/// # Arguments
+///
+/// * `config` - 백업 설정입니다.
+/// * `timestamp` - `2024-10-05T14:00` 형식의 스냅샷 시각입니다.
+///
+/// # Returns
+///
+/// 시각 형식이 틀렸거나, `stamp_status` 스냅샷이 없거나, 파일을 읽지 못하면 오류 메시지를 반환합니다.
+pub fn read_snapshot(config: &BackupConfig, timestamp: &str) -> Result<Snapshot, String> {
+    // 형식을 먼저 확인하여 다른 경로의 파일을 읽지 못하도록 함
+    if chrono::NaiveDateTime::parse_from_str(timestamp, SNAPSHOT_FORMAT).is_err() {
+        return Err(format!("Invalid snapshot name {}", timestamp));
+    }
+
+    let dir = Path::new(&config.dir);
+    Ok(Snapshot {
+        stamp_history: read_snapshot_file(dir, "stamp_status", timestamp)?
+            .ok_or_else(|| format!("Snapshot {} not found", timestamp))?,
+        user_list: read_snapshot_file(dir, "user_status", timestamp)?,
+        completions: read_snapshot_file(dir, "completion_status", timestamp)?,
+        teams: read_snapshot_file(dir, "team_status", timestamp)?,
+    })
+}
+
+/// 체크섬과 파싱이 모두 맞는 `file_name`의 가장 최근 스냅샷을 찾는 함수입니다.
+/// 데이터베이스 파일이 손상되었을 때 대신 불러오는 데 사용하며, 손상된 스냅샷은 건너뜁니다.
+///
+/// # Returns
+///
+/// 스냅샷 시각과 불러온 데이터를 반환합니다. 사용할 수 있는 스냅샷이 없으면 `None`을 반환합니다.
+pub fn latest_valid_snapshot<T: DeserializeOwned>(
+    config: &BackupConfig,
+    file_name: &str,
+) -> Option<(String, T)> {
+    let dir = Path::new(&config.dir);
+    list_snapshots(dir, file_name)
+        .into_iter()
+        .rev()
+        .find_map(
+            |timestamp| match read_snapshot_file(dir, file_name, &timestamp) {
+                Ok(Some(data)) => Some((timestamp, data)),
+                Ok(None) => None,
+                Err(e) => {
+                    warn!("Skipping damaged backup {} : {}", timestamp, e);
+                    None
+                }
+            },
+        )
+}
+
+/// 바이트 배열을 소문자 16진수 문자열로 변환합니다.
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub(crate) fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    let key = PKey::hmac(key).unwrap();
+    let mut signer = Signer::new(MessageDigest::sha256(), &key).unwrap();
+    signer.update(data.as_bytes()).unwrap();
+    signer.sign_to_vec().unwrap()
+}
+
+/// AWS Signature Version 4 서명 키를 계산합니다.
+pub fn signing_key(secret_key: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let key = hmac_sha256(format!("AWS4{}", secret_key).as_bytes(), date);
+    let key = hmac_sha256(&key, region);
+    let key = hmac_sha256(&key, service);
+    hmac_sha256(&key, "aws4_request")
+}
+
+/// S3 객체 키를 URI 경로에 맞게 인코딩합니다. `/`와 예약되지 않은 문자만 그대로 둡니다.
+fn encode_key(key: &str) -> String {
+    uri_encode(key, false)
+}
+
+/// 예약되지 않은 문자를 제외하고 퍼센트 인코딩합니다. 쿼리 값에서는 `/`도 인코딩합니다.
+fn uri_encode(value: &str, encode_slash: bool) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            b'/' if !encode_slash => "/".to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// S3 `PutObject` 요청의 URL과 서명 헤더를 만드는 함수입니다.
+///
+/// # Returns
+///
+/// 요청 URL과 `(헤더 이름, 값)` 목록을 반환합니다. 저장소 주소를 해석할 수 없으면 `None`을 반환합니다.
+pub fn sign_put(
+    config: &S3Config,
+    key: &str,
+    body: &[u8],
+    now: DateTime<Utc>,
+) -> Option<(String, Vec<(&'static str, String)>)> {
+    sign_request(config, "PUT", Some(key), &[], body, now)
+}
+
+/// S3 요청의 URL과 서명 헤더를 만듭니다. `key`가 없으면 버킷에 보내는 요청(`ListObjectsV2`)입니다.
+fn sign_request(
+    config: &S3Config,
+    method: &str,
+    key: Option<&str>,
+    query: &[(&str, &str)],
+    body: &[u8],
+    now: DateTime<Utc>,
+) -> Option<(String, Vec<(&'static str, String)>)> {
+    let endpoint = reqwest::Url::parse(&config.endpoint).ok()?;
+    let host = match endpoint.port() {
+        Some(port) => format!("{}:{}", endpoint.host_str()?, port),
+        None => endpoint.host_str()?.to_string(),
+    };
+    let mut path = format!(
+        "{}/{}",
+        endpoint.path().trim_end_matches('/'),
+        config.bucket
+    );
+    if let Some(key) = key {
+        path.push('/');
+        path.push_str(&encode_key(&format!("{}{}", config.prefix, key)));
+    }
+    // 서명할 쿼리는 인코딩한 이름 순으로 정렬
+    let mut query: Vec<String> = query
+        .iter()
+        .map(|(name, value)| format!("{}={}", uri_encode(name, true), uri_encode(value, true)))
+        .collect();
+    query.sort();
+    let query = query.join("&");
+
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+    let payload_hash = to_hex(&sha256(body));
+    let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+    let canonical_request = format!(
+        "{method}\n{path}\n{query}\nhost:{host}\nx-amz-content-sha256:{payload_hash}\nx-amz-date:{amz_date}\n\n{signed_headers}\n{payload_hash}"
+    );
+    let scope = format!("{}/{}/s3/aws4_request", date, config.region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        to_hex(&sha256(canonical_request.as_bytes()))
+    );
+    let signature = to_hex(&hmac_sha256(
+        &signing_key(&config.secret_key, &date, &config.region, "s3"),
+        &string_to_sign,
+    ));
+
+    let mut url = format!("{}://{}{}", endpoint.scheme(), host, path);
+    if !query.is_empty() {
+        url.push('?');
+        url.push_str(&query);
+    }
+    Some((
+        url,
+        vec![
+            (
+                "Authorization",
+                format!(
+                    "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+                    config.access_key, scope, signed_headers, signature
+                ),
+            ),
+            ("x-amz-content-sha256", payload_hash),
+            ("x-amz-date", amz_date),
+        ],
+    ))
+}
+
+/// 객체 하나를 S3 호환 저장소에 올립니다.
+async fn put_object(
+    client: &reqwest::Client,
+    config: &S3Config,
+    key: &str,
+    body: Vec<u8>,
+) -> Result<(), String> {
+    let (url, headers) = sign_put(config, key, &body, Utc::now())
+        .ok_or_else(|| format!("invalid endpoint {}", config.endpoint))?;
+    let mut request = client.put(url).body(body);
+    for (name, value) in headers {
+        request = request.header(name, value);
+    }
+    match request.send().await {
+        Ok(response) if response.status().is_success() => Ok(()),
+        Ok(response) => Err(format!("status {}", response.status())),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// 저장한 스냅샷을 S3 호환 저장소에 비동기로 올립니다. 결과를 기다리지 않으므로 관리자 명령을 지연시키지 않습니다.
+/// 내려받은 스냅샷도 확인할 수 있도록 체크섬 파일을 함께 올립니다.
+pub fn upload_snapshots(config: S3Config, paths: Vec<PathBuf>) {
+    actix_rt::spawn(async move {
+        let client = reqwest::Client::new();
+        for path in paths
+            .into_iter()
+            .flat_map(|path| [checksum_path(&path), path])
+        {
+            let Some(key) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            let body = match async_std::fs::read(&path).await {
+                Ok(body) => body,
+                Err(e) => {
+                    error!("Backup upload {} failed: {}", path.display(), e);
+                    continue;
+                }
+            };
+            match put_object(&client, &config, key, body).await {
+                Ok(_) => info!("Backup uploaded : {}", key),
+                Err(e) => error!("Backup upload {} failed : {}", key, e),
+            }
+        }
+    });
+}
+
+/// 내보낸 파일이나 감사 기록처럼 스냅샷이 아닌 파일을 S3 호환 저장소에 비동기로 올립니다.
+/// 같은 키의 객체가 있으면 덮어씁니다.
+///
+/// # Arguments
+///
+/// * `config` - 저장소 설정입니다.
+/// * `key` - `prefix` 뒤에 붙일 객체 키입니다. (예: `exports/users.csv`)
+/// * `body` - 올릴 내용입니다.
+pub fn upload_object(config: S3Config, key: String, body: Vec<u8>) {
+    actix_rt::spawn(async move {
+        match put_object(&reqwest::Client::new(), &config, &key, body).await {
+            Ok(_) => info!("Uploaded to S3 : {}", key),
+            Err(e) => error!("S3 upload {} failed : {}", key, e),
+        }
+    });
+}
+
+/// 서명한 `GET` 요청을 보내고 응답 본문을 반환합니다.
+async fn get(
+    client: &reqwest::Client,
+    config: &S3Config,
+    key: Option<&str>,
+    query: &[(&str, &str)],
+) -> Result<Vec<u8>, String> {
+    let (url, headers) = sign_request(config, "GET", key, query, b"", Utc::now())
+        .ok_or_else(|| format!("invalid endpoint {}", config.endpoint))?;
+    let mut request = client.get(url);
+    for (name, value) in headers {
+        request = request.header(name, value);
+    }
+    match request.send().await {
+        Ok(response) if response.status().is_success() => response
+            .bytes()
+            .await
+            .map(|body| body.to_vec())
+            .map_err(|e| e.to_string()),
+        Ok(response) => Err(format!("status {}", response.status())),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// XML 응답에서 `<tag>` 값들을 순서대로 꺼냅니다.
+fn xml_values(xml: &str, tag: &str) -> Vec<String> {
+    let (open, close) = (format!("<{}>", tag), format!("</{}>", tag));
+    xml.split(&open)
+        .skip(1)
+        .filter_map(|rest| rest.split_once(&close).map(|(value, _)| value))
+        .map(|value| {
+            value
+                .replace("&lt;", "<")
+                .replace("&gt;", ">")
+                .replace("&quot;", "\"")
+                .replace("&apos;", "'")
+                .replace("&amp;", "&")
+        })
+        .collect()
+}
+
+/// `prefix` 아래에 있는 객체 키 목록을 `ListObjectsV2`로 가져옵니다. 반환하는 키에는 `prefix`가 빠져 있습니다.
+async fn list_objects(client: &reqwest::Client, config: &S3Config) -> Result<Vec<String>, String> {
+    let mut keys = Vec::new();
+    let mut token: Option<String> = None;
+    loop {
+        let mut query = vec![("list-type", "2"), ("prefix", config.prefix.as_str())];
+        if let Some(token) = &token {
+            query.push(("continuation-token", token));
+        }
+        let body = get(client, config, None, &query).await?;
+        let xml = String::from_utf8_lossy(&body);
+        keys.extend(
+            xml_values(&xml, "Key")
+                .into_iter()
+                .filter_map(|key| key.strip_prefix(&config.prefix).map(str::to_string)),
+        );
+        token = match xml_values(&xml, "IsTruncated").first().map(String::as_str) {
+            Some("true") => xml_values(&xml, "NextContinuationToken").into_iter().next(),
+            _ => None,
+        };
+        if token.is_none() {
+            return Ok(keys);
+        }
+    }
+}
+
+/// `backup` 명령으로 올린 스냅샷(또는 그 체크섬 파일)의 객체 키인지 확인합니다.
+fn is_snapshot_key(key: &str) -> bool {
+    let name = key.strip_suffix(".sha256").unwrap_or(key);
+    let Some((stem, extension)) = name.rsplit_once('.') else {
+        return false;
+    };
+    !key.contains('/')
+        && DatabaseFormat::ALL
+            .iter()
+            .any(|format| format.extension() == extension)
+        && stem
+            .len()
+            .checked_sub(16)
+            .and_then(|start| stem.get(start..))
+            .is_some_and(|timestamp| {
+                chrono::NaiveDateTime::parse_from_str(timestamp, SNAPSHOT_FORMAT).is_ok()
+            })
+}
+
+/// S3 호환 저장소에 올린 스냅샷 중 `backup.dir`에 없는 것을 내려받는 함수입니다.
+/// 내려받은 스냅샷은 로컬 스냅샷과 같이 `restore` 명령이나 손상된 데이터베이스 복구(`latest_valid_snapshot`)에 사용합니다.
+///
+/// # Returns
+///
+/// 내려받은 파일 경로 목록(체크섬 파일 포함)을 반환합니다. 저장소가 설정되지 않았으면 빈 목록을 반환합니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let downloaded = download_snapshots(&config.backup).await?;
+/// let timestamps = list_snapshots(Path::new(&config.backup.dir), "stamp_status");
+/// ```
+pub async fn download_snapshots(config: &BackupConfig) -> Result<Vec<PathBuf>, String> {
+    let Some(s3) = &config.s3 else {
+        return Ok(Vec::new());
+    };
+    let client = reqwest::Client::new();
+    let dir = PathBuf::from(&config.dir);
+    fs::create_dir_all(&dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+
+    let mut downloaded = Vec::new();
+    for key in list_objects(&client, s3).await? {
+        let path = dir.join(&key);
+        if !is_snapshot_key(&key) || path.exists() {
+            continue;
+        }
+        let body = get(&client, s3, Some(&key), &[])
+            .await
+            .map_err(|e| format!("{}: {}", key, e))?;
+        async_std::fs::write(&path, body)
+            .await
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        downloaded.push(path);
+    }
+    info!(
+        "{} backup files downloaded from S3 to {}",
+        downloaded.len(),
+        dir.display()
+    );
+    Ok(downloaded)
+}
+
+/// 시작할 때 로컬에 `stamp_status` 스냅샷이 하나도 없으면 S3 호환 저장소에서 스냅샷을 내려받는 함수입니다.
+/// 디스크가 유지되지 않는 환경에서 다시 시작해도 손상된 데이터베이스를 저장소의 스냅샷으로 복구할 수 있습니다.
+pub async fn download_missing_snapshots(config: &BackupConfig) {
+    if config.s3.is_none() || !list_snapshots(Path::new(&config.dir), "stamp_status").is_empty() {
+        return;
+    }
+    if let Err(e) = download_snapshots(config).await {
+        error!("Backup download from S3 failed : {}", e);
+    }
+}
@@ -0,0 +1,98 @@
+use actix_web::{
+    body::{EitherBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    http::Uri,
+    middleware::Next,
+    web::Data,
+    HttpRequest, HttpResponse,
+};
+
+use crate::config::Config;
+use crate::state::Reloadable;
+
+/// 설정된 URL 접두사(예: `/stamptour`)를 정규화하여 반환합니다.
+/// 접두사가 없거나 `/`이면 빈 문자열을 반환합니다.
+///
+/// # Example
+///
+/// ```rust
+/// use gj_stamptour::base_path::normalize;
+///
+/// assert_eq!(normalize("stamptour/"), "/stamptour");
+/// assert_eq!(normalize("/"), "");
+/// ```
+pub fn normalize(base_path: &str) -> String {
+    let trimmed = base_path.trim().trim_matches('/');
+    if trimmed.is_empty() {
+        String::new()
+    } else {
+        format!("/{}", trimmed)
+    }
+}
+
+/// 요청의 설정에서 URL 접두사를 꺼내 반환합니다.
+pub fn base_path(req: &HttpRequest) -> String {
+    req.app_data::<Data<Reloadable<Config>>>()
+        .map(|config| normalize(&config.get().base_path))
+        .unwrap_or_default()
+}
+
+/// 서버 내부 경로 앞에 URL 접두사를 붙여 반환합니다.
+///
+/// # Arguments
+///
+/// * `req` - 설정을 앱 데이터에서 꺼내기 위한 `HttpRequest`입니다.
+/// * `path` - `/`로 시작하는 서버 내부 경로입니다.
+pub fn prefixed(req: &HttpRequest, path: &str) -> String {
+    format!("{}{}", base_path(req), path)
+}
+
+/// 리버스 프록시 뒤의 하위 경로(예: `/stamptour`)에 서버를 올릴 수 있도록
+/// 요청 경로에서 URL 접두사를 떼어낸 뒤 라우팅하는 미들웨어입니다.
+/// 접두사로 시작하지 않는 요청은 404 응답을 반환합니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// App::new()
+///     .wrap(actix_web::middleware::from_fn(strip_base_path))
+///     .configure(|cfg| state.register(cfg))
+///     .configure(routes);
+/// ```
+pub async fn strip_base_path(
+    mut req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, actix_web::Error> {
+    let base_path = base_path(req.request());
+    if base_path.is_empty() {
+        return next
+            .call(req)
+            .await
+            .map(ServiceResponse::map_into_left_body);
+    }
+
+    let path = req.path().to_string();
+    let stripped = match path.strip_prefix(&base_path) {
+        Some("") => "/",
+        Some(rest) if rest.starts_with('/') => rest,
+        _ => {
+            let response = HttpResponse::NotFound().finish();
+            return Ok(req.into_response(response).map_into_right_body());
+        }
+    };
+
+    // 쿼리 문자열은 유지한 채 경로만 교체
+    let path_and_query = match req.query_string() {
+        "" => stripped.to_string(),
+        query => format!("{}?{}", stripped, query),
+    };
+    let mut parts = req.head().uri.clone().into_parts();
+    parts.path_and_query = path_and_query.parse().ok();
+    if let Ok(uri) = Uri::from_parts(parts) {
+        req.match_info_mut().get_mut().update(&uri);
+        req.head_mut().uri = uri;
+    }
+    next.call(req)
+        .await
+        .map(ServiceResponse::map_into_left_body)
+}
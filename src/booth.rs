@@ -0,0 +1,365 @@
+use actix_web::{
+    get,
+    web::{Data, Path, Query},
+    HttpRequest, HttpResponse,
+};
+use chrono::{DateTime, Utc};
+use log::warn;
+use openssl::rand::rand_bytes;
+use qrcode::{render::svg, QrCode};
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+use crate::backup::{hmac_sha256, to_hex};
+use crate::base_path::prefixed;
+use crate::config::Config;
+use crate::error::AppError;
+use crate::report::parse_timestamp;
+use crate::staff::is_staff;
+use crate::state::{Reloadable, StampHistory, StampIdList};
+use crate::template::escape_html;
+
+/// QR 코드를 바꾸지 않을 때 부스 화면을 새로 고치는 간격(초)입니다.
+const DEFAULT_REFRESH_SECS: u64 = 30;
+
+/// `resources/config.json`의 `booth` 항목입니다.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct BoothConfig {
+    /// 부스 화면의 QR 코드를 바꾸는 간격(초)입니다. 설정하면 `/check`는 현재 또는 직전 QR 코드의
+    /// 토큰만 받으므로, 인쇄해 둔 QR 코드나 찍어 간 사진으로는 스템프를 찍을 수 없습니다.
+    /// 없으면 QR 코드를 바꾸지 않습니다.
+    pub qr_rotation_secs: Option<u64>,
+    /// 부스 화면에 표시할 최근 방문자 수입니다.
+    pub recent_visitors: usize,
+}
+
+impl Default for BoothConfig {
+    fn default() -> Self {
+        BoothConfig {
+            qr_rotation_secs: None,
+            recent_visitors: 10,
+        }
+    }
+}
+
+/// QR 코드 토큰 서명에 사용하는 키입니다. 서버를 시작할 때마다 새로 만들어집니다.
+static QR_SECRET: OnceLock<[u8; 32]> = OnceLock::new();
+
+fn qr_secret() -> &'static [u8; 32] {
+    QR_SECRET.get_or_init(|| {
+        let mut secret = [0u8; 32];
+        rand_bytes(&mut secret).unwrap();
+        secret
+    })
+}
+
+/// 주어진 시간 구간의 QR 코드 토큰을 계산합니다.
+fn qr_token_at(stamp_id: &str, window: i64) -> String {
+    to_hex(&hmac_sha256(qr_secret(), &format!("{}:{}", stamp_id, window))[..6])
+}
+
+/// 현재 QR 코드에 넣을 토큰을 반환합니다. QR 코드를 바꾸지 않도록 설정되어 있으면 `None`을 반환합니다.
+pub fn qr_token(config: &BoothConfig, stamp_id: &str, now: DateTime<Utc>) -> Option<String> {
+    let rotation = config.qr_rotation_secs?.max(1) as i64;
+    Some(qr_token_at(stamp_id, now.timestamp().div_euclid(rotation)))
+}
+
+/// `/check` 요청의 QR 코드 토큰을 확인하는 함수입니다. 화면이 바뀌는 순간에 찍은 방문객을 위해
+/// 직전 구간의 토큰도 허용합니다.
+///
+/// # Returns
+///
+/// QR 코드를 바꾸지 않도록 설정되어 있거나 토큰이 유효하면 `true`를 반환합니다.
+pub fn is_valid_qr_token(
+    config: &BoothConfig,
+    stamp_id: &str,
+    token: Option<&str>,
+    now: DateTime<Utc>,
+) -> bool {
+    let Some(rotation) = config.qr_rotation_secs else {
+        return true;
+    };
+    let window = now.timestamp().div_euclid(rotation.max(1) as i64);
+    token.is_some_and(|token| {
+        token == qr_token_at(stamp_id, window) || token == qr_token_at(stamp_id, window - 1)
+    })
+}
+
+/// 부스 화면 링크에 붙이는 부스별 접근 키입니다. `staff_token`으로 서명하므로 다른 부스 화면은 볼 수 없습니다.
+pub fn booth_key(staff_token: &str, stamp_id: &str) -> String {
+    to_hex(&hmac_sha256(staff_token.as_bytes(), stamp_id)[..8])
+}
+
+/// 부스 화면에 표시하는 최근 방문자입니다.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RecentVisitor {
+    pub user_name: String,
+    /// 서버 시간대 기준 방문 시각(`HH:MM:SS`)입니다.
+    pub time: String,
+}
+
+/// 부스 운영자가 휴대폰으로 확인하는 부스 현황입니다.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BoothSummary {
+    pub stamp_id: String,
+    pub stamp_name: String,
+    pub scan_count: usize,
+    /// 최근 방문자 목록입니다. 가장 최근 방문자가 먼저 옵니다.
+    pub recent_visitors: Vec<RecentVisitor>,
+}
+
+/// 스템프 기록으로 부스 현황을 계산하는 함수입니다.
+///
+/// # Arguments
+///
+/// * `stamp_id_list` - 전체 스템프 목록입니다.
+/// * `stamp_history` - 스템프 기록입니다.
+/// * `stamp_id` - 현황을 볼 스템프 ID입니다.
+/// * `limit` - 최근 방문자를 최대 몇 명까지 표시할지 정합니다.
+///
+/// # Returns
+///
+/// 스템프가 없으면 `None`을 반환합니다.
+pub fn booth_summary(
+    stamp_id_list: &StampIdList,
+    stamp_history: &StampHistory,
+    stamp_id: &str,
+    limit: usize,
+) -> Option<BoothSummary> {
+    let stamp = stamp_id_list.stamp_id_list.get(stamp_id)?;
+    let entries = stamp_history.entries(stamp_id);
+
+    Some(BoothSummary {
+        stamp_id: stamp.stampId.clone(),
+        stamp_name: stamp.stampName.clone(),
+        scan_count: entries.len(),
+        recent_visitors: entries
+            .iter()
+            .rev()
+            .take(limit)
+            .map(|entry| RecentVisitor {
+                user_name: entry.user_name.clone(),
+                time: parse_timestamp(&entry.timestamp)
+                    .map(|timestamp| timestamp.format("%H:%M:%S").to_string())
+                    .unwrap_or_default(),
+            })
+            .collect(),
+    })
+}
+
+/// 스템프를 찍는 `/check` 주소의 QR 코드를 SVG로 만듭니다.
+pub fn qr_svg(url: &str) -> String {
+    QrCode::new(url.as_bytes())
+        .map(|code| code.render::<svg::Color>().min_dimensions(240, 240).build())
+        .unwrap_or_default()
+}
+
+/// 부스 현황을 휴대폰에서 보기 좋은 HTML 문서로 변환하는 함수입니다.
+/// QR 코드가 바뀌는 간격마다 자동으로 새로 고칩니다.
+pub fn booth_html(summary: &BoothSummary, qr_svg: &str, refresh_secs: u64) -> String {
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html lang=\"ko\">\n<head><meta charset=\"utf-8\"><meta name=\"viewport\" content=\"width=device-width, initial-scale=1\"><meta http-equiv=\"refresh\" content=\"{refresh}\"><title>{name} 부스</title></head>\n<body>\n<h1>{name}</h1>\n<p>스캔 수: <strong>{count}</strong></p>\n<div>{qr}</div>\n",
+        refresh = refresh_secs,
+        name = escape_html(&summary.stamp_name),
+        count = summary.scan_count,
+        qr = qr_svg,
+    );
+
+    html.push_str("<h2>최근 방문자</h2>\n<table>\n<tr><th>시각</th><th>이름</th></tr>\n");
+    for visitor in summary.recent_visitors.iter() {
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td></tr>\n",
+            visitor.time,
+            escape_html(&visitor.user_name)
+        ));
+    }
+    html.push_str("</table>\n</body>\n</html>\n");
+    html
+}
+
+#[derive(Deserialize)]
+pub struct BoothQuery {
+    pub key: Option<String>,
+}
+
+/// 요청이 부스 화면을 볼 수 있는지 확인합니다. 직원 인증 또는 해당 부스의 접근 키가 필요합니다.
+fn is_booth_authorized(
+    req: &HttpRequest,
+    config: &Config,
+    stamp_id: &str,
+    key: Option<&str>,
+) -> bool {
+    is_staff(req, config)
+        || config
+            .staff_token
+            .as_deref()
+            .zip(key)
+            .is_some_and(|(staff_token, key)| key == booth_key(staff_token, stamp_id))
+}
+
+/// 스템프를 찍는 `/check` 경로를 만듭니다. QR 코드를 바꾸는 경우 현재 토큰을 포함합니다.
+///
+/// # Arguments
+///
+/// * `config` - QR 코드 설정입니다.
+/// * `stamp_id` - 토큰 계산에 사용하는 스템프 ID입니다.
+/// * `code` - `s=`에 넣을 스템프 ID 또는 별칭 코드입니다.
+/// * `now` - 토큰을 계산할 시각입니다.
+pub fn check_path(config: &BoothConfig, stamp_id: &str, code: &str, now: DateTime<Utc>) -> String {
+    match qr_token(config, stamp_id, now) {
+        Some(token) => format!("/check?t={}&s={}", token, code),
+        None => format!("/check?s={}", code),
+    }
+}
+
+/// QR 코드에 넣을 `/check` 전체 주소를 만듭니다. QR 코드를 바꾸는 경우 현재 토큰을 포함합니다.
+fn check_url(req: &HttpRequest, config: &Config, stamp_id: &str) -> String {
+    let check_path = check_path(&config.booth, stamp_id, stamp_id, Utc::now());
+    let connection = req.connection_info();
+    format!(
+        "{}://{}{}",
+        connection.scheme(),
+        connection.host(),
+        prefixed(req, &check_path)
+    )
+}
+
+/// 부스 운영자용 현황 페이지를 반환하는 비동기 함수입니다. 스캔 수, 최근 방문자, 스템프를 찍는 QR 코드를 보여줍니다.
+///
+/// # Arguments
+///
+/// * `stamp_id` - 현황을 볼 스템프 ID입니다.
+/// * `query` - `booth links` 관리자 명령으로 만든 부스별 접근 키(`key`)를 담은 쿼리입니다.
+///
+/// # Returns
+///
+/// 직원 인증 또는 부스별 접근 키가 맞으면 부스 현황 HTML이 200 OK 응답으로 반환됩니다.
+/// 인증에 실패하면 401 Unauthorized 응답이, 스템프가 없으면 404 Not Found 응답이 반환됩니다.
+#[get("/staff/booth/{stamp_id}")]
+pub async fn handle_booth(
+    req: HttpRequest,
+    stamp_id: Path<String>,
+    query: Query<BoothQuery>,
+    stamp_id_list: Data<Reloadable<StampIdList>>,
+    stamp_history: Data<StampHistory>,
+    config: Data<Reloadable<Config>>,
+) -> Result<HttpResponse, AppError> {
+    let config = config.get();
+    if !is_booth_authorized(&req, &config, &stamp_id, query.key.as_deref()) {
+        warn!("Unauthorized access to the booth page has been identified.");
+        return Err(AppError::Unauthorized);
+    }
+
+    let summary = booth_summary(
+        &stamp_id_list.get(),
+        &stamp_history,
+        &stamp_id,
+        config.booth.recent_visitors,
+    );
+    let Some(summary) = summary else {
+        return Err(AppError::NotFound);
+    };
+
+    let url = check_url(&req, &config, &summary.stamp_id);
+    let refresh_secs = config
+        .booth
+        .qr_rotation_secs
+        .unwrap_or(DEFAULT_REFRESH_SECS);
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(booth_html(&summary, &qr_svg(&url), refresh_secs)))
+}
+
+/// 키오스크 화면이 QR 코드를 다시 받아오는 간격(초)입니다. QR 코드가 바뀌는 간격의 절반으로 하여
+/// 화면의 QR 코드가 직전 구간보다 오래되지 않도록 합니다.
+pub fn kiosk_poll_secs(config: &BoothConfig) -> u64 {
+    config
+        .qr_rotation_secs
+        .map_or(DEFAULT_REFRESH_SECS, |rotation| (rotation / 2).max(1))
+}
+
+/// 태블릿에 전체 화면으로 띄우는 키오스크 HTML을 만듭니다. QR 코드는 `{현재 주소}/qr`에서 주기적으로 다시 받아옵니다.
+pub fn kiosk_html(stamp_name: &str, qr_svg: &str, poll_secs: u64) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"ko\">\n<head><meta charset=\"utf-8\"><meta name=\"viewport\" content=\"width=device-width, initial-scale=1\"><title>{name}</title>\n<style>html,body{{height:100%;margin:0}}body{{display:flex;flex-direction:column;align-items:center;justify-content:center;font-family:sans-serif}}#qr svg{{width:min(80vw,80vh);height:auto}}</style></head>\n<body>\n<h1>{name}</h1>\n<div id=\"qr\">{qr}</div>\n<script>\nsetInterval(function () {{\n  fetch(location.pathname + \"/qr\" + location.search, {{ cache: \"no-store\" }})\n    .then(function (res) {{ return res.ok ? res.text() : Promise.reject(res.status); }})\n    .then(function (svg) {{ document.getElementById(\"qr\").innerHTML = svg; }})\n    .catch(function () {{}});\n}}, {poll_ms});\n</script>\n</body>\n</html>\n",
+        name = escape_html(stamp_name),
+        qr = qr_svg,
+        poll_ms = poll_secs * 1000,
+    )
+}
+
+/// 부스에서 인쇄물 대신 태블릿으로 QR 코드를 보여주는 키오스크 페이지를 반환하는 비동기 함수입니다.
+///
+/// # Arguments
+///
+/// * `stamp_id` - QR 코드를 보여줄 스템프 ID입니다.
+/// * `query` - 부스별 접근 키(`key`)를 담은 쿼리입니다.
+///
+/// # Returns
+///
+/// 인증에 성공하면 키오스크 HTML이 200 OK 응답으로 반환됩니다.
+/// 인증에 실패하면 401 Unauthorized 응답이, 스템프가 없으면 404 Not Found 응답이 반환됩니다.
+#[get("/kiosk/{stamp_id}")]
+pub async fn handle_kiosk(
+    req: HttpRequest,
+    stamp_id: Path<String>,
+    query: Query<BoothQuery>,
+    stamp_id_list: Data<Reloadable<StampIdList>>,
+    config: Data<Reloadable<Config>>,
+) -> Result<HttpResponse, AppError> {
+    let config = config.get();
+    if !is_booth_authorized(&req, &config, &stamp_id, query.key.as_deref()) {
+        warn!("Unauthorized access to the kiosk page has been identified.");
+        return Err(AppError::Unauthorized);
+    }
+    let Some(stamp) = stamp_id_list
+        .get()
+        .stamp_id_list
+        .get(stamp_id.as_str())
+        .cloned()
+    else {
+        return Err(AppError::NotFound);
+    };
+
+    let url = check_url(&req, &config, &stamp.stampId);
+    Ok(HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(kiosk_html(
+            &stamp.stampName,
+            &qr_svg(&url),
+            kiosk_poll_secs(&config.booth),
+        )))
+}
+
+/// 키오스크 화면이 주기적으로 요청하는 현재 QR 코드를 SVG로 반환하는 비동기 함수입니다.
+///
+/// # Returns
+///
+/// 인증에 성공하면 `image/svg+xml` 형식의 QR 코드가 200 OK 응답으로 반환됩니다.
+/// 인증에 실패하면 401 Unauthorized 응답이, 스템프가 없으면 404 Not Found 응답이 반환됩니다.
+#[get("/kiosk/{stamp_id}/qr")]
+pub async fn handle_kiosk_qr(
+    req: HttpRequest,
+    stamp_id: Path<String>,
+    query: Query<BoothQuery>,
+    stamp_id_list: Data<Reloadable<StampIdList>>,
+    config: Data<Reloadable<Config>>,
+) -> Result<HttpResponse, AppError> {
+    let config = config.get();
+    if !is_booth_authorized(&req, &config, &stamp_id, query.key.as_deref()) {
+        return Err(AppError::Unauthorized);
+    }
+    if !stamp_id_list
+        .get()
+        .stamp_id_list
+        .contains_key(stamp_id.as_str())
+    {
+        return Err(AppError::NotFound);
+    }
+
+    Ok(HttpResponse::Ok()
+        .content_type("image/svg+xml")
+        .body(qr_svg(&check_url(&req, &config, &stamp_id))))
+}
@@ -0,0 +1,324 @@
+use actix_web::{
+    post,
+    web::{Bytes, Data, Json},
+    HttpRequest, HttpResponse,
+};
+use log::{error, info, warn};
+use openssl::{base64::encode_block, hash::MessageDigest, memcmp, pkey::PKey, sign::Signer};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::{Mutex, OnceLock};
+
+use crate::config::Config;
+use crate::error::AppError;
+use crate::lock::MutexExt;
+use crate::progress::{collected_stamps, user_progress};
+use crate::report::parse_timestamp;
+use crate::state::{Reloadable, Stamp, StampHistory, StampIdList, UserList};
+
+/// LINE 응답 메시지를 보내는 API 주소입니다.
+const LINE_REPLY_URL: &str = "https://api.line.me/v2/bot/message/reply";
+
+/// `resources/config.json`의 `bot` 항목입니다. 설정한 메신저의 웹훅만 사용할 수 있습니다.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct BotConfig {
+    pub telegram: Option<TelegramConfig>,
+    pub line: Option<LineConfig>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TelegramConfig {
+    /// `setWebhook`의 `secret_token`으로 지정한 값입니다.
+    /// Telegram이 `X-Telegram-Bot-Api-Secret-Token` 헤더로 보내므로 다른 곳에서 온 요청을 거를 수 있습니다.
+    pub secret_token: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LineConfig {
+    /// 웹훅 서명(`X-Line-Signature`) 확인에 사용하는 채널 비밀 값입니다.
+    pub channel_secret: String,
+    /// 응답 메시지를 보낼 때 사용하는 채널 액세스 토큰입니다.
+    pub channel_access_token: String,
+}
+
+/// 아직 찍지 않은 스템프 중 안내할 부스를 고르는 함수입니다.
+/// 모두 발급된 스템프와 숨은 스템프는 제외합니다.
+///
+/// # Returns
+///
+/// 유저가 마지막으로 찍은 부스와 가장 가까운 부스를 반환합니다. 위치가 설정되지 않아 거리를 비교할 수 없으면
+/// 스템프 ID 순서로 첫 번째 부스를 반환하며, 남은 부스가 없으면 `None`을 반환합니다.
+pub fn nearest_uncollected<'a>(
+    stamp_id_list: &'a StampIdList,
+    stamp_history: &StampHistory,
+    user_id: &str,
+) -> Option<&'a Stamp> {
+    let collected = collected_stamps(stamp_id_list, stamp_history, user_id);
+    let mut remaining: Vec<&Stamp> = stamp_id_list
+        .visible()
+        .filter(|stamp| !collected.contains(&stamp.stampId))
+        .filter(|stamp| !stamp.is_sold_out(stamp_history.issued(&stamp.stampId)))
+        .collect();
+    remaining.sort_by(|a, b| a.stampId.cmp(&b.stampId));
+
+    // 위치가 있는 스템프 중 가장 최근에 찍은 스템프의 위치를 기준으로 함
+    let last_position = stamp_history
+        .to_map()
+        .iter()
+        .filter_map(|(stamp_id, entries)| {
+            let coordinates = stamp_id_list
+                .stamp_id_list
+                .get(stamp_id)?
+                .stampCoordinates?;
+            entries
+                .iter()
+                .filter(|entry| entry.user_id == user_id)
+                .map(|entry| (parse_timestamp(&entry.timestamp), coordinates))
+                .max_by_key(|(timestamp, _)| *timestamp)
+        })
+        .max_by_key(|(timestamp, _)| *timestamp)
+        .map(|(_, coordinates)| coordinates);
+
+    let nearest = last_position.and_then(|position| {
+        remaining
+            .iter()
+            .filter_map(|stamp| Some((*stamp, stamp.stampCoordinates?.distance_meters(&position))))
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(stamp, _)| stamp)
+    });
+    nearest.or(remaining.first().copied())
+}
+
+/// 방문객이 보낸 메시지에 대한 답장을 만드는 함수입니다.
+///
+/// # Arguments
+///
+/// * `text` - 방문객이 보낸 메시지입니다. 6자리 유저 코드이면 진행 상황과 다음 부스를 안내하고,
+///   그 밖의 메시지에는 사용법을 안내합니다.
+///
+/// # Returns
+///
+/// 답장 문구를 반환합니다. 유저 코드만으로 조회되므로 이름과 같은 개인정보는 포함하지 않습니다.
+pub fn reply_for(
+    config: &Config,
+    stamp_id_list: &StampIdList,
+    stamp_history: &StampHistory,
+    user_list: &UserList,
+    text: &str,
+) -> String {
+    let code = text.trim();
+    if code.len() != 6 || !code.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return "안녕하세요! 스템프 투어 페이지에 표시된 6자리 유저 코드를 보내주시면 진행 상황을 알려드립니다."
+            .to_string();
+    }
+    let Some(user_id) = user_list.find_by_code(code) else {
+        return "유저 코드를 찾을 수 없습니다. 코드를 다시 확인해주세요.".to_string();
+    };
+
+    let progress = user_progress(config, stamp_id_list, stamp_history, user_id, "");
+    let mut lines = vec![format!(
+        "📋 스템프 {}/{}개 · {}점",
+        progress.collected_count, progress.total_count, progress.points
+    )];
+    if let Some(tier) = &progress.tier {
+        lines.push(format!("🏅 {} 등급 달성", tier));
+    }
+    if let Some(next_tier) = &progress.next_tier {
+        lines.push(format!(
+            "다음 {} 등급까지 {}개 남았습니다.",
+            next_tier.tier_name, next_tier.remaining
+        ));
+    }
+    match nearest_uncollected(stamp_id_list, stamp_history, user_id) {
+        Some(stamp) => lines.push(format!(
+            "📍 다음 부스: {} ({})",
+            stamp.stampName, stamp.stampLocation
+        )),
+        None if progress.collected_count == progress.total_count => {
+            lines.push("🎉 모든 스템프를 모았습니다!".to_string())
+        }
+        None => lines.push("남은 스템프가 모두 발급되었습니다.".to_string()),
+    }
+    lines.join("\n")
+}
+
+/// 현재 상태로 답장을 만듭니다. 잠금 순서(`user_list` → `stamp_history`)를 지킵니다.
+fn reply(
+    config: &Config,
+    stamp_id_list: &StampIdList,
+    stamp_history: &StampHistory,
+    user_list: &Mutex<UserList>,
+    text: &str,
+) -> String {
+    let user_list = user_list.lock_or_recover();
+    reply_for(config, stamp_id_list, stamp_history, &user_list, text)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TelegramUpdate {
+    pub message: Option<TelegramMessage>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TelegramMessage {
+    pub chat: TelegramChat,
+    pub text: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TelegramChat {
+    pub id: i64,
+}
+
+/// Telegram 봇 웹훅을 처리하는 비동기 함수입니다.
+///
+/// # Returns
+///
+/// 텍스트 메시지에는 `sendMessage` 메서드를 담은 응답으로 바로 답장합니다. 그 밖의 업데이트는 빈 200 OK 응답이 반환됩니다.
+/// 봇이 설정되지 않았으면 404 Not Found 응답이, 비밀 값이 틀리면 401 Unauthorized 응답이 반환됩니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// // https://api.telegram.org/bot<토큰>/setWebhook?url=https://<주소>/api/v1/bot/telegram&secret_token=<secret_token>
+/// ```
+#[post("/api/v1/bot/telegram")]
+pub async fn handle_telegram(
+    req: HttpRequest,
+    update: Json<TelegramUpdate>,
+    user_list: Data<Mutex<UserList>>,
+    stamp_id_list: Data<Reloadable<StampIdList>>,
+    stamp_history: Data<StampHistory>,
+    config: Data<Reloadable<Config>>,
+) -> Result<HttpResponse, AppError> {
+    let config = config.get();
+    let Some(telegram) = config.bot.telegram.as_ref() else {
+        return Err(AppError::NotFound);
+    };
+    let secret = req
+        .headers()
+        .get("x-telegram-bot-api-secret-token")
+        .map(|value| value.as_bytes())
+        .unwrap_or_default();
+    if secret.len() != telegram.secret_token.len()
+        || !memcmp::eq(secret, telegram.secret_token.as_bytes())
+    {
+        warn!("Unauthorized access to the Telegram webhook has been identified.");
+        return Err(AppError::Unauthorized);
+    }
+
+    let Some(TelegramMessage {
+        chat,
+        text: Some(text),
+    }) = update.into_inner().message
+    else {
+        return Ok(HttpResponse::Ok().finish());
+    };
+    let text = reply(
+        &config,
+        &stamp_id_list.get(),
+        &stamp_history,
+        &user_list,
+        &text,
+    );
+    Ok(HttpResponse::Ok()
+        .json(json!({ "method": "sendMessage", "chat_id": chat.id, "text": text })))
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LineWebhook {
+    pub events: Vec<LineEvent>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LineEvent {
+    pub reply_token: Option<String>,
+    pub message: Option<LineMessage>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LineMessage {
+    pub text: Option<String>,
+}
+
+/// LINE 웹훅 본문의 서명(`X-Line-Signature`)을 계산합니다. 채널 비밀 값으로 만든 HMAC-SHA256의 base64입니다.
+pub fn line_signature(channel_secret: &str, body: &[u8]) -> String {
+    let key = PKey::hmac(channel_secret.as_bytes()).unwrap();
+    let mut signer = Signer::new(MessageDigest::sha256(), &key).unwrap();
+    signer.update(body).unwrap();
+    encode_block(&signer.sign_to_vec().unwrap())
+}
+
+fn client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(reqwest::Client::new)
+}
+
+/// LINE 웹훅을 처리하는 비동기 함수입니다. 서명을 확인한 뒤 텍스트 메시지마다 응답 API로 답장합니다.
+///
+/// # Returns
+///
+/// 답장은 비동기로 보내며 바로 200 OK 응답이 반환됩니다. 봇이 설정되지 않았으면 404 Not Found 응답이,
+/// 서명이 틀리면 401 Unauthorized 응답이, 본문 형식이 틀리면 400 Bad Request 응답이 반환됩니다.
+#[post("/api/v1/bot/line")]
+pub async fn handle_line(
+    req: HttpRequest,
+    body: Bytes,
+    user_list: Data<Mutex<UserList>>,
+    stamp_id_list: Data<Reloadable<StampIdList>>,
+    stamp_history: Data<StampHistory>,
+    config: Data<Reloadable<Config>>,
+) -> Result<HttpResponse, AppError> {
+    let config = config.get();
+    let Some(line) = config.bot.line.clone() else {
+        return Err(AppError::NotFound);
+    };
+    let expected = line_signature(&line.channel_secret, &body);
+    let signature = req
+        .headers()
+        .get("x-line-signature")
+        .map(|value| value.as_bytes())
+        .unwrap_or_default();
+    if signature.len() != expected.len() || !memcmp::eq(signature, expected.as_bytes()) {
+        warn!("Unauthorized access to the LINE webhook has been identified.");
+        return Err(AppError::Unauthorized);
+    }
+    let webhook = serde_json::from_slice::<LineWebhook>(&body)
+        .map_err(|e| AppError::Validation(format!("Invalid LINE webhook: {}", e)))?;
+
+    let stamp_id_list = stamp_id_list.get();
+    let replies: Vec<(String, String)> = webhook
+        .events
+        .into_iter()
+        .filter_map(|event| {
+            let text = event.message?.text?;
+            Some((
+                event.reply_token?,
+                reply(&config, &stamp_id_list, &stamp_history, &user_list, &text),
+            ))
+        })
+        .collect();
+    if !replies.is_empty() {
+        info!("Replying to {} LINE messages.", replies.len());
+        actix_rt::spawn(async move {
+            for (reply_token, text) in replies {
+                let result = client()
+                    .post(LINE_REPLY_URL)
+                    .bearer_auth(&line.channel_access_token)
+                    .json(&json!({
+                        "replyToken": reply_token,
+                        "messages": [{ "type": "text", "text": text }],
+                    }))
+                    .send()
+                    .await
+                    .and_then(|response| response.error_for_status());
+                if let Err(e) = result {
+                    error!("LINE reply failed : {}", e);
+                }
+            }
+        });
+    }
+    Ok(HttpResponse::Ok().finish())
+}
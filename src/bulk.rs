@@ -0,0 +1,352 @@
+use actix_web::{
+    http::StatusCode,
+    routes,
+    web::{Data, Json},
+    HttpRequest, HttpResponse,
+};
+use chrono::Utc;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Mutex,
+};
+
+use crate::config::Config;
+use crate::error::AppError;
+use crate::lock::MutexExt;
+use crate::notifier::{Event, Notifier};
+use crate::persistence::{Dataset, Persister};
+use crate::progress::record_progress;
+use crate::retention::{soft_delete_user, PersonalData};
+use crate::staff::{is_staff, revoke_stamp};
+use crate::state::{
+    AuditLog, AuditRecord, CompletionList, Reloadable, StampHistory, StampIdList, StampUserInfo,
+    TeamList, UserList, UserStampList,
+};
+
+/// 한 번에 처리할 수 있는 최대 작업 수입니다.
+pub const MAX_BULK_OPERATIONS: usize = 1000;
+
+/// 일괄 처리할 작업 하나입니다. 유저는 ID 또는 6자리 유저 코드로 적습니다.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BulkOperation {
+    /// `delete user` 관리자 명령과 같이 되돌릴 수 있게 삭제합니다.
+    DeleteUser { user: String },
+    /// 가장 최근의 스템프 기록을 취소합니다.
+    RevokeStamp { user: String, stamp_id: String },
+    /// 아직 없는 스템프 기록을 지금 시각으로 추가합니다. 수량 제한은 확인하지 않습니다.
+    AddStamp { user: String, stamp_id: String },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BulkRequest {
+    pub operations: Vec<BulkOperation>,
+    /// 감사 기록에 남길 작업자 이름입니다.
+    #[serde(default = "default_actor")]
+    pub actor: String,
+    /// 모든 작업의 감사 기록에 남길 사유입니다.
+    pub reason: String,
+}
+
+fn default_actor() -> String {
+    "admin".to_string()
+}
+
+/// 실행할 수 없는 작업입니다. `index`는 요청한 작업 목록에서의 위치입니다.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BulkError {
+    pub index: usize,
+    pub error: String,
+}
+
+/// 일괄 처리 결과입니다. 실행할 수 없는 작업이 하나라도 있으면 아무것도 바꾸지 않고 `applied`가 `false`입니다.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct BulkResult {
+    pub applied: bool,
+    pub deleted_users: usize,
+    pub revoked_stamps: usize,
+    pub added_stamps: usize,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<BulkError>,
+}
+
+/// 유저를 찾아 확인을 마친 작업입니다.
+enum Planned {
+    DeleteUser(String),
+    RevokeStamp(String, String),
+    AddStamp(String, String),
+}
+
+/// 모든 작업을 앞에서부터 차례로 실행한다고 가정하고 실행할 수 있는지 확인합니다.
+/// 같은 요청 안에서 앞서 삭제한 유저나 앞서 추가, 취소한 기록도 반영합니다.
+fn plan(
+    operations: &[BulkOperation],
+    user_list: &UserList,
+    stamp_history: &StampHistory,
+    stamp_id_list: &StampIdList,
+) -> Result<Vec<Planned>, Vec<BulkError>> {
+    let mut deleted = HashSet::new();
+    // (유저 ID, 스템프 ID)별로 앞선 작업이 바꾼 기록 수
+    let mut changed: HashMap<(String, String), i64> = HashMap::new();
+    let mut planned = Vec::new();
+    let mut errors = Vec::new();
+
+    for (index, operation) in operations.iter().enumerate() {
+        let (BulkOperation::DeleteUser { user }
+        | BulkOperation::RevokeStamp { user, .. }
+        | BulkOperation::AddStamp { user, .. }) = operation;
+        let user_id = match user_list.resolve(user) {
+            Some(user_id) if !deleted.contains(&user_id) => user_id,
+            _ => {
+                errors.push(BulkError {
+                    index,
+                    error: format!("User {} not found", user),
+                });
+                continue;
+            }
+        };
+        let result = match operation {
+            BulkOperation::DeleteUser { .. } => {
+                deleted.insert(user_id.clone());
+                Ok(Planned::DeleteUser(user_id))
+            }
+            BulkOperation::RevokeStamp { stamp_id, .. }
+            | BulkOperation::AddStamp { stamp_id, .. }
+                if !stamp_id_list.stamp_id_list.contains_key(stamp_id) =>
+            {
+                Err(format!("Stamp {} not found", stamp_id))
+            }
+            BulkOperation::RevokeStamp { stamp_id, .. }
+            | BulkOperation::AddStamp { stamp_id, .. } => {
+                let key = (user_id.clone(), stamp_id.clone());
+                let count = stamp_history
+                    .entries(stamp_id)
+                    .iter()
+                    .filter(|entry| entry.user_id == user_id)
+                    .count() as i64
+                    + changed.get(&key).copied().unwrap_or_default();
+                match operation {
+                    BulkOperation::RevokeStamp { .. } if count <= 0 => Err(format!(
+                        "User {} has no record for stamp {}",
+                        user_id, stamp_id
+                    )),
+                    BulkOperation::RevokeStamp { .. } => {
+                        *changed.entry(key).or_default() -= 1;
+                        Ok(Planned::RevokeStamp(user_id, stamp_id.clone()))
+                    }
+                    _ if count > 0 => {
+                        Err(format!("User {} already has stamp {}", user_id, stamp_id))
+                    }
+                    _ => {
+                        *changed.entry(key).or_default() += 1;
+                        Ok(Planned::AddStamp(user_id, stamp_id.clone()))
+                    }
+                }
+            }
+        };
+        match result {
+            Ok(operation) => planned.push(operation),
+            Err(error) => errors.push(BulkError { index, error }),
+        }
+    }
+
+    match errors.is_empty() {
+        true => Ok(planned),
+        false => Err(errors),
+    }
+}
+
+/// 작업 목록을 한꺼번에 실행하는 함수입니다. 모든 락을 잡은 채 먼저 전부 확인한 뒤 실행하므로,
+/// 실행할 수 없는 작업이 하나라도 있으면 아무것도 바꾸지 않습니다.
+///
+/// # Arguments
+///
+/// * `request` - 작업 목록과 작업자, 사유입니다.
+/// * `data` - 잠근 참가자 데이터 묶음입니다.
+/// * `config` - 완주 등급을 확인할 설정입니다.
+/// * `stamp_id_list` - 스템프 목록입니다.
+/// * `notifier` - 완주 인원 달성을 확인할 알림 설정입니다.
+///
+/// # Returns
+///
+/// 처리 결과와, 락을 놓은 뒤 `Notifier::notify`로 보낼 완주 인원 달성 사건 목록을 반환합니다.
+pub fn run_bulk(
+    request: &BulkRequest,
+    data: PersonalData,
+    config: &Config,
+    stamp_id_list: &StampIdList,
+    notifier: &Notifier,
+) -> (BulkResult, Vec<Event>) {
+    let planned = match plan(
+        &request.operations,
+        data.user_list,
+        data.stamp_history,
+        stamp_id_list,
+    ) {
+        Ok(planned) => planned,
+        Err(errors) => {
+            let result = BulkResult {
+                errors,
+                ..Default::default()
+            };
+            return (result, Vec::new());
+        }
+    };
+
+    let mut result = BulkResult {
+        applied: true,
+        ..Default::default()
+    };
+    let mut stamped = Vec::new();
+    for operation in planned {
+        match operation {
+            Planned::DeleteUser(user_id) => {
+                soft_delete_user(
+                    PersonalData {
+                        user_list: &mut *data.user_list,
+                        user_stamp_list: &mut *data.user_stamp_list,
+                        stamp_history: data.stamp_history,
+                        completions: &mut *data.completions,
+                        teams: &mut *data.teams,
+                        audit_log: &mut *data.audit_log,
+                    },
+                    &user_id,
+                    &request.actor,
+                    &request.reason,
+                );
+                result.deleted_users += 1;
+            }
+            Planned::RevokeStamp(user_id, stamp_id) => {
+                revoke_stamp(
+                    data.stamp_history,
+                    data.audit_log,
+                    &user_id,
+                    &stamp_id,
+                    &request.actor,
+                    &request.reason,
+                );
+                result.revoked_stamps += 1;
+            }
+            Planned::AddStamp(user_id, stamp_id) => {
+                let timestamp = Utc::now().to_string();
+                data.stamp_history.push(
+                    &stamp_id,
+                    StampUserInfo {
+                        user_name: data.user_list.users[&user_id].clone(),
+                        user_id: user_id.clone(),
+                        timestamp: timestamp.clone(),
+                        seq: 0, // 기록할 때 순번 발급
+                    },
+                );
+                data.audit_log.entries.push(AuditRecord {
+                    action: "add stamp".to_string(),
+                    user_id: user_id.clone(),
+                    stamp_id: Some(stamp_id),
+                    actor: request.actor.clone(),
+                    reason: request.reason.clone(),
+                    timestamp,
+                });
+                stamped.push(user_id);
+                result.added_stamps += 1;
+            }
+        }
+    }
+
+    // 추가한 스템프로 달성한 완주 등급 기록 (이후 작업에서 삭제된 유저는 제외)
+    stamped.sort();
+    stamped.dedup();
+    let mut milestones = Vec::new();
+    for user_id in stamped
+        .iter()
+        .filter(|user_id| data.user_list.users.contains_key(*user_id))
+    {
+        milestones.extend(record_progress(
+            config,
+            stamp_id_list,
+            data.stamp_history,
+            data.completions,
+            data.teams,
+            notifier,
+            user_id,
+        ));
+    }
+    (result, milestones)
+}
+
+/// 시험 운영일 뒤 정리처럼 많은 유저 삭제, 스템프 취소, 스템프 추가를 한 번의 요청으로 처리하는 비동기 함수입니다.
+/// 직원 API와 같은 인증(`Authorization: Bearer <staff_token>`, 없으면 로컬 요청)이 필요합니다.
+///
+/// # Returns
+///
+/// 모든 작업을 실행하면 처리 수를 담은 `BulkResult`가 200 OK 응답으로 반환됩니다.
+/// 실행할 수 없는 작업이 있으면 아무것도 바꾸지 않고 `errors`를 담은 400 Bad Request 응답이 반환됩니다.
+/// 직원 인증에 실패하면 401 Unauthorized 응답이 반환됩니다.
+#[routes]
+#[post("/api/v1/admin/bulk")]
+#[post("/api/admin/bulk")]
+#[allow(clippy::too_many_arguments)]
+pub async fn handle_bulk(
+    req: HttpRequest,
+    body: Json<BulkRequest>,
+    user_list: Data<Mutex<UserList>>,
+    user_stamp_list: Data<Mutex<UserStampList>>,
+    stamp_history: Data<StampHistory>,
+    completions: Data<Mutex<CompletionList>>,
+    teams: Data<Mutex<TeamList>>,
+    audit_log: Data<Mutex<AuditLog>>,
+    stamp_id_list: Data<Reloadable<StampIdList>>,
+    config: Data<Reloadable<Config>>,
+    notifier: Data<Notifier>,
+    persister: Data<Persister>,
+) -> Result<HttpResponse, AppError> {
+    let config = config.get();
+    if !is_staff(&req, &config) {
+        warn!("Unauthorized access to the bulk admin API has been identified.");
+        return Err(AppError::Unauthorized);
+    }
+    if body.operations.is_empty() || body.operations.len() > MAX_BULK_OPERATIONS {
+        return Err(AppError::Validation(format!(
+            "operations must contain 1 to {} items",
+            MAX_BULK_OPERATIONS
+        )));
+    }
+
+    let (result, milestones) = run_bulk(
+        &body,
+        PersonalData {
+            user_list: &mut user_list.lock_or_recover(),
+            user_stamp_list: &mut user_stamp_list.lock_or_recover(),
+            stamp_history: &stamp_history,
+            completions: &mut completions.lock_or_recover(),
+            teams: &mut teams.lock_or_recover(),
+            audit_log: &mut audit_log.lock_or_recover(),
+        },
+        &config,
+        &stamp_id_list.get(),
+        &notifier,
+    );
+    for event in milestones {
+        notifier.notify(event);
+    }
+    if !result.applied {
+        return Err(AppError::Rejected(
+            StatusCode::BAD_REQUEST,
+            serde_json::to_value(&result).unwrap_or_default(),
+        ));
+    }
+
+    persister.mark(&[
+        Dataset::UserStatus,
+        Dataset::StampStatus,
+        Dataset::CompletionStatus,
+        Dataset::TeamStatus,
+        Dataset::AuditLog,
+    ]);
+    info!(
+        "{} ran bulk operations ({} users deleted, {} stamps revoked, {} stamps added) : {}",
+        body.actor, result.deleted_users, result.revoked_stamps, result.added_stamps, body.reason
+    );
+    Ok(HttpResponse::Ok().json(result))
+}
@@ -0,0 +1,99 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use crate::config::Config;
+use crate::storage::{parse_stamp_list, resources_dir};
+
+/// `--event-dir`로 지정한 행사 폴더입니다. 지정하지 않으면 기존 `resources` 폴더를 사용합니다.
+static EVENT_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// 행사 폴더에 꼭 있어야 하는 HTML 템플릿입니다.
+pub const REQUIRED_TEMPLATES: [&str; 5] = [
+    "index.html",
+    "check.html",
+    "complete.html",
+    "error404.html",
+    "error500.html",
+];
+
+/// `--event-dir <폴더>` 실행 인수를 찾아 행사 폴더로 사용하도록 설정합니다.
+/// 설정 파일을 읽기 전에 호출해야 하며, 한 번 설정한 폴더는 바꾸지 않습니다.
+///
+/// # Returns
+///
+/// 행사 폴더를 지정했으면 그 경로를 반환합니다.
+pub fn select_event_dir(args: &[String]) -> Option<&'static Path> {
+    let dir = args
+        .iter()
+        .position(|arg| arg == "--event-dir")
+        .and_then(|index| args.get(index + 1))?;
+    Some(EVENT_DIR.get_or_init(|| PathBuf::from(dir)))
+}
+
+/// 사용 중인 행사 폴더를 반환합니다. `--event-dir`로 실행하지 않았으면 `None`입니다.
+pub fn event_dir() -> Option<&'static Path> {
+    EVENT_DIR.get().map(PathBuf::as_path)
+}
+
+/// 행사마다 다른 파일(`config.json`, `api/stampList.json`, `missions.json` 등)의 경로를 만드는 함수입니다.
+/// 템플릿과 같은 `resources_dir()` 기준이므로 행사 폴더를 사용하면 그 안의 경로를, 아니면 실행 파일 옆 `resources` 안의 경로를 반환합니다.
+///
+/// # Example
+///
+/// ```rust
+/// use gj_stamptour::bundle::event_path;
+/// use gj_stamptour::storage::resources_dir;
+///
+/// assert_eq!(event_path("config.json"), resources_dir().join("config.json"));
+/// ```
+pub fn event_path(relative: &str) -> PathBuf {
+    resources_dir().join(relative)
+}
+
+/// 행사 폴더가 서버를 시작할 수 있는 구성인지 확인하는 함수입니다.
+/// 하나만 고치고 다시 실행하는 일이 없도록 발견한 문제를 모두 모아 반환합니다.
+///
+/// 행사 폴더는 `resources` 폴더와 같은 구조이며 다음을 확인합니다.
+/// * `config.json` - 있으면 올바른 설정이어야 합니다. 없으면 기본 설정을 사용합니다.
+/// * `api/stampList.json` - 있어야 하며 `parse_stamp_list`의 검증을 통과해야 합니다.
+/// * `html/` - `REQUIRED_TEMPLATES`의 템플릿이 모두 있어야 합니다.
+///
+/// # Returns
+///
+/// 문제가 없으면 `Ok(())`, 있으면 문제를 한 줄에 하나씩 담은 목록을 반환합니다.
+pub fn validate_bundle(dir: &Path) -> Result<(), Vec<String>> {
+    if !dir.is_dir() {
+        return Err(vec![format!("{} is not a directory", dir.display())]);
+    }
+    let mut problems = Vec::new();
+
+    if let Ok(content) = fs::read_to_string(dir.join("config.json")) {
+        if let Err(e) = serde_json::from_str::<Config>(&content) {
+            problems.push(format!("config.json: {}", e));
+        }
+    }
+
+    match fs::read_to_string(dir.join("api/stampList.json")) {
+        Ok(content) => {
+            if let Err(e) = parse_stamp_list(&content) {
+                problems.push(format!("api/stampList.json: {}", e));
+            }
+        }
+        Err(e) => problems.push(format!("api/stampList.json: {}", e)),
+    }
+
+    for template in REQUIRED_TEMPLATES {
+        if !dir.join("html").join(template).is_file() {
+            problems.push(format!("html/{} is missing", template));
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(problems)
+    }
+}
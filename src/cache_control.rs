@@ -0,0 +1,136 @@
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    http::header::{HeaderValue, CACHE_CONTROL, SET_COOKIE},
+    middleware::Next,
+};
+use std::path::Path;
+
+/// 지문이 붙지 않은 이미지와 글꼴의 캐시 시간입니다. 행사 중에 지도 이미지를 바꾸더라도 하루 안에 반영됩니다.
+pub const ASSET_CACHE: &str = "public, max-age=86400";
+
+/// 이미지와 글꼴 확장자입니다.
+const ASSET_EXTENSIONS: [&str; 11] = [
+    "png", "jpg", "jpeg", "gif", "webp", "svg", "ico", "ttf", "otf", "woff", "woff2",
+];
+
+/// 브라우저나 프록시에 남으면 안 되는 로그인(세션 유저 ID를 반환), 부스 운영자, 키오스크, 개인정보 경로입니다.
+/// `/`로 끝나는 항목은 그 아래의 모든 경로를 뜻합니다.
+const PRIVATE_PATHS: [&str; 7] = [
+    "/login",
+    "/api/v1/login",
+    "/staff/",
+    "/kiosk/",
+    "/me",
+    "/me/",
+    "/api/v1/me/",
+];
+
+/// 응답의 캐시 정책 분류입니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheClass {
+    /// HTML 페이지, 스템프 페이지, JSON API처럼 매번 서버에 확인해야 하는 응답입니다.
+    Revalidate,
+    /// 이미지와 글꼴처럼 오래 캐시해도 되는 응답입니다.
+    Asset,
+    /// 관리자 API, 로그인, 쿠키를 설정하는 응답처럼 저장하면 안 되는 응답입니다.
+    Private,
+}
+
+impl CacheClass {
+    /// 분류에 해당하는 `Cache-Control` 값입니다.
+    pub fn header(self) -> &'static str {
+        match self {
+            CacheClass::Revalidate => "no-cache",
+            CacheClass::Asset => ASSET_CACHE,
+            CacheClass::Private => "no-store",
+        }
+    }
+}
+
+/// 이미지나 글꼴 파일 경로인지 확인하는 함수입니다.
+pub fn is_asset(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| ASSET_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+}
+
+/// 공개 서버의 요청 경로와 응답으로 캐시 정책을 정하는 함수입니다.
+///
+/// # Arguments
+///
+/// * `path` - 요청 경로입니다.
+/// * `success` - 응답이 성공(2xx)인지 여부입니다. 오류 응답은 오래 캐시하지 않습니다.
+/// * `sets_cookie` - 응답이 쿠키를 설정하는지 여부입니다.
+///
+/// # Example
+///
+/// ```rust
+/// use gj_stamptour::cache_control::{classify, CacheClass};
+///
+/// assert_eq!(classify("/stamp/", true, false), CacheClass::Revalidate);
+/// assert_eq!(classify("/img/map.png", true, false), CacheClass::Asset);
+/// assert_eq!(classify("/img/missing.png", false, false), CacheClass::Revalidate);
+/// assert_eq!(classify("/kiosk/a", true, false), CacheClass::Private);
+/// assert_eq!(classify("/api/v1/login", true, false), CacheClass::Private);
+/// assert_eq!(classify("/login.html", true, false), CacheClass::Revalidate);
+/// ```
+pub fn classify(path: &str, success: bool, sets_cookie: bool) -> CacheClass {
+    let is_private = PRIVATE_PATHS.iter().any(|private| {
+        if private.ends_with('/') {
+            path.starts_with(private)
+        } else {
+            path == *private
+        }
+    });
+    if sets_cookie || is_private {
+        return CacheClass::Private;
+    }
+    match is_asset(path) && success {
+        true => CacheClass::Asset,
+        false => CacheClass::Revalidate,
+    }
+}
+
+/// 공개 서버의 응답에 `Cache-Control` 헤더를 붙이는 미들웨어입니다.
+/// 지문이 붙은 정적 파일이나 공개 통계처럼 핸들러가 직접 지정한 값은 그대로 둡니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// App::new()
+///     .wrap(actix_web::middleware::from_fn(apply_cache_policy))
+///     .configure(routes);
+/// ```
+pub async fn apply_cache_policy(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let path = req.path().to_string();
+    let mut res = next.call(req).await?;
+
+    if !res.headers().contains_key(CACHE_CONTROL) {
+        let class = classify(
+            &path,
+            res.status().is_success(),
+            res.headers().contains_key(SET_COOKIE),
+        );
+        res.headers_mut()
+            .insert(CACHE_CONTROL, HeaderValue::from_static(class.header()));
+    }
+    Ok(res)
+}
+
+/// 관리자 서버의 모든 응답을 저장하지 않도록 `Cache-Control: no-store`를 붙이는 미들웨어입니다.
+pub async fn apply_admin_cache_policy(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let mut res = next.call(req).await?;
+    res.headers_mut().insert(
+        CACHE_CONTROL,
+        HeaderValue::from_static(CacheClass::Private.header()),
+    );
+    Ok(res)
+}
@@ -0,0 +1,428 @@
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::from_str;
+use std::{collections::HashMap, fs, time::Duration};
+
+use crate::achievements::AchievementConfig;
+use crate::backup::BackupConfig;
+use crate::booth::BoothConfig;
+use crate::bot::BotConfig;
+use crate::bundle::event_path;
+use crate::cache_control::is_asset;
+use crate::cors::CorsConfig;
+use crate::event::EventConfig;
+use crate::features::FeatureConfig;
+use crate::geofence::GeofenceConfig;
+use crate::guestbook::GuestbookConfig;
+use crate::logging::LogConfig;
+use crate::nfc::NfcConfig;
+use crate::notifier::NotifierConfig;
+use crate::persistence::PersistenceConfig;
+use crate::push::WebPushConfig;
+use crate::retention::RetentionConfig;
+use crate::route_order::RouteConfig;
+use crate::share::ShareConfig;
+use crate::sheets::SheetsConfig;
+use crate::staging;
+use crate::tls::TlsConfig;
+use crate::venue_map::MapConfig;
+
+/// `resources/config.json`에서 읽어오는 행사 설정입니다. 파일에 없는 항목은 기본값을 사용합니다.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct Config {
+    /// 완주 등급 목록입니다. 비어있으면 모든 스템프를 모은 경우 하나의 등급만 사용합니다.
+    pub completion_tiers: Vec<CompletionTier>,
+    /// 팀 완주 등급 목록입니다. 비어있으면 `completion_tiers`를 그대로 사용합니다.
+    pub team_completion_tiers: Vec<CompletionTier>,
+    /// 로그인과 스템프 찍기를 마감하는 시각(RFC 3339)입니다. 없으면 관리자가 직접 종료합니다.
+    pub closes_at: Option<String>,
+    /// 운영 알림을 보낼 Discord 또는 Slack 웹훅 설정입니다. 없으면 알림을 보내지 않습니다.
+    pub notifier: Option<NotifierConfig>,
+    /// 직원 API에 필요한 토큰입니다. 없으면 로컬 요청만 허용합니다.
+    pub staff_token: Option<String>,
+    /// 작업자 수와 연결 유지 시간 등 HTTP 서버 설정입니다.
+    pub server: ServerConfig,
+    /// 리버스 프록시 뒤의 하위 경로에 올릴 때 사용하는 URL 접두사(예: `/stamptour`)입니다.
+    /// 비어있으면 최상위 경로에서 서비스합니다. HTML에서는 `%BASE_PATH%`로 사용할 수 있습니다.
+    pub base_path: String,
+    /// `backup` 관리자 명령으로 저장하는 스냅샷 설정입니다.
+    pub backup: BackupConfig,
+    /// `user_status.json`과 `stamp_status.json`의 개인정보를 AES-256-GCM으로 암호화하는 키입니다.
+    /// base64 또는 16진수로 적은 32바이트 값이며, 환경 변수 `STAMPTOUR_ENCRYPTION_KEY`가 우선합니다.
+    /// 없으면 암호화하지 않습니다.
+    pub encryption_key: Option<String>,
+    /// 행사 종료 후 개인정보 보관 기간과 처리 방법입니다.
+    pub retention: RetentionConfig,
+    /// 익명화 내보내기에서 유저 ID 해싱에 사용할 솔트입니다.
+    /// 없으면 내보낼 때마다 새로 만들어 다른 파일의 방문자와 연결할 수 없게 합니다.
+    pub export_salt: Option<String>,
+    /// 부스 운영자 화면과 QR 코드 설정입니다.
+    pub booth: BoothConfig,
+    /// 결과를 주기적으로 올릴 Google 스프레드시트 설정입니다. 없으면 올리지 않습니다.
+    pub sheets: Option<SheetsConfig>,
+    /// 완주와 품절 임박을 방문객에게 알리는 Web Push 설정입니다. 없으면 알림을 보내지 않습니다.
+    pub web_push: Option<WebPushConfig>,
+    /// 유저 코드로 진행 상황을 알려주는 Telegram, LINE 챗봇 설정입니다.
+    pub bot: BotConfig,
+    /// 로그 파일과 파일 교체 설정입니다.
+    pub log: LogConfig,
+    /// 변경된 데이터를 데이터베이스 파일에 모아서 저장하는 간격입니다.
+    pub persistence: PersistenceConfig,
+    /// 한 유저가 스템프를 확인할 수 있는 최소 간격(초)입니다. 같은 QR 코드를 연달아 찍는 것을 막으며,
+    /// 간격 안에 다시 찍으면 429 응답을 받습니다. 0이면 제한하지 않습니다.
+    pub scan_cooldown_secs: u64,
+    /// 스템프를 찍는 NFC 태그 설정입니다.
+    pub nfc: NfcConfig,
+    /// PWA가 보낸 방문객 위치로 부스에서 먼 확인을 거절하는 설정입니다.
+    pub geofence: GeofenceConfig,
+    /// 스템프를 찍는 순서입니다. 설정하지 않으면 어떤 순서로든 찍을 수 있습니다.
+    pub route: RouteConfig,
+    /// 스템프를 찍을 때마다 확인하는 업적 목록입니다. (선착순 완주, 먹거리 부스 모두 방문 등)
+    pub achievements: Vec<AchievementConfig>,
+    /// SNS 공유 이미지와 공유 페이지 설정입니다.
+    pub share: ShareConfig,
+    /// `/map.svg`에서 방문한 부스를 표시하는 색입니다.
+    pub map: MapConfig,
+    /// 방명록 글 길이, 작성 간격, 검토 설정입니다.
+    pub guestbook: GuestbookConfig,
+    /// `/api/v1/event`로 알려주는 행사 날짜와 부스 운영 시간입니다.
+    pub event: EventConfig,
+    /// 다른 출처에서 제공하는 PWA가 `/api/*`를 호출할 수 있도록 허용하는 CORS 설정입니다.
+    pub cors: CorsConfig,
+    /// 리허설용 시험 운영 여부입니다. 켜면 `resources/staging`에 따로 저장하고 페이지에 `TEST MODE` 띠를 표시합니다.
+    /// 실행 인수 `--dry-run`으로도 켤 수 있으며, 서버를 다시 시작해야 꺼집니다.
+    pub staging: bool,
+    /// 순위표, 미션, 위치 확인처럼 행사마다 켜고 끄는 기능입니다. `feature` 관리자 명령으로 실행 중에 바꿀 수 있습니다.
+    pub features: FeatureConfig,
+    /// 공개 서버가 직접 처리할 TLS 설정입니다. 없으면 HTTP만 받고, HTTPS는 리버스 프록시에서 처리합니다.
+    pub tls: Option<TlsConfig>,
+}
+
+/// `resources/config.json`의 `server` 항목입니다. 작은 행사용 서버에 맞게 조정할 수 있습니다.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct ServerConfig {
+    /// 공개 서버의 작업자 스레드 수입니다. 없으면 CPU 코어 수를 사용합니다.
+    pub workers: Option<usize>,
+    /// 요청이 없는 연결을 유지하는 시간(초)입니다. 0이면 연결을 유지하지 않습니다.
+    pub keep_alive_secs: u64,
+    /// 요청 헤더를 모두 받을 때까지 기다리는 시간(초)입니다. 0이면 제한하지 않습니다.
+    pub client_request_timeout_secs: u64,
+    /// 응답을 보낸 뒤 클라이언트가 연결을 끊을 때까지 기다리는 시간(초)입니다.
+    /// 느린 휴대폰에서 큰 SVG를 내려받는 경우 늘려야 합니다. 0이면 제한하지 않습니다.
+    pub client_disconnect_timeout_secs: u64,
+    /// 종료 신호를 받은 뒤 처리 중인 요청이 끝나기를 기다리는 최대 시간(초)입니다.
+    /// 이 시간 안에 끝난 `/stamp/` 요청의 기록은 종료 전에 저장됩니다.
+    pub shutdown_timeout_secs: u64,
+    /// 공개 서버의 HTTP 포트가 HTTP/1.1과 함께 평문 HTTP/2(h2c)도 받을지 여부입니다.
+    /// 브라우저는 h2c를 사용하지 않으므로, TLS를 처리하는 리버스 프록시가 서버까지 HTTP/2로 전달할 때만 켭니다.
+    /// 서버가 TLS를 직접 처리하면(`tls`) 이 설정과 관계없이 ALPN으로 HTTP/2를 협상합니다.
+    pub http2: bool,
+    /// `/api/*` 요청의 처리 제한 시간(초)입니다. 넘으면 503 응답을 보냅니다. 0이면 제한하지 않습니다.
+    pub api_timeout_secs: u64,
+    /// 이미지와 글꼴 요청의 처리 제한 시간(초)입니다. 큰 지도 이미지를 내려받는 시간을 고려합니다.
+    pub asset_timeout_secs: u64,
+    /// 그 외 페이지 요청의 처리 제한 시간(초)입니다.
+    pub page_timeout_secs: u64,
+    /// 공개 서버(`/login` 등)가 받는 JSON 본문의 최대 크기(바이트)입니다.
+    pub public_body_limit: usize,
+    /// 관리자 서버(`/admin` 등)가 받는 JSON 본문의 최대 크기(바이트)입니다.
+    pub admin_body_limit: usize,
+    /// 개찰구 기기용 gRPC 서버의 주소(`0.0.0.0:50051` 등)입니다. 없으면 gRPC 서버를 실행하지 않습니다.
+    pub grpc_address: Option<String>,
+    /// 리버스 프록시의 IP 주소 목록입니다. 이 주소에서 온 요청은 `X-Forwarded-For`로 방문객 IP를 찾습니다.
+    /// 유닉스 소켓으로 연결한 프록시는 항상 신뢰합니다.
+    pub trusted_proxies: Vec<String>,
+}
+
+impl Default for ServerConfig {
+    /// 연결 관련 값은 actix-web의 기본값과 같습니다.
+    fn default() -> Self {
+        ServerConfig {
+            workers: None,
+            keep_alive_secs: 5,
+            client_request_timeout_secs: 5,
+            client_disconnect_timeout_secs: 1,
+            shutdown_timeout_secs: 30,
+            http2: false,
+            api_timeout_secs: 5,
+            asset_timeout_secs: 30,
+            page_timeout_secs: 10,
+            public_body_limit: 4 * 1024,
+            admin_body_limit: 64 * 1024,
+            grpc_address: None,
+            trusted_proxies: Vec::new(),
+        }
+    }
+}
+
+impl Config {
+    pub fn scan_cooldown(&self) -> Duration {
+        Duration::from_secs(self.scan_cooldown_secs)
+    }
+}
+
+impl ServerConfig {
+    pub fn keep_alive(&self) -> Duration {
+        Duration::from_secs(self.keep_alive_secs)
+    }
+
+    pub fn client_request_timeout(&self) -> Duration {
+        Duration::from_secs(self.client_request_timeout_secs)
+    }
+
+    pub fn client_disconnect_timeout(&self) -> Duration {
+        Duration::from_secs(self.client_disconnect_timeout_secs)
+    }
+
+    pub fn shutdown_timeout(&self) -> Duration {
+        Duration::from_secs(self.shutdown_timeout_secs)
+    }
+
+    /// 요청 경로에 적용할 처리 제한 시간입니다. 제한하지 않으면 `None`을 반환합니다.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use gj_stamptour::config::ServerConfig;
+    /// use std::time::Duration;
+    ///
+    /// let config = ServerConfig::default();
+    /// assert_eq!(config.request_timeout("/api/v1/event"), Some(Duration::from_secs(5)));
+    /// assert_eq!(config.request_timeout("/img/map.png"), Some(Duration::from_secs(30)));
+    /// assert_eq!(config.request_timeout("/stamp/"), Some(Duration::from_secs(10)));
+    /// ```
+    pub fn request_timeout(&self, path: &str) -> Option<Duration> {
+        let secs = if path.starts_with("/api/") {
+            self.api_timeout_secs
+        } else if is_asset(path) {
+            self.asset_timeout_secs
+        } else {
+            self.page_timeout_secs
+        };
+        (secs > 0).then(|| Duration::from_secs(secs))
+    }
+}
+
+/// "아무 부스나 5개 = 소형 경품"과 같은 완주 등급입니다.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CompletionTier {
+    pub tier_name: String,
+    /// 필요한 스템프 개수입니다. 없으면 모든 스템프가 필요합니다.
+    #[serde(default)]
+    pub required: Option<usize>,
+}
+
+impl Config {
+    /// 필요한 스템프 개수가 적은 순서로 정렬된 완주 등급 목록을 반환합니다.
+    ///
+    /// # Arguments
+    ///
+    /// * `total` - 전체 스템프 개수입니다. `required`가 없는 등급의 기준이 됩니다.
+    ///
+    /// # Returns
+    ///
+    /// `(등급 이름, 필요한 스템프 개수)` 목록을 반환합니다.
+    pub fn tiers(&self, total: usize) -> Vec<(String, usize)> {
+        sorted_tiers(&self.completion_tiers, total)
+    }
+
+    /// 필요한 스템프 개수가 적은 순서로 정렬된 팀 완주 등급 목록을 반환합니다.
+    pub fn team_tiers(&self, total: usize) -> Vec<(String, usize)> {
+        if self.team_completion_tiers.is_empty() {
+            self.tiers(total)
+        } else {
+            sorted_tiers(&self.team_completion_tiers, total)
+        }
+    }
+}
+
+/// 완주 등급을 `(등급 이름, 필요한 스템프 개수)` 목록으로 변환하고 필요한 개수 순으로 정렬합니다.
+/// 등급이 비어있으면 모든 스템프를 모은 경우 하나의 "complete" 등급을 사용합니다.
+fn sorted_tiers(completion_tiers: &[CompletionTier], total: usize) -> Vec<(String, usize)> {
+    let mut tiers: Vec<(String, usize)> = if completion_tiers.is_empty() {
+        vec![("complete".to_string(), total)]
+    } else {
+        completion_tiers
+            .iter()
+            .map(|tier| {
+                (
+                    tier.tier_name.clone(),
+                    tier.required.unwrap_or(total).min(total),
+                )
+            })
+            .collect()
+    };
+    tiers.sort_by_key(|(_, required)| *required);
+    tiers
+}
+
+/// 설정 파일을 읽어 `Config` 구조체로 변환하는 함수입니다.
+///
+/// # Returns
+///
+/// 설정 파일이 존재하면 해당 내용을, 존재하지 않으면 기본 설정을 반환합니다. 파싱에 실패하면 패닉이 발생합니다.
+pub fn load_config() -> Config {
+    read_config().expect("Failed to parse config")
+}
+
+/// 설정 파일을 읽어 `Config` 구조체로 변환하는 함수입니다. 서버 실행 중 다시 불러올 때 사용합니다.
+/// `--event-dir`로 실행했으면 행사 폴더의 `config.json`을 읽습니다.
+///
+/// # Returns
+///
+/// 설정 파일이 존재하면 해당 내용을, 존재하지 않으면 기본 설정을 반환합니다. 파싱에 실패하면 오류를 반환합니다.
+pub fn read_config() -> Result<Config, serde_json::Error> {
+    match fs::read_to_string(event_path("config.json")) {
+        Ok(file_content) => {
+            let mut config = from_str(&file_content)?;
+            staging::apply(&mut config);
+            info!("Config load complete");
+            Ok(config)
+        }
+        Err(_) => {
+            warn!("Config file not found, using defaults");
+            let mut config = Config::default();
+            staging::apply(&mut config);
+            Ok(config)
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct AddressInfo {
+    pub address: String,
+    pub port: u16,
+    pub protocol: String,
+    /// 관리자/직원 API만 제공하는 서버의 주소입니다. 기본값은 `127.0.0.1`입니다.
+    pub admin_address: String,
+    /// 관리자/직원 API만 제공하는 서버의 포트입니다. 기본값은 8081입니다.
+    pub admin_port: u16,
+    /// 추가로 바인딩할 유닉스 도메인 소켓 경로입니다. 없으면 TCP로만 바인딩합니다.
+    pub unix_socket: Option<String>,
+    /// 유닉스 도메인 소켓 파일의 권한입니다. 기본값은 `0o660`입니다.
+    pub unix_socket_mode: u32,
+}
+
+/// 바로 다음 인수를 값으로 받는 실행 옵션입니다. 여기에 없는 옵션(`--dry-run`, `--check`, `--repair`)은 값을 받지 않습니다.
+pub const VALUE_FLAGS: [&str; 10] = [
+    "-a",
+    "-p",
+    "--protocol",
+    "--admin-address",
+    "--admin-port",
+    "--bind-unix",
+    "--unix-mode",
+    "--event-dir",
+    "--simulate",
+    "--target",
+];
+
+/// 커맨드라인 인수를 파싱하여 서버 바인딩 정보를 추출합니다.
+///
+/// # Arguments
+///
+/// * `cmd` - 커맨드라인 인수를 나타내는 문자열 벡터입니다.
+/// * `cmd_len` - 커맨드라인 인수 벡터의 길이입니다.
+///
+/// # Returns
+///
+/// 파싱된 서버 바인딩 정보(address, port, protocol, admin_address, admin_port, unix_socket, unix_socket_mode)를 담고 있는 `AddressInfo` 구조체입니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let args = vec![
+///     "프로그램_이름".to_string(),
+///     "-a".to_string(), "127.0.0.1".to_string(),
+///     "-p".to_string(), "8080".to_string(),
+///     "--protocol".to_string(), "https".to_string(),
+///     "--admin-port".to_string(), "9090".to_string(),
+///     "--bind-unix".to_string(), "/run/stamptour.sock".to_string(),
+/// ];
+/// let address_info = handle_args(args, 11);
+/// assert_eq!(address_info.address, "127.0.0.1");
+/// assert_eq!(address_info.port, 8080);
+/// assert_eq!(address_info.protocol, "https");
+/// assert_eq!(address_info.admin_port, 9090);
+/// assert_eq!(address_info.unix_socket.as_deref(), Some("/run/stamptour.sock"));
+/// ```
+pub fn handle_args(cmd: Vec<String>, _cmd_len: usize) -> AddressInfo {
+    // 커맨드라인 옵션과 값을 저장할 HashMap
+    let mut cmd_line = HashMap::new();
+
+    // 주소, 포트, 프로토콜의 기본값
+    let mut address = "127.0.0.1".to_string();
+    let mut port = 80;
+    let mut protocol = "http".to_string();
+    let mut admin_address = "127.0.0.1".to_string();
+    let mut admin_port = 8081;
+    let mut unix_socket = None;
+    let mut unix_socket_mode = 0o660;
+
+    // 프로그램 이름을 제외하고 커맨드라인 인수를 반복
+    // 값을 받는 옵션만 다음 인수를 값으로 가져가므로 `--dry-run` 같은 옵션이 앞에 있어도 짝이 밀리지 않음
+    let mut args_iter = cmd.iter().skip(1);
+    while let Some(key) = args_iter.next() {
+        if !VALUE_FLAGS.contains(&key.as_str()) {
+            continue;
+        }
+        // 커맨드라인 옵션과 값을 cmd_line HashMap에 채움
+        if let Some(value) = args_iter.next() {
+            cmd_line.insert(&key[..], value);
+        }
+    }
+
+    // 커맨드라인 인수에서 주소가 제공되면 업데이트
+    if let Some(addr) = cmd_line.get("-a") {
+        address = addr.to_string();
+    }
+
+    // 커맨드라인 인수에서 포트가 제공되면 업데이트
+    if let Some(port_str) = cmd_line.get("-p") {
+        if let Ok(p) = port_str.parse() {
+            port = p;
+        }
+    }
+
+    // 커맨드라인 인수에서 프로토콜이 제공되면 업데이트
+    if let Some(proto) = cmd_line.get("--protocol") {
+        protocol = proto.to_string();
+    }
+
+    // 커맨드라인 인수에서 관리자 서버 주소가 제공되면 업데이트
+    if let Some(addr) = cmd_line.get("--admin-address") {
+        admin_address = addr.to_string();
+    }
+
+    // 커맨드라인 인수에서 관리자 서버 포트가 제공되면 업데이트
+    if let Some(port_str) = cmd_line.get("--admin-port") {
+        if let Ok(p) = port_str.parse() {
+            admin_port = p;
+        }
+    }
+
+    // 커맨드라인 인수에서 유닉스 도메인 소켓 경로가 제공되면 업데이트
+    if let Some(socket_path) = cmd_line.get("--bind-unix") {
+        unix_socket = Some(socket_path.to_string());
+    }
+
+    // 커맨드라인 인수에서 소켓 권한(8진수, 예: 660)이 제공되면 업데이트
+    if let Some(mode_str) = cmd_line.get("--unix-mode") {
+        if let Ok(mode) = u32::from_str_radix(mode_str, 8) {
+            unix_socket_mode = mode;
+        }
+    }
+
+    // 파싱된 정보를 담은 AddressInfo 구조체를 생성하고 반환
+    AddressInfo {
+        address,
+        port,
+        protocol,
+        admin_address,
+        admin_port,
+        unix_socket,
+        unix_socket_mode,
+    }
+}
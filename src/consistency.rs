@@ -0,0 +1,206 @@
+use log::{error, info, warn};
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::config::load_config;
+use crate::lock::MutexExt;
+use crate::state::{StampHistory, StampIdList, UserList, UserStampList};
+use crate::storage::{load_state, save_file};
+
+/// `user_status.json`, `stamp_status.json`, `stampList.json` 사이의 참조 오류 목록입니다.
+#[derive(Serialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct ConsistencyReport {
+    /// 등록되지 않은 유저의 스템프 기록 `(스템프 ID, 유저 ID)`입니다.
+    pub orphan_entries: Vec<(String, String)>,
+    /// 스템프 목록에 없는 스템프의 기록 `(스템프 ID, 기록 수)`입니다.
+    /// 이름이 바뀐 스템프일 수 있으므로 자동으로 지우지 않습니다.
+    pub unknown_stamps: Vec<(String, usize)>,
+    /// 같은 유저가 같은 스템프를 여러 번 받은 기록 `(스템프 ID, 유저 ID, 중복 수)`입니다.
+    pub duplicate_entries: Vec<(String, String, usize)>,
+    /// 스템프 목록에는 있지만 기록 목록이 없는 스템프 ID입니다. 스템프를 찍을 때 패닉의 원인이 됩니다.
+    pub missing_stamps: Vec<String>,
+    /// 등록되지 않은 유저나 스템프를 가리키는 확인 대기 항목의 유저 ID입니다.
+    pub orphan_pending: Vec<String>,
+}
+
+impl ConsistencyReport {
+    /// 발견된 오류가 없는지 확인합니다.
+    pub fn is_clean(&self) -> bool {
+        self == &ConsistencyReport::default()
+    }
+
+    /// 관리자 명령과 `--check` 실행 결과에 출력할 요약 문자열을 반환합니다.
+    pub fn summary(&self) -> String {
+        if self.is_clean() {
+            return "No inconsistencies found".to_string();
+        }
+
+        let mut lines = Vec::new();
+        for (stamp_id, user_id) in self.orphan_entries.iter() {
+            lines.push(format!(
+                "orphan entry: unknown user {} on stamp {}",
+                user_id, stamp_id
+            ));
+        }
+        for (stamp_id, count) in self.unknown_stamps.iter() {
+            lines.push(format!("unknown stamp: {} ({} entries)", stamp_id, count));
+        }
+        for (stamp_id, user_id, count) in self.duplicate_entries.iter() {
+            lines.push(format!(
+                "duplicate entry: user {} has {} entries on stamp {}",
+                user_id, count, stamp_id
+            ));
+        }
+        for stamp_id in self.missing_stamps.iter() {
+            lines.push(format!("missing history: stamp {}", stamp_id));
+        }
+        for user_id in self.orphan_pending.iter() {
+            lines.push(format!("orphan pending stamp: user {}", user_id));
+        }
+        lines.join("\n")
+    }
+}
+
+/// 상태 파일 사이의 참조 오류를 찾는 함수입니다.
+///
+/// # Arguments
+///
+/// * `stamp_list` - `stampList.json`에서 읽은 스템프 목록입니다.
+/// * `user_list` - 등록된 유저 목록입니다.
+/// * `stamp_history` - 스템프 기록입니다.
+/// * `user_stamp_list` - 확인 대기 중인 스템프 목록입니다.
+///
+/// # Returns
+///
+/// 발견된 오류를 담은 `ConsistencyReport`를 반환합니다. 각 목록은 정렬되어 있습니다.
+pub fn check(
+    stamp_list: &StampIdList,
+    user_list: &UserList,
+    stamp_history: &StampHistory,
+    user_stamp_list: &UserStampList,
+) -> ConsistencyReport {
+    let mut report = ConsistencyReport::default();
+
+    for (stamp_id, entries) in stamp_history.to_map().iter() {
+        if !stamp_list.stamp_id_list.contains_key(stamp_id) {
+            report
+                .unknown_stamps
+                .push((stamp_id.clone(), entries.len()));
+        }
+
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for entry in entries.iter() {
+            *counts.entry(&entry.user_id).or_default() += 1;
+        }
+        for (user_id, count) in counts {
+            if !user_list.users.contains_key(user_id) {
+                report
+                    .orphan_entries
+                    .push((stamp_id.clone(), user_id.to_string()));
+            } else if count > 1 {
+                report
+                    .duplicate_entries
+                    .push((stamp_id.clone(), user_id.to_string(), count));
+            }
+        }
+    }
+
+    report.missing_stamps = stamp_list
+        .stamp_id_list
+        .keys()
+        .filter(|stamp_id| !stamp_history.contains(stamp_id))
+        .cloned()
+        .collect();
+    report.orphan_pending = user_stamp_list
+        .user_stamp_list
+        .iter()
+        .filter(|(user_id, stamp_id)| {
+            !user_list.users.contains_key(*user_id)
+                || !stamp_list.stamp_id_list.contains_key(*stamp_id)
+        })
+        .map(|(user_id, _)| user_id.clone())
+        .collect();
+
+    report.orphan_entries.sort();
+    report.unknown_stamps.sort();
+    report.duplicate_entries.sort();
+    report.orphan_pending.sort();
+    report
+}
+
+/// 참조 오류를 찾아 고치는 함수입니다. 등록되지 않은 유저의 기록과 확인 대기 항목을 지우고,
+/// 중복 기록은 가장 먼저 받은 기록만 남기며, 빠진 스템프 기록 목록을 만듭니다.
+/// 스템프 목록에 없는 스템프의 기록은 보고만 하고 지우지 않습니다.
+///
+/// # Returns
+///
+/// 고치기 전에 발견된 오류를 담은 `ConsistencyReport`를 반환합니다.
+pub fn repair(
+    stamp_list: &StampIdList,
+    user_list: &UserList,
+    stamp_history: &StampHistory,
+    user_stamp_list: &mut UserStampList,
+) -> ConsistencyReport {
+    let report = check(stamp_list, user_list, stamp_history, user_stamp_list);
+
+    stamp_history.update_all(|_, entries| {
+        let mut seen = Vec::new();
+        entries.retain(|entry| {
+            let keep =
+                user_list.users.contains_key(&entry.user_id) && !seen.contains(&entry.user_id);
+            seen.push(entry.user_id.clone());
+            keep
+        });
+    });
+    for stamp_id in report.missing_stamps.iter() {
+        stamp_history.insert_stamp(stamp_id);
+    }
+    for user_id in report.orphan_pending.iter() {
+        user_stamp_list.user_stamp_list.remove(user_id);
+    }
+
+    if !report.is_clean() {
+        warn!("Repaired state inconsistencies:\n{}", report.summary());
+    }
+    report
+}
+
+/// `--check`, `--repair` 실행 옵션을 처리하는 함수입니다. 서버를 시작하지 않고 저장된 상태 파일을 검사합니다.
+///
+/// # Arguments
+///
+/// * `fix` - `true`이면 오류를 고친 뒤 `stamp_status.json`을 저장합니다.
+///
+/// # Returns
+///
+/// 오류가 없거나 모두 고쳤으면 0, 남은 오류가 있으면 1을 프로세스 종료 코드로 반환합니다.
+pub fn run_check(fix: bool) -> i32 {
+    let state = load_state(load_config());
+    let stamp_list = state.stamp_list.get();
+    let user_list = state.user_list.lock_or_recover();
+    let stamp_history = state.stamp_history.get_ref();
+    let mut user_stamp_list = state.user_stamp_list.lock_or_recover();
+
+    let report = if fix {
+        repair(&stamp_list, &user_list, stamp_history, &mut user_stamp_list)
+    } else {
+        check(&stamp_list, &user_list, stamp_history, &user_stamp_list)
+    };
+    println!("{}", report.summary());
+
+    if fix && !report.is_clean() {
+        if save_file("stamp_status", stamp_history).is_err() {
+            error!("Failed to save repaired stamp_status");
+            return 1;
+        }
+        info!("Repaired stamp_status saved");
+    }
+
+    // 스템프 목록에 없는 스템프의 기록은 고치지 않으므로 남은 오류로 취급
+    let remaining = if fix {
+        check(&stamp_list, &user_list, stamp_history, &user_stamp_list)
+    } else {
+        report
+    };
+    i32::from(!remaining.is_clean())
+}
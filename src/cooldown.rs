@@ -0,0 +1,65 @@
+use dashmap::{mapref::entry::Entry, DashMap};
+use std::time::{Duration, Instant};
+
+/// 유저별로 마지막으로 받아들인 스템프 확인 시각을 기억하여, 같은 QR 코드를 연달아 찍어
+/// 스템프 기록이 쌓이는 것을 막습니다.
+///
+/// # Example
+///
+/// ```rust
+/// use gj_stamptour::cooldown::ScanCooldown;
+/// use std::time::{Duration, Instant};
+///
+/// let cooldown = ScanCooldown::default();
+/// let now = Instant::now();
+/// let interval = Duration::from_secs(10);
+/// assert_eq!(cooldown.try_accept("u1", interval, now), Ok(()));
+/// assert_eq!(
+///     cooldown.try_accept("u1", interval, now + Duration::from_secs(3)),
+///     Err(Duration::from_secs(7))
+/// );
+/// assert_eq!(cooldown.try_accept("u2", interval, now), Ok(()));
+/// assert_eq!(cooldown.try_accept("u1", interval, now + interval), Ok(()));
+/// ```
+#[derive(Default)]
+pub struct ScanCooldown {
+    last_scans: DashMap<String, Instant>,
+}
+
+impl ScanCooldown {
+    /// 유저의 스템프 확인을 받아들일지 정하는 함수입니다. 받아들인 경우에만 시각을 기록하므로,
+    /// 대기 중에 다시 찍어도 대기 시간이 늘어나지 않습니다.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - 스템프를 확인하는 유저 ID입니다.
+    /// * `interval` - 확인 사이의 최소 간격입니다. 0이면 항상 받아들입니다.
+    /// * `now` - 현재 시각입니다.
+    ///
+    /// # Returns
+    ///
+    /// 받아들이면 `Ok(())`, 간격이 지나지 않았으면 남은 대기 시간을 담은 `Err`를 반환합니다.
+    pub fn try_accept(
+        &self,
+        user_id: &str,
+        interval: Duration,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        if interval.is_zero() {
+            return Ok(());
+        }
+        match self.last_scans.entry(user_id.to_string()) {
+            Entry::Occupied(mut last_scan) => {
+                let elapsed = now.saturating_duration_since(*last_scan.get());
+                if elapsed < interval {
+                    return Err(interval - elapsed);
+                }
+                last_scan.insert(now);
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(now);
+            }
+        }
+        Ok(())
+    }
+}
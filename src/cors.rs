@@ -0,0 +1,189 @@
+use actix_web::{
+    body::{EitherBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    http::{
+        header::{
+            HeaderMap, HeaderValue, ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_HEADERS,
+            ACCESS_CONTROL_ALLOW_METHODS, ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_MAX_AGE,
+            ACCESS_CONTROL_REQUEST_METHOD, ORIGIN, VARY,
+        },
+        Method,
+    },
+    middleware::Next,
+    web::Data,
+    HttpResponse,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::state::Reloadable;
+
+/// CORS 헤더를 붙이는 경로의 접두사입니다. HTML 페이지는 같은 출처에서만 사용합니다.
+const API_PREFIX: &str = "/api/";
+
+/// `resources/config.json`의 `cors` 항목입니다. 다른 출처에서 제공하는 PWA가 `/api/*`를 호출할 수 있게 합니다.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct CorsConfig {
+    /// 허용하는 출처(`https://app.example.com` 등) 목록입니다. `*`는 모든 출처를 허용하지만 쿠키(세션)는 보내지 않습니다.
+    /// 비어있으면 CORS 헤더를 붙이지 않습니다.
+    pub allowed_origins: Vec<String>,
+    /// 다른 출처의 요청에 쿠키(세션)를 함께 보내도록 허용할지 여부입니다.
+    /// `allowed_origins`에 직접 적은 출처에만 적용하며, `*`로 허용한 출처에는 적용하지 않습니다.
+    pub allow_credentials: bool,
+    /// 허용하는 HTTP 메서드 목록입니다.
+    pub allowed_methods: Vec<String>,
+    /// 허용하는 요청 헤더 목록입니다.
+    pub allowed_headers: Vec<String>,
+    /// 브라우저가 사전 요청(preflight) 결과를 캐시하는 시간(초)입니다.
+    pub max_age_secs: u64,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        CorsConfig {
+            allowed_origins: Vec::new(),
+            allow_credentials: false,
+            allowed_methods: vec!["GET".to_string(), "POST".to_string()],
+            allowed_headers: vec!["Content-Type".to_string(), "Authorization".to_string()],
+            max_age_secs: 600,
+        }
+    }
+}
+
+impl CorsConfig {
+    /// 출처가 허용 목록에 있는지 확인합니다. 끝의 `/`와 대소문자는 무시합니다.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use gj_stamptour::cors::CorsConfig;
+    ///
+    /// let config = CorsConfig {
+    ///     allowed_origins: vec!["https://app.example.com/".to_string()],
+    ///     ..Default::default()
+    /// };
+    /// assert!(config.allows_origin("https://App.example.com"));
+    /// assert!(!config.allows_origin("https://evil.example.com"));
+    /// assert!(!CorsConfig::default().allows_origin("https://app.example.com"));
+    /// ```
+    pub fn allows_origin(&self, origin: &str) -> bool {
+        let origin = origin.trim_end_matches('/');
+        self.allowed_origins.iter().any(|allowed| {
+            allowed == "*" || allowed.trim_end_matches('/').eq_ignore_ascii_case(origin)
+        })
+    }
+
+    /// 출처가 `*`가 아닌 허용 목록에 직접 적혀 있는지 확인합니다. 쿠키(세션)는 이 출처에만 보내도록 허용합니다.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use gj_stamptour::cors::CorsConfig;
+    ///
+    /// let config = CorsConfig {
+    ///     allowed_origins: vec!["*".to_string(), "https://app.example.com".to_string()],
+    ///     ..Default::default()
+    /// };
+    /// assert!(config.lists_origin("https://app.example.com/"));
+    /// assert!(!config.lists_origin("https://evil.example.com"));
+    /// ```
+    pub fn lists_origin(&self, origin: &str) -> bool {
+        let origin = origin.trim_end_matches('/');
+        self.allowed_origins
+            .iter()
+            .any(|allowed| allowed.trim_end_matches('/').eq_ignore_ascii_case(origin))
+    }
+
+    /// 메서드가 허용 목록에 있는지 확인합니다.
+    pub fn allows_method(&self, method: &str) -> bool {
+        self.allowed_methods
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(method))
+    }
+}
+
+/// 허용한 출처에 보내는 응답에 공통 CORS 헤더를 붙입니다.
+/// 목록에 직접 적은 출처는 출처마다 응답이 다르므로 요청한 출처를 그대로 돌려주고 `Vary: Origin`을 붙입니다.
+/// `*`로만 허용한 출처에는 아무 사이트나 방문객의 세션으로 API를 호출할 수 없도록 쿠키 허용 없이 `*`를 보냅니다.
+fn insert_cors_headers(headers: &mut HeaderMap, config: &CorsConfig, origin: &HeaderValue) {
+    let listed = origin
+        .to_str()
+        .is_ok_and(|origin| config.lists_origin(origin));
+    if !listed {
+        headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, HeaderValue::from_static("*"));
+        return;
+    }
+    headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, origin.clone());
+    headers.append(VARY, HeaderValue::from_static("Origin"));
+    if config.allow_credentials {
+        headers.insert(
+            ACCESS_CONTROL_ALLOW_CREDENTIALS,
+            HeaderValue::from_static("true"),
+        );
+    }
+}
+
+/// `/api/*` 요청에 CORS 헤더를 붙이는 미들웨어입니다. 다른 경로와 허용하지 않은 출처의 요청은 그대로 둡니다.
+///
+/// 허용한 출처의 사전 요청(`OPTIONS` + `Access-Control-Request-Method`)에는 핸들러를 거치지 않고 204 응답을 반환합니다.
+/// 허용하지 않은 메서드를 묻는 사전 요청에는 CORS 헤더 없이 응답하여 브라우저가 막도록 합니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// App::new()
+///     .wrap(actix_web::middleware::from_fn(apply_cors))
+///     .configure(|cfg| state.register(cfg))
+///     .configure(routes);
+/// ```
+pub async fn apply_cors(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, actix_web::Error> {
+    let config = req
+        .app_data::<Data<Reloadable<Config>>>()
+        .map(|config| config.get().cors.clone());
+    let origin = req.headers().get(ORIGIN).cloned();
+    let (Some(config), Some(origin), true) = (config, origin, req.path().starts_with(API_PREFIX))
+    else {
+        return Ok(next.call(req).await?.map_into_left_body());
+    };
+    if !origin
+        .to_str()
+        .is_ok_and(|origin| config.allows_origin(origin))
+    {
+        return Ok(next.call(req).await?.map_into_left_body());
+    }
+
+    let requested_method = req
+        .headers()
+        .get(ACCESS_CONTROL_REQUEST_METHOD)
+        .and_then(|method| method.to_str().ok())
+        .map(|method| method.to_string());
+    if let (&Method::OPTIONS, Some(requested_method)) = (req.method(), requested_method) {
+        if !config.allows_method(&requested_method) {
+            return Ok(req.into_response(HttpResponse::NoContent().finish().map_into_right_body()));
+        }
+        let mut res = req.into_response(
+            HttpResponse::NoContent()
+                .insert_header((
+                    ACCESS_CONTROL_ALLOW_METHODS,
+                    config.allowed_methods.join(", "),
+                ))
+                .insert_header((
+                    ACCESS_CONTROL_ALLOW_HEADERS,
+                    config.allowed_headers.join(", "),
+                ))
+                .insert_header((ACCESS_CONTROL_MAX_AGE, config.max_age_secs.to_string()))
+                .finish()
+                .map_into_right_body(),
+        );
+        insert_cors_headers(res.headers_mut(), &config, &origin);
+        return Ok(res);
+    }
+
+    let mut res = next.call(req).await?.map_into_left_body();
+    insert_cors_headers(res.headers_mut(), &config, &origin);
+    Ok(res)
+}
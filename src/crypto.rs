@@ -0,0 +1,236 @@
+use log::info;
+use openssl::{
+    base64::{decode_block, encode_block},
+    rand::rand_bytes,
+    symm::{decrypt_aead, encrypt_aead, Cipher},
+};
+use serde_json::{json, Value};
+use std::{env, sync::RwLock};
+
+use crate::config::Config;
+use crate::lock::RwLockExt;
+
+/// 설정 파일 대신 암호화 키를 전달할 때 사용하는 환경 변수입니다. 설정 파일보다 우선합니다.
+pub const KEY_ENV: &str = "STAMPTOUR_ENCRYPTION_KEY";
+
+/// 필드 단위로 암호화된 문자열 앞에 붙는 표시입니다.
+pub const ENCRYPTED_PREFIX: &str = "enc:v1:";
+
+/// 파일 전체를 암호화한 경우 `{"encrypted": "aes-256-gcm", "data": ...}` 형식으로 저장합니다.
+const ALGORITHM: &str = "aes-256-gcm";
+
+/// `stamp_status.json`에서 암호화하는 유저 필드입니다.
+const USER_FIELDS: [&str; 2] = ["user_name", "user_id"];
+
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+
+pub type Key = [u8; 32];
+
+/// 저장 계층에서 사용하는 암호화 키입니다. 서버 시작 시 `configure`로 한 번 설정합니다.
+static KEY: RwLock<Option<Key>> = RwLock::new(None);
+
+/// base64 또는 16진수 64자리로 적힌 256비트 키를 해석합니다.
+pub fn parse_key(encoded: &str) -> Result<Key, String> {
+    let encoded = encoded.trim();
+    let bytes = if encoded.len() == 64 && encoded.chars().all(|c| c.is_ascii_hexdigit()) {
+        (0..32)
+            .map(|i| u8::from_str_radix(&encoded[i * 2..i * 2 + 2], 16))
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(|e| e.to_string())?
+    } else {
+        decode_block(encoded).map_err(|_| "Encryption key is not valid base64".to_string())?
+    };
+    bytes
+        .try_into()
+        .map_err(|_| "Encryption key must be 32 bytes (AES-256)".to_string())
+}
+
+/// 환경 변수 또는 설정의 `encryption_key`로 저장 계층의 암호화 키를 설정하는 함수입니다.
+///
+/// # Returns
+///
+/// 키가 없으면 암호화하지 않고 `Ok(false)`, 키를 설정하면 `Ok(true)`, 키 형식이 틀리면 오류 메시지를 반환합니다.
+pub fn configure(config: &Config) -> Result<bool, String> {
+    let encoded = env::var(KEY_ENV).ok().or(config.encryption_key.clone());
+    let key = encoded.as_deref().map(parse_key).transpose()?;
+    *KEY.write_or_recover() = key;
+    if key.is_some() {
+        info!("Personal data encryption at rest is enabled");
+    }
+    Ok(key.is_some())
+}
+
+/// 현재 설정된 암호화 키를 반환합니다.
+pub fn key() -> Option<Key> {
+    *KEY.read_or_recover()
+}
+
+/// AES-256-GCM으로 암호화하여 `base64(nonce || 암호문 || 태그)`를 반환합니다.
+pub fn encrypt(key: &Key, plaintext: &[u8]) -> String {
+    encode_block(&encrypt_bytes(key, plaintext))
+}
+
+/// AES-256-GCM으로 암호화하여 `nonce || 암호문 || 태그`를 반환합니다. 바이너리 형식의 데이터베이스 파일에 사용합니다.
+pub fn encrypt_bytes(key: &Key, plaintext: &[u8]) -> Vec<u8> {
+    let mut nonce = [0u8; NONCE_LEN];
+    rand_bytes(&mut nonce).unwrap();
+    let mut tag = [0u8; TAG_LEN];
+    let ciphertext = encrypt_aead(
+        Cipher::aes_256_gcm(),
+        key,
+        Some(&nonce),
+        &[],
+        plaintext,
+        &mut tag,
+    )
+    .unwrap();
+    [&nonce[..], &ciphertext, &tag].concat()
+}
+
+/// `encrypt`로 암호화한 값을 복호화합니다. 키가 다르거나 내용이 변조되었으면 오류를 반환합니다.
+pub fn decrypt(key: &Key, encoded: &str) -> Result<Vec<u8>, String> {
+    let bytes = decode_block(encoded).map_err(|_| "Encrypted data is not valid base64")?;
+    decrypt_bytes(key, &bytes)
+}
+
+/// `encrypt_bytes`로 암호화한 값을 복호화합니다. 키가 다르거나 내용이 변조되었으면 오류를 반환합니다.
+pub fn decrypt_bytes(key: &Key, bytes: &[u8]) -> Result<Vec<u8>, String> {
+    if bytes.len() < NONCE_LEN + TAG_LEN {
+        return Err("Encrypted data is too short".to_string());
+    }
+    let (nonce, rest) = bytes.split_at(NONCE_LEN);
+    let (ciphertext, tag) = rest.split_at(rest.len() - TAG_LEN);
+    decrypt_aead(
+        Cipher::aes_256_gcm(),
+        key,
+        Some(nonce),
+        &[],
+        ciphertext,
+        tag,
+    )
+    .map_err(|_| "Decryption failed: wrong encryption key or corrupted data".to_string())
+}
+
+/// 개인정보가 있어 암호화하는 데이터베이스 파일인지 확인합니다. (`seal` 참고)
+pub fn is_personal(file_name: &str) -> bool {
+    matches!(file_name, "user_status" | "stamp_status")
+}
+
+/// 데이터베이스 파일에 저장하기 전에 개인정보를 암호화하는 함수입니다.
+/// `user_status`는 파일 전체를, `stamp_status`는 기록의 `user_name`, `user_id` 필드를 암호화하고
+/// 나머지 파일은 그대로 반환합니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let sealed = seal("user_status", serde_json::to_value(&user_list)?, &key);
+/// assert_eq!(sealed["encrypted"], "aes-256-gcm");
+/// ```
+pub fn seal(file_name: &str, value: Value, key: &Key) -> Value {
+    match file_name {
+        "user_status" => json!({
+            "encrypted": ALGORITHM,
+            "data": encrypt(key, value.to_string().as_bytes()),
+        }),
+        "stamp_status" => map_user_fields(value, &mut |field| {
+            Ok(format!(
+                "{}{}",
+                ENCRYPTED_PREFIX,
+                encrypt(key, field.as_bytes())
+            ))
+        })
+        .unwrap(),
+        _ => value,
+    }
+}
+
+/// `seal`로 암호화된 내용을 복호화하는 함수입니다. 암호화되지 않은 파일은 그대로 반환하므로
+/// 암호화를 켜기 전에 저장한 파일도 읽을 수 있습니다.
+///
+/// # Returns
+///
+/// 암호화된 내용이 있는데 키가 없거나 복호화에 실패하면 오류 메시지를 반환합니다.
+pub fn unseal(value: Value, key: Option<&Key>) -> Result<Value, String> {
+    let missing_key = || format!("Data is encrypted but {} is not set", KEY_ENV);
+
+    if let Some(data) = value
+        .get("encrypted")
+        .filter(|algorithm| *algorithm == ALGORITHM)
+        .and(value.get("data"))
+        .and_then(Value::as_str)
+    {
+        let plaintext = decrypt(key.ok_or_else(missing_key)?, data)?;
+        return serde_json::from_slice(&plaintext).map_err(|e| e.to_string());
+    }
+
+    map_user_fields(
+        value,
+        &mut |field| match field.strip_prefix(ENCRYPTED_PREFIX) {
+            Some(encrypted) => {
+                let plaintext = decrypt(key.ok_or_else(missing_key)?, encrypted)?;
+                String::from_utf8(plaintext).map_err(|e| e.to_string())
+            }
+            None => Ok(field.to_string()),
+        },
+    )
+}
+
+/// JSON 전체를 돌며 `USER_FIELDS`에 해당하는 문자열 필드를 변환합니다.
+fn map_user_fields<F>(value: Value, f: &mut F) -> Result<Value, String>
+where
+    F: FnMut(&str) -> Result<String, String>,
+{
+    Ok(match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(name, field)| {
+                    let field = match field {
+                        Value::String(text) if USER_FIELDS.contains(&name.as_str()) => {
+                            Value::String(f(&text)?)
+                        }
+                        field => map_user_fields(field, f)?,
+                    };
+                    Ok((name, field))
+                })
+                .collect::<Result<_, String>>()?,
+        ),
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| map_user_fields(item, f))
+                .collect::<Result<_, String>>()?,
+        ),
+        value => value,
+    })
+}
+
+/// JWT와 Web Push에서 사용하는 URL-safe base64(패딩 없음)로 인코딩합니다.
+///
+/// # Example
+///
+/// ```rust
+/// use gj_stamptour::crypto::{decode_base64_url, encode_base64_url};
+///
+/// assert_eq!(encode_base64_url(&[0xfb, 0xff]), "-_8");
+/// assert_eq!(decode_base64_url("-_8").unwrap(), [0xfb, 0xff]);
+/// ```
+pub fn encode_base64_url(bytes: &[u8]) -> String {
+    encode_block(bytes)
+        .trim_end_matches('=')
+        .replace('+', "-")
+        .replace('/', "_")
+}
+
+/// URL-safe base64를 디코딩합니다. 패딩은 있어도 없어도 됩니다. 형식이 틀리면 `None`을 반환합니다.
+pub fn decode_base64_url(encoded: &str) -> Option<Vec<u8>> {
+    let mut base64 = encoded
+        .trim()
+        .trim_end_matches('=')
+        .replace('-', "+")
+        .replace('_', "/");
+    while !base64.len().is_multiple_of(4) {
+        base64.push('=');
+    }
+    decode_block(&base64).ok()
+}
@@ -0,0 +1,154 @@
+use actix_web::{
+    http::{
+        header::{ALLOW, RETRY_AFTER},
+        StatusCode,
+    },
+    HttpResponse, ResponseError,
+};
+use futures_util::FutureExt;
+use log::error;
+use serde_json::{json, Value};
+use std::{fmt, io};
+
+use crate::i18n;
+
+/// 핸들러가 반환하는 오류입니다. 핸들러는 `Result<HttpResponse, AppError>`를 반환하며,
+/// 오류는 actix가 `ResponseError`로 응답을 만듭니다.
+///
+/// 페이지 오류(`NotFound`, `Unauthorized` 등)는 `resources/html`의 `errorXXX.html`을 담은 응답이 되고,
+/// API 오류(`Validation`, `Missing` 등)는 `{"error": 메시지}`를 담은 JSON 응답이 됩니다.
+/// 서버 내부 오류(`Storage`, `Template`, `Panic`, `Internal`)는 로그에만 자세한 내용을 남기고 `error500.html`을 보냅니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[get("/api/v1/teams/{team_code}")]
+/// pub async fn handle_team_progress(code: Path<String>, teams: Data<Mutex<TeamList>>) -> Result<HttpResponse, AppError> {
+///     let teams = teams.lock_or_recover();
+///     let team = teams.teams.get(&*code).ok_or(AppError::Missing("Team not found".to_string()))?;
+///     Ok(HttpResponse::Ok().json(team))
+/// }
+/// ```
+#[derive(Debug)]
+pub enum AppError {
+    /// 페이지나 파일이 없습니다. (404, `error404.html`)
+    NotFound,
+    /// 로그인하지 않았거나 인증에 실패했습니다. (401, `error401.html`)
+    Unauthorized,
+    /// 경로에서 허용하지 않는 메서드입니다. 허용하는 메서드 목록을 `Allow` 헤더로 보냅니다. (405, `error405.html`)
+    MethodNotAllowed(&'static str),
+    /// 너무 자주 요청했습니다. 다시 시도할 수 있을 때까지 남은 시간(초)을 `Retry-After` 헤더로 보냅니다. (429, `error429.html`)
+    TooManyRequests(u64),
+    /// 제한 시간 안에 요청을 처리하지 못했습니다. (503, `error503.html`)
+    Unavailable,
+    /// 요청 내용이 잘못되었습니다. (400 JSON)
+    Validation(String),
+    /// 허용되지 않은 요청입니다. (403 JSON)
+    Forbidden(String),
+    /// API에서 찾는 유저, 팀, 스템프 등이 없습니다. (404 JSON)
+    Missing(String),
+    /// 현재 상태와 충돌하는 요청입니다. (409 JSON)
+    Conflict(String),
+    /// 설정되지 않아 사용할 수 없는 기능입니다. (503 JSON)
+    Disabled(String),
+    /// 메시지 외의 정보를 함께 보내는 JSON 오류입니다.
+    Rejected(StatusCode, Value),
+    /// 파일이나 데이터베이스를 읽고 쓰지 못했습니다. (500, `error500.html`)
+    Storage(String),
+    /// HTML 템플릿이나 공유 이미지를 읽거나 렌더링하지 못했습니다. (500, `error500.html`)
+    Template(String),
+    /// 핸들러가 패닉했습니다. 로그는 `panic::catch_panic`이 남깁니다. (500, `error500.html`)
+    Panic(String),
+    /// 서버 구성이 잘못되었습니다. 핸들러가 사용하는 앱 데이터가 등록되지 않은 경우 등입니다. (500, `error500.html`)
+    Internal(String),
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::NotFound => write!(f, "Not found"),
+            AppError::Unauthorized => write!(f, "Unauthorized"),
+            AppError::MethodNotAllowed(allow) => write!(f, "Method not allowed (allow: {})", allow),
+            AppError::TooManyRequests(secs) => {
+                write!(f, "Too many requests (retry after {}s)", secs)
+            }
+            AppError::Unavailable => write!(f, "Service unavailable"),
+            AppError::Validation(message)
+            | AppError::Forbidden(message)
+            | AppError::Missing(message)
+            | AppError::Conflict(message)
+            | AppError::Disabled(message) => write!(f, "{}", message),
+            AppError::Rejected(status, body) => write!(f, "{} {}", status, body),
+            AppError::Storage(message) => write!(f, "Storage error: {}", message),
+            AppError::Template(message) => write!(f, "Template error: {}", message),
+            AppError::Panic(message) => write!(f, "Handler panicked: {}", message),
+            AppError::Internal(message) => write!(f, "Internal error: {}", message),
+        }
+    }
+}
+
+impl From<io::Error> for AppError {
+    fn from(e: io::Error) -> Self {
+        AppError::Storage(e.to_string())
+    }
+}
+
+/// 오류 페이지 템플릿을 요청 언어로 읽습니다. 템플릿이 없으면 빈 본문을 사용합니다.
+///
+/// `i18n::localized_template`은 파일을 바로 읽으므로 기다리지 않고 결과를 꺼낼 수 있습니다.
+fn error_page(file: &str) -> String {
+    i18n::localized_template(file)
+        .now_or_never()
+        .and_then(Result::ok)
+        .unwrap_or_default()
+}
+
+impl ResponseError for AppError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AppError::NotFound | AppError::Missing(_) => StatusCode::NOT_FOUND,
+            AppError::Unauthorized => StatusCode::UNAUTHORIZED,
+            AppError::MethodNotAllowed(_) => StatusCode::METHOD_NOT_ALLOWED,
+            AppError::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
+            AppError::Unavailable | AppError::Disabled(_) => StatusCode::SERVICE_UNAVAILABLE,
+            AppError::Validation(_) => StatusCode::BAD_REQUEST,
+            AppError::Forbidden(_) => StatusCode::FORBIDDEN,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::Rejected(status, _) => *status,
+            AppError::Storage(_)
+            | AppError::Template(_)
+            | AppError::Panic(_)
+            | AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let mut response = HttpResponse::build(self.status_code());
+        match self {
+            AppError::NotFound => response.body(error_page("error404.html")),
+            AppError::Unauthorized => response.body(error_page("error401.html")),
+            AppError::MethodNotAllowed(allow) => response
+                .insert_header((ALLOW, *allow))
+                .body(error_page("error405.html")),
+            AppError::TooManyRequests(secs) => response
+                .insert_header((RETRY_AFTER, *secs))
+                .body(error_page("error429.html")),
+            // 잠시 후 다시 시도하도록 안내
+            AppError::Unavailable => response
+                .insert_header((RETRY_AFTER, 1))
+                .body(error_page("error503.html")),
+            AppError::Validation(message)
+            | AppError::Forbidden(message)
+            | AppError::Missing(message)
+            | AppError::Conflict(message)
+            | AppError::Disabled(message) => response.json(json!({ "error": message })),
+            AppError::Rejected(_, body) => response.json(body),
+            // 파일 경로 등 내부 정보는 응답에 담지 않고 로그에만 남김
+            AppError::Storage(_) | AppError::Template(_) | AppError::Internal(_) => {
+                error!("{}", self);
+                response.body(error_page("error500.html"))
+            }
+            AppError::Panic(_) => response.body(error_page("error500.html")),
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use actix_web::{routes, web::Data, HttpResponse};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, sync::Mutex};
+
+use crate::announcement::active_announcement;
+use crate::config::Config;
+use crate::features::{feature_states, Feature};
+use crate::lock::MutexExt;
+use crate::state::{Announcement, Reloadable, StampIdList, TourStatus};
+use crate::tour::is_closed;
+
+/// `resources/config.json`의 `event` 항목입니다. PWA 머리글에 표시하는 행사 정보입니다.
+/// 행사 이름은 `share.festival_name`, 마감 시각은 `closes_at`을 함께 사용합니다.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct EventConfig {
+    /// 행사 시작 시각(RFC 3339)입니다.
+    pub starts_at: Option<String>,
+    /// 행사 날짜(`YYYY-MM-DD`) 목록입니다.
+    pub dates: Vec<String>,
+    /// 모든 부스에 공통인 운영 시간(예: `"10:00-17:00"`)입니다. 스템프의 `stampHours`가 우선합니다.
+    pub booth_hours: Option<String>,
+}
+
+/// `/api/v1/event`로 반환되는 행사 정보입니다.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EventInfo {
+    pub name: String,
+    pub starts_at: Option<String>,
+    pub closes_at: Option<String>,
+    pub dates: Vec<String>,
+    /// 로그인과 스템프 찍기를 받지 않는 상태인지입니다. 서버의 마감 판단과 같습니다.
+    pub closed: bool,
+    /// 마감까지 남은 시간(초)입니다. 마감되었으면 0, 마감 시각이 없으면 `null`입니다.
+    pub remaining_secs: Option<i64>,
+    /// 공개 스템프 ID별 부스 운영 시간입니다. 운영 시간이 정해지지 않은 부스는 빠집니다.
+    pub booth_hours: BTreeMap<String, String>,
+    /// 표시 중인 공지입니다.
+    pub announcement: Option<Announcement>,
+    /// 리허설용 시험 운영 중인지입니다. PWA는 `TEST MODE` 띠를 표시합니다.
+    pub staging: bool,
+    /// 기능별로 켜져 있는지입니다. PWA는 꺼진 기능의 메뉴를 숨깁니다.
+    pub features: BTreeMap<Feature, bool>,
+}
+
+/// 행사 설정과 현재 상태로 행사 정보를 계산하는 함수입니다.
+///
+/// # Arguments
+///
+/// * `config` - 행사 설정입니다.
+/// * `stamp_id_list` - 부스 운영 시간을 읽을 스템프 목록입니다.
+/// * `tour_status` - 관리자가 설정한 투어 운영 상태입니다.
+/// * `announcement` - 표시 중인 공지입니다.
+/// * `now` - 남은 시간 계산의 기준 시각입니다.
+pub fn event_info(
+    config: &Config,
+    stamp_id_list: &StampIdList,
+    tour_status: &TourStatus,
+    announcement: Option<Announcement>,
+    now: DateTime<Utc>,
+) -> EventInfo {
+    let closed = is_closed(config, tour_status, now);
+    let remaining_secs = match closed {
+        true => Some(0),
+        false => config
+            .closes_at
+            .as_deref()
+            .and_then(|closes_at| DateTime::parse_from_rfc3339(closes_at).ok())
+            .map(|closes_at| (closes_at.with_timezone(&Utc) - now).num_seconds().max(0)),
+    };
+    let booth_hours = stamp_id_list
+        .visible()
+        .filter_map(|stamp| {
+            let hours = stamp
+                .stampHours
+                .as_ref()
+                .or(config.event.booth_hours.as_ref())?;
+            Some((stamp.stampId.clone(), hours.clone()))
+        })
+        .collect();
+
+    EventInfo {
+        name: config.share.festival_name.clone(),
+        starts_at: config.event.starts_at.clone(),
+        closes_at: config.closes_at.clone(),
+        dates: config.event.dates.clone(),
+        closed,
+        remaining_secs,
+        booth_hours,
+        announcement,
+        staging: config.staging,
+        features: feature_states(config, tour_status),
+    }
+}
+
+/// 행사 이름, 날짜, 마감까지 남은 시간, 부스 운영 시간, 공지를 JSON으로 반환하는 비동기 함수입니다.
+/// PWA는 이 값을 사용하여 서버의 마감 판단과 같은 정보를 표시합니다.
+///
+/// # Returns
+///
+/// `EventInfo`를 담은 200 OK 응답이 반환됩니다.
+#[routes]
+#[get("/api/v1/event")]
+#[get("/api/event")]
+pub async fn handle_event(
+    config: Data<Reloadable<Config>>,
+    stamp_id_list: Data<Reloadable<StampIdList>>,
+    tour_status: Data<Mutex<TourStatus>>,
+    announcement: Data<Mutex<Option<Announcement>>>,
+) -> HttpResponse {
+    let tour_status = tour_status.lock_or_recover().clone();
+    HttpResponse::Ok().json(event_info(
+        &config.get(),
+        &stamp_id_list.get(),
+        &tour_status,
+        active_announcement(&announcement),
+        Utc::now(),
+    ))
+}
@@ -0,0 +1,315 @@
+use log::{error, info};
+use openssl::sha::sha256;
+use rust_xlsxwriter::{Format, Workbook, Worksheet, XlsxError};
+use std::{fs, io, path::PathBuf};
+
+use crate::backup::{upload_object, S3Config};
+use crate::config::Config;
+use crate::progress::user_progress;
+use crate::state::{CompletionList, FeedbackList, StampHistory, StampIdList, TeamList, UserList};
+
+/// 유저별 진행 상황을 CSV로 변환하는 함수입니다.
+///
+/// # Arguments
+///
+/// * `config` - 완주 등급이 정의된 행사 설정입니다.
+/// * `stamp_id_list` - 전체 스템프 목록입니다.
+/// * `stamp_history` - 스템프 기록입니다.
+/// * `user_list` - 등록된 유저 목록입니다.
+///
+/// # Returns
+///
+/// `user_id,user_name,collected_count,points,tier` 열을 가진 CSV 문자열을 반환합니다.
+pub fn users_csv(
+    config: &Config,
+    stamp_id_list: &StampIdList,
+    stamp_history: &StampHistory,
+    user_list: &UserList,
+) -> Result<String, csv::Error> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(["user_id", "user_name", "collected_count", "points", "tier"])?;
+
+    for (user_id, user_name) in user_list.users.iter() {
+        let progress = user_progress(config, stamp_id_list, stamp_history, user_id, user_name);
+        writer.write_record([
+            user_id,
+            user_name,
+            &progress.collected_count.to_string(),
+            &progress.points.to_string(),
+            &progress.tier.unwrap_or_default(),
+        ])?;
+    }
+
+    let bytes = writer.into_inner().map_err(|e| e.into_error())?;
+    Ok(String::from_utf8(bytes).unwrap_or_default())
+}
+
+/// 협력사 보고용 경품 수령 기록을 CSV로 변환하는 함수입니다.
+///
+/// # Arguments
+///
+/// * `completions` - 경품 수령 기록을 담은 완주 기록입니다.
+/// * `user_list` - 등록된 유저 목록입니다. 개인정보를 지운 유저의 이름은 비워 둡니다.
+///
+/// # Returns
+///
+/// 지급한 순서대로 `code,user_name,tier,redeemed_by,timestamp` 열을 가진 CSV 문자열을 반환합니다.
+pub fn redemptions_csv(
+    completions: &CompletionList,
+    user_list: &UserList,
+) -> Result<String, csv::Error> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(["code", "user_name", "tier", "redeemed_by", "timestamp"])?;
+
+    for record in completions.redemptions.iter() {
+        writer.write_record([
+            record.code.as_str(),
+            user_list
+                .users
+                .get(&record.user_id)
+                .map_or("", |user_name| user_name.as_str()),
+            &record.tier_name,
+            &record.redeemed_by,
+            &record.timestamp,
+        ])?;
+    }
+
+    let bytes = writer.into_inner().map_err(|e| e.into_error())?;
+    Ok(String::from_utf8(bytes).unwrap_or_default())
+}
+
+/// 방문 후기를 CSV로 변환하는 함수입니다. 설문 결과를 공유할 수 있도록 유저 ID와 이름은 넣지 않습니다.
+///
+/// # Returns
+///
+/// 보낸 시각 순으로 정렬된 `rating,comment,timestamp` 열을 가진 CSV 문자열을 반환합니다.
+pub fn feedback_csv(feedback: &FeedbackList) -> Result<String, csv::Error> {
+    let mut entries: Vec<_> = feedback.entries.values().collect();
+    entries.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(["rating", "comment", "timestamp"])?;
+    for entry in entries {
+        writer.write_record([
+            entry.rating.to_string().as_str(),
+            &entry.comment,
+            &entry.timestamp,
+        ])?;
+    }
+
+    let bytes = writer.into_inner().map_err(|e| e.into_error())?;
+    Ok(String::from_utf8(bytes).unwrap_or_default())
+}
+
+/// 유저 ID를 솔트와 함께 SHA-256으로 해싱하여 16자리 가명으로 변환합니다.
+pub fn hash_user_id(salt: &str, user_id: &str) -> String {
+    sha256(format!("{}:{}", salt, user_id).as_bytes())[..8]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// 연구용으로 공유할 수 있도록 이름 없이 해싱된 유저 ID로 스템프 기록을 CSV로 변환하는 함수입니다.
+///
+/// # Arguments
+///
+/// * `stamp_history` - 스템프 기록입니다.
+/// * `salt` - 유저 ID 해싱에 사용할 솔트입니다. 같은 솔트로 만든 파일끼리만 같은 방문자를 연결할 수 있습니다.
+///
+/// # Returns
+///
+/// 기록 순번(`seq`) 순으로 정렬된 `visitor,stamp_id,timestamp,seq` 열을 가진 CSV 문자열을 반환합니다.
+/// 기기 시계가 달라 시각이 뒤바뀐 기록도 서버에 반영된 순서대로 나옵니다.
+pub fn anonymized_history_csv(
+    stamp_history: &StampHistory,
+    salt: &str,
+) -> Result<String, csv::Error> {
+    let stamp_history = stamp_history.to_map();
+    let mut rows: Vec<(u64, &str, &str, String)> = stamp_history
+        .iter()
+        .flat_map(|(stamp_id, entries)| {
+            entries.iter().map(move |entry| {
+                (
+                    entry.seq,
+                    entry.timestamp.as_str(),
+                    stamp_id.as_str(),
+                    hash_user_id(salt, &entry.user_id),
+                )
+            })
+        })
+        .collect();
+    rows.sort();
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(["visitor", "stamp_id", "timestamp", "seq"])?;
+    for (seq, timestamp, stamp_id, visitor) in rows {
+        writer.write_record([visitor.as_str(), stamp_id, timestamp, &seq.to_string()])?;
+    }
+
+    let bytes = writer.into_inner().map_err(|e| e.into_error())?;
+    Ok(String::from_utf8(bytes).unwrap_or_default())
+}
+
+/// 시트 이름을 정하고 첫 행에 굵은 글씨의 머리글을 쓴 뒤 머리글을 고정합니다.
+fn add_sheet<'a>(
+    workbook: &'a mut Workbook,
+    name: &str,
+    headers: &[&str],
+) -> Result<&'a mut Worksheet, XlsxError> {
+    let worksheet = workbook.add_worksheet();
+    worksheet.set_name(name)?;
+    worksheet.write_row_with_format(0, 0, headers.iter().copied(), &Format::new().set_bold())?;
+    worksheet.set_freeze_panes(1, 0)?;
+    Ok(worksheet)
+}
+
+/// 최종 참가자 결과를 엑셀(xlsx) 통합 문서로 변환하는 함수입니다.
+///
+/// # Arguments
+///
+/// * `config` - 완주 등급이 정의된 행사 설정입니다.
+/// * `stamp_id_list` - 전체 스템프 목록입니다.
+/// * `stamp_history` - 스템프 기록입니다.
+/// * `user_list` - 등록된 유저 목록입니다.
+/// * `completions` - 유저별 완주 기록입니다.
+/// * `teams` - 팀 목록입니다.
+///
+/// # Returns
+///
+/// `users`, `stamp_history`, `completions` 세 개의 시트를 가진 xlsx 파일 내용을 반환합니다.
+pub fn results_xlsx(
+    config: &Config,
+    stamp_id_list: &StampIdList,
+    stamp_history: &StampHistory,
+    user_list: &UserList,
+    completions: &CompletionList,
+    teams: &TeamList,
+) -> Result<Vec<u8>, XlsxError> {
+    let mut workbook = Workbook::new();
+
+    // 유저별 진행 상황
+    let worksheet = add_sheet(
+        &mut workbook,
+        "users",
+        &[
+            "user_id",
+            "user_name",
+            "team_name",
+            "collected_count",
+            "points",
+            "tier",
+        ],
+    )?;
+    for (row, (user_id, user_name)) in (1..).zip(user_list.users.iter()) {
+        let progress = user_progress(config, stamp_id_list, stamp_history, user_id, user_name);
+        let team_name = teams
+            .team_of(user_id)
+            .and_then(|team_code| teams.teams.get(team_code))
+            .map(|team| team.team_name.as_str())
+            .unwrap_or_default();
+        worksheet.write(row, 0, user_id)?;
+        worksheet.write(row, 1, user_name)?;
+        worksheet.write(row, 2, team_name)?;
+        worksheet.write(row, 3, progress.collected_count as u32)?;
+        worksheet.write(row, 4, progress.points)?;
+        worksheet.write(row, 5, progress.tier.unwrap_or_default())?;
+    }
+    worksheet.autofit();
+
+    // 스템프별 발급 기록 (스템프 ID, 시각 순)
+    let worksheet = add_sheet(
+        &mut workbook,
+        "stamp_history",
+        &[
+            "stamp_id",
+            "stamp_name",
+            "user_id",
+            "user_name",
+            "timestamp",
+            "seq",
+        ],
+    )?;
+    let stamp_history = stamp_history.to_map();
+    let mut stamp_ids: Vec<&String> = stamp_history.keys().collect();
+    stamp_ids.sort();
+    let entries = stamp_ids.into_iter().flat_map(|stamp_id| {
+        stamp_history[stamp_id]
+            .iter()
+            .map(move |entry| (stamp_id, entry))
+    });
+    for (row, (stamp_id, entry)) in (1..).zip(entries) {
+        let stamp_name = stamp_id_list
+            .stamp_id_list
+            .get(stamp_id)
+            .map(|stamp| stamp.stampName.as_str())
+            .unwrap_or_default();
+        worksheet.write(row, 0, stamp_id)?;
+        worksheet.write(row, 1, stamp_name)?;
+        worksheet.write(row, 2, &entry.user_id)?;
+        worksheet.write(row, 3, &entry.user_name)?;
+        worksheet.write(row, 4, &entry.timestamp)?;
+        worksheet.write(row, 5, entry.seq)?;
+    }
+    worksheet.autofit();
+
+    // 완주 등급 달성 기록
+    let worksheet = add_sheet(
+        &mut workbook,
+        "completions",
+        &["user_id", "user_name", "tier_name", "timestamp"],
+    )?;
+    let records = completions
+        .completions
+        .iter()
+        .flat_map(|(user_id, records)| records.iter().map(move |record| (user_id, record)));
+    for (row, (user_id, record)) in (1..).zip(records) {
+        worksheet.write(row, 0, user_id)?;
+        worksheet.write(
+            row,
+            1,
+            user_list
+                .users
+                .get(user_id)
+                .map(String::as_str)
+                .unwrap_or_default(),
+        )?;
+        worksheet.write(row, 2, &record.tier_name)?;
+        worksheet.write(row, 3, &record.timestamp)?;
+    }
+    worksheet.autofit();
+
+    workbook.save_to_buffer()
+}
+
+/// 내보내기 파일을 `resources/exports/{file_name}`에 저장하는 함수입니다.
+/// S3 호환 저장소를 설정했으면 `exports/{file_name}` 키로 함께 올립니다.
+///
+/// # Returns
+///
+/// 저장에 성공하면 저장된 파일 경로를, 실패하면 입출력 오류를 반환합니다.
+pub fn write_export(
+    file_name: &str,
+    contents: &[u8],
+    s3: Option<&S3Config>,
+) -> io::Result<PathBuf> {
+    let dir = PathBuf::from("resources/exports");
+    let file_path = dir.join(file_name);
+
+    fs::create_dir_all(&dir)
+        .and_then(|_| fs::write(&file_path, contents))
+        .map(|_| {
+            info!("Export saved to {}", file_path.display());
+            if let Some(s3) = s3 {
+                upload_object(
+                    s3.clone(),
+                    format!("exports/{}", file_name),
+                    contents.to_vec(),
+                );
+            }
+            file_path
+        })
+        .map_err(|e| {
+            error!("Export {} failed: {}", file_name, e);
+            e
+        })
+}
@@ -0,0 +1,152 @@
+use actix_web::{web::Data, HttpRequest};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt, sync::Mutex};
+
+use crate::config::Config;
+use crate::error::AppError;
+use crate::lock::MutexExt;
+use crate::state::{Reloadable, TourStatus};
+
+/// 행사마다 켜고 끌 수 있는 시험적인 기능입니다.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Feature {
+    /// 개인과 팀 순위표(`/api/v1/leaderboard`, `/api/v1/teams/leaderboard`)입니다.
+    Leaderboard,
+    /// 오늘의 미션(`/api/v1/missions`)입니다.
+    Missions,
+    /// 스템프를 찍을 때 방문객 위치를 확인하는 기능(`geofence`)입니다.
+    Geofencing,
+}
+
+impl Feature {
+    /// 모든 기능입니다.
+    pub const ALL: [Feature; 3] = [Feature::Leaderboard, Feature::Missions, Feature::Geofencing];
+
+    /// 설정과 관리자 명령에서 사용하는 이름입니다.
+    pub fn name(self) -> &'static str {
+        match self {
+            Feature::Leaderboard => "leaderboard",
+            Feature::Missions => "missions",
+            Feature::Geofencing => "geofencing",
+        }
+    }
+
+    /// 이름으로 기능을 찾습니다.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use gj_stamptour::features::Feature;
+    ///
+    /// assert_eq!(Feature::parse("missions"), Some(Feature::Missions));
+    /// assert_eq!(Feature::parse("chat"), None);
+    /// ```
+    pub fn parse(name: &str) -> Option<Feature> {
+        Feature::ALL
+            .into_iter()
+            .find(|feature| feature.name() == name)
+    }
+}
+
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// `resources/config.json`의 `features` 항목입니다. 행사마다 기본으로 켤 기능을 정합니다.
+/// 기존 행사와 같게 동작하도록 기본값은 모두 켜져 있습니다.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct FeatureConfig {
+    pub leaderboard: bool,
+    pub missions: bool,
+    pub geofencing: bool,
+}
+
+impl Default for FeatureConfig {
+    fn default() -> Self {
+        FeatureConfig {
+            leaderboard: true,
+            missions: true,
+            geofencing: true,
+        }
+    }
+}
+
+impl FeatureConfig {
+    /// 설정에서 기능을 켰는지 확인합니다.
+    pub fn get(&self, feature: Feature) -> bool {
+        match feature {
+            Feature::Leaderboard => self.leaderboard,
+            Feature::Missions => self.missions,
+            Feature::Geofencing => self.geofencing,
+        }
+    }
+}
+
+/// 기능이 켜져 있는지 확인하는 함수입니다. `feature` 관리자 명령으로 바꾼 값이 설정보다 우선합니다.
+///
+/// # Arguments
+///
+/// * `config` - 기능별 기본값을 담은 행사 설정입니다.
+/// * `tour_status` - 관리자가 바꾼 기능별 값을 담은 투어 운영 상태입니다.
+/// * `feature` - 확인할 기능입니다.
+///
+/// # Example
+///
+/// ```rust
+/// use gj_stamptour::{config::Config, features::{is_enabled, Feature}, state::TourStatus};
+///
+/// let mut tour_status = TourStatus::default();
+/// assert!(is_enabled(&Config::default(), &tour_status, Feature::Leaderboard));
+/// tour_status.features.insert(Feature::Leaderboard, false);
+/// assert!(!is_enabled(&Config::default(), &tour_status, Feature::Leaderboard));
+/// ```
+pub fn is_enabled(config: &Config, tour_status: &TourStatus, feature: Feature) -> bool {
+    tour_status
+        .features
+        .get(&feature)
+        .copied()
+        .unwrap_or_else(|| config.features.get(feature))
+}
+
+/// 모든 기능이 켜져 있는지를 이름 순으로 반환합니다. `/api/v1/event`와 `features` 관리자 명령에서 사용합니다.
+pub fn feature_states(config: &Config, tour_status: &TourStatus) -> BTreeMap<Feature, bool> {
+    Feature::ALL
+        .into_iter()
+        .map(|feature| (feature, is_enabled(config, tour_status, feature)))
+        .collect()
+}
+
+/// 요청의 앱 데이터로 기능이 켜져 있는지 확인하는 함수입니다. 핸들러 인수를 늘리지 않고 사용할 수 있습니다.
+/// 앱 데이터에 설정이 없으면 기본 설정을 사용합니다.
+pub fn feature_enabled(req: &HttpRequest, feature: Feature) -> bool {
+    let config = req
+        .app_data::<Data<Reloadable<Config>>>()
+        .map(|config| config.get())
+        .unwrap_or_default();
+    let tour_status = req
+        .app_data::<Data<Mutex<TourStatus>>>()
+        .map(|tour_status| tour_status.lock_or_recover().clone())
+        .unwrap_or_default();
+    is_enabled(&config, &tour_status, feature)
+}
+
+/// 꺼진 기능의 API 요청을 503 응답으로 거절하는 함수입니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// pub async fn handle_leaderboard(req: HttpRequest, ...) -> Result<HttpResponse, AppError> {
+///     require_feature(&req, Feature::Leaderboard)?;
+///     ...
+/// }
+/// ```
+pub fn require_feature(req: &HttpRequest, feature: Feature) -> Result<(), AppError> {
+    match feature_enabled(req, feature) {
+        true => Ok(()),
+        false => Err(AppError::Disabled(format!("{} is disabled", feature))),
+    }
+}
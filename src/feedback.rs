@@ -0,0 +1,93 @@
+use actix_web::{routes, web::Data, web::Json, HttpResponse};
+use chrono::Utc;
+use log::info;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Mutex;
+
+use crate::error::AppError;
+use crate::lock::MutexExt;
+use crate::persistence::{Dataset, Persister};
+use crate::session::CurrentUser;
+use crate::state::{Feedback, FeedbackList};
+
+/// 후기 한 개의 최대 글자 수입니다.
+pub const MAX_COMMENT_CHARS: usize = 1000;
+
+/// `POST /feedback`으로 받는 방문 후기입니다.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct FeedbackRequest {
+    /// 1부터 5까지의 만족도입니다.
+    pub rating: u8,
+    #[serde(default)]
+    pub comment: String,
+}
+
+impl FeedbackList {
+    /// 유저의 후기를 저장합니다. 이미 보낸 후기가 있으면 새 후기로 바꿉니다.
+    ///
+    /// # Returns
+    ///
+    /// 이전 후기를 바꿨으면 `true`를 반환합니다.
+    pub fn submit(&mut self, user_id: &str, rating: u8, comment: &str) -> bool {
+        self.entries
+            .insert(
+                user_id.to_string(),
+                Feedback {
+                    rating,
+                    comment: comment.trim().to_string(),
+                    timestamp: Utc::now().to_string(),
+                },
+            )
+            .is_some()
+    }
+}
+
+/// 로그인한 유저의 만족도와 후기를 저장하는 비동기 함수입니다. 출구의 종이 설문함을 대신합니다.
+///
+/// # Arguments
+///
+/// * `feedback` - `{"rating": 5, "comment": "..."}` 형식의 후기입니다. `comment`는 생략할 수 있습니다.
+///
+/// # Returns
+///
+/// 저장하면 200 OK 응답이 반환됩니다. 만족도가 1~5가 아니거나 후기가 너무 길면 400 Bad Request 응답이,
+/// 쿠키가 없거나 등록되지 않은 유저는 401 Unauthorized 응답이 반환됩니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// fetch("/feedback", { method: "POST", headers: { "Content-Type": "application/json" }, body: '{"rating": 5, "comment": "재밌었어요"}' });
+/// ```
+#[routes]
+#[post("/api/v1/feedback")]
+#[post("/feedback")]
+pub async fn handle_feedback(
+    user: CurrentUser,
+    feedback: Json<FeedbackRequest>,
+    feedback_list: Data<Mutex<FeedbackList>>,
+    persister: Data<Persister>,
+) -> Result<HttpResponse, AppError> {
+    if !(1..=5).contains(&feedback.rating) {
+        return Err(AppError::Validation(
+            "Rating must be between 1 and 5".to_string(),
+        ));
+    }
+    if feedback.comment.trim().chars().count() > MAX_COMMENT_CHARS {
+        return Err(AppError::Validation(format!(
+            "Comment must be at most {} characters",
+            MAX_COMMENT_CHARS
+        )));
+    }
+
+    let replaced =
+        feedback_list
+            .lock_or_recover()
+            .submit(&user.user_id, feedback.rating, &feedback.comment);
+    persister.mark(&[Dataset::Feedback]);
+    info!(
+        "User {} submitted feedback (rating {}).",
+        user.user_id, feedback.rating
+    );
+    Ok(HttpResponse::Ok().json(json!({ "saved": true, "replaced": replaced })))
+}
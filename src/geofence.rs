@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+use crate::state::{Coordinates, Stamp};
+
+/// `resources/config.json`의 `geofence` 항목입니다. 실내에서는 GPS가 부정확하므로 기본으로 꺼져 있습니다.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct GeofenceConfig {
+    /// `stampCoordinates`와 `stampRadius`가 있는 스템프에서 방문객 위치를 확인할지 여부입니다.
+    pub enabled: bool,
+    /// 위치를 보내지 않은 확인도 거절할지 여부입니다. 끄면 위치 권한을 거부한 방문객도 스템프를 찍을 수 있습니다.
+    pub require_location: bool,
+}
+
+/// 방문객 기기가 보낸 위치입니다.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClientLocation {
+    pub coordinates: Coordinates,
+    /// 위치의 오차 반경(미터)입니다.
+    pub accuracy_meters: f64,
+}
+
+/// 방문객 위치가 스템프의 허용 반경 안인지 확인하는 함수입니다.
+/// 위치 오차만큼 반경을 넓혀 주되, 오차가 반경보다 크더라도 반경의 두 배까지만 허용합니다.
+///
+/// # Arguments
+///
+/// * `config` - 위치 확인 설정입니다.
+/// * `stamp` - 확인할 스템프입니다.
+/// * `location` - 방문객 기기가 보낸 위치입니다. 보내지 않았으면 `None`입니다.
+///
+/// # Returns
+///
+/// 허용하면 `Ok(())`, 거절하면 로그에 남길 사유를 반환합니다. 위치 확인을 끄거나
+/// 스템프에 좌표나 반경이 없으면 항상 허용합니다.
+///
+/// # Example
+///
+/// ```rust
+/// use gj_stamptour::geofence::{check_geofence, ClientLocation, GeofenceConfig};
+/// use gj_stamptour::state::{Coordinates, Stamp};
+///
+/// let config = GeofenceConfig { enabled: true, require_location: false };
+/// let stamp = Stamp {
+///     stampCoordinates: Some(Coordinates { latitude: 35.15, longitude: 126.85 }),
+///     stampRadius: Some(50),
+///     ..Default::default()
+/// };
+/// let near = ClientLocation {
+///     coordinates: Coordinates { latitude: 35.1502, longitude: 126.85 },
+///     accuracy_meters: 10.0,
+/// };
+/// let far = ClientLocation {
+///     coordinates: Coordinates { latitude: 35.16, longitude: 126.85 },
+///     accuracy_meters: 10.0,
+/// };
+/// assert!(check_geofence(&config, &stamp, Some(near)).is_ok());
+/// assert!(check_geofence(&config, &stamp, Some(far)).is_err());
+/// assert!(check_geofence(&config, &stamp, None).is_ok());
+/// ```
+pub fn check_geofence(
+    config: &GeofenceConfig,
+    stamp: &Stamp,
+    location: Option<ClientLocation>,
+) -> Result<(), String> {
+    let (Some(booth), Some(radius)) = (stamp.stampCoordinates, stamp.stampRadius) else {
+        return Ok(());
+    };
+    if !config.enabled {
+        return Ok(());
+    }
+    let Some(location) = location else {
+        return match config.require_location {
+            true => Err("no location was sent".to_string()),
+            false => Ok(()),
+        };
+    };
+
+    let radius = f64::from(radius);
+    let allowed = radius + location.accuracy_meters.clamp(0.0, radius);
+    let distance = booth.distance_meters(&location.coordinates);
+    match distance <= allowed {
+        true => Ok(()),
+        false => Err(format!(
+            "{:.0}m away from the booth (allowed {:.0}m)",
+            distance, allowed
+        )),
+    }
+}
@@ -0,0 +1,379 @@
+use actix_web::{
+    get, post,
+    web::{Data, Json},
+    HttpRequest, HttpResponse,
+};
+use async_graphql::{
+    Context, EmptyMutation, EmptySubscription, Object, Result, Schema, SimpleObject,
+};
+use chrono::{DateTime, Local, Utc};
+use log::warn;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use crate::config::Config;
+use crate::error::AppError;
+use crate::lock::MutexExt;
+use crate::progress::current_tier;
+use crate::report::parse_timestamp;
+use crate::staff::is_staff;
+use crate::state::{CompletionList, Reloadable, StampHistory, StampIdList, TeamList, UserList};
+use crate::stats::{parse_bucket, public_stats, timeseries};
+
+/// 목록 조회에서 `first`를 지정하지 않았을 때 반환하는 개수입니다.
+pub const DEFAULT_PAGE_SIZE: usize = 100;
+
+/// 목록 조회 한 번에 반환하는 최대 개수입니다.
+pub const MAX_PAGE_SIZE: usize = 1000;
+
+/// 쿼리가 중첩될 수 있는 최대 깊이입니다.
+const MAX_DEPTH: usize = 8;
+
+pub type StampTourSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// 쿼리 처리 중 사용하는 공유 상태입니다. 요청마다 만들어 쿼리에 넘깁니다.
+pub struct Sources {
+    pub config: Arc<Config>,
+    pub stamp_id_list: Arc<StampIdList>,
+    pub user_list: Data<Mutex<UserList>>,
+    pub stamp_history: Data<StampHistory>,
+    pub completions: Data<Mutex<CompletionList>>,
+    pub teams: Data<Mutex<TeamList>>,
+}
+
+#[derive(SimpleObject)]
+pub struct StampNode {
+    pub stamp_id: String,
+    pub stamp_name: String,
+    pub stamp_location: String,
+    pub stamp_desc: String,
+    pub points: u32,
+    /// 발급 가능한 최대 수량입니다. 없으면 수량 제한이 없습니다.
+    pub max_issued: Option<usize>,
+    /// 지금까지 발급된 수량입니다.
+    pub issued: usize,
+}
+
+#[derive(SimpleObject)]
+pub struct UserNode {
+    pub user_id: String,
+    pub user_name: String,
+    pub user_code: Option<String>,
+    pub team_code: Option<String>,
+    /// 모은 스템프 ID 목록입니다. 현재 스템프 목록에 없는 스템프는 제외합니다.
+    pub collected: Vec<String>,
+    pub collected_count: usize,
+    pub points: u32,
+    /// 현재 달성한 가장 높은 완주 등급입니다.
+    pub tier: Option<String>,
+}
+
+#[derive(SimpleObject)]
+pub struct HistoryNode {
+    pub stamp_id: String,
+    pub user_id: String,
+    pub user_name: String,
+    pub timestamp: String,
+}
+
+#[derive(SimpleObject)]
+pub struct StatsNode {
+    pub participants: usize,
+    pub stamps_today: usize,
+    pub completions: usize,
+}
+
+#[derive(SimpleObject)]
+pub struct SeriesNode {
+    pub stamp_id: String,
+    /// `TimeseriesNode::buckets`와 같은 순서의 구간별 발급 수입니다.
+    pub counts: Vec<usize>,
+}
+
+#[derive(SimpleObject)]
+pub struct TimeseriesNode {
+    pub bucket_seconds: i64,
+    /// 각 구간의 시작 시각(RFC 3339, UTC)입니다.
+    pub buckets: Vec<String>,
+    pub stamps: Vec<SeriesNode>,
+}
+
+/// `first`, `offset` 인자를 목록에 적용합니다.
+fn page<T>(items: impl Iterator<Item = T>, first: Option<usize>, offset: Option<usize>) -> Vec<T> {
+    items
+        .skip(offset.unwrap_or(0))
+        .take(first.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE))
+        .collect()
+}
+
+/// RFC 3339 시각 인자를 해석합니다.
+fn parse_time(name: &str, value: Option<String>) -> Result<Option<DateTime<Utc>>> {
+    value
+        .map(|value| {
+            DateTime::parse_from_rfc3339(&value)
+                .map(|time| time.with_timezone(&Utc))
+                .map_err(|_| format!("{} must be an RFC 3339 timestamp", name).into())
+        })
+        .transpose()
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// 스템프 목록입니다. `ids`를 지정하면 해당 스템프만 반환합니다.
+    async fn stamps(&self, ctx: &Context<'_>, ids: Option<Vec<String>>) -> Vec<StampNode> {
+        let sources = ctx.data_unchecked::<Sources>();
+        let stamp_history = &sources.stamp_history;
+        let mut stamps: Vec<StampNode> = sources
+            .stamp_id_list
+            .stamp_id_list
+            .values()
+            .filter(|stamp| ids.as_ref().is_none_or(|ids| ids.contains(&stamp.stampId)))
+            .map(|stamp| StampNode {
+                stamp_id: stamp.stampId.clone(),
+                stamp_name: stamp.stampName.clone(),
+                stamp_location: stamp.stampLocation.clone(),
+                stamp_desc: stamp.stampDesc.clone(),
+                points: stamp.points(),
+                max_issued: stamp.maxIssued,
+                issued: stamp_history.issued(&stamp.stampId),
+            })
+            .collect();
+        stamps.sort_by(|a, b| a.stamp_id.cmp(&b.stamp_id));
+        stamps
+    }
+
+    /// 유저 목록입니다. 이름 일부, 팀, 모은 스템프, 최소 개수로 거를 수 있으며 유저 이름 순서로 반환합니다.
+    #[allow(clippy::too_many_arguments)]
+    async fn users(
+        &self,
+        ctx: &Context<'_>,
+        name_contains: Option<String>,
+        team_code: Option<String>,
+        collected_stamp: Option<String>,
+        min_collected: Option<usize>,
+        first: Option<usize>,
+        offset: Option<usize>,
+    ) -> Vec<UserNode> {
+        let sources = ctx.data_unchecked::<Sources>();
+        let stamp_id_list = &sources.stamp_id_list;
+        let user_list = sources.user_list.lock_or_recover();
+        let collections = sources.stamp_history.collections();
+        let teams = sources.teams.lock_or_recover();
+
+        let codes: HashMap<&String, &String> = user_list
+            .codes
+            .iter()
+            .map(|(user_code, user_id)| (user_id, user_code))
+            .collect();
+        let team_codes: HashMap<&String, &String> = teams
+            .teams
+            .iter()
+            .flat_map(|(team_code, team)| {
+                team.members.iter().map(move |member| (member, team_code))
+            })
+            .collect();
+        let tiers = sources.config.tiers(stamp_id_list.visible_count());
+        let name_contains = name_contains.map(|name| name.to_lowercase());
+        let team_code = team_code.map(|code| code.trim().to_uppercase());
+
+        let mut users: Vec<(&String, &String)> = user_list.users.iter().collect();
+        users.sort_by(|a, b| a.1.cmp(b.1).then(a.0.cmp(b.0)));
+        let users = users.into_iter().filter_map(|(user_id, user_name)| {
+            let collected: Vec<String> = collections
+                .get(user_id)
+                .into_iter()
+                .flatten()
+                .filter(|stamp_id| stamp_id_list.stamp_id_list.contains_key(*stamp_id))
+                .cloned()
+                .collect();
+            // 숨은 스템프는 목록에는 보여주고 개수와 등급에는 포함하지 않음
+            let count = collected
+                .iter()
+                .filter(|stamp_id| !stamp_id_list.is_hidden(stamp_id))
+                .count();
+            let matches = name_contains
+                .as_ref()
+                .is_none_or(|name| user_name.to_lowercase().contains(name))
+                && team_code
+                    .as_ref()
+                    .is_none_or(|code| team_codes.get(user_id) == Some(&code))
+                && collected_stamp
+                    .as_ref()
+                    .is_none_or(|stamp_id| collected.contains(stamp_id))
+                && min_collected.is_none_or(|min| count >= min);
+            matches.then(|| UserNode {
+                user_id: user_id.clone(),
+                user_name: user_name.clone(),
+                user_code: codes.get(user_id).map(|code| code.to_string()),
+                team_code: team_codes.get(user_id).map(|code| code.to_string()),
+                collected_count: count,
+                points: stamp_id_list.points(&collected),
+                tier: current_tier(&tiers, count),
+                collected,
+            })
+        });
+        page(users, first, offset)
+    }
+
+    /// 스템프 기록을 시각 순서로 반환합니다. 스템프, 유저, 기간(`since` 이상 `until` 미만, RFC 3339)으로 거를 수 있습니다.
+    #[allow(clippy::too_many_arguments)]
+    async fn history(
+        &self,
+        ctx: &Context<'_>,
+        stamp_id: Option<String>,
+        user_id: Option<String>,
+        since: Option<String>,
+        until: Option<String>,
+        first: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<HistoryNode>> {
+        let sources = ctx.data_unchecked::<Sources>();
+        let since = parse_time("since", since)?;
+        let until = parse_time("until", until)?;
+        let stamp_history = sources.stamp_history.to_map();
+
+        let mut entries: Vec<(Option<DateTime<Local>>, HistoryNode)> = stamp_history
+            .iter()
+            .filter(|(id, _)| stamp_id.as_ref().is_none_or(|stamp_id| stamp_id == *id))
+            .flat_map(|(id, entries)| entries.iter().map(move |entry| (id, entry)))
+            .filter(|(_, entry)| {
+                user_id
+                    .as_ref()
+                    .is_none_or(|user_id| *user_id == entry.user_id)
+            })
+            .map(|(id, entry)| (parse_timestamp(&entry.timestamp), id, entry))
+            .filter(|(timestamp, _, _)| {
+                since.is_none_or(|since| timestamp.is_some_and(|timestamp| timestamp >= since))
+                    && until
+                        .is_none_or(|until| timestamp.is_some_and(|timestamp| timestamp < until))
+            })
+            .map(|(timestamp, id, entry)| {
+                let node = HistoryNode {
+                    stamp_id: id.clone(),
+                    user_id: entry.user_id.clone(),
+                    user_name: entry.user_name.clone(),
+                    timestamp: entry.timestamp.clone(),
+                };
+                (timestamp, node)
+            })
+            .collect();
+        entries.sort_by_key(|(timestamp, _)| *timestamp);
+        Ok(page(
+            entries.into_iter().map(|(_, node)| node),
+            first,
+            offset,
+        ))
+    }
+
+    /// 참가자 수, 오늘 발급된 스템프 수, 완주 인원입니다.
+    async fn stats(&self, ctx: &Context<'_>) -> StatsNode {
+        let sources = ctx.data_unchecked::<Sources>();
+        let stats = public_stats(
+            &sources.user_list.lock_or_recover(),
+            &sources.stamp_history,
+            &sources.completions.lock_or_recover(),
+            Local::now().date_naive(),
+        );
+        StatsNode {
+            participants: stats.participants,
+            stamps_today: stats.stamps_today,
+            completions: stats.completions,
+        }
+    }
+
+    /// 스템프별 발급 수를 `bucket`(`30s`, `15m`, `1h`, `1d` 등, 기본 `15m`) 구간으로 나눈 시계열입니다.
+    async fn timeseries(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = "15m")] bucket: String,
+    ) -> Result<TimeseriesNode> {
+        let sources = ctx.data_unchecked::<Sources>();
+        let bucket = parse_bucket(&bucket).ok_or("bucket must look like 30s, 15m, 1h or 1d")?;
+        let series = timeseries(&sources.stamp_history, bucket)?;
+        Ok(TimeseriesNode {
+            bucket_seconds: series.bucket_seconds,
+            buckets: series.buckets,
+            stamps: series
+                .stamps
+                .into_iter()
+                .map(|(stamp_id, counts)| SeriesNode { stamp_id, counts })
+                .collect(),
+        })
+    }
+}
+
+/// GraphQL 스키마를 반환합니다. 처음 호출할 때 한 번만 만듭니다.
+pub fn schema() -> &'static StampTourSchema {
+    static SCHEMA: OnceLock<StampTourSchema> = OnceLock::new();
+    SCHEMA.get_or_init(|| {
+        Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+            .limit_depth(MAX_DEPTH)
+            .finish()
+    })
+}
+
+/// 대시보드에서 유저, 스템프, 기록, 통계를 원하는 형태로 조회하는 GraphQL 비동기 함수입니다.
+///
+/// # Arguments
+///
+/// * `request` - `{"query": "...", "variables": {...}}` 형식의 GraphQL 요청입니다.
+///
+/// # Returns
+///
+/// 쿼리 결과(`data`, `errors`)가 200 OK 응답으로 반환됩니다. 직원 인증에 실패하면 401 Unauthorized 응답이 반환됩니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// // POST /graphql
+/// // {"query": "{ users(teamCode: \"ABC234\", minCollected: 3) { userName collectedCount } }"}
+/// ```
+#[post("/graphql")]
+#[allow(clippy::too_many_arguments)]
+pub async fn handle_graphql(
+    req: HttpRequest,
+    request: Json<async_graphql::Request>,
+    user_list: Data<Mutex<UserList>>,
+    stamp_id_list: Data<Reloadable<StampIdList>>,
+    stamp_history: Data<StampHistory>,
+    completions: Data<Mutex<CompletionList>>,
+    teams: Data<Mutex<TeamList>>,
+    config: Data<Reloadable<Config>>,
+) -> Result<HttpResponse, AppError> {
+    let config = config.get();
+    if !is_staff(&req, &config) {
+        warn!("Unauthorized access to the GraphQL API has been identified.");
+        return Err(AppError::Unauthorized);
+    }
+
+    let response = schema()
+        .execute(request.into_inner().data(Sources {
+            config,
+            stamp_id_list: stamp_id_list.get(),
+            user_list,
+            stamp_history,
+            completions,
+            teams,
+        }))
+        .await;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// GraphQL 스키마(SDL)를 텍스트로 반환하는 비동기 함수입니다. 대시보드 도구의 자동 완성에 사용합니다.
+#[get("/graphql")]
+pub async fn handle_graphql_schema(
+    req: HttpRequest,
+    config: Data<Reloadable<Config>>,
+) -> Result<HttpResponse, AppError> {
+    if !is_staff(&req, &config.get()) {
+        warn!("Unauthorized access to the GraphQL API has been identified.");
+        return Err(AppError::Unauthorized);
+    }
+    Ok(HttpResponse::Ok()
+        .content_type("text/plain; charset=utf-8")
+        .body(schema().sdl()))
+}
@@ -0,0 +1,289 @@
+use chrono::Utc;
+use log::{info, warn};
+use std::{future::Future, net::SocketAddr};
+use tonic::{transport::Server, Request, Response, Status};
+
+use crate::config::Config;
+use crate::handlers::user_registration;
+use crate::lock::MutexExt;
+use crate::persistence::Dataset;
+use crate::progress::{record_progress, user_progress, Progress};
+use crate::report::parse_timestamp;
+use crate::state::{AppState, AuditRecord, StampUserInfo, User, UserName};
+use crate::tour::is_closed;
+
+/// `proto/stamptour.proto`에서 생성한 메시지와 서비스입니다.
+pub mod proto {
+    tonic::include_proto!("stamptour");
+}
+
+use proto::stamp_tour_server::{StampTour, StampTourServer};
+
+impl From<User> for proto::User {
+    fn from(user: User) -> Self {
+        proto::User {
+            user_id: user.user_id,
+            user_name: user.user_name,
+            user_code: user.user_code.unwrap_or_default(),
+            team_code: user.team_code.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<Progress> for proto::Progress {
+    fn from(progress: Progress) -> Self {
+        let (next_tier, next_tier_remaining) = progress
+            .next_tier
+            .map(|next| (next.tier_name, next.remaining as u32))
+            .unwrap_or_default();
+        proto::Progress {
+            user_id: progress.user_id,
+            user_name: progress.user_name,
+            collected_count: progress.collected_count as u32,
+            total_count: progress.total_count as u32,
+            collected: progress.collected,
+            points: progress.points,
+            tier: progress.tier.unwrap_or_default(),
+            next_tier,
+            next_tier_remaining,
+        }
+    }
+}
+
+/// 개찰구 기기가 사용하는 gRPC 서비스입니다. 웹 서버와 같은 공유 상태(`AppState`)를 사용하므로
+/// 어느 쪽으로 찍은 스템프든 바로 반영됩니다.
+#[derive(Clone)]
+pub struct StampTourService {
+    state: AppState,
+}
+
+impl StampTourService {
+    pub fn new(state: AppState) -> Self {
+        StampTourService { state }
+    }
+
+    /// 요청이 직원 기기에서 온 것인지 확인합니다. `staff::is_staff`와 같이 `staff_token`이 있으면
+    /// `authorization: Bearer <staff_token>` 메타데이터가 필요하고, 없으면 로컬 요청만 허용합니다.
+    #[allow(clippy::result_large_err)] // tonic의 Status를 그대로 반환
+    fn authorize<T>(&self, request: &Request<T>, config: &Config) -> Result<(), Status> {
+        let authorized = match &config.staff_token {
+            Some(staff_token) => request
+                .metadata()
+                .get("authorization")
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.strip_prefix("Bearer "))
+                .is_some_and(|token| token == staff_token),
+            None => request
+                .remote_addr()
+                .is_some_and(|addr| addr.ip().is_loopback()),
+        };
+        if authorized {
+            Ok(())
+        } else {
+            warn!("Unauthorized access to the gRPC service has been identified.");
+            Err(Status::unauthenticated("Invalid staff token"))
+        }
+    }
+
+    /// 유저 코드로 유저 ID와 이름을 찾습니다.
+    #[allow(clippy::result_large_err)]
+    fn find_user(&self, user_code: &str) -> Result<(String, String), Status> {
+        let user_list = self.state.user_list.lock_or_recover();
+        user_list
+            .find_by_code(user_code)
+            .and_then(|user_id| {
+                let user_name = user_list.users.get(user_id)?;
+                Some((user_id.clone(), user_name.clone()))
+            })
+            .ok_or_else(|| Status::not_found("User not found"))
+    }
+
+    fn progress(&self, config: &Config, user_id: &str, user_name: &str) -> proto::Progress {
+        user_progress(
+            config,
+            &self.state.stamp_list.get(),
+            &self.state.stamp_history,
+            user_id,
+            user_name,
+        )
+        .into()
+    }
+}
+
+#[tonic::async_trait]
+impl StampTour for StampTourService {
+    async fn register(
+        &self,
+        request: Request<proto::RegisterRequest>,
+    ) -> Result<Response<proto::User>, Status> {
+        let config = self.state.config.get();
+        self.authorize(&request, &config)?;
+        if is_closed(
+            &config,
+            &self.state.tour_status.lock_or_recover(),
+            Utc::now(),
+        ) {
+            return Err(Status::failed_precondition("Tour ended"));
+        }
+        let request = request.into_inner();
+        let team_code =
+            Some(request.team_code.trim().to_uppercase()).filter(|code| !code.is_empty());
+
+        let mut user = user_registration(UserName {
+            user_name: request.user_name,
+            team_code: None,
+            team_name: None,
+        });
+        if let Some(team_code) = team_code {
+            let mut teams = self.state.teams.lock_or_recover();
+            let Some(team) = teams.teams.get_mut(&team_code) else {
+                return Err(Status::not_found("Team not found"));
+            };
+            team.members.push(user.user_id.clone());
+            user.team_code = Some(team_code);
+        }
+        self.state.user_list.lock_or_recover().insert(&mut user);
+        self.state
+            .persister
+            .mark(&[Dataset::UserStatus, Dataset::TeamStatus]);
+
+        info!("{:?} has started a stomp tour via gRPC.", user);
+        Ok(Response::new(user.into()))
+    }
+
+    async fn record_stamp(
+        &self,
+        request: Request<proto::RecordStampRequest>,
+    ) -> Result<Response<proto::RecordStampResponse>, Status> {
+        let config = self.state.config.get();
+        let stamp_id_list = self.state.stamp_list.get();
+        self.authorize(&request, &config)?;
+        if is_closed(
+            &config,
+            &self.state.tour_status.lock_or_recover(),
+            Utc::now(),
+        ) {
+            return Err(Status::failed_precondition("Tour ended"));
+        }
+        let request = request.into_inner();
+        let Some(stamp) = stamp_id_list.stamp_id_list.get(&request.stamp_id) else {
+            return Err(Status::not_found("Stamp not found"));
+        };
+        let (user_id, user_name) = self.find_user(&request.user_code)?;
+
+        let timestamp = Utc::now().to_string();
+        let entry = StampUserInfo {
+            user_name: user_name.clone(),
+            user_id: user_id.clone(),
+            timestamp: timestamp.clone(),
+            seq: 0, // 기록할 때 순번 발급
+        };
+        if !self
+            .state
+            .stamp_history
+            .push_if(&request.stamp_id, entry, |issued| {
+                !stamp.is_sold_out(issued)
+            })
+        {
+            return Err(Status::resource_exhausted("Stamp is sold out"));
+        }
+        info!(
+            "A gRPC device recorded stamp {} for user {}.",
+            request.stamp_id, user_id
+        );
+
+        let milestones = record_progress(
+            &config,
+            &stamp_id_list,
+            &self.state.stamp_history,
+            &mut self.state.completions.lock_or_recover(),
+            &mut self.state.teams.lock_or_recover(),
+            &self.state.notifier,
+            &user_id,
+        );
+        for event in milestones {
+            self.state.notifier.notify(event);
+        }
+        self.state
+            .audit_log
+            .lock_or_recover()
+            .entries
+            .push(AuditRecord {
+                action: "grpc stamp".to_string(),
+                user_id: user_id.clone(),
+                stamp_id: Some(request.stamp_id.clone()),
+                actor: "grpc".to_string(),
+                reason: String::new(),
+                timestamp: timestamp.clone(),
+            });
+        self.state.persister.mark(&[
+            Dataset::StampStatus,
+            Dataset::CompletionStatus,
+            Dataset::TeamStatus,
+            Dataset::AuditLog,
+        ]);
+
+        Ok(Response::new(proto::RecordStampResponse {
+            user_code: request.user_code.trim().to_uppercase(),
+            stamp_id: request.stamp_id,
+            timestamp,
+            progress: Some(self.progress(&config, &user_id, &user_name)),
+        }))
+    }
+
+    async fn get_progress(
+        &self,
+        request: Request<proto::UserRequest>,
+    ) -> Result<Response<proto::Progress>, Status> {
+        let config = self.state.config.get();
+        self.authorize(&request, &config)?;
+        let (user_id, user_name) = self.find_user(&request.get_ref().user_code)?;
+        Ok(Response::new(self.progress(&config, &user_id, &user_name)))
+    }
+
+    async fn get_history(
+        &self,
+        request: Request<proto::UserRequest>,
+    ) -> Result<Response<proto::History>, Status> {
+        let config = self.state.config.get();
+        self.authorize(&request, &config)?;
+        let (user_id, _) = self.find_user(&request.get_ref().user_code)?;
+
+        let mut entries: Vec<proto::HistoryEntry> = self
+            .state
+            .stamp_history
+            .to_map()
+            .iter()
+            .flat_map(|(stamp_id, entries)| {
+                entries
+                    .iter()
+                    .filter(|entry| entry.user_id == user_id)
+                    .map(|entry| proto::HistoryEntry {
+                        stamp_id: stamp_id.clone(),
+                        timestamp: entry.timestamp.clone(),
+                    })
+            })
+            .collect();
+        entries.sort_by_key(|entry| parse_timestamp(&entry.timestamp));
+        Ok(Response::new(proto::History { entries }))
+    }
+}
+
+/// 설정된 주소에서 gRPC 서버를 실행하는 비동기 함수입니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// grpc::serve(state.clone(), "0.0.0.0:50051".parse().unwrap(), async {}).await?;
+/// ```
+pub async fn serve(
+    state: AppState,
+    address: SocketAddr,
+    shutdown: impl Future<Output = ()>,
+) -> Result<(), tonic::transport::Error> {
+    info!("gRPC server is listening on {}.", address);
+    Server::builder()
+        .add_service(StampTourServer::new(StampTourService::new(state)))
+        .serve_with_shutdown(address, shutdown)
+        .await
+}
@@ -0,0 +1,255 @@
+use actix_web::{get, routes, web::Data, web::Json, HttpResponse};
+use chrono::{Local, Utc};
+use log::info;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Mutex;
+
+use crate::config::Config;
+use crate::error::AppError;
+use crate::lock::MutexExt;
+use crate::persistence::{Dataset, Persister};
+use crate::report::parse_timestamp;
+use crate::session::CurrentUser;
+use crate::state::{Guestbook, GuestbookEntry, GuestbookStatus, Reloadable};
+use crate::template::escape_html;
+
+/// `resources/config.json`의 `guestbook` 항목입니다.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct GuestbookConfig {
+    /// 글 한 개의 최대 글자 수입니다.
+    pub max_chars: usize,
+    /// 한 유저가 글을 남길 수 있는 최소 간격(초)입니다.
+    pub interval_secs: u64,
+    /// `true`면 관리자가 승인한 글만 화면에 나타납니다. `false`면 바로 나타나고 문제가 있는 글만 숨깁니다.
+    pub require_approval: bool,
+    /// `/guestbook` 화면에 보여줄 최근 글 수입니다.
+    pub display_count: usize,
+    /// `/guestbook` 화면을 새로 고치는 간격(초)입니다.
+    pub refresh_secs: u64,
+}
+
+impl Default for GuestbookConfig {
+    fn default() -> Self {
+        GuestbookConfig {
+            max_chars: 200,
+            interval_secs: 300,
+            require_approval: true,
+            display_count: 30,
+            refresh_secs: 30,
+        }
+    }
+}
+
+/// `POST /guestbook`으로 받는 방명록 글입니다.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct GuestbookRequest {
+    pub message: String,
+}
+
+impl Guestbook {
+    /// 새 글을 추가하고 글 번호를 반환합니다.
+    pub fn post(
+        &mut self,
+        user_id: &str,
+        user_name: &str,
+        message: &str,
+        status: GuestbookStatus,
+    ) -> u64 {
+        self.next_id += 1;
+        self.entries.push(GuestbookEntry {
+            entry_id: self.next_id,
+            user_id: user_id.to_string(),
+            user_name: user_name.to_string(),
+            message: message.to_string(),
+            timestamp: Utc::now().to_string(),
+            status,
+        });
+        self.next_id
+    }
+
+    /// 유저가 마지막으로 글을 남긴 뒤 `interval_secs`가 지나지 않았으면 남은 시간(초)을 반환합니다.
+    pub fn wait_secs(&self, user_id: &str, interval_secs: u64) -> Option<u64> {
+        let last = self
+            .entries
+            .iter()
+            .rev()
+            .find(|entry| entry.user_id == user_id)
+            .and_then(|entry| parse_timestamp(&entry.timestamp))?;
+        let elapsed = (Local::now() - last).num_seconds().max(0) as u64;
+        (elapsed < interval_secs).then(|| interval_secs - elapsed)
+    }
+
+    /// 글의 검토 상태를 바꿉니다. 해당 번호의 글이 없으면 `false`를 반환합니다.
+    pub fn moderate(&mut self, entry_id: u64, status: GuestbookStatus) -> bool {
+        match self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.entry_id == entry_id)
+        {
+            Some(entry) => {
+                entry.status = status;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 화면에 보여줄 승인된 글을 최근 글부터 최대 `count`개 반환합니다.
+    pub fn approved(&self, count: usize) -> Vec<&GuestbookEntry> {
+        self.entries
+            .iter()
+            .rev()
+            .filter(|entry| entry.status == GuestbookStatus::Approved)
+            .take(count)
+            .collect()
+    }
+}
+
+/// `guestbook queue`, `guestbook approve <번호>`, `guestbook hide <번호>` 관리자 명령을 처리하는 함수입니다.
+///
+/// # Arguments
+///
+/// * `args` - `guestbook ` 뒤의 명령입니다.
+/// * `guestbook` - 방명록입니다.
+/// * `persister` - 바뀐 방명록을 저장할 `Persister`입니다.
+///
+/// # Returns
+///
+/// 관리자 명령의 출력을 반환합니다.
+pub fn guestbook_command(
+    args: &str,
+    guestbook: &Mutex<Guestbook>,
+    persister: &Persister,
+) -> String {
+    let usage = "Usage: guestbook queue|guestbook approve <id>|guestbook hide <id>".to_string();
+    let (action, arg) = args.trim().split_once(' ').unwrap_or((args.trim(), ""));
+    let status = match action {
+        "queue" => {
+            let guestbook = guestbook.lock_or_recover();
+            let pending: Vec<String> = guestbook
+                .entries
+                .iter()
+                .filter(|entry| entry.status == GuestbookStatus::Pending)
+                .map(|entry| format!("#{} {}: {}", entry.entry_id, entry.user_name, entry.message))
+                .collect();
+            return match pending.is_empty() {
+                true => "No pending guestbook entries".to_string(),
+                false => pending.join("\n"),
+            };
+        }
+        "approve" => GuestbookStatus::Approved,
+        "hide" => GuestbookStatus::Hidden,
+        _ => return usage,
+    };
+    let Ok(entry_id) = arg.trim().trim_start_matches('#').parse::<u64>() else {
+        return usage;
+    };
+    if !guestbook.lock_or_recover().moderate(entry_id, status) {
+        return format!("Guestbook entry #{} not found", entry_id);
+    }
+    persister.mark(&[Dataset::Guestbook]);
+    info!("Guestbook entry #{} set to {:?}", entry_id, status);
+    format!("Guestbook entry #{} {}", entry_id, action_done(status))
+}
+
+fn action_done(status: GuestbookStatus) -> &'static str {
+    match status {
+        GuestbookStatus::Approved => "approved",
+        GuestbookStatus::Hidden => "hidden",
+        GuestbookStatus::Pending => "queued",
+    }
+}
+
+/// 로그인한 유저의 방명록 글을 저장하는 비동기 함수입니다.
+///
+/// # Arguments
+///
+/// * `body` - `{"message": "..."}` 형식의 글입니다.
+///
+/// # Returns
+///
+/// 저장하면 글 번호와 검토 상태(`pending` 또는 `approved`)가 200 OK 응답으로 반환됩니다.
+/// 글이 비었거나 너무 길면 400 Bad Request 응답이, 쿠키가 없거나 등록되지 않은 유저는 401 Unauthorized 응답이,
+/// 글을 남길 수 있는 간격이 지나지 않았으면 `Retry-After` 헤더와 함께 429 Too Many Requests 응답이 반환됩니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// fetch("/guestbook", { method: "POST", headers: { "Content-Type": "application/json" }, body: '{"message": "즐거운 축제였어요!"}' });
+/// ```
+#[routes]
+#[post("/api/v1/guestbook")]
+#[post("/guestbook")]
+pub async fn handle_guestbook_post(
+    user: CurrentUser,
+    body: Json<GuestbookRequest>,
+    guestbook: Data<Mutex<Guestbook>>,
+    config: Data<Reloadable<Config>>,
+    persister: Data<Persister>,
+) -> Result<HttpResponse, AppError> {
+    let CurrentUser { user_id, user_name } = user;
+    let config = &config.get().guestbook;
+    let message = body.message.trim();
+    if message.is_empty() || message.chars().count() > config.max_chars {
+        return Err(AppError::Validation(format!(
+            "Message must be 1 to {} characters",
+            config.max_chars
+        )));
+    }
+    let status = match config.require_approval {
+        true => GuestbookStatus::Pending,
+        false => GuestbookStatus::Approved,
+    };
+
+    let posted = {
+        let mut guestbook = guestbook.lock_or_recover();
+        match guestbook.wait_secs(&user_id, config.interval_secs) {
+            Some(wait_secs) => Err(wait_secs),
+            None => Ok(guestbook.post(&user_id, &user_name, message, status)),
+        }
+    };
+    let entry_id = posted.map_err(AppError::TooManyRequests)?;
+    persister.mark(&[Dataset::Guestbook]);
+    info!("User {} left guestbook entry #{}.", user_id, entry_id);
+    Ok(HttpResponse::Ok().json(json!({ "entry_id": entry_id, "status": status })))
+}
+
+/// 행사장 화면에 띄우는 방명록 HTML을 만듭니다. 이름과 글은 이스케이프됩니다.
+pub fn guestbook_html(entries: &[&GuestbookEntry], refresh_secs: u64) -> String {
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html lang=\"ko\">\n<head><meta charset=\"utf-8\"><meta name=\"viewport\" content=\"width=device-width, initial-scale=1\"><meta http-equiv=\"refresh\" content=\"{refresh}\"><title>방명록</title>\n<style>body{{font-family:sans-serif;margin:2em}}li{{margin:.5em 0;font-size:1.4em}}</style></head>\n<body>\n<h1>방명록</h1>\n<ul>\n",
+        refresh = refresh_secs,
+    );
+    for entry in entries {
+        html.push_str(&format!(
+            "<li><strong>{}</strong> {}</li>\n",
+            escape_html(&entry.user_name),
+            escape_html(&entry.message)
+        ));
+    }
+    html.push_str("</ul>\n</body>\n</html>\n");
+    html
+}
+
+/// 승인된 방명록 글을 최근 글부터 보여주는 행사장 화면을 반환하는 비동기 함수입니다.
+///
+/// # Returns
+///
+/// 방명록 HTML이 200 OK 응답으로 반환됩니다.
+#[get("/guestbook")]
+pub async fn handle_guestbook_page(
+    guestbook: Data<Mutex<Guestbook>>,
+    config: Data<Reloadable<Config>>,
+) -> HttpResponse {
+    let config = &config.get().guestbook;
+    let html = guestbook_html(
+        &guestbook.lock_or_recover().approved(config.display_count),
+        config.refresh_secs,
+    );
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .insert_header(("Cache-Control", "no-cache"))
+        .body(html)
+}
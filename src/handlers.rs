@@ -0,0 +1,2478 @@
+use actix_web::{
+    error::{InternalError, JsonPayloadError},
+    get,
+    http::header::{HeaderValue, CACHE_CONTROL},
+    routes,
+    web::post,
+    web::resource,
+    web::route,
+    web::Bytes,
+    web::Data,
+    web::Json,
+    web::JsonConfig,
+    web::Query,
+    web::Redirect,
+    web::ServiceConfig,
+    HttpRequest, HttpResponse, Responder, ResponseError, Route,
+};
+use log::{error, info, warn};
+use serde::Deserialize;
+use std::{path::Path, sync::Mutex, time::Instant};
+use uuid::Uuid;
+
+use crate::achievements::{record_achievements, user_achievements};
+use crate::acme::handle_acme_challenge;
+use crate::announcement::{handle_announcement, inject, inject_announcement, parse_announce};
+use crate::assets::{inject_assets, AssetManifest, IMMUTABLE_CACHE};
+use crate::backup::{
+    download_snapshots, list_snapshots, read_snapshot, upload_snapshots, write_snapshot, Snapshot,
+    SNAPSHOT_FORMAT,
+};
+use crate::base_path::prefixed;
+use crate::booth::{booth_key, handle_booth, handle_kiosk, handle_kiosk_qr, is_valid_qr_token};
+use crate::bot::{handle_line, handle_telegram};
+use crate::bulk::handle_bulk;
+use crate::config::{Config, ServerConfig};
+use crate::consistency::{check, repair};
+use crate::cooldown::ScanCooldown;
+use crate::error::AppError;
+use crate::event::handle_event;
+use crate::export::{
+    anonymized_history_csv, feedback_csv, redemptions_csv, results_xlsx, users_csv, write_export,
+};
+use crate::features::{feature_states, is_enabled, require_feature, Feature};
+use crate::feedback::handle_feedback;
+use crate::geofence::{check_geofence, ClientLocation};
+use crate::graphql::{handle_graphql, handle_graphql_schema};
+use crate::guestbook::{guestbook_command, handle_guestbook_page, handle_guestbook_post};
+use crate::i18n;
+use crate::import::{import_users, read_import, users_mapping_csv};
+use crate::lock::MutexExt;
+use crate::logging::handle_logs;
+use crate::me::{handle_delete_me, handle_me};
+use crate::merge::{merge_users, parse_merge, preview_merge};
+use crate::metrics::{handle_metrics, Metrics};
+use crate::missions::handle_missions;
+use crate::nfc::handle_nfc_check;
+use crate::notifier::{Event, Notifier};
+use crate::page_cache::StampPageCache;
+use crate::partner::{handle_partner_stamp, parse_partner_create};
+use crate::persistence::{Dataset, Persister};
+use crate::progress::{leaderboard, record_completions, user_progress};
+use crate::push::{
+    generate_vapid_keys, handle_push_key, handle_push_subscribe, handle_push_unsubscribe,
+};
+use crate::report::{daily_report, write_report};
+use crate::retention::{purge_deleted, restore_user, soft_delete_user, PersonalData};
+use crate::route_order::required_before;
+use crate::session::CurrentUser;
+use crate::share::{handle_share_image, handle_share_page};
+use crate::short_link::{handle_short_link, short_command};
+use crate::staff::{handle_redeem, handle_revoke, handle_staff_user, reset_progress, revoke_stamp};
+use crate::staging::inject_banner;
+use crate::state::{
+    AppState, AuditRecord, Command, CompletionList, Coordinates, Reloadable, Stamp, StampHistory,
+    StampIdList, StampInfo, StampUserInfo, TeamList, TourStatus, UndoAction, User, UserList,
+    UserName, UserStampList,
+};
+use crate::stats::{handle_public_stats, handle_timeseries};
+use crate::storage::{
+    html_file_name, html_template, is_binary, path, read_file, reload_state, resource_path,
+    safe_resource_path, stream_file, to_database, DatabaseFormat,
+};
+use crate::sync::{handle_sync, SYNC_BODY_LIMIT};
+use crate::teams::{
+    create_team, handle_team_leaderboard, handle_team_progress, record_team_completions,
+};
+use crate::template::{escape_html, render};
+use crate::tour::{handle_tour_ended, is_closed};
+use crate::undo::{capture_progress, undo_last};
+use crate::venue_map::handle_map;
+
+/// 메인 폼 요청을 처리하는 비동기 함수입니다. 'index.html' 파일을 읽어와서
+/// 200 OK 응답으로 반환합니다.
+///
+/// # Returns
+///
+/// 성공적으로 'index.html' 파일을 읽은 경우, 해당 파일의 내용을 담은 200 OK 응답이 반환됩니다.
+/// 파일이 텍스트 파일이 아닌 경우 `AppError::Template`(500 응답)이 반환됩니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[actix_web::main]
+/// async fn main() {
+///     // Actix-web 앱 생성 및 라우터 등록
+///     let app = App::new().service(index);
+///     // HTTP 서버 생성 및 실행
+///     HttpServer::new(|| {
+///         app.clone()
+///     })
+///     .bind("127.0.0.1:8080").unwrap()
+///     .run()
+///     .await
+///     .unwrap();
+/// }
+/// ```
+#[get("/")]
+pub async fn index(req: HttpRequest) -> Result<HttpResponse, AppError> {
+    // 'index.html' 파일을 읽어 200 OK 응답과 파일 내용 반환
+    let index = html_template("index.html").await?;
+    Ok(HttpResponse::Ok().body(inject(&req, index)))
+}
+
+/// 404 Not Found 응답을 처리하는 비동기 함수입니다. 'error404.html' 파일을 읽어와서
+/// 404 Not Found 응답으로 반환합니다.
+///
+/// # Returns
+///
+/// 'error404.html' 파일을 읽은 경우, 해당 파일의 내용을 담은 404 Not Found 응답이 반환됩니다.
+/// 파일이 존재하지 않거나 읽기에 실패한 경우 빈 본문의 404 Not Found 응답이 반환됩니다. (`AppError::NotFound` 참고)
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[actix_web::main]
+/// async fn main() {
+///     // Actix-web 앱 생성 및 라우터 등록
+///     let app = App::new().default_service(route().to(handle_404));
+///     // HTTP 서버 생성 및 실행
+///     HttpServer::new(|| {
+///         app.clone()
+///     })
+///     .bind("127.0.0.1:8080").unwrap()
+///     .run()
+///     .await
+///     .unwrap();
+/// }
+/// ```
+pub async fn handle_404() -> HttpResponse {
+    // 404 Not Found 응답과 'error404.html' 파일 내용 반환
+    AppError::NotFound.error_response()
+}
+
+/// 401 Unauthorized 응답을 처리하는 비동기 함수입니다. 'error401.html' 파일을 읽어와서
+/// 401 Unauthorized 응답으로 반환합니다.
+///
+/// # Returns
+///
+/// 'error401.html' 파일을 읽은 경우, 해당 파일의 내용을 담은 401 Unauthorized 응답이 반환됩니다.
+/// 파일이 존재하지 않거나 읽기에 실패한 경우 빈 본문의 401 Unauthorized 응답이 반환됩니다. (`AppError::Unauthorized` 참고)
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[actix_web::main]
+/// async fn main() {
+///     // Actix-web 앱 생성 및 라우터 등록
+///     let app = App::new().default_service(route().to(handle_401));
+///     // HTTP 서버 생성 및 실행
+///     HttpServer::new(|| {
+///         app.clone()
+///     })
+///     .bind("127.0.0.1:8080").unwrap()
+///     .run()
+///     .await
+///     .unwrap();
+/// }
+/// ```
+pub async fn handle_401() -> HttpResponse {
+    // 401 Unauthorized 응답과 'error401.html' 파일 내용 반환
+    AppError::Unauthorized.error_response()
+}
+
+/// 405 Method Not Allowed 응답을 처리하는 비동기 함수입니다. 'error405.html' 파일을 읽어와서
+/// 허용하는 메서드를 담은 `Allow` 헤더와 함께 반환합니다.
+///
+/// # Arguments
+///
+/// * `allow` - 요청한 경로에서 허용하는 메서드 목록입니다. (예: `"GET"`, `"GET, POST"`)
+///
+/// # Returns
+///
+/// 'error405.html' 파일의 내용을 담은 405 Method Not Allowed 응답이 반환됩니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let app = App::new().service(resource("/check").default_service(route().to(|| handle_405("GET"))));
+/// ```
+pub async fn handle_405(allow: &'static str) -> HttpResponse {
+    // 405 Method Not Allowed 응답과 'error405.html' 파일 내용 반환
+    AppError::MethodNotAllowed(allow).error_response()
+}
+
+/// 429 Too Many Requests 응답을 처리하는 비동기 함수입니다. 'error429.html' 파일을 읽어와서
+/// 다시 시도할 수 있을 때까지 남은 시간을 담은 `Retry-After` 헤더와 함께 반환합니다.
+///
+/// # Arguments
+///
+/// * `retry_after_secs` - 다시 시도할 수 있을 때까지 남은 시간(초)입니다.
+///
+/// # Returns
+///
+/// 'error429.html' 파일의 내용을 담은 429 Too Many Requests 응답이 반환됩니다.
+pub async fn handle_429(retry_after_secs: u64) -> HttpResponse {
+    // 429 Too Many Requests 응답과 'error429.html' 파일 내용 반환
+    AppError::TooManyRequests(retry_after_secs).error_response()
+}
+
+/// 503 Service Unavailable 응답을 처리하는 비동기 함수입니다. 'error503.html' 파일을 읽어와서
+/// 잠시 후 다시 시도하도록 `Retry-After` 헤더와 함께 반환합니다.
+///
+/// # Returns
+///
+/// 'error503.html' 파일의 내용을 담은 503 Service Unavailable 응답이 반환됩니다.
+pub async fn handle_503() -> HttpResponse {
+    // 503 Service Unavailable 응답과 'error503.html' 파일 내용 반환
+    AppError::Unavailable.error_response()
+}
+
+/// 메서드가 맞지 않는 요청에 405 응답을 보내는 라우트를 생성합니다. `resource`의 `default_service`로 사용합니다.
+fn method_not_allowed(allow: &'static str) -> Route {
+    route().to(move || handle_405(allow))
+}
+
+/// 동적 페이지 요청을 처리하는 비동기 함수입니다. 요청된 폴더 및 파일명을 사용하여 파일을 읽어와서
+/// HTTP 응답으로 반환합니다. `/img/icons/star.png`처럼 하위 폴더가 여러 단계인 경로도 처리합니다.
+///
+/// # Arguments
+///
+/// * `req` - `HttpRequest` 객체로, 동적 페이지 요청에 대한 정보를 포함합니다.
+///
+/// # Returns
+///
+/// 텍스트 파일이나 바이너리 파일을 읽을경우, 해당 파일의 내용을 담은 200 OK 응답이 반환됩니다.
+/// 이미지나 글꼴 같은 바이너리 파일은 스트리밍으로 전송됩니다.
+/// 파일이 존재하지 않거나 읽기에 실패한 경우 404 Not Found 응답이 반환됩니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[actix_web::main]
+/// async fn main() {
+///     // Actix-web 앱 생성 및 라우터 등록
+///     let app = App::new().service(handle_req);
+///     // HTTP 서버 생성 및 실행
+///     HttpServer::new(|| {
+///         app.clone()
+///     })
+///     .bind("127.0.0.1:8080").unwrap()
+///     .run()
+///     .await
+///     .unwrap();
+/// }
+/// ```
+#[get("/{folder}/{file:.*}")]
+pub async fn handle_req(req: HttpRequest) -> Result<HttpResponse, AppError> {
+    // 요청된 폴더 및 파일명을 추출
+    let folder = req.match_info().query("folder");
+    let file = req.match_info().query("file");
+
+    // 지문이 붙은 파일 이름이면 원래 파일을 오래 캐시하도록 전송
+    let original = req
+        .app_data::<Data<Reloadable<AssetManifest>>>()
+        .and_then(|manifest| {
+            manifest
+                .get()
+                .original(&format!("{}/{}", folder, file))
+                .and_then(|original| original.split_once('/'))
+                .map(|(_, original)| original.to_string())
+        });
+    let Some(original) = original else {
+        return serve_file(&req, folder, file).await;
+    };
+    let mut response = serve_file(&req, folder, &original).await?;
+    response
+        .headers_mut()
+        .insert(CACHE_CONTROL, HeaderValue::from_static(IMMUTABLE_CACHE));
+    Ok(response)
+}
+
+/// `resources/{folder}/{file}` 파일을 읽어 응답을 생성하는 비동기 함수입니다.
+/// `file`은 `icons/star.png`처럼 하위 폴더를 포함할 수 있습니다.
+async fn serve_file(req: &HttpRequest, folder: &str, file: &str) -> Result<HttpResponse, AppError> {
+    // resources 밖이나 존재하지 않는 파일은 404 응답
+    let file_path =
+        safe_resource_path(&format!("{}/{}", folder, file)).ok_or(AppError::NotFound)?;
+
+    // 바이너리 파일은 메모리에 모두 읽지 않고 스트리밍으로 전송 (확인 직후 지워진 파일은 404 응답)
+    if is_binary(file) {
+        let body = stream_file(&file_path)
+            .await
+            .map_err(|_| AppError::NotFound)?;
+        return Ok(HttpResponse::Ok().body(body));
+    }
+
+    // read_file 함수를 사용하여 파일 읽기 시도
+    Ok(match read_file(&file_path).await {
+        Ok(result) => HttpResponse::Ok().body(inject(req, result)), // 파일이 텍스트 파일일일경우 200 OK 응답과 파일 내용 반환
+        Err(error) => HttpResponse::Ok().body(error), // 바이너리 파일일시 200 OK 응답과 바이너리 파일 전송
+    })
+}
+
+/// `/check` 요청의 쿼리 문자열입니다.
+#[derive(Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct CheckQuery {
+    /// 스템프 ID입니다.
+    pub s: String,
+    /// 부스 화면의 QR 코드에 담긴 토큰입니다.
+    pub t: Option<String>,
+    /// PWA가 붙이는 방문객 위치의 위도입니다.
+    pub lat: Option<String>,
+    /// PWA가 붙이는 방문객 위치의 경도입니다.
+    pub lng: Option<String>,
+    /// PWA가 붙이는 방문객 위치의 오차 반경(미터)입니다.
+    pub acc: Option<String>,
+}
+
+impl CheckQuery {
+    /// 쿼리에 담긴 방문객 위치를 반환합니다. 위도나 경도가 없거나 숫자가 아니면 `None`을 반환하며,
+    /// 오차 반경이 없으면 0으로 봅니다.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use gj_stamptour::handlers::parse_check_query;
+    ///
+    /// let location = parse_check_query("s=a&lat=35.15&lng=126.85&acc=12").location().unwrap();
+    /// assert_eq!(location.coordinates.latitude, 35.15);
+    /// assert_eq!(location.accuracy_meters, 12.0);
+    /// assert!(parse_check_query("s=a&lat=35.15").location().is_none());
+    /// assert!(parse_check_query("s=a&lat=NaN&lng=126.85").location().is_none());
+    /// ```
+    pub fn location(&self) -> Option<ClientLocation> {
+        let number = |value: &Option<String>| {
+            value
+                .as_deref()
+                .and_then(|value| value.parse::<f64>().ok())
+                .filter(|value| value.is_finite())
+        };
+        let (latitude, longitude) = (number(&self.lat)?, number(&self.lng)?);
+        if latitude.abs() > 90.0 || longitude.abs() > 180.0 {
+            return None;
+        }
+        Some(ClientLocation {
+            coordinates: Coordinates {
+                latitude,
+                longitude,
+            },
+            accuracy_meters: number(&self.acc).unwrap_or(0.0),
+        })
+    }
+}
+
+/// `/check` 요청의 쿼리 문자열에서 스템프 ID와 QR 토큰을 꺼내는 함수입니다.
+/// 매개변수 순서와 상관없이 읽으며, 형식이 잘못된 쿼리는 빈 스템프 ID로 취급합니다.
+///
+/// # Example
+///
+/// ```rust
+/// use gj_stamptour::handlers::parse_check_query;
+///
+/// let query = parse_check_query("s=a&t=123");
+/// assert_eq!(query.s, "a");
+/// assert_eq!(query.t.as_deref(), Some("123"));
+/// assert_eq!(parse_check_query("lists=a").s, "");
+/// ```
+pub fn parse_check_query(query: &str) -> CheckQuery {
+    Query::<CheckQuery>::from_query(query)
+        .map(Query::into_inner)
+        .unwrap_or_default()
+}
+
+/// 스템프 확인 및 찍기 요청을 처리하는 비동기 함수입니다. 유저의 쿠키를 확인하고,
+/// 유저가 등록된 사용자인지, 스템프 ID가 유효한지 확인한 후, 유저의 스템프를 갱신합니다.
+///
+/// # Arguments
+///
+/// * `req` - `HttpRequest` 객체로, 유저의 쿠키와 스템프 ID를 포함합니다.
+/// * `stamp_id_list` - 유효한 스템프 ID 정보를 관리하는 `StampIdList`에 대한 `Data<Reloadable<StampIdList>>`입니다.
+/// * `user_stamp_list` - 유저의 스템프 정보를 관리하는 `UserStampList`에 대한 `Data<Mutex<UserStampList>>`입니다.
+/// * `stamp_history` - 발급 수량 확인에 사용할 `StampHistory`에 대한 `Data<StampHistory>`입니다.
+/// * `scan_cooldown` - 유저별 마지막 확인 시각을 기억하는 `ScanCooldown`에 대한 `Data<ScanCooldown>`입니다.
+///
+/// # Returns
+///
+/// 유저의 쿠키 및 스템프 ID가 유효한 경우, 유저의 스템프를 갱신하고 임시적인 리다이렉션(307)을 반환합니다.
+/// 유저의 쿠키가 없거나, 등록된 사용자가 아닌 경우, 유효한 스템프 ID가 아닌 경우, 같이 리다이렉션을 반환합니다.
+/// 스템프의 `maxIssued` 수량이 모두 발급된 경우 품절 페이지를, 투어가 종료된 경우 종료 페이지를 반환합니다.
+/// `scan_cooldown_secs` 안에 다시 확인한 경우 429 Too Many Requests 응답을 반환합니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[actix_web::main]
+/// async fn main() {
+///     // Actix-web 앱 생성 및 라우터 등록
+///     let app = App::new().service(handle_check);
+///     // HTTP 서버 생성 및 실행
+///     HttpServer::new(|| {
+///         app.clone()
+///     })
+///     .bind("127.0.0.1:8080").unwrap()
+///     .run()
+///     .await
+///     .unwrap();
+/// }
+/// ```
+#[get("/check")]
+#[allow(clippy::too_many_arguments)]
+pub async fn handle_check(
+    req: HttpRequest,
+    stamp_id_list: Data<Reloadable<StampIdList>>,
+    user_stamp_list: Data<Mutex<UserStampList>>,
+    stamp_history: Data<StampHistory>,
+    config: Data<Reloadable<Config>>,
+    tour_status: Data<Mutex<TourStatus>>,
+    metrics: Data<Metrics>,
+    scan_cooldown: Data<ScanCooldown>,
+) -> Result<HttpResponse, AppError> {
+    let config = config.get();
+    let stamp_id_list = stamp_id_list.get();
+    // 투어가 종료된 경우 종료 페이지 반환
+    if is_closed(&config, &tour_status.lock_or_recover(), chrono::Utc::now()) {
+        return handle_tour_ended(&req).await;
+    }
+
+    // 로그인하지 않았거나 등록되지 않은 유저인 경우 임시 리다이렉션 반환
+    let Some(CurrentUser { user_id, .. }) = CurrentUser::from_cookie(&req) else {
+        warn!("A user who is not logged in or not registered attempted to access with a stamp.");
+        return Ok(redirect_to_stamp(&req));
+    };
+
+    // URL에서 스템프 코드와 QR 토큰 추출
+    let query = parse_check_query(req.query_string());
+    let (code, token) = (&query.s, &query.t);
+
+    // 유효한 스템프 ID 또는 별칭 코드인 경우 유저의 스템프 정보 갱신
+    if let Some(stamp) = stamp_id_list.resolve(code) {
+        // QR 코드를 바꾸는 경우 부스 화면에 표시된 토큰인지 확인
+        if !is_valid_qr_token(
+            &config.booth,
+            &stamp.stampId,
+            token.as_deref(),
+            chrono::Utc::now(),
+        ) {
+            warn!(
+                "User {} used an expired QR code for stamp {}.",
+                user_id, stamp.stampId
+            );
+            return Ok(redirect_to_stamp(&req));
+        }
+
+        // 위치 확인을 켠 경우 부스에서 먼 곳의 확인 거절 (관리자가 기능을 끄면 확인하지 않음)
+        let geofencing = is_enabled(&config, &tour_status.lock_or_recover(), Feature::Geofencing);
+        let location_check = match geofencing {
+            true => check_geofence(&config.geofence, stamp, query.location()),
+            false => Ok(()),
+        };
+        if let Err(e) = location_check {
+            warn!(
+                "User {} was rejected for stamp {}: {}.",
+                user_id, stamp.stampId, e
+            );
+            return Ok(redirect_to_stamp(&req));
+        }
+
+        // 로그 출력: 별칭 코드는 어느 포스터인지 알 수 있도록 출력
+        if *code != stamp.stampId {
+            info!(
+                "User {} scanned stamp {} with alias {}.",
+                user_id, stamp.stampId, code
+            );
+        }
+        return accept_scan(
+            &req,
+            &user_id,
+            stamp,
+            &stamp_id_list,
+            &config,
+            &metrics,
+            &stamp_history,
+            &user_stamp_list,
+            &scan_cooldown,
+        )
+        .await;
+    }
+
+    // 아무 의미없는 랜덤 주소로 리다이렉션
+    Ok(redirect_to_stamp(&req))
+}
+
+/// 확인을 마친 스템프를 유저의 확인 대기 스템프로 등록하는 비동기 함수입니다.
+/// QR 코드(`/check`)와 NFC 태그(`/nfc/check`)가 함께 사용합니다.
+///
+/// # Arguments
+///
+/// * `user_id` - 스템프를 확인한 유저 ID입니다.
+/// * `stamp` - 확인한 스템프입니다.
+///
+/// # Returns
+///
+/// 등록한 경우 스템프 페이지로 가는 임시 리다이렉션(307)을 반환합니다.
+/// 방문 순서(`route`)상 먼저 가야 할 부스가 있으면 안내 페이지를, 발급 수량이 모두 소진된 경우 품절 페이지를,
+/// `scan_cooldown_secs` 안에 다시 확인한 경우 `AppError::TooManyRequests`(429 응답)를 반환합니다.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn accept_scan(
+    req: &HttpRequest,
+    user_id: &str,
+    stamp: &Stamp,
+    stamp_id_list: &StampIdList,
+    config: &Config,
+    metrics: &Metrics,
+    stamp_history: &StampHistory,
+    user_stamp_list: &Mutex<UserStampList>,
+    scan_cooldown: &ScanCooldown,
+) -> Result<HttpResponse, AppError> {
+    let stamp_id = &stamp.stampId;
+
+    // 방문 순서를 정한 경우 앞 구역에서 아직 찍지 않은 부스 안내
+    if !config.route.stages.is_empty() {
+        let collected = stamp_history.collected_by(user_id);
+        if let Some(required) = required_before(&config.route, stamp_id_list, stamp_id, &collected)
+        {
+            info!(
+                "User {} must visit stamp {} before stamp {}.",
+                user_id, required.stampId, stamp_id
+            );
+            return handle_route_required(req, required).await;
+        }
+    }
+
+    // 발급 수량이 모두 소진된 경우 품절 페이지 반환
+    if stamp.is_sold_out(issued_count(metrics, stamp_history, stamp_id)) {
+        info!("User {} requested sold out stamp {}.", user_id, stamp_id);
+        return handle_sold_out(req, stamp).await;
+    }
+
+    // 직전에 받아들인 확인 이후 최소 간격이 지나지 않은 경우 429 반환
+    if let Err(remaining) =
+        scan_cooldown.try_accept(user_id, config.scan_cooldown(), Instant::now())
+    {
+        warn!(
+            "User {} scanned stamp {} too quickly ({}ms left).",
+            user_id,
+            stamp_id,
+            remaining.as_millis()
+        );
+        return Err(AppError::TooManyRequests(
+            remaining.as_secs_f64().ceil() as u64
+        ));
+    }
+
+    // 로그 출력: 유저 ID 및 스템프 ID 정보 출력
+    info!("User {} requests stamp {}.", user_id, stamp_id);
+
+    // Mutex를 사용하여 유저의 스템프 정보 갱신
+    {
+        let mut user_stamp_list = metrics.lock("user_stamp_list", user_stamp_list);
+        user_stamp_list
+            .user_stamp_list
+            .insert(user_id.to_string(), stamp_id.clone());
+        // user_stamp_list는 여기서 더 이상 사용되지 않으므로 이 지점에서 뮤텍스 해제
+    }
+
+    // 아무 의미없는 랜덤 주소로 리다이렉션
+    Ok(redirect_to_stamp(req))
+}
+
+/// 아무 의미없는 랜덤 주소의 스템프 페이지로 보내는 임시 리다이렉션(307) 응답을 생성합니다.
+pub(crate) fn redirect_to_stamp(req: &HttpRequest) -> HttpResponse {
+    Redirect::to(prefixed(req, &format!("/stamp/?random={}", Uuid::new_v4())))
+        .temporary()
+        .respond_to(req)
+        .map_into_boxed_body()
+}
+
+/// 스템프가 지금까지 발급된 횟수를 반환합니다.
+fn issued_count(metrics: &Metrics, stamp_history: &StampHistory, stamp_id: &str) -> usize {
+    stamp_history
+        .with_shard(stamp_id, |shard| metrics.lock("stamp_history", shard).len())
+        .unwrap_or(0)
+}
+
+/// 발급 수량이 모두 소진된 스템프의 품절 페이지를 반환하는 비동기 함수입니다.
+/// 'soldout.html' 파일을 읽어 스템프 정보로 형식화합니다.
+///
+/// # Arguments
+///
+/// * `req` - 공지 삽입에 사용할 `HttpRequest`입니다.
+/// * `stamp` - 품절된 스템프입니다.
+///
+/// # Returns
+///
+/// 형식화된 품절 페이지를 담은 200 OK 응답이 반환됩니다.
+pub async fn handle_sold_out(req: &HttpRequest, stamp: &Stamp) -> Result<HttpResponse, AppError> {
+    let template = html_template("soldout.html").await?;
+    Ok(HttpResponse::Ok().body(inject(req, render_stamp(&template, stamp))))
+}
+
+/// 방문 순서상 먼저 가야 할 부스를 안내하는 비동기 함수입니다.
+/// 'route.html' 파일을 읽어 먼저 가야 할 스템프 정보로 형식화합니다.
+///
+/// # Arguments
+///
+/// * `req` - 공지 삽입에 사용할 `HttpRequest`입니다.
+/// * `stamp` - 먼저 방문해야 하는 스템프입니다.
+///
+/// # Returns
+///
+/// 형식화된 안내 페이지를 담은 200 OK 응답이 반환됩니다.
+pub async fn handle_route_required(
+    req: &HttpRequest,
+    stamp: &Stamp,
+) -> Result<HttpResponse, AppError> {
+    let template = html_template("route.html").await?;
+    Ok(HttpResponse::Ok().body(inject(req, render_stamp(&template, stamp))))
+}
+
+/// 스템프 찍기 요청을 처리하는 비동기 함수입니다. 유저의 쿠키를 확인하고, 해당 유저의 스템프를 가져온 후,
+/// 유저의 스템프를 갱신하고 형식화된 HTML을 반환합니다.
+///
+/// # Arguments
+///
+/// * `req` - `HttpRequest` 객체로, 요청에 대한 정보를 포함합니다.
+/// * `user` - 쿠키로 확인한 유저입니다. 쿠키가 없거나 등록되지 않은 유저는 핸들러 실행 전에 401 응답을 받습니다.
+/// * `user_stamp_list` - 유저의 스템프 정보를 관리하는 `UserStampList`에 대한 `Data<Mutex<UserStampList>>`입니다.
+/// * `stamp_id_list` - 스템프 페이지 형식화에 사용할 `StampIdList`에 대한 `Data<Reloadable<StampIdList>>`입니다.
+/// * `config` - 완주 등급이 정의된 `Config`에 대한 `Data<Reloadable<Config>>`입니다.
+/// * `completions` - 유저별 완주 기록을 관리하는 `CompletionList`에 대한 `Data<Mutex<CompletionList>>`입니다.
+/// * `teams` - 팀 완주 등급 기록에 사용할 `TeamList`에 대한 `Data<Mutex<TeamList>>`입니다.
+///
+/// # Returns
+///
+/// 유저의 스템프를 성공적으로 찍은 경우, 해당 스템프를 형식화한 HTML과 함께 200 OK 응답이 반환됩니다.
+/// 유저의 쿠키가 없거나 스템프 url이 틀린 경우, 스템프를 찾지 못한 경우 401 Unauthorized 또는 404 Not Found 응답이 반환됩니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[actix_web::main]
+/// async fn main() {
+///     // Actix-web 앱 생성 및 라우터 등록
+///     let app = App::new().service(handle_stamp);
+///     // HTTP 서버 생성 및 실행
+///     HttpServer::new(|| {
+///         app.clone()
+///     })
+///     .bind("127.0.0.1:8080").unwrap()
+///     .run()
+///     .await
+///     .unwrap();
+/// }
+/// ```
+#[get("/stamp/")]
+#[allow(clippy::too_many_arguments)]
+pub async fn handle_stamp(
+    req: HttpRequest,
+    user: CurrentUser,
+    user_stamp_list: Data<Mutex<UserStampList>>,
+    user_history: Data<StampHistory>,
+    stamp_id_list: Data<Reloadable<StampIdList>>,
+    config: Data<Reloadable<Config>>,
+    completions: Data<Mutex<CompletionList>>,
+    teams: Data<Mutex<TeamList>>,
+    tour_status: Data<Mutex<TourStatus>>,
+    notifier: Data<Notifier>,
+    metrics: Data<Metrics>,
+    persister: Data<Persister>,
+    stamp_pages: Data<StampPageCache>,
+) -> Result<HttpResponse, AppError> {
+    let config = config.get();
+    let stamp_id_list = stamp_id_list.get();
+    // 확인 이후 투어가 종료된 경우 종료 페이지 반환
+    if is_closed(&config, &tour_status.lock_or_recover(), chrono::Utc::now()) {
+        return handle_tour_ended(&req).await;
+    }
+
+    let user_id = user.user_id.as_str();
+
+    // 유저의 확인 대기 스템프를 꺼냄 (한 번의 락으로 확인과 삭제를 함께 처리)
+    let stamp_id = metrics
+        .lock("user_stamp_list", &user_stamp_list)
+        .user_stamp_list
+        .remove(user_id);
+
+    // 확인 대기 중인 스템프가 없는 경우 401 반환
+    let Some(stamp_id) = stamp_id else {
+        warn!(
+            "User {} attempted an unacceptable access to the stamp.",
+            user_id
+        );
+        return Err(AppError::Unauthorized); // 확인 대기 중인 스템프가 없을 경우 401 Unauthorized 응답 전송
+    };
+
+    let user_name = user.user_name;
+    let timestamp = chrono::prelude::Utc::now().to_string();
+    // 기록 샤드의 읽기 가드를 들고 다른 락을 잡거나 기다리면 같은 샤드에 쓰는 작업과 교착될 수 있으므로
+    // 기록을 추가하는 이 클로저 안에서만 가드를 유지
+    let sold_out = user_history.with_shard(&stamp_id, |shard| {
+        // 같은 스템프의 기록만 잠그므로 다른 부스의 스템프 기록을 기다리지 않음
+        let mut entries = metrics.lock("stamp_history", shard);
+
+        match stamp_id_list.stamp_id_list.get(&stamp_id) {
+            // 확인 이후 다른 유저가 마지막 수량을 가져간 경우 기록하지 않음
+            Some(stamp) if stamp.is_sold_out(entries.len()) => Some(stamp),
+            _ => {
+                entries.push(StampUserInfo {
+                    user_id: user_id.to_string(),
+                    user_name,
+                    timestamp,
+                    seq: user_history.next_seq(),
+                });
+                None
+            }
+        }
+    });
+    // 확인 이후 스템프 목록을 다시 불러와 스템프가 사라진 경우 404 반환
+    let Some(sold_out) = sold_out else {
+        warn!(
+            "User {} sent a request for removed stamp {}.",
+            user_id, stamp_id
+        );
+        return Err(AppError::NotFound);
+    };
+
+    // 품절된 경우 품절 페이지 반환
+    if let Some(stamp) = sold_out {
+        info!("User {} missed sold out stamp {}.", user_id, stamp_id);
+        return handle_sold_out(&req, stamp).await;
+    }
+    persister.mark(&[
+        Dataset::StampStatus,
+        Dataset::CompletionStatus,
+        Dataset::TeamStatus,
+    ]);
+
+    // 새로 달성한 완주 등급과 업적 기록
+    let milestones = {
+        let mut completions = metrics.lock("completions", &completions);
+        let reached = record_completions(
+            &config,
+            &stamp_id_list,
+            &user_history,
+            &mut completions,
+            user_id,
+        );
+        for name in record_achievements(
+            &config,
+            &stamp_id_list,
+            &user_history,
+            &mut completions,
+            user_id,
+        ) {
+            info!("User {} earned achievement {}.", user_id, name);
+        }
+
+        let mut milestones = Vec::new();
+        for tier_name in reached {
+            info!("User {} reached completion tier {}.", user_id, tier_name);
+            let count = completions
+                .completions
+                .values()
+                .filter(|records| records.iter().any(|record| record.tier_name == tier_name))
+                .count();
+            if notifier.is_milestone(count) {
+                milestones.push(Event::CompletionMilestone { tier_name, count });
+            }
+        }
+        milestones
+    };
+    for event in milestones {
+        notifier.notify(event);
+    }
+
+    // 팀에 속한 유저인 경우 팀 완주 등급 기록
+    let team_code = metrics.lock("teams", &teams).team_of(user_id).cloned();
+    if let Some(team_code) = team_code {
+        let mut teams = metrics.lock("teams", &teams);
+        if let Some(team) = teams.teams.get_mut(&team_code) {
+            for tier_name in record_team_completions(&config, &stamp_id_list, &user_history, team) {
+                info!("Team {} reached completion tier {}.", team_code, tier_name);
+            }
+        }
+    }
+
+    // 로그 출력: 스템프 찍기 완료 메시지
+    info!(
+        "The stamp {} request for user {} has been completed.",
+        stamp_id, user_id
+    );
+
+    // 스템프를 찾은 경우 200 OK 응답과 형식화된 HTML 반환
+    if let Some(stamp) = stamp_id_list.stamp_id_list.get(&stamp_id) {
+        return Ok(HttpResponse::Ok().body(stamp_page(&req, &stamp_pages, stamp).await?));
+    }
+
+    // 스템프를 찾지 못한 경우 404 Not Found 응답 반환
+    warn!("User {} sent an invalid stamp request.", user_id);
+    Err(AppError::NotFound)
+}
+
+/// `revoke <유저 ID 또는 코드> <스템프 ID> <사유>` 관리자 명령의 인수를 나누는 함수입니다.
+///
+/// # Returns
+///
+/// 유저, 스템프 ID, 사유가 모두 있으면 `Some((유저, 스템프 ID, 사유))`, 하나라도 빠지면 `None`이 반환됩니다.
+///
+/// # Example
+///
+/// ```rust
+/// use gj_stamptour::handlers::parse_revoke;
+///
+/// assert_eq!(parse_revoke("u1 a 중복 발급"), Some(("u1", "a", "중복 발급")));
+/// assert_eq!(parse_revoke("u1 a"), None);
+/// ```
+pub fn parse_revoke(args: &str) -> Option<(&str, &str, &str)> {
+    let mut args = args.trim().splitn(3, ' ');
+    let (user, stamp_id, reason) = (args.next()?, args.next()?, args.next()?.trim());
+    (!stamp_id.is_empty() && !reason.is_empty()).then_some((user, stamp_id, reason))
+}
+
+/// `reset user <유저 ID 또는 코드> <사유>` 관리자 명령의 인수를 나누는 함수입니다.
+///
+/// # Returns
+///
+/// 유저와 사유가 모두 있으면 `Some((유저, 사유))`, 사유가 없으면 `None`이 반환됩니다.
+pub fn parse_reset_user(args: &str) -> Option<(&str, &str)> {
+    let (user, reason) = args.trim().split_once(' ')?;
+    let reason = reason.trim();
+    (!reason.is_empty()).then_some((user, reason))
+}
+
+/// 관리자 명령을 처리하는 비동기 함수입니다. 로컬에서 보낸 요청만 처리하며, 명령마다 `*_command` 함수를 실행합니다.
+///
+/// # Arguments
+///
+/// * `command` - 실행할 관리자 명령입니다.
+/// * `state` - 앱 데이터로 등록된 공유 상태입니다.
+///
+/// # Returns
+///
+/// 명령 결과를 담은 200 OK 응답이 반환되며, 알 수 없는 명령이면 결과는 `Command not found`입니다.
+/// 로컬이 아닌 곳에서 보낸 요청이면 401 Unauthorized 응답이 반환됩니다.
+pub async fn handle_admin(
+    command: Json<Command>,
+    state: AppState,
+    req: HttpRequest,
+) -> Result<HttpResponse, AppError> {
+    let ip = req.peer_addr().map(|addr| addr.ip());
+    if !ip.is_some_and(|ip| ip.is_loopback()) {
+        warn!(
+            "{} Unauthorized access to the Admin page has been identified in .",
+            ip.map(|ip| ip.to_string()).unwrap_or_default()
+        );
+        return Err(AppError::Unauthorized);
+    }
+
+    let output = admin_command(&command.command, &state, &req).await;
+    Ok(HttpResponse::Ok().json(Command {
+        command: "".to_string(),
+        output,
+    }))
+}
+
+/// 관리자 명령에 맞는 `*_command` 함수를 실행하고 결과 메시지를 반환합니다.
+async fn admin_command(command: &str, state: &AppState, req: &HttpRequest) -> String {
+    match command {
+        "stamp status" => stamp_status_command(state),
+        "save all" => save_all_command(state).await,
+        "backup" => backup_command(state),
+        "backups" => backups_command(state),
+        "download backups" => download_backups_command(state).await,
+        "export users" => export_users_command(state),
+        "export redemptions" => export_redemptions_command(state),
+        "export feedback" => export_feedback_command(state),
+        "export anonymized" => export_anonymized_command(state),
+        "export xlsx" => export_xlsx_command(state),
+        "deleted users" => deleted_users_command(state),
+        "purge deleted" => purge_deleted_command(state),
+        "undo" => undo_command(state),
+        "undo list" => undo_list_command(state),
+        "booth links" => booth_links_command(state, req),
+        "vapid keys" => vapid_keys_command(),
+        "partners" => partners_command(state),
+        "check state" => check_state_command(state, false),
+        "repair state" => check_state_command(state, true),
+        "reload" => reload_command(state),
+        "clear announcement" => clear_announcement_command(state),
+        "close tour" => tour_command(state, true),
+        "open tour" => tour_command(state, false),
+        "features" => features_command(state),
+        _ => {
+            // 인수를 받는 명령
+            if let Some(timestamp) = command.strip_prefix("restore ") {
+                restore_command(state, timestamp.trim())
+            } else if let Some(file_name) = command.strip_prefix("import users ") {
+                import_users_command(state, file_name.trim())
+            } else if let Some(args) = command.strip_prefix("revoke ") {
+                revoke_command(state, command, args)
+            } else if let Some(args) = command.strip_prefix("merge ") {
+                merge_command(state, args)
+            } else if let Some(args) = command.strip_prefix("reset user ") {
+                reset_user_command(state, command, args)
+            } else if let Some(args) = command.strip_prefix("delete user ") {
+                delete_user_command(state, command, args)
+            } else if let Some(user) = command.strip_prefix("undelete user ") {
+                undelete_user_command(state, user.trim())
+            } else if let Some(args) = command.strip_prefix("partner create ") {
+                partner_create_command(state, args).await
+            } else if let Some(name) = command.strip_prefix("partner revoke ") {
+                partner_revoke_command(state, name.trim()).await
+            } else if let Some(args) = command.strip_prefix("short ") {
+                // 인쇄용 QR 코드에 넣을 짧은 주소 발급, 폐기, 조회
+                let config = state.config.get();
+                short_command(
+                    args,
+                    &state.short_links,
+                    &state.stamp_list.get(),
+                    &state.persister,
+                    &config.base_path,
+                )
+            } else if let Some(args) = command.strip_prefix("guestbook ") {
+                // 행사장 화면에 띄우기 전 방명록 글 검토 (승인 대기 목록, 승인, 숨기기)
+                guestbook_command(args, &state.guestbook, &state.persister)
+            } else if let Some(args) = command.strip_prefix("announce ") {
+                announce_command(state, args)
+            } else if let Some(date) = command.strip_prefix("daily report") {
+                daily_report_command(state, date.trim())
+            } else if let Some(args) = command.strip_prefix("feature ") {
+                feature_command(state, args)
+            } else {
+                "Command not found".to_string()
+            }
+        }
+    }
+}
+
+fn stamp_status_command(state: &AppState) -> String {
+    info!("Database lookup request : stamp status");
+    state.persister.mark(&[Dataset::StampStatus]);
+    format!("{:?}", state.stamp_history.to_map())
+}
+
+async fn save_all_command(state: &AppState) -> String {
+    // 저장 작업자가 모든 파일을 저장할 때까지 기다림 (실패한 파일은 작업자가 운영진에게 알림)
+    match state.persister.flush().await {
+        Ok(()) => "All databases saved".to_string(),
+        Err(failed) => format!("Database save failed: {}", failed.join(", ")),
+    }
+}
+
+fn backup_command(state: &AppState) -> String {
+    // 완주 기록 등을 되돌릴 수 있도록 모든 데이터베이스를 같은 시각의 스냅샷으로 저장
+    let config = state.config.get();
+    let files = database_files(state, config.persistence.format);
+    match write_snapshot(&config.backup, &files, chrono::Local::now()) {
+        Ok((saved, removed)) => {
+            let uploading = config.backup.s3.is_some();
+            if let Some(s3) = config.backup.s3.clone() {
+                upload_snapshots(s3, saved.clone());
+            }
+            format!(
+                "Backup saved ({} files, {} old snapshots removed){}",
+                saved.len(),
+                removed,
+                if uploading { ", uploading to S3" } else { "" }
+            )
+        }
+        Err(e) => {
+            error!("Backup failed: {}", e);
+            state.notifier.notify(Event::SaveFailed {
+                file_name: "backup".to_string(),
+            });
+            format!("Backup failed: {}", e)
+        }
+    }
+}
+
+fn backups_command(state: &AppState) -> String {
+    let timestamps = list_snapshots(Path::new(&state.config.get().backup.dir), "stamp_status");
+    if timestamps.is_empty() {
+        "No backups".to_string()
+    } else {
+        timestamps.join("\n")
+    }
+}
+
+async fn download_backups_command(state: &AppState) -> String {
+    // S3에 올린 스냅샷을 내려받아 `backups`와 `restore` 명령으로 사용할 수 있도록 함
+    let config = state.config.get();
+    match &config.backup.s3 {
+        None => "S3 is not configured".to_string(),
+        Some(_) => match download_snapshots(&config.backup).await {
+            Ok(downloaded) => format!("Downloaded {} backup files from S3", downloaded.len()),
+            Err(e) => {
+                error!("Backup download failed: {}", e);
+                format!("Backup download failed: {}", e)
+            }
+        },
+    }
+}
+
+fn restore_command(state: &AppState, timestamp: &str) -> String {
+    // 스냅샷을 먼저 읽은 뒤 현재 상태를 따로 저장하므로, 같은 분에 만든 스냅샷을 복원해도 안전함
+    let config = state.config.get();
+    let snapshot = match read_snapshot(&config.backup, timestamp) {
+        Ok(snapshot) => snapshot,
+        Err(e) => return format!("Restore failed: {}", e),
+    };
+    let files = database_files(state, config.persistence.format);
+    let now = chrono::Local::now();
+    if let Err(e) = write_snapshot(&config.backup, &files, now) {
+        return format!("Restore aborted, could not save current state: {}", e);
+    }
+    restore_snapshot(snapshot, state, timestamp);
+    state.persister.mark(&[
+        Dataset::StampStatus,
+        Dataset::UserStatus,
+        Dataset::CompletionStatus,
+        Dataset::TeamStatus,
+        Dataset::AuditLog,
+    ]);
+    format!(
+        "Restored snapshot {} (previous state saved as {})",
+        timestamp,
+        now.format(SNAPSHOT_FORMAT)
+    )
+}
+
+fn export_users_command(state: &AppState) -> String {
+    let config = state.config.get();
+    let csv = users_csv(
+        &config,
+        &state.stamp_list.get(),
+        &state.stamp_history,
+        &state.user_list.lock_or_recover(),
+    );
+    match csv.map(|csv| write_export("users.csv", csv.as_bytes(), config.backup.s3.as_ref())) {
+        Ok(Ok(file_path)) => format!("Users exported to {}", file_path.display()),
+        _ => "User export failed".to_string(),
+    }
+}
+
+fn export_redemptions_command(state: &AppState) -> String {
+    // 협력사 보고용 경품 수령 기록
+    let config = state.config.get();
+    let csv = redemptions_csv(
+        &state.completions.lock_or_recover(),
+        &state.user_list.lock_or_recover(),
+    );
+    match csv.map(|csv| write_export("redemptions.csv", csv.as_bytes(), config.backup.s3.as_ref()))
+    {
+        Ok(Ok(file_path)) => format!("Redemptions exported to {}", file_path.display()),
+        _ => "Redemption export failed".to_string(),
+    }
+}
+
+fn export_feedback_command(state: &AppState) -> String {
+    let config = state.config.get();
+    let csv = feedback_csv(&state.feedback.lock_or_recover());
+    match csv.map(|csv| write_export("feedback.csv", csv.as_bytes(), config.backup.s3.as_ref())) {
+        Ok(Ok(file_path)) => format!("Feedback exported to {}", file_path.display()),
+        _ => "Feedback export failed".to_string(),
+    }
+}
+
+fn export_anonymized_command(state: &AppState) -> String {
+    // 이름은 제외하고 유저 ID는 해싱하여 방문 흐름 분석용 자료로 내보냄
+    let config = state.config.get();
+    let salt = config.export_salt.clone().unwrap_or_else(|| {
+        let mut salt = [0u8; 16];
+        openssl::rand::rand_bytes(&mut salt).unwrap();
+        salt.iter().map(|byte| format!("{:02x}", byte)).collect()
+    });
+    let csv = anonymized_history_csv(&state.stamp_history, &salt);
+    match csv.map(|csv| {
+        write_export(
+            "anonymized_history.csv",
+            csv.as_bytes(),
+            config.backup.s3.as_ref(),
+        )
+    }) {
+        Ok(Ok(file_path)) => format!("Anonymized history exported to {}", file_path.display()),
+        _ => "Anonymized export failed".to_string(),
+    }
+}
+
+fn export_xlsx_command(state: &AppState) -> String {
+    let config = state.config.get();
+    let xlsx = results_xlsx(
+        &config,
+        &state.stamp_list.get(),
+        &state.stamp_history,
+        &state.user_list.lock_or_recover(),
+        &state.completions.lock_or_recover(),
+        &state.teams.lock_or_recover(),
+    );
+    match xlsx.map(|xlsx| write_export("results.xlsx", &xlsx, config.backup.s3.as_ref())) {
+        Ok(Ok(file_path)) => format!("Results exported to {}", file_path.display()),
+        Ok(Err(_)) => "Results export failed".to_string(),
+        Err(e) => {
+            error!("Results export failed : {}", e);
+            "Results export failed".to_string()
+        }
+    }
+}
+
+fn import_users_command(state: &AppState, file_name: &str) -> String {
+    // "import users <파일>" 형식이며, resources/imports 폴더의 CSV를 읽음
+    let Some(source) = read_import(file_name) else {
+        return format!("Import file {} not found", file_name);
+    };
+    let imported = {
+        let mut user_list = state.user_list.lock_or_recover();
+        let mut teams = state.teams.lock_or_recover();
+        import_users(&source, &mut user_list, &mut teams)
+    };
+    state
+        .persister
+        .mark(&[Dataset::UserStatus, Dataset::TeamStatus]);
+    let mapping_name = format!("imported_{}", file_name);
+    match imported.and_then(|users| users_mapping_csv(&users).map(|mapping| (users.len(), mapping)))
+    {
+        Ok((count, mapping)) => {
+            info!("{} users imported from {}", count, file_name);
+            let config = state.config.get();
+            match write_export(&mapping_name, mapping.as_bytes(), config.backup.s3.as_ref()) {
+                Ok(file_path) => format!(
+                    "{} users imported, mapping saved to {}",
+                    count,
+                    file_path.display()
+                ),
+                Err(_) => format!("{} users imported, mapping save failed", count),
+            }
+        }
+        Err(e) => format!("User import failed : {}", e),
+    }
+}
+
+fn revoke_command(state: &AppState, command: &str, args: &str) -> String {
+    let Some((user, stamp_id, reason)) = parse_revoke(args) else {
+        return "Usage: revoke <user> <stamp_id> <reason>".to_string();
+    };
+    let Some(user_id) = state.user_list.lock_or_recover().resolve(user) else {
+        return format!("User {} not found", user);
+    };
+    let revoked = revoke_stamp(
+        &state.stamp_history,
+        &mut state.audit_log.lock_or_recover(),
+        &user_id,
+        stamp_id,
+        "admin",
+        reason,
+    );
+    match revoked {
+        Some(revoked) => {
+            let output = format!(
+                "Revoked stamp {} of {} recorded at {}",
+                stamp_id, user_id, revoked.timestamp
+            );
+            state.admin_history.lock_or_recover().push(
+                command,
+                UndoAction::RevokeStamp {
+                    stamp_id: stamp_id.to_string(),
+                    entry: revoked,
+                },
+            );
+            state.persister.mark(&[
+                Dataset::StampStatus,
+                Dataset::AuditLog,
+                Dataset::AdminHistory,
+            ]);
+            output
+        }
+        None => format!("User {} has no record for stamp {}", user_id, stamp_id),
+    }
+}
+
+fn merge_command(state: &AppState, args: &str) -> String {
+    // 다른 브라우저에서 중복 등록한 유저를 하나로 합침 (--dry-run은 바꾸지 않고 결과만 표시)
+    let Some((dry_run, target, duplicate)) = parse_merge(args) else {
+        return "Usage: merge [--dry-run] <keep_user> <duplicate_user>".to_string();
+    };
+    let mut user_list = state.user_list.lock_or_recover();
+    let (target, duplicate) = match (user_list.resolve(target), user_list.resolve(duplicate)) {
+        (None, _) => return format!("User {} not found", target),
+        (_, None) => return format!("User {} not found", duplicate),
+        (Some(target), Some(duplicate)) => (target, duplicate),
+    };
+    if dry_run {
+        return match preview_merge(
+            &user_list,
+            &state.stamp_history,
+            &state.teams.lock_or_recover(),
+            &target,
+            &duplicate,
+        ) {
+            Ok(report) => format!(
+                "Dry run: merging {} into {} would move {}",
+                duplicate,
+                target,
+                report.summary()
+            ),
+            Err(e) => e,
+        };
+    }
+
+    let mut user_stamp_list = state.user_stamp_list.lock_or_recover();
+    let mut completions = state.completions.lock_or_recover();
+    let mut teams = state.teams.lock_or_recover();
+    let mut audit_log = state.audit_log.lock_or_recover();
+    let merged = merge_users(
+        PersonalData {
+            user_list: &mut user_list,
+            user_stamp_list: &mut user_stamp_list,
+            stamp_history: &state.stamp_history,
+            completions: &mut completions,
+            teams: &mut teams,
+            audit_log: &mut audit_log,
+        },
+        &target,
+        &duplicate,
+        "admin",
+    );
+    match merged {
+        Ok(report) => {
+            // 합친 스템프로 새로 달성한 완주 등급 기록
+            record_completions(
+                &state.config.get(),
+                &state.stamp_list.get(),
+                &state.stamp_history,
+                &mut completions,
+                &target,
+            );
+            state.persister.mark(&[
+                Dataset::UserStatus,
+                Dataset::StampStatus,
+                Dataset::CompletionStatus,
+                Dataset::TeamStatus,
+                Dataset::AuditLog,
+            ]);
+            info!("Merged user {} into {}", duplicate, target);
+            format!(
+                "Merged {} into {}: moved {}",
+                duplicate,
+                target,
+                report.summary()
+            )
+        }
+        Err(e) => e,
+    }
+}
+
+fn reset_user_command(state: &AppState, command: &str, args: &str) -> String {
+    // 유저 등록은 유지
+    let Some((user, reason)) = parse_reset_user(args) else {
+        return "Usage: reset user <user> <reason>".to_string();
+    };
+    let Some(user_id) = state.user_list.lock_or_recover().resolve(user) else {
+        return format!("User {} not found", user);
+    };
+    let mut completions = state.completions.lock_or_recover();
+    let undo = capture_progress(&state.stamp_history, &completions, &user_id);
+    let removed = reset_progress(
+        &state.stamp_history,
+        &mut state.user_stamp_list.lock_or_recover(),
+        &mut completions,
+        &mut state.audit_log.lock_or_recover(),
+        &user_id,
+        "admin",
+        reason,
+    );
+    state.admin_history.lock_or_recover().push(command, undo);
+    state.persister.mark(&[
+        Dataset::StampStatus,
+        Dataset::CompletionStatus,
+        Dataset::AuditLog,
+        Dataset::AdminHistory,
+    ]);
+    format!("Reset user {} ({} stamps removed)", user_id, removed)
+}
+
+fn delete_user_command(state: &AppState, command: &str, args: &str) -> String {
+    // 실수로 지워도 되돌릴 수 있도록 기록을 보관한 채 삭제 (purge deleted로 영구 삭제)
+    let Some((user, reason)) = parse_reset_user(args) else {
+        return "Usage: delete user <user> <reason>".to_string();
+    };
+    let mut user_list = state.user_list.lock_or_recover();
+    let Some(user_id) = user_list.resolve(user) else {
+        return format!("User {} not found", user);
+    };
+    soft_delete_user(
+        PersonalData {
+            user_list: &mut user_list,
+            user_stamp_list: &mut state.user_stamp_list.lock_or_recover(),
+            stamp_history: &state.stamp_history,
+            completions: &mut state.completions.lock_or_recover(),
+            teams: &mut state.teams.lock_or_recover(),
+            audit_log: &mut state.audit_log.lock_or_recover(),
+        },
+        &user_id,
+        "admin",
+        reason,
+    );
+    state.admin_history.lock_or_recover().push(
+        command,
+        UndoAction::DeleteUser {
+            user_id: user_id.clone(),
+        },
+    );
+    state.persister.mark(&[
+        Dataset::UserStatus,
+        Dataset::StampStatus,
+        Dataset::CompletionStatus,
+        Dataset::TeamStatus,
+        Dataset::AuditLog,
+        Dataset::AdminHistory,
+    ]);
+    info!("Deleted user {}", user_id);
+    format!(
+        "Deleted user {} (undelete user {} to undo)",
+        user_id, user_id
+    )
+}
+
+fn undelete_user_command(state: &AppState, user: &str) -> String {
+    let mut user_list = state.user_list.lock_or_recover();
+    let restored = restore_user(
+        PersonalData {
+            user_list: &mut user_list,
+            user_stamp_list: &mut state.user_stamp_list.lock_or_recover(),
+            stamp_history: &state.stamp_history,
+            completions: &mut state.completions.lock_or_recover(),
+            teams: &mut state.teams.lock_or_recover(),
+            audit_log: &mut state.audit_log.lock_or_recover(),
+        },
+        user,
+        "admin",
+    );
+    match restored {
+        Some(user_id) => {
+            state.persister.mark(&[
+                Dataset::UserStatus,
+                Dataset::StampStatus,
+                Dataset::CompletionStatus,
+                Dataset::TeamStatus,
+                Dataset::AuditLog,
+            ]);
+            info!("Restored user {}", user_id);
+            format!(
+                "Restored user {} (code {})",
+                user_id,
+                user_list.code_of(&user_id).cloned().unwrap_or_default()
+            )
+        }
+        None => format!("Deleted user {} not found", user),
+    }
+}
+
+fn deleted_users_command(state: &AppState) -> String {
+    let user_list = state.user_list.lock_or_recover();
+    match user_list.deleted.is_empty() {
+        true => "No deleted users".to_string(),
+        false => user_list
+            .deleted
+            .iter()
+            .map(|(user_id, deleted)| {
+                format!(
+                    "{} ({} stamps) deleted at {}: {}",
+                    user_id,
+                    deleted.stamps.len(),
+                    deleted.deleted_at,
+                    deleted.reason
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n"),
+    }
+}
+
+fn purge_deleted_command(state: &AppState) -> String {
+    // 되돌릴 수 없으므로 행사가 끝난 뒤에 실행
+    let purged = purge_deleted(PersonalData {
+        user_list: &mut state.user_list.lock_or_recover(),
+        user_stamp_list: &mut state.user_stamp_list.lock_or_recover(),
+        stamp_history: &state.stamp_history,
+        completions: &mut state.completions.lock_or_recover(),
+        teams: &mut state.teams.lock_or_recover(),
+        audit_log: &mut state.audit_log.lock_or_recover(),
+    });
+    state.persister.mark(&[
+        Dataset::UserStatus,
+        Dataset::CompletionStatus,
+        Dataset::AuditLog,
+    ]);
+    info!("Purged {} deleted users", purged);
+    format!("Purged {} deleted users", purged)
+}
+
+fn undo_command(state: &AppState) -> String {
+    // 실수로 실행한 삭제, 스템프 취소, 초기화를 마지막 작업부터 되돌림
+    let undone = undo_last(
+        &mut state.admin_history.lock_or_recover(),
+        PersonalData {
+            user_list: &mut state.user_list.lock_or_recover(),
+            user_stamp_list: &mut state.user_stamp_list.lock_or_recover(),
+            stamp_history: &state.stamp_history,
+            completions: &mut state.completions.lock_or_recover(),
+            teams: &mut state.teams.lock_or_recover(),
+            audit_log: &mut state.audit_log.lock_or_recover(),
+        },
+        "admin",
+    );
+    state.persister.mark(&[
+        Dataset::UserStatus,
+        Dataset::StampStatus,
+        Dataset::CompletionStatus,
+        Dataset::TeamStatus,
+        Dataset::AuditLog,
+        Dataset::AdminHistory,
+    ]);
+    match undone {
+        Ok((undone, user_id)) => {
+            info!("Undid `{}` for user {}", undone, user_id);
+            format!("Undid `{}`", undone)
+        }
+        Err(e) => e,
+    }
+}
+
+fn undo_list_command(state: &AppState) -> String {
+    let admin_history = state.admin_history.lock_or_recover();
+    match admin_history.entries.is_empty() {
+        true => "Nothing to undo".to_string(),
+        false => admin_history
+            .entries
+            .iter()
+            .rev()
+            .map(|operation| format!("{} {}", operation.timestamp, operation.command))
+            .collect::<Vec<String>>()
+            .join("\n"),
+    }
+}
+
+fn booth_links_command(state: &AppState, req: &HttpRequest) -> String {
+    // 부스 운영자에게 나눠줄 부스별 현황 페이지와 키오스크 화면 주소
+    let Some(staff_token) = state.config.get().staff_token.clone() else {
+        return "Set staff_token in config.json to create booth links".to_string();
+    };
+    state
+        .stamp_list
+        .get()
+        .stamp_id_list
+        .keys()
+        .map(|stamp_id| {
+            let key = booth_key(&staff_token, stamp_id);
+            format!(
+                "{}: {} {}",
+                stamp_id,
+                prefixed(req, &format!("/staff/booth/{}?key={}", stamp_id, key)),
+                prefixed(req, &format!("/kiosk/{}?key={}", stamp_id, key))
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn vapid_keys_command() -> String {
+    // Web Push에 사용할 VAPID 키 쌍 생성 (config.json의 web_push.vapid_private_key에 설정)
+    let (private_key, public_key) = generate_vapid_keys();
+    format!(
+        "VAPID private key: {}\nVAPID public key: {}\nSet web_push.vapid_private_key in config.json.",
+        private_key, public_key
+    )
+}
+
+async fn partner_create_command(state: &AppState, args: &str) -> String {
+    // "partner create <이름> <스템프 ID,...>" 형식으로 협력사 API 키 발급
+    let (name, stamp_ids) = match parse_partner_create(args, &state.stamp_list.get()) {
+        Ok(parsed) => parsed,
+        Err(message) => return message,
+    };
+    let key = state
+        .partner_keys
+        .lock_or_recover()
+        .create(&name, stamp_ids.clone());
+    // 키는 한 번만 보여주므로 저장이 끝난 뒤에 응답
+    match state.persister.flush().await {
+        Ok(_) => {
+            info!("Partner key created for {}", name);
+            format!(
+                "Partner key for {} (stamps: {}): {}\nThe key is shown only once.",
+                name,
+                stamp_ids.into_iter().collect::<Vec<String>>().join(", "),
+                key
+            )
+        }
+        Err(_) => "Partner key save failed".to_string(),
+    }
+}
+
+async fn partner_revoke_command(state: &AppState, name: &str) -> String {
+    let revoked = state.partner_keys.lock_or_recover().revoke(name);
+    if !revoked {
+        format!("Partner {} not found", name)
+    } else if state.persister.flush().await.is_err() {
+        "Partner key save failed".to_string()
+    } else {
+        info!("Partner key revoked for {}", name);
+        format!("Partner key for {} revoked", name)
+    }
+}
+
+fn partners_command(state: &AppState) -> String {
+    let partner_keys = state.partner_keys.lock_or_recover();
+    if partner_keys.partners.is_empty() {
+        return "No partners".to_string();
+    }
+    partner_keys
+        .partners
+        .iter()
+        .map(|(name, partner)| {
+            format!(
+                "{}: {}{}",
+                name,
+                partner
+                    .stamp_ids
+                    .iter()
+                    .cloned()
+                    .collect::<Vec<String>>()
+                    .join(", "),
+                if partner.revoked_at.is_some() {
+                    " (revoked)"
+                } else {
+                    ""
+                }
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn check_state_command(state: &AppState, fix: bool) -> String {
+    // 유저 목록, 스템프 기록, 스템프 목록 사이의 참조 오류 검사 (repair는 수정까지)
+    let stamp_id_list = state.stamp_list.get();
+    let user_list = state.user_list.lock_or_recover();
+    let mut user_stamp_list = state.user_stamp_list.lock_or_recover();
+    let report = if fix {
+        let report = repair(
+            &stamp_id_list,
+            &user_list,
+            &state.stamp_history,
+            &mut user_stamp_list,
+        );
+        state.persister.mark(&[Dataset::StampStatus]);
+        report
+    } else {
+        check(
+            &stamp_id_list,
+            &user_list,
+            &state.stamp_history,
+            &user_stamp_list,
+        )
+    };
+    report.summary()
+}
+
+fn reload_command(state: &AppState) -> String {
+    // 설정 파일, 스템프 목록, 미션 목록, 정적 파일 대응표 다시 불러오기 (SIGHUP과 동일)
+    match reload_state(
+        &state.config,
+        &state.stamp_list,
+        &state.stamp_history,
+        &state.assets,
+        &state.stamp_pages,
+        &state.missions,
+    ) {
+        Ok(stamp_count) => {
+            state.notifier.notify(Event::StampReload { stamp_count });
+            format!("Reloaded config and {} stamps", stamp_count)
+        }
+        Err(e) => {
+            error!("Reload failed: {}", e);
+            format!("Reload failed: {}", e)
+        }
+    }
+}
+
+fn announce_command(state: &AppState, args: &str) -> String {
+    // "announce <분> <메시지>" 형식으로 공지 설정
+    match parse_announce(args, chrono::Utc::now()) {
+        Some(new_announcement) => {
+            info!("Announcement set : {:?}", new_announcement);
+            let output = format!("Announcement set until {}", new_announcement.expires_at);
+            *state.announcement.lock_or_recover() = Some(new_announcement);
+            state.persister.mark(&[Dataset::Announcement]);
+            output
+        }
+        None => "Usage: announce <minutes> <message>".to_string(),
+    }
+}
+
+fn clear_announcement_command(state: &AppState) -> String {
+    *state.announcement.lock_or_recover() = None;
+    state.persister.mark(&[Dataset::Announcement]);
+    "Announcement cleared".to_string()
+}
+
+fn daily_report_command(state: &AppState, date: &str) -> String {
+    // "daily report [YYYY-MM-DD]" 형식이며, 날짜가 없으면 오늘 요약을 생성
+    let date = match date {
+        "" => Ok(chrono::Local::now().date_naive()),
+        date => date.parse::<chrono::NaiveDate>(),
+    };
+    let Ok(date) = date else {
+        return "Usage: daily report [YYYY-MM-DD]".to_string();
+    };
+    let report = daily_report(
+        date,
+        &state.stamp_list.get(),
+        &state.stamp_history,
+        &state.user_list.lock_or_recover(),
+        &state.completions.lock_or_recover(),
+    );
+    match write_report(&report) {
+        Ok(file_path) => format!("Daily report saved to {}", file_path.display()),
+        Err(_) => "Daily report failed".to_string(),
+    }
+}
+
+fn tour_command(state: &AppState, closed: bool) -> String {
+    // 관리자가 직접 투어 운영 상태를 고정하며, 예약 종료 시각보다 우선합니다.
+    state.tour_status.lock_or_recover().closed = Some(closed);
+    state.persister.mark(&[Dataset::TourStatus]);
+    info!("Tour {} by admin", if closed { "closed" } else { "opened" });
+    if closed {
+        "Tour closed".to_string()
+    } else {
+        "Tour opened".to_string()
+    }
+}
+
+fn features_command(state: &AppState) -> String {
+    feature_states(&state.config.get(), &state.tour_status.lock_or_recover())
+        .into_iter()
+        .map(|(feature, enabled)| format!("{}: {}", feature, if enabled { "on" } else { "off" }))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn feature_command(state: &AppState, args: &str) -> String {
+    // "feature <이름> on|off|default" 형식이며, default는 관리자가 바꾼 값을 지우고 설정을 따릅니다.
+    let (name, value) = args.trim().split_once(' ').unwrap_or((args.trim(), ""));
+    match (Feature::parse(name), value.trim()) {
+        (Some(feature), value @ ("on" | "off")) => {
+            state
+                .tour_status
+                .lock_or_recover()
+                .features
+                .insert(feature, value == "on");
+            state.persister.mark(&[Dataset::TourStatus]);
+            info!("Feature {} turned {} by admin", feature, value);
+            format!("Feature {} turned {}", feature, value)
+        }
+        (Some(feature), "default") => {
+            let mut tour_status = state.tour_status.lock_or_recover();
+            tour_status.features.remove(&feature);
+            state.persister.mark(&[Dataset::TourStatus]);
+            let enabled = is_enabled(&state.config.get(), &tour_status, feature);
+            info!("Feature {} reset to config by admin", feature);
+            format!(
+                "Feature {} follows config ({})",
+                feature,
+                if enabled { "on" } else { "off" }
+            )
+        }
+        _ => "Usage: feature <leaderboard|missions|geofencing> <on|off|default>".to_string(),
+    }
+}
+
+/// 백업과 복원에 사용할 데이터베이스 파일 목록을 `format` 형식으로 직렬화하는 함수입니다.
+/// 암호화 키가 설정되어 있으면 스냅샷의 개인정보도 암호화됩니다.
+///
+/// # Returns
+///
+/// `(데이터베이스 파일 이름, 저장 형식의 내용)` 목록을 반환합니다. 직렬화에 실패한 파일은 제외됩니다.
+fn database_files(state: &AppState, format: DatabaseFormat) -> Vec<(&'static str, Vec<u8>)> {
+    [
+        (
+            "stamp_status",
+            to_database("stamp_status", &**state.stamp_history, format),
+        ),
+        (
+            "user_status",
+            to_database("user_status", &*state.user_list.lock_or_recover(), format),
+        ),
+        (
+            "completion_status",
+            to_database(
+                "completion_status",
+                &*state.completions.lock_or_recover(),
+                format,
+            ),
+        ),
+        (
+            "team_status",
+            to_database("team_status", &*state.teams.lock_or_recover(), format),
+        ),
+        (
+            "announcement",
+            to_database(
+                "announcement",
+                &*state.announcement.lock_or_recover(),
+                format,
+            ),
+        ),
+        (
+            "tour_status",
+            to_database("tour_status", &*state.tour_status.lock_or_recover(), format),
+        ),
+        (
+            "audit_log",
+            to_database("audit_log", &*state.audit_log.lock_or_recover(), format),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(file_name, contents)| contents.ok().map(|contents| (file_name, contents)))
+    .collect()
+}
+
+/// 스냅샷의 참가자 진행 상황을 현재 상태에 덮어쓰고 감사 기록에 남기는 함수입니다.
+/// 락은 유저 목록, 스템프 기록, 완주 기록, 팀 순서로 잡습니다.
+fn restore_snapshot(snapshot: Snapshot, state: &AppState, timestamp: &str) {
+    let mut user_list = state.user_list.lock_or_recover();
+    let mut completions = state.completions.lock_or_recover();
+    let mut teams = state.teams.lock_or_recover();
+
+    if let Some(snapshot_users) = snapshot.user_list {
+        *user_list = snapshot_users;
+    }
+    state.stamp_history.replace(snapshot.stamp_history);
+    if let Some(snapshot_completions) = snapshot.completions {
+        *completions = snapshot_completions;
+    }
+    if let Some(snapshot_teams) = snapshot.teams {
+        *teams = snapshot_teams;
+    }
+
+    state.audit_log.lock_or_recover().entries.push(AuditRecord {
+        action: "restore".to_string(),
+        user_id: String::new(),
+        stamp_id: None,
+        actor: "admin".to_string(),
+        reason: format!("snapshot {}", timestamp),
+        timestamp: chrono::prelude::Utc::now().to_string(),
+    });
+    warn!("State restored from snapshot {}", timestamp);
+}
+
+/// 유저의 진행 상황을 JSON으로 반환하는 비동기 함수입니다.
+///
+/// # Arguments
+///
+/// * `user` - 쿠키로 확인한 유저입니다.
+/// * `stamp_id_list` - 전체 스템프 목록인 `StampIdList`에 대한 `Data<Reloadable<StampIdList>>`입니다.
+/// * `stamp_history` - 스템프 기록을 관리하는 `StampHistory`에 대한 `Data<StampHistory>`입니다.
+/// * `config` - 완주 등급이 정의된 `Config`에 대한 `Data<Reloadable<Config>>`입니다.
+/// * `completions` - 달성한 업적과 완주 코드를 찾을 `CompletionList`에 대한 `Data<Mutex<CompletionList>>`입니다.
+///
+/// # Returns
+///
+/// 등록된 유저인 경우 모은 스템프와 완주 등급, 업적, 완주 코드를 담은 200 OK 응답이 반환됩니다.
+/// 쿠키가 없거나 등록되지 않은 유저인 경우 401 Unauthorized 응답이 반환됩니다.
+#[routes]
+#[get("/api/v1/progress")]
+#[get("/progress")]
+pub async fn handle_progress(
+    user: CurrentUser,
+    stamp_id_list: Data<Reloadable<StampIdList>>,
+    stamp_history: Data<StampHistory>,
+    config: Data<Reloadable<Config>>,
+    completions: Data<Mutex<CompletionList>>,
+) -> HttpResponse {
+    let config = config.get();
+    let stamp_id_list = stamp_id_list.get();
+    let mut progress = user_progress(
+        &config,
+        &stamp_id_list,
+        &stamp_history,
+        &user.user_id,
+        &user.user_name,
+    );
+    {
+        let completions = completions.lock_or_recover();
+        progress.achievements = user_achievements(&config, &completions, &user.user_id);
+        progress.completion_code = completions.codes.get(&user.user_id).cloned();
+        progress.redeemed = completions.redemption_of(&user.user_id).is_some();
+    }
+
+    HttpResponse::Ok().json(progress)
+}
+
+/// 완주 페이지를 처리하는 비동기 함수입니다. 'complete.html' 파일을 읽어 유저의 진행 상황으로 형식화합니다.
+///
+/// 템플릿에서는 `%USER_NAME%`, `%TIER_NAME%`, `%NEXT_TIER%`, `%REMAINING%`,
+/// `%COLLECTED_COUNT%`, `%TOTAL_COUNT%` 자리표시자를 사용할 수 있습니다.
+/// `%ACHIEVEMENTS%`는 달성한 업적마다 `<li>` 항목으로, `%COMPLETION_CODE%`는 경품 수령용 완주 코드로 바뀝니다.
+///
+/// # Returns
+///
+/// 등록된 유저인 경우 형식화된 완주 페이지가 담긴 200 OK 응답이 반환됩니다.
+/// 쿠키가 없거나 등록되지 않은 유저인 경우 401 Unauthorized 응답이 반환됩니다.
+#[get("/complete")]
+pub async fn handle_complete(
+    req: HttpRequest,
+    user: CurrentUser,
+    stamp_id_list: Data<Reloadable<StampIdList>>,
+    stamp_history: Data<StampHistory>,
+    config: Data<Reloadable<Config>>,
+    completions: Data<Mutex<CompletionList>>,
+) -> Result<HttpResponse, AppError> {
+    let config = config.get();
+    let stamp_id_list = stamp_id_list.get();
+    let progress = user_progress(
+        &config,
+        &stamp_id_list,
+        &stamp_history,
+        &user.user_id,
+        &user.user_name,
+    );
+    let next_tier = progress.next_tier.clone();
+    let (achievements, completion_code) = {
+        let completions = completions.lock_or_recover();
+        let achievements: String = user_achievements(&config, &completions, &user.user_id)
+            .iter()
+            .map(|achievement| format!("<li>{}</li>", escape_html(&achievement.name)))
+            .collect();
+        (
+            achievements,
+            completions
+                .codes
+                .get(&user.user_id)
+                .cloned()
+                .unwrap_or_default(),
+        )
+    };
+
+    let template = html_template("complete.html").await?;
+    Ok(HttpResponse::Ok().body(inject(
+        &req,
+        render(
+            &template,
+            &[
+                ("USER_NAME", &escape_html(&progress.user_name)),
+                ("TIER_NAME", &progress.tier.unwrap_or_default()),
+                (
+                    "NEXT_TIER",
+                    &next_tier
+                        .as_ref()
+                        .map(|next| next.tier_name.clone())
+                        .unwrap_or_default(),
+                ),
+                (
+                    "REMAINING",
+                    &next_tier.map_or(0, |next| next.remaining).to_string(),
+                ),
+                ("COLLECTED_COUNT", &progress.collected_count.to_string()),
+                ("TOTAL_COUNT", &progress.total_count.to_string()),
+                ("ACHIEVEMENTS", &achievements),
+                ("COMPLETION_CODE", &completion_code),
+            ],
+        ),
+    )))
+}
+
+#[derive(Deserialize)]
+pub struct LeaderboardQuery {
+    pub limit: Option<usize>,
+}
+
+/// 점수 순위표를 JSON으로 반환하는 비동기 함수입니다.
+///
+/// # Arguments
+///
+/// * `query` - 반환할 최대 인원(`limit`, 기본 10명, 최대 100명)을 담은 쿼리입니다.
+///
+/// # Returns
+///
+/// 순위, 이름, 점수, 모은 스템프 개수를 담은 `LeaderboardEntry` 목록이 200 OK 응답으로 반환됩니다.
+/// 순위표 기능(`features.leaderboard`)이 꺼져 있으면 503 응답이 반환됩니다.
+#[routes]
+#[get("/api/v1/leaderboard")]
+#[get("/api/leaderboard")]
+pub async fn handle_leaderboard(
+    query: Query<LeaderboardQuery>,
+    user_list: Data<Mutex<UserList>>,
+    stamp_id_list: Data<Reloadable<StampIdList>>,
+    stamp_history: Data<StampHistory>,
+    req: HttpRequest,
+) -> Result<HttpResponse, AppError> {
+    require_feature(&req, Feature::Leaderboard)?;
+    let stamp_id_list = stamp_id_list.get();
+    let limit = query.limit.unwrap_or(10).min(100);
+    let entries = leaderboard(
+        &stamp_id_list,
+        &stamp_history,
+        &user_list.lock_or_recover(),
+        limit,
+    );
+
+    Ok(HttpResponse::Ok().json(entries))
+}
+
+/// 예전 프런트엔드가 읽던 `/api/stampList.json` 요청에 공개 스템프 목록을 반환하는 비동기 함수입니다.
+/// 원본 파일에는 숨은 스템프와 별칭, 위치 정보가 있으므로 `resources/api` 폴더는 정적 파일로 제공하지 않고,
+/// 숨은 스템프를 뺀 `StampInfo`만 원본과 같은 `{"stampList": [...]}` 형식으로 반환합니다.
+#[get("/api/stampList.json")]
+pub async fn handle_stamp_list_file(stamp_id_list: Data<Reloadable<StampIdList>>) -> HttpResponse {
+    let mut stamps: Vec<StampInfo> = stamp_id_list
+        .get()
+        .visible()
+        .cloned()
+        .map(StampInfo::from)
+        .collect();
+    stamps.sort_by(|a, b| a.stampId.cmp(&b.stampId));
+
+    HttpResponse::Ok().json(serde_json::json!({ "stampList": stamps }))
+}
+
+/// 스템프 목록을 요청 언어로 번역하여 JSON으로 반환하는 비동기 함수입니다.
+/// `stampNames`, `stampDescs`에 해당 언어가 없으면 기본 이름과 설명을 사용하며, 숨은 스템프는 제외합니다.
+///
+/// # Returns
+///
+/// `stampId` 순으로 정렬된 `StampInfo` 목록이 200 OK 응답으로 반환됩니다.
+#[routes]
+#[get("/api/v1/stamps")]
+#[get("/api/stamps")]
+pub async fn handle_stamps(stamp_id_list: Data<Reloadable<StampIdList>>) -> HttpResponse {
+    let locale = i18n::current();
+    let mut stamps: Vec<StampInfo> = stamp_id_list
+        .get()
+        .visible()
+        .map(|stamp| stamp.localized(locale).into())
+        .collect();
+    stamps.sort_by(|a, b| a.stampId.cmp(&b.stampId));
+
+    HttpResponse::Ok()
+        .insert_header(("Vary", "Accept-Language, Cookie"))
+        .json(stamps)
+}
+
+/// 로그인 요청을 처리하는 비동기 함수입니다. 주어진 사용자 이름을 사용하여 새로운 사용자를 등록하고,
+/// 등록된 사용자 정보를 유저 리스트에 추가한 후, 성공 응답을 반환합니다.
+///
+/// # Arguments
+///
+/// * `name` - JSON 형식으로 전달된 사용자 이름을 나타내는 `Json<UserName>` 객체입니다.
+/// * `user_list` - 사용자 정보를 관리하는 `UserList`에 대한 `Data<Mutex<UserList>>`입니다.
+/// * `teams` - 팀 정보를 관리하는 `TeamList`에 대한 `Data<Mutex<TeamList>>`입니다.
+/// * `tour_status` - 투어 종료 여부 확인에 사용할 `TourStatus`에 대한 `Data<Mutex<TourStatus>>`입니다.
+///
+/// # Returns
+///
+/// 성공적으로 사용자를 등록하고 유저 리스트에 추가한 경우, 해당 사용자 정보를 담은 성공 응답(`HttpResponse::Ok()`)이 반환됩니다.
+/// `team_code`로 참가하려는 팀이 존재하지 않는 경우 404 Not Found 응답이 반환됩니다.
+/// 투어가 종료된 경우 403 Forbidden 응답이 반환됩니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[actix_web::main]
+/// async fn main() {
+///     // Actix-web 앱 생성 및 라우터 등록
+///     let app = App::new().service(resource("/login").route(post().to(handle_login)));
+///     // HTTP 서버 생성 및 실행
+///     HttpServer::new(|| {
+///         app.clone()
+///     })
+///     .bind("127.0.0.1:8080").unwrap()
+///     .run()
+///     .await
+///     .unwrap();
+/// }
+/// ```
+pub async fn handle_login(
+    name: Json<UserName>,
+    user_list: Data<Mutex<UserList>>,
+    _user_stamp_record: Data<StampHistory>,
+    teams: Data<Mutex<TeamList>>,
+    config: Data<Reloadable<Config>>,
+    tour_status: Data<Mutex<TourStatus>>,
+    persister: Data<Persister>,
+) -> Result<HttpResponse, AppError> {
+    let config = config.get();
+    // 투어가 종료된 경우 새로운 유저를 등록하지 않음
+    if is_closed(&config, &tour_status.lock_or_recover(), chrono::Utc::now()) {
+        warn!("Login attempted after the tour ended.");
+        return Err(AppError::Forbidden("Tour ended".to_string()));
+    }
+
+    let team_code = name
+        .team_code
+        .as_ref()
+        .map(|code| code.trim().to_uppercase());
+    let team_name = name.team_name.clone();
+
+    // 참가하려는 팀이 존재하지 않는 경우 유저를 등록하지 않음
+    if let Some(team_code) = &team_code {
+        if !teams.lock_or_recover().teams.contains_key(team_code) {
+            warn!("Login attempted with unknown team code {}.", team_code);
+            return Err(AppError::Missing("Team not found".to_string()));
+        }
+    }
+
+    // 주어진 사용자 이름으로 새로운 사용자 등록
+    let mut user = user_registration(name.0);
+
+    // 팀 코드가 있으면 팀에 참가하고, 팀 이름만 있으면 새로운 팀 생성
+    {
+        let mut teams = teams.lock_or_recover();
+        let team_code = match (team_code, team_name) {
+            (Some(team_code), _) => Some(team_code),
+            (None, Some(team_name)) => Some(create_team(&mut teams, &team_name)),
+            (None, None) => None,
+        };
+        if let Some(team) = team_code
+            .as_ref()
+            .and_then(|code| teams.teams.get_mut(code))
+        {
+            team.members.push(user.user_id.clone());
+        }
+        user.team_code = team_code;
+    }
+
+    // Mutex를 사용하여 유저 리스트에 등록된 사용자 추가 (직원 조회용 코드 발급)
+    user_list.lock_or_recover().insert(&mut user);
+    persister.mark(&[Dataset::UserStatus, Dataset::TeamStatus]);
+
+    // 로그 출력: 사용자 등록 메시지
+    info!("{:?} has started a stomp tour.", user);
+    // 성공 응답과 등록된 사용자 정보를 JSON 형태로 반환
+    Ok(HttpResponse::Ok().json(user))
+}
+
+/// 주어진 사용자 이름을 사용하여 새로운 사용자를 등록하는 함수입니다.
+///
+/// # Arguments
+///
+/// * `name` - 사용자 이름을 나타내는 `UserName` 구조체입니다.
+///
+/// # Returns
+///
+/// 등록된 사용자를 나타내는 `User` 구조체를 반환합니다. 사용자 ID는 무작위로 생성됩니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// // 사용자 이름 생성
+/// let user_name = UserName { user_name: "JohnDoe".to_string(), team_code: None, team_name: None };
+/// // 사용자 등록
+/// let new_user = user_registration(user_name);
+/// println!("Registered User: {:?}", new_user);
+/// ```
+pub fn user_registration(name: UserName) -> User {
+    // 새로운 사용자 생성 및 사용자 ID는 무작위로 생성
+    User {
+        user_name: name.user_name,
+        user_id: Uuid::new_v4().to_string(),
+        team_code: None,
+        user_code: None,
+    }
+}
+
+/// 주어진 스탬프 정보를 사용하여 HTML 파일을 형식화하는 비동기 함수입니다.
+/// 스탬프에 `stampTemplate`이 지정되어 있으면 해당 템플릿을, 없거나 읽을 수 없으면 'check.html'을 사용합니다.
+///
+/// # Arguments
+///
+/// * `stamp` - 형식화에 사용될 스탬프입니다.
+///
+/// # Returns
+///
+/// 성공적으로 HTML 파일을 읽고 형식화한 경우 해당 파일의 내용을 반환하며,
+/// 'check.html'까지 읽지 못한 경우 `AppError::Template`을 반환합니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[tokio::main]
+/// async fn main() {
+///     let stamp = stamp_id_list.stamp_id_list.get("123456").unwrap();
+///     let formatted_html = format_file(stamp).await?;
+///     println!("Formatted HTML: {}", formatted_html);
+/// }
+/// ```
+pub async fn format_file(stamp: &Stamp) -> Result<String, AppError> {
+    let file = read_stamp_template(stamp).await?;
+    Ok(render_stamp(&file, &stamp.localized(i18n::current())))
+}
+
+/// 스템프 페이지 응답 본문을 반환합니다. 캐시에 없으면 템플릿을 읽어 렌더링한 뒤 캐시에 저장하며,
+/// 템플릿을 읽지 못한 경우는 저장하지 않아 다음 요청에서 다시 읽습니다.
+async fn stamp_page(
+    req: &HttpRequest,
+    stamp_pages: &StampPageCache,
+    stamp: &Stamp,
+) -> Result<Bytes, AppError> {
+    let locale = i18n::current();
+    if let Some(page) = stamp_pages.get(&stamp.stampId, locale) {
+        return Ok(page.body(req));
+    }
+    let file = read_stamp_template(stamp).await?;
+    // 시험 운영은 서버를 다시 시작할 때까지 꺼지지 않으므로 띠를 넣은 채로 저장
+    let html = inject_banner(
+        req,
+        inject_assets(req, render_stamp(&file, &stamp.localized(locale))),
+    );
+    // 빈 템플릿은 아직 파일이 준비되지 않았을 수 있으므로 저장하지 않음
+    if file.is_empty() {
+        return Ok(Bytes::from(inject_announcement(req, html)));
+    }
+    Ok(stamp_pages.insert(&stamp.stampId, locale, html).body(req))
+}
+
+/// 스템프 페이지 템플릿을 읽습니다. 스템프 전용 템플릿을 읽을 수 없으면 `check.html`을 읽습니다.
+async fn read_stamp_template(stamp: &Stamp) -> Result<String, AppError> {
+    // 스탬프 전용 템플릿이 있으면 먼저 읽기 시도
+    let custom = match &stamp.stampTemplate {
+        Some(template) => match path("html", template).await {
+            Ok(file) if !file.is_empty() => Some(file),
+            _ => {
+                warn!(
+                    "Stamp template {} for stamp {} could not be read.",
+                    template, stamp.stampId
+                );
+                None
+            }
+        },
+        None => None,
+    };
+
+    // 'check.html' 파일 읽기 시도
+    match custom {
+        Some(file) => Ok(file),
+        None => html_template("check.html").await,
+    }
+}
+
+/// 템플릿의 스탬프 자리표시자(`%STAMP_ID%`, `%STAMP_NAME%` 등)를 스탬프 정보로 대체합니다.
+fn render_stamp(file: &str, stamp: &Stamp) -> String {
+    let content = stamp.stampContent.clone().unwrap_or_default();
+    render(
+        file,
+        &[
+            ("STAMP_ID", &stamp.stampId),
+            ("STAMP_NAME", &stamp.stampName),
+            ("STAMP_LOCATION", &stamp.stampLocation),
+            ("STAMP_DESC", &stamp.stampDesc),
+            ("STAMP_IMAGE", &content.image),
+            ("SPONSOR_MESSAGE", &content.sponsorMessage),
+            ("NEXT_HINT", &content.nextHint),
+        ],
+    )
+}
+
+/// HTML 파일을 처리하는 핸들러 함수입니다. 요청된 파일을 읽어와 HTTP 응답으로 반환합니다.
+///
+/// # Arguments
+///
+/// * `req` - `HttpRequest` 객체로, 요청에 대한 정보를 포함합니다.
+///
+/// # Returns
+///
+/// `HttpResponse` 객체로, 성공적으로 파일을 읽은 경우 해당 파일의 내용을 담아 반환하고,
+/// 파일이 없으면 `AppError::NotFound`(404 응답)를 반환합니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[actix_web::main]
+/// async fn main() {
+///     // Actix-web 앱 생성 및 라우터 등록
+///     let app = App::new().service(handle_html);
+///     // HTTP 서버 생성 및 실행
+///     HttpServer::new(|| {
+///         app.clone()
+///     })
+///     .bind("127.0.0.1:8080").unwrap()
+///     .run()
+///     .await
+///     .unwrap();
+/// }
+/// ```
+#[get("/{file}")]
+pub async fn handle_html(req: HttpRequest) -> Result<HttpResponse, AppError> {
+    // 확장자가 없으면 '.html'을 붙이고, 다른 폴더를 가리키는 파일 이름은 거부
+    let file = html_file_name(req.match_info().query("file")).ok_or(AppError::NotFound)?;
+
+    // 파일이 존재하지 않는 경우 404 응답 반환
+    if !resource_path("html", &file).is_file() {
+        error!("File not found {}", file);
+        return Err(AppError::NotFound);
+    }
+
+    // 파일이 성공적으로 읽혔을 경우 200 OK 응답과 파일 내용 반환
+    let result = html_template(&file).await?;
+    Ok(HttpResponse::Ok().body(inject(&req, result)))
+}
+
+/// 잘못된 JSON 본문에 대해 actix의 기본 오류 문구 대신 구조화된 JSON 오류를 반환하는 함수입니다.
+///
+/// # Arguments
+///
+/// * `err` - JSON 본문 파싱 중 발생한 오류입니다.
+/// * `req` - 오류가 발생한 요청입니다. 로그에 IP와 본문 크기를 남기는 데 사용합니다.
+///
+/// # Returns
+///
+/// `{"error": "Invalid JSON body", "detail": ...}`을 담은 400 Bad Request 응답으로 변환된 오류를 반환합니다.
+pub fn json_error_handler(err: JsonPayloadError, req: &HttpRequest) -> actix_web::Error {
+    let ip = req
+        .peer_addr()
+        .map(|addr| addr.ip().to_string())
+        .unwrap_or_default();
+    let length = match &err {
+        JsonPayloadError::OverflowKnownLength { length, .. } => length.to_string(),
+        _ => req
+            .headers()
+            .get("content-length")
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("unknown")
+            .to_string(),
+    };
+    // 크기 제한을 넘은 본문은 serde로 넘기지 않고 413 응답
+    if let JsonPayloadError::OverflowKnownLength { limit, .. }
+    | JsonPayloadError::Overflow { limit } = &err
+    {
+        warn!(
+            "{} sent an oversized JSON body ({} bytes, limit {}) to {}",
+            ip,
+            length,
+            limit,
+            req.path()
+        );
+        let response = HttpResponse::PayloadTooLarge().json(serde_json::json!({
+            "error": "Request body too large",
+            "limit": limit,
+        }));
+        return InternalError::from_response(err, response).into();
+    }
+
+    warn!(
+        "{} sent a malformed JSON body ({} bytes) to {} : {}",
+        ip,
+        length,
+        req.path(),
+        err
+    );
+
+    let response = HttpResponse::BadRequest().json(serde_json::json!({
+        "error": "Invalid JSON body",
+        "detail": err.to_string(),
+    }));
+    InternalError::from_response(err, response).into()
+}
+
+/// 본문 크기 제한과 구조화된 오류 응답을 적용한 `JsonConfig`를 생성하는 함수입니다.
+///
+/// # Arguments
+///
+/// * `limit` - 허용할 JSON 본문의 최대 크기(바이트)입니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// App::new()
+///     .configure(routes)
+///     .app_data(json_config(config.server.public_body_limit)); // 기본 제한 덮어쓰기
+/// ```
+pub fn json_config(limit: usize) -> JsonConfig {
+    JsonConfig::default()
+        .limit(limit)
+        .error_handler(json_error_handler)
+}
+
+/// 공개 서버에서 매크로로 등록한 경로별 허용 메서드입니다. 메서드가 맞지 않는 요청에 405 응답과
+/// `Allow` 헤더를 보내는 데 사용하므로, 경로를 추가하거나 메서드를 바꾸면 함께 고쳐야 합니다.
+/// `tests/methods.rs`가 `routes`에 등록한 핸들러의 라우트 매크로와 이 목록이 같은지 확인합니다.
+pub const PUBLIC_METHODS: [(&str, &str); 45] = [
+    ("/", "GET"),
+    ("/api/v1/me/delete", "POST"),
+    ("/me/delete", "POST"),
+    ("/me", "GET"),
+    ("/api/v1/feedback", "POST"),
+    ("/feedback", "POST"),
+    ("/api/v1/guestbook", "POST"),
+    ("/guestbook", "GET, POST"),
+    ("/api/v1/partner/stamp", "POST"),
+    ("/api/partner/stamp", "POST"),
+    ("/api/v1/push/key", "GET"),
+    ("/api/v1/push/subscribe", "POST"),
+    ("/api/v1/push/unsubscribe", "POST"),
+    ("/api/v1/bot/telegram", "POST"),
+    ("/api/v1/bot/line", "POST"),
+    ("/staff/booth/{stamp_id}", "GET"),
+    ("/kiosk/{stamp_id}/qr", "GET"),
+    ("/kiosk/{stamp_id}", "GET"),
+    ("/check", "GET"),
+    ("/stamp/", "GET"),
+    ("/api/v1/progress", "GET"),
+    ("/progress", "GET"),
+    ("/complete", "GET"),
+    ("/api/v1/leaderboard", "GET"),
+    ("/api/leaderboard", "GET"),
+    ("/api/v1/missions", "GET"),
+    ("/share/{user_code}.png", "GET"),
+    ("/share/{user_code}", "GET"),
+    ("/map.svg", "GET"),
+    ("/api/v1/stamps", "GET"),
+    ("/api/stamps", "GET"),
+    ("/api/stampList.json", "GET"),
+    ("/api/v1/stats/public", "GET"),
+    ("/api/stats/public", "GET"),
+    ("/api/v1/announcement", "GET"),
+    ("/api/announcement", "GET"),
+    ("/api/v1/event", "GET"),
+    ("/api/event", "GET"),
+    ("/api/v1/teams/leaderboard", "GET"),
+    ("/api/teams/leaderboard", "GET"),
+    ("/api/v1/teams/{team_code}", "GET"),
+    ("/api/teams/{team_code}", "GET"),
+    ("/.well-known/acme-challenge/{token}", "GET"),
+    ("/s/{short}", "GET"),
+    ("/nfc/check", "GET"),
+];
+
+/// 관리자 서버에서 매크로로 등록한 경로별 허용 메서드입니다. (`PUBLIC_METHODS` 참고)
+pub const ADMIN_METHODS: [(&str, &str); 9] = [
+    ("/api/v1/staff/users/{user_code}", "GET"),
+    ("/api/staff/users/{user_code}", "GET"),
+    ("/metrics", "GET"),
+    ("/api/v1/stats/timeseries", "GET"),
+    ("/api/stats/timeseries", "GET"),
+    ("/graphql", "GET, POST"),
+    ("/api/v1/admin/bulk", "POST"),
+    ("/api/admin/bulk", "POST"),
+    ("/admin/logs", "GET"),
+];
+
+/// 모든 라우트를 등록하는 함수입니다. 등록 순서가 곧 매칭 우선순위이므로 구체적인 경로를 먼저 등록합니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let app = App::new()
+///     .configure(|cfg| state.register(cfg))
+///     .configure(routes);
+/// ```
+pub fn routes(cfg: &mut ServiceConfig) {
+    cfg.app_data(json_config(ServerConfig::default().public_body_limit)) // JSON 본문 크기 제한 및 오류 처리
+        .service(index) // 인덱스 요청 처리
+        .service(
+            resource(["/api/v1/login", "/login"])
+                .route(post().to(handle_login))
+                .default_service(method_not_allowed("POST")),
+        ) // 로그인 요청 처리
+        .service(
+            resource(["/api/v1/sync", "/api/sync"])
+                .app_data(json_config(SYNC_BODY_LIMIT))
+                .route(post().to(handle_sync))
+                .default_service(method_not_allowed("POST")),
+        ) // 오프라인 부스 기록 동기화 처리
+        .service(handle_delete_me) // 개인정보 삭제 요청 처리
+        .service(handle_me) // 내 스템프 페이지 처리
+        .service(handle_feedback) // 방문 후기 저장 처리
+        .service(handle_guestbook_post) // 방명록 글 저장 처리
+        .service(handle_guestbook_page) // 방명록 화면 처리
+        .service(handle_partner_stamp) // 협력사 스템프 찍기 처리
+        .service(handle_push_key) // Web Push 공개 키 요청 처리
+        .service(handle_push_subscribe) // Web Push 구독 처리
+        .service(handle_push_unsubscribe) // Web Push 구독 해지 처리
+        .service(handle_telegram) // Telegram 챗봇 웹훅 처리
+        .service(handle_line) // LINE 챗봇 웹훅 처리
+        .service(handle_booth) // 부스 운영자 화면 처리
+        .service(handle_kiosk_qr) // 키오스크 QR 코드 갱신 처리
+        .service(handle_kiosk) // 키오스크 화면 처리
+        .service(handle_check) // 스템프 리다이렉션 처리
+        .service(handle_short_link) // 인쇄용 짧은 주소 리다이렉션 처리
+        .service(handle_nfc_check) // NFC 태그 스템프 확인 처리
+        .service(handle_stamp) // 스템프 찍기 처리
+        .service(handle_progress) // 진행 상황 요청 처리
+        .service(handle_complete) // 완주 페이지 처리
+        .service(handle_leaderboard) // 순위표 요청 처리
+        .service(handle_share_image) // 공유 이미지 요청 처리
+        .service(handle_share_page) // 공유 페이지 요청 처리
+        .service(handle_map) // 방문한 부스를 표시한 행사장 지도 요청 처리
+        .service(handle_missions) // 오늘의 미션 요청 처리
+        .service(handle_stamps) // 번역된 스템프 목록 요청 처리
+        .service(handle_stamp_list_file) // 스템프 목록 파일 주소 요청 처리
+        .service(handle_public_stats) // 공개 통계 요청 처리
+        .service(handle_announcement) // 공지 요청 처리
+        .service(handle_event) // 행사 정보 요청 처리
+        .service(handle_team_leaderboard) // 팀 순위표 요청 처리
+        .service(handle_team_progress) // 팀 진행 상황 요청 처리
+        .service(handle_acme_challenge); // 인증서 발급용 ACME 인증 요청 처리
+
+    // 메서드만 틀린 요청은 404 대신 405 응답 전송 (파일 요청으로 처리되지 않도록 먼저 등록)
+    for (path, allow) in PUBLIC_METHODS {
+        cfg.service(resource(path).default_service(method_not_allowed(allow)));
+    }
+
+    cfg.service(handle_html) // HTML 요청 처리
+        .service(handle_req) // 일반 파일 요청 처리
+        .default_service(route().to(handle_404)); // 만약 위의 처리 항목 중 해당되는게 없으면 404 응답 전송
+}
+
+/// 관리자 전용 서버의 라우트를 등록하는 함수입니다. 공개 서버에는 관리자/직원 API가 전혀 노출되지 않도록
+/// `routes`와 분리된 별도의 `HttpServer`에서만 사용합니다.
+///
+/// # Arguments
+///
+/// * `cfg` - 라우트를 등록할 `ServiceConfig`입니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// HttpServer::new(move || {
+///     App::new()
+///         .configure(|cfg| state.register(cfg))
+///         .configure(admin_routes)
+/// })
+/// .bind(("127.0.0.1", 8081))?
+/// ```
+pub fn admin_routes(cfg: &mut ServiceConfig) {
+    cfg.app_data(json_config(ServerConfig::default().admin_body_limit)) // JSON 본문 크기 제한 및 오류 처리
+        .service(
+            resource("/admin")
+                .route(post().to(handle_admin))
+                .default_service(method_not_allowed("POST")),
+        ) // 관리자 명령 처리
+        .service(
+            resource(["/api/v1/staff/revoke", "/api/staff/revoke"])
+                .route(post().to(handle_revoke))
+                .default_service(method_not_allowed("POST")),
+        ) // 스템프 기록 취소 처리
+        .service(
+            resource(["/api/v1/staff/redeem", "/staff/redeem"])
+                .route(post().to(handle_redeem))
+                .default_service(method_not_allowed("POST")),
+        ) // 경품 수령 처리
+        .service(handle_staff_user) // 직원용 유저 조회 처리
+        .service(handle_bulk) // 관리자 일괄 작업 처리
+        .service(handle_logs) // 최근 로그 조회 처리
+        .service(handle_metrics) // 메트릭 요청 처리
+        .service(handle_timeseries) // 발급 추이 시계열 요청 처리
+        .service(handle_graphql) // GraphQL 쿼리 처리
+        .service(handle_graphql_schema); // GraphQL 스키마 요청 처리
+
+    // 메서드만 틀린 요청은 405 응답 전송
+    for (path, allow) in ADMIN_METHODS {
+        cfg.service(resource(path).default_service(method_not_allowed(allow)));
+    }
+
+    cfg.default_service(route().to(handle_404)); // 그 외 요청은 404 응답 전송
+}
@@ -0,0 +1,191 @@
+use actix_web::{
+    body::MessageBody,
+    cookie::{time::Duration, Cookie},
+    dev::{ServiceRequest, ServiceResponse},
+    middleware::Next,
+    web::Query,
+};
+use std::collections::HashMap;
+
+use crate::storage::{read_file, resource_path};
+
+/// 제공하는 언어 목록입니다. 첫 번째 언어가 기본 언어입니다.
+pub const LOCALES: [&str; 3] = ["ko", "en", "ja"];
+
+/// 요청 언어를 지정하는 쿼리 매개변수와 쿠키 이름입니다.
+pub const LANG_PARAM: &str = "lang";
+
+tokio::task_local! {
+    static LOCALE: &'static str;
+}
+
+/// 현재 처리 중인 요청의 언어를 반환합니다. 요청 처리 밖에서 호출하면 기본 언어를 반환합니다.
+pub fn current() -> &'static str {
+    LOCALE.try_with(|locale| *locale).unwrap_or(LOCALES[0])
+}
+
+/// `en-US`와 같은 언어 태그를 제공하는 언어로 변환합니다. 제공하지 않는 언어면 `None`을 반환합니다.
+pub fn supported(tag: &str) -> Option<&'static str> {
+    let primary = tag.trim().split(['-', '_']).next()?.to_lowercase();
+    LOCALES.into_iter().find(|locale| *locale == primary)
+}
+
+/// `Accept-Language` 헤더에서 가중치(`q`)가 가장 높은 제공 언어를 찾습니다.
+pub fn from_accept_language(header: &str) -> Option<&'static str> {
+    let mut candidates: Vec<(f32, &'static str)> = header
+        .split(',')
+        .filter_map(|part| {
+            let mut fields = part.split(';');
+            let locale = supported(fields.next()?)?;
+            let quality = fields
+                .find_map(|field| field.trim().strip_prefix("q="))
+                .and_then(|q| q.parse().ok())
+                .unwrap_or(1.0);
+            (quality > 0.0).then_some((quality, locale))
+        })
+        .collect();
+    // 가중치가 같으면 헤더에 먼저 적힌 언어를 우선함
+    candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+    candidates.first().map(|(_, locale)| *locale)
+}
+
+/// 요청 언어를 정하는 함수입니다. `?lang=` 매개변수, `lang` 쿠키, `Accept-Language` 헤더 순서로 확인합니다.
+///
+/// # Returns
+///
+/// 제공하는 언어 중 하나를 반환하며, 일치하는 언어가 없으면 기본 언어(한국어)를 반환합니다.
+pub fn negotiate(
+    query: Option<&str>,
+    cookie: Option<&str>,
+    accept_language: Option<&str>,
+) -> &'static str {
+    query
+        .and_then(supported)
+        .or_else(|| cookie.and_then(supported))
+        .or_else(|| accept_language.and_then(from_accept_language))
+        .unwrap_or(LOCALES[0])
+}
+
+/// 요청마다 언어를 정하는 미들웨어입니다. `?lang=`으로 언어를 고르면 `lang` 쿠키에 저장하여
+/// 다음 요청부터는 매개변수 없이도 같은 언어로 응답합니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// App::new()
+///     .wrap(actix_web::middleware::from_fn(select_locale))
+///     .configure(routes);
+/// ```
+pub async fn select_locale(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let query = Query::<HashMap<String, String>>::from_query(req.query_string())
+        .ok()
+        .and_then(|query| query.get(LANG_PARAM).and_then(|lang| supported(lang)));
+    let cookie = req.cookie(LANG_PARAM);
+    let accept_language = req
+        .headers()
+        .get("accept-language")
+        .and_then(|value| value.to_str().ok());
+    let locale = negotiate(
+        query,
+        cookie.as_ref().map(|cookie| cookie.value()),
+        accept_language,
+    );
+
+    let mut res = LOCALE.scope(locale, next.call(req)).await?;
+
+    if let Some(locale) = query {
+        let mut cookie = Cookie::new(LANG_PARAM, locale);
+        cookie.set_path("/");
+        cookie.set_max_age(Duration::days(365));
+        res.response_mut().add_cookie(&cookie).ok();
+    }
+    Ok(res)
+}
+
+/// `resources/i18n/{언어}.json` 문자열 묶음을 읽습니다. 파일이 없거나 형식이 틀리면 빈 묶음을 반환합니다.
+pub async fn bundle(locale: &str) -> HashMap<String, String> {
+    async_std::fs::read_to_string(resource_path("i18n", &format!("{}.json", locale)))
+        .await
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// HTML의 `%T:키%` 자리표시자를 문자열 묶음으로, `%LANG%`을 언어 코드로 치환하는 함수입니다.
+/// 요청 언어의 묶음에 없는 키는 기본 언어 묶음에서 찾고, 그래도 없으면 키를 그대로 표시합니다.
+///
+/// # Example
+///
+/// ```rust
+/// use std::collections::HashMap;
+/// use gj_stamptour::i18n::translate;
+///
+/// let en = HashMap::from([("start".to_string(), "Start".to_string())]);
+/// let ko = HashMap::from([("start".to_string(), "시작하기".to_string()), ("ok".to_string(), "확인".to_string())]);
+/// assert_eq!(translate("<html lang=\"%LANG%\">%T:start% %T:ok% %T:none%", "en", &en, &ko), "<html lang=\"en\">Start 확인 none");
+/// ```
+pub fn translate(
+    html: &str,
+    locale: &str,
+    strings: &HashMap<String, String>,
+    fallback: &HashMap<String, String>,
+) -> String {
+    let mut output = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find("%T:") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 3..];
+        match after.find('%') {
+            Some(end) => {
+                let key = &after[..end];
+                let value = strings
+                    .get(key)
+                    .or_else(|| fallback.get(key))
+                    .map(String::as_str)
+                    .unwrap_or(key);
+                output.push_str(value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                output.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    output.push_str(rest);
+    output.replace("%LANG%", locale)
+}
+
+/// 현재 요청 언어에 맞는 HTML 템플릿을 읽는 함수입니다. `resources/html/{언어}/{파일}`이 있으면 그 파일을,
+/// 없으면 `resources/html/{파일}`을 읽은 뒤 문자열 묶음을 적용합니다.
+///
+/// # Returns
+///
+/// 템플릿을 읽지 못하면 `read_file`의 오류를 그대로 반환합니다.
+pub async fn localized_template(file: &str) -> Result<String, Vec<u8>> {
+    let locale = current();
+    let localized = resource_path("html", &format!("{}/{}", locale, file));
+    let template = if localized.is_file() {
+        read_file(&localized).await?
+    } else {
+        read_file(&resource_path("html", file)).await?
+    };
+
+    if !template.contains("%T:") && !template.contains("%LANG%") {
+        return Ok(template);
+    }
+    let fallback = if locale == LOCALES[0] {
+        HashMap::new()
+    } else {
+        bundle(LOCALES[0]).await
+    };
+    Ok(translate(
+        &template,
+        locale,
+        &bundle(locale).await,
+        &fallback,
+    ))
+}
@@ -0,0 +1,103 @@
+use serde::Deserialize;
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use crate::handlers::user_registration;
+use crate::state::{TeamList, User, UserList, UserName};
+use crate::teams::create_team;
+
+/// 가져올 CSV의 한 줄입니다. `user_name` 열은 필수이고 `team_name` 열은 선택입니다.
+#[derive(Deserialize, Debug)]
+struct ImportRow {
+    user_name: String,
+    #[serde(default)]
+    team_name: Option<String>,
+}
+
+/// `resources/imports/{file_name}`을 읽는 함수입니다. 다른 폴더의 파일은 읽지 않습니다.
+pub fn read_import(file_name: &str) -> Option<String> {
+    if file_name.is_empty() || file_name.contains(['/', '\\']) || file_name.contains("..") {
+        return None;
+    }
+    fs::read_to_string(PathBuf::from("resources/imports").join(file_name)).ok()
+}
+
+/// 미리 등록할 단체 명단 CSV로 유저를 생성하는 함수입니다.
+///
+/// # Arguments
+///
+/// * `source` - `user_name`(필수), `team_name`(선택) 열을 가진 CSV 문자열입니다.
+/// * `user_list` - 생성된 유저를 추가할 유저 목록입니다.
+/// * `teams` - 팀 목록입니다. 같은 이름의 팀이 있으면 참가하고, 없으면 새로 만듭니다.
+///
+/// # Returns
+///
+/// 생성된 유저 목록을 반환합니다. CSV 형식이 틀리면 아무 유저도 추가하지 않고 오류를 반환합니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let users = import_users("user_name,team_name\n김철수,3학년 2반\n", &mut user_list, &mut teams)?;
+/// ```
+pub fn import_users(
+    source: &str,
+    user_list: &mut UserList,
+    teams: &mut TeamList,
+) -> Result<Vec<User>, csv::Error> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(source.as_bytes());
+    let rows = reader
+        .deserialize()
+        .collect::<Result<Vec<ImportRow>, csv::Error>>()?;
+
+    let mut team_codes: HashMap<String, String> = teams
+        .teams
+        .iter()
+        .map(|(team_code, team)| (team.team_name.clone(), team_code.clone()))
+        .collect();
+
+    let mut users = Vec::new();
+    for row in rows.into_iter().filter(|row| !row.user_name.is_empty()) {
+        let mut user = user_registration(UserName {
+            user_name: row.user_name,
+            team_code: None,
+            team_name: None,
+        });
+
+        if let Some(team_name) = row.team_name.filter(|team_name| !team_name.is_empty()) {
+            let team_code = team_codes
+                .entry(team_name.clone())
+                .or_insert_with(|| create_team(teams, &team_name))
+                .clone();
+            if let Some(team) = teams.teams.get_mut(&team_code) {
+                team.members.push(user.user_id.clone());
+            }
+            user.team_code = Some(team_code);
+        }
+
+        user_list.insert(&mut user);
+        users.push(user);
+    }
+    Ok(users)
+}
+
+/// 손목 밴드 QR 코드를 미리 인쇄할 수 있도록 생성된 유저의 ID 대응표를 CSV로 변환하는 함수입니다.
+///
+/// # Returns
+///
+/// `user_id,user_code,user_name,team_code` 열을 가진 CSV 문자열을 반환합니다.
+pub fn users_mapping_csv(users: &[User]) -> Result<String, csv::Error> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(["user_id", "user_code", "user_name", "team_code"])?;
+    for user in users {
+        writer.write_record([
+            user.user_id.as_str(),
+            user.user_code.as_deref().unwrap_or_default(),
+            user.user_name.as_str(),
+            user.team_code.as_deref().unwrap_or_default(),
+        ])?;
+    }
+
+    let bytes = writer.into_inner().map_err(|e| e.into_error())?;
+    Ok(String::from_utf8(bytes).unwrap_or_default())
+}
@@ -0,0 +1,272 @@
+pub mod access_log;
+pub mod achievements;
+pub mod acme;
+pub mod announcement;
+pub mod api_version;
+pub mod assets;
+pub mod backup;
+pub mod base_path;
+pub mod booth;
+pub mod bot;
+pub mod bulk;
+pub mod bundle;
+pub mod cache_control;
+pub mod config;
+pub mod consistency;
+pub mod cooldown;
+pub mod cors;
+pub mod crypto;
+pub mod error;
+pub mod event;
+pub mod export;
+pub mod features;
+pub mod feedback;
+pub mod geofence;
+pub mod graphql;
+pub mod grpc;
+pub mod guestbook;
+pub mod handlers;
+pub mod i18n;
+pub mod import;
+pub mod lock;
+pub mod logging;
+pub mod me;
+pub mod merge;
+pub mod metrics;
+pub mod missions;
+pub mod nfc;
+pub mod notifier;
+pub mod page_cache;
+pub mod panic;
+pub mod partner;
+pub mod persistence;
+pub mod progress;
+pub mod push;
+pub mod report;
+pub mod request_id;
+pub mod retention;
+pub mod route_order;
+pub mod scheduler;
+pub mod session;
+pub mod share;
+pub mod sheets;
+pub mod short_link;
+pub mod shutdown;
+pub mod simulate;
+#[cfg(unix)]
+pub mod socket;
+pub mod staff;
+pub mod staging;
+pub mod state;
+pub mod stats;
+pub mod storage;
+pub mod sync;
+pub mod teams;
+pub mod template;
+pub mod timeout;
+pub mod tls;
+pub mod tour;
+pub mod undo;
+pub mod venue_map;
+
+use actix_web::{middleware::from_fn, web::Data, App, HttpServer};
+use log::{error, info, warn};
+use std::sync::Arc;
+
+use crate::access_log::log_access;
+use crate::acme::schedule_renewal;
+use crate::api_version::mark_legacy_path;
+use crate::base_path::strip_base_path;
+use crate::cache_control::{apply_admin_cache_policy, apply_cache_policy};
+use crate::config::{load_config, AddressInfo};
+use crate::cors::apply_cors;
+use crate::handlers::{admin_routes, json_config, routes};
+use crate::i18n::select_locale;
+use crate::metrics::record_latency;
+use crate::panic::catch_panic;
+use crate::request_id::assign_request_id;
+use crate::storage::load_state;
+use crate::timeout::apply_timeout;
+use crate::tls::{load_certified_key, rustls_config, CertResolver};
+
+// Actix-web 서버 구성 및 설정
+pub async fn run(address: AddressInfo) -> std::io::Result<()> {
+    // 설정 및 데이터베이스 초기화
+    let config = load_config();
+    let server_config = config.server.clone();
+    let tls_config = config.tls.clone();
+    let state = load_state(config);
+    let public_body_limit = server_config.public_body_limit;
+    let admin_body_limit = server_config.admin_body_limit;
+
+    // gRPC 서버는 HTTP 서버와 같은 공유 상태를 사용
+    let grpc_address = server_config
+        .grpc_address
+        .as_deref()
+        .map(|grpc_address| {
+            grpc_address
+                .parse::<std::net::SocketAddr>()
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))
+        })
+        .transpose()?;
+    let grpc_state = state.clone();
+
+    let move_address = address.clone();
+    let admin_state = state.clone();
+    let admin_address = address.clone();
+
+    // 변경된 데이터는 저장 작업자가 모아서 저장
+    persistence::start(&state);
+    let shutdown_state = state.clone();
+
+    // SIGHUP을 받으면 설정 파일, 스템프 목록, 정적 파일 대응표를 다시 불러옴
+    #[cfg(unix)]
+    socket::reload_on_hangup(state.clone());
+
+    // 보관 기간이 지난 개인정보를 주기적으로 익명화하거나 삭제
+    retention::schedule_purge(state.clone());
+
+    // 설정된 경우 Google 스프레드시트에 결과를 주기적으로 업로드
+    sheets::schedule_sync(state.clone());
+
+    // 설정된 경우 완주와 품절 임박을 구독한 방문객에게 Web Push로 알림
+    push::schedule_notifications(state.clone());
+
+    let public_server = HttpServer::new(move || {
+        App::new()
+            // .wrap(Logger::default()) // 로거 시작
+            .wrap(from_fn(catch_panic)) // 핸들러 패닉을 500 응답으로 변환
+            .wrap(from_fn(apply_timeout)) // 경로별 처리 제한 시간 적용
+            .wrap(from_fn(apply_cache_policy)) // 응답 종류별 캐시 정책 적용
+            .wrap(from_fn(mark_legacy_path)) // 이전 API 경로 응답에 새 경로 안내
+            .wrap(from_fn(select_locale)) // 요청 언어 선택
+            .wrap(from_fn(apply_cors)) // 다른 출처의 PWA에서 온 API 요청 허용
+            .wrap(from_fn(strip_base_path)) // URL 접두사 제거
+            .wrap(from_fn(record_latency)) // 경로별 처리 시간 측정
+            .wrap(from_fn(assign_request_id)) // 요청 ID 부여
+            .wrap(from_fn(log_access)) // 접근 로그 기록
+            .app_data(Data::new(move_address.clone())) // 전역변수 선언
+            .configure(|cfg| state.register(cfg))
+            .configure(routes)
+            .app_data(json_config(public_body_limit)) // 설정된 본문 크기 제한 적용
+    })
+    .keep_alive(server_config.keep_alive()) // 연결 유지 시간
+    .client_request_timeout(server_config.client_request_timeout())
+    .client_disconnect_timeout(server_config.client_disconnect_timeout())
+    .shutdown_timeout(server_config.shutdown_timeout_secs) // 종료할 때 처리 중인 요청을 기다리는 시간
+    .disable_signals(); // 종료 신호는 shutdown 모듈에서 처리
+
+    // 작업자 수가 설정되지 않으면 CPU 코어 수만큼 생성
+    let public_server = match server_config.workers {
+        Some(workers) => public_server.workers(workers),
+        None => public_server,
+    };
+
+    // systemd 소켓 활성화로 넘겨받은 소켓이 있으면 사용하고, 없으면 직접 바인딩
+    #[cfg(unix)]
+    let listeners = socket::systemd_listeners();
+    #[cfg(not(unix))]
+    let listeners: Vec<std::net::TcpListener> = Vec::new();
+    // HTTP/2가 켜져 있으면 같은 포트에서 HTTP/1.1과 평문 HTTP/2(h2c)를 모두 받음
+    let public_server = match (listeners.is_empty(), server_config.http2) {
+        (true, false) => public_server.bind((address.address.as_str(), address.port))?, // 서버 바인딩
+        (true, true) => public_server.bind_auto_h2c((address.address.as_str(), address.port))?,
+        (false, http2) => listeners
+            .into_iter()
+            .try_fold(public_server, |server, listener| match http2 {
+                true => server.listen_auto_h2c(listener),
+                false => server.listen(listener),
+            })?,
+    };
+
+    // TLS를 직접 처리하면 HTTPS 포트에서 ALPN으로 HTTP/2와 HTTP/1.1을 협상
+    let public_server = match &tls_config {
+        Some(tls) => {
+            let resolver = Arc::new(CertResolver::default());
+            match (load_certified_key(tls), &tls.acme) {
+                (Ok(certified_key), _) => resolver.set(certified_key),
+                // ACME로 발급받을 인증서는 아직 없을 수 있음 (발급되면 재시작 없이 적용)
+                (Err(e), Some(_)) => warn!("No certificate yet, waiting for ACME: {}", e),
+                (Err(e), None) => return Err(std::io::Error::other(e)),
+            }
+            schedule_renewal(tls.clone(), resolver.clone());
+            info!("TLS enabled on port {} (ALPN: h2, http/1.1)", tls.port);
+            public_server.bind_rustls_0_23(
+                (address.address.as_str(), tls.port),
+                rustls_config(resolver),
+            )?
+        }
+        None => public_server,
+    };
+
+    // 리버스 프록시용 유닉스 도메인 소켓 바인딩 (TCP 바인딩과 함께 사용)
+    #[cfg(unix)]
+    let public_server = match &address.unix_socket {
+        Some(socket_path) => {
+            let socket_path = std::path::Path::new(socket_path);
+            socket::remove_stale_socket(socket_path)?;
+            let server = public_server.bind_uds(socket_path)?;
+            socket::set_socket_mode(socket_path, address.unix_socket_mode)?;
+            server
+        }
+        None => public_server,
+    };
+    let public_server = public_server.run();
+    let public_handle = public_server.handle();
+
+    // 관리자/직원 API는 공개 서버와 분리된 별도의 포트에서만 제공
+    let admin_server = HttpServer::new(move || {
+        App::new()
+            .wrap(from_fn(catch_panic)) // 핸들러 패닉을 500 응답으로 변환
+            .wrap(from_fn(apply_admin_cache_policy)) // 관리자 응답은 저장하지 않음
+            .wrap(from_fn(mark_legacy_path)) // 이전 API 경로 응답에 새 경로 안내
+            .wrap(from_fn(record_latency)) // 경로별 처리 시간 측정
+            .wrap(from_fn(assign_request_id)) // 요청 ID 부여
+            .app_data(Data::new(admin_address.clone())) // 전역변수 선언
+            .configure(|cfg| admin_state.register(cfg))
+            .configure(admin_routes)
+            .app_data(json_config(admin_body_limit)) // 설정된 본문 크기 제한 적용
+    })
+    .workers(1)
+    .shutdown_timeout(server_config.shutdown_timeout_secs)
+    .disable_signals()
+    .bind((address.admin_address.as_str(), address.admin_port))? // 관리자 서버 바인딩
+    .run();
+
+    // 종료 신호를 받으면 처리 중인 요청을 마친 뒤 서버를 멈춤
+    let (grpc_stop, grpc_stopped) = tokio::sync::oneshot::channel::<()>();
+    shutdown::drain_on_signal(
+        vec![public_handle, admin_server.handle()],
+        grpc_stop,
+        Data::clone(&shutdown_state.metrics),
+        server_config.shutdown_timeout(),
+    );
+
+    // 모든 소켓 바인딩이 끝났으므로 systemd에 준비 완료 알림
+    #[cfg(unix)]
+    socket::notify_ready();
+
+    // 개찰구 기기용 gRPC 서버 (설정된 경우에만 실행)
+    let grpc_server = async move {
+        match grpc_address {
+            Some(grpc_address) => grpc::serve(grpc_state, grpc_address, async {
+                let _ = grpc_stopped.await;
+            })
+            .await
+            .map_err(std::io::Error::other),
+            None => Ok(()),
+        }
+    };
+
+    let result = tokio::try_join!(public_server, admin_server, grpc_server).map(|_| ());
+
+    // 종료 전에 아직 저장하지 않은 변경을 저장 (서버가 멈춘 뒤이므로 마무리된 요청의 변경도 포함)
+    match shutdown_state.persister.flush().await {
+        Ok(()) => info!("All databases saved before shutdown"),
+        Err(failed) => error!(
+            "Database save failed before shutdown: {}",
+            failed.join(", ")
+        ),
+    }
+    result
+}
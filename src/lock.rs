@@ -0,0 +1,65 @@
+use log::warn;
+use std::sync::{Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// 독(poison)된 `Mutex`를 복구하며 잠그는 확장 트레이트입니다.
+///
+/// 락을 잡은 핸들러가 패닉하면 `Mutex`가 독되어 이후의 `.lock().unwrap()`이 모두 패닉하므로 서버 전체가 멈춥니다.
+/// 상태 데이터는 한 번의 대입이나 삽입으로 바뀌어 중간 상태로 남지 않으므로, 독된 락도 경고를 남기고 그대로 사용합니다.
+///
+/// # Example
+///
+/// ```rust
+/// use gj_stamptour::lock::MutexExt;
+/// use std::sync::Mutex;
+///
+/// let counter = Mutex::new(0);
+/// let _ = std::panic::catch_unwind(|| {
+///     let _guard = counter.lock().unwrap();
+///     panic!("handler bug");
+/// });
+/// assert!(counter.is_poisoned());
+///
+/// *counter.lock_or_recover() += 1;
+/// assert_eq!(*counter.lock_or_recover(), 1);
+/// assert!(!counter.is_poisoned());
+/// ```
+pub trait MutexExt<T> {
+    /// 락을 잡습니다. 독된 락이면 경고를 남기고 독을 지운 뒤 그대로 잡습니다.
+    fn lock_or_recover(&self) -> MutexGuard<'_, T>;
+}
+
+impl<T> MutexExt<T> for Mutex<T> {
+    fn lock_or_recover(&self) -> MutexGuard<'_, T> {
+        self.lock().unwrap_or_else(|poisoned| {
+            warn!("Recovering a mutex poisoned by an earlier panic");
+            self.clear_poison();
+            poisoned.into_inner()
+        })
+    }
+}
+
+/// 독된 `RwLock`을 복구하며 잠그는 확장 트레이트입니다. (`MutexExt` 참고)
+pub trait RwLockExt<T> {
+    /// 읽기 락을 잡습니다. 독된 락이면 경고를 남기고 독을 지운 뒤 그대로 잡습니다.
+    fn read_or_recover(&self) -> RwLockReadGuard<'_, T>;
+    /// 쓰기 락을 잡습니다. 독된 락이면 경고를 남기고 독을 지운 뒤 그대로 잡습니다.
+    fn write_or_recover(&self) -> RwLockWriteGuard<'_, T>;
+}
+
+impl<T> RwLockExt<T> for RwLock<T> {
+    fn read_or_recover(&self) -> RwLockReadGuard<'_, T> {
+        self.read().unwrap_or_else(|poisoned| {
+            warn!("Recovering a read-write lock poisoned by an earlier panic");
+            self.clear_poison();
+            poisoned.into_inner()
+        })
+    }
+
+    fn write_or_recover(&self) -> RwLockWriteGuard<'_, T> {
+        self.write().unwrap_or_else(|poisoned| {
+            warn!("Recovering a read-write lock poisoned by an earlier panic");
+            self.clear_poison();
+            poisoned.into_inner()
+        })
+    }
+}
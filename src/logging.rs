@@ -0,0 +1,362 @@
+use actix_web::{
+    get,
+    web::{Data, Query},
+    HttpRequest, HttpResponse,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use log::{warn, Level};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{Mutex, OnceLock},
+};
+
+use crate::config::Config;
+use crate::error::AppError;
+use crate::lock::MutexExt;
+use crate::request_id::format_log;
+use crate::staff::is_staff;
+use crate::state::Reloadable;
+
+/// `resources/config.json`의 `log` 항목입니다. `file`을 설정하면 콘솔과 함께 파일에도 로그를 남깁니다.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct LogConfig {
+    /// 로그 파일 경로입니다. (예: `"logs/stamptour.log"`) 없으면 콘솔에만 출력합니다.
+    pub file: Option<String>,
+    /// 접근 로그(Combined Log Format) 파일 경로입니다. 교체 설정은 `file`과 같습니다. 없으면 남기지 않습니다.
+    pub access_file: Option<String>,
+    /// 파일이 이 크기(MB)를 넘으면 새 파일로 바꿉니다. 0이면 크기로 나누지 않습니다.
+    pub max_size_mb: u64,
+    /// 날짜(UTC)가 바뀌면 새 파일로 바꿉니다.
+    pub daily: bool,
+    /// 남겨둘 이전 로그 파일 수입니다. 0이면 지우지 않습니다.
+    pub keep: usize,
+    /// `/admin/logs`에서 볼 수 있도록 메모리에 남겨둘 최근 로그 줄 수입니다.
+    pub buffer_lines: usize,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        LogConfig {
+            file: None,
+            access_file: None,
+            max_size_mb: 10,
+            daily: true,
+            keep: 14,
+            buffer_lines: 1000,
+        }
+    }
+}
+
+/// 크기와 날짜에 따라 파일을 바꾸며 로그를 기록하는 구조체입니다.
+/// 이전 파일은 `<경로>.<YYYYMMDD-HHMMSS>` 이름으로 옮기고, `keep`개를 넘는 오래된 파일은 지웁니다.
+pub struct RotatingFile {
+    path: PathBuf,
+    max_bytes: u64,
+    daily: bool,
+    keep: usize,
+    file: File,
+    size: u64,
+    opened_on: NaiveDate,
+}
+
+impl RotatingFile {
+    /// 로그 파일을 이어쓰기로 엽니다. 디렉터리가 없으면 만듭니다.
+    pub fn open(config: &LogConfig, path: &Path) -> io::Result<Self> {
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let metadata = file.metadata()?;
+        // 이미 있는 파일은 마지막으로 수정된 날짜에 열린 것으로 봄
+        let opened_on = metadata
+            .modified()
+            .map(|modified| DateTime::<Utc>::from(modified).date_naive())
+            .unwrap_or_else(|_| Utc::now().date_naive());
+        Ok(RotatingFile {
+            path: path.to_path_buf(),
+            max_bytes: config.max_size_mb * 1024 * 1024,
+            daily: config.daily,
+            keep: config.keep,
+            file,
+            size: metadata.len(),
+            opened_on,
+        })
+    }
+
+    /// 크기 제한을 바이트 단위로 바꿉니다. 0이면 크기로 나누지 않습니다.
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    fn should_rotate(&self, len: u64, now: DateTime<Utc>) -> bool {
+        let too_large = self.max_bytes > 0 && self.size > 0 && self.size + len > self.max_bytes;
+        let new_day = self.daily && now.date_naive() != self.opened_on;
+        too_large || new_day
+    }
+
+    /// 현재 파일을 이전 파일로 옮기고 새 파일을 엽니다.
+    fn rotate(&mut self, now: DateTime<Utc>) -> io::Result<()> {
+        self.file.flush()?;
+        let stamp = now.format("%Y%m%d-%H%M%S").to_string();
+        let mut rotated = PathBuf::from(format!("{}.{}", self.path.display(), stamp));
+        let mut suffix = 1;
+        while rotated.exists() {
+            rotated = PathBuf::from(format!("{}.{}-{}", self.path.display(), stamp, suffix));
+            suffix += 1;
+        }
+        fs::rename(&self.path, &rotated)?;
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.size = 0;
+        self.opened_on = now.date_naive();
+        self.prune()
+    }
+
+    /// 이전 로그 파일 목록을 오래된 순서로 반환합니다.
+    pub fn rotated_files(&self) -> Vec<PathBuf> {
+        let Some(file_name) = self.path.file_name().and_then(|name| name.to_str()) else {
+            return Vec::new();
+        };
+        let prefix = format!("{}.", file_name);
+        let dir = match self.path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let mut files: Vec<PathBuf> = fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(&prefix))
+            })
+            .collect();
+        files.sort();
+        files
+    }
+
+    fn prune(&self) -> io::Result<()> {
+        if self.keep == 0 {
+            return Ok(());
+        }
+        let files = self.rotated_files();
+        for path in files.iter().take(files.len().saturating_sub(self.keep)) {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    /// `now` 시각에 로그를 기록합니다. 필요하면 먼저 파일을 바꿉니다.
+    pub fn write_at(&mut self, buf: &[u8], now: DateTime<Utc>) -> io::Result<()> {
+        if self.should_rotate(buf.len() as u64, now) {
+            self.rotate(now)?;
+        }
+        self.file.write_all(buf)?;
+        self.size += buf.len() as u64;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_at(buf, Utc::now())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// 최근 로그 줄을 메모리에 남겨두는 링 버퍼입니다. 가득 차면 가장 오래된 줄부터 버립니다.
+///
+/// # Example
+///
+/// ```rust
+/// use gj_stamptour::logging::LogBuffer;
+/// use log::Level;
+///
+/// let buffer = LogBuffer::new(2);
+/// buffer.push("[2024-10-05T05:00:00Z INFO  gj_stamptour] 시작\n");
+/// buffer.push("[2024-10-05T05:00:01Z WARN  gj_stamptour] 느림\n");
+/// buffer.push("[2024-10-05T05:00:02Z ERROR gj_stamptour] 실패\n");
+/// assert_eq!(buffer.tail(10, Level::Trace).len(), 2);
+/// assert_eq!(buffer.tail(10, Level::Error), vec!["[2024-10-05T05:00:02Z ERROR gj_stamptour] 실패"]);
+/// ```
+pub struct LogBuffer {
+    capacity: usize,
+    lines: Mutex<VecDeque<(Level, String)>>,
+}
+
+impl LogBuffer {
+    pub fn new(capacity: usize) -> Self {
+        LogBuffer {
+            capacity,
+            lines: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    /// 기록한 로그를 줄 단위로 넣습니다. 로그 수준은 `format_log` 형식의 두 번째 값으로 판별하며,
+    /// 여러 줄에 걸친 로그의 나머지 줄은 앞 줄의 수준을 따릅니다.
+    pub fn push(&self, text: &str) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut lines = self.lines.lock_or_recover();
+        for line in text.lines().filter(|line| !line.is_empty()) {
+            let level = line
+                .split_whitespace()
+                .nth(1)
+                .and_then(|level| Level::from_str(level).ok())
+                .or_else(|| lines.back().map(|(level, _)| *level))
+                .unwrap_or(Level::Info);
+            if lines.len() == self.capacity {
+                lines.pop_front();
+            }
+            lines.push_back((level, line.to_string()));
+        }
+    }
+
+    /// `level` 이상인 최근 로그를 최대 `count`줄까지 오래된 순서로 반환합니다.
+    pub fn tail(&self, count: usize, level: Level) -> Vec<String> {
+        let lines = self.lines.lock_or_recover();
+        let mut tail: Vec<String> = lines
+            .iter()
+            .rev()
+            .filter(|(line_level, _)| *line_level <= level)
+            .take(count)
+            .map(|(_, line)| line.clone())
+            .collect();
+        tail.reverse();
+        tail
+    }
+}
+
+static LOG_BUFFER: OnceLock<LogBuffer> = OnceLock::new();
+
+/// 서버의 최근 로그 버퍼입니다. `init`을 호출하기 전에는 기본 크기의 빈 버퍼를 반환합니다.
+pub fn recent_logs() -> &'static LogBuffer {
+    LOG_BUFFER.get_or_init(|| LogBuffer::new(LogConfig::default().buffer_lines))
+}
+
+/// 로그를 콘솔(stderr)과 최근 로그 버퍼, 설정했으면 파일에 함께 기록합니다. 파일 기록에 실패해도 콘솔 출력은 유지합니다.
+struct Tee {
+    file: Option<RotatingFile>,
+    failed: bool,
+}
+
+impl Write for Tee {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        io::stderr().write_all(buf)?;
+        recent_logs().push(&String::from_utf8_lossy(buf));
+        let Some(file) = self.file.as_mut() else {
+            return Ok(buf.len());
+        };
+        if let Err(e) = file.write_at(buf, Utc::now()) {
+            // 같은 오류를 반복해서 출력하지 않음
+            if !self.failed {
+                eprintln!("Failed to write log file : {}", e);
+            }
+            self.failed = true;
+        } else {
+            self.failed = false;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stderr().flush()?;
+        match self.file.as_mut() {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// 로거를 초기화하는 함수입니다. 요청 처리 중인 로그에는 요청 ID를 덧붙이며,
+/// 최근 로그를 `log.buffer_lines`줄까지 메모리에 남기고, `log.file`이 설정되어 있으면 콘솔과 함께 파일에도 기록합니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let log_config = read_config().map(|config| config.log).unwrap_or_default();
+/// logging::init(&log_config);
+/// ```
+pub fn init(config: &LogConfig) {
+    let mut builder =
+        env_logger::Builder::from_env(env_logger::Env::new().default_filter_or("info"));
+    builder.format(format_log);
+    LOG_BUFFER.get_or_init(|| LogBuffer::new(config.buffer_lines));
+
+    let file =
+        config
+            .file
+            .as_ref()
+            .and_then(|path| match RotatingFile::open(config, Path::new(path)) {
+                Ok(file) => Some(file),
+                Err(e) => {
+                    eprintln!("Cannot open log file {} : {}", path, e);
+                    None
+                }
+            });
+    builder.target(env_logger::Target::Pipe(Box::new(Tee {
+        file,
+        failed: false,
+    })));
+    builder.init();
+}
+
+/// 최근 로그 조회 요청의 쿼리입니다.
+#[derive(Deserialize, Debug)]
+pub struct LogQuery {
+    /// 반환할 최대 줄 수입니다. 기본 200줄입니다.
+    pub lines: Option<usize>,
+    /// 이 수준 이상의 로그만 반환합니다. (`error`, `warn`, `info`, `debug`, `trace`) 기본은 모든 로그입니다.
+    pub level: Option<String>,
+}
+
+/// SSH 없이 휴대폰으로 문제를 확인할 수 있도록 최근 로그를 반환하는 비동기 함수입니다.
+/// 직원 API와 같은 인증(`Authorization: Bearer <staff_token>`, 없으면 로컬 요청)이 필요합니다.
+///
+/// # Returns
+///
+/// 최근 로그가 오래된 순서의 텍스트로 200 OK 응답으로 반환됩니다.
+/// 직원 인증에 실패하면 401 Unauthorized 응답이, 로그 수준을 알 수 없으면 400 Bad Request 응답이 반환됩니다.
+#[get("/admin/logs")]
+pub async fn handle_logs(
+    req: HttpRequest,
+    query: Query<LogQuery>,
+    config: Data<Reloadable<Config>>,
+) -> Result<HttpResponse, AppError> {
+    if !is_staff(&req, &config.get()) {
+        warn!("Unauthorized access to the admin logs has been identified.");
+        return Err(AppError::Unauthorized);
+    }
+    let level = match query.level.as_deref().map(Level::from_str) {
+        None => Level::Trace,
+        Some(Ok(level)) => level,
+        Some(Err(_)) => return Err(AppError::Validation("Unknown log level".to_string())),
+    };
+
+    let mut body = recent_logs()
+        .tail(query.lines.unwrap_or(200), level)
+        .join("\n");
+    body.push('\n');
+    Ok(HttpResponse::Ok()
+        .content_type("text/plain; charset=utf-8")
+        .body(body))
+}
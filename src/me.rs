@@ -0,0 +1,209 @@
+use actix_web::{
+    cookie::Cookie, get, http::StatusCode, routes, web::Data, web::Json, HttpRequest, HttpResponse,
+};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+use crate::announcement::inject;
+use crate::config::Config;
+use crate::error::AppError;
+use crate::lock::MutexExt;
+use crate::persistence::{Dataset, Persister};
+use crate::progress::user_progress;
+use crate::retention::{delete_user, PersonalData};
+use crate::session::{CurrentUser, SESSION_COOKIE};
+use crate::state::{
+    AuditLog, AuditRecord, CompletionList, Reloadable, Stamp, StampHistory, StampIdList, TeamList,
+    UserList, UserStampList,
+};
+use crate::storage::{html_template, resource_path};
+use crate::template::{escape_html, render};
+
+/// `resources/html/me.html`이 없을 때 사용하는 내 스템프 페이지 템플릿입니다.
+const DEFAULT_TEMPLATE: &str = "<!DOCTYPE html>\n<html lang=\"ko\">\n<head><meta charset=\"utf-8\"><meta name=\"viewport\" content=\"width=device-width, initial-scale=1\"><title>%USER_NAME%님의 스템프</title>\n<style>body{font-family:sans-serif;margin:1.5em}ul{list-style:none;padding:0}li{display:flex;align-items:center;gap:.75em;margin:.5em 0}img{width:48px;height:48px;object-fit:cover}</style></head>\n<body>\n<p>%ANNOUNCEMENT%</p>\n<h1>%USER_NAME%님의 스템프</h1>\n<p>%COLLECTED_COUNT% / %TOTAL_COUNT%</p>\n<p>복구 코드: <strong>%USER_CODE%</strong></p>\n<h2>모은 스템프</h2>\n<ul>%COLLECTED_STAMPS%</ul>\n<h2>남은 부스</h2>\n<ul>%REMAINING_STAMPS%</ul>\n</body>\n</html>\n";
+
+/// 스템프 하나를 목록 항목으로 만듭니다. 이미지(`stampContent.image`)가 있으면 함께 표시합니다.
+fn stamp_item(stamp: &Stamp) -> String {
+    let image = stamp
+        .stampContent
+        .as_ref()
+        .map(|content| content.image.as_str())
+        .filter(|image| !image.is_empty())
+        .map(|image| format!("<img src=\"{}\" alt=\"\">", escape_html(image)))
+        .unwrap_or_default();
+    format!(
+        "<li>{}<span>{}</span> <small>{}</small></li>",
+        image,
+        escape_html(&stamp.stampName),
+        escape_html(&stamp.stampLocation)
+    )
+}
+
+/// 방문객이 모은 스템프와 남은 부스, 복구 코드를 보여주는 페이지를 반환하는 비동기 함수입니다.
+/// `resources/html/me.html` 템플릿을 사용하며, 없으면 내장 템플릿을 사용합니다.
+///
+/// 템플릿에서는 `%USER_NAME%`, `%USER_CODE%`, `%COLLECTED_COUNT%`, `%TOTAL_COUNT%` 자리표시자를 사용할 수 있습니다.
+/// `%COLLECTED_STAMPS%`와 `%REMAINING_STAMPS%`는 스템프마다 `<li>` 항목으로 바뀝니다.
+/// 복구 코드는 다른 기기에서 기록을 찾을 때 직원에게 보여주는 6자리 유저 코드입니다.
+///
+/// # Returns
+///
+/// 등록된 유저인 경우 형식화된 페이지가 담긴 200 OK 응답이 반환됩니다.
+/// 쿠키가 없거나 등록되지 않은 유저인 경우 401 Unauthorized 응답이 반환됩니다.
+#[get("/me")]
+pub async fn handle_me(
+    req: HttpRequest,
+    user: CurrentUser,
+    user_list: Data<Mutex<UserList>>,
+    stamp_id_list: Data<Reloadable<StampIdList>>,
+    stamp_history: Data<StampHistory>,
+    config: Data<Reloadable<Config>>,
+) -> Result<HttpResponse, AppError> {
+    let stamp_id_list = stamp_id_list.get();
+    let progress = user_progress(
+        &config.get(),
+        &stamp_id_list,
+        &stamp_history,
+        &user.user_id,
+        &user.user_name,
+    );
+    let user_code = user_list
+        .lock_or_recover()
+        .code_of(&user.user_id)
+        .cloned()
+        .unwrap_or_default();
+    let (collected, remaining): (Vec<&Stamp>, Vec<&Stamp>) = stamp_id_list
+        .visible()
+        .partition(|stamp| progress.collected.contains(&stamp.stampId));
+
+    // 번역된 템플릿도 읽을 수 있도록 파일이 있을 때만 `html_template`으로 읽음
+    let template = match resource_path("html", "me.html").is_file() {
+        true => html_template("me.html").await?,
+        false => DEFAULT_TEMPLATE.to_string(),
+    };
+    Ok(HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(inject(
+            &req,
+            render(
+                &template,
+                &[
+                    ("USER_NAME", &escape_html(&progress.user_name)),
+                    ("USER_CODE", &user_code),
+                    ("COLLECTED_COUNT", &progress.collected_count.to_string()),
+                    ("TOTAL_COUNT", &progress.total_count.to_string()),
+                    (
+                        "COLLECTED_STAMPS",
+                        &collected.into_iter().map(stamp_item).collect::<String>(),
+                    ),
+                    (
+                        "REMAINING_STAMPS",
+                        &remaining.into_iter().map(stamp_item).collect::<String>(),
+                    ),
+                ],
+            ),
+        )))
+}
+
+/// 개인정보 삭제 요청입니다. 실수로 삭제하지 않도록 `confirm`이 `true`여야 합니다.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct DeleteRequest {
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+/// 요청한 유저의 등록 정보와 기록을 지우는 비동기 함수입니다.
+/// 스템프 기록은 부스별 통계를 위해 이름 없는 익명 ID로 바꾸어 남기고, `user_id` 쿠키를 지웁니다.
+///
+/// # Arguments
+///
+/// * `confirm` - `{"confirm": true}` 형식의 확인 요청입니다.
+///
+/// # Returns
+///
+/// 삭제에 성공하면 200 OK 응답이 반환됩니다.
+/// 확인하지 않은 요청은 400 Bad Request, 쿠키가 없거나 등록되지 않은 유저는 401 Unauthorized 응답이 반환됩니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// fetch("/me/delete", { method: "POST", headers: { "Content-Type": "application/json" }, body: '{"confirm": true}' });
+/// ```
+#[allow(clippy::too_many_arguments)]
+#[routes]
+#[post("/api/v1/me/delete")]
+#[post("/me/delete")]
+pub async fn handle_delete_me(
+    user: CurrentUser,
+    confirm: Json<DeleteRequest>,
+    user_list: Data<Mutex<UserList>>,
+    user_stamp_list: Data<Mutex<UserStampList>>,
+    stamp_history: Data<StampHistory>,
+    completions: Data<Mutex<CompletionList>>,
+    teams: Data<Mutex<TeamList>>,
+    audit_log: Data<Mutex<AuditLog>>,
+    persister: Data<Persister>,
+) -> Result<HttpResponse, AppError> {
+    let user_id = user.user_id;
+    if !confirm.confirm {
+        return Err(AppError::Rejected(
+            StatusCode::BAD_REQUEST,
+            serde_json::json!({
+                "error": "Deletion must be confirmed",
+                "hint": "Send {\"confirm\": true} to delete your registration and stamp records"
+            }),
+        ));
+    }
+
+    let alias = {
+        let mut user_list = user_list.lock_or_recover();
+        let mut user_stamp_list = user_stamp_list.lock_or_recover();
+        let mut completions = completions.lock_or_recover();
+        let mut teams = teams.lock_or_recover();
+        let mut audit_log = audit_log.lock_or_recover();
+        let alias = delete_user(
+            PersonalData {
+                user_list: &mut user_list,
+                user_stamp_list: &mut user_stamp_list,
+                stamp_history: &stamp_history,
+                completions: &mut completions,
+                teams: &mut teams,
+                audit_log: &mut audit_log,
+            },
+            &user_id,
+        );
+        if let Some(alias) = &alias {
+            audit_log.entries.push(AuditRecord {
+                action: "delete".to_string(),
+                user_id: alias.clone(),
+                stamp_id: None,
+                actor: "self".to_string(),
+                reason: "self-service deletion request".to_string(),
+                timestamp: chrono::prelude::Utc::now().to_string(),
+            });
+        }
+        alias
+    };
+
+    let Some(alias) = alias else {
+        warn!("Unregistered user {} requested deletion.", user_id);
+        return Err(AppError::Unauthorized);
+    };
+    info!("User deleted their data (history kept as {}).", alias);
+    // 지운 개인정보가 파일에 남지 않도록 관련 파일을 모두 다시 저장
+    persister.mark(&[
+        Dataset::UserStatus,
+        Dataset::StampStatus,
+        Dataset::CompletionStatus,
+        Dataset::TeamStatus,
+        Dataset::AuditLog,
+    ]);
+
+    let mut removal = Cookie::new(SESSION_COOKIE, "");
+    removal.set_path("/");
+    removal.make_removal();
+    Ok(HttpResponse::Ok()
+        .cookie(removal)
+        .json(serde_json::json!({ "deleted": true })))
+}
@@ -0,0 +1,248 @@
+use std::collections::BTreeSet;
+
+use crate::retention::PersonalData;
+use crate::state::{AuditRecord, CompletionList, StampHistory, TeamList, UserList};
+
+/// 중복 등록한 유저를 합친 결과입니다. 미리보기(`--dry-run`)에서도 같은 값을 계산합니다.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MergeReport {
+    /// 남기는 유저 ID입니다.
+    pub target: String,
+    /// 지우는 중복 유저 ID입니다.
+    pub duplicate: String,
+    /// 남기는 유저에게 옮긴 스템프 기록 수입니다.
+    pub moved_entries: usize,
+    /// 합친 뒤 남기는 유저가 새로 갖게 되는 스템프 ID입니다.
+    pub gained_stamps: BTreeSet<String>,
+    /// 중복 유저 대신 남기는 유저가 들어가는 팀 코드입니다.
+    pub moved_team: Option<String>,
+}
+
+impl MergeReport {
+    /// 관리자 명령 출력용 요약입니다.
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "{} history entries, new stamps: {}",
+            self.moved_entries,
+            match self.gained_stamps.is_empty() {
+                true => "none".to_string(),
+                false => self
+                    .gained_stamps
+                    .iter()
+                    .cloned()
+                    .collect::<Vec<String>>()
+                    .join(", "),
+            }
+        );
+        if let Some(team_code) = &self.moved_team {
+            summary.push_str(&format!(", joins team {}", team_code));
+        }
+        summary
+    }
+}
+
+/// `merge [--dry-run] <남길 유저> <중복 유저>` 관리자 명령의 인수를 해석하는 함수입니다.
+///
+/// # Returns
+///
+/// 미리보기 여부와 두 유저를 반환합니다. 유저가 두 명이 아니면 `None`을 반환합니다.
+///
+/// # Example
+///
+/// ```rust
+/// use gj_stamptour::merge::parse_merge;
+///
+/// assert_eq!(parse_merge("u1 u2"), Some((false, "u1", "u2")));
+/// assert_eq!(parse_merge("--dry-run ABC234 u2"), Some((true, "ABC234", "u2")));
+/// assert_eq!(parse_merge("u1"), None);
+/// ```
+pub fn parse_merge(args: &str) -> Option<(bool, &str, &str)> {
+    let mut words: Vec<&str> = args.split_whitespace().collect();
+    let dry_run = words.first() == Some(&"--dry-run");
+    if dry_run {
+        words.remove(0);
+    }
+    match words[..] {
+        [target, duplicate] => Some((dry_run, target, duplicate)),
+        _ => None,
+    }
+}
+
+/// 두 유저를 합쳤을 때의 결과를 바꾸지 않고 계산하는 함수입니다.
+///
+/// # Arguments
+///
+/// * `user_list` - 유저 목록입니다.
+/// * `stamp_history` - 스템프 기록입니다.
+/// * `teams` - 팀 목록입니다.
+/// * `target` - 남기는 유저 ID입니다.
+/// * `duplicate` - 지우는 중복 유저 ID입니다.
+///
+/// # Returns
+///
+/// 두 유저가 모두 등록되어 있고 서로 다르면 `MergeReport`를, 아니면 오류 메시지를 반환합니다.
+pub fn preview_merge(
+    user_list: &UserList,
+    stamp_history: &StampHistory,
+    teams: &TeamList,
+    target: &str,
+    duplicate: &str,
+) -> Result<MergeReport, String> {
+    for user_id in [target, duplicate] {
+        if !user_list.users.contains_key(user_id) {
+            return Err(format!("User {} not found", user_id));
+        }
+    }
+    if target == duplicate {
+        return Err("Cannot merge a user into itself".to_string());
+    }
+
+    let target_collected = stamp_history.collected_by(target);
+    let moved_entries = stamp_history
+        .to_map()
+        .values()
+        .flatten()
+        .filter(|entry| entry.user_id == duplicate)
+        .count();
+    let moved_team = match teams.team_of(target) {
+        Some(_) => None,
+        None => teams.team_of(duplicate).cloned(),
+    };
+
+    Ok(MergeReport {
+        target: target.to_string(),
+        duplicate: duplicate.to_string(),
+        moved_entries,
+        gained_stamps: stamp_history
+            .collected_by(duplicate)
+            .difference(&target_collected)
+            .cloned()
+            .collect(),
+        moved_team,
+    })
+}
+
+/// 중복 유저의 완주 기록, 업적, 완주 코드, 경품 수령 기록을 남기는 유저에게 옮깁니다.
+/// 같은 등급이나 업적은 먼저 달성한 기록을 남기고, 완주 코드는 남기는 유저의 코드가 있으면 그 코드를 사용합니다.
+fn merge_completions(completions: &mut CompletionList, target: &str, duplicate: &str) {
+    if let Some(records) = completions.completions.remove(duplicate) {
+        let target_records = completions
+            .completions
+            .entry(target.to_string())
+            .or_default();
+        for record in records {
+            match target_records
+                .iter_mut()
+                .find(|existing| existing.tier_name == record.tier_name)
+            {
+                Some(existing) if record.timestamp < existing.timestamp => *existing = record,
+                Some(_) => {}
+                None => target_records.push(record),
+            }
+        }
+    }
+    if let Some(records) = completions.achievements.remove(duplicate) {
+        let target_records = completions
+            .achievements
+            .entry(target.to_string())
+            .or_default();
+        for record in records {
+            match target_records
+                .iter_mut()
+                .find(|existing| existing.achievement_id == record.achievement_id)
+            {
+                Some(existing) if record.timestamp < existing.timestamp => *existing = record,
+                Some(_) => {}
+                None => target_records.push(record),
+            }
+        }
+    }
+    if let Some(code) = completions.codes.remove(duplicate) {
+        completions.codes.entry(target.to_string()).or_insert(code);
+    }
+    for record in completions
+        .redemptions
+        .iter_mut()
+        .filter(|record| record.user_id == duplicate)
+    {
+        record.user_id = target.to_string();
+    }
+}
+
+/// 중복 등록한 유저를 남기는 유저에게 합치는 함수입니다.
+///
+/// 중복 유저의 스템프 기록은 남기는 유저의 이름으로 옮겨 모은 스템프가 합쳐지고, 부스별 발급 수는 그대로 유지됩니다.
+/// 완주 기록과 경품 수령 기록도 옮긴 뒤 중복 유저의 등록 정보, 조회 코드, 확인 대기 스템프를 지웁니다.
+/// 남기는 유저가 팀에 없으면 중복 유저가 있던 팀에 대신 들어갑니다.
+///
+/// # Arguments
+///
+/// * `data` - 잠근 참가자 데이터 묶음입니다.
+/// * `target` - 남기는 유저 ID입니다.
+/// * `duplicate` - 지우는 중복 유저 ID입니다.
+/// * `actor` - 감사 기록에 남길 작업자 이름입니다.
+///
+/// # Returns
+///
+/// 합친 결과를 담은 `MergeReport`를 반환합니다. 유저를 찾을 수 없으면 아무것도 바꾸지 않고 오류 메시지를 반환합니다.
+pub fn merge_users(
+    data: PersonalData,
+    target: &str,
+    duplicate: &str,
+    actor: &str,
+) -> Result<MergeReport, String> {
+    let report = preview_merge(
+        data.user_list,
+        data.stamp_history,
+        data.teams,
+        target,
+        duplicate,
+    )?;
+    let target_name = data.user_list.users[target].clone();
+
+    data.stamp_history.update_all(|_, entries| {
+        for entry in entries
+            .iter_mut()
+            .filter(|entry| entry.user_id == duplicate)
+        {
+            entry.user_id = target.to_string();
+            entry.user_name = target_name.clone();
+        }
+    });
+    merge_completions(data.completions, target, duplicate);
+    for team in data.teams.teams.values_mut() {
+        match team.members.iter().position(|member| member == duplicate) {
+            Some(index) if report.moved_team.is_some() => team.members[index] = target.to_string(),
+            Some(_) => team.members.retain(|member| member != duplicate),
+            None => {}
+        }
+    }
+    if let Some(pending) = data.user_stamp_list.user_stamp_list.remove(duplicate) {
+        data.user_stamp_list
+            .user_stamp_list
+            .entry(target.to_string())
+            .or_insert(pending);
+    }
+    for record in data
+        .audit_log
+        .entries
+        .iter_mut()
+        .filter(|record| record.user_id == duplicate)
+    {
+        record.user_id = target.to_string();
+    }
+    data.user_list.users.remove(duplicate);
+    data.user_list
+        .codes
+        .retain(|_, code_user_id| code_user_id != duplicate);
+
+    data.audit_log.entries.push(AuditRecord {
+        action: "merge".to_string(),
+        user_id: target.to_string(),
+        stamp_id: None,
+        actor: actor.to_string(),
+        reason: format!("merged duplicate user {}", duplicate),
+        timestamp: chrono::prelude::Utc::now().to_string(),
+    });
+    Ok(report)
+}
@@ -0,0 +1,382 @@
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    get,
+    middleware::Next,
+    web::Data,
+    HttpRequest, HttpResponse,
+};
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    ops::{Deref, DerefMut},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex, MutexGuard,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::error::AppError;
+use crate::lock::MutexExt;
+
+/// 요청 처리 시간 히스토그램의 구간 경계(초)입니다.
+const REQUEST_BUCKETS: [f64; 12] = [
+    0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// 락 대기 시간 히스토그램의 구간 경계(초)입니다. 대부분 마이크로초 단위이므로 더 촘촘합니다.
+const LOCK_BUCKETS: [f64; 12] = [
+    0.00001, 0.00005, 0.0001, 0.0005, 0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.5, 1.0,
+];
+
+/// 데이터베이스 파일 직렬화와 쓰기 시간 히스토그램의 구간 경계(초)입니다.
+const SAVE_BUCKETS: [f64; 10] = [0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 5.0];
+
+/// Prometheus 형식의 누적 히스토그램입니다.
+#[derive(Debug, Clone)]
+pub struct Histogram {
+    buckets: &'static [f64],
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new(buckets: &'static [f64]) -> Self {
+        Histogram {
+            buckets,
+            counts: vec![0; buckets.len()],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    /// 측정값 하나를 기록합니다.
+    pub fn observe(&mut self, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        for (bucket, count) in self.buckets.iter().zip(self.counts.iter_mut()) {
+            if seconds <= *bucket {
+                *count += 1;
+            }
+        }
+        self.sum += seconds;
+        self.count += 1;
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// `{name}_bucket`, `{name}_sum`, `{name}_count` 줄을 출력합니다.
+    fn write(&self, out: &mut String, name: &str, labels: &str) {
+        for (bucket, count) in self.buckets.iter().zip(self.counts.iter()) {
+            let _ = writeln!(out, "{name}_bucket{{{labels},le=\"{bucket}\"}} {count}");
+        }
+        let _ = writeln!(out, "{name}_bucket{{{labels},le=\"+Inf\"}} {}", self.count);
+        let _ = writeln!(out, "{name}_sum{{{labels}}} {}", self.sum);
+        let _ = writeln!(out, "{name}_count{{{labels}}} {}", self.count);
+    }
+}
+
+/// 데이터베이스 파일 하나의 저장 기록입니다.
+#[derive(Debug, Clone)]
+pub struct SaveStats {
+    /// 잠그고 복사하여 직렬화하는 데 걸린 시간입니다.
+    pub serialize: Histogram,
+    /// 파일에 쓰는 데 걸린 시간입니다.
+    pub write: Histogram,
+    /// 지금까지 쓴 바이트 수입니다.
+    pub bytes_written: u64,
+}
+
+impl Default for SaveStats {
+    fn default() -> Self {
+        SaveStats {
+            serialize: Histogram::new(&SAVE_BUCKETS),
+            write: Histogram::new(&SAVE_BUCKETS),
+            bytes_written: 0,
+        }
+    }
+}
+
+/// 경로별 요청 처리 시간, 공유 상태 락 대기와 점유 시간, 데이터베이스 저장 시간을 모으는 구조체입니다.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    /// `(메서드, 경로 패턴)`별 요청 처리 시간입니다.
+    requests: Mutex<BTreeMap<(String, String), Histogram>>,
+    /// 락 이름별 대기 시간입니다.
+    lock_waits: Mutex<BTreeMap<&'static str, Histogram>>,
+    /// 락 이름별로 락을 잡고 있던 시간입니다.
+    lock_holds: Mutex<BTreeMap<&'static str, Histogram>>,
+    /// 데이터베이스 파일별 저장 기록입니다.
+    saves: Mutex<BTreeMap<&'static str, SaveStats>>,
+    /// 지금 처리 중인 요청 수입니다. 종료할 때 요청이 끝나기를 기다리는 데 사용합니다.
+    in_flight: AtomicUsize,
+}
+
+/// 처리 중인 요청 수를 세는 가드입니다. 요청이 끝나거나 취소되어 놓으면 수를 줄입니다.
+struct InFlight(Data<Metrics>);
+
+impl InFlight {
+    fn new(metrics: Data<Metrics>) -> Self {
+        metrics.in_flight.fetch_add(1, Ordering::SeqCst);
+        InFlight(metrics)
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.0.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// `Metrics::lock`이 반환하는 뮤텍스 가드입니다. 놓을 때 락을 잡고 있던 시간을 기록합니다.
+pub struct TimedGuard<'a, T> {
+    guard: MutexGuard<'a, T>,
+    metrics: &'a Metrics,
+    lock: &'static str,
+    acquired: Instant,
+}
+
+impl<T> Deref for TimedGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<T> DerefMut for TimedGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.guard
+    }
+}
+
+impl<T> Drop for TimedGuard<'_, T> {
+    fn drop(&mut self) {
+        self.metrics
+            .observe_lock_hold(self.lock, self.acquired.elapsed());
+    }
+}
+
+impl Metrics {
+    /// 요청 처리 시간을 기록합니다.
+    pub fn observe_request(&self, method: &str, route: &str, duration: Duration) {
+        self.requests
+            .lock_or_recover()
+            .entry((method.to_string(), route.to_string()))
+            .or_insert_with(|| Histogram::new(&REQUEST_BUCKETS))
+            .observe(duration);
+    }
+
+    /// 락 대기 시간을 기록합니다.
+    pub fn observe_lock_wait(&self, lock: &'static str, duration: Duration) {
+        self.lock_waits
+            .lock_or_recover()
+            .entry(lock)
+            .or_insert_with(|| Histogram::new(&LOCK_BUCKETS))
+            .observe(duration);
+    }
+
+    /// 락을 잡고 있던 시간을 기록합니다.
+    pub fn observe_lock_hold(&self, lock: &'static str, duration: Duration) {
+        self.lock_holds
+            .lock_or_recover()
+            .entry(lock)
+            .or_insert_with(|| Histogram::new(&LOCK_BUCKETS))
+            .observe(duration);
+    }
+
+    /// 데이터베이스 파일을 직렬화하는 데 걸린 시간을 기록합니다.
+    pub fn observe_serialize(&self, file_name: &'static str, duration: Duration) {
+        self.saves
+            .lock_or_recover()
+            .entry(file_name)
+            .or_default()
+            .serialize
+            .observe(duration);
+    }
+
+    /// 데이터베이스 파일을 쓰는 데 걸린 시간과 쓴 바이트 수를 기록합니다.
+    pub fn observe_write(&self, file_name: &'static str, duration: Duration, bytes: usize) {
+        let mut saves = self.saves.lock_or_recover();
+        let stats = saves.entry(file_name).or_default();
+        stats.write.observe(duration);
+        stats.bytes_written += bytes as u64;
+    }
+
+    /// 데이터베이스 파일의 저장 기록을 반환합니다.
+    pub fn save_stats(&self, file_name: &str) -> Option<SaveStats> {
+        self.saves.lock_or_recover().get(file_name).cloned()
+    }
+
+    /// 락을 얻을 때까지 기다린 시간을 기록하면서 뮤텍스를 잠그는 함수입니다.
+    /// 반환된 가드를 놓을 때 락을 잡고 있던 시간도 기록합니다.
+    ///
+    /// # Arguments
+    ///
+    /// * `lock` - 메트릭에 표시할 락 이름입니다.
+    /// * `mutex` - 잠글 뮤텍스입니다.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let mut user_list = metrics.lock("user_list", &user_list);
+    /// ```
+    pub fn lock<'a, T>(&'a self, lock: &'static str, mutex: &'a Mutex<T>) -> TimedGuard<'a, T> {
+        let start = Instant::now();
+        let guard = mutex.lock_or_recover();
+        let acquired = Instant::now();
+        self.observe_lock_wait(lock, acquired - start);
+        TimedGuard {
+            guard,
+            metrics: self,
+            lock,
+            acquired,
+        }
+    }
+
+    /// 경로별 요청 처리 시간 히스토그램을 반환합니다.
+    pub fn request_histogram(&self, method: &str, route: &str) -> Option<Histogram> {
+        self.requests
+            .lock_or_recover()
+            .get(&(method.to_string(), route.to_string()))
+            .cloned()
+    }
+
+    /// 지금 처리 중인 요청 수를 반환합니다.
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// 모든 메트릭을 Prometheus 텍스트 형식으로 변환합니다.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        out.push_str("# HELP stamptour_http_requests_in_flight HTTP requests being processed.\n");
+        out.push_str("# TYPE stamptour_http_requests_in_flight gauge\n");
+        let _ = writeln!(
+            out,
+            "stamptour_http_requests_in_flight {}",
+            self.in_flight()
+        );
+
+        out.push_str(
+            "# HELP stamptour_http_request_duration_seconds HTTP request latency by route.\n",
+        );
+        out.push_str("# TYPE stamptour_http_request_duration_seconds histogram\n");
+        for ((method, route), histogram) in self.requests.lock_or_recover().iter() {
+            histogram.write(
+                &mut out,
+                "stamptour_http_request_duration_seconds",
+                &format!("method=\"{method}\",route=\"{route}\""),
+            );
+        }
+
+        out.push_str(
+            "# HELP stamptour_lock_wait_seconds Time spent waiting for shared state locks.\n",
+        );
+        out.push_str("# TYPE stamptour_lock_wait_seconds histogram\n");
+        for (lock, histogram) in self.lock_waits.lock_or_recover().iter() {
+            histogram.write(
+                &mut out,
+                "stamptour_lock_wait_seconds",
+                &format!("lock=\"{lock}\""),
+            );
+        }
+
+        out.push_str("# HELP stamptour_lock_hold_seconds Time shared state locks were held.\n");
+        out.push_str("# TYPE stamptour_lock_hold_seconds histogram\n");
+        for (lock, histogram) in self.lock_holds.lock_or_recover().iter() {
+            histogram.write(
+                &mut out,
+                "stamptour_lock_hold_seconds",
+                &format!("lock=\"{lock}\""),
+            );
+        }
+
+        let saves = self.saves.lock_or_recover();
+        out.push_str(
+            "# HELP stamptour_save_serialize_seconds Time spent copying and serializing database files.\n",
+        );
+        out.push_str("# TYPE stamptour_save_serialize_seconds histogram\n");
+        for (file_name, stats) in saves.iter() {
+            stats.serialize.write(
+                &mut out,
+                "stamptour_save_serialize_seconds",
+                &format!("file=\"{file_name}\""),
+            );
+        }
+        out.push_str("# HELP stamptour_save_write_seconds Time spent writing database files.\n");
+        out.push_str("# TYPE stamptour_save_write_seconds histogram\n");
+        for (file_name, stats) in saves.iter() {
+            stats.write.write(
+                &mut out,
+                "stamptour_save_write_seconds",
+                &format!("file=\"{file_name}\""),
+            );
+        }
+        out.push_str("# HELP stamptour_save_bytes_total Bytes written to database files.\n");
+        out.push_str("# TYPE stamptour_save_bytes_total counter\n");
+        for (file_name, stats) in saves.iter() {
+            let _ = writeln!(
+                out,
+                "stamptour_save_bytes_total{{file=\"{file_name}\"}} {}",
+                stats.bytes_written
+            );
+        }
+        out
+    }
+}
+
+/// 요청마다 처리 시간을 측정하여 경로 패턴별로 기록하는 미들웨어입니다.
+/// 등록되지 않은 경로는 라벨 수가 늘어나지 않도록 `default`로 묶습니다. 처리 중인 요청 수도 함께 셉니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// App::new()
+///     .wrap(actix_web::middleware::from_fn(record_latency))
+///     .configure(|cfg| state.register(cfg))
+///     .configure(routes);
+/// ```
+pub async fn record_latency(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let start = Instant::now();
+    let metrics = req.app_data::<Data<Metrics>>().cloned();
+    let _in_flight = metrics.clone().map(InFlight::new);
+    let method = req.method().to_string();
+
+    let res = next.call(req).await?;
+
+    if let Some(metrics) = metrics {
+        let route = res
+            .request()
+            .match_pattern()
+            .unwrap_or_else(|| "default".to_string());
+        metrics.observe_request(&method, &route, start.elapsed());
+    }
+    Ok(res)
+}
+
+/// 수집한 메트릭을 Prometheus 텍스트 형식으로 반환하는 비동기 함수입니다.
+///
+/// # Returns
+///
+/// 관리자 페이지와 같이 로컬에서 요청한 경우에만 200 OK 응답이, 그 외에는 401 Unauthorized 응답이 반환됩니다.
+#[get("/metrics")]
+pub async fn handle_metrics(
+    req: HttpRequest,
+    metrics: Data<Metrics>,
+) -> Result<HttpResponse, AppError> {
+    if !req.peer_addr().is_some_and(|addr| addr.ip().is_loopback()) {
+        return Err(AppError::Unauthorized);
+    }
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4")
+        .body(metrics.render()))
+}
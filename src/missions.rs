@@ -0,0 +1,183 @@
+use actix_web::{get, web::Data, HttpRequest, HttpResponse};
+use chrono::{Local, NaiveDate};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use crate::bundle::event_path;
+use crate::error::AppError;
+use crate::features::{require_feature, Feature};
+use crate::report::parse_timestamp;
+use crate::session::CurrentUser;
+use crate::state::{Reloadable, StampHistory};
+
+/// `resources/missions.json`에서 읽어오는 하루 미션 목록입니다. 행사 기간 동안 매일 다시 찾아오도록
+/// 날짜별로 다른 미션을 정할 수 있습니다.
+///
+/// # Example
+///
+/// ```json
+/// {
+///   "missions": [
+///     { "mission_id": "day1-three", "name": "오늘 스템프 3개 모으기", "dates": ["2024-10-18"], "required": 3 },
+///     { "mission_id": "food", "name": "먹거리 부스 방문", "stamp_ids": ["food-1", "food-2"] }
+///   ]
+/// }
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct MissionList {
+    pub missions: Vec<Mission>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct Mission {
+    pub mission_id: String,
+    pub name: String,
+    pub description: String,
+    /// 미션을 진행하는 날짜(`YYYY-MM-DD`, 서버 시간대) 목록입니다. 비어있으면 매일 진행합니다.
+    pub dates: Vec<String>,
+    /// 그날 찍어야 하는 스템프 수입니다. 없으면 `stamp_ids`를 모두 찍어야 합니다.
+    pub required: Option<usize>,
+    /// 미션에 포함되는 스템프 ID 목록입니다. 비어있으면 모든 스템프를 셉니다.
+    pub stamp_ids: Vec<String>,
+}
+
+impl Mission {
+    /// 주어진 날짜에 진행하는 미션인지 확인합니다.
+    pub fn is_active(&self, date: NaiveDate) -> bool {
+        self.dates.is_empty()
+            || self
+                .dates
+                .iter()
+                .any(|d| NaiveDate::parse_from_str(d.trim(), "%Y-%m-%d") == Ok(date))
+    }
+
+    /// 미션을 달성하는 데 필요한 스템프 수입니다.
+    pub fn required(&self) -> usize {
+        self.required.unwrap_or(self.stamp_ids.len()).max(1)
+    }
+}
+
+/// `/api/v1/missions`로 반환되는 미션 하나의 오늘 진행 상황입니다.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MissionStatus {
+    pub mission_id: String,
+    pub name: String,
+    pub description: String,
+    /// 오늘 찍은 미션 스템프 수입니다. 같은 스템프는 한 번만 셉니다.
+    pub collected: usize,
+    pub required: usize,
+    pub completed: bool,
+}
+
+/// `resources/missions.json`을 읽어 `MissionList`로 변환하는 함수입니다. 서버 실행 중 다시 불러올 때 사용합니다.
+///
+/// # Returns
+///
+/// 파일이 없으면 빈 미션 목록을, 파싱에 실패하면 오류 메시지를 반환합니다.
+pub fn read_missions() -> Result<MissionList, String> {
+    match std::fs::read_to_string(event_path("missions.json")) {
+        Ok(file_content) => {
+            let missions: MissionList =
+                serde_json::from_str(&file_content).map_err(|e| e.to_string())?;
+            info!("Loaded {} missions", missions.missions.len());
+            Ok(missions)
+        }
+        Err(_) => Ok(MissionList::default()),
+    }
+}
+
+/// 서버를 시작할 때 미션 목록을 읽는 함수입니다. 파일이 잘못되었으면 경고를 남기고 미션 없이 시작합니다.
+pub fn load_missions() -> MissionList {
+    read_missions().unwrap_or_else(|e| {
+        warn!("missions.json is invalid, missions disabled: {}", e);
+        MissionList::default()
+    })
+}
+
+/// 유저가 주어진 날짜에 찍은 스템프 ID 목록을 찾는 함수입니다.
+pub fn collected_on(
+    stamp_history: &StampHistory,
+    user_id: &str,
+    date: NaiveDate,
+) -> BTreeSet<String> {
+    stamp_history
+        .to_map()
+        .into_iter()
+        .filter(|(_, entries)| {
+            entries.iter().any(|entry| {
+                entry.user_id == user_id
+                    && parse_timestamp(&entry.timestamp).is_some_and(|t| t.date_naive() == date)
+            })
+        })
+        .map(|(stamp_id, _)| stamp_id)
+        .collect()
+}
+
+/// 주어진 날짜에 진행하는 미션별 유저의 진행 상황을 계산하는 함수입니다.
+///
+/// # Arguments
+///
+/// * `missions` - 전체 미션 목록입니다.
+/// * `stamp_history` - 스템프 기록입니다.
+/// * `user_id` - 진행 상황을 계산할 유저 ID입니다.
+/// * `date` - 기준 날짜(서버 시간대)입니다.
+///
+/// # Returns
+///
+/// 그날 진행하는 미션만 `missions.json`에 적힌 순서대로 반환합니다.
+pub fn mission_status(
+    missions: &MissionList,
+    stamp_history: &StampHistory,
+    user_id: &str,
+    date: NaiveDate,
+) -> Vec<MissionStatus> {
+    let collected = collected_on(stamp_history, user_id, date);
+    missions
+        .missions
+        .iter()
+        .filter(|mission| mission.is_active(date))
+        .map(|mission| {
+            let collected = collected
+                .iter()
+                .filter(|stamp_id| {
+                    mission.stamp_ids.is_empty() || mission.stamp_ids.contains(stamp_id)
+                })
+                .count();
+            let required = mission.required();
+            MissionStatus {
+                mission_id: mission.mission_id.clone(),
+                name: mission.name.clone(),
+                description: mission.description.clone(),
+                collected: collected.min(required),
+                required,
+                completed: collected >= required,
+            }
+        })
+        .collect()
+}
+
+/// 오늘 진행하는 미션과 현재 유저의 진행 상황을 JSON으로 반환하는 비동기 함수입니다.
+///
+/// # Returns
+///
+/// 등록된 유저인 경우 `MissionStatus` 목록을 담은 200 OK 응답이 반환됩니다.
+/// 쿠키가 없거나 등록되지 않은 유저인 경우 401 Unauthorized 응답이 반환됩니다.
+/// 미션 기능(`features.missions`)이 꺼져 있으면 503 응답이 반환됩니다.
+#[get("/api/v1/missions")]
+pub async fn handle_missions(
+    user: CurrentUser,
+    missions: Data<Reloadable<MissionList>>,
+    stamp_history: Data<StampHistory>,
+    req: HttpRequest,
+) -> Result<HttpResponse, AppError> {
+    require_feature(&req, Feature::Missions)?;
+    Ok(HttpResponse::Ok().json(mission_status(
+        &missions.get(),
+        &stamp_history,
+        &user.user_id,
+        Local::now().date_naive(),
+    )))
+}
@@ -0,0 +1,197 @@
+use actix_web::{
+    get,
+    web::{Data, Query},
+    HttpRequest, HttpResponse,
+};
+use log::warn;
+use openssl::memcmp;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, sync::Mutex};
+
+use crate::backup::{hmac_sha256, to_hex};
+use crate::config::Config;
+use crate::cooldown::ScanCooldown;
+use crate::error::AppError;
+use crate::handlers::{accept_scan, redirect_to_stamp};
+use crate::lock::MutexExt;
+use crate::metrics::Metrics;
+use crate::persistence::{Dataset, Persister};
+use crate::session::CurrentUser;
+use crate::state::{
+    NfcCounterList, Reloadable, StampHistory, StampIdList, TourStatus, UserStampList,
+};
+use crate::tour::{handle_tour_ended, is_closed};
+
+/// `resources/config.json`의 `nfc` 항목입니다.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct NfcConfig {
+    /// 태그의 MAC 계산에 사용하는 비밀 값입니다. 없으면 `/nfc/check`는 모든 태그를 거절합니다.
+    pub secret: Option<String>,
+    /// 태그 UID(16진수)별 스템프 ID입니다. (예: `{"04A1B2C3D4E5F6": "library"}`)
+    pub tags: BTreeMap<String, String>,
+}
+
+/// NFC 태그가 NDEF URI 레코드로 보내는 `/nfc/check` 주소의 쿼리 문자열입니다.
+#[derive(Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct NfcPayload {
+    /// 태그 UID(16진수)입니다.
+    pub uid: String,
+    /// 태그를 읽을 때마다 1씩 늘어나는 읽기 카운터(16진수 6자리)입니다.
+    pub ctr: String,
+    /// `tag_mac`으로 계산한 MAC(16진수 16자리)입니다.
+    pub mac: String,
+}
+
+/// 태그 UID와 읽기 카운터의 MAC을 계산합니다. `{UID}:{카운터}`의 HMAC-SHA256 앞 8바이트를 16진수로 적은 값이며,
+/// UID와 카운터는 대문자 16진수로 적습니다. 태그에 주소를 기록하는 장비도 같은 방법으로 계산해야 합니다.
+///
+/// # Example
+///
+/// ```rust
+/// use gj_stamptour::nfc::tag_mac;
+///
+/// let mac = tag_mac("secret", "04A1B2C3D4E5F6", 42);
+/// assert_eq!(mac.len(), 16);
+/// assert_eq!(mac, tag_mac("secret", "04a1b2c3d4e5f6", 42));
+/// assert_ne!(mac, tag_mac("secret", "04A1B2C3D4E5F6", 43));
+/// ```
+pub fn tag_mac(secret: &str, uid: &str, counter: u32) -> String {
+    let message = format!("{}:{:06X}", uid.to_uppercase(), counter);
+    to_hex(&hmac_sha256(secret.as_bytes(), &message)[..8])
+}
+
+/// `/nfc/check` 쿼리 문자열을 확인하여 태그의 스템프 ID와 읽기 카운터를 꺼내는 함수입니다.
+/// 이전에 받은 카운터보다 큰지는 호출한 쪽에서 확인합니다.
+///
+/// # Returns
+///
+/// 형식이 맞고, 등록된 태그이고, MAC이 맞으면 `Ok((UID, 스템프 ID, 카운터))`를 반환합니다.
+/// 그 외에는 로그에 남길 오류 메시지를 반환합니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let (uid, stamp_id, counter) = verify_tag(&config.nfc, req.query_string())?;
+/// ```
+pub fn verify_tag<'a>(
+    config: &'a NfcConfig,
+    query: &str,
+) -> Result<(String, &'a str, u32), String> {
+    let Some(secret) = config.secret.as_deref() else {
+        return Err("NFC secret is not configured".to_string());
+    };
+    let payload = Query::<NfcPayload>::from_query(query)
+        .map(Query::into_inner)
+        .unwrap_or_default();
+
+    let uid = payload.uid.to_uppercase();
+    if uid.is_empty() || !uid.len().is_multiple_of(2) || !uid.chars().all(|c| c.is_ascii_hexdigit())
+    {
+        return Err(format!("Invalid tag UID {:?}", payload.uid));
+    }
+    if payload.ctr.len() != 6 {
+        return Err(format!("Invalid read counter {:?}", payload.ctr));
+    }
+    let counter = u32::from_str_radix(&payload.ctr, 16)
+        .map_err(|_| format!("Invalid read counter {:?}", payload.ctr))?;
+    let Some((_, stamp_id)) = config
+        .tags
+        .iter()
+        .find(|(tag_uid, _)| tag_uid.eq_ignore_ascii_case(&uid))
+    else {
+        return Err(format!("Unknown tag {}", uid));
+    };
+
+    let expected = tag_mac(secret, &uid, counter);
+    let mac = payload.mac.to_lowercase();
+    if mac.len() != expected.len() || !memcmp::eq(mac.as_bytes(), expected.as_bytes()) {
+        return Err(format!("Invalid MAC for tag {}", uid));
+    }
+    Ok((uid, stamp_id, counter))
+}
+
+/// NFC 태그로 스템프를 확인하는 비동기 함수입니다. 태그의 MAC과 읽기 카운터를 확인한 뒤에는
+/// QR 코드의 `/check`와 같이 유저의 확인 대기 스템프를 등록하고 스템프 페이지로 보냅니다.
+///
+/// # Returns
+///
+/// 확인에 성공하면 스템프 페이지로 가는 임시 리다이렉션(307)을 반환합니다. 로그인하지 않았거나,
+/// MAC이 틀렸거나, 이미 받은 카운터를 다시 보낸 태그 주소도 `/check`처럼 리다이렉션만 하고 기록하지 않습니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let app = App::new().service(handle_nfc_check);
+/// ```
+#[get("/nfc/check")]
+#[allow(clippy::too_many_arguments)]
+pub async fn handle_nfc_check(
+    req: HttpRequest,
+    stamp_id_list: Data<Reloadable<StampIdList>>,
+    user_stamp_list: Data<Mutex<UserStampList>>,
+    stamp_history: Data<StampHistory>,
+    config: Data<Reloadable<Config>>,
+    tour_status: Data<Mutex<TourStatus>>,
+    metrics: Data<Metrics>,
+    scan_cooldown: Data<ScanCooldown>,
+    nfc_counters: Data<Mutex<NfcCounterList>>,
+    persister: Data<Persister>,
+) -> Result<HttpResponse, AppError> {
+    let config = config.get();
+    let stamp_id_list = stamp_id_list.get();
+    // 투어가 종료된 경우 종료 페이지 반환
+    if is_closed(&config, &tour_status.lock_or_recover(), chrono::Utc::now()) {
+        return handle_tour_ended(&req).await;
+    }
+
+    // 로그인하지 않았거나 등록되지 않은 유저인 경우 임시 리다이렉션 반환
+    let Some(CurrentUser { user_id, .. }) = CurrentUser::from_cookie(&req) else {
+        warn!("A user who is not logged in or not registered attempted to access with an NFC tag.");
+        return Ok(redirect_to_stamp(&req));
+    };
+
+    let (uid, stamp_id, counter) = match verify_tag(&config.nfc, req.query_string()) {
+        Ok(tag) => tag,
+        Err(e) => {
+            warn!("User {} sent a rejected NFC tag: {}", user_id, e);
+            return Ok(redirect_to_stamp(&req));
+        }
+    };
+    let Some(stamp) = stamp_id_list.stamp_id_list.get(stamp_id) else {
+        warn!("NFC tag {} points to unknown stamp {}.", uid, stamp_id);
+        return Ok(redirect_to_stamp(&req));
+    };
+
+    // 이전에 받은 카운터 이하이면 복사해 둔 태그 주소로 보고 거절
+    {
+        let mut nfc_counters = nfc_counters.lock_or_recover();
+        if let Some(last) = nfc_counters
+            .counters
+            .get(&uid)
+            .filter(|last| counter <= **last)
+        {
+            warn!(
+                "User {} replayed NFC tag {} (counter {}, last {}).",
+                user_id, uid, counter, last
+            );
+            return Ok(redirect_to_stamp(&req));
+        }
+        nfc_counters.counters.insert(uid, counter);
+    }
+    persister.mark(&[Dataset::NfcCounters]);
+
+    accept_scan(
+        &req,
+        &user_id,
+        stamp,
+        &stamp_id_list,
+        &config,
+        &metrics,
+        &stamp_history,
+        &user_stamp_list,
+        &scan_cooldown,
+    )
+    .await
+}
@@ -0,0 +1,114 @@
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// 알림을 보낼 메신저 종류입니다.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifierService {
+    Discord,
+    Slack,
+}
+
+/// `resources/config.json`의 `notifier` 항목입니다.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NotifierConfig {
+    pub service: NotifierService,
+    pub webhook_url: String,
+    /// 몇 번째 완주마다 알림을 보낼지 정합니다. 기본값은 100입니다.
+    #[serde(default = "default_completion_milestone")]
+    pub completion_milestone: usize,
+}
+
+fn default_completion_milestone() -> usize {
+    100
+}
+
+/// 운영진에게 알릴 만한 사건입니다.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// 완주 등급 달성 인원이 정해진 단위에 도달했습니다.
+    CompletionMilestone { tier_name: String, count: usize },
+    /// 스템프 목록을 다시 불러왔습니다.
+    StampReload { stamp_count: usize },
+    /// 데이터베이스 파일 저장에 실패했습니다.
+    SaveFailed { file_name: String },
+}
+
+impl Event {
+    /// 메신저에 표시할 알림 문구를 반환합니다.
+    pub fn message(&self) -> String {
+        match self {
+            Event::CompletionMilestone { tier_name, count } => {
+                format!("🎉 {tier_name} 등급 달성 {count}명 돌파!")
+            }
+            Event::StampReload { stamp_count } => {
+                format!("🔄 스템프 목록을 다시 불러왔습니다. (스템프 {stamp_count}개)")
+            }
+            Event::SaveFailed { file_name } => {
+                format!("⚠️ 데이터베이스 저장 실패: {file_name}")
+            }
+        }
+    }
+}
+
+/// 설정된 Discord 또는 Slack 웹훅으로 알림을 보내는 구조체입니다.
+/// 설정이 없으면 알림을 보내지 않습니다.
+#[derive(Clone, Default)]
+pub struct Notifier {
+    config: Option<NotifierConfig>,
+    client: reqwest::Client,
+}
+
+impl Notifier {
+    pub fn new(config: Option<NotifierConfig>) -> Self {
+        Notifier {
+            config,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// 완주 인원 `count`가 알림을 보낼 단위에 해당하는지 확인합니다.
+    pub fn is_milestone(&self, count: usize) -> bool {
+        self.config.as_ref().is_some_and(|config| {
+            config.completion_milestone > 0 && count.is_multiple_of(config.completion_milestone)
+        })
+    }
+
+    /// 알림을 비동기로 전송합니다. 전송 결과를 기다리지 않으므로 요청 처리를 지연시키지 않습니다.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - 전송할 사건입니다.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// notifier.notify(Event::SaveFailed { file_name: "user_status".to_string() });
+    /// ```
+    pub fn notify(&self, event: Event) {
+        let Some(config) = self.config.clone() else {
+            return;
+        };
+        let client = self.client.clone();
+        let body = payload(config.service, &event.message());
+
+        actix_rt::spawn(async move {
+            match client.post(&config.webhook_url).json(&body).send().await {
+                Ok(response) if response.status().is_success() => {
+                    info!("Notification sent : {:?}", event)
+                }
+                Ok(response) => error!("Notification failed with {}", response.status()),
+                Err(e) => error!("Notification failed : {}", e),
+            }
+        });
+    }
+}
+
+/// 메신저 종류에 맞는 웹훅 요청 본문을 생성합니다.
+pub fn payload(service: NotifierService, message: &str) -> Value {
+    match service {
+        NotifierService::Discord => json!({ "content": message }),
+        NotifierService::Slack => json!({ "text": message }),
+    }
+}
@@ -0,0 +1,76 @@
+use actix_web::{web::Bytes, HttpRequest};
+use dashmap::DashMap;
+use std::sync::Arc;
+
+use crate::announcement::{inject_announcement, is_announcing};
+use crate::template::render;
+
+/// 렌더링을 마친 스템프 페이지 하나입니다.
+pub struct StampPage {
+    /// 정적 파일 주소와 스템프 정보를 치환하고 `%ANNOUNCEMENT%`만 남겨둔 HTML입니다. 시험 운영 중이면 `TEST MODE` 띠가 들어 있습니다.
+    html: String,
+    /// 공지가 없을 때 그대로 보내는 응답 본문입니다.
+    plain: Bytes,
+}
+
+impl StampPage {
+    /// 응답 본문을 반환합니다. 공지가 없으면 저장해둔 본문을 복사 없이 그대로 반환합니다.
+    pub fn body(&self, req: &HttpRequest) -> Bytes {
+        if is_announcing(req) && self.html.contains("%ANNOUNCEMENT%") {
+            Bytes::from(inject_announcement(req, self.html.clone()))
+        } else {
+            self.plain.clone()
+        }
+    }
+}
+
+/// `/stamp/` 응답 페이지를 스템프와 언어별로 저장하는 캐시입니다.
+///
+/// 템플릿은 설정과 스템프 목록을 다시 불러올 때만 바뀐 것으로 보므로, `reload_state`에서 `clear`로 비웁니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let page = match stamp_pages.get(&stamp.stampId, locale) {
+///     Some(page) => page,
+///     None => stamp_pages.insert(&stamp.stampId, locale, html),
+/// };
+/// HttpResponse::Ok().body(page.body(&req))
+/// ```
+#[derive(Default)]
+pub struct StampPageCache {
+    pages: DashMap<String, Vec<(&'static str, Arc<StampPage>)>>,
+}
+
+impl StampPageCache {
+    pub fn get(&self, stamp_id: &str, locale: &str) -> Option<Arc<StampPage>> {
+        self.pages
+            .get(stamp_id)?
+            .iter()
+            .find_map(|(page_locale, page)| (*page_locale == locale).then(|| Arc::clone(page)))
+    }
+
+    /// 렌더링한 페이지를 저장하고 반환합니다. 같은 스템프와 언어의 페이지가 있으면 교체합니다.
+    pub fn insert(&self, stamp_id: &str, locale: &'static str, html: String) -> Arc<StampPage> {
+        let plain = Bytes::from(render(&html, &[("ANNOUNCEMENT", "")]));
+        let page = Arc::new(StampPage { html, plain });
+        let mut pages = self.pages.entry(stamp_id.to_string()).or_default();
+        pages.retain(|(page_locale, _)| *page_locale != locale);
+        pages.push((locale, Arc::clone(&page)));
+        page
+    }
+
+    /// 저장한 페이지를 모두 지웁니다.
+    pub fn clear(&self) {
+        self.pages.clear();
+    }
+
+    /// 저장한 페이지 수입니다.
+    pub fn len(&self) -> usize {
+        self.pages.iter().map(|pages| pages.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pages.is_empty()
+    }
+}
@@ -0,0 +1,63 @@
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    middleware::Next,
+};
+use futures_util::FutureExt;
+use log::error;
+use std::{any::Any, panic::AssertUnwindSafe};
+
+use crate::error::AppError;
+use crate::request_id;
+
+/// 패닉 값에서 메시지를 꺼냅니다. `panic!`에 넘긴 문자열이 아니면 고정된 문구를 반환합니다.
+///
+/// # Example
+///
+/// ```rust
+/// use gj_stamptour::panic::panic_message;
+///
+/// let payload = std::panic::catch_unwind(|| panic!("lock poisoned")).unwrap_err();
+/// assert_eq!(panic_message(&*payload), "lock poisoned");
+/// ```
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "non-string panic payload".to_string())
+}
+
+/// 핸들러에서 발생한 패닉을 잡아 요청 ID와 함께 로그에 남기고 `error500.html`을 담은 500 응답으로 바꾸는 미들웨어입니다.
+/// 패닉이 연결을 끊고 작업자를 멈추게 하는 대신 그 요청만 실패하도록 합니다.
+///
+/// 요청 ID와 요청 언어가 정해진 뒤에 실행되도록 `assign_request_id`, `select_locale`보다 안쪽에 둡니다.
+/// `record_latency`보다 안쪽에 있으므로 패닉이 발생해도 처리 중인 요청 수가 줄어듭니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// App::new()
+///     .wrap(actix_web::middleware::from_fn(catch_panic))
+///     .wrap(actix_web::middleware::from_fn(assign_request_id))
+///     .configure(routes);
+/// ```
+pub async fn catch_panic(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let request_line = format!("{} {}", req.method(), req.path());
+    match AssertUnwindSafe(next.call(req)).catch_unwind().await {
+        Ok(res) => res,
+        Err(payload) => {
+            let message = panic_message(&*payload);
+            error!(
+                "{} panicked (request id {}) : {}",
+                request_line,
+                request_id::current().unwrap_or_else(|| "-".to_string()),
+                message
+            );
+            Err(AppError::Panic(message).into())
+        }
+    }
+}
@@ -0,0 +1,262 @@
+use actix_web::{
+    routes,
+    web::{Data, Json},
+    HttpRequest, HttpResponse,
+};
+use chrono::Utc;
+use log::{info, warn};
+use openssl::{rand::rand_bytes, sha::sha256};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeSet, sync::Mutex};
+
+use crate::backup::to_hex;
+use crate::config::Config;
+use crate::error::AppError;
+use crate::lock::MutexExt;
+use crate::notifier::Notifier;
+use crate::persistence::{Dataset, Persister};
+use crate::progress::record_progress;
+use crate::state::{
+    AuditLog, AuditRecord, CompletionList, PartnerKey, PartnerKeyList, Reloadable, StampHistory,
+    StampIdList, StampUserInfo, TeamList, TourStatus, UserList,
+};
+use crate::tour::is_closed;
+
+/// 협력사 API 키 앞에 붙는 표시입니다.
+pub const PARTNER_KEY_PREFIX: &str = "pk_";
+
+/// API 키를 저장할 때 사용하는 SHA-256 해시(16진수)를 계산합니다.
+pub fn hash_key(key: &str) -> String {
+    to_hex(&sha256(key.as_bytes()))
+}
+
+impl PartnerKeyList {
+    /// 협력사 API 키를 새로 발급하는 함수입니다. 같은 이름의 키가 있으면 폐기 여부와 관계없이 새 키로 바꿉니다.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - 협력사 이름입니다.
+    /// * `stamp_ids` - 이 키로 찍을 수 있는 스템프 ID 목록입니다.
+    ///
+    /// # Returns
+    ///
+    /// 발급한 키 원문을 반환합니다. 원문은 저장하지 않으므로 이때만 확인할 수 있습니다.
+    pub fn create(&mut self, name: &str, stamp_ids: BTreeSet<String>) -> String {
+        let mut random = [0u8; 24];
+        rand_bytes(&mut random).unwrap();
+        let key = format!("{}{}", PARTNER_KEY_PREFIX, to_hex(&random));
+        self.partners.insert(
+            name.to_string(),
+            PartnerKey {
+                key_hash: hash_key(&key),
+                stamp_ids,
+                created_at: Utc::now().to_string(),
+                revoked_at: None,
+            },
+        );
+        key
+    }
+
+    /// 협력사 API 키를 폐기합니다. 해당 이름의 사용 중인 키가 없으면 `false`를 반환합니다.
+    pub fn revoke(&mut self, name: &str) -> bool {
+        match self.partners.get_mut(name) {
+            Some(partner) if partner.revoked_at.is_none() => {
+                partner.revoked_at = Some(Utc::now().to_string());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// API 키 원문으로 사용 중인 협력사를 찾습니다.
+    pub fn find(&self, key: &str) -> Option<(&String, &PartnerKey)> {
+        let key_hash = hash_key(key);
+        self.partners
+            .iter()
+            .find(|(_, partner)| partner.revoked_at.is_none() && partner.key_hash == key_hash)
+    }
+}
+
+/// `partner create <이름> <스템프 ID,...>` 관리자 명령을 해석하는 함수입니다.
+///
+/// # Returns
+///
+/// 협력사 이름과 스템프 ID 목록을 반환합니다. 형식이 틀리거나 없는 스템프가 있으면 오류 메시지를 반환합니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let (name, stamp_ids) = parse_partner_create("acme a,b", &stamp_id_list)?;
+/// ```
+pub fn parse_partner_create(
+    args: &str,
+    stamp_id_list: &StampIdList,
+) -> Result<(String, BTreeSet<String>), String> {
+    let usage = || "Usage: partner create <name> <stamp_id>[,<stamp_id>...]".to_string();
+    let (name, stamp_ids) = args.trim().split_once(' ').ok_or_else(usage)?;
+    let stamp_ids: BTreeSet<String> = stamp_ids
+        .split(',')
+        .map(str::trim)
+        .filter(|stamp_id| !stamp_id.is_empty())
+        .map(str::to_string)
+        .collect();
+    if stamp_ids.is_empty() {
+        return Err(usage());
+    }
+    if let Some(unknown) = stamp_ids
+        .iter()
+        .find(|stamp_id| !stamp_id_list.stamp_id_list.contains_key(*stamp_id))
+    {
+        return Err(format!("Stamp {} not found", unknown));
+    }
+    Ok((name.to_string(), stamp_ids))
+}
+
+/// 요청 헤더에서 협력사 API 키를 꺼냅니다. `Authorization: Bearer <키>` 또는 `X-API-Key: <키>`를 사용합니다.
+fn request_key(req: &HttpRequest) -> Option<&str> {
+    let headers = req.headers();
+    headers
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .or_else(|| {
+            headers
+                .get("x-api-key")
+                .and_then(|value| value.to_str().ok())
+        })
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PartnerStamp {
+    /// 방문객의 6자리 유저 코드입니다.
+    pub user_code: String,
+    pub stamp_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PartnerStampResponse {
+    pub user_code: String,
+    pub stamp_id: String,
+    pub timestamp: String,
+}
+
+/// 협력사의 체크인 시스템이 서버 간 호출로 스템프를 찍는 비동기 함수입니다.
+///
+/// # Arguments
+///
+/// * `req` - API 키를 담은 `Authorization: Bearer` 또는 `X-API-Key` 헤더가 있는 요청입니다.
+/// * `body` - 방문객의 유저 코드와 스템프 ID입니다.
+///
+/// # Returns
+///
+/// 스템프를 기록하면 `PartnerStampResponse`가 200 OK 응답으로 반환됩니다.
+/// 키가 없거나 폐기되었으면 401 Unauthorized 응답이, 키에 허용되지 않은 스템프거나 투어가 종료되었으면
+/// 403 Forbidden 응답이, 유저 코드나 스템프가 없으면 404 Not Found 응답이, 수량이 모두 발급되었으면
+/// 409 Conflict 응답이 반환됩니다.
+#[routes]
+#[post("/api/v1/partner/stamp")]
+#[post("/api/partner/stamp")]
+#[allow(clippy::too_many_arguments)]
+pub async fn handle_partner_stamp(
+    req: HttpRequest,
+    body: Json<PartnerStamp>,
+    partner_keys: Data<Mutex<PartnerKeyList>>,
+    user_list: Data<Mutex<UserList>>,
+    stamp_id_list: Data<Reloadable<StampIdList>>,
+    stamp_history: Data<StampHistory>,
+    completions: Data<Mutex<CompletionList>>,
+    teams: Data<Mutex<TeamList>>,
+    audit_log: Data<Mutex<AuditLog>>,
+    tour_status: Data<Mutex<TourStatus>>,
+    notifier: Data<Notifier>,
+    config: Data<Reloadable<Config>>,
+    persister: Data<Persister>,
+) -> Result<HttpResponse, AppError> {
+    let config = config.get();
+    let stamp_id_list = stamp_id_list.get();
+    let partner = {
+        let partner_keys = partner_keys.lock_or_recover();
+        request_key(&req)
+            .and_then(|key| partner_keys.find(key))
+            .map(|(name, partner)| (name.clone(), partner.stamp_ids.clone()))
+    };
+    let Some((partner_name, stamp_ids)) = partner else {
+        warn!("Unauthorized access to the partner API has been identified.");
+        return Err(AppError::Unauthorized);
+    };
+
+    if !stamp_ids.contains(&body.stamp_id) {
+        warn!(
+            "Partner {} attempted to record stamp {} outside its scope.",
+            partner_name, body.stamp_id
+        );
+        return Err(AppError::Forbidden(
+            "Stamp is not allowed for this key".to_string(),
+        ));
+    }
+    if is_closed(&config, &tour_status.lock_or_recover(), Utc::now()) {
+        return Err(AppError::Forbidden("Tour has ended".to_string()));
+    }
+    let Some(stamp) = stamp_id_list.stamp_id_list.get(&body.stamp_id) else {
+        return Err(AppError::Missing("Stamp not found".to_string()));
+    };
+
+    let user = {
+        let user_list = user_list.lock_or_recover();
+        user_list.find_by_code(&body.user_code).and_then(|user_id| {
+            let user_name = user_list.users.get(user_id)?;
+            Some((user_id.clone(), user_name.clone()))
+        })
+    };
+    let Some((user_id, user_name)) = user else {
+        return Err(AppError::Missing("User not found".to_string()));
+    };
+
+    let timestamp = Utc::now().to_string();
+    let entry = StampUserInfo {
+        user_name,
+        user_id: user_id.clone(),
+        timestamp: timestamp.clone(),
+        seq: 0, // 기록할 때 순번 발급
+    };
+    if !stamp_history.push_if(&body.stamp_id, entry, |issued| !stamp.is_sold_out(issued)) {
+        return Err(AppError::Conflict("Stamp is sold out".to_string()));
+    }
+    info!(
+        "Partner {} recorded stamp {} for user {}.",
+        partner_name, body.stamp_id, user_id
+    );
+
+    let milestones = record_progress(
+        &config,
+        &stamp_id_list,
+        &stamp_history,
+        &mut completions.lock_or_recover(),
+        &mut teams.lock_or_recover(),
+        &notifier,
+        &user_id,
+    );
+    for event in milestones {
+        notifier.notify(event);
+    }
+    audit_log.lock_or_recover().entries.push(AuditRecord {
+        action: "partner stamp".to_string(),
+        user_id,
+        stamp_id: Some(body.stamp_id.clone()),
+        actor: format!("partner:{}", partner_name),
+        reason: String::new(),
+        timestamp: timestamp.clone(),
+    });
+    persister.mark(&[
+        Dataset::StampStatus,
+        Dataset::CompletionStatus,
+        Dataset::TeamStatus,
+        Dataset::AuditLog,
+    ]);
+
+    Ok(HttpResponse::Ok().json(PartnerStampResponse {
+        user_code: body.user_code.trim().to_uppercase(),
+        stamp_id: body.stamp_id.clone(),
+        timestamp,
+    }))
+}
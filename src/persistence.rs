@@ -0,0 +1,409 @@
+use actix_rt::time::{timeout, Instant};
+use actix_web::web;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeSet, sync::Mutex, time::Duration};
+use tokio::sync::{mpsc, oneshot};
+
+use crate::backup::upload_object;
+use crate::lock::MutexExt;
+use crate::notifier::Event;
+use crate::state::AppState;
+use crate::storage::{to_database, write_database, DatabaseFormat};
+
+/// `resources/config.json`의 `persistence` 항목입니다. 변경된 데이터를 모아서 저장하는 간격을 정합니다.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct PersistenceConfig {
+    /// 마지막 변경 후 이 시간(ms) 동안 다른 변경이 없으면 저장합니다.
+    pub debounce_ms: u64,
+    /// 변경이 계속되더라도 첫 변경 후 이 시간(ms)이 지나면 저장합니다.
+    pub max_delay_ms: u64,
+    /// 0보다 크면 `debounce_ms`, `max_delay_ms` 대신 첫 변경 후 이 시간(분)마다 모아서 저장합니다.
+    /// 변경이 적은 작은 행사에서 디스크 쓰기를 줄입니다.
+    pub autosave_interval_minutes: u64,
+    /// 0보다 크면 저장하지 않은 변경이 이 수만큼 쌓였을 때 기다리지 않고 바로 저장합니다.
+    /// 서버가 갑자기 멈춰도 잃는 변경이 이 수보다 적도록 합니다.
+    pub save_after_mutations: usize,
+    /// 데이터베이스 파일과 백업 스냅샷의 저장 형식(`json` 또는 `binary`)입니다.
+    /// 불러올 때는 형식을 자동으로 판별하므로 언제든 바꿀 수 있습니다.
+    pub format: DatabaseFormat,
+}
+
+impl Default for PersistenceConfig {
+    fn default() -> Self {
+        PersistenceConfig {
+            debounce_ms: 1000,
+            max_delay_ms: 5000,
+            autosave_interval_minutes: 0,
+            save_after_mutations: 0,
+            format: DatabaseFormat::Json,
+        }
+    }
+}
+
+/// `resources/database`에 저장하는 데이터베이스 파일입니다.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Dataset {
+    StampStatus,
+    UserStatus,
+    CompletionStatus,
+    TeamStatus,
+    Announcement,
+    TourStatus,
+    AuditLog,
+    PartnerKeys,
+    PushSubscriptions,
+    ShortLinks,
+    NfcCounters,
+    Feedback,
+    Guestbook,
+    AdminHistory,
+}
+
+impl Dataset {
+    /// 모든 데이터베이스 파일입니다.
+    pub const ALL: [Dataset; 14] = [
+        Dataset::StampStatus,
+        Dataset::UserStatus,
+        Dataset::CompletionStatus,
+        Dataset::TeamStatus,
+        Dataset::Announcement,
+        Dataset::TourStatus,
+        Dataset::AuditLog,
+        Dataset::PartnerKeys,
+        Dataset::PushSubscriptions,
+        Dataset::ShortLinks,
+        Dataset::NfcCounters,
+        Dataset::Feedback,
+        Dataset::Guestbook,
+        Dataset::AdminHistory,
+    ];
+
+    /// 확장자를 제외한 파일 이름입니다.
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Dataset::StampStatus => "stamp_status",
+            Dataset::UserStatus => "user_status",
+            Dataset::CompletionStatus => "completion_status",
+            Dataset::TeamStatus => "team_status",
+            Dataset::Announcement => "announcement",
+            Dataset::TourStatus => "tour_status",
+            Dataset::AuditLog => "audit_log",
+            Dataset::PartnerKeys => "partner_keys",
+            Dataset::PushSubscriptions => "push_subscriptions",
+            Dataset::ShortLinks => "short_links",
+            Dataset::NfcCounters => "nfc_counters",
+            Dataset::Feedback => "feedback",
+            Dataset::Guestbook => "guestbook",
+            Dataset::AdminHistory => "admin_history",
+        }
+    }
+}
+
+/// 저장 작업자에게 보내는 메시지입니다.
+enum Message {
+    /// 변경 하나로 데이터가 바뀌었으므로 잠시 뒤에 저장합니다.
+    Changed(Vec<Dataset>),
+    /// 모든 파일을 바로 저장하고 저장에 실패한 파일 이름을 돌려줍니다.
+    Flush(oneshot::Sender<Vec<&'static str>>),
+}
+
+/// 변경된 데이터를 저장 작업자에게 알리는 구조체입니다.
+///
+/// 요청을 처리하는 쪽은 `mark`로 바뀐 파일만 알리고, 실제 저장은 `start`로 실행한 작업자가
+/// 변경을 모아 요청 처리용 락을 잡지 않은 채로 합니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// user_list.lock_or_recover().insert(&mut user);
+/// persister.mark(&[Dataset::UserStatus]);
+/// ```
+pub struct Persister {
+    sender: mpsc::UnboundedSender<Message>,
+    receiver: Mutex<Option<mpsc::UnboundedReceiver<Message>>>,
+    /// 변경이 잠잠해지면 저장하기까지 기다리는 시간입니다. 주기 저장을 사용하면 `None`입니다.
+    debounce: Option<Duration>,
+    max_delay: Duration,
+    save_after: Option<usize>,
+    format: DatabaseFormat,
+}
+
+impl Persister {
+    pub fn new(config: &PersistenceConfig) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let (debounce, max_delay) = match config.autosave_interval_minutes {
+            0 => (
+                Some(Duration::from_millis(config.debounce_ms)),
+                Duration::from_millis(config.max_delay_ms.max(config.debounce_ms)),
+            ),
+            minutes => (None, Duration::from_secs(minutes * 60)),
+        };
+        Persister {
+            sender,
+            receiver: Mutex::new(Some(receiver)),
+            debounce,
+            max_delay,
+            save_after: (config.save_after_mutations > 0).then_some(config.save_after_mutations),
+            format: config.format,
+        }
+    }
+
+    /// 변경 하나로 바뀐 데이터베이스 파일을 알립니다. 기다리지 않으므로 락을 잡은 채로 호출해도 됩니다.
+    /// 한 번 호출할 때마다 `save_after_mutations`의 변경 하나로 셉니다.
+    pub fn mark(&self, datasets: &[Dataset]) {
+        if datasets.is_empty() {
+            return;
+        }
+        // 작업자가 없으면 받을 곳이 없으므로 무시
+        self.sender.send(Message::Changed(datasets.to_vec())).ok();
+    }
+
+    /// 모든 데이터베이스 파일을 바로 저장하고 끝날 때까지 기다립니다.
+    ///
+    /// # Returns
+    ///
+    /// 모두 저장했으면 `Ok(())`, 저장에 실패한 파일이 있으면 그 파일 이름 목록을 반환합니다.
+    /// 저장 작업자가 실행 중이 아니면 모든 파일 이름을 반환합니다.
+    pub async fn flush(&self) -> Result<(), Vec<&'static str>> {
+        let all = || {
+            Dataset::ALL
+                .iter()
+                .map(|dataset| dataset.file_name())
+                .collect()
+        };
+        if self.receiver.lock_or_recover().is_some() {
+            error!("Persistence writer is not running");
+            return Err(all());
+        }
+        let (reply, failed) = oneshot::channel();
+        if self.sender.send(Message::Flush(reply)).is_err() {
+            return Err(all());
+        }
+        match failed.await {
+            Ok(failed) if failed.is_empty() => Ok(()),
+            Ok(failed) => Err(failed),
+            Err(_) => Err(all()),
+        }
+    }
+}
+
+/// 데이터베이스 파일 하나의 현재 내용을 저장 형식으로 만듭니다.
+/// 락은 값을 복사하는 동안만 잡고, 직렬화는 락을 푼 뒤에 합니다.
+fn snapshot(state: &AppState, dataset: Dataset) -> Result<Vec<u8>, String> {
+    let format = state.persister.format;
+    let file_name = dataset.file_name();
+    match dataset {
+        Dataset::StampStatus => {
+            let stamp_history = state.stamp_history.get_ref().clone();
+            to_database(file_name, stamp_history, format)
+        }
+        Dataset::UserStatus => {
+            let user_list = state.user_list.lock_or_recover().clone();
+            to_database(file_name, user_list, format)
+        }
+        Dataset::CompletionStatus => {
+            let completions = state.completions.lock_or_recover().clone();
+            to_database(file_name, completions, format)
+        }
+        Dataset::TeamStatus => {
+            let teams = state.teams.lock_or_recover().clone();
+            to_database(file_name, teams, format)
+        }
+        Dataset::Announcement => {
+            let announcement = state.announcement.lock_or_recover().clone();
+            to_database(file_name, announcement, format)
+        }
+        Dataset::TourStatus => {
+            let tour_status = state.tour_status.lock_or_recover().clone();
+            to_database(file_name, tour_status, format)
+        }
+        Dataset::AuditLog => {
+            let audit_log = state.audit_log.lock_or_recover().clone();
+            to_database(file_name, audit_log, format)
+        }
+        Dataset::PartnerKeys => {
+            let partner_keys = state.partner_keys.lock_or_recover().clone();
+            to_database(file_name, partner_keys, format)
+        }
+        Dataset::PushSubscriptions => {
+            let subscriptions = state.push_subscriptions.lock_or_recover().clone();
+            to_database(file_name, subscriptions, format)
+        }
+        Dataset::ShortLinks => {
+            let short_links = state.short_links.lock_or_recover().clone();
+            to_database(file_name, short_links, format)
+        }
+        Dataset::NfcCounters => {
+            let nfc_counters = state.nfc_counters.lock_or_recover().clone();
+            to_database(file_name, nfc_counters, format)
+        }
+        Dataset::Feedback => {
+            let feedback = state.feedback.lock_or_recover().clone();
+            to_database(file_name, feedback, format)
+        }
+        Dataset::Guestbook => {
+            let guestbook = state.guestbook.lock_or_recover().clone();
+            to_database(file_name, guestbook, format)
+        }
+        Dataset::AdminHistory => {
+            let admin_history = state.admin_history.lock_or_recover().clone();
+            to_database(file_name, admin_history, format)
+        }
+    }
+}
+
+/// 데이터베이스 파일들을 저장합니다. 파일 쓰기는 작업자 스레드를 막지 않도록 별도 스레드에서 합니다.
+///
+/// # Returns
+///
+/// 저장에 실패한 파일 이름 목록을 반환합니다.
+async fn save(state: &AppState, datasets: BTreeSet<Dataset>) -> Vec<&'static str> {
+    let mut failed = Vec::new();
+    let mut files = Vec::new();
+    for dataset in datasets {
+        let start = Instant::now();
+        let snapshot = snapshot(state, dataset);
+        state
+            .metrics
+            .observe_serialize(dataset.file_name(), start.elapsed());
+        match snapshot {
+            Ok(contents) => files.push((dataset.file_name(), contents)),
+            Err(e) => {
+                error!("Failed to serialize {} : {}", dataset.file_name(), e);
+                failed.push(dataset.file_name());
+            }
+        }
+    }
+
+    let file_names: Vec<&'static str> = files.iter().map(|(file_name, _)| *file_name).collect();
+    let format = state.persister.format;
+    // 디스크가 유지되지 않는 배포 환경에서도 감사 기록이 남도록 저장할 때마다 S3 호환 저장소에 덮어씀
+    if let Some(s3) = state.config.get().backup.s3.clone() {
+        let audit_log = Dataset::AuditLog.file_name();
+        if let Some((_, contents)) = files.iter().find(|(file_name, _)| *file_name == audit_log) {
+            upload_object(
+                s3,
+                format!("{}.{}", audit_log, format.extension()),
+                contents.clone(),
+            );
+        }
+    }
+    // 파일별로 쓰기 결과와 걸린 시간, 쓴 바이트 수를 돌려받아 메트릭에 기록
+    let written = web::block(move || {
+        files
+            .into_iter()
+            .map(|(file_name, contents)| {
+                let start = Instant::now();
+                let result = write_database(file_name, format, &contents)
+                    .map_err(|e| error!("Failed to save {} : {}", file_name, e));
+                (file_name, result, start.elapsed(), contents.len())
+            })
+            .collect::<Vec<_>>()
+    })
+    .await;
+    match written {
+        Ok(results) => {
+            for (file_name, result, duration, bytes) in results {
+                match result {
+                    Ok(()) => state.metrics.observe_write(file_name, duration, bytes),
+                    Err(()) => failed.push(file_name),
+                }
+            }
+        }
+        Err(e) => {
+            error!("Persistence writer thread failed : {}", e);
+            failed.extend(file_names);
+        }
+    }
+
+    for file_name in failed.iter() {
+        state.notifier.notify(Event::SaveFailed {
+            file_name: file_name.to_string(),
+        });
+    }
+    failed
+}
+
+/// 변경 알림을 받아 모아서 저장하는 작업자를 실행합니다. 서버마다 한 번만 실행하며,
+/// actix 런타임 안에서 호출해야 합니다.
+///
+/// 첫 변경 후 `debounce_ms` 동안 조용하거나 `max_delay_ms`가 지나면 그동안 바뀐 파일을 한 번에 저장합니다.
+/// `autosave_interval_minutes`를 설정하면 첫 변경 후 그 시간이 지날 때 저장하며,
+/// 어느 경우든 저장하지 않은 변경이 `save_after_mutations`만큼 쌓이면 바로 저장합니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let state = load_state(load_config());
+/// persistence::start(&state);
+/// ```
+pub fn start(state: &AppState) {
+    let Some(mut receiver) = state.persister.receiver.lock_or_recover().take() else {
+        return;
+    };
+    let (debounce, max_delay, save_after) = (
+        state.persister.debounce,
+        state.persister.max_delay,
+        state.persister.save_after,
+    );
+    let state = state.clone();
+    info!(
+        "Persistence writer started (debounce {:?}, max delay {:?}, save after {:?} mutations)",
+        debounce, max_delay, save_after
+    );
+
+    actix_rt::spawn(async move {
+        while let Some(message) = receiver.recv().await {
+            let mut changed = BTreeSet::new();
+            let mut flushes = Vec::new();
+            let mut mutations = receive(message, &mut changed, &mut flushes);
+
+            // 변경이 잠잠해지거나 저장 주기가 될 때까지 모으되, 바로 저장하라는 요청이 오거나
+            // 저장하지 않은 변경이 너무 많이 쌓이면 기다리지 않음
+            let deadline = Instant::now() + max_delay;
+            while flushes.is_empty()
+                && Instant::now() < deadline
+                && save_after.is_none_or(|save_after| mutations < save_after)
+            {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                let wait = debounce.map_or(remaining, |debounce| debounce.min(remaining));
+                match timeout(wait, receiver.recv()).await {
+                    Ok(Some(message)) => mutations += receive(message, &mut changed, &mut flushes),
+                    Ok(None) | Err(_) => break,
+                }
+            }
+
+            if !flushes.is_empty() {
+                changed.extend(Dataset::ALL);
+            }
+            let failed = save(&state, changed).await;
+            for reply in flushes {
+                reply.send(failed.clone()).ok();
+            }
+        }
+    });
+}
+
+/// 받은 메시지를 저장할 파일 목록이나 저장 완료를 기다리는 요청 목록에 더합니다.
+///
+/// # Returns
+///
+/// 메시지가 알린 변경 수(0 또는 1)를 반환합니다.
+fn receive(
+    message: Message,
+    changed: &mut BTreeSet<Dataset>,
+    flushes: &mut Vec<oneshot::Sender<Vec<&'static str>>>,
+) -> usize {
+    match message {
+        Message::Changed(datasets) => {
+            changed.extend(datasets);
+            1
+        }
+        Message::Flush(reply) => {
+            flushes.push(reply);
+            0
+        }
+    }
+}
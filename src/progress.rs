@@ -0,0 +1,314 @@
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::achievements::{record_achievements, EarnedAchievement};
+use crate::config::Config;
+use crate::notifier::{Event, Notifier};
+use crate::state::{
+    generate_code, CompletionList, CompletionRecord, StampHistory, StampIdList, StampInfo,
+    TeamList, UserList,
+};
+use crate::teams::record_team_completions;
+
+/// `/progress`로 반환되는 유저의 진행 상황입니다.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Progress {
+    pub user_id: String,
+    pub user_name: String,
+    pub collected: Vec<String>,
+    pub collected_count: usize,
+    pub total_count: usize,
+    /// 모은 스템프의 점수 합계입니다.
+    pub points: u32,
+    /// 현재 달성한 가장 높은 완주 등급입니다.
+    pub tier: Option<String>,
+    /// 다음 완주 등급과 남은 스템프 개수입니다.
+    pub next_tier: Option<NextTier>,
+    /// 찾은 숨은 스템프의 비밀 배지입니다. 하나도 찾지 않았으면 응답에 포함하지 않습니다.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub secret_badges: Vec<StampInfo>,
+    /// 달성한 업적입니다. 완주 기록이 필요하므로 `user_progress`는 비워 두고 `achievements::user_achievements`로 채웁니다.
+    #[serde(default)]
+    pub achievements: Vec<EarnedAchievement>,
+    /// 경품 수령 시 직원에게 보여주는 완주 코드입니다. 완주 등급을 달성하기 전에는 응답에 포함하지 않습니다.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completion_code: Option<String>,
+    /// 완주 코드로 이미 경품을 받았는지 여부입니다.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub redeemed: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct NextTier {
+    pub tier_name: String,
+    pub remaining: usize,
+}
+
+/// 유저가 모은 스템프 중 현재 스템프 목록에 존재하는 공개 스템프 ID만 반환합니다.
+/// 숨은 스템프는 `found_hidden`으로 따로 구합니다.
+pub fn collected_stamps(
+    stamp_id_list: &StampIdList,
+    stamp_history: &StampHistory,
+    user_id: &str,
+) -> Vec<String> {
+    stamp_history
+        .collected_by(user_id)
+        .into_iter()
+        .filter(|stamp_id| stamp_id_list.stamp_id_list.contains_key(stamp_id))
+        .filter(|stamp_id| !stamp_id_list.is_hidden(stamp_id))
+        .collect()
+}
+
+/// 유저가 찾은 숨은 스템프 ID를 반환합니다.
+pub fn found_hidden(
+    stamp_id_list: &StampIdList,
+    stamp_history: &StampHistory,
+    user_id: &str,
+) -> Vec<String> {
+    stamp_history
+        .collected_by(user_id)
+        .into_iter()
+        .filter(|stamp_id| stamp_id_list.is_hidden(stamp_id))
+        .collect()
+}
+
+/// 모은 스템프 개수로 달성한 가장 높은 완주 등급을 반환합니다.
+///
+/// # Arguments
+///
+/// * `tiers` - `Config::tiers`로 구한 (등급 이름, 필요한 스템프 개수) 목록입니다.
+/// * `collected` - 모은 스템프 개수입니다.
+pub fn current_tier(tiers: &[(String, usize)], collected: usize) -> Option<String> {
+    tiers
+        .iter()
+        .filter(|(_, required)| collected >= *required)
+        .map(|(tier_name, _)| tier_name.clone())
+        .next_back()
+}
+
+/// 유저의 진행 상황과 완주 등급을 계산하는 함수입니다.
+///
+/// # Arguments
+///
+/// * `config` - 완주 등급이 정의된 행사 설정입니다.
+/// * `stamp_id_list` - 전체 스템프 목록입니다.
+/// * `stamp_history` - 스템프 기록입니다.
+/// * `user_id` - 진행 상황을 계산할 유저 ID입니다.
+/// * `user_name` - 유저 이름입니다.
+///
+/// # Returns
+///
+/// 모은 스템프, 현재 등급, 다음 등급까지 남은 개수, 찾은 숨은 스템프의 비밀 배지를 담은 `Progress`가 반환됩니다.
+/// 숨은 스템프는 개수와 등급에는 포함되지 않고 점수에만 더해집니다.
+pub fn user_progress(
+    config: &Config,
+    stamp_id_list: &StampIdList,
+    stamp_history: &StampHistory,
+    user_id: &str,
+    user_name: &str,
+) -> Progress {
+    let collected = collected_stamps(stamp_id_list, stamp_history, user_id);
+    let hidden = found_hidden(stamp_id_list, stamp_history, user_id);
+    let total_count = stamp_id_list.visible_count();
+    let tiers = config.tiers(total_count);
+
+    let tier = current_tier(&tiers, collected.len());
+    let next_tier = tiers
+        .iter()
+        .find(|(_, required)| collected.len() < *required)
+        .map(|(tier_name, required)| NextTier {
+            tier_name: tier_name.clone(),
+            remaining: required - collected.len(),
+        });
+
+    Progress {
+        user_id: user_id.to_string(),
+        user_name: user_name.to_string(),
+        collected_count: collected.len(),
+        points: stamp_id_list.points(collected.iter().chain(&hidden)),
+        collected,
+        total_count,
+        tier,
+        next_tier,
+        secret_badges: hidden
+            .iter()
+            .filter_map(|stamp_id| stamp_id_list.stamp_id_list.get(stamp_id))
+            .map(|stamp| stamp.clone().into())
+            .collect(),
+        achievements: Vec::new(),
+        completion_code: None,
+        redeemed: false,
+    }
+}
+
+/// 스템프가 기록될 때 새로 달성한 완주 등급을 완주 기록에 추가하는 함수입니다.
+///
+/// # Arguments
+///
+/// * `config` - 완주 등급이 정의된 행사 설정입니다.
+/// * `stamp_id_list` - 전체 스템프 목록입니다.
+/// * `stamp_history` - 방금 스템프가 기록된 스템프 기록입니다.
+/// * `completions` - 유저별 완주 기록입니다.
+/// * `user_id` - 스템프를 찍은 유저 ID입니다.
+///
+/// # Returns
+///
+/// 이번에 새로 달성한 등급 이름 목록을 반환합니다.
+pub fn record_completions(
+    config: &Config,
+    stamp_id_list: &StampIdList,
+    stamp_history: &StampHistory,
+    completions: &mut CompletionList,
+    user_id: &str,
+) -> Vec<String> {
+    let collected = collected_stamps(stamp_id_list, stamp_history, user_id).len();
+    let records = completions
+        .completions
+        .entry(user_id.to_string())
+        .or_default();
+    let timestamp = chrono::prelude::Utc::now().to_string();
+
+    let mut reached = Vec::new();
+    for (tier_name, required) in config.tiers(stamp_id_list.visible_count()) {
+        if collected >= required && !records.iter().any(|record| record.tier_name == tier_name) {
+            records.push(CompletionRecord {
+                tier_name: tier_name.clone(),
+                timestamp: timestamp.clone(),
+            });
+            reached.push(tier_name);
+        }
+    }
+
+    if records.is_empty() {
+        completions.completions.remove(user_id);
+    } else if !completions.codes.contains_key(user_id) {
+        // 경품 수령용 완주 코드는 처음 완주했을 때 한 번만 발급
+        let code = loop {
+            let code = generate_code(8);
+            if completions.find_by_code(&code).is_none() {
+                break code;
+            }
+        };
+        completions.codes.insert(user_id.to_string(), code);
+    }
+
+    reached
+}
+
+/// 스템프 페이지를 거치지 않고 기록된 스템프(오프라인 동기화, 협력사 API 등)에 대해
+/// 유저와 유저가 속한 팀의 완주 등급, 유저의 업적을 기록하는 함수입니다.
+///
+/// # Returns
+///
+/// 알림을 보내야 하는 완주 인원 달성 사건 목록을 반환합니다. 락을 놓은 뒤에 `Notifier::notify`로 보냅니다.
+pub fn record_progress(
+    config: &Config,
+    stamp_id_list: &StampIdList,
+    stamp_history: &StampHistory,
+    completions: &mut CompletionList,
+    teams: &mut TeamList,
+    notifier: &Notifier,
+    user_id: &str,
+) -> Vec<Event> {
+    let mut milestones = Vec::new();
+    for tier_name in record_completions(config, stamp_id_list, stamp_history, completions, user_id)
+    {
+        info!("User {} reached completion tier {}.", user_id, tier_name);
+        let count = completions
+            .completions
+            .values()
+            .filter(|records| records.iter().any(|record| record.tier_name == tier_name))
+            .count();
+        if notifier.is_milestone(count) {
+            milestones.push(Event::CompletionMilestone { tier_name, count });
+        }
+    }
+    for name in record_achievements(config, stamp_id_list, stamp_history, completions, user_id) {
+        info!("User {} earned achievement {}.", user_id, name);
+    }
+
+    if let Some(team_code) = teams.team_of(user_id).cloned() {
+        if let Some(team) = teams.teams.get_mut(&team_code) {
+            for tier_name in record_team_completions(config, stamp_id_list, stamp_history, team) {
+                info!("Team {} reached completion tier {}.", team_code, tier_name);
+            }
+        }
+    }
+    milestones
+}
+
+/// 리더보드의 한 줄입니다. 공개 API이므로 유저 ID는 포함하지 않습니다.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LeaderboardEntry {
+    pub rank: usize,
+    pub user_name: String,
+    pub points: u32,
+    pub collected_count: usize,
+}
+
+/// 유저별 점수와 모은 스템프 개수를 계산하는 함수입니다.
+///
+/// # Returns
+///
+/// 스템프를 하나 이상 모은 유저에 대해 `(유저 ID, 점수, 모은 스템프 개수)` 목록을 반환합니다.
+/// 숨은 스템프는 점수에만 더하고 개수에는 포함하지 않습니다.
+pub fn user_scores<'a>(
+    stamp_id_list: &StampIdList,
+    stamp_history: &StampHistory,
+    user_list: &'a UserList,
+) -> Vec<(&'a String, u32, usize)> {
+    let collections = stamp_history.collections();
+    user_list
+        .users
+        .keys()
+        .filter_map(|user_id| {
+            let collected = collections.get(user_id)?;
+            let count = collected
+                .iter()
+                .filter(|stamp_id| stamp_id_list.stamp_id_list.contains_key(*stamp_id))
+                .filter(|stamp_id| !stamp_id_list.is_hidden(stamp_id))
+                .count();
+            Some((user_id, stamp_id_list.points(collected), count))
+        })
+        .collect()
+}
+
+/// 점수가 높은 순서로 정렬된 유저 목록을 계산하는 함수입니다.
+/// 점수가 같으면 모은 스템프가 많은 유저가 앞서고, 둘 다 같으면 같은 순위로 처리합니다.
+///
+/// # Arguments
+///
+/// * `stamp_id_list` - 전체 스템프 목록입니다.
+/// * `stamp_history` - 스템프 기록입니다.
+/// * `user_list` - 등록된 유저 목록입니다.
+/// * `limit` - 반환할 최대 유저 수입니다.
+///
+/// # Returns
+///
+/// 순위가 매겨진 `LeaderboardEntry` 목록을 반환합니다.
+pub fn leaderboard(
+    stamp_id_list: &StampIdList,
+    stamp_history: &StampHistory,
+    user_list: &UserList,
+    limit: usize,
+) -> Vec<LeaderboardEntry> {
+    let mut scores = user_scores(stamp_id_list, stamp_history, user_list);
+    scores.sort_by_key(|&(_, points, count)| std::cmp::Reverse((points, count)));
+
+    let mut entries: Vec<LeaderboardEntry> = Vec::new();
+    for (index, (user_id, points, collected_count)) in scores.into_iter().take(limit).enumerate() {
+        let rank = match entries.last() {
+            Some(last) if (last.points, last.collected_count) == (points, collected_count) => {
+                last.rank
+            }
+            _ => index + 1,
+        };
+        entries.push(LeaderboardEntry {
+            rank,
+            user_name: user_list.users[user_id].clone(),
+            points,
+            collected_count,
+        });
+    }
+    entries
+}
@@ -0,0 +1,631 @@
+use actix_web::{
+    get, post,
+    web::{Data, Json},
+    HttpRequest, HttpResponse,
+};
+use chrono::{DateTime, Utc};
+use log::{error, info, warn};
+use openssl::{
+    bn::{BigNum, BigNumContext},
+    derive::Deriver,
+    ec::{EcGroup, EcKey, EcPoint, PointConversionForm},
+    ecdsa::EcdsaSig,
+    error::ErrorStack,
+    hash::MessageDigest,
+    nid::Nid,
+    pkey::{PKey, Private},
+    rand::rand_bytes,
+    sha::sha256,
+    sign::Signer,
+    symm::{encrypt_aead, Cipher},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::{Mutex, OnceLock},
+    time::Duration,
+};
+
+use crate::base_path::normalize;
+use crate::config::Config;
+use crate::crypto::{decode_base64_url, encode_base64_url};
+use crate::error::AppError;
+use crate::lock::MutexExt;
+use crate::persistence::{Dataset, Persister};
+use crate::report::parse_timestamp;
+use crate::scheduler;
+use crate::session::CurrentUser;
+use crate::state::{
+    AppState, CompletionList, PushKeys, PushSubscription, PushSubscriptionList, Reloadable, Stamp,
+    StampHistory, StampIdList,
+};
+
+/// 완주와 품절 임박 알림을 확인하는 주기입니다.
+const NOTIFY_INTERVAL: Duration = Duration::from_secs(15);
+
+/// 푸시 서비스가 전달하지 못한 알림을 보관하는 시간(초)입니다.
+const TTL_SECS: u32 = 24 * 60 * 60;
+
+/// 암호화한 알림 본문의 레코드 크기입니다. (RFC 8188)
+const RECORD_SIZE: u32 = 4096;
+
+/// 유저 한 명이 유지할 수 있는 구독 수입니다. 넘으면 가장 오래된 구독부터 지웁니다.
+pub const MAX_SUBSCRIPTIONS_PER_USER: usize = 5;
+
+/// `resources/config.json`의 `web_push` 항목입니다. 없으면 Web Push를 사용하지 않습니다.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WebPushConfig {
+    /// VAPID 개인 키(URL-safe base64로 적은 32바이트 값)입니다. `vapid keys` 관리자 명령으로 만들 수 있습니다.
+    pub vapid_private_key: String,
+    /// 푸시 서비스가 문제가 있을 때 연락할 주소(`mailto:` 또는 `https:`)입니다.
+    pub subject: String,
+    /// 수량 제한이 있는 스템프의 남은 수량이 이 값 이하로 줄어들면 아직 찍지 않은 구독자에게 알립니다.
+    #[serde(default = "default_low_stock_threshold")]
+    pub low_stock_threshold: usize,
+}
+
+fn default_low_stock_threshold() -> usize {
+    10
+}
+
+/// 서비스 워커에 전달하는 알림 내용입니다.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PushMessage {
+    pub title: String,
+    pub body: String,
+    /// 알림을 눌렀을 때 열 주소입니다.
+    pub url: String,
+}
+
+impl PushMessage {
+    /// 완주 등급을 달성했을 때 보내는 알림입니다.
+    pub fn completion(config: &Config, tier_name: &str) -> Self {
+        PushMessage {
+            title: "🎉 완주를 축하합니다!".to_string(),
+            body: format!("{} 등급을 달성했습니다. 경품을 받아가세요.", tier_name),
+            url: format!("{}/complete", normalize(&config.base_path)),
+        }
+    }
+
+    /// 수량 제한이 있는 스템프가 곧 모두 소진될 때 보내는 알림입니다.
+    pub fn low_stock(config: &Config, stamp: &Stamp, remaining: usize) -> Self {
+        PushMessage {
+            title: "⏰ 마감 임박".to_string(),
+            body: format!(
+                "{}({}) 스템프가 {}개 남았습니다.",
+                stamp.stampName, stamp.stampLocation, remaining
+            ),
+            url: format!("{}/", normalize(&config.base_path)),
+        }
+    }
+}
+
+/// Web Push와 ACME 계정 키가 사용하는 P-256 곡선입니다.
+pub(crate) fn p256() -> Result<EcGroup, ErrorStack> {
+    EcGroup::from_curve_name(Nid::X9_62_PRIME256V1)
+}
+
+/// URL-safe base64로 적은 32바이트 VAPID 개인 키를 읽습니다.
+pub fn vapid_key(private_key: &str) -> Result<EcKey<Private>, String> {
+    let bytes = decode_base64_url(private_key)
+        .filter(|bytes| bytes.len() == 32)
+        .ok_or("VAPID private key must be 32 bytes of URL-safe base64")?;
+    let key = || -> Result<EcKey<Private>, ErrorStack> {
+        let group = p256()?;
+        let private_number = BigNum::from_slice(&bytes)?;
+        let mut ctx = BigNumContext::new()?;
+        let mut public_point = EcPoint::new(&group)?;
+        public_point.mul_generator2(&group, &private_number, &mut ctx)?;
+        EcKey::from_private_components(&group, &private_number, &public_point)
+    };
+    key().map_err(|e| format!("Invalid VAPID private key: {}", e))
+}
+
+/// 키의 공개 키를 압축하지 않은 65바이트 형식으로 반환합니다.
+pub fn public_key(key: &EcKey<Private>) -> Vec<u8> {
+    key.public_key()
+        .to_bytes(
+            key.group(),
+            PointConversionForm::UNCOMPRESSED,
+            &mut BigNumContext::new().unwrap(),
+        )
+        .unwrap()
+}
+
+/// 새 VAPID 키 쌍을 만들어 (개인 키, 공개 키)를 URL-safe base64로 반환합니다.
+pub fn generate_vapid_keys() -> (String, String) {
+    let key = EcKey::generate(&p256().unwrap()).unwrap();
+    let private_key = key.private_key().to_vec_padded(32).unwrap();
+    (
+        encode_base64_url(&private_key),
+        encode_base64_url(&public_key(&key)),
+    )
+}
+
+/// 푸시 서비스에 보낼 VAPID `Authorization` 헤더 값을 만드는 함수입니다. (RFC 8292)
+///
+/// # Arguments
+///
+/// * `key` - VAPID 개인 키입니다.
+/// * `endpoint` - 구독의 전송 주소입니다. 주소의 출처(origin)가 JWT의 `aud`가 됩니다.
+/// * `subject` - 연락처(`mailto:` 또는 `https:`)입니다.
+/// * `now` - 발급 시각(Unix 초)입니다. 토큰은 12시간 동안 유효합니다.
+pub fn vapid_authorization(
+    key: &EcKey<Private>,
+    endpoint: &str,
+    subject: &str,
+    now: i64,
+) -> Result<String, String> {
+    let audience = reqwest::Url::parse(endpoint)
+        .map_err(|e| format!("Invalid push endpoint: {}", e))?
+        .origin()
+        .ascii_serialization();
+    let header = json!({ "typ": "JWT", "alg": "ES256" });
+    let claims = json!({ "aud": audience, "exp": now + 12 * 60 * 60, "sub": subject });
+    let unsigned = format!(
+        "{}.{}",
+        encode_base64_url(header.to_string().as_bytes()),
+        encode_base64_url(claims.to_string().as_bytes())
+    );
+
+    let signature = sign_es256(key, unsigned.as_bytes())
+        .map_err(|e| format!("Failed to sign VAPID token: {}", e))?;
+    Ok(format!(
+        "vapid t={}.{}, k={}",
+        unsigned,
+        encode_base64_url(&signature),
+        encode_base64_url(&public_key(key))
+    ))
+}
+
+/// JWS의 ES256 서명을 만듭니다. DER이 아닌 r과 s를 이어붙인 64바이트입니다.
+pub(crate) fn sign_es256(key: &EcKey<Private>, data: &[u8]) -> Result<Vec<u8>, ErrorStack> {
+    let signature = EcdsaSig::sign(&sha256(data), key)?;
+    let mut bytes = signature.r().to_vec_padded(32)?;
+    bytes.extend(signature.s().to_vec_padded(32)?);
+    Ok(bytes)
+}
+
+fn hmac(key: &[u8], parts: &[&[u8]]) -> Result<Vec<u8>, ErrorStack> {
+    let key = PKey::hmac(key)?;
+    let mut signer = Signer::new(MessageDigest::sha256(), &key)?;
+    for part in parts {
+        signer.update(part)?;
+    }
+    signer.sign_to_vec()
+}
+
+/// 알림 내용을 구독 키로 암호화하는 함수입니다. (RFC 8291, `aes128gcm`)
+/// 매번 새 임시 키와 솔트를 사용합니다.
+pub fn encrypt(payload: &[u8], keys: &PushKeys) -> Result<Vec<u8>, String> {
+    let ua_public = decode_base64_url(&keys.p256dh).ok_or("Invalid p256dh key")?;
+    let auth_secret = decode_base64_url(&keys.auth).ok_or("Invalid auth secret")?;
+    let as_key = p256()
+        .and_then(|group| EcKey::generate(&group))
+        .map_err(|e| e.to_string())?;
+    let mut salt = [0u8; 16];
+    rand_bytes(&mut salt).map_err(|e| e.to_string())?;
+    encrypt_with(payload, &ua_public, &auth_secret, &as_key, &salt)
+}
+
+/// 임시 키와 솔트를 지정하여 알림 내용을 암호화하는 함수입니다. 테스트 벡터 확인에 사용합니다.
+///
+/// # Arguments
+///
+/// * `ua_public` - 브라우저의 P-256 공개 키(65바이트)입니다.
+/// * `auth_secret` - 브라우저의 인증 비밀 값(16바이트)입니다.
+/// * `as_key` - 서버의 임시 키입니다.
+/// * `salt` - 16바이트 솔트입니다.
+///
+/// # Returns
+///
+/// 솔트, 레코드 크기, 서버 임시 공개 키를 담은 머리글 뒤에 암호문을 붙여 반환합니다.
+pub fn encrypt_with(
+    payload: &[u8],
+    ua_public: &[u8],
+    auth_secret: &[u8],
+    as_key: &EcKey<Private>,
+    salt: &[u8; 16],
+) -> Result<Vec<u8>, String> {
+    if ua_public.len() != 65 || auth_secret.len() != 16 {
+        return Err("Invalid subscription keys".to_string());
+    }
+    // 마지막 레코드에는 구분 바이트 0x02를 붙이며, 하나의 레코드에 들어가야 함
+    if payload.len() + 1 + 16 > RECORD_SIZE as usize {
+        return Err("Push message is too long".to_string());
+    }
+
+    let encrypted = || -> Result<Vec<u8>, ErrorStack> {
+        let group = p256()?;
+        let mut ctx = BigNumContext::new()?;
+        let as_public =
+            as_key
+                .public_key()
+                .to_bytes(&group, PointConversionForm::UNCOMPRESSED, &mut ctx)?;
+        let ua_point = EcPoint::from_bytes(&group, ua_public, &mut ctx)?;
+        let ua_key = PKey::from_ec_key(EcKey::from_public_key(&group, &ua_point)?)?;
+        let as_pkey = PKey::from_ec_key(as_key.clone())?;
+        let mut deriver = Deriver::new(&as_pkey)?;
+        deriver.set_peer(&ua_key)?;
+        let ecdh_secret = deriver.derive_to_vec()?;
+
+        // IKM = HKDF(auth_secret, ecdh_secret, "WebPush: info" || 0x00 || ua_public || as_public)
+        let prk_key = hmac(auth_secret, &[&ecdh_secret])?;
+        let ikm = hmac(&prk_key, &[b"WebPush: info\0", ua_public, &as_public, &[1]])?;
+        let prk = hmac(salt, &[&ikm])?;
+        let cek = hmac(&prk, &[b"Content-Encoding: aes128gcm\0", &[1]])?;
+        let nonce = hmac(&prk, &[b"Content-Encoding: nonce\0", &[1]])?;
+
+        let mut plaintext = payload.to_vec();
+        plaintext.push(2);
+        let mut tag = [0u8; 16];
+        let ciphertext = encrypt_aead(
+            Cipher::aes_128_gcm(),
+            &cek[..16],
+            Some(&nonce[..12]),
+            &[],
+            &plaintext,
+            &mut tag,
+        )?;
+
+        let mut body = salt.to_vec();
+        body.extend(RECORD_SIZE.to_be_bytes());
+        body.push(as_public.len() as u8);
+        body.extend(&as_public);
+        body.extend(ciphertext);
+        body.extend(tag);
+        Ok(body)
+    };
+    encrypted().map_err(|e| format!("Failed to encrypt push message: {}", e))
+}
+
+/// 구독 정보가 올바른지 확인합니다. 전송 주소는 `https://`여야 하고 키 길이가 맞아야 합니다.
+pub fn is_valid_subscription(subscription: &PushSubscription) -> bool {
+    subscription.endpoint.starts_with("https://")
+        && decode_base64_url(&subscription.keys.p256dh)
+            .is_some_and(|key| key.len() == 65 && key[0] == 4)
+        && decode_base64_url(&subscription.keys.auth).is_some_and(|auth| auth.len() == 16)
+}
+
+impl PushSubscriptionList {
+    /// 유저의 구독을 추가합니다. 같은 전송 주소는 다른 유저의 것이어도 새 구독으로 바꿉니다.
+    pub fn subscribe(&mut self, user_id: &str, subscription: PushSubscription) {
+        self.unsubscribe(&subscription.endpoint);
+        let subscriptions = self.subscriptions.entry(user_id.to_string()).or_default();
+        subscriptions.push(subscription);
+        if subscriptions.len() > MAX_SUBSCRIPTIONS_PER_USER {
+            subscriptions.remove(0);
+        }
+    }
+
+    /// 전송 주소로 구독을 지웁니다. 지운 구독이 있으면 `true`를 반환합니다.
+    pub fn unsubscribe(&mut self, endpoint: &str) -> bool {
+        let mut removed = false;
+        self.subscriptions.retain(|_, subscriptions| {
+            let before = subscriptions.len();
+            subscriptions.retain(|subscription| subscription.endpoint != endpoint);
+            removed |= subscriptions.len() != before;
+            !subscriptions.is_empty()
+        });
+        removed
+    }
+}
+
+fn client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(reqwest::Client::new)
+}
+
+/// 알림 하나를 푸시 서비스로 전송하고 응답 상태 코드를 반환합니다.
+async fn deliver(
+    key: &EcKey<Private>,
+    subject: &str,
+    subscription: &PushSubscription,
+    payload: &[u8],
+) -> Result<u16, String> {
+    let body = encrypt(payload, &subscription.keys)?;
+    let authorization =
+        vapid_authorization(key, &subscription.endpoint, subject, Utc::now().timestamp())?;
+    client()
+        .post(&subscription.endpoint)
+        .header("Authorization", authorization)
+        .header("TTL", TTL_SECS.to_string())
+        .header("Content-Encoding", "aes128gcm")
+        .header("Content-Type", "application/octet-stream")
+        .body(body)
+        .send()
+        .await
+        .map(|response| response.status().as_u16())
+        .map_err(|e| e.to_string())
+}
+
+/// 알림을 여러 구독에 비동기로 전송합니다. 전송 결과를 기다리지 않으며,
+/// 푸시 서비스가 만료되었다고 응답한 구독(404, 410)은 목록에서 지웁니다.
+pub fn send(
+    config: &WebPushConfig,
+    subscriptions: &Data<Mutex<PushSubscriptionList>>,
+    persister: &Data<Persister>,
+    targets: Vec<PushSubscription>,
+    message: &PushMessage,
+) {
+    if targets.is_empty() {
+        return;
+    }
+    let key = match vapid_key(&config.vapid_private_key) {
+        Ok(key) => key,
+        Err(e) => {
+            error!("Web push disabled: {}", e);
+            return;
+        }
+    };
+    let subject = config.subject.clone();
+    let payload = serde_json::to_vec(message).unwrap();
+    let subscriptions = Data::clone(subscriptions);
+    let persister = Data::clone(persister);
+
+    actix_rt::spawn(async move {
+        let mut expired = Vec::new();
+        for subscription in targets {
+            match deliver(&key, &subject, &subscription, &payload).await {
+                Ok(status) if (200..300).contains(&status) => {}
+                Ok(404 | 410) => expired.push(subscription.endpoint),
+                Ok(status) => error!("Web push failed with {}", status),
+                Err(e) => error!("Web push failed : {}", e),
+            }
+        }
+        if !expired.is_empty() {
+            let mut subscriptions = subscriptions.lock_or_recover();
+            for endpoint in &expired {
+                subscriptions.unsubscribe(endpoint);
+            }
+            info!("Removed {} expired push subscriptions", expired.len());
+            persister.mark(&[Dataset::PushSubscriptions]);
+        }
+    });
+}
+
+/// 수량 제한이 있는 스템프별 남은 수량을 반환합니다.
+pub fn remaining_counts(
+    stamp_id_list: &StampIdList,
+    stamp_history: &StampHistory,
+) -> HashMap<String, usize> {
+    stamp_id_list
+        .stamp_id_list
+        .values()
+        .filter_map(|stamp| {
+            let issued = stamp_history.issued(&stamp.stampId);
+            Some((
+                stamp.stampId.clone(),
+                stamp.maxIssued?.saturating_sub(issued),
+            ))
+        })
+        .collect()
+}
+
+/// 남은 수량이 `threshold` 이하로 새로 줄어든 스템프와 남은 수량을 반환합니다. 모두 소진된 스템프는 제외합니다.
+pub fn newly_low_stock(
+    before: &HashMap<String, usize>,
+    after: &HashMap<String, usize>,
+    threshold: usize,
+) -> Vec<(String, usize)> {
+    let mut stamps: Vec<(String, usize)> = after
+        .iter()
+        .filter(|(stamp_id, remaining)| {
+            **remaining > 0
+                && **remaining <= threshold
+                && before
+                    .get(*stamp_id)
+                    .is_none_or(|before| *before > threshold)
+        })
+        .map(|(stamp_id, remaining)| (stamp_id.clone(), *remaining))
+        .collect();
+    stamps.sort();
+    stamps
+}
+
+/// `since` 이후 `until` 이전에 달성한 완주 등급을 (유저 ID, 등급 이름) 목록으로 반환합니다.
+pub fn new_completions(
+    completions: &CompletionList,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> Vec<(String, String)> {
+    completions
+        .completions
+        .iter()
+        .flat_map(|(user_id, records)| {
+            records
+                .iter()
+                .filter(|record| {
+                    parse_timestamp(&record.timestamp)
+                        .is_some_and(|timestamp| timestamp > since && timestamp <= until)
+                })
+                .map(|record| (user_id.clone(), record.tier_name.clone()))
+        })
+        .collect()
+}
+
+/// 완주한 유저와 품절이 임박한 스템프를 주기적으로 확인하여 구독자에게 알리도록 등록합니다.
+/// 서버가 시작되기 전의 기록은 다시 알리지 않습니다.
+pub fn schedule_notifications(state: AppState) {
+    if state.config.get().web_push.is_none() {
+        return;
+    }
+    let mut last_scan = Utc::now();
+    let mut remaining = remaining_counts(&state.stamp_list.get(), &state.stamp_history);
+
+    scheduler::every("web push", NOTIFY_INTERVAL, move || {
+        let config = state.config.get();
+        let Some(web_push) = config.web_push.as_ref() else {
+            return;
+        };
+        let stamp_id_list = state.stamp_list.get();
+        let now = Utc::now();
+
+        let completed = new_completions(&state.completions.lock_or_recover(), last_scan, now);
+        let (current, low_stock) = {
+            let stamp_history = &state.stamp_history;
+            let current = remaining_counts(&stamp_id_list, stamp_history);
+            let low_stock: Vec<(String, usize, BTreeSet<String>)> =
+                newly_low_stock(&remaining, &current, web_push.low_stock_threshold)
+                    .into_iter()
+                    .map(|(stamp_id, left)| {
+                        let collectors = stamp_history
+                            .entries(&stamp_id)
+                            .iter()
+                            .map(|entry| entry.user_id.clone())
+                            .collect();
+                        (stamp_id, left, collectors)
+                    })
+                    .collect();
+            (current, low_stock)
+        };
+        last_scan = now;
+        remaining = current;
+
+        let subscriptions = state.push_subscriptions.lock_or_recover().clone();
+        for (user_id, tier_name) in completed {
+            if let Some(targets) = subscriptions.subscriptions.get(&user_id) {
+                let message = PushMessage::completion(&config, &tier_name);
+                send(
+                    web_push,
+                    &state.push_subscriptions,
+                    &state.persister,
+                    targets.clone(),
+                    &message,
+                );
+            }
+        }
+        for (stamp_id, left, collectors) in low_stock {
+            let Some(stamp) = stamp_id_list.stamp_id_list.get(&stamp_id) else {
+                continue;
+            };
+            info!(
+                "Stamp {} has {} left; notifying subscribers.",
+                stamp_id, left
+            );
+            let targets = subscriptions
+                .subscriptions
+                .iter()
+                .filter(|(user_id, _)| !collectors.contains(*user_id))
+                .flat_map(|(_, targets)| targets.iter().cloned())
+                .collect();
+            let message = PushMessage::low_stock(&config, stamp, left);
+            send(
+                web_push,
+                &state.push_subscriptions,
+                &state.persister,
+                targets,
+                &message,
+            );
+        }
+    });
+}
+
+/// 브라우저가 구독할 때 사용하는 VAPID 공개 키를 반환하는 비동기 함수입니다.
+///
+/// # Returns
+///
+/// `{"public_key": "..."}`가 200 OK 응답으로 반환됩니다. Web Push가 설정되지 않았으면 503 Service Unavailable 응답이 반환됩니다.
+#[get("/api/v1/push/key")]
+pub async fn handle_push_key(config: Data<Reloadable<Config>>) -> Result<HttpResponse, AppError> {
+    let key = config
+        .get()
+        .web_push
+        .as_ref()
+        .map(|web_push| vapid_key(&web_push.vapid_private_key));
+    match key {
+        Some(Ok(key)) => {
+            Ok(HttpResponse::Ok()
+                .json(json!({ "public_key": encode_base64_url(&public_key(&key)) })))
+        }
+        Some(Err(e)) => {
+            error!("{}", e);
+            Err(AppError::Disabled("Web push is unavailable".to_string()))
+        }
+        None => Err(AppError::Disabled("Web push is not configured".to_string())),
+    }
+}
+
+/// 로그인한 유저의 Web Push 구독을 저장하는 비동기 함수입니다.
+///
+/// # Arguments
+///
+/// * `subscription` - `PushManager.subscribe()`가 반환한 구독을 `JSON.stringify`한 본문입니다.
+///
+/// # Returns
+///
+/// 저장하면 200 OK 응답이 반환됩니다. 쿠키가 없거나 등록되지 않은 유저는 401 Unauthorized 응답이,
+/// 구독 형식이 틀리면 400 Bad Request 응답이, Web Push가 설정되지 않았으면 503 Service Unavailable 응답이 반환됩니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// const { public_key } = await (await fetch("/api/v1/push/key")).json();
+/// const subscription = await registration.pushManager.subscribe({ userVisibleOnly: true, applicationServerKey: public_key });
+/// fetch("/api/v1/push/subscribe", { method: "POST", headers: { "Content-Type": "application/json" }, body: JSON.stringify(subscription) });
+/// ```
+#[post("/api/v1/push/subscribe")]
+pub async fn handle_push_subscribe(
+    req: HttpRequest,
+    subscription: Json<PushSubscription>,
+    push_subscriptions: Data<Mutex<PushSubscriptionList>>,
+    config: Data<Reloadable<Config>>,
+    persister: Data<Persister>,
+) -> Result<HttpResponse, AppError> {
+    if config.get().web_push.is_none() {
+        return Err(AppError::Disabled("Web push is not configured".to_string()));
+    }
+    let Some(CurrentUser { user_id, .. }) = CurrentUser::from_cookie(&req) else {
+        warn!("Unauthorized push subscription has been detected.");
+        return Err(AppError::Unauthorized);
+    };
+    if !is_valid_subscription(&subscription) {
+        return Err(AppError::Validation(
+            "Invalid push subscription".to_string(),
+        ));
+    }
+
+    push_subscriptions
+        .lock_or_recover()
+        .subscribe(&user_id, subscription.into_inner());
+    persister.mark(&[Dataset::PushSubscriptions]);
+    info!("User {} subscribed to web push.", user_id);
+    Ok(HttpResponse::Ok().json(json!({ "subscribed": true })))
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Unsubscribe {
+    pub endpoint: String,
+}
+
+/// 로그인한 유저의 Web Push 구독을 지우는 비동기 함수입니다.
+///
+/// # Returns
+///
+/// `{"unsubscribed": true}` 또는 해당 구독이 없으면 `false`가 200 OK 응답으로 반환됩니다.
+/// 쿠키가 없거나 등록되지 않은 유저는 401 Unauthorized 응답이 반환됩니다.
+#[post("/api/v1/push/unsubscribe")]
+pub async fn handle_push_unsubscribe(
+    user: CurrentUser,
+    body: Json<Unsubscribe>,
+    push_subscriptions: Data<Mutex<PushSubscriptionList>>,
+    persister: Data<Persister>,
+) -> HttpResponse {
+    let user_id = user.user_id;
+
+    let mut push_subscriptions = push_subscriptions.lock_or_recover();
+    let owned = push_subscriptions
+        .subscriptions
+        .get(&user_id)
+        .is_some_and(|subscriptions| {
+            subscriptions
+                .iter()
+                .any(|subscription| subscription.endpoint == body.endpoint)
+        });
+    let removed = owned && push_subscriptions.unsubscribe(&body.endpoint);
+    if removed {
+        persister.mark(&[Dataset::PushSubscriptions]);
+    }
+    HttpResponse::Ok().json(json!({ "unsubscribed": removed }))
+}
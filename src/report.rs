@@ -0,0 +1,176 @@
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, Timelike};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs, io,
+    path::PathBuf,
+};
+
+use crate::state::{CompletionList, StampHistory, StampIdList, UserList};
+use crate::template::escape_html;
+
+/// 아침 운영 회의에 사용하는 하루 운영 요약입니다.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DailyReport {
+    /// 요약 대상 날짜(서버 시간대 기준)입니다.
+    pub date: String,
+    /// 지금까지 등록된 전체 유저 수입니다.
+    pub registered_users: usize,
+    /// 이 날 스템프를 한 번 이상 찍은 유저 수입니다.
+    pub active_users: usize,
+    pub total_stamps: usize,
+    /// 부스별, 시간대(0~23시)별 스템프 발급 수입니다.
+    pub stamps_by_booth: BTreeMap<String, BTreeMap<u32, usize>>,
+    /// 이 날 달성된 완주 등급별 인원입니다.
+    pub completions: BTreeMap<String, usize>,
+    /// 스템프 발급 수가 많은 순서의 부스 목록입니다.
+    pub top_booths: Vec<BoothCount>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BoothCount {
+    pub stamp_id: String,
+    pub stamp_name: String,
+    pub count: usize,
+}
+
+/// `chrono::Utc::now().to_string()` 형식으로 저장된 기록 시각을 서버 시간대로 변환합니다.
+pub fn parse_timestamp(timestamp: &str) -> Option<DateTime<Local>> {
+    NaiveDateTime::parse_from_str(timestamp.trim_end_matches(" UTC"), "%Y-%m-%d %H:%M:%S%.f")
+        .ok()
+        .map(|timestamp| timestamp.and_utc().with_timezone(&Local))
+}
+
+/// 주어진 날짜의 운영 요약을 계산하는 함수입니다.
+///
+/// # Arguments
+///
+/// * `date` - 요약할 날짜입니다.
+/// * `stamp_id_list` - 전체 스템프 목록입니다.
+/// * `stamp_history` - 스템프 기록입니다.
+/// * `user_list` - 등록된 유저 목록입니다.
+/// * `completions` - 유저별 완주 기록입니다.
+///
+/// # Returns
+///
+/// 부스별 시간대 발급 수, 완주 인원, 상위 부스를 담은 `DailyReport`가 반환됩니다.
+pub fn daily_report(
+    date: NaiveDate,
+    stamp_id_list: &StampIdList,
+    stamp_history: &StampHistory,
+    user_list: &UserList,
+    completions: &CompletionList,
+) -> DailyReport {
+    let on_date = |timestamp: &str| {
+        parse_timestamp(timestamp).filter(|timestamp| timestamp.date_naive() == date)
+    };
+
+    let mut stamps_by_booth: BTreeMap<String, BTreeMap<u32, usize>> = BTreeMap::new();
+    let mut active_users = BTreeSet::new();
+    for (stamp_id, entries) in stamp_history.to_map().iter() {
+        for entry in entries {
+            if let Some(timestamp) = on_date(&entry.timestamp) {
+                *stamps_by_booth
+                    .entry(stamp_id.clone())
+                    .or_default()
+                    .entry(timestamp.hour())
+                    .or_default() += 1;
+                active_users.insert(entry.user_id.clone());
+            }
+        }
+    }
+
+    let mut completion_counts: BTreeMap<String, usize> = BTreeMap::new();
+    for record in completions.completions.values().flatten() {
+        if on_date(&record.timestamp).is_some() {
+            *completion_counts
+                .entry(record.tier_name.clone())
+                .or_default() += 1;
+        }
+    }
+
+    let mut top_booths: Vec<BoothCount> = stamps_by_booth
+        .iter()
+        .map(|(stamp_id, hours)| BoothCount {
+            stamp_id: stamp_id.clone(),
+            stamp_name: stamp_id_list
+                .stamp_id_list
+                .get(stamp_id)
+                .map(|stamp| stamp.stampName.clone())
+                .unwrap_or_default(),
+            count: hours.values().sum(),
+        })
+        .collect();
+    top_booths.sort_by_key(|booth| std::cmp::Reverse(booth.count));
+
+    DailyReport {
+        date: date.to_string(),
+        registered_users: user_list.users.len(),
+        active_users: active_users.len(),
+        total_stamps: top_booths.iter().map(|booth| booth.count).sum(),
+        stamps_by_booth,
+        completions: completion_counts,
+        top_booths,
+    }
+}
+
+/// 운영 요약을 회의에서 바로 볼 수 있는 HTML 문서로 변환하는 함수입니다.
+pub fn report_html(report: &DailyReport) -> String {
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html lang=\"ko\">\n<head><meta charset=\"utf-8\"><title>{date} 운영 요약</title></head>\n<body>\n<h1>{date} 운영 요약</h1>\n<ul>\n<li>등록 유저: {registered}</li>\n<li>활동 유저: {active}</li>\n<li>발급 스템프: {total}</li>\n</ul>\n",
+        date = report.date,
+        registered = report.registered_users,
+        active = report.active_users,
+        total = report.total_stamps,
+    );
+
+    html.push_str("<h2>완주</h2>\n<table>\n<tr><th>등급</th><th>인원</th></tr>\n");
+    for (tier_name, count) in report.completions.iter() {
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td></tr>\n",
+            escape_html(tier_name),
+            count
+        ));
+    }
+    html.push_str("</table>\n");
+
+    html.push_str("<h2>부스별 시간대 발급 수</h2>\n<table>\n<tr><th>부스</th>");
+    for hour in 0..24 {
+        html.push_str(&format!("<th>{}시</th>", hour));
+    }
+    html.push_str("<th>합계</th></tr>\n");
+    for booth in report.top_booths.iter() {
+        html.push_str(&format!("<tr><td>{}</td>", escape_html(&booth.stamp_name)));
+        let hours = &report.stamps_by_booth[&booth.stamp_id];
+        for hour in 0..24 {
+            html.push_str(&format!("<td>{}</td>", hours.get(&hour).unwrap_or(&0)));
+        }
+        html.push_str(&format!("<td>{}</td></tr>\n", booth.count));
+    }
+    html.push_str("</table>\n</body>\n</html>\n");
+    html
+}
+
+/// 운영 요약을 `resources/reports/{날짜}.json`과 `resources/reports/{날짜}.html`로 저장하는 함수입니다.
+///
+/// # Returns
+///
+/// 저장에 성공하면 HTML 파일 경로를, 실패하면 입출력 오류를 반환합니다.
+pub fn write_report(report: &DailyReport) -> io::Result<PathBuf> {
+    let dir = PathBuf::from("resources/reports");
+    let json_path = dir.join(format!("{}.json", report.date));
+    let html_path = dir.join(format!("{}.html", report.date));
+
+    fs::create_dir_all(&dir)
+        .and_then(|_| fs::write(&json_path, serde_json::to_vec(report)?))
+        .and_then(|_| fs::write(&html_path, report_html(report)))
+        .map(|_| {
+            info!("Daily report saved to {}", html_path.display());
+            html_path
+        })
+        .map_err(|e| {
+            error!("Daily report {} failed: {}", report.date, e);
+            e
+        })
+}
@@ -0,0 +1,102 @@
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    http::header::{HeaderName, HeaderValue},
+    middleware::Next,
+    HttpMessage,
+};
+use std::io::Write;
+use uuid::Uuid;
+
+/// 요청 ID를 주고받는 헤더 이름입니다.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// 요청 확장 데이터에 저장되는 요청 ID입니다.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+/// 현재 처리 중인 요청의 ID를 반환합니다. 요청 처리 밖에서 호출하면 `None`을 반환합니다.
+pub fn current() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+/// 클라이언트가 보낸 요청 ID를 그대로 사용해도 되는지 확인합니다.
+/// 로그를 오염시키지 않도록 64자 이하의 영문, 숫자, `-`, `_`만 허용합니다.
+fn is_valid(request_id: &str) -> bool {
+    !request_id.is_empty()
+        && request_id.len() <= 64
+        && request_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// 요청마다 ID를 부여하는 미들웨어입니다. `X-Request-Id` 헤더가 올바르면 그 값을, 아니면 새 UUID를 사용하며,
+/// 요청 처리 중의 모든 로그에 ID를 붙이고 응답 헤더로 돌려줍니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// App::new()
+///     .wrap(actix_web::middleware::from_fn(assign_request_id))
+///     .configure(routes);
+/// ```
+pub async fn assign_request_id(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let request_id = req
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| is_valid(value))
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    req.extensions_mut().insert(RequestId(request_id.clone()));
+
+    let mut res = REQUEST_ID.scope(request_id.clone(), next.call(req)).await?;
+
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        res.headers_mut()
+            .insert(HeaderName::from_static(REQUEST_ID_HEADER), value);
+    }
+    Ok(res)
+}
+
+/// `env_logger`의 기본 형식에 요청 ID를 덧붙이는 로그 형식 함수입니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// env_logger::Builder::from_env(env_logger::Env::new().default_filter_or("info"))
+///     .format(format_log)
+///     .init();
+/// ```
+pub fn format_log(
+    buf: &mut env_logger::fmt::Formatter,
+    record: &log::Record,
+) -> std::io::Result<()> {
+    let timestamp = buf.timestamp();
+    match current() {
+        Some(request_id) => writeln!(
+            buf,
+            "[{} {:<5} {} {}] {}",
+            timestamp,
+            record.level(),
+            record.target(),
+            request_id,
+            record.args()
+        ),
+        None => writeln!(
+            buf,
+            "[{} {:<5} {}] {}",
+            timestamp,
+            record.level(),
+            record.target(),
+            record.args()
+        ),
+    }
+}
@@ -0,0 +1,506 @@
+use chrono::{DateTime, Duration, Utc};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::lock::MutexExt;
+use crate::persistence::Dataset;
+use crate::report::parse_timestamp;
+use crate::scheduler;
+use crate::state::{
+    AppState, AuditLog, AuditRecord, CompletionList, DeletedUser, StampHistory, StampUserInfo,
+    TeamList, UserList, UserStampList,
+};
+use crate::storage::save_file;
+
+/// 익명화된 유저 ID 앞에 붙는 접두사입니다.
+pub const ANONYMOUS_PREFIX: &str = "anon-";
+
+/// 보관 기간이 지난 개인정보를 처리하는 방법입니다.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PurgeMode {
+    /// 이름을 지우고 유저 ID를 `anon-1`과 같은 익명 ID로 바꿉니다. 방문 순서 등의 흐름은 남습니다.
+    #[default]
+    Anonymize,
+    /// 유저와 스템프 기록을 모두 지우고 집계 통계만 남깁니다.
+    Delete,
+}
+
+/// `resources/config.json`의 `retention` 항목입니다.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct RetentionConfig {
+    /// 행사 종료 후 개인정보를 보관하는 기간(일)입니다. 없으면 자동으로 지우지 않습니다.
+    pub days: Option<i64>,
+    /// 행사 종료 시각(RFC 3339)입니다. 없으면 설정의 `closes_at`을 사용합니다.
+    pub event_end: Option<String>,
+    pub mode: PurgeMode,
+}
+
+impl RetentionConfig {
+    /// 개인정보를 처리할 시각을 계산합니다. 보관 기간이나 행사 종료 시각이 없으면 `None`을 반환합니다.
+    pub fn purge_at(&self, closes_at: Option<&str>) -> Option<DateTime<Utc>> {
+        let days = self.days?;
+        let event_end = self.event_end.as_deref().or(closes_at)?;
+        DateTime::parse_from_rfc3339(event_end)
+            .ok()
+            .map(|event_end| event_end.with_timezone(&Utc) + Duration::days(days))
+    }
+}
+
+/// 개인정보를 지운 뒤에도 남겨두는 집계 통계입니다. `resources/database/aggregate_stats.json`에 저장됩니다.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct AggregateStats {
+    pub registered_users: usize,
+    /// 스템프별 발급 수입니다.
+    pub stamps_by_booth: BTreeMap<String, usize>,
+    /// 완주 등급별 달성 인원입니다.
+    pub completions: BTreeMap<String, usize>,
+    pub purged_at: String,
+}
+
+/// 현재 상태의 집계 통계를 계산합니다.
+pub fn aggregate_stats(
+    user_list: &UserList,
+    stamp_history: &StampHistory,
+    completions: &CompletionList,
+    now: DateTime<Utc>,
+) -> AggregateStats {
+    let mut completion_counts: BTreeMap<String, usize> = BTreeMap::new();
+    for record in completions.completions.values().flatten() {
+        *completion_counts
+            .entry(record.tier_name.clone())
+            .or_default() += 1;
+    }
+
+    AggregateStats {
+        registered_users: user_list.users.len(),
+        stamps_by_booth: stamp_history
+            .stamp_ids()
+            .into_iter()
+            .map(|stamp_id| {
+                let issued = stamp_history.issued(&stamp_id);
+                (stamp_id, issued)
+            })
+            .collect(),
+        completions: completion_counts,
+        purged_at: now.to_rfc3339(),
+    }
+}
+
+/// 처리 대상이 되는 참가자 데이터 묶음입니다. 락 순서(유저 목록, 확인 대기, 스템프 기록, 완주, 팀, 감사 기록)대로 잠근 값을 담습니다.
+pub struct PersonalData<'a> {
+    pub user_list: &'a mut UserList,
+    pub user_stamp_list: &'a mut UserStampList,
+    pub stamp_history: &'a StampHistory,
+    pub completions: &'a mut CompletionList,
+    pub teams: &'a mut TeamList,
+    pub audit_log: &'a mut AuditLog,
+}
+
+/// 스템프 기록에서 가장 큰 익명 ID 번호를 찾습니다. 익명 ID가 없으면 0을 반환합니다.
+fn last_anonymous_number(stamp_history: &StampHistory) -> usize {
+    stamp_history
+        .to_map()
+        .values()
+        .flatten()
+        .filter_map(|entry| {
+            entry
+                .user_id
+                .strip_prefix(ANONYMOUS_PREFIX)?
+                .parse::<usize>()
+                .ok()
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// 유저 한 명의 등록 정보와 확인 대기 스템프, 완주 기록, 팀 참가 정보를 지우고
+/// 스템프 기록은 새 익명 ID로 바꾸는 함수입니다. 부스별 발급 수와 수량 제한은 그대로 유지됩니다.
+///
+/// # Returns
+///
+/// 등록된 유저였으면 스템프 기록에 사용한 익명 ID를, 등록되지 않은 유저면 `None`을 반환합니다.
+pub fn delete_user(data: PersonalData, user_id: &str) -> Option<String> {
+    data.user_list.users.remove(user_id)?;
+    data.user_list
+        .codes
+        .retain(|_, code_user_id| code_user_id != user_id);
+    data.user_stamp_list.user_stamp_list.remove(user_id);
+    data.completions.completions.remove(user_id);
+    data.completions.achievements.remove(user_id);
+    data.completions.codes.remove(user_id);
+    for team in data.teams.teams.values_mut() {
+        team.members.retain(|member| member != user_id);
+    }
+
+    let alias = format!(
+        "{}{}",
+        ANONYMOUS_PREFIX,
+        last_anonymous_number(data.stamp_history) + 1
+    );
+    data.stamp_history.update_all(|_, entries| {
+        for entry in entries.iter_mut().filter(|entry| entry.user_id == user_id) {
+            entry.user_id = alias.clone();
+            entry.user_name.clear();
+        }
+    });
+    for record in data
+        .audit_log
+        .entries
+        .iter_mut()
+        .filter(|record| record.user_id == user_id)
+    {
+        record.user_id = alias.clone();
+    }
+    for record in data
+        .completions
+        .redemptions
+        .iter_mut()
+        .filter(|record| record.user_id == user_id)
+    {
+        record.user_id = alias.clone();
+    }
+    Some(alias)
+}
+
+/// 유저를 되돌릴 수 있게 삭제하는 함수입니다. 등록 정보와 스템프 기록, 완주 기록, 팀 참가 정보를 각 목록에서 빼어
+/// `user_list.deleted`에 보관하므로 삭제된 유저는 모든 조회와 내보내기, 부스별 발급 수에서 빠집니다.
+/// 경품 수령 기록은 같은 코드로 다시 받지 않도록 그대로 둡니다.
+///
+/// # Returns
+///
+/// 삭제했으면 `true`, 등록되지 않은 유저면 `false`를 반환합니다.
+pub fn soft_delete_user(data: PersonalData, user_id: &str, actor: &str, reason: &str) -> bool {
+    let Some(user_name) = data.user_list.users.remove(user_id) else {
+        return false;
+    };
+    let user_code = data.user_list.code_of(user_id).cloned();
+    if let Some(user_code) = &user_code {
+        data.user_list.codes.remove(user_code);
+    }
+    let mut stamps = Vec::new();
+    data.stamp_history.update_all(|stamp_id, entries| {
+        let (removed, kept) = std::mem::take(entries)
+            .into_iter()
+            .partition(|entry| entry.user_id == user_id);
+        *entries = kept;
+        stamps.extend(
+            removed
+                .into_iter()
+                .map(|entry: StampUserInfo| (stamp_id.to_string(), entry)),
+        );
+    });
+    let team_code = data.teams.team_of(user_id).cloned();
+    for team in data.teams.teams.values_mut() {
+        team.members.retain(|member| member != user_id);
+    }
+    data.user_stamp_list.user_stamp_list.remove(user_id);
+
+    data.user_list.deleted.insert(
+        user_id.to_string(),
+        DeletedUser {
+            user_name,
+            user_code,
+            reason: reason.to_string(),
+            deleted_at: Utc::now().to_string(),
+            stamps,
+            completions: data
+                .completions
+                .completions
+                .remove(user_id)
+                .unwrap_or_default(),
+            achievements: data
+                .completions
+                .achievements
+                .remove(user_id)
+                .unwrap_or_default(),
+            completion_code: data.completions.codes.remove(user_id),
+            team_code,
+        },
+    );
+    data.audit_log.entries.push(AuditRecord {
+        action: "delete".to_string(),
+        user_id: user_id.to_string(),
+        stamp_id: None,
+        actor: actor.to_string(),
+        reason: reason.to_string(),
+        timestamp: Utc::now().to_string(),
+    });
+    true
+}
+
+/// 삭제된 유저를 삭제 전 상태로 되돌리는 함수입니다. 유저 코드가 그사이 다른 유저에게 발급되었으면 새 코드를 발급하고,
+/// 팀이 없어졌으면 팀에는 다시 넣지 않습니다.
+///
+/// # Arguments
+///
+/// * `data` - 잠근 참가자 데이터 묶음입니다.
+/// * `user` - 삭제된 유저의 ID 또는 삭제 전 유저 코드입니다.
+/// * `actor` - 감사 기록에 남길 작업자 이름입니다.
+///
+/// # Returns
+///
+/// 되돌린 유저 ID를 반환합니다. 삭제된 유저가 아니면 `None`을 반환합니다.
+pub fn restore_user(data: PersonalData, user: &str, actor: &str) -> Option<String> {
+    let user_code = user.trim().to_uppercase();
+    let user_id = match data.user_list.deleted.contains_key(user) {
+        true => user.to_string(),
+        false => data
+            .user_list
+            .deleted
+            .iter()
+            .find(|(_, deleted)| deleted.user_code.as_ref() == Some(&user_code))
+            .map(|(user_id, _)| user_id.clone())?,
+    };
+    let deleted = data.user_list.deleted.remove(&user_id)?;
+
+    let user_code = deleted
+        .user_code
+        .filter(|user_code| !data.user_list.codes.contains_key(user_code))
+        .unwrap_or_else(|| data.user_list.new_code());
+    data.user_list.codes.insert(user_code, user_id.clone());
+    data.user_list
+        .users
+        .insert(user_id.clone(), deleted.user_name);
+    for (stamp_id, entry) in deleted.stamps {
+        data.stamp_history.push(&stamp_id, entry);
+        data.stamp_history.update(&stamp_id, |entries| {
+            entries.sort_by_key(|entry| parse_timestamp(&entry.timestamp))
+        });
+    }
+    if !deleted.completions.is_empty() {
+        data.completions
+            .completions
+            .insert(user_id.clone(), deleted.completions);
+    }
+    if !deleted.achievements.is_empty() {
+        data.completions
+            .achievements
+            .insert(user_id.clone(), deleted.achievements);
+    }
+    if let Some(completion_code) = deleted.completion_code {
+        data.completions
+            .codes
+            .insert(user_id.clone(), completion_code);
+    }
+    if let Some(team) = deleted
+        .team_code
+        .and_then(|team_code| data.teams.teams.get_mut(&team_code))
+    {
+        team.members.push(user_id.clone());
+    }
+    data.audit_log.entries.push(AuditRecord {
+        action: "undelete".to_string(),
+        user_id: user_id.clone(),
+        stamp_id: None,
+        actor: actor.to_string(),
+        reason: format!("deleted at {}: {}", deleted.deleted_at, deleted.reason),
+        timestamp: Utc::now().to_string(),
+    });
+    Some(user_id)
+}
+
+/// 삭제된 유저의 보관 기록을 영구히 지우는 함수입니다. 감사 기록과 경품 수령 기록의 유저 ID는 익명 ID로 바꿉니다.
+///
+/// # Returns
+///
+/// 영구히 지운 유저 수를 반환합니다.
+pub fn purge_deleted(data: PersonalData) -> usize {
+    let deleted = std::mem::take(&mut data.user_list.deleted);
+    let mut next = last_anonymous_number(data.stamp_history);
+    for user_id in deleted.keys() {
+        next += 1;
+        let alias = format!("{}{}", ANONYMOUS_PREFIX, next);
+        for record in data
+            .audit_log
+            .entries
+            .iter_mut()
+            .filter(|record| &record.user_id == user_id)
+        {
+            record.user_id = alias.clone();
+        }
+        for record in data
+            .completions
+            .redemptions
+            .iter_mut()
+            .filter(|record| &record.user_id == user_id)
+        {
+            record.user_id = alias.clone();
+        }
+    }
+    deleted.len()
+}
+
+/// 이름을 지우고 유저 ID를 익명 ID로 바꾸는 함수입니다. 이미 익명화된 ID는 그대로 둡니다.
+///
+/// # Returns
+///
+/// 익명화한 유저 수를 반환합니다.
+pub fn anonymize(data: PersonalData) -> usize {
+    let mut aliases: HashMap<String, String> = HashMap::new();
+    let mut next = last_anonymous_number(data.stamp_history);
+    let mut alias = |user_id: &str| -> String {
+        if user_id.starts_with(ANONYMOUS_PREFIX) {
+            return user_id.to_string();
+        }
+        aliases
+            .entry(user_id.to_string())
+            .or_insert_with(|| {
+                next += 1;
+                format!("{}{}", ANONYMOUS_PREFIX, next)
+            })
+            .clone()
+    };
+
+    // 유저 목록 순서대로 익명 ID를 부여하여 실행할 때마다 같은 결과가 나오도록 함
+    for user_id in data.user_list.users.keys() {
+        alias(user_id);
+    }
+    data.stamp_history.update_all(|_, entries| {
+        for entry in entries.iter_mut() {
+            entry.user_id = alias(&entry.user_id);
+            entry.user_name.clear();
+        }
+    });
+    data.completions.completions = std::mem::take(&mut data.completions.completions)
+        .into_iter()
+        .map(|(user_id, records)| (alias(&user_id), records))
+        .collect();
+    data.completions.achievements = std::mem::take(&mut data.completions.achievements)
+        .into_iter()
+        .map(|(user_id, records)| (alias(&user_id), records))
+        .collect();
+    data.completions.codes = std::mem::take(&mut data.completions.codes)
+        .into_iter()
+        .map(|(user_id, code)| (alias(&user_id), code))
+        .collect();
+    for record in data.completions.redemptions.iter_mut() {
+        record.user_id = alias(&record.user_id);
+    }
+    for team in data.teams.teams.values_mut() {
+        for member in team.members.iter_mut() {
+            *member = alias(member);
+        }
+    }
+    for record in data.audit_log.entries.iter_mut() {
+        if !record.user_id.is_empty() {
+            record.user_id = alias(&record.user_id);
+        }
+    }
+
+    data.user_list.users.clear();
+    data.user_list.codes.clear();
+    data.user_list.deleted.clear();
+    data.user_stamp_list.user_stamp_list.clear();
+    aliases.len()
+}
+
+/// 유저와 유저별 기록을 모두 지우는 함수입니다. 스템프별 기록 목록은 빈 목록으로 남겨둡니다.
+///
+/// # Returns
+///
+/// 지운 유저 수를 반환합니다.
+pub fn delete(data: PersonalData) -> usize {
+    let removed = data.user_list.users.len();
+    data.user_list.users.clear();
+    data.user_list.codes.clear();
+    data.user_list.deleted.clear();
+    data.user_stamp_list.user_stamp_list.clear();
+    data.stamp_history.update_all(|_, entries| entries.clear());
+    data.completions.completions.clear();
+    data.completions.achievements.clear();
+    data.completions.codes.clear();
+    for record in data.completions.redemptions.iter_mut() {
+        record.user_id.clear();
+    }
+    for team in data.teams.teams.values_mut() {
+        team.members.clear();
+    }
+    for record in data.audit_log.entries.iter_mut() {
+        record.user_id.clear();
+    }
+    removed
+}
+
+/// 보관 기간이 지났으면 개인정보를 익명화하거나 지우고 데이터베이스에 저장하는 함수입니다.
+///
+/// # Returns
+///
+/// 처리했으면 처리한 유저 수를, 아직 보관 기간이거나 처리할 유저가 없으면 `None`을 반환합니다.
+pub fn purge_if_due(state: &AppState, now: DateTime<Utc>) -> Option<usize> {
+    let config = state.config.get();
+    let purge_at = config.retention.purge_at(config.closes_at.as_deref())?;
+    if now < purge_at {
+        return None;
+    }
+
+    let mut user_list = state.user_list.lock_or_recover();
+    let mut user_stamp_list = state.user_stamp_list.lock_or_recover();
+    let stamp_history = state.stamp_history.get_ref();
+    let mut completions = state.completions.lock_or_recover();
+    let mut teams = state.teams.lock_or_recover();
+    let mut audit_log = state.audit_log.lock_or_recover();
+
+    let has_personal_data = !user_list.users.is_empty()
+        || !user_list.deleted.is_empty()
+        || stamp_history
+            .to_map()
+            .values()
+            .flatten()
+            .any(|entry| !entry.user_name.is_empty());
+    if !has_personal_data {
+        return None;
+    }
+
+    // 첫 처리 때의 통계만 남기고, 이후 처리에서는 덮어쓰지 않음
+    if !user_list.users.is_empty() {
+        let stats = aggregate_stats(&user_list, stamp_history, &completions, now);
+        if save_file("aggregate_stats", &stats).is_err() {
+            error!("Retention purge aborted: aggregate statistics could not be saved");
+            return None;
+        }
+    }
+
+    let data = PersonalData {
+        user_list: &mut user_list,
+        user_stamp_list: &mut user_stamp_list,
+        stamp_history,
+        completions: &mut completions,
+        teams: &mut teams,
+        audit_log: &mut audit_log,
+    };
+    let purged = match config.retention.mode {
+        PurgeMode::Anonymize => anonymize(data),
+        PurgeMode::Delete => delete(data),
+    };
+
+    state.persister.mark(&[
+        Dataset::UserStatus,
+        Dataset::StampStatus,
+        Dataset::CompletionStatus,
+        Dataset::TeamStatus,
+        Dataset::AuditLog,
+    ]);
+    warn!(
+        "Retention period ended at {}: {:?} applied to {} users",
+        purge_at, config.retention.mode, purged
+    );
+    Some(purged)
+}
+
+/// 한 시간마다 보관 기간이 지났는지 확인하여 개인정보를 처리하도록 등록합니다.
+pub fn schedule_purge(state: AppState) {
+    if state.config.get().retention.days.is_none() {
+        return;
+    }
+    info!("Personal data retention policy is enabled");
+    scheduler::every(
+        "retention purge",
+        std::time::Duration::from_secs(60 * 60),
+        move || {
+            purge_if_due(&state, Utc::now());
+        },
+    );
+}
@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use crate::state::{Stamp, StampIdList};
+
+/// `resources/config.json`의 `route` 항목입니다. 행사장 동선을 정하기 위해 스템프를 찍는 순서를 지정합니다.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct RouteConfig {
+    /// 차례대로 방문해야 하는 구역 목록입니다. 구역은 스템프 ID 목록이며, 앞 구역의 스템프를 모두 모아야
+    /// 다음 구역의 스템프를 찍을 수 있습니다. 한 구역에 스템프를 하나씩 적으면 정해진 순서대로만 찍을 수 있습니다.
+    /// 어느 구역에도 없는 스템프는 언제든 찍을 수 있으며, 비어있으면 순서를 확인하지 않습니다.
+    pub stages: Vec<Vec<String>>,
+}
+
+/// 스템프를 찍기 전에 먼저 방문해야 하는 부스를 찾는 함수입니다.
+///
+/// # Arguments
+///
+/// * `config` - 방문 순서 설정입니다.
+/// * `stamp_id_list` - 스템프 목록입니다. 목록에서 지운 스템프는 방문하지 않아도 됩니다.
+/// * `stamp_id` - 찍으려는 스템프 ID입니다.
+/// * `collected` - 유저가 이미 모은 스템프 ID 목록입니다.
+///
+/// # Returns
+///
+/// 앞 구역에서 아직 모으지 않은 첫 번째 스템프를 반환합니다. 바로 찍을 수 있으면 `None`을 반환합니다.
+///
+/// # Example
+///
+/// ```rust
+/// use gj_stamptour::route_order::{required_before, RouteConfig};
+/// use gj_stamptour::state::{Stamp, StampIdList};
+/// use std::collections::BTreeSet;
+///
+/// let stamps = StampIdList {
+///     stamp_id_list: ["a", "b", "c"]
+///         .map(|id| (id.to_string(), Stamp { stampId: id.to_string(), ..Default::default() }))
+///         .into(),
+/// };
+/// let config = RouteConfig { stages: vec![vec!["a".to_string()], vec!["b".to_string()]] };
+/// let collected = BTreeSet::from(["a".to_string()]);
+/// assert_eq!(required_before(&config, &stamps, "b", &BTreeSet::new()).unwrap().stampId, "a");
+/// assert!(required_before(&config, &stamps, "b", &collected).is_none());
+/// assert!(required_before(&config, &stamps, "c", &BTreeSet::new()).is_none());
+/// ```
+pub fn required_before<'a>(
+    config: &RouteConfig,
+    stamp_id_list: &'a StampIdList,
+    stamp_id: &str,
+    collected: &BTreeSet<String>,
+) -> Option<&'a Stamp> {
+    let stage = config
+        .stages
+        .iter()
+        .position(|stage| stage.iter().any(|id| id == stamp_id))?;
+    config.stages[..stage]
+        .iter()
+        .flatten()
+        .filter(|id| !collected.contains(*id))
+        .find_map(|id| stamp_id_list.stamp_id_list.get(id))
+}
@@ -0,0 +1,30 @@
+use log::info;
+use std::time::Duration;
+
+/// 주어진 주기마다 작업을 실행하도록 등록하는 함수입니다. 첫 실행은 등록 직후에 이루어집니다.
+/// actix 런타임 안에서 호출해야 합니다.
+///
+/// # Arguments
+///
+/// * `name` - 로그에 표시할 작업 이름입니다.
+/// * `period` - 실행 주기입니다.
+/// * `task` - 실행할 작업입니다. 오래 걸리는 작업은 작업자 스레드를 막지 않도록 짧게 유지해야 합니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// scheduler::every("retention", Duration::from_secs(3600), move || purge_if_due(&state, Utc::now()));
+/// ```
+pub fn every<F>(name: &'static str, period: Duration, mut task: F)
+where
+    F: FnMut() + 'static,
+{
+    info!("Scheduled task {} every {:?}", name, period);
+    actix_rt::spawn(async move {
+        let mut interval = actix_rt::time::interval(period);
+        loop {
+            interval.tick().await;
+            task();
+        }
+    });
+}
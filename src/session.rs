@@ -0,0 +1,73 @@
+use actix_web::{dev::Payload, web::Data, FromRequest, HttpRequest};
+use futures_util::future::LocalBoxFuture;
+use log::warn;
+use std::sync::Mutex;
+
+use crate::error::AppError;
+use crate::lock::MutexExt;
+use crate::metrics::Metrics;
+use crate::state::UserList;
+
+/// 로그인한 유저의 ID를 담는 쿠키 이름입니다.
+pub const SESSION_COOKIE: &str = "user_id";
+
+/// 세션 쿠키로 확인한 등록된 유저입니다.
+///
+/// 핸들러 인수로 받으면 쿠키가 없거나 등록되지 않은 유저 ID인 요청은 핸들러를 실행하지 않고
+/// `error401.html`을 담은 401 Unauthorized 응답으로 끝납니다. 로그인하지 않은 요청에 다른 응답을
+/// 보내야 하는 핸들러는 `CurrentUser::from_cookie`를 직접 호출합니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[get("/api/v1/progress")]
+/// pub async fn handle_progress(user: CurrentUser) -> HttpResponse {
+///     HttpResponse::Ok().body(user.user_name)
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CurrentUser {
+    pub user_id: String,
+    pub user_name: String,
+}
+
+impl CurrentUser {
+    /// 요청의 세션 쿠키가 가리키는 유저를 유저 목록에서 찾습니다.
+    ///
+    /// # Returns
+    ///
+    /// 쿠키가 있고 등록된 유저이면 `Some(CurrentUser)`, 그 외에는 `None`을 반환합니다.
+    pub fn from_cookie(req: &HttpRequest) -> Option<CurrentUser> {
+        let user_id = req.cookie(SESSION_COOKIE)?.value().to_string();
+        let user_list = req.app_data::<Data<Mutex<UserList>>>()?;
+        // 유저 목록 전체를 복사하지 않고 락을 잡은 채로 이름만 꺼냄
+        let user_name = match req.app_data::<Data<Metrics>>() {
+            Some(metrics) => metrics
+                .lock("user_list", user_list)
+                .users
+                .get(&user_id)
+                .cloned(),
+            None => user_list.lock_or_recover().users.get(&user_id).cloned(),
+        }?;
+        Some(CurrentUser { user_id, user_name })
+    }
+}
+
+impl FromRequest for CurrentUser {
+    type Error = AppError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let user = CurrentUser::from_cookie(req);
+        let path = req.path().to_string();
+        Box::pin(async move {
+            match user {
+                Some(user) => Ok(user),
+                None => {
+                    warn!("Unauthorized access to {} has been detected.", path);
+                    Err(AppError::Unauthorized)
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,254 @@
+use actix_web::{
+    get,
+    web::{self, Data, Path},
+    HttpRequest, HttpResponse,
+};
+use resvg::{tiny_skia, usvg};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::base_path::prefixed;
+use crate::bundle::event_path;
+use crate::config::Config;
+use crate::error::AppError;
+use crate::lock::MutexExt;
+use crate::progress::{user_progress, Progress};
+use crate::state::{Reloadable, StampHistory, StampIdList, UserList};
+use crate::template::{escape_html, render};
+
+/// `resources/share.svg`가 없을 때 사용하는 공유 이미지 템플릿입니다. Open Graph 권장 크기인 1200x630입니다.
+const DEFAULT_TEMPLATE: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" width="1200" height="630" viewBox="0 0 1200 630">
+<rect width="1200" height="630" fill="#1e3a8a"/>
+<rect x="40" y="40" width="1120" height="550" rx="32" fill="#ffffff"/>
+<text x="100" y="160" font-family="sans-serif" font-size="44" fill="#1e3a8a">%FESTIVAL_NAME%</text>
+<text x="100" y="300" font-family="sans-serif" font-size="72" font-weight="bold" fill="#111827">%USER_NAME%</text>
+<text x="100" y="430" font-family="sans-serif" font-size="56" fill="#374151">%COLLECTED_COUNT% / %TOTAL_COUNT%</text>
+<text x="100" y="520" font-family="sans-serif" font-size="40" fill="#2563eb">%TIER_NAME%</text>
+</svg>"##;
+
+/// `resources/config.json`의 `share` 항목입니다. 방문객이 SNS에 공유하는 이미지와 페이지에 사용합니다.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct ShareConfig {
+    /// 공유 이미지와 페이지 제목에 표시할 행사 이름입니다.
+    pub festival_name: String,
+}
+
+impl Default for ShareConfig {
+    fn default() -> Self {
+        ShareConfig {
+            festival_name: "스템프 투어".to_string(),
+        }
+    }
+}
+
+/// 공유 이미지에 사용할 글꼴 목록입니다. 시스템 글꼴과 `resources/fonts`의 글꼴을 처음 한 번만 읽습니다.
+static FONTS: OnceLock<Arc<usvg::fontdb::Database>> = OnceLock::new();
+
+fn fonts() -> Arc<usvg::fontdb::Database> {
+    Arc::clone(FONTS.get_or_init(|| {
+        let mut fonts = usvg::fontdb::Database::new();
+        fonts.load_system_fonts();
+        fonts.load_fonts_dir(event_path("fonts"));
+        Arc::new(fonts)
+    }))
+}
+
+/// 공유 이미지 SVG 템플릿에 진행 상황을 채우는 함수입니다.
+///
+/// 템플릿에서는 `%FESTIVAL_NAME%`, `%USER_NAME%`, `%COLLECTED_COUNT%`, `%TOTAL_COUNT%`, `%TIER_NAME%`
+/// 자리표시자를 사용할 수 있으며, 값은 XML에 맞게 이스케이프됩니다.
+///
+/// # Example
+///
+/// ```rust
+/// use gj_stamptour::share::share_svg;
+///
+/// let svg = share_svg("<text>%USER_NAME%</text>", "축제", "<홍길동>", 3, 5, None);
+/// assert_eq!(svg, "<text>&lt;홍길동&gt;</text>");
+/// ```
+pub fn share_svg(
+    template: &str,
+    festival_name: &str,
+    user_name: &str,
+    collected_count: usize,
+    total_count: usize,
+    tier_name: Option<&str>,
+) -> String {
+    render(
+        template,
+        &[
+            ("FESTIVAL_NAME", &escape_html(festival_name)),
+            ("USER_NAME", &escape_html(user_name)),
+            ("COLLECTED_COUNT", &collected_count.to_string()),
+            ("TOTAL_COUNT", &total_count.to_string()),
+            ("TIER_NAME", &escape_html(tier_name.unwrap_or_default())),
+        ],
+    )
+}
+
+/// SVG를 PNG로 변환하는 함수입니다.
+///
+/// # Returns
+///
+/// SVG를 해석하지 못하거나 크기가 0이면 오류 메시지를 반환합니다.
+pub fn rasterize(svg: &str) -> Result<Vec<u8>, String> {
+    let options = usvg::Options {
+        font_family: "sans-serif".to_string(),
+        fontdb: fonts(),
+        ..Default::default()
+    };
+    let tree = usvg::Tree::from_str(svg, &options).map_err(|e| e.to_string())?;
+    let size = tree.size().to_int_size();
+    let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())
+        .ok_or_else(|| "Image size is empty".to_string())?;
+    resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+    pixmap.encode_png().map_err(|e| e.to_string())
+}
+
+/// 유저 코드로 공유할 진행 상황을 찾습니다.
+fn shared_progress(
+    user_code: &str,
+    user_list: &Mutex<UserList>,
+    stamp_id_list: &Reloadable<StampIdList>,
+    stamp_history: &StampHistory,
+    config: &Config,
+) -> Option<Progress> {
+    let (user_id, user_name) = {
+        let user_list = user_list.lock_or_recover();
+        let user_id = user_list.find_by_code(user_code)?.clone();
+        let user_name = user_list.users.get(&user_id)?.clone();
+        (user_id, user_name)
+    };
+    Some(user_progress(
+        config,
+        &stamp_id_list.get(),
+        stamp_history,
+        &user_id,
+        &user_name,
+    ))
+}
+
+/// SNS 공유용 진행 상황 이미지를 PNG로 반환하는 비동기 함수입니다. `resources/share.svg` 템플릿을 사용하며,
+/// 없으면 내장 템플릿을 사용합니다. 한글 이름을 표시하려면 `resources/fonts`에 글꼴을 넣습니다.
+///
+/// # Arguments
+///
+/// * `user_code` - 공유할 유저의 6자리 코드입니다.
+///
+/// # Returns
+///
+/// 유저를 찾은 경우 PNG 이미지가 200 OK 응답으로, 코드가 없으면 404 Not Found 응답이 반환됩니다.
+/// 이미지를 만들지 못하면 500 Internal Server Error 응답이 반환됩니다.
+#[get("/share/{user_code}.png")]
+pub async fn handle_share_image(
+    user_code: Path<String>,
+    user_list: Data<Mutex<UserList>>,
+    stamp_id_list: Data<Reloadable<StampIdList>>,
+    stamp_history: Data<StampHistory>,
+    config: Data<Reloadable<Config>>,
+) -> Result<HttpResponse, AppError> {
+    let config = config.get();
+    let Some(progress) = shared_progress(
+        &user_code,
+        &user_list,
+        &stamp_id_list,
+        &stamp_history,
+        &config,
+    ) else {
+        return Err(AppError::NotFound);
+    };
+
+    let template = async_std::fs::read_to_string(event_path("share.svg"))
+        .await
+        .unwrap_or_else(|_| DEFAULT_TEMPLATE.to_string());
+    let svg = share_svg(
+        &template,
+        &config.share.festival_name,
+        &progress.user_name,
+        progress.collected_count,
+        progress.total_count,
+        progress.tier.as_deref(),
+    );
+    // 래스터화는 CPU를 오래 쓰므로 워커 스레드를 막지 않도록 블로킹 스레드에서 처리
+    let png = web::block(move || rasterize(&svg))
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|png| png)
+        .map_err(|e| AppError::Template(format!("Share image rendering failed : {}", e)))?;
+    Ok(HttpResponse::Ok()
+        .content_type("image/png")
+        .insert_header(("Cache-Control", "public, max-age=300"))
+        .body(png))
+}
+
+/// 공유 페이지 HTML을 만듭니다. SNS 미리보기가 공유 이미지를 표시하도록 Open Graph 메타 태그를 포함합니다.
+pub fn share_html(
+    festival_name: &str,
+    progress: &Progress,
+    page_url: &str,
+    image_url: &str,
+) -> String {
+    let title = format!("{}님의 {}", progress.user_name, festival_name);
+    let description = format!(
+        "스템프 {}개 중 {}개를 모았어요!",
+        progress.total_count, progress.collected_count
+    );
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"ko\">\n<head><meta charset=\"utf-8\"><meta name=\"viewport\" content=\"width=device-width, initial-scale=1\"><title>{title}</title>\n<meta property=\"og:type\" content=\"website\">\n<meta property=\"og:title\" content=\"{title}\">\n<meta property=\"og:description\" content=\"{description}\">\n<meta property=\"og:url\" content=\"{page_url}\">\n<meta property=\"og:image\" content=\"{image_url}\">\n<meta property=\"og:image:width\" content=\"1200\">\n<meta property=\"og:image:height\" content=\"630\">\n<meta name=\"twitter:card\" content=\"summary_large_image\">\n<style>body{{margin:0;font-family:sans-serif;text-align:center}}img{{max-width:100%;height:auto}}</style></head>\n<body>\n<img src=\"{image_url}\" alt=\"{title}\">\n<p>{description}</p>\n</body>\n</html>\n",
+        title = escape_html(&title),
+        description = escape_html(&description),
+        page_url = escape_html(page_url),
+        image_url = escape_html(image_url),
+    )
+}
+
+/// 공유 이미지를 미리보기로 보여주는 공유 페이지를 반환하는 비동기 함수입니다.
+///
+/// # Arguments
+///
+/// * `user_code` - 공유할 유저의 6자리 코드입니다.
+///
+/// # Returns
+///
+/// 유저를 찾은 경우 Open Graph 메타 태그를 담은 HTML이 200 OK 응답으로, 코드가 없으면 404 Not Found 응답이 반환됩니다.
+#[get("/share/{user_code}")]
+pub async fn handle_share_page(
+    req: HttpRequest,
+    user_code: Path<String>,
+    user_list: Data<Mutex<UserList>>,
+    stamp_id_list: Data<Reloadable<StampIdList>>,
+    stamp_history: Data<StampHistory>,
+    config: Data<Reloadable<Config>>,
+) -> Result<HttpResponse, AppError> {
+    let config = config.get();
+    let Some(progress) = shared_progress(
+        &user_code,
+        &user_list,
+        &stamp_id_list,
+        &stamp_history,
+        &config,
+    ) else {
+        return Err(AppError::NotFound);
+    };
+
+    // SNS 미리보기는 절대 주소만 읽으므로 요청 호스트로 전체 주소를 만듦
+    let user_code = user_code.trim().to_uppercase();
+    let connection = req.connection_info();
+    let origin = format!("{}://{}", connection.scheme(), connection.host());
+    let page_url = format!(
+        "{}{}",
+        origin,
+        prefixed(&req, &format!("/share/{}", user_code))
+    );
+    let image_url = format!("{}.png", page_url);
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(share_html(
+            &config.share.festival_name,
+            &progress,
+            &page_url,
+            &image_url,
+        )))
+}
@@ -0,0 +1,290 @@
+use chrono::Utc;
+use log::{error, info};
+use openssl::{hash::MessageDigest, pkey::PKey, sign::Signer};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{collections::HashMap, time::Duration};
+
+use crate::config::Config;
+use crate::crypto::encode_base64_url;
+use crate::lock::MutexExt;
+use crate::progress::current_tier;
+use crate::scheduler;
+use crate::state::{AppState, StampHistory, StampIdList, TeamList, UserList};
+
+/// Sheets API에 요청할 권한입니다.
+const SHEETS_SCOPE: &str = "https://www.googleapis.com/auth/spreadsheets";
+
+/// `resources/config.json`의 `sheets` 항목입니다. 설정하면 등록 현황과 스템프별 발급 수를
+/// 주기적으로 Google 스프레드시트에 덮어씁니다.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SheetsConfig {
+    /// 스프레드시트 주소의 `/d/` 뒤에 있는 ID입니다.
+    pub spreadsheet_id: String,
+    /// Google Cloud 서비스 계정 키(JSON) 파일 경로입니다. 스프레드시트를 이 계정의 이메일과 공유해야 합니다.
+    pub credentials_file: String,
+    /// 업로드 주기(초)입니다. 기본값은 300초입니다.
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+    /// 참가자 목록을 쓸 시트 이름입니다.
+    #[serde(default = "default_users_sheet")]
+    pub users_sheet: String,
+    /// 스템프별 발급 수를 쓸 시트 이름입니다.
+    #[serde(default = "default_totals_sheet")]
+    pub totals_sheet: String,
+}
+
+fn default_interval_secs() -> u64 {
+    300
+}
+
+fn default_users_sheet() -> String {
+    "Registrations".to_string()
+}
+
+fn default_totals_sheet() -> String {
+    "Totals".to_string()
+}
+
+/// 서비스 계정 키 파일 중 인증에 필요한 항목입니다.
+#[derive(Deserialize, Debug, Clone)]
+pub struct ServiceAccount {
+    pub client_email: String,
+    pub private_key: String,
+    #[serde(default = "default_token_uri")]
+    pub token_uri: String,
+}
+
+fn default_token_uri() -> String {
+    "https://oauth2.googleapis.com/token".to_string()
+}
+
+/// 참가자 시트의 내용을 만드는 함수입니다. 첫 행은 머리글이며 유저 코드 순서로 정렬합니다.
+///
+/// # Returns
+///
+/// `user_code`, `user_name`, `team_name`, `collected_count`, `points`, `tier` 열을 반환합니다.
+pub fn registration_rows(
+    config: &Config,
+    stamp_id_list: &StampIdList,
+    stamp_history: &StampHistory,
+    user_list: &UserList,
+    teams: &TeamList,
+) -> Vec<Vec<Value>> {
+    let collections = stamp_history.collections();
+    let codes: HashMap<&String, &String> = user_list
+        .codes
+        .iter()
+        .map(|(user_code, user_id)| (user_id, user_code))
+        .collect();
+    let team_names: HashMap<&String, &String> = teams
+        .teams
+        .values()
+        .flat_map(|team| team.members.iter().map(|member| (member, &team.team_name)))
+        .collect();
+    let tiers = config.tiers(stamp_id_list.visible_count());
+
+    let mut users: Vec<(&String, &String)> = user_list.users.iter().collect();
+    users.sort_by_key(|(user_id, _)| codes.get(user_id).copied());
+    let rows = users.into_iter().map(|(user_id, user_name)| {
+        let collected: Vec<&String> = collections
+            .get(user_id)
+            .into_iter()
+            .flatten()
+            .filter(|stamp_id| stamp_id_list.stamp_id_list.contains_key(*stamp_id))
+            .collect();
+        let count = collected
+            .iter()
+            .filter(|stamp_id| !stamp_id_list.is_hidden(stamp_id))
+            .count();
+        vec![
+            json!(codes.get(user_id).map_or("", |code| code.as_str())),
+            json!(user_name),
+            json!(team_names.get(user_id).map_or("", |name| name.as_str())),
+            json!(count),
+            json!(stamp_id_list.points(collected.iter().copied())),
+            json!(current_tier(&tiers, count).unwrap_or_default()),
+        ]
+    });
+
+    std::iter::once(
+        [
+            "user_code",
+            "user_name",
+            "team_name",
+            "collected_count",
+            "points",
+            "tier",
+        ]
+        .map(|header| json!(header))
+        .to_vec(),
+    )
+    .chain(rows)
+    .collect()
+}
+
+/// 스템프별 발급 수 시트의 내용을 만드는 함수입니다. 첫 행은 머리글이며 스템프 ID 순서로 정렬합니다.
+///
+/// # Returns
+///
+/// `stamp_id`, `stamp_name`, `issued`, `max_issued` 열을 반환합니다. 수량 제한이 없으면 `max_issued`는 비어 있습니다.
+pub fn total_rows(stamp_id_list: &StampIdList, stamp_history: &StampHistory) -> Vec<Vec<Value>> {
+    let mut stamps: Vec<_> = stamp_id_list.stamp_id_list.values().collect();
+    stamps.sort_by(|a, b| a.stampId.cmp(&b.stampId));
+    let rows = stamps.into_iter().map(|stamp| {
+        let issued = stamp_history.issued(&stamp.stampId);
+        vec![
+            json!(stamp.stampId),
+            json!(stamp.stampName),
+            json!(issued),
+            stamp
+                .maxIssued
+                .map_or(json!(""), |max_issued| json!(max_issued)),
+        ]
+    });
+
+    std::iter::once(
+        ["stamp_id", "stamp_name", "issued", "max_issued"]
+            .map(|header| json!(header))
+            .to_vec(),
+    )
+    .chain(rows)
+    .collect()
+}
+
+/// 서비스 계정 키로 액세스 토큰 요청에 사용할 RS256 JWT를 만드는 함수입니다.
+///
+/// # Arguments
+///
+/// * `account` - 서비스 계정 키입니다.
+/// * `now` - 발급 시각(Unix 초)입니다. 토큰은 한 시간 동안 유효합니다.
+pub fn signed_jwt(account: &ServiceAccount, now: i64) -> Result<String, String> {
+    let header = json!({ "alg": "RS256", "typ": "JWT" });
+    let claims = json!({
+        "iss": account.client_email,
+        "scope": SHEETS_SCOPE,
+        "aud": account.token_uri,
+        "iat": now,
+        "exp": now + 3600,
+    });
+    let unsigned = format!(
+        "{}.{}",
+        encode_base64_url(header.to_string().as_bytes()),
+        encode_base64_url(claims.to_string().as_bytes())
+    );
+
+    let key = PKey::private_key_from_pem(account.private_key.as_bytes())
+        .map_err(|e| format!("Invalid service account key: {}", e))?;
+    let signature = Signer::new(MessageDigest::sha256(), &key)
+        .and_then(|mut signer| {
+            signer.update(unsigned.as_bytes())?;
+            signer.sign_to_vec()
+        })
+        .map_err(|e| format!("Failed to sign the token request: {}", e))?;
+    Ok(format!("{}.{}", unsigned, encode_base64_url(&signature)))
+}
+
+/// 시트 이름을 A1 표기법에서 사용할 수 있도록 작은따옴표로 감쌉니다.
+fn quoted(sheet: &str) -> String {
+    format!("'{}'", sheet.replace('\'', "''"))
+}
+
+/// 서비스 계정으로 액세스 토큰을 받은 뒤 두 시트를 비우고 새 내용으로 덮어쓰는 비동기 함수입니다.
+pub async fn push(
+    client: &reqwest::Client,
+    config: &SheetsConfig,
+    users: Vec<Vec<Value>>,
+    totals: Vec<Vec<Value>>,
+) -> Result<(), String> {
+    let account: ServiceAccount = async_std::fs::read_to_string(&config.credentials_file)
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|contents| serde_json::from_str(&contents).map_err(|e| e.to_string()))
+        .map_err(|e| format!("Cannot read {}: {}", config.credentials_file, e))?;
+    let assertion = signed_jwt(&account, Utc::now().timestamp())?;
+
+    let token: Value = client
+        .post(&account.token_uri)
+        .form(&[
+            ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+            ("assertion", assertion.as_str()),
+        ])
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Token request failed: {}", e))?
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+    let access_token = token["access_token"]
+        .as_str()
+        .ok_or("Token response has no access_token")?;
+
+    let base = format!(
+        "https://sheets.googleapis.com/v4/spreadsheets/{}/values",
+        config.spreadsheet_id
+    );
+    let sheets = [&config.users_sheet, &config.totals_sheet];
+    client
+        .post(format!("{}:batchClear", base))
+        .bearer_auth(access_token)
+        .json(&json!({ "ranges": sheets.map(|sheet| quoted(sheet)) }))
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Clearing sheets failed: {}", e))?;
+    client
+        .post(format!("{}:batchUpdate", base))
+        .bearer_auth(access_token)
+        .json(&json!({
+            "valueInputOption": "RAW",
+            "data": [
+                { "range": format!("{}!A1", quoted(&config.users_sheet)), "values": users },
+                { "range": format!("{}!A1", quoted(&config.totals_sheet)), "values": totals },
+            ],
+        }))
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Updating sheets failed: {}", e))?;
+    Ok(())
+}
+
+/// 현재 상태를 시트 내용으로 만든 뒤 업로드를 비동기로 시작합니다. 업로드를 기다리지 않습니다.
+pub fn sync_now(state: &AppState, client: &reqwest::Client) {
+    let config = state.config.get();
+    let Some(sheets) = config.sheets.clone() else {
+        return;
+    };
+    let stamp_id_list = state.stamp_list.get();
+    let (users, totals) = {
+        let user_list = state.user_list.lock_or_recover();
+        let stamp_history = &state.stamp_history;
+        let teams = state.teams.lock_or_recover();
+        (
+            registration_rows(&config, &stamp_id_list, stamp_history, &user_list, &teams),
+            total_rows(&stamp_id_list, stamp_history),
+        )
+    };
+
+    let client = client.clone();
+    actix_rt::spawn(async move {
+        match push(&client, &sheets, users, totals).await {
+            Ok(()) => info!("Results pushed to Google Sheets"),
+            Err(e) => error!("Google Sheets sync failed: {}", e),
+        }
+    });
+}
+
+/// `sheets.interval_secs`마다 Google 스프레드시트에 결과를 올리도록 등록합니다.
+pub fn schedule_sync(state: AppState) {
+    let Some(sheets) = state.config.get().sheets.clone() else {
+        return;
+    };
+    let client = reqwest::Client::new();
+    scheduler::every(
+        "google sheets sync",
+        Duration::from_secs(sheets.interval_secs.max(60)),
+        move || sync_now(&state, &client),
+    );
+}
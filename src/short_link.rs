@@ -0,0 +1,170 @@
+use actix_web::{
+    get,
+    web::{Data, Path, Redirect},
+    HttpRequest, HttpResponse, Responder,
+};
+use chrono::Utc;
+use log::{info, warn};
+use std::sync::Mutex;
+
+use crate::base_path::prefixed;
+use crate::booth::check_path;
+use crate::config::Config;
+use crate::error::AppError;
+use crate::lock::MutexExt;
+use crate::persistence::{Dataset, Persister};
+use crate::state::{generate_code, Reloadable, ShortLink, ShortLinkList, StampIdList};
+
+/// 짧은 코드의 길이입니다. 32글자 알파벳이므로 약 3,300만 개의 코드를 만들 수 있습니다.
+const SHORT_CODE_LENGTH: usize = 5;
+
+impl ShortLinkList {
+    /// 스템프 코드로 이동하는 짧은 주소를 새로 만듭니다.
+    ///
+    /// # Returns
+    ///
+    /// 새로 만든 짧은 코드를 반환합니다.
+    pub fn create(&mut self, stamp_code: &str) -> String {
+        let mut short = generate_code(SHORT_CODE_LENGTH);
+        while self.links.contains_key(&short) {
+            short = generate_code(SHORT_CODE_LENGTH);
+        }
+        self.links.insert(
+            short.clone(),
+            ShortLink {
+                stamp_code: stamp_code.to_string(),
+                created_at: Utc::now().to_string(),
+                revoked_at: None,
+            },
+        );
+        short
+    }
+
+    /// 짧은 주소를 폐기합니다. 해당 코드의 사용 중인 주소가 없으면 `false`를 반환합니다.
+    pub fn revoke(&mut self, short: &str) -> bool {
+        match self.links.get_mut(&short.to_uppercase()) {
+            Some(link) if link.revoked_at.is_none() => {
+                link.revoked_at = Some(Utc::now().to_string());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 사용 중인 짧은 코드의 스템프 코드를 찾습니다. 받아 적은 코드도 찾을 수 있도록 대소문자를 구분하지 않습니다.
+    pub fn find(&self, short: &str) -> Option<&str> {
+        self.links
+            .get(&short.to_uppercase())
+            .filter(|link| link.revoked_at.is_none())
+            .map(|link| link.stamp_code.as_str())
+    }
+}
+
+/// `short create <스템프 ID 또는 별칭>`, `short revoke <코드>`, `short list` 관리자 명령을 처리하는 함수입니다.
+///
+/// # Arguments
+///
+/// * `args` - `short ` 뒤의 명령입니다.
+/// * `short_links` - 짧은 주소 목록입니다.
+/// * `stamp_id_list` - 스템프 코드 확인에 사용할 스템프 목록입니다.
+/// * `persister` - 바뀐 목록을 저장할 `Persister`입니다.
+/// * `base_path` - 출력하는 주소 앞에 붙일 URL 접두사입니다.
+///
+/// # Returns
+///
+/// 관리자 명령의 출력을 반환합니다.
+pub fn short_command(
+    args: &str,
+    short_links: &Mutex<ShortLinkList>,
+    stamp_id_list: &StampIdList,
+    persister: &Persister,
+    base_path: &str,
+) -> String {
+    let usage = "Usage: short create <stamp_id>|short revoke <code>|short list".to_string();
+    let (action, arg) = args.trim().split_once(' ').unwrap_or((args.trim(), ""));
+    let arg = arg.trim();
+    match action {
+        "create" if !arg.is_empty() => {
+            if stamp_id_list.resolve(arg).is_none() {
+                return format!("Stamp {} not found", arg);
+            }
+            let short = short_links.lock_or_recover().create(arg);
+            persister.mark(&[Dataset::ShortLinks]);
+            info!("Short link {} created for stamp code {}", short, arg);
+            format!("{}/s/{} -> /check?s={}", base_path, short, arg)
+        }
+        "revoke" if !arg.is_empty() => {
+            if !short_links.lock_or_recover().revoke(arg) {
+                return format!("Short link {} not found", arg);
+            }
+            persister.mark(&[Dataset::ShortLinks]);
+            info!("Short link {} revoked", arg);
+            format!("Short link {} revoked", arg.to_uppercase())
+        }
+        "list" => {
+            let short_links = short_links.lock_or_recover();
+            if short_links.links.is_empty() {
+                return "No short links".to_string();
+            }
+            short_links
+                .links
+                .iter()
+                .map(|(short, link)| {
+                    format!(
+                        "{}/s/{} -> {}{}",
+                        base_path,
+                        short,
+                        link.stamp_code,
+                        if link.revoked_at.is_some() {
+                            " (revoked)"
+                        } else {
+                            ""
+                        }
+                    )
+                })
+                .collect::<Vec<String>>()
+                .join("\n")
+        }
+        _ => usage,
+    }
+}
+
+/// 짧은 주소를 스템프를 찍는 `/check` 주소로 보내는 비동기 함수입니다.
+/// QR 코드를 바꾸는 경우 부스 화면과 같이 현재 토큰을 붙이므로, 유출된 짧은 주소는 바로 폐기해야 합니다.
+///
+/// # Returns
+///
+/// 사용 중인 짧은 코드이면 `/check`로 가는 임시 리다이렉션(307)을, 없거나 폐기된 코드이면 404 응답을 반환합니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let app = App::new().service(handle_short_link);
+/// ```
+#[get("/s/{short}")]
+pub async fn handle_short_link(
+    req: HttpRequest,
+    short: Path<String>,
+    short_links: Data<Mutex<ShortLinkList>>,
+    stamp_id_list: Data<Reloadable<StampIdList>>,
+    config: Data<Reloadable<Config>>,
+) -> Result<HttpResponse, AppError> {
+    let stamp_code = short_links
+        .lock_or_recover()
+        .find(&short)
+        .map(str::to_string);
+    let stamp_id_list = stamp_id_list.get();
+    // 목록에서 지운 별칭을 가리키는 주소도 찾지 못한 것으로 처리
+    let Some((stamp_code, stamp)) =
+        stamp_code.and_then(|code| stamp_id_list.resolve(&code).map(|stamp| (code, stamp)))
+    else {
+        warn!("Unknown or revoked short link {} was requested.", short);
+        return Err(AppError::NotFound);
+    };
+
+    let check_path = check_path(&config.get().booth, &stamp.stampId, &stamp_code, Utc::now());
+    Ok(Redirect::to(prefixed(&req, &check_path))
+        .temporary()
+        .respond_to(&req)
+        .map_into_boxed_body())
+}
@@ -0,0 +1,83 @@
+use actix_rt::time::{sleep, Instant};
+use actix_web::{dev::ServerHandle, web::Data};
+use futures_util::future::join_all;
+use log::{error, info, warn};
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+use crate::metrics::Metrics;
+
+/// SIGINT(Ctrl+C) 또는 SIGTERM을 받을 때까지 기다립니다.
+pub async fn wait_for_signal() {
+    #[cfg(unix)]
+    {
+        use actix_rt::signal::unix::{signal, SignalKind};
+        let Ok(mut terminate) = signal(SignalKind::terminate()) else {
+            error!("Failed to install SIGTERM handler");
+            let _ = actix_rt::signal::ctrl_c().await;
+            return;
+        };
+        tokio::select! {
+            _ = actix_rt::signal::ctrl_c() => {}
+            _ = terminate.recv() => {}
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = actix_rt::signal::ctrl_c().await;
+}
+
+/// 서버들이 새 연결을 받지 않고 처리 중인 요청을 모두 마칠 때까지 기다린 뒤 멈추는 함수입니다.
+///
+/// actix의 정상 종료(`stop(true)`)는 작업자가 종료 요청보다 연결 채널이 닫힌 것을 먼저 보면
+/// 처리 중인 요청을 끊으므로, 먼저 연결 수락을 멈추고 `record_latency`가 세는 처리 중인 요청이 없어진 뒤에 멈춥니다.
+///
+/// # Arguments
+///
+/// * `servers` - 멈출 서버 목록입니다. `HttpServer::disable_signals`로 실행해야 합니다.
+/// * `metrics` - 처리 중인 요청 수를 세는 메트릭입니다.
+/// * `timeout` - 처리 중인 요청을 기다리는 최대 시간입니다. 지나면 남은 요청을 끊습니다.
+pub async fn drain(servers: Vec<ServerHandle>, metrics: &Metrics, timeout: Duration) {
+    join_all(servers.iter().map(|server| server.pause())).await;
+    let start = Instant::now();
+    while metrics.in_flight() > 0 && start.elapsed() < timeout {
+        sleep(Duration::from_millis(50)).await;
+    }
+    match metrics.in_flight() {
+        0 => {}
+        remaining => warn!(
+            "{} requests did not finish within the shutdown timeout",
+            remaining
+        ),
+    }
+    join_all(servers.iter().map(|server| server.stop(true))).await;
+}
+
+/// 종료 신호를 받으면 gRPC 서버를 멈추고 HTTP 서버들의 처리 중인 요청을 마무리하도록 합니다.
+///
+/// actix의 기본 신호 처리는 SIGINT에 처리 중인 요청을 바로 끊으므로, 어떤 종료 신호든 요청을 마친 뒤 멈춥니다.
+/// 서버가 모두 멈추면 `run`이 남은 변경을 저장하므로 마무리된 `/stamp/` 요청의 기록도 저장됩니다.
+///
+/// # Arguments
+///
+/// * `servers` - 멈출 HTTP 서버 목록입니다.
+/// * `grpc_stop` - gRPC 서버에 종료를 알리는 채널입니다.
+/// * `metrics` - 처리 중인 요청 수를 세는 메트릭입니다.
+/// * `timeout` - 처리 중인 요청을 기다리는 최대 시간입니다.
+pub fn drain_on_signal(
+    servers: Vec<ServerHandle>,
+    grpc_stop: oneshot::Sender<()>,
+    metrics: Data<Metrics>,
+    timeout: Duration,
+) {
+    actix_rt::spawn(async move {
+        wait_for_signal().await;
+        info!(
+            "Shutdown signal received, finishing {} in-flight requests (up to {}s)",
+            metrics.in_flight(),
+            timeout.as_secs()
+        );
+        let _ = grpc_stop.send(());
+        drain(servers, &metrics, timeout).await;
+        info!("All connections drained");
+    });
+}
@@ -0,0 +1,273 @@
+use futures_util::future::join_all;
+use log::{error, info};
+use reqwest::{header::COOKIE, redirect::Policy, Client, StatusCode};
+use serde_json::json;
+use std::{
+    collections::BTreeMap,
+    fmt,
+    time::{Duration, Instant},
+};
+
+use crate::state::{StampInfo, User};
+
+/// 가상 방문객이 거치는 요청 단계입니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Step {
+    Login,
+    Check,
+    Stamp,
+}
+
+impl Step {
+    pub fn name(self) -> &'static str {
+        match self {
+            Step::Login => "login",
+            Step::Check => "check",
+            Step::Stamp => "stamp",
+        }
+    }
+}
+
+/// 단계별 요청 결과입니다.
+#[derive(Debug, Default, Clone)]
+pub struct StepResult {
+    /// 성공한 요청의 응답 시간입니다.
+    pub latencies: Vec<Duration>,
+    pub failures: usize,
+}
+
+/// 부하 시험 결과입니다.
+#[derive(Debug, Default, Clone)]
+pub struct SimulationReport {
+    pub visitors: usize,
+    pub elapsed: Duration,
+    pub steps: BTreeMap<Step, StepResult>,
+}
+
+impl SimulationReport {
+    fn record(&mut self, step: Step, latency: Duration, ok: bool) {
+        let result = self.steps.entry(step).or_default();
+        if ok {
+            result.latencies.push(latency);
+        } else {
+            result.failures += 1;
+        }
+    }
+
+    /// 보낸 요청 수입니다.
+    pub fn requests(&self) -> usize {
+        self.steps
+            .values()
+            .map(|result| result.latencies.len() + result.failures)
+            .sum()
+    }
+
+    /// 실패한 요청 수입니다.
+    pub fn failures(&self) -> usize {
+        self.steps.values().map(|result| result.failures).sum()
+    }
+
+    /// 초당 처리한 요청 수입니다.
+    pub fn throughput(&self) -> f64 {
+        match self.elapsed.as_secs_f64() {
+            secs if secs > 0.0 => self.requests() as f64 / secs,
+            _ => 0.0,
+        }
+    }
+}
+
+/// 응답 시간 목록에서 백분위수를 구합니다. (nearest-rank 방식)
+///
+/// # Example
+///
+/// ```rust
+/// use gj_stamptour::simulate::percentile;
+/// use std::time::Duration;
+///
+/// let latencies: Vec<Duration> = (1..=100).map(Duration::from_millis).collect();
+/// assert_eq!(percentile(&latencies, 50.0), Some(Duration::from_millis(50)));
+/// assert_eq!(percentile(&latencies, 99.0), Some(Duration::from_millis(99)));
+/// assert_eq!(percentile(&[], 50.0), None);
+/// ```
+pub fn percentile(latencies: &[Duration], p: f64) -> Option<Duration> {
+    if latencies.is_empty() {
+        return None;
+    }
+    let mut sorted = latencies.to_vec();
+    sorted.sort();
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+impl fmt::Display for SimulationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} visitors, {} requests ({} failed) in {:.2}s: {:.1} req/s",
+            self.visitors,
+            self.requests(),
+            self.failures(),
+            self.elapsed.as_secs_f64(),
+            self.throughput()
+        )?;
+        let ms = |latency: Option<Duration>| {
+            latency.map_or("-".to_string(), |latency| {
+                format!("{:.1}ms", latency.as_secs_f64() * 1000.0)
+            })
+        };
+        for (step, result) in &self.steps {
+            writeln!(
+                f,
+                "{:<6} ok {:>7} failed {:>5}  p50 {:>9}  p90 {:>9}  p99 {:>9}  max {:>9}",
+                step.name(),
+                result.latencies.len(),
+                result.failures,
+                ms(percentile(&result.latencies, 50.0)),
+                ms(percentile(&result.latencies, 90.0)),
+                ms(percentile(&result.latencies, 99.0)),
+                ms(result.latencies.iter().max().copied()),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// 방문객 한 명이 등록한 뒤 모든 스템프를 찍습니다. 방문객마다 시작 부스를 달리하여 한 부스에 몰리지 않도록 합니다.
+async fn visit(
+    client: &Client,
+    target: &str,
+    index: usize,
+    stamp_ids: &[String],
+) -> Vec<(Step, Duration, bool)> {
+    let mut results = Vec::new();
+
+    let started = Instant::now();
+    let user = match client
+        .post(format!("{}/api/v1/login", target))
+        .json(&json!({ "user_name": format!("simulated-{}", index) }))
+        .send()
+        .await
+    {
+        Ok(response) if response.status().is_success() => response.json::<User>().await.ok(),
+        _ => None,
+    };
+    results.push((Step::Login, started.elapsed(), user.is_some()));
+    let Some(user) = user else {
+        return results;
+    };
+    let cookie = format!("user_id={}", user.user_id);
+
+    for offset in 0..stamp_ids.len() {
+        let stamp_id = &stamp_ids[(index + offset) % stamp_ids.len()];
+
+        // `/check`는 스템프 페이지로 리다이렉션하며, 리다이렉션은 따라가지 않고 `/stamp/`를 직접 요청
+        let started = Instant::now();
+        let checked = client
+            .get(format!("{}/check?s={}", target, stamp_id))
+            .header(COOKIE, &cookie)
+            .send()
+            .await
+            .is_ok_and(|response| response.status() == StatusCode::TEMPORARY_REDIRECT);
+        results.push((Step::Check, started.elapsed(), checked));
+
+        let started = Instant::now();
+        let stamped = client
+            .get(format!("{}/stamp/", target))
+            .header(COOKIE, &cookie)
+            .send()
+            .await
+            .is_ok_and(|response| response.status() == StatusCode::OK);
+        results.push((Step::Stamp, started.elapsed(), stamped));
+    }
+    results
+}
+
+/// 실행 중인 서버에 가상 방문객 `visitors`명을 동시에 보내 등록, 확인, 스템프 찍기를 반복하는 부하 시험을 합니다.
+///
+/// 부스별 QR 토큰(`booth.qr_rotate_secs`)을 사용하는 서버에서는 확인 요청이 거절되므로 시험용 서버에서 실행해야 합니다.
+///
+/// # Arguments
+///
+/// * `target` - `http://127.0.0.1:80`과 같은 서버 주소입니다. URL 접두사를 사용하면 접두사까지 적습니다.
+/// * `visitors` - 동시에 움직일 가상 방문객 수입니다.
+///
+/// # Returns
+///
+/// 단계별 응답 시간과 실패 수를 담은 결과를 반환합니다. 스템프 목록을 가져오지 못하면 오류 메시지를 반환합니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let report = simulate("http://127.0.0.1:8080", 500).await?;
+/// println!("{}", report);
+/// ```
+pub async fn simulate(target: &str, visitors: usize) -> Result<SimulationReport, String> {
+    let target = target.trim_end_matches('/');
+    let client = Client::builder()
+        .redirect(Policy::none())
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let stamps: Vec<StampInfo> = client
+        .get(format!("{}/api/v1/stamps", target))
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Cannot fetch stamp list from {}: {}", target, e))?
+        .json()
+        .await
+        .map_err(|e| format!("Invalid stamp list from {}: {}", target, e))?;
+    let stamp_ids: Vec<String> = stamps.into_iter().map(|stamp| stamp.stampId).collect();
+    if stamp_ids.is_empty() {
+        return Err(format!("{} has no stamps", target));
+    }
+
+    info!(
+        "Simulating {} visitors over {} stamps against {}",
+        visitors,
+        stamp_ids.len(),
+        target
+    );
+    let started = Instant::now();
+    let results =
+        join_all((0..visitors).map(|index| visit(&client, target, index, &stamp_ids))).await;
+
+    let mut report = SimulationReport {
+        visitors,
+        elapsed: started.elapsed(),
+        ..Default::default()
+    };
+    for (step, latency, ok) in results.into_iter().flatten() {
+        report.record(step, latency, ok);
+    }
+    Ok(report)
+}
+
+/// `--simulate <방문객 수> [--target <주소>]` 실행 인수를 처리합니다.
+///
+/// # Returns
+///
+/// 프로세스 종료 코드를 반환합니다. 실패한 요청이 있으면 1을 반환합니다.
+pub async fn run_simulation(args: &[String]) -> i32 {
+    let value = |name: &str| {
+        args.iter()
+            .position(|arg| arg == name)
+            .and_then(|index| args.get(index + 1))
+    };
+    let Some(visitors) = value("--simulate").and_then(|visitors| visitors.parse().ok()) else {
+        error!("Usage: --simulate <visitors> [--target http://127.0.0.1:80]");
+        return 2;
+    };
+    let target = value("--target").map_or("http://127.0.0.1:80", String::as_str);
+
+    match simulate(target, visitors).await {
+        Ok(report) => {
+            println!("{}", report);
+            i32::from(report.failures() > 0)
+        }
+        Err(e) => {
+            error!("Simulation failed: {}", e);
+            1
+        }
+    }
+}
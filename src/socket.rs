@@ -0,0 +1,193 @@
+use actix_rt::signal::unix::{signal, SignalKind};
+use log::{error, info, warn};
+use std::{
+    env, fs, io,
+    net::TcpListener,
+    os::{
+        fd::FromRawFd,
+        unix::{
+            fs::{FileTypeExt, PermissionsExt},
+            net::UnixDatagram,
+        },
+    },
+    path::Path,
+};
+
+use crate::notifier::Event;
+use crate::state::AppState;
+use crate::storage::reload_state;
+
+/// systemd가 넘겨주는 첫 번째 파일 디스크립터 번호입니다.
+const SD_LISTEN_FDS_START: i32 = 3;
+
+/// 이전 실행에서 남은 유닉스 도메인 소켓 파일을 지우는 함수입니다.
+/// 같은 경로에 소켓이 아닌 파일이 있으면 실수로 지우지 않도록 오류를 반환합니다.
+///
+/// # Arguments
+///
+/// * `path` - 바인딩할 소켓 파일 경로입니다.
+///
+/// # Returns
+///
+/// 파일이 없거나 남은 소켓을 지운 경우 `Ok(())`, 소켓이 아닌 파일이 있으면 `AlreadyExists` 오류를 반환합니다.
+pub fn remove_stale_socket(path: &Path) -> io::Result<()> {
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => {
+            warn!("Removing stale socket {}", path.display());
+            fs::remove_file(path)
+        }
+        Ok(_) => Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} exists and is not a socket", path.display()),
+        )),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+/// 바인딩된 소켓 파일의 권한을 설정하는 함수입니다.
+/// nginx와 같은 리버스 프록시가 같은 그룹으로 접근할 수 있도록 기본값은 `660`입니다.
+///
+/// # Arguments
+///
+/// * `path` - 소켓 파일 경로입니다.
+/// * `mode` - 설정할 권한(8진수)입니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// set_socket_mode(Path::new("/run/stamptour.sock"), 0o660)?;
+/// ```
+pub fn set_socket_mode(path: &Path, mode: u32) -> io::Result<()> {
+    fs::set_permissions(path, fs::Permissions::from_mode(mode))?;
+    info!(
+        "Listening on unix socket {} (mode {:o})",
+        path.display(),
+        mode
+    );
+    Ok(())
+}
+
+/// `LISTEN_PID`와 `LISTEN_FDS` 환경변수로부터 이 프로세스가 넘겨받은 소켓 개수를 계산합니다.
+///
+/// # Arguments
+///
+/// * `listen_pid` - `LISTEN_PID` 환경변수 값입니다.
+/// * `listen_fds` - `LISTEN_FDS` 환경변수 값입니다.
+/// * `pid` - 현재 프로세스 ID입니다.
+///
+/// # Returns
+///
+/// 다른 프로세스를 위한 값이거나 형식이 틀리면 0을 반환합니다.
+pub fn listen_fds(listen_pid: Option<&str>, listen_fds: Option<&str>, pid: u32) -> usize {
+    match (listen_pid, listen_fds) {
+        (Some(listen_pid), Some(listen_fds)) if listen_pid.parse() == Ok(pid) => {
+            listen_fds.parse().unwrap_or(0)
+        }
+        _ => 0,
+    }
+}
+
+/// systemd 소켓 활성화로 넘겨받은 TCP 리스너를 반환하는 함수입니다.
+/// 재시작하는 동안에도 systemd가 소켓을 유지하므로 QR 스캔 요청이 끊기지 않습니다.
+///
+/// # Returns
+///
+/// 소켓 활성화로 실행되지 않았으면 빈 목록을 반환합니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let listeners = systemd_listeners();
+/// let server = if listeners.is_empty() {
+///     server.bind(("0.0.0.0", 80))?
+/// } else {
+///     listeners.into_iter().try_fold(server, |server, listener| server.listen(listener))?
+/// };
+/// ```
+pub fn systemd_listeners() -> Vec<TcpListener> {
+    let count = listen_fds(
+        env::var("LISTEN_PID").ok().as_deref(),
+        env::var("LISTEN_FDS").ok().as_deref(),
+        std::process::id(),
+    );
+    // 자식 프로세스가 같은 소켓을 가져가지 않도록 환경변수 제거
+    env::remove_var("LISTEN_PID");
+    env::remove_var("LISTEN_FDS");
+    env::remove_var("LISTEN_FDNAMES");
+
+    (0..count as i32)
+        .map(|offset| {
+            info!(
+                "Inherited socket fd {} from systemd",
+                SD_LISTEN_FDS_START + offset
+            );
+            // SAFETY: systemd가 LISTEN_FDS 개수만큼 3번부터 열린 소켓을 넘겨주며, 이 함수에서만 소유권을 가져감
+            unsafe { TcpListener::from_raw_fd(SD_LISTEN_FDS_START + offset) }
+        })
+        .collect()
+}
+
+/// `NOTIFY_SOCKET`으로 systemd에 상태 메시지를 보내는 함수입니다.
+///
+/// # Arguments
+///
+/// * `socket_path` - `NOTIFY_SOCKET` 환경변수 값입니다. `@`로 시작하면 추상 소켓입니다.
+/// * `state` - 보낼 상태 메시지입니다. (예: `READY=1`)
+pub fn notify(socket_path: &str, state: &str) -> io::Result<()> {
+    let socket = UnixDatagram::unbound()?;
+    match socket_path.strip_prefix('@') {
+        #[cfg(target_os = "linux")]
+        Some(name) => {
+            use std::os::linux::net::SocketAddrExt;
+            let address = std::os::unix::net::SocketAddr::from_abstract_name(name)?;
+            socket.send_to_addr(state.as_bytes(), &address)?;
+        }
+        _ => {
+            socket.send_to(state.as_bytes(), socket_path)?;
+        }
+    }
+    Ok(())
+}
+
+/// 서버가 요청을 받을 준비가 되었음을 systemd에 알립니다. `Type=notify`가 아니면 아무것도 하지 않습니다.
+pub fn notify_ready() {
+    if let Ok(socket_path) = env::var("NOTIFY_SOCKET") {
+        match notify(&socket_path, "READY=1") {
+            Ok(_) => info!("Notified systemd readiness"),
+            Err(e) => warn!("Failed to notify systemd : {}", e),
+        }
+    }
+}
+
+/// SIGHUP을 받을 때마다 설정 파일, 스템프 목록, 정적 파일 대응표를 다시 불러오는 작업을 시작합니다.
+/// 관리자 명령 `reload`와 같은 동작입니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// reload_on_hangup(state.clone());
+/// // kill -HUP $(pidof GJ_StampTour)
+/// ```
+pub fn reload_on_hangup(state: AppState) {
+    actix_rt::spawn(async move {
+        let Ok(mut hangup) = signal(SignalKind::hangup()) else {
+            error!("Failed to install SIGHUP handler");
+            return;
+        };
+        while hangup.recv().await.is_some() {
+            info!("SIGHUP received, reloading");
+            match reload_state(
+                &state.config,
+                &state.stamp_list,
+                &state.stamp_history,
+                &state.assets,
+                &state.stamp_pages,
+                &state.missions,
+            ) {
+                Ok(stamp_count) => state.notifier.notify(Event::StampReload { stamp_count }),
+                Err(e) => error!("Reload failed: {}", e),
+            }
+        }
+    });
+}
@@ -0,0 +1,355 @@
+use actix_web::{
+    http::StatusCode,
+    routes,
+    web::{Data, Json, Path},
+    HttpRequest, HttpResponse,
+};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+use crate::config::Config;
+use crate::error::AppError;
+use crate::lock::MutexExt;
+use crate::persistence::{Dataset, Persister};
+use crate::progress::{user_progress, Progress};
+use crate::state::{
+    AuditLog, AuditRecord, CompletionList, RedemptionRecord, Reloadable, StampHistory, StampIdList,
+    StampUserInfo, TeamList, UserList, UserStampList,
+};
+
+/// 직원 조회 API로 반환되는 유저 정보입니다.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StaffUser {
+    pub user_code: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub team_code: Option<String>,
+    #[serde(flatten)]
+    pub progress: Progress,
+}
+
+/// 요청이 직원 API를 사용할 수 있는지 확인하는 함수입니다.
+///
+/// 설정에 `staff_token`이 있으면 `Authorization: Bearer <staff_token>` 헤더가 필요하고,
+/// 없으면 관리자 페이지와 같이 로컬에서 요청한 경우만 허용합니다.
+pub fn is_staff(req: &HttpRequest, config: &Config) -> bool {
+    match &config.staff_token {
+        Some(staff_token) => req
+            .headers()
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|token| token == staff_token),
+        None => req.peer_addr().is_some_and(|addr| addr.ip().is_loopback()),
+    }
+}
+
+/// 직원이 짧은 유저 코드로 방문객을 찾는 비동기 함수입니다. 수동 스템프 찍기 화면에서 사용합니다.
+///
+/// # Arguments
+///
+/// * `user_code` - 방문객이 불러준 6자리 코드입니다. 대소문자는 구분하지 않습니다.
+///
+/// # Returns
+///
+/// 유저를 찾은 경우 코드, 팀, 진행 상황을 담은 `StaffUser`가 200 OK 응답으로 반환됩니다.
+/// 직원 인증에 실패하면 401 Unauthorized 응답이, 코드가 없으면 404 Not Found 응답이 반환됩니다.
+#[routes]
+#[get("/api/v1/staff/users/{user_code}")]
+#[get("/api/staff/users/{user_code}")]
+pub async fn handle_staff_user(
+    req: HttpRequest,
+    user_code: Path<String>,
+    user_list: Data<Mutex<UserList>>,
+    stamp_id_list: Data<Reloadable<StampIdList>>,
+    stamp_history: Data<StampHistory>,
+    teams: Data<Mutex<TeamList>>,
+    config: Data<Reloadable<Config>>,
+) -> Result<HttpResponse, AppError> {
+    let config = config.get();
+    let stamp_id_list = stamp_id_list.get();
+    if !is_staff(&req, &config) {
+        warn!("Unauthorized access to the staff API has been identified.");
+        return Err(AppError::Unauthorized);
+    }
+
+    let user = {
+        let user_list = user_list.lock_or_recover();
+        user_list.find_by_code(&user_code).and_then(|user_id| {
+            let user_name = user_list.users.get(user_id)?;
+            Some((user_id.clone(), user_name.clone()))
+        })
+    };
+    let Some((user_id, user_name)) = user else {
+        return Err(AppError::Missing("User not found".to_string()));
+    };
+
+    let progress = user_progress(
+        &config,
+        &stamp_id_list,
+        &stamp_history,
+        &user_id,
+        &user_name,
+    );
+
+    Ok(HttpResponse::Ok().json(StaffUser {
+        user_code: user_code.trim().to_uppercase(),
+        team_code: teams.lock_or_recover().team_of(&user_id).cloned(),
+        progress,
+    }))
+}
+
+/// 잘못 찍은 스템프 기록을 취소하는 함수입니다. 같은 스템프를 여러 번 찍었다면 가장 최근 기록을 취소합니다.
+///
+/// # Arguments
+///
+/// * `stamp_history` - 스템프 기록입니다.
+/// * `audit_log` - 취소 내역을 남길 수정 내역입니다.
+/// * `user_id` - 기록을 취소할 유저 ID입니다.
+/// * `stamp_id` - 취소할 스템프 ID입니다.
+/// * `actor` - 취소한 직원 이름입니다.
+/// * `reason` - 취소 사유입니다.
+///
+/// # Returns
+///
+/// 취소된 기록을 반환합니다. 해당 기록이 없으면 `None`을 반환하며 수정 내역도 남기지 않습니다.
+pub fn revoke_stamp(
+    stamp_history: &StampHistory,
+    audit_log: &mut AuditLog,
+    user_id: &str,
+    stamp_id: &str,
+    actor: &str,
+    reason: &str,
+) -> Option<StampUserInfo> {
+    let revoked = stamp_history.update(stamp_id, |entries| {
+        let index = entries.iter().rposition(|entry| entry.user_id == user_id)?;
+        Some(entries.remove(index))
+    })??;
+
+    audit_log.entries.push(AuditRecord {
+        action: "revoke".to_string(),
+        user_id: user_id.to_string(),
+        stamp_id: Some(stamp_id.to_string()),
+        actor: actor.to_string(),
+        reason: reason.to_string(),
+        timestamp: chrono::prelude::Utc::now().to_string(),
+    });
+    info!(
+        "{} revoked stamp {} of user {} : {}",
+        actor, stamp_id, user_id, reason
+    );
+    Some(revoked)
+}
+
+/// 유저 등록은 유지한 채 모든 스템프 기록, 확인 대기 중인 스템프, 완주 기록과 업적을 지우는 함수입니다.
+/// 테스트 기기를 초기화하거나 분쟁을 해결할 때 사용합니다.
+///
+/// # Arguments
+///
+/// * `stamp_history` - 스템프 기록입니다.
+/// * `user_stamp_list` - 확인 대기 중인 스템프 목록입니다.
+/// * `completions` - 유저별 완주 기록입니다.
+/// * `audit_log` - 초기화 내역을 남길 수정 내역입니다.
+/// * `user_id` - 초기화할 유저 ID입니다.
+/// * `actor` - 초기화한 직원 이름입니다.
+/// * `reason` - 초기화 사유입니다.
+///
+/// # Returns
+///
+/// 지운 스템프 기록 개수를 반환합니다.
+pub fn reset_progress(
+    stamp_history: &StampHistory,
+    user_stamp_list: &mut UserStampList,
+    completions: &mut CompletionList,
+    audit_log: &mut AuditLog,
+    user_id: &str,
+    actor: &str,
+    reason: &str,
+) -> usize {
+    let mut removed = 0;
+    stamp_history.update_all(|_, entries| {
+        let before = entries.len();
+        entries.retain(|entry| entry.user_id != user_id);
+        removed += before - entries.len();
+    });
+    user_stamp_list.user_stamp_list.remove(user_id);
+    completions.completions.remove(user_id);
+    completions.achievements.remove(user_id);
+    // 경품 수령 기록은 남겨서 다시 완주해도 경품을 두 번 받지 않도록 함
+    completions.codes.remove(user_id);
+
+    audit_log.entries.push(AuditRecord {
+        action: "reset".to_string(),
+        user_id: user_id.to_string(),
+        stamp_id: None,
+        actor: actor.to_string(),
+        reason: reason.to_string(),
+        timestamp: chrono::prelude::Utc::now().to_string(),
+    });
+    info!(
+        "{} reset progress of user {} ({} stamps) : {}",
+        actor, user_id, removed, reason
+    );
+    removed
+}
+
+/// 스템프 기록 취소 요청입니다.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RevokeRequest {
+    /// 유저 ID 또는 직원 조회용 코드입니다.
+    pub user: String,
+    pub stamp_id: String,
+    /// 취소한 직원 이름입니다.
+    pub revoked_by: String,
+    pub reason: String,
+}
+
+/// 직원이 잘못 찍은 스템프 기록을 취소하는 비동기 함수입니다.
+///
+/// # Returns
+///
+/// 기록을 취소한 경우 취소된 `StampUserInfo`를 담은 200 OK 응답이 반환됩니다.
+/// 직원 인증에 실패하면 401 Unauthorized 응답이, 유저나 기록이 없으면 404 Not Found 응답이 반환됩니다.
+#[allow(clippy::too_many_arguments)]
+pub async fn handle_revoke(
+    req: HttpRequest,
+    body: Json<RevokeRequest>,
+    user_list: Data<Mutex<UserList>>,
+    user_stamp_list: Data<Mutex<UserStampList>>,
+    stamp_history: Data<StampHistory>,
+    audit_log: Data<Mutex<AuditLog>>,
+    config: Data<Reloadable<Config>>,
+    persister: Data<Persister>,
+) -> Result<HttpResponse, AppError> {
+    let config = config.get();
+    if !is_staff(&req, &config) {
+        warn!("Unauthorized access to the staff API has been identified.");
+        return Err(AppError::Unauthorized);
+    }
+
+    let Some(user_id) = user_list.lock_or_recover().resolve(&body.user) else {
+        return Err(AppError::Missing("User not found".to_string()));
+    };
+
+    // 확인 후 아직 찍히지 않은 같은 스템프 요청도 함께 취소
+    {
+        let mut user_stamp_list = user_stamp_list.lock_or_recover();
+        if user_stamp_list.user_stamp_list.get(&user_id) == Some(&body.stamp_id) {
+            user_stamp_list.user_stamp_list.remove(&user_id);
+        }
+    }
+
+    let revoked = revoke_stamp(
+        &stamp_history,
+        &mut audit_log.lock_or_recover(),
+        &user_id,
+        &body.stamp_id,
+        &body.revoked_by,
+        &body.reason,
+    );
+    let revoked = revoked.ok_or_else(|| AppError::Missing("Stamp record not found".to_string()))?;
+    persister.mark(&[Dataset::StampStatus, Dataset::AuditLog]);
+    Ok(HttpResponse::Ok().json(revoked))
+}
+
+/// 경품 수령 요청입니다.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RedeemRequest {
+    /// 방문객의 완주 페이지에 표시된 완주 코드입니다. QR 코드를 스캔하거나 직접 입력합니다.
+    pub code: String,
+    /// 경품을 지급한 직원 이름입니다.
+    pub redeemed_by: String,
+}
+
+/// 완주 코드를 확인한 결과입니다.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Redemption {
+    /// 이번에 경품 지급을 기록했습니다.
+    Redeemed(RedemptionRecord),
+    /// 이미 경품을 받은 코드입니다. 처음 지급한 기록을 담습니다.
+    AlreadyRedeemed(RedemptionRecord),
+    NotFound,
+}
+
+/// 완주 코드로 경품 지급을 기록하는 함수입니다. 같은 유저는 한 번만 경품을 받을 수 있습니다.
+///
+/// # Arguments
+///
+/// * `completions` - 완주 코드와 경품 수령 기록을 담은 완주 기록입니다.
+/// * `code` - 확인할 완주 코드입니다. 대소문자는 구분하지 않습니다.
+/// * `actor` - 경품을 지급한 직원 이름입니다.
+///
+/// # Returns
+///
+/// 처음 확인한 코드면 `Redemption::Redeemed`를, 이미 경품을 받았으면 처음 지급한 기록을 담은 `Redemption::AlreadyRedeemed`를,
+/// 발급되지 않은 코드면 `Redemption::NotFound`를 반환합니다.
+pub fn redeem(completions: &mut CompletionList, code: &str, actor: &str) -> Redemption {
+    let Some(user_id) = completions.find_by_code(code).cloned() else {
+        return Redemption::NotFound;
+    };
+    if let Some(record) = completions.redemption_of(&user_id) {
+        return Redemption::AlreadyRedeemed(record.clone());
+    }
+
+    let record = RedemptionRecord {
+        code: completions.codes[&user_id].clone(),
+        tier_name: completions
+            .completions
+            .get(&user_id)
+            .and_then(|records| records.last())
+            .map(|record| record.tier_name.clone())
+            .unwrap_or_default(),
+        user_id,
+        redeemed_by: actor.to_string(),
+        timestamp: chrono::prelude::Utc::now().to_string(),
+    };
+    info!(
+        "{} handed out the prize for code {} ({})",
+        actor, record.code, record.tier_name
+    );
+    completions.redemptions.push(record.clone());
+    Redemption::Redeemed(record)
+}
+
+/// 직원이 완주 코드를 확인하고 경품 지급을 기록하는 비동기 함수입니다.
+///
+/// # Returns
+///
+/// 처음 확인한 코드면 `RedemptionRecord`를 담은 200 OK 응답이 반환됩니다.
+/// 이미 경품을 받은 코드면 처음 지급한 기록을 담은 409 Conflict 응답이, 없는 코드면 404 Not Found 응답이 반환됩니다.
+/// 직원 인증에 실패하면 401 Unauthorized 응답이 반환됩니다.
+pub async fn handle_redeem(
+    req: HttpRequest,
+    body: Json<RedeemRequest>,
+    completions: Data<Mutex<CompletionList>>,
+    config: Data<Reloadable<Config>>,
+    persister: Data<Persister>,
+) -> Result<HttpResponse, AppError> {
+    if !is_staff(&req, &config.get()) {
+        warn!("Unauthorized access to the staff API has been identified.");
+        return Err(AppError::Unauthorized);
+    }
+
+    let redemption = redeem(
+        &mut completions.lock_or_recover(),
+        &body.code,
+        &body.redeemed_by,
+    );
+    match redemption {
+        Redemption::Redeemed(record) => {
+            persister.mark(&[Dataset::CompletionStatus]);
+            Ok(HttpResponse::Ok().json(record))
+        }
+        Redemption::AlreadyRedeemed(record) => {
+            warn!("Completion code {} was presented again.", record.code);
+            Err(AppError::Rejected(
+                StatusCode::CONFLICT,
+                serde_json::json!({
+                    "error": "Prize already redeemed",
+                    "redemption": record,
+                }),
+            ))
+        }
+        Redemption::NotFound => Err(AppError::Missing("Completion code not found".to_string())),
+    }
+}
@@ -0,0 +1,107 @@
+use actix_web::{web::Data, HttpRequest};
+use log::warn;
+use std::{
+    path::Path,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use crate::config::Config;
+use crate::state::Reloadable;
+
+/// 시험 운영 중인지 여부입니다. 데이터베이스 위치가 바뀌므로 한 번 켜면 서버를 다시 시작할 때까지 끄지 않습니다.
+static STAGING: AtomicBool = AtomicBool::new(false);
+
+/// 실제 행사 데이터를 저장하는 폴더입니다.
+pub const DATABASE_DIR: &str = "resources/database";
+
+/// 시험 운영 중 데이터를 저장하는 폴더입니다. 실제 행사 데이터와 섞이지 않습니다.
+pub const STAGING_DATABASE_DIR: &str = "resources/staging";
+
+/// 시험 운영 중인 페이지의 `<body>` 바로 뒤에 넣는 띠입니다.
+pub const BANNER: &str = concat!(
+    r#"<div id="test-mode-banner" style="position:sticky;top:0;z-index:9999;padding:6px;"#,
+    r#"background:#d32f2f;color:#fff;font-weight:bold;text-align:center">TEST MODE</div>"#
+);
+
+/// 시험 운영을 켭니다. `--dry-run` 실행 인수나 `config.json`의 `staging`으로 켜며, 끌 수 없습니다.
+pub fn enable() {
+    if !STAGING.swap(true, Ordering::SeqCst) {
+        warn!(
+            "Staging mode enabled, data is saved to {} instead of {}",
+            STAGING_DATABASE_DIR, DATABASE_DIR
+        );
+    }
+}
+
+/// 시험 운영 중인지 확인합니다.
+pub fn is_staging() -> bool {
+    STAGING.load(Ordering::SeqCst)
+}
+
+/// 데이터베이스 파일을 읽고 쓰는 폴더를 반환합니다. 시험 운영 중이면 `STAGING_DATABASE_DIR`입니다.
+pub fn database_dir() -> &'static str {
+    match is_staging() {
+        true => STAGING_DATABASE_DIR,
+        false => DATABASE_DIR,
+    }
+}
+
+/// 읽어온 설정에 시험 운영을 반영하는 함수입니다. `read_config`가 설정을 읽을 때마다 호출합니다.
+///
+/// 설정의 `staging`이 켜져 있으면 시험 운영을 켭니다. 시험 운영 중에는 실제 백업이 덮어쓰이거나 지워지지 않도록
+/// 스냅샷을 `{backup.dir}/staging`에 저장하고, 실제 통계를 더럽히지 않도록 S3 업로드와 스프레드시트 연동을 끕니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let mut config: Config = from_str(&file_content)?;
+/// staging::apply(&mut config);
+/// ```
+pub fn apply(config: &mut Config) {
+    if config.staging {
+        enable();
+    }
+    if !is_staging() {
+        return;
+    }
+    config.staging = true;
+    config.backup.dir = Path::new(&config.backup.dir)
+        .join("staging")
+        .to_string_lossy()
+        .to_string();
+    config.backup.s3 = None;
+    config.sheets = None;
+}
+
+/// HTML의 `<body>` 태그 바로 뒤에 `BANNER`를 넣는 함수입니다. `<body>` 태그가 없으면 맨 앞에 넣습니다.
+///
+/// # Example
+///
+/// ```rust
+/// use gj_stamptour::staging::{insert_banner, BANNER};
+///
+/// let html = insert_banner("<html><body class=\"main\"><h1>스템프</h1></body></html>");
+/// assert_eq!(html, format!("<html><body class=\"main\">{}<h1>스템프</h1></body></html>", BANNER));
+/// ```
+pub fn insert_banner(html: &str) -> String {
+    let body_start = html
+        .find("<body")
+        .and_then(|start| html[start..].find('>').map(|end| start + end + 1))
+        .unwrap_or(0);
+    let mut output = String::with_capacity(html.len() + BANNER.len());
+    output.push_str(&html[..body_start]);
+    output.push_str(BANNER);
+    output.push_str(&html[body_start..]);
+    output
+}
+
+/// 시험 운영 중이면 HTML 페이지에 `TEST MODE` 띠를 넣는 함수입니다. (`announcement::inject` 참고)
+pub fn inject_banner(req: &HttpRequest, html: String) -> String {
+    let staging = req
+        .app_data::<Data<Reloadable<Config>>>()
+        .is_some_and(|config| config.get().staging);
+    match staging {
+        true => insert_banner(&html),
+        false => html,
+    }
+}
@@ -0,0 +1,1216 @@
+#![allow(non_snake_case)]
+
+use actix_web::{
+    dev::Payload,
+    web::{Data, ServiceConfig},
+    FromRequest, HttpRequest,
+};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use std::{
+    collections::BTreeMap,
+    collections::BTreeSet,
+    collections::HashMap,
+    collections::HashSet,
+    future::{ready, Ready},
+    hash::{Hash, Hasher},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, PoisonError, RwLock, RwLockReadGuard,
+    },
+};
+
+use crate::access_log::AccessLog;
+use crate::assets::AssetManifest;
+use crate::config::Config;
+use crate::cooldown::ScanCooldown;
+use crate::error::AppError;
+use crate::features::Feature;
+use crate::lock::{MutexExt, RwLockExt};
+use crate::metrics::Metrics;
+use crate::missions::MissionList;
+use crate::notifier::Notifier;
+use crate::page_cache::StampPageCache;
+use crate::persistence::Persister;
+use crate::report::parse_timestamp;
+use crate::stats::StatsCache;
+use crate::storage::{skip_empty_map, skip_empty_vec, skip_none};
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Default, Eq, PartialEq, Clone, Hash)]
+pub struct Stamp {
+    pub stampId: String,
+    pub stampLocation: String,
+    pub stampName: String,
+    pub stampDesc: String,
+    /// 스템프 전용 템플릿 파일 이름입니다. 없으면 `check.html`을 사용합니다.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stampTemplate: Option<String>,
+    /// 스템프 페이지에 삽입할 추가 내용입니다.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stampContent: Option<StampContent>,
+    /// 발급 가능한 최대 수량입니다. 없으면 수량 제한이 없습니다.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maxIssued: Option<usize>,
+    /// 스템프의 점수입니다. 없으면 1점입니다.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stampPoints: Option<u32>,
+    /// 언어별 스템프 이름입니다. (예: `{"en": "Library", "ja": "図書館"}`) 없는 언어는 `stampName`을 사용합니다.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub stampNames: BTreeMap<String, String>,
+    /// 언어별 스템프 설명입니다. 없는 언어는 `stampDesc`를 사용합니다.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub stampDescs: BTreeMap<String, String>,
+    /// 부스의 위치입니다. 챗봇이 가까운 부스를 안내할 때 사용합니다.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stampCoordinates: Option<Coordinates>,
+    /// 위치 확인(`geofence`)을 켠 경우 스템프를 찍을 수 있는 부스 좌표로부터의 반경(미터)입니다.
+    /// 없으면 위치를 확인하지 않습니다.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stampRadius: Option<u32>,
+    /// `/check?s=`에 `stampId` 대신 사용할 수 있는 추가 코드입니다. 포스터마다 다른 코드를 인쇄해 두면
+    /// 유출된 포스터의 코드만 목록에서 지우고 `reload`하여 막을 수 있습니다.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stampAliases: Vec<String>,
+    /// 숨은 보너스 스템프 여부입니다. 숨은 스템프는 공개 스템프 목록과 진행 상황의 개수, 완주 등급에
+    /// 포함되지 않으며, 찾은 유저의 `/progress`에만 비밀 배지로 나타납니다. 점수는 보너스로 더해집니다.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub stampHidden: bool,
+    /// 부스 운영 시간(예: `"10:00-15:00"`)입니다. 없으면 설정의 `event.booth_hours`를 사용합니다.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stampHours: Option<String>,
+}
+
+/// WGS84 위도와 경도입니다.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+pub struct Coordinates {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+// 스템프 목록 비교에 사용하기 위해 값을 비트 단위로 비교합니다.
+impl Eq for Coordinates {}
+
+impl Hash for Coordinates {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.latitude.to_bits().hash(state);
+        self.longitude.to_bits().hash(state);
+    }
+}
+
+impl Coordinates {
+    /// 두 위치 사이의 거리(미터)를 반환합니다. (하버사인 공식)
+    pub fn distance_meters(&self, other: &Coordinates) -> f64 {
+        const EARTH_RADIUS_METERS: f64 = 6_371_000.0;
+        let (lat1, lat2) = (self.latitude.to_radians(), other.latitude.to_radians());
+        let d_lat = lat2 - lat1;
+        let d_lon = (other.longitude - self.longitude).to_radians();
+        let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_METERS * a.sqrt().asin()
+    }
+}
+
+impl Stamp {
+    /// 지금까지 발급된 수량이 `maxIssued`에 도달했는지 확인합니다.
+    pub fn is_sold_out(&self, issued: usize) -> bool {
+        self.maxIssued
+            .is_some_and(|max_issued| issued >= max_issued)
+    }
+
+    /// 스템프의 점수를 반환합니다.
+    pub fn points(&self) -> u32 {
+        self.stampPoints.unwrap_or(1)
+    }
+
+    /// 이름과 설명을 `locale` 언어로 바꾼 스템프를 반환합니다. 번역이 없는 항목은 기본 값을 유지합니다.
+    pub fn localized(&self, locale: &str) -> Stamp {
+        let mut stamp = self.clone();
+        if let Some(name) = self.stampNames.get(locale) {
+            stamp.stampName = name.clone();
+        }
+        if let Some(desc) = self.stampDescs.get(locale) {
+            stamp.stampDesc = desc.clone();
+        }
+        stamp
+    }
+}
+
+/// 공개 API로 제공하는 스템프 정보입니다. 템플릿, 수량 제한 등 내부 설정은 포함하지 않습니다.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StampInfo {
+    pub stampId: String,
+    pub stampName: String,
+    pub stampLocation: String,
+    pub stampDesc: String,
+}
+
+impl From<Stamp> for StampInfo {
+    fn from(stamp: Stamp) -> Self {
+        StampInfo {
+            stampId: stamp.stampId,
+            stampName: stamp.stampName,
+            stampLocation: stamp.stampLocation,
+            stampDesc: stamp.stampDesc,
+        }
+    }
+}
+
+/// 스템프 페이지에 표시되는 부스별 내용 블록입니다.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Default, Eq, PartialEq, Clone, Hash)]
+pub struct StampContent {
+    #[serde(default)]
+    pub image: String,
+    #[serde(default)]
+    pub sponsorMessage: String,
+    #[serde(default)]
+    pub nextHint: String,
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StampList {
+    pub stampList: HashSet<Stamp>,
+}
+
+#[derive(Debug, Clone)]
+pub struct StampIdList {
+    pub stamp_id_list: BTreeMap<String, Stamp>,
+}
+
+impl StampIdList {
+    /// QR 코드의 스템프 코드에 해당하는 스템프를 찾습니다. `stampId`가 우선하며, 없으면 `stampAliases`에서 찾습니다.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use gj_stamptour::state::{Stamp, StampIdList};
+    ///
+    /// let stamp = Stamp {
+    ///     stampId: "library".to_string(),
+    ///     stampAliases: vec!["library-gate".to_string()],
+    ///     ..Default::default()
+    /// };
+    /// let stamps = StampIdList {
+    ///     stamp_id_list: [(stamp.stampId.clone(), stamp)].into(),
+    /// };
+    /// assert_eq!(stamps.resolve("library-gate").unwrap().stampId, "library");
+    /// assert_eq!(stamps.resolve("library").unwrap().stampId, "library");
+    /// assert!(stamps.resolve("gym").is_none());
+    /// ```
+    pub fn resolve(&self, code: &str) -> Option<&Stamp> {
+        self.stamp_id_list.get(code).or_else(|| {
+            self.stamp_id_list
+                .values()
+                .find(|stamp| stamp.stampAliases.iter().any(|alias| alias == code))
+        })
+    }
+
+    /// 스템프가 숨은 스템프인지 확인합니다. 목록에 없는 스템프는 숨은 스템프가 아닙니다.
+    pub fn is_hidden(&self, stamp_id: &str) -> bool {
+        self.stamp_id_list
+            .get(stamp_id)
+            .is_some_and(|stamp| stamp.stampHidden)
+    }
+
+    /// 숨은 스템프를 제외한 공개 스템프 목록을 반환합니다.
+    pub fn visible(&self) -> impl Iterator<Item = &Stamp> {
+        self.stamp_id_list
+            .values()
+            .filter(|stamp| !stamp.stampHidden)
+    }
+
+    /// 진행 상황과 완주 등급의 기준이 되는 공개 스템프 개수를 반환합니다.
+    pub fn visible_count(&self) -> usize {
+        self.visible().count()
+    }
+
+    /// 주어진 스템프 ID들의 점수 합계를 반환합니다. 목록에 없는 스템프는 무시합니다.
+    pub fn points<'a>(&self, stamp_ids: impl IntoIterator<Item = &'a String>) -> u32 {
+        stamp_ids
+            .into_iter()
+            .filter_map(|stamp_id| self.stamp_id_list.get(stamp_id))
+            .map(Stamp::points)
+            .sum()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UserName {
+    pub user_name: String,
+    /// 참가할 팀 코드입니다.
+    #[serde(default)]
+    pub team_code: Option<String>,
+    /// 새로 만들 팀 이름입니다. `team_code`와 함께 전달되면 `team_code`가 우선합니다.
+    #[serde(default)]
+    pub team_name: Option<String>,
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, Hash)]
+pub struct User {
+    pub user_name: String,
+    pub user_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub team_code: Option<String>,
+    /// 전화로도 불러줄 수 있는 6자리 직원 조회용 코드입니다.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_code: Option<String>,
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct UserList {
+    pub users: BTreeMap<String, String>,
+    /// 직원 조회용 코드별 유저 ID입니다.
+    #[serde(default)]
+    pub codes: BTreeMap<String, String>,
+    /// 관리자가 삭제하여 모든 조회와 내보내기에서 빠진 유저입니다. `purge deleted` 전까지는 되돌릴 수 있습니다.
+    #[serde(default, skip_serializing_if = "skip_empty_map")]
+    pub deleted: BTreeMap<String, DeletedUser>,
+}
+
+/// 삭제된 유저를 되돌리는 데 필요한 기록입니다. 삭제할 때 다른 목록에서 빼 둔 값을 그대로 담습니다.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct DeletedUser {
+    pub user_name: String,
+    #[serde(default, skip_serializing_if = "skip_none")]
+    pub user_code: Option<String>,
+    pub reason: String,
+    pub deleted_at: String,
+    /// 스템프 기록에서 빼 둔 (스템프 ID, 기록) 목록입니다.
+    #[serde(default)]
+    pub stamps: Vec<(String, StampUserInfo)>,
+    #[serde(default)]
+    pub completions: Vec<CompletionRecord>,
+    #[serde(default)]
+    pub achievements: Vec<AchievementRecord>,
+    #[serde(default, skip_serializing_if = "skip_none")]
+    pub completion_code: Option<String>,
+    #[serde(default, skip_serializing_if = "skip_none")]
+    pub team_code: Option<String>,
+}
+
+impl UserList {
+    /// 유저를 목록에 추가하고 직원 조회용 코드를 발급하여 `user.user_code`에 기록합니다.
+    pub fn insert(&mut self, user: &mut User) {
+        let user_code = self.new_code();
+        self.codes.insert(user_code.clone(), user.user_id.clone());
+        self.users
+            .insert(user.user_id.clone(), user.user_name.clone());
+        user.user_code = Some(user_code);
+    }
+
+    /// 아직 코드가 없는 유저(코드 도입 이전에 등록된 유저)에게 코드를 발급합니다.
+    ///
+    /// # Returns
+    ///
+    /// 새로 코드를 발급한 유저 수를 반환합니다.
+    pub fn assign_missing_codes(&mut self) -> usize {
+        let coded: BTreeSet<String> = self.codes.values().cloned().collect();
+        let missing: Vec<String> = self
+            .users
+            .keys()
+            .filter(|user_id| !coded.contains(*user_id))
+            .cloned()
+            .collect();
+        for user_id in missing.iter() {
+            let user_code = self.new_code();
+            self.codes.insert(user_code, user_id.clone());
+        }
+        missing.len()
+    }
+
+    /// 직원 조회용 코드로 유저 ID를 찾습니다. 대소문자는 구분하지 않습니다.
+    pub fn find_by_code(&self, user_code: &str) -> Option<&String> {
+        self.codes.get(&user_code.trim().to_uppercase())
+    }
+
+    /// 유저 ID 또는 직원 조회용 코드로 유저 ID를 찾습니다.
+    pub fn resolve(&self, user: &str) -> Option<String> {
+        if self.users.contains_key(user) {
+            return Some(user.to_string());
+        }
+        self.find_by_code(user).cloned()
+    }
+
+    /// 유저의 직원 조회용 코드를 반환합니다.
+    pub fn code_of(&self, user_id: &str) -> Option<&String> {
+        self.codes
+            .iter()
+            .find(|(_, coded_user_id)| *coded_user_id == user_id)
+            .map(|(user_code, _)| user_code)
+    }
+
+    /// 사용 중이지 않은 코드를 생성합니다.
+    pub(crate) fn new_code(&self) -> String {
+        let mut user_code = generate_code(6);
+        while self.codes.contains_key(&user_code) {
+            user_code = generate_code(6);
+        }
+        user_code
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UserStampList {
+    pub user_stamp_list: HashMap<String, String>,
+}
+
+/// 스템프 ID별 발급 기록입니다. 스템프마다 따로 잠그므로 서로 다른 부스에서 동시에 찍은 스템프는
+/// 서로 기다리지 않습니다. 여러 스템프를 훑는 함수는 스템프를 하나씩 잠그므로, 훑는 도중 다른 부스에서
+/// 기록된 스템프가 결과에 포함되지 않을 수 있습니다.
+///
+/// 모든 접근은 바깥 `RwLock`의 읽기 잠금 안에서 하고 `replace`만 쓰기 잠금을 잡으므로,
+/// 기록 전체를 바꾸는 동안 비었거나 일부만 바뀐 기록이 보이지 않습니다.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(from = "StampHistoryFile")]
+pub struct StampHistory {
+    stamp_history: RwLock<DashMap<String, Mutex<Vec<StampUserInfo>>>>,
+    /// 마지막으로 발급한 기록 순번입니다. 취소나 삭제로 가장 최근 기록이 지워진 뒤 다시 시작해도
+    /// 같은 순번을 다시 발급하지 않도록 함께 저장합니다.
+    last_seq: AtomicU64,
+}
+
+/// 저장된 `stamp_status`의 형식입니다. 불러온 뒤 순번을 정하기 위해 거칩니다.
+#[derive(Deserialize)]
+struct StampHistoryFile {
+    stamp_history: HashMap<String, Vec<StampUserInfo>>,
+    /// `last_seq`가 추가되기 전에 저장된 파일에는 없으므로 기록의 가장 큰 순번을 사용합니다.
+    #[serde(default)]
+    last_seq: u64,
+}
+
+impl From<StampHistoryFile> for StampHistory {
+    fn from(file: StampHistoryFile) -> Self {
+        StampHistory::with_last_seq(file.stamp_history, file.last_seq)
+    }
+}
+
+/// 순번이 없는 기록(`seq`가 0)에 가장 큰 순번 다음부터 시각 순으로 순번을 붙이고, 가장 큰 순번을 반환합니다.
+/// 순번이 추가되기 전에 저장된 기록을 불러올 때 사용합니다.
+///
+/// # Arguments
+///
+/// * `last_seq` - 저장된 마지막 순번입니다. 기록의 순번이 더 크더라도 순번은 이보다 작아지지 않습니다.
+fn assign_missing_seqs(
+    stamp_history: &mut HashMap<String, Vec<StampUserInfo>>,
+    last_seq: u64,
+) -> u64 {
+    let mut last_seq = stamp_history
+        .values()
+        .flatten()
+        .map(|entry| entry.seq)
+        .max()
+        .unwrap_or_default()
+        .max(last_seq);
+    let mut missing: Vec<&mut StampUserInfo> = stamp_history
+        .values_mut()
+        .flatten()
+        .filter(|entry| entry.seq == 0)
+        .collect();
+    missing.sort_by_cached_key(|entry| parse_timestamp(&entry.timestamp));
+    for entry in missing {
+        last_seq += 1;
+        entry.seq = last_seq;
+    }
+    last_seq
+}
+
+impl From<HashMap<String, Vec<StampUserInfo>>> for StampHistory {
+    fn from(stamp_history: HashMap<String, Vec<StampUserInfo>>) -> Self {
+        StampHistory::with_last_seq(stamp_history, 0)
+    }
+}
+
+impl Clone for StampHistory {
+    fn clone(&self) -> Self {
+        StampHistory::with_last_seq(self.to_map(), self.last_seq())
+    }
+}
+
+impl StampHistory {
+    /// 기록과 저장된 마지막 순번으로 `StampHistory`를 만듭니다. 순번이 없는 기록에는 새 순번을 붙입니다.
+    fn with_last_seq(
+        mut stamp_history: HashMap<String, Vec<StampUserInfo>>,
+        last_seq: u64,
+    ) -> Self {
+        let last_seq = assign_missing_seqs(&mut stamp_history, last_seq);
+        StampHistory {
+            stamp_history: RwLock::new(
+                stamp_history
+                    .into_iter()
+                    .map(|(stamp_id, entries)| (stamp_id, Mutex::new(entries)))
+                    .collect(),
+            ),
+            last_seq: AtomicU64::new(last_seq),
+        }
+    }
+
+    /// 기록 전체의 읽기 잠금을 잡습니다. `replace`가 기록을 바꾸는 동안에는 기다립니다.
+    /// `with_shard`, `update`, `update_all`에 넘긴 `f`도 이 잠금 안에서 실행되므로,
+    /// `f`에서 `next_seq` 외의 다른 함수를 부르면 기다리고 있는 `replace`와 교착될 수 있습니다.
+    fn map(&self) -> RwLockReadGuard<'_, DashMap<String, Mutex<Vec<StampUserInfo>>>> {
+        self.stamp_history.read_or_recover()
+    }
+
+    /// 스템프 하나의 기록을 `f`에 넘깁니다. 넘겨받은 값을 잠가 해당 스템프의 기록만 읽거나 고칠 수 있습니다.
+    /// 해당 스템프의 기록이 없으면 `None`을 반환합니다.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let issued = stamp_history.with_shard("booth-1", |shard| metrics.lock("stamp_history", shard).len());
+    /// ```
+    pub fn with_shard<R>(
+        &self,
+        stamp_id: &str,
+        f: impl FnOnce(&Mutex<Vec<StampUserInfo>>) -> R,
+    ) -> Option<R> {
+        let map = self.map();
+        let shard = map.get(stamp_id)?;
+        Some(f(shard.value()))
+    }
+
+    /// 스템프 하나의 기록을 `f`로 고칩니다. 해당 스템프의 기록이 없으면 `None`을 반환합니다.
+    pub fn update<R>(
+        &self,
+        stamp_id: &str,
+        f: impl FnOnce(&mut Vec<StampUserInfo>) -> R,
+    ) -> Option<R> {
+        self.with_shard(stamp_id, |shard| f(&mut shard.lock_or_recover()))
+    }
+
+    /// 모든 스템프의 기록을 하나씩 잠그며 `f`로 고칩니다.
+    pub fn update_all(&self, mut f: impl FnMut(&str, &mut Vec<StampUserInfo>)) {
+        for shard in self.map().iter() {
+            f(shard.key(), &mut shard.value().lock_or_recover());
+        }
+    }
+
+    /// 다음 기록 순번을 발급합니다. 순번은 서버 안에서 항상 증가하므로 기기 시계가 달라도 기록의 순서와 중복을 판단할 수 있습니다.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use gj_stamptour::state::StampHistory;
+    ///
+    /// let stamp_history = StampHistory::default();
+    /// assert_eq!(stamp_history.next_seq(), 1);
+    /// assert_eq!(stamp_history.next_seq(), 2);
+    /// assert_eq!(stamp_history.last_seq(), 2);
+    /// ```
+    pub fn next_seq(&self) -> u64 {
+        self.last_seq.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// 마지막으로 발급한 기록 순번을 반환합니다. 기록이 없으면 0을 반환합니다.
+    pub fn last_seq(&self) -> u64 {
+        self.last_seq.load(Ordering::SeqCst)
+    }
+
+    /// 순번이 없는 기록에 새 순번을 붙입니다. 되돌리기 등으로 다시 넣는 기록은 원래 순번을 유지합니다.
+    fn with_seq(&self, mut entry: StampUserInfo) -> StampUserInfo {
+        if entry.seq == 0 {
+            entry.seq = self.next_seq();
+        }
+        entry
+    }
+
+    /// 스템프 기록을 추가합니다. 해당 스템프의 기록이 없으면 새로 만듭니다.
+    /// `entry.seq`가 0이면 새 순번을 붙입니다.
+    pub fn push(&self, stamp_id: &str, entry: StampUserInfo) {
+        let entry = self.with_seq(entry);
+        if self
+            .update(stamp_id, |entries| entries.push(entry.clone()))
+            .is_none()
+        {
+            self.map()
+                .entry(stamp_id.to_string())
+                .or_default()
+                .lock_or_recover()
+                .push(entry);
+        }
+    }
+
+    /// 스템프 기록을 추가합니다. 지금까지의 발급 횟수로 `can_issue`가 `false`를 반환하면 추가하지 않습니다.
+    /// 확인과 추가를 같은 잠금 안에서 하므로 한정 수량을 넘겨 발급하지 않습니다. `entry.seq`가 0이면 새 순번을 붙입니다.
+    ///
+    /// # Returns
+    ///
+    /// 기록을 추가했으면 `true`를 반환합니다.
+    pub fn push_if(
+        &self,
+        stamp_id: &str,
+        entry: StampUserInfo,
+        can_issue: impl FnOnce(usize) -> bool,
+    ) -> bool {
+        self.insert_stamp(stamp_id);
+        self.update(stamp_id, |entries| {
+            let issue = can_issue(entries.len());
+            if issue {
+                entries.push(self.with_seq(entry));
+            }
+            issue
+        })
+        .unwrap_or(false)
+    }
+
+    /// 기록이 없는 스템프의 빈 기록을 만듭니다.
+    pub fn insert_stamp(&self, stamp_id: &str) {
+        if !self.contains(stamp_id) {
+            self.map().entry(stamp_id.to_string()).or_default();
+        }
+    }
+
+    /// 스템프의 기록을 모두 지웁니다.
+    pub fn remove_stamp(&self, stamp_id: &str) -> Option<Vec<StampUserInfo>> {
+        self.map()
+            .remove(stamp_id)
+            .map(|(_, entries)| entries.into_inner().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// 스템프의 기록이 있는지 확인합니다.
+    pub fn contains(&self, stamp_id: &str) -> bool {
+        self.map().contains_key(stamp_id)
+    }
+
+    /// 스템프 하나의 기록을 복사하여 반환합니다. 기록이 없으면 빈 목록을 반환합니다.
+    pub fn entries(&self, stamp_id: &str) -> Vec<StampUserInfo> {
+        self.update(stamp_id, |entries| entries.clone())
+            .unwrap_or_default()
+    }
+
+    /// 스템프가 지금까지 발급된 횟수를 반환합니다.
+    pub fn issued(&self, stamp_id: &str) -> usize {
+        self.update(stamp_id, |entries| entries.len())
+            .unwrap_or_default()
+    }
+
+    /// 기록이 있는 스템프 ID를 정렬하여 반환합니다.
+    pub fn stamp_ids(&self) -> Vec<String> {
+        let mut stamp_ids: Vec<String> =
+            self.map().iter().map(|shard| shard.key().clone()).collect();
+        stamp_ids.sort();
+        stamp_ids
+    }
+
+    /// 모든 기록을 복사하여 반환합니다. 저장, 내보내기와 같이 전체 기록이 필요한 경우에 사용합니다.
+    pub fn to_map(&self) -> HashMap<String, Vec<StampUserInfo>> {
+        self.map()
+            .iter()
+            .map(|shard| (shard.key().clone(), shard.value().lock_or_recover().clone()))
+            .collect()
+    }
+
+    /// 모든 기록을 `other`의 내용으로 바꿉니다. 순번은 되돌아가지 않도록 두 기록 중 큰 쪽부터 이어서 발급합니다.
+    /// 쓰기 잠금 안에서 한 번에 바꾸므로, 다른 요청은 바꾸기 전이나 바꾼 뒤의 기록만 봅니다.
+    pub fn replace(&self, other: StampHistory) {
+        let stamp_history = other
+            .stamp_history
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner);
+        let mut map = self.stamp_history.write_or_recover();
+        self.last_seq
+            .fetch_max(other.last_seq.into_inner(), Ordering::SeqCst);
+        *map = stamp_history;
+    }
+
+    /// 유저가 한 번 이상 찍은 스템프 ID 목록을 반환합니다.
+    pub fn collected_by(&self, user_id: &str) -> BTreeSet<String> {
+        self.map()
+            .iter()
+            .filter(|shard| {
+                shard
+                    .value()
+                    .lock_or_recover()
+                    .iter()
+                    .any(|entry| entry.user_id == user_id)
+            })
+            .map(|shard| shard.key().clone())
+            .collect()
+    }
+
+    /// 모든 유저가 한 번 이상 찍은 스템프 ID 목록을 유저 ID별로 반환합니다.
+    pub fn collections(&self) -> HashMap<String, BTreeSet<String>> {
+        let mut collections: HashMap<String, BTreeSet<String>> = HashMap::new();
+        self.update_all(|stamp_id, entries| {
+            for entry in entries.iter() {
+                collections
+                    .entry(entry.user_id.clone())
+                    .or_default()
+                    .insert(stamp_id.to_string());
+            }
+        });
+        collections
+    }
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, Hash)]
+pub struct StampUserInfo {
+    pub user_name: String,
+    pub user_id: String,
+    pub timestamp: String,
+    /// 서버가 발급한 기록 순번입니다. 재시작해도 이어지며 항상 증가합니다. 0이면 아직 발급되지 않은 기록입니다.
+    /// 기록 목록은 시각 순서이므로 나중에 합친 오프라인 기록은 앞쪽에 있어도 순번이 더 큽니다.
+    #[serde(default)]
+    pub seq: u64,
+}
+
+/// 유저별로 달성한 완주 등급 기록입니다.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CompletionList {
+    pub completions: BTreeMap<String, Vec<CompletionRecord>>,
+    /// 유저별로 달성한 업적 기록입니다.
+    #[serde(default, skip_serializing_if = "skip_empty_map")]
+    pub achievements: BTreeMap<String, Vec<AchievementRecord>>,
+    /// 유저별 경품 수령용 완주 코드입니다. 처음 완주 등급을 달성할 때 발급됩니다.
+    #[serde(default, skip_serializing_if = "skip_empty_map")]
+    pub codes: BTreeMap<String, String>,
+    /// 직원이 완주 코드를 확인하고 경품을 지급한 기록입니다.
+    #[serde(default, skip_serializing_if = "skip_empty_vec")]
+    pub redemptions: Vec<RedemptionRecord>,
+}
+
+impl CompletionList {
+    /// 완주 코드로 유저 ID를 찾습니다. 대소문자와 앞뒤 공백은 무시합니다.
+    pub fn find_by_code(&self, code: &str) -> Option<&String> {
+        let code = code.trim().to_uppercase();
+        self.codes
+            .iter()
+            .find(|(_, user_code)| **user_code == code)
+            .map(|(user_id, _)| user_id)
+    }
+
+    /// 유저의 경품 수령 기록을 찾습니다.
+    pub fn redemption_of(&self, user_id: &str) -> Option<&RedemptionRecord> {
+        self.redemptions
+            .iter()
+            .find(|record| record.user_id == user_id)
+    }
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CompletionRecord {
+    pub tier_name: String,
+    pub timestamp: String,
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AchievementRecord {
+    pub achievement_id: String,
+    pub timestamp: String,
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RedemptionRecord {
+    pub code: String,
+    pub user_id: String,
+    /// 경품을 받을 때 달성한 가장 높은 완주 등급입니다.
+    pub tier_name: String,
+    /// 경품을 지급한 직원 이름입니다.
+    pub redeemed_by: String,
+    pub timestamp: String,
+}
+
+/// 팀 코드별 팀 정보입니다.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TeamList {
+    pub teams: BTreeMap<String, Team>,
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Team {
+    pub team_name: String,
+    pub members: Vec<String>,
+    /// 팀이 달성한 팀 완주 등급 기록입니다.
+    #[serde(default)]
+    pub completions: Vec<CompletionRecord>,
+}
+
+impl TeamList {
+    /// 유저가 속한 팀 코드를 반환합니다.
+    pub fn team_of(&self, user_id: &str) -> Option<&String> {
+        self.teams
+            .iter()
+            .find(|(_, team)| team.members.iter().any(|member| member == user_id))
+            .map(|(team_code, _)| team_code)
+    }
+}
+
+/// 관리자가 설정하는 사이트 전체 공지입니다.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Announcement {
+    pub message: String,
+    /// 공지가 만료되는 시각(RFC 3339)입니다.
+    pub expires_at: String,
+}
+
+/// 직원이나 관리자가 기록을 수정한 내역입니다.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AuditLog {
+    pub entries: Vec<AuditRecord>,
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AuditRecord {
+    /// 수행한 작업입니다. (예: `revoke`)
+    pub action: String,
+    pub user_id: String,
+    #[serde(default, skip_serializing_if = "skip_none")]
+    pub stamp_id: Option<String>,
+    /// 작업을 수행한 직원 이름입니다.
+    pub actor: String,
+    pub reason: String,
+    pub timestamp: String,
+}
+
+/// 자체 체크인 시스템을 쓰는 협력사 부스의 API 키 목록입니다. 키 원문은 저장하지 않습니다.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct PartnerKeyList {
+    /// 협력사 이름별 API 키입니다.
+    pub partners: BTreeMap<String, PartnerKey>,
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PartnerKey {
+    /// API 키의 SHA-256 해시(16진수)입니다.
+    pub key_hash: String,
+    /// 이 키로 찍을 수 있는 스템프 ID 목록입니다.
+    pub stamp_ids: BTreeSet<String>,
+    pub created_at: String,
+    /// 키를 폐기한 시각입니다. 폐기된 키로는 스템프를 찍을 수 없습니다.
+    #[serde(default, skip_serializing_if = "skip_none")]
+    pub revoked_at: Option<String>,
+}
+
+/// 인쇄용 QR 코드에 넣는 짧은 주소(`/s/{코드}`) 목록입니다.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShortLinkList {
+    /// 짧은 코드별 주소입니다.
+    pub links: BTreeMap<String, ShortLink>,
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ShortLink {
+    /// 이동할 스템프 ID 또는 별칭 코드입니다.
+    pub stamp_code: String,
+    pub created_at: String,
+    /// 주소를 폐기한 시각입니다. 폐기된 주소는 404 응답을 받습니다.
+    #[serde(default, skip_serializing_if = "skip_none")]
+    pub revoked_at: Option<String>,
+}
+
+/// NFC 태그 UID별로 마지막으로 받아들인 읽기 카운터입니다. 같은 태그 주소를 다시 보내는 것을 막습니다.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct NfcCounterList {
+    pub counters: BTreeMap<String, u32>,
+}
+
+/// 출구 설문을 대신하는 방문 후기 목록입니다. 유저마다 마지막으로 보낸 후기 하나만 남깁니다.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeedbackList {
+    /// 유저 ID별 후기입니다.
+    pub entries: BTreeMap<String, Feedback>,
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Feedback {
+    /// 1부터 5까지의 만족도입니다.
+    pub rating: u8,
+    pub comment: String,
+    pub timestamp: String,
+}
+
+/// 행사장 화면에 띄우는 방명록입니다. 글은 남긴 순서대로 저장합니다.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Guestbook {
+    /// 다음 글에 붙일 번호입니다.
+    pub next_id: u64,
+    pub entries: Vec<GuestbookEntry>,
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct GuestbookEntry {
+    /// 관리자 명령에서 글을 가리키는 번호입니다.
+    pub entry_id: u64,
+    pub user_id: String,
+    /// 글을 남길 때의 유저 이름입니다.
+    pub user_name: String,
+    pub message: String,
+    pub timestamp: String,
+    pub status: GuestbookStatus,
+}
+
+/// 되돌릴 수 있는 최근 관리자 작업 목록입니다. `undo` 관리자 명령은 마지막 작업부터 되돌립니다.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AdminHistory {
+    pub entries: Vec<AdminOperation>,
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AdminOperation {
+    /// 실행한 관리자 명령 그대로입니다.
+    pub command: String,
+    pub timestamp: String,
+    #[serde(with = "undo_action_format")]
+    pub undo: UndoAction,
+}
+
+/// bincode는 `kind`로 구분하는 `UndoAction`을 읽지 못하므로, 바이너리 형식에서는 JSON 문자열로 저장합니다.
+mod undo_action_format {
+    use serde::{
+        de::Error as _, ser::Error as _, Deserialize, Deserializer, Serialize, Serializer,
+    };
+
+    use super::UndoAction;
+
+    pub fn serialize<S: Serializer>(undo: &UndoAction, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            undo.serialize(serializer)
+        } else {
+            serializer.serialize_str(&serde_json::to_string(undo).map_err(S::Error::custom)?)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<UndoAction, D::Error> {
+        if deserializer.is_human_readable() {
+            UndoAction::deserialize(deserializer)
+        } else {
+            let json = String::deserialize(deserializer)?;
+            serde_json::from_str(&json).map_err(D::Error::custom)
+        }
+    }
+}
+
+/// 관리자 작업을 되돌리는 데 필요한 값입니다.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum UndoAction {
+    /// 삭제된 유저를 `undelete user`와 같이 되돌립니다.
+    DeleteUser { user_id: String },
+    /// 취소한 스템프 기록을 다시 넣습니다.
+    RevokeStamp {
+        stamp_id: String,
+        entry: StampUserInfo,
+    },
+    /// 초기화하기 전의 스템프 기록과 완주 기록을 다시 넣습니다.
+    ResetProgress {
+        user_id: String,
+        stamps: Vec<(String, StampUserInfo)>,
+        completions: Vec<CompletionRecord>,
+        achievements: Vec<AchievementRecord>,
+        completion_code: Option<String>,
+    },
+}
+
+/// 방명록 글의 검토 상태입니다. 승인된 글만 `/guestbook`에 나타납니다.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GuestbookStatus {
+    Pending,
+    Approved,
+    Hidden,
+}
+
+/// 유저별 Web Push 구독 목록입니다. 한 유저가 여러 기기에서 구독할 수 있습니다.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct PushSubscriptionList {
+    pub subscriptions: BTreeMap<String, Vec<PushSubscription>>,
+}
+
+/// 브라우저의 `PushManager.subscribe()`가 반환한 구독 정보입니다. `JSON.stringify`한 형식 그대로 받습니다.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PushSubscription {
+    /// 푸시 서비스가 발급한 전송 주소입니다.
+    pub endpoint: String,
+    pub keys: PushKeys,
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PushKeys {
+    /// 브라우저의 P-256 공개 키(URL-safe base64)입니다.
+    pub p256dh: String,
+    /// 브라우저의 인증 비밀 값(URL-safe base64)입니다.
+    pub auth: String,
+}
+
+/// 관리자가 설정한 투어 운영 상태입니다.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct TourStatus {
+    /// `Some(true)`면 종료, `Some(false)`면 운영 중으로 고정합니다. 없으면 설정의 `closes_at`을 따릅니다.
+    #[serde(default)]
+    pub closed: Option<bool>,
+    /// `feature` 관리자 명령으로 켜거나 끈 기능입니다. 없는 기능은 설정의 `features`를 따릅니다.
+    #[serde(default)]
+    pub features: BTreeMap<Feature, bool>,
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Command {
+    pub command: String,
+    pub output: String,
+}
+
+/// 서버를 멈추지 않고 다시 불러올 수 있는 설정 값입니다.
+///
+/// 요청마다 `get`으로 그 시점의 값을 `Arc`로 꺼내 쓰므로 락을 잡은 채로 `await`하지 않으며,
+/// 다시 불러오는 동안 처리 중인 요청은 이전 값을 끝까지 사용합니다.
+///
+/// # Example
+///
+/// ```rust
+/// use gj_stamptour::state::Reloadable;
+///
+/// let greeting = Reloadable::new("안녕".to_string());
+/// let before = greeting.get();
+/// greeting.replace("반가워".to_string());
+/// assert_eq!(*before, "안녕");
+/// assert_eq!(*greeting.get(), "반가워");
+/// ```
+#[derive(Debug, Default)]
+pub struct Reloadable<T>(RwLock<Arc<T>>);
+
+impl<T> Reloadable<T> {
+    pub fn new(value: T) -> Self {
+        Reloadable(RwLock::new(Arc::new(value)))
+    }
+
+    /// 현재 값을 반환합니다.
+    pub fn get(&self) -> Arc<T> {
+        Arc::clone(&self.0.read_or_recover())
+    }
+
+    /// 값을 새 값으로 교체합니다.
+    pub fn replace(&self, value: T) {
+        *self.0.write_or_recover() = Arc::new(value);
+    }
+}
+
+/// 서버의 모든 핸들러가 공유하는 상태를 하나로 묶은 구조체입니다.
+///
+/// `HttpServer`의 워커마다 같은 `Data`를 공유해야 하므로 `Clone`은 내부 `Arc`만 복제합니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let state = load_state(load_config());
+/// let app = App::new()
+///     .configure(|cfg| state.register(cfg))
+///     .configure(routes);
+/// ```
+#[derive(Clone)]
+pub struct AppState {
+    pub config: Data<Reloadable<Config>>,
+    pub stamp_list: Data<Reloadable<StampIdList>>,
+    pub user_list: Data<Mutex<UserList>>,
+    pub user_stamp_list: Data<Mutex<UserStampList>>,
+    pub stamp_history: Data<StampHistory>,
+    pub completions: Data<Mutex<CompletionList>>,
+    pub teams: Data<Mutex<TeamList>>,
+    pub announcement: Data<Mutex<Option<Announcement>>>,
+    pub tour_status: Data<Mutex<TourStatus>>,
+    pub notifier: Data<Notifier>,
+    pub metrics: Data<Metrics>,
+    pub audit_log: Data<Mutex<AuditLog>>,
+    pub assets: Data<Reloadable<AssetManifest>>,
+    pub stats: Data<StatsCache>,
+    pub partner_keys: Data<Mutex<PartnerKeyList>>,
+    pub push_subscriptions: Data<Mutex<PushSubscriptionList>>,
+    pub access_log: Data<AccessLog>,
+    pub persister: Data<Persister>,
+    pub stamp_pages: Data<StampPageCache>,
+    pub scan_cooldown: Data<ScanCooldown>,
+    pub short_links: Data<Mutex<ShortLinkList>>,
+    pub nfc_counters: Data<Mutex<NfcCounterList>>,
+    pub missions: Data<Reloadable<MissionList>>,
+    pub feedback: Data<Mutex<FeedbackList>>,
+    pub guestbook: Data<Mutex<Guestbook>>,
+    pub admin_history: Data<Mutex<AdminHistory>>,
+}
+
+impl AppState {
+    /// 설정과 스템프 목록으로 비어있는 공유 상태를 생성합니다.
+    /// 저장된 데이터베이스를 불러오려면 `storage::load_state`를 사용합니다.
+    pub fn new(config: Config, stamp_list: StampIdList) -> Self {
+        AppState {
+            notifier: Data::new(Notifier::new(config.notifier.clone())),
+            access_log: Data::new(AccessLog::new(&config)),
+            persister: Data::new(Persister::new(&config.persistence)),
+            stamp_pages: Data::new(StampPageCache::default()),
+            scan_cooldown: Data::new(ScanCooldown::default()),
+            short_links: Data::new(Mutex::new(ShortLinkList::default())),
+            nfc_counters: Data::new(Mutex::new(NfcCounterList::default())),
+            missions: Data::new(Reloadable::new(MissionList::default())),
+            feedback: Data::new(Mutex::new(FeedbackList::default())),
+            guestbook: Data::new(Mutex::new(Guestbook::default())),
+            admin_history: Data::new(Mutex::new(AdminHistory::default())),
+            config: Data::new(Reloadable::new(config)),
+            user_list: Data::new(Mutex::new(UserList::default())),
+            user_stamp_list: Data::new(Mutex::new(UserStampList {
+                user_stamp_list: HashMap::new(),
+            })),
+            stamp_history: Data::new(StampHistory::from(stamp_history(stamp_list.clone()))),
+            completions: Data::new(Mutex::new(CompletionList::default())),
+            teams: Data::new(Mutex::new(TeamList::default())),
+            announcement: Data::new(Mutex::new(None)),
+            tour_status: Data::new(Mutex::new(TourStatus::default())),
+            metrics: Data::new(Metrics::default()),
+            audit_log: Data::new(Mutex::new(AuditLog::default())),
+            assets: Data::new(Reloadable::new(AssetManifest::default())),
+            stats: Data::new(StatsCache::default()),
+            partner_keys: Data::new(Mutex::new(PartnerKeyList::default())),
+            push_subscriptions: Data::new(Mutex::new(PushSubscriptionList::default())),
+            stamp_list: Data::new(Reloadable::new(stamp_list)),
+        }
+    }
+
+    /// 공유 상태를 앱 데이터로 등록합니다.
+    pub fn register(&self, cfg: &mut ServiceConfig) {
+        cfg.app_data(Data::clone(&self.config)) // 전역변수 선언
+            .app_data(Data::clone(&self.stamp_list)) // 전역변수 선언
+            .app_data(Data::clone(&self.user_list)) // 전역변수 선언
+            .app_data(Data::clone(&self.user_stamp_list)) // 전역변수 선언
+            .app_data(Data::clone(&self.stamp_history)) // 전역변수 선언
+            .app_data(Data::clone(&self.completions)) // 전역변수 선언
+            .app_data(Data::clone(&self.teams)) // 전역변수 선언
+            .app_data(Data::clone(&self.announcement)) // 전역변수 선언
+            .app_data(Data::clone(&self.tour_status)) // 전역변수 선언
+            .app_data(Data::clone(&self.notifier)) // 전역변수 선언
+            .app_data(Data::clone(&self.metrics)) // 전역변수 선언
+            .app_data(Data::clone(&self.audit_log)) // 전역변수 선언
+            .app_data(Data::clone(&self.assets)) // 전역변수 선언
+            .app_data(Data::clone(&self.stats)) // 전역변수 선언
+            .app_data(Data::clone(&self.partner_keys)) // 전역변수 선언
+            .app_data(Data::clone(&self.push_subscriptions)) // 전역변수 선언
+            .app_data(Data::clone(&self.access_log)) // 전역변수 선언
+            .app_data(Data::clone(&self.persister)) // 전역변수 선언
+            .app_data(Data::clone(&self.stamp_pages)) // 전역변수 선언
+            .app_data(Data::clone(&self.scan_cooldown)) // 전역변수 선언
+            .app_data(Data::clone(&self.short_links)) // 전역변수 선언
+            .app_data(Data::clone(&self.nfc_counters)) // 전역변수 선언
+            .app_data(Data::clone(&self.missions)) // 전역변수 선언
+            .app_data(Data::clone(&self.feedback)) // 전역변수 선언
+            .app_data(Data::clone(&self.guestbook)) // 전역변수 선언
+            .app_data(Data::clone(&self.admin_history)); // 전역변수 선언
+    }
+
+    /// `register`로 등록한 앱 데이터에서 공유 상태를 다시 모읍니다. 등록되지 않은 항목이 있으면 `None`을 반환합니다.
+    pub fn from_app_data(req: &HttpRequest) -> Option<Self> {
+        fn data<T: ?Sized + 'static>(req: &HttpRequest) -> Option<Data<T>> {
+            req.app_data::<Data<T>>().cloned()
+        }
+
+        Some(AppState {
+            config: data(req)?,
+            stamp_list: data(req)?,
+            user_list: data(req)?,
+            user_stamp_list: data(req)?,
+            stamp_history: data(req)?,
+            completions: data(req)?,
+            teams: data(req)?,
+            announcement: data(req)?,
+            tour_status: data(req)?,
+            notifier: data(req)?,
+            metrics: data(req)?,
+            audit_log: data(req)?,
+            assets: data(req)?,
+            stats: data(req)?,
+            partner_keys: data(req)?,
+            push_subscriptions: data(req)?,
+            access_log: data(req)?,
+            persister: data(req)?,
+            stamp_pages: data(req)?,
+            scan_cooldown: data(req)?,
+            short_links: data(req)?,
+            nfc_counters: data(req)?,
+            missions: data(req)?,
+            feedback: data(req)?,
+            guestbook: data(req)?,
+            admin_history: data(req)?,
+        })
+    }
+}
+
+/// 여러 공유 상태를 사용하는 핸들러는 인수 대신 `AppState`를 받습니다.
+/// 앱 데이터가 등록되지 않았으면 500 응답(`AppError::Internal`)을 보냅니다.
+impl FromRequest for AppState {
+    type Error = AppError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(
+            AppState::from_app_data(req)
+                .ok_or_else(|| AppError::Internal("Shared state is not registered".to_string())),
+        )
+    }
+}
+
+/// 스템프 ID 리스트로부터 비어있는 스템프 기록을 생성하는 함수입니다.
+///
+/// # Arguments
+///
+/// * `stamp_id_list` - 기록을 생성할 스템프 ID 목록입니다.
+///
+/// # Returns
+///
+/// 모든 스템프 ID에 대해 빈 기록 벡터를 담은 `HashMap`을 반환합니다.
+pub fn stamp_history(stamp_id_list: StampIdList) -> HashMap<String, Vec<StampUserInfo>> {
+    let mut stamp_history = HashMap::new();
+
+    for stamp_id in stamp_id_list.stamp_id_list.keys() {
+        stamp_history.insert(stamp_id.clone(), Vec::new());
+    }
+
+    stamp_history
+}
+
+/// 사람이 읽고 받아쓰기 쉬운 무작위 코드를 생성하는 함수입니다.
+/// 헷갈리기 쉬운 `0`, `O`, `1`, `I`는 사용하지 않습니다.
+///
+/// # Arguments
+///
+/// * `length` - 코드 길이입니다. 최대 16자입니다.
+///
+/// # Returns
+///
+/// 대문자와 숫자로 이루어진 코드를 반환합니다.
+pub fn generate_code(length: usize) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+
+    // 32글자 알파벳이므로 바이트를 32로 나눈 나머지는 치우치지 않음
+    uuid::Uuid::new_v4()
+        .as_bytes()
+        .iter()
+        .take(length)
+        .map(|byte| ALPHABET[*byte as usize % ALPHABET.len()] as char)
+        .collect()
+}
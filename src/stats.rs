@@ -0,0 +1,247 @@
+use actix_web::{
+    routes,
+    web::{Data, Query},
+    HttpRequest, HttpResponse,
+};
+use chrono::{DateTime, Local, NaiveDate, TimeDelta};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::config::Config;
+use crate::error::AppError;
+use crate::lock::MutexExt;
+use crate::report::parse_timestamp;
+use crate::staff::is_staff;
+use crate::state::{CompletionList, Reloadable, StampHistory, UserList};
+
+/// 공개 통계를 다시 계산하기 전까지 재사용하는 시간입니다. 응답의 `max-age`에도 사용합니다.
+pub const PUBLIC_STATS_TTL: Duration = Duration::from_secs(10);
+
+/// 입구 전광판과 보도자료에 사용하는 공개 통계입니다. 개인을 식별할 수 있는 정보는 포함하지 않습니다.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PublicStats {
+    /// 등록된 전체 참가자 수입니다.
+    pub participants: usize,
+    /// 오늘(서버 시간대 기준) 발급된 스템프 수입니다.
+    pub stamps_today: usize,
+    /// 완주 등급을 하나 이상 달성한 참가자 수입니다.
+    pub completions: usize,
+}
+
+/// 공개 통계를 계산하는 함수입니다.
+///
+/// # Arguments
+///
+/// * `user_list` - 등록된 유저 목록입니다.
+/// * `stamp_history` - 스템프 기록입니다.
+/// * `completions` - 유저별 완주 기록입니다.
+/// * `today` - 오늘 날짜(서버 시간대 기준)입니다.
+pub fn public_stats(
+    user_list: &UserList,
+    stamp_history: &StampHistory,
+    completions: &CompletionList,
+    today: NaiveDate,
+) -> PublicStats {
+    PublicStats {
+        participants: user_list.users.len(),
+        stamps_today: stamp_history
+            .to_map()
+            .values()
+            .flatten()
+            .filter(|entry| {
+                parse_timestamp(&entry.timestamp)
+                    .is_some_and(|timestamp| timestamp.date_naive() == today)
+            })
+            .count(),
+        completions: completions
+            .completions
+            .values()
+            .filter(|records| !records.is_empty())
+            .count(),
+    }
+}
+
+/// 자주 요청되는 통계를 잠시 저장해두는 캐시입니다. 전광판이 여러 대여도 공유 상태는
+/// `PUBLIC_STATS_TTL`마다 한 번만 잠급니다.
+#[derive(Debug, Default)]
+pub struct StatsCache {
+    public: Mutex<Option<(Instant, PublicStats)>>,
+}
+
+impl StatsCache {
+    /// 저장된 통계가 `PUBLIC_STATS_TTL`보다 오래되었으면 `compute`로 다시 계산하여 반환합니다.
+    pub fn public_stats(&self, compute: impl FnOnce() -> PublicStats) -> PublicStats {
+        let mut cached = self.public.lock_or_recover();
+        match cached.as_ref() {
+            Some((computed_at, stats)) if computed_at.elapsed() < PUBLIC_STATS_TTL => stats.clone(),
+            _ => {
+                let stats = compute();
+                *cached = Some((Instant::now(), stats.clone()));
+                stats
+            }
+        }
+    }
+}
+
+/// 인증 없이 공개 통계를 JSON으로 반환하는 비동기 함수입니다.
+///
+/// # Returns
+///
+/// 참가자 수, 오늘 발급된 스템프 수, 완주 인원을 담은 `PublicStats`가 200 OK 응답으로 반환됩니다.
+/// 응답은 `PUBLIC_STATS_TTL` 동안 캐시할 수 있습니다.
+#[routes]
+#[get("/api/v1/stats/public")]
+#[get("/api/stats/public")]
+pub async fn handle_public_stats(
+    cache: Data<StatsCache>,
+    user_list: Data<Mutex<UserList>>,
+    stamp_history: Data<StampHistory>,
+    completions: Data<Mutex<CompletionList>>,
+) -> HttpResponse {
+    let stats = cache.public_stats(|| {
+        public_stats(
+            &user_list.lock_or_recover(),
+            &stamp_history,
+            &completions.lock_or_recover(),
+            Local::now().date_naive(),
+        )
+    });
+
+    HttpResponse::Ok()
+        .insert_header((
+            "Cache-Control",
+            format!("public, max-age={}", PUBLIC_STATS_TTL.as_secs()),
+        ))
+        .json(stats)
+}
+
+/// 시계열 구간 수의 상한입니다. 너무 작은 구간으로 긴 기간을 요청하면 400 Bad Request로 거절합니다.
+pub const MAX_BUCKETS: usize = 5000;
+
+/// 스템프별 발급 수를 일정한 시간 구간으로 나눈 시계열입니다.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Timeseries {
+    /// 구간 길이(초)입니다.
+    pub bucket_seconds: i64,
+    /// 각 구간의 시작 시각(RFC 3339, UTC)입니다.
+    pub buckets: Vec<String>,
+    /// 스템프 ID별로 `buckets`와 같은 순서의 구간별 발급 수입니다.
+    pub stamps: BTreeMap<String, Vec<usize>>,
+}
+
+/// `15m`, `1h`, `30s`와 같은 구간 길이를 해석합니다. 형식이 틀리거나 0이면 `None`을 반환합니다.
+pub fn parse_bucket(bucket: &str) -> Option<TimeDelta> {
+    let bucket = bucket.trim();
+    let (amount, unit) = bucket.split_at(bucket.find(|c: char| !c.is_ascii_digit())?);
+    let amount: i64 = amount.parse().ok().filter(|amount| *amount > 0)?;
+    match unit {
+        "s" => TimeDelta::try_seconds(amount),
+        "m" => TimeDelta::try_minutes(amount),
+        "h" => TimeDelta::try_hours(amount),
+        "d" => TimeDelta::try_days(amount),
+        _ => None,
+    }
+}
+
+/// 스템프 기록을 `bucket` 길이의 구간으로 나누어 스템프별 발급 수를 세는 함수입니다.
+/// 구간은 Unix 시각 기준으로 정렬되며, 첫 기록부터 마지막 기록까지 비어 있는 구간도 0으로 포함합니다.
+///
+/// # Returns
+///
+/// 구간 수가 `MAX_BUCKETS`를 넘으면 오류 메시지를 반환합니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let series = timeseries(&stamp_history, parse_bucket("15m").unwrap())?;
+/// println!("{:?}", series.stamps["a"]);
+/// ```
+pub fn timeseries(stamp_history: &StampHistory, bucket: TimeDelta) -> Result<Timeseries, String> {
+    let bucket_seconds = bucket.num_seconds().max(1);
+    let stamp_history = stamp_history.to_map();
+    let mut counts: BTreeMap<&str, BTreeMap<i64, usize>> = BTreeMap::new();
+    for (stamp_id, entries) in stamp_history.iter() {
+        let stamp_counts = counts.entry(stamp_id).or_default();
+        for entry in entries {
+            if let Some(timestamp) = parse_timestamp(&entry.timestamp) {
+                let index = timestamp.timestamp().div_euclid(bucket_seconds);
+                *stamp_counts.entry(index).or_default() += 1;
+            }
+        }
+    }
+
+    let indexes = counts.values().flat_map(|stamp_counts| stamp_counts.keys());
+    let first = indexes.clone().min().copied().unwrap_or(0);
+    let range = first..indexes.max().map_or(first, |last| last + 1);
+    if range.clone().count() > MAX_BUCKETS {
+        return Err(format!(
+            "Too many buckets; use a bucket larger than {}s",
+            bucket_seconds
+        ));
+    }
+
+    Ok(Timeseries {
+        bucket_seconds,
+        buckets: range
+            .clone()
+            .map(|index| {
+                DateTime::from_timestamp(index * bucket_seconds, 0)
+                    .unwrap_or_default()
+                    .to_rfc3339()
+            })
+            .collect(),
+        stamps: counts
+            .into_iter()
+            .map(|(stamp_id, stamp_counts)| {
+                let series = range
+                    .clone()
+                    .map(|index| stamp_counts.get(&index).copied().unwrap_or(0))
+                    .collect();
+                (stamp_id.to_string(), series)
+            })
+            .collect(),
+    })
+}
+
+#[derive(Deserialize)]
+pub struct TimeseriesQuery {
+    pub bucket: Option<String>,
+}
+
+/// 대시보드의 발급 추이 그래프에 사용할 시계열을 JSON으로 반환하는 비동기 함수입니다.
+///
+/// # Arguments
+///
+/// * `query` - 구간 길이(`bucket`, 기본 `15m`)를 담은 쿼리입니다.
+///
+/// # Returns
+///
+/// 스템프별 구간 발급 수를 담은 `Timeseries`가 200 OK 응답으로 반환됩니다.
+/// 직원 인증에 실패하면 401 Unauthorized 응답이, 구간 길이가 잘못되었으면 400 Bad Request 응답이 반환됩니다.
+#[routes]
+#[get("/api/v1/stats/timeseries")]
+#[get("/api/stats/timeseries")]
+pub async fn handle_timeseries(
+    req: HttpRequest,
+    query: Query<TimeseriesQuery>,
+    stamp_history: Data<StampHistory>,
+    config: Data<Reloadable<Config>>,
+) -> Result<HttpResponse, AppError> {
+    if !is_staff(&req, &config.get()) {
+        warn!("Unauthorized access to the stats API has been identified.");
+        return Err(AppError::Unauthorized);
+    }
+
+    let Some(bucket) = parse_bucket(query.bucket.as_deref().unwrap_or("15m")) else {
+        return Err(AppError::Validation(
+            "bucket must look like 30s, 15m, 1h or 1d".to_string(),
+        ));
+    };
+    let series = timeseries(&stamp_history, bucket).map_err(AppError::Validation)?;
+    Ok(HttpResponse::Ok().json(series))
+}
@@ -0,0 +1,953 @@
+use actix_web::{body::SizedStream, web, web::Bytes, web::Data};
+use async_std::io::ReadExt;
+use futures_util::{stream, Stream};
+use log::{error, info, warn};
+use openssl::sha::sha256;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::from_str;
+use std::{
+    cell::Cell,
+    collections::BTreeMap,
+    env,
+    ffi::OsStr,
+    fs::File,
+    io::{self, Read, Write},
+    path::{Component, Path, PathBuf},
+    sync::Mutex,
+};
+
+use crate::assets::AssetManifest;
+use crate::backup::{latest_valid_snapshot, to_hex, BackupConfig};
+use crate::bundle::{event_dir, event_path};
+use crate::config::{read_config, Config};
+use crate::crypto;
+use crate::error::AppError;
+use crate::i18n;
+use crate::missions::{load_missions, read_missions, MissionList};
+use crate::page_cache::StampPageCache;
+use crate::staging;
+use crate::state::{
+    stamp_history, AppState, CompletionList, Reloadable, Stamp, StampHistory, StampIdList,
+    TeamList, UserList,
+};
+
+/// 주어진 데이터를 JSON으로 직렬화하여 `resources/database/{file_name}.json`에 저장하는 함수입니다.
+///
+/// # Arguments
+///
+/// * `file_name` - 확장자를 제외한 데이터베이스 파일 이름입니다.
+/// * `data` - 저장할 데이터입니다.
+///
+/// # Returns
+///
+/// 저장에 성공하면 `Ok(true)`, 파일 생성이나 직렬화에 실패하면 `Err(false)`가 반환됩니다.
+pub fn save_file<T: serde::Serialize>(file_name: &str, data: T) -> Result<bool, bool> {
+    let saved = to_database_json(file_name, data).and_then(|json| {
+        write_database(file_name, DatabaseFormat::Json, &json).map_err(|e| e.to_string())
+    });
+    match saved {
+        Ok(_) => {
+            info!("Database save complete");
+            Ok(true)
+        }
+        Err(_) => {
+            error!("Database save Failed");
+            Err(false)
+        }
+    }
+}
+
+/// 데이터베이스 파일과 백업 스냅샷의 저장 형식입니다. `persistence.format`으로 정합니다.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DatabaseFormat {
+    /// 사람이 읽을 수 있는 JSON입니다. (`.json`)
+    #[default]
+    Json,
+    /// 데이터를 bincode로 바로 직렬화한 뒤 zstd로 압축한 형식입니다. (`.bin`) 참가자가 많은 행사에서 파일이 작고 빠릅니다.
+    /// 필드 이름을 저장하지 않으므로 저장하는 타입의 필드 순서가 바뀌면 이전 파일을 읽을 수 없습니다.
+    Binary,
+}
+
+/// zstd 압축 데이터의 시작 바이트입니다. 읽을 때 형식을 판별하는 데 사용합니다.
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// 암호화한 바이너리 형식의 시작 바이트입니다. 뒤에 압축한 내용을 AES-256-GCM으로 암호화한 값이 이어집니다.
+const ENCRYPTED_MAGIC: [u8; 4] = *b"GJE1";
+
+/// 바이너리 형식의 zstd 압축 수준입니다.
+const ZSTD_LEVEL: i32 = 3;
+
+impl DatabaseFormat {
+    /// 모든 저장 형식입니다.
+    pub const ALL: [DatabaseFormat; 2] = [DatabaseFormat::Json, DatabaseFormat::Binary];
+
+    /// 파일 확장자입니다.
+    pub fn extension(self) -> &'static str {
+        match self {
+            DatabaseFormat::Json => "json",
+            DatabaseFormat::Binary => "bin",
+        }
+    }
+
+    /// 저장된 내용의 형식을 판별합니다. zstd나 암호화 표시로 시작하지 않으면 JSON으로 봅니다.
+    pub fn detect(contents: &[u8]) -> Self {
+        if contents.starts_with(&ZSTD_MAGIC) || contents.starts_with(&ENCRYPTED_MAGIC) {
+            DatabaseFormat::Binary
+        } else {
+            DatabaseFormat::Json
+        }
+    }
+}
+
+thread_local! {
+    /// 이 스레드에서 바이너리 형식으로 직렬화하는 중인지 여부입니다. (`skip_none` 참고)
+    static WRITING_BINARY: Cell<bool> = const { Cell::new(false) };
+}
+
+/// 직렬화가 끝나거나 실패해도 `WRITING_BINARY`를 되돌리는 표시입니다.
+struct WritingBinary;
+
+impl WritingBinary {
+    fn start() -> Self {
+        WRITING_BINARY.set(true);
+        WritingBinary
+    }
+}
+
+impl Drop for WritingBinary {
+    fn drop(&mut self) {
+        WRITING_BINARY.set(false);
+    }
+}
+
+/// 빈 `Option` 필드를 JSON에서 생략하는 `skip_serializing_if` 함수입니다.
+/// bincode는 필드 이름 없이 순서대로 읽으므로, 바이너리 형식으로 저장할 때는 생략하지 않습니다.
+pub fn skip_none<T>(value: &Option<T>) -> bool {
+    !WRITING_BINARY.get() && value.is_none()
+}
+
+/// 빈 `BTreeMap` 필드를 JSON에서 생략하는 `skip_serializing_if` 함수입니다. (`skip_none` 참고)
+pub fn skip_empty_map<K, V>(value: &BTreeMap<K, V>) -> bool {
+    !WRITING_BINARY.get() && value.is_empty()
+}
+
+/// 빈 `Vec` 필드를 JSON에서 생략하는 `skip_serializing_if` 함수입니다. (`skip_none` 참고)
+pub fn skip_empty_vec<T>(value: &[T]) -> bool {
+    !WRITING_BINARY.get() && value.is_empty()
+}
+
+/// `resources/database/{file_name}.{확장자}` 경로입니다. 시험 운영 중이면 `resources/staging` 아래의 경로입니다.
+fn database_path(file_name: &str, format: DatabaseFormat) -> PathBuf {
+    PathBuf::from(format!(
+        "{}/{}.{}",
+        staging::database_dir(),
+        file_name,
+        format.extension()
+    ))
+}
+
+/// 파일의 체크섬을 담는 파일 경로(`{path}.sha256`)입니다.
+pub fn checksum_path(path: &Path) -> PathBuf {
+    let mut checksum_path = path.as_os_str().to_owned();
+    checksum_path.push(".sha256");
+    PathBuf::from(checksum_path)
+}
+
+/// 내용의 SHA-256 체크섬을 소문자 16진수 문자열로 반환합니다.
+pub fn checksum(contents: &[u8]) -> String {
+    to_hex(&sha256(contents))
+}
+
+/// `sha256sum -c`로도 확인할 수 있는 `{체크섬}  {파일 이름}` 형식의 체크섬 파일 내용을 만듭니다.
+fn checksum_line(path: &Path, contents: &[u8]) -> String {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    format!("{}  {}\n", checksum(contents), file_name)
+}
+
+/// 파일과 함께 `{path}.sha256` 체크섬 파일을 씁니다. 백업 스냅샷 저장에 사용합니다.
+pub fn write_with_checksum(path: &Path, contents: &[u8]) -> io::Result<()> {
+    std::fs::write(path, contents)?;
+    std::fs::write(checksum_path(path), checksum_line(path, contents))
+}
+
+/// 읽은 내용이 `{path}.sha256`에 기록된 체크섬 중 하나와 같은지 확인합니다.
+/// `write_database`가 저장하는 동안에는 새 내용과 이전 내용의 체크섬이 함께 기록되어 있습니다.
+/// 체크섬 파일이 없으면 체크섬을 기록하기 전에 저장된 파일로 보고 확인하지 않습니다.
+///
+/// # Returns
+///
+/// 체크섬이 다르면 파일이 잘렸거나 손상된 것이므로 오류 메시지를 반환합니다.
+pub fn verify_checksum(path: &Path, contents: &[u8]) -> Result<(), String> {
+    let lines = match std::fs::read_to_string(checksum_path(path)) {
+        Ok(lines) => lines,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(format!("{}: {}", checksum_path(path).display(), e)),
+    };
+    let actual = checksum(contents);
+    if lines.lines().any(|line| {
+        line.split_whitespace()
+            .next()
+            .is_some_and(|expected| expected.eq_ignore_ascii_case(&actual))
+    }) {
+        Ok(())
+    } else {
+        Err(format!("{}: checksum mismatch", path.display()))
+    }
+}
+
+/// 임시 파일에 쓰고 동기화한 뒤 이름을 바꿉니다.
+fn write_atomic(path: &Path, temp_path: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut file = File::create(temp_path)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    std::fs::rename(temp_path, path)
+}
+
+/// 파일을 지웁니다. 없는 파일은 무시합니다.
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// 저장 형식으로 만든 내용을 `resources/database/{file_name}.{확장자}`에 쓰고, 체크섬을 `{확장자}.sha256` 파일에 씁니다.
+/// 임시 파일에 먼저 쓰고 이름을 바꾸므로, 쓰는 도중 서버가 멈춰도 이전 파일이 깨지지 않습니다.
+/// 데이터 파일을 바꾸는 동안에는 체크섬 파일에 이전 체크섬을 함께 남겨두므로, 두 파일의 이름을 바꾸는 사이에 멈춰도
+/// 남아 있는 데이터 파일이 체크섬 오류로 버려지지 않습니다.
+/// 다른 형식으로 저장된 같은 이름의 파일은 지워서, 다음에 불러올 때 오래된 파일을 읽지 않도록 합니다.
+pub fn write_database(file_name: &str, format: DatabaseFormat, contents: &[u8]) -> io::Result<()> {
+    let path = database_path(file_name, format);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let temp_path = path.with_extension(format!("{}.tmp", format.extension()));
+    let checksum_file = checksum_path(&path);
+    let checksum_temp = checksum_file.with_extension("sha256.tmp");
+    let line = checksum_line(&path, contents);
+    // 데이터 파일의 이름을 바꾸기 전까지는 이전 내용의 체크섬도 맞는 것으로 보도록 함께 기록
+    let previous = match std::fs::read_to_string(&checksum_file) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        previous => previous?,
+    };
+    write_atomic(
+        &checksum_file,
+        &checksum_temp,
+        format!("{}{}", line, previous).as_bytes(),
+    )?;
+    write_atomic(&path, &temp_path, contents)?;
+    write_atomic(&checksum_file, &checksum_temp, line.as_bytes())?;
+
+    for other in DatabaseFormat::ALL
+        .into_iter()
+        .filter(|other| *other != format)
+    {
+        let other_path = database_path(file_name, other);
+        remove_if_exists(&other_path)?;
+        remove_if_exists(&checksum_path(&other_path))?;
+    }
+    Ok(())
+}
+
+/// 데이터베이스 파일에 저장할 내용을 만드는 함수입니다. 암호화 키가 설정되어 있으면 개인정보를 암호화합니다.
+/// JSON은 개인정보 필드를 암호화하고, 바이너리 형식은 압축한 내용 전체를 암호화합니다.
+///
+/// # Arguments
+///
+/// * `file_name` - 확장자를 제외한 데이터베이스 파일 이름입니다. 암호화할 내용을 정하는 데 사용합니다.
+/// * `data` - 저장할 데이터입니다.
+/// * `format` - 저장 형식입니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let contents = to_database("stamp_status", &stamp_history, DatabaseFormat::Binary)?;
+/// write_database("stamp_status", DatabaseFormat::Binary, &contents)?;
+/// ```
+pub fn to_database<T: serde::Serialize>(
+    file_name: &str,
+    data: T,
+    format: DatabaseFormat,
+) -> Result<Vec<u8>, String> {
+    let key = crypto::key();
+    match format {
+        DatabaseFormat::Json => {
+            // `Value`를 거치면 키가 정렬되어 `HashMap`으로 저장하는 기록도 저장할 때마다 같은 순서로 씀
+            let value = serde_json::to_value(data).map_err(|e| e.to_string())?;
+            let value = match key {
+                Some(key) => crypto::seal(file_name, value, &key),
+                None => value,
+            };
+            serde_json::to_vec(&value).map_err(|e| e.to_string())
+        }
+        DatabaseFormat::Binary => {
+            let encoded = {
+                let _writing = WritingBinary::start();
+                bincode::serialize(&data).map_err(|e| e.to_string())?
+            };
+            let compressed =
+                zstd::encode_all(encoded.as_slice(), ZSTD_LEVEL).map_err(|e| e.to_string())?;
+            match key {
+                Some(key) if crypto::is_personal(file_name) => Ok([
+                    &ENCRYPTED_MAGIC[..],
+                    &crypto::encrypt_bytes(&key, &compressed),
+                ]
+                .concat()),
+                _ => Ok(compressed),
+            }
+        }
+    }
+}
+
+/// 데이터베이스 파일에 저장할 JSON을 만드는 함수입니다. (`to_database` 참고)
+pub fn to_database_json<T: serde::Serialize>(file_name: &str, data: T) -> Result<Vec<u8>, String> {
+    to_database(file_name, data, DatabaseFormat::Json)
+}
+
+/// `to_database`로 저장한 내용을 읽는 함수입니다. 형식은 내용으로 판별하므로 JSON과 바이너리 파일을
+/// 모두 읽을 수 있고, 암호화된 내용은 설정된 키로 복호화합니다.
+///
+/// # Returns
+///
+/// 파싱이나 복호화에 실패하면 오류 메시지를 반환합니다.
+pub fn from_database<T: DeserializeOwned>(contents: &[u8]) -> Result<T, String> {
+    let key = crypto::key();
+    match DatabaseFormat::detect(contents) {
+        DatabaseFormat::Json => {
+            let value = serde_json::from_slice(contents).map_err(|e| e.to_string())?;
+            let value = crypto::unseal(value, key.as_ref())?;
+            serde_json::from_value(value).map_err(|e| e.to_string())
+        }
+        DatabaseFormat::Binary => {
+            let compressed = match contents.strip_prefix(&ENCRYPTED_MAGIC) {
+                Some(encrypted) => {
+                    let key = key.ok_or_else(|| {
+                        format!("Data is encrypted but {} is not set", crypto::KEY_ENV)
+                    })?;
+                    crypto::decrypt_bytes(&key, encrypted)?
+                }
+                None => contents.to_vec(),
+            };
+            let decoded = zstd::decode_all(compressed.as_slice()).map_err(|e| e.to_string())?;
+            bincode::deserialize(&decoded).map_err(|e| e.to_string())
+        }
+    }
+}
+
+/// `to_database_json`으로 저장한 JSON을 읽는 함수입니다. (`from_database` 참고)
+pub fn from_database_json<T: DeserializeOwned>(file_content: &str) -> Result<T, String> {
+    from_database(file_content.as_bytes())
+}
+
+/// 데이터베이스 파일을 읽고 체크섬을 확인합니다. 두 형식의 파일이 모두 있으면 가장 최근에 저장한 파일을 읽습니다.
+///
+/// # Returns
+///
+/// 파일이 없으면 `None`을, 읽지 못했거나 체크섬이 다르면 오류 메시지를 반환합니다.
+pub fn read_database(file_name: &str) -> Option<Result<Vec<u8>, String>> {
+    let path = DatabaseFormat::ALL
+        .into_iter()
+        .map(|format| database_path(file_name, format))
+        .filter_map(|path| {
+            let modified = std::fs::metadata(&path).and_then(|meta| meta.modified());
+            modified.ok().map(|modified| (modified, path))
+        })
+        .max()
+        .map(|(_, path)| path)?;
+    let contents = std::fs::read(&path).map_err(|e| format!("{}: {}", path.display(), e));
+    Some(contents.and_then(|contents| verify_checksum(&path, &contents).map(|_| contents)))
+}
+
+/// JSON 형식의 스탬프 정보를 읽어와서 `StampIdList` 구조체로 변환하는 함수입니다.
+///
+/// # Returns
+///
+/// 성공적으로 파일을 열고 JSON을 읽어온 경우, 해당 정보를 담은 `StampIdList`가 반환됩니다.
+/// 파일이 없거나 검증에 실패한 경우 문제가 된 위치와 항목을 로그로 남기고 서버를 시작하지 않고 종료합니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[tokio::main]
+/// async fn main() {
+///     let stamp_id_list = parse_json();
+///     println!("Loaded Stamp ID List: {:?}", stamp_id_list);
+/// }
+/// ```
+pub fn stamp_db() -> StampIdList {
+    match read_stamp_list() {
+        Ok(stamp_id_list) => {
+            info!("Stamp Database load complete");
+            stamp_id_list
+        }
+        Err(e) => {
+            error!(
+                "{} is invalid:\n{}\nFix the file and start the server again.",
+                event_path("api/stampList.json").display(),
+                e
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+/// `resources/api/stampList.json`을 읽어 `StampIdList`로 변환하는 함수입니다. 서버 실행 중 다시 불러올 때 사용합니다.
+///
+/// # Returns
+///
+/// 파일을 읽지 못했거나 `parse_stamp_list`의 검증에 실패하면 오류 메시지를 반환합니다.
+pub fn read_stamp_list() -> Result<StampIdList, String> {
+    let file_content =
+        std::fs::read_to_string(event_path("api/stampList.json")).map_err(|e| e.to_string())?;
+    parse_stamp_list(&file_content)
+}
+
+/// 중복된 `stampId`를 찾기 위해 스템프를 파일에 적힌 순서대로 읽는 구조체입니다.
+#[derive(Deserialize)]
+struct RawStampList {
+    #[serde(rename = "stampList")]
+    stamps: Vec<Stamp>,
+}
+
+/// `stampList.json` 내용을 검증하여 `StampIdList`로 변환하는 함수입니다.
+///
+/// # Arguments
+///
+/// * `file_content` - `stampList.json` 파일 내용입니다.
+///
+/// # Returns
+///
+/// JSON 문법 오류는 줄과 열, 해당 줄의 내용을 담은 메시지를 반환합니다.
+/// 스템프가 없거나, `stampId`나 `stampAliases`의 코드가 중복되었거나, `stampId`, `stampName`, `stampLocation`이 비어 있으면
+/// 발견된 문제를 한 줄에 하나씩 담은 메시지를 반환합니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let error = parse_stamp_list("{\"stampList\": [}").unwrap_err();
+/// assert!(error.contains("line 1"));
+/// ```
+pub fn parse_stamp_list(file_content: &str) -> Result<StampIdList, String> {
+    let stamp_list: RawStampList = from_str(file_content).map_err(|e| {
+        // 문제가 된 줄을 함께 보여 주어 어디를 고쳐야 하는지 바로 알 수 있도록 함
+        let source_line = file_content
+            .lines()
+            .nth(e.line().saturating_sub(1))
+            .unwrap_or_default();
+        format!(
+            "{}\n  {}\n  {}^",
+            e,
+            source_line,
+            " ".repeat(e.column().saturating_sub(1))
+        )
+    })?;
+
+    if stamp_list.stamps.is_empty() {
+        return Err("Stamp list is empty".to_string());
+    }
+
+    let mut problems = Vec::new();
+    let mut first_index: BTreeMap<&str, usize> = BTreeMap::new();
+    for (index, stamp) in stamp_list.stamps.iter().enumerate() {
+        for (field, value) in [
+            ("stampId", &stamp.stampId),
+            ("stampName", &stamp.stampName),
+            ("stampLocation", &stamp.stampLocation),
+        ] {
+            if value.trim().is_empty() {
+                problems.push(format!("stampList[{}]: {} is empty", index, field));
+            }
+        }
+        if stamp.stampId.trim().is_empty() {
+            continue;
+        }
+        if let Some(first) = first_index.insert(&stamp.stampId, index) {
+            problems.push(format!(
+                "stampList[{}]: duplicate stampId \"{}\" (first defined at stampList[{}])",
+                index, stamp.stampId, first
+            ));
+            first_index.insert(&stamp.stampId, first);
+        }
+    }
+    // 별칭 코드는 다른 스템프 ID나 별칭과 겹치면 어느 스템프인지 알 수 없으므로 거절
+    let mut alias_index: BTreeMap<&str, usize> = BTreeMap::new();
+    for (index, stamp) in stamp_list.stamps.iter().enumerate() {
+        for alias in &stamp.stampAliases {
+            if alias.trim().is_empty() {
+                problems.push(format!(
+                    "stampList[{}]: stampAliases has an empty code",
+                    index
+                ));
+            } else if let Some(other) = first_index.get(alias.as_str()) {
+                problems.push(format!(
+                    "stampList[{}]: alias \"{}\" is the stampId of stampList[{}]",
+                    index, alias, other
+                ));
+            } else if let Some(first) = alias_index.insert(alias, index) {
+                problems.push(format!(
+                    "stampList[{}]: duplicate alias \"{}\" (first defined at stampList[{}])",
+                    index, alias, first
+                ));
+                alias_index.insert(alias, first);
+            }
+        }
+    }
+    if !problems.is_empty() {
+        return Err(problems.join("\n"));
+    }
+
+    Ok(StampIdList {
+        stamp_id_list: stamp_list
+            .stamps
+            .into_iter()
+            .map(|stamp| (stamp.stampId.clone(), stamp))
+            .collect(),
+    })
+}
+
+pub fn stamp_history_db(stamp_id_list: StampIdList, backup: &BackupConfig) -> StampHistory {
+    load_database("stamp_status", backup)
+        .unwrap_or_else(|| StampHistory::from(stamp_history(stamp_id_list)))
+}
+
+pub fn user_list_db(backup: &BackupConfig) -> UserList {
+    load_database("user_status", backup).unwrap_or_default()
+}
+
+/// `resources/database/{file_name}`의 JSON 또는 바이너리 파일을 읽어 주어진 타입으로 변환하는 함수입니다.
+///
+/// 파일이 잘렸거나 체크섬이 맞지 않거나 파싱에 실패하면, 빈 데이터로 시작하지 않고
+/// `backup.dir`에서 체크섬과 파싱이 모두 맞는 가장 최근 스냅샷을 대신 불러옵니다.
+/// 디스크를 잃고 다시 시작하여 파일이 없을 때도 사용할 수 있는 스냅샷이 있으면 불러옵니다.
+///
+/// # Arguments
+///
+/// * `file_name` - 확장자를 제외한 데이터베이스 파일 이름입니다.
+/// * `backup` - 손상된 파일 대신 불러올 스냅샷을 찾을 백업 설정입니다.
+///
+/// # Returns
+///
+/// 파일이나 스냅샷을 불러오면 `Some(T)`, 둘 다 없으면 `None`이 반환됩니다.
+/// 파일이 손상되었고 사용할 수 있는 스냅샷도 없으면 패닉이 발생합니다.
+pub fn load_database<T: DeserializeOwned>(file_name: &str, backup: &BackupConfig) -> Option<T> {
+    let Some(contents) = read_database(file_name) else {
+        let (timestamp, data) = latest_valid_snapshot(backup, file_name)?;
+        warn!(
+            "{} Database is missing and was restored from backup {}. Changes after the backup are lost.",
+            file_name, timestamp
+        );
+        return Some(data);
+    };
+    let loaded = contents.and_then(|contents| from_database(&contents));
+    match loaded {
+        Ok(data) => {
+            info!("{} Database load complete", file_name);
+            Some(data)
+        }
+        Err(e) => {
+            error!("{} Database is damaged : {}", file_name, e);
+            let Some((timestamp, data)) = latest_valid_snapshot(backup, file_name) else {
+                panic!(
+                    "{} Database is damaged and no valid backup was found in {}",
+                    file_name, backup.dir
+                );
+            };
+            warn!(
+                "{} Database was restored from backup {}. Changes after the backup are lost.",
+                file_name, timestamp
+            );
+            Some(data)
+        }
+    }
+}
+
+pub fn completion_list_db(backup: &BackupConfig) -> CompletionList {
+    load_database("completion_status", backup).unwrap_or_default()
+}
+
+pub fn team_list_db(backup: &BackupConfig) -> TeamList {
+    load_database("team_status", backup).unwrap_or_default()
+}
+
+/// 저장된 데이터베이스를 모두 불러와 공유 상태를 생성하는 함수입니다.
+///
+/// # Arguments
+///
+/// * `config` - 행사 설정입니다.
+///
+/// # Returns
+///
+/// 스템프 목록, 유저 리스트, 스템프 기록, 완주 기록, 팀 목록, 공지, 투어 운영 상태, 협력사 API 키, Web Push 구독, 수정 내역을 담은 `AppState`가 반환됩니다.
+pub fn load_state(config: Config) -> AppState {
+    if let Err(e) = crypto::configure(&config) {
+        error!(
+            "{}\nSet a base64 or hex encoded 32 byte key and start the server again.",
+            e
+        );
+        std::process::exit(1);
+    }
+    let stamp_list = stamp_db();
+    let backup = config.backup.clone();
+    let mut state = AppState::new(config, stamp_list.clone());
+
+    let mut user_list = user_list_db(&backup);
+    let assigned = user_list.assign_missing_codes();
+    if assigned > 0 {
+        info!("Assigned staff lookup codes to {} existing users", assigned);
+    }
+    state.user_list = Data::new(Mutex::new(user_list));
+    state.stamp_history = Data::new(stamp_history_db(stamp_list, &backup));
+    state.completions = Data::new(Mutex::new(completion_list_db(&backup)));
+    state.teams = Data::new(Mutex::new(team_list_db(&backup)));
+    state.announcement = Data::new(Mutex::new(load_database("announcement", &backup)));
+    state.audit_log = Data::new(Mutex::new(
+        load_database("audit_log", &backup).unwrap_or_default(),
+    ));
+    state.tour_status = Data::new(Mutex::new(
+        load_database("tour_status", &backup).unwrap_or_default(),
+    ));
+    state.partner_keys = Data::new(Mutex::new(
+        load_database("partner_keys", &backup).unwrap_or_default(),
+    ));
+    state.push_subscriptions = Data::new(Mutex::new(
+        load_database("push_subscriptions", &backup).unwrap_or_default(),
+    ));
+    state.short_links = Data::new(Mutex::new(
+        load_database("short_links", &backup).unwrap_or_default(),
+    ));
+    state.nfc_counters = Data::new(Mutex::new(
+        load_database("nfc_counters", &backup).unwrap_or_default(),
+    ));
+    state.feedback = Data::new(Mutex::new(
+        load_database("feedback", &backup).unwrap_or_default(),
+    ));
+    state.guestbook = Data::new(Mutex::new(
+        load_database("guestbook", &backup).unwrap_or_default(),
+    ));
+    state.admin_history = Data::new(Mutex::new(
+        load_database("admin_history", &backup).unwrap_or_default(),
+    ));
+    state.missions = Data::new(Reloadable::new(load_missions()));
+    state.assets = Data::new(Reloadable::new(AssetManifest::build(&resources_dir())));
+    state
+}
+
+/// 지정된 폴더와 파일 이름을 사용하여 파일의 경로를 설정하고, `read_file` 함수를 사용하여 파일을 비동기적으로 읽어옵니다.
+///
+/// # Arguments
+///
+/// * `folder` - 파일이 위치한 폴더의 이름입니다.
+/// * `file` - 읽어올 파일의 이름입니다.
+///
+/// # Returns
+///
+/// 읽은 파일이 텍스트 일경우 `Ok(String)`이 반환되며, 바이너리 파일인 경우 `Err(Vec<u8>)`이 반환됩니다.
+/// `html` 폴더는 요청 언어별 템플릿과 문자열 묶음이 적용된 내용을 반환합니다. (`i18n::localized_template` 참고)
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[get("/")]
+/// async fn index() -> impl Responder {
+///     match path("html", "index.html").await {
+///         Ok(v) => HttpResponse::Ok().body(v),
+///         Err(_) => handle_404().await,
+///     }
+/// }
+/// ```
+pub async fn path(folder: &str, file: &str) -> Result<String, Vec<u8>> {
+    // HTML 템플릿은 요청 언어에 맞는 파일과 문자열 묶음을 사용
+    if folder == "html" {
+        return i18n::localized_template(file).await;
+    }
+    // 파일 경로에서 읽어온 결과를 반환
+    read_file(resource_path(folder, file).as_path()).await
+}
+
+/// `resources/html`의 템플릿을 요청 언어로 읽는 비동기 함수입니다. (`path("html", file)` 참고)
+///
+/// # Returns
+///
+/// 템플릿 내용이 반환됩니다. 파일이 없으면 빈 문자열이, 텍스트 파일이 아니면 `AppError::Template`이 반환됩니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[get("/complete")]
+/// async fn handle_complete(req: HttpRequest) -> Result<HttpResponse, AppError> {
+///     Ok(HttpResponse::Ok().body(inject(&req, html_template("complete.html").await?)))
+/// }
+/// ```
+pub async fn html_template(file: &str) -> Result<String, AppError> {
+    path("html", file)
+        .await
+        .map_err(|_| AppError::Template(format!("{} is not a text file", file)))
+}
+
+/// `resources_dir()`을 기준으로 `resources/{folder}/{file}` 경로를 만드는 함수입니다.
+pub fn resource_path(folder: &str, file: &str) -> PathBuf {
+    resources_dir().join(folder).join(file)
+}
+
+/// 템플릿과 정적 파일을 읽는 `resources` 폴더 경로를 반환합니다.
+/// `--event-dir`로 실행했으면 행사 폴더를, 아니면 실행 파일 위치를 기준으로 한 `resources` 폴더를 사용합니다.
+pub fn resources_dir() -> PathBuf {
+    if let Some(dir) = event_dir() {
+        return dir.to_path_buf();
+    }
+    // 현재 실행 파일 경로를 얻고, 오류가 발생하면 기본값을 사용합니다.
+    env::current_exe()
+        .map(|exe_path| {
+            exe_path
+                .parent()
+                .map_or(Default::default(), |exe_dir| exe_dir.join("resources"))
+        })
+        .unwrap_or_default()
+}
+
+/// 정적 파일로 제공하는 `resources` 하위 폴더입니다. 목록에 없는 폴더는 제공하지 않으므로,
+/// 데이터베이스, 백업, 내보내기, 보고서처럼 나중에 추가되는 데이터 폴더도 따로 막지 않아도 공개되지 않습니다.
+/// 스템프 목록 원본이 있는 `api` 폴더도 숨은 스템프가 드러나므로 제공하지 않습니다. (`handle_stamp_list_file` 참고)
+pub const PUBLIC_FOLDERS: [&str; 6] = ["css", "fonts", "html", "i18n", "img", "js"];
+
+/// 요청 경로가 `resources` 폴더 안의 공개 경로인지 파일 시스템에 접근하지 않고 확인하는 함수입니다.
+/// `..`, `.`, 절대 경로, 빈 경로와 `PUBLIC_FOLDERS`에 없는 폴더로 시작하는 경로는 거부합니다.
+///
+/// # Example
+///
+/// ```rust
+/// use gj_stamptour::storage::public_resource_path;
+///
+/// assert!(public_resource_path("img/icons/star.png").is_some());
+/// assert!(public_resource_path("img/../database/user_status.json").is_none());
+/// assert!(public_resource_path("database/user_status.json").is_none());
+/// assert!(public_resource_path("exports/users.csv").is_none());
+/// assert!(public_resource_path("backups/user_status-20241005-130000.json").is_none());
+/// ```
+pub fn public_resource_path(relative: &str) -> Option<&Path> {
+    let relative = Path::new(relative);
+    if !relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
+    {
+        return None;
+    }
+    let folder = relative.components().next()?.as_os_str().to_str()?;
+    PUBLIC_FOLDERS.contains(&folder).then_some(relative)
+}
+
+/// `/{file}` 요청의 파일 이름을 `resources/html` 안의 파일 이름으로 바꾸는 함수입니다.
+/// 확장자가 없으면 `.html`을 붙이고, 하위 폴더나 상위 폴더를 가리키는 이름은 거부합니다.
+///
+/// # Example
+///
+/// ```rust
+/// use gj_stamptour::storage::html_file_name;
+///
+/// assert_eq!(html_file_name("index").as_deref(), Some("index.html"));
+/// assert_eq!(html_file_name("style.css").as_deref(), Some("style.css"));
+/// assert_eq!(html_file_name(".."), None);
+/// ```
+pub fn html_file_name(file: &str) -> Option<String> {
+    if Path::new(file).file_name() != Some(OsStr::new(file)) {
+        return None;
+    }
+    match file.contains('.') {
+        true => Some(file.to_string()),
+        false => Some(format!("{}.html", file)),
+    }
+}
+
+/// 요청 경로를 `resources` 폴더 안의 실제 파일 경로로 안전하게 변환하는 함수입니다.
+/// `..`나 절대 경로, 공개하지 않는 폴더, 심볼릭 링크로 `resources` 밖을 가리키는 경로는 거부합니다.
+///
+/// # Arguments
+///
+/// * `relative` - `resources` 기준 상대 경로입니다. (예: `img/icons/star.png`)
+///
+/// # Returns
+///
+/// `resources` 안에 존재하는 파일이면 `Some(PathBuf)`, 그 외에는 `None`을 반환합니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// assert!(safe_resource_path("img/icons/star.png").is_some());
+/// assert!(safe_resource_path("img/../database/user_status.json").is_none());
+/// ```
+pub fn safe_resource_path(relative: &str) -> Option<PathBuf> {
+    let relative = public_resource_path(relative)?;
+    let root = resources_dir();
+    let full_path = root.join(relative);
+    let canonical = full_path.canonicalize().ok()?;
+    (canonical.starts_with(root.canonicalize().ok()?) && canonical.is_file()).then_some(full_path)
+}
+
+/// 이진 파일 확장자 목록입니다. 이 파일들은 메모리에 한 번에 읽지 않고 스트리밍으로 전송합니다.
+pub const BINARY_EXTENSIONS: [&str; 6] = ["ico", "png", "webp", "ttf", "woff2", "woff"];
+
+/// 스트리밍 전송 시 한 번에 읽는 크기(바이트)입니다.
+const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
+/// 파일 이름의 확장자가 이진 파일 목록에 있는지 확인합니다.
+pub fn is_binary(file: &str) -> bool {
+    Path::new(file)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| BINARY_EXTENSIONS.contains(&extension))
+}
+
+/// 파일 전체를 메모리에 올리지 않고 일정 크기씩 읽어 전송하는 응답 본문을 생성하는 비동기 함수입니다.
+/// 큰 지도 이미지나 글꼴을 여러 명이 동시에 내려받아도 메모리 사용량이 늘어나지 않습니다.
+///
+/// # Arguments
+///
+/// * `path` - 전송할 파일 경로입니다.
+///
+/// # Returns
+///
+/// 파일을 열 수 있으면 `Content-Length`가 설정된 `SizedStream`을, 아니면 입출력 오류를 반환합니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// match stream_file(&resource_path("img", "map.png")).await {
+///     Ok(body) => HttpResponse::Ok().body(body),
+///     Err(_) => handle_404().await,
+/// }
+/// ```
+pub async fn stream_file(
+    path: &Path,
+) -> io::Result<SizedStream<impl Stream<Item = io::Result<Bytes>>>> {
+    let file = async_std::fs::File::open(path).await?;
+    let size = file.metadata().await?.len();
+
+    // 읽기 오류가 나면 오류를 한 번 전달한 뒤 스트림을 종료
+    let chunks = stream::unfold(Some(file), |file| async move {
+        let mut file = file?;
+        let mut buffer = vec![0; STREAM_CHUNK_SIZE];
+        match file.read(&mut buffer).await {
+            Ok(0) => None,
+            Ok(read) => {
+                buffer.truncate(read);
+                Some((Ok(Bytes::from(buffer)), Some(file)))
+            }
+            Err(e) => {
+                error!("File streaming failed : {}", e);
+                Some((Err(e), None))
+            }
+        }
+    });
+    Ok(SizedStream::new(size, chunks))
+}
+
+/// 지정된 경로의 파일을 읽어 문자열 또는 이진 데이터로 반환하는 비동기 함수입니다.
+///
+/// # Arguments
+///
+/// * `path` - 파일을 나타내는 경로입니다.
+///
+/// # Returns
+///
+/// 읽은 파일이 텍스트 일경우 `Ok(String)`이 반환되며, 바이너리 파일인 경우 `Err(Vec<u8>)`이 반환됩니다.
+/// 디스크 읽기가 멈추더라도 작업자 스레드를 막지 않도록 블로킹 스레드에서 읽으므로,
+/// `apply_timeout`이 제한 시간이 지난 요청을 끊고 작업자가 다른 요청을 처리할 수 있습니다.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// match read_file(file_path.as_path()).await {
+///     Ok(v) => Ok(v),
+///     Err(e) => Err(e),
+/// }
+/// ```
+pub async fn read_file(path: &Path) -> Result<String, Vec<u8>> {
+    let path = path.to_path_buf();
+    web::block(move || read_file_blocking(&path))
+        .await
+        .unwrap_or_else(|e| {
+            error!("File read failed : {}", e);
+            Ok(String::new())
+        })
+}
+
+/// `read_file`의 블로킹 스레드에서 실행하는 부분입니다.
+fn read_file_blocking(path: &Path) -> Result<String, Vec<u8>> {
+    // 파일이 없거나 읽지 못하면 빈 내용으로 처리 (존재 여부는 호출하는 쪽에서 확인)
+    let mut binary_contents = Vec::new();
+    if let Err(e) = File::open(path).and_then(|mut file| file.read_to_end(&mut binary_contents)) {
+        if e.kind() != io::ErrorKind::NotFound {
+            warn!("Failed to read {} : {}", path.display(), e);
+        }
+    }
+
+    // 파일 확장자를 추출하고, 이진 파일 목록에 있는 경우 에러를 반환
+    let split_extension: Vec<&str> = path.to_str().unwrap_or_default().split('.').collect();
+
+    if let Some(&list_extension) = split_extension.last() {
+        if BINARY_EXTENSIONS.contains(&list_extension) {
+            return Err(binary_contents);
+        } else if "svg" == list_extension {
+            let mut str_contents = String::new();
+            if svg::open(path, &mut str_contents).is_err() {
+                str_contents.clear();
+            }
+            return Ok(str_contents);
+        }
+    }
+
+    // 이진 데이터를 문자열로 변환하고, 변환에 실패하면 에러를 반환
+    String::from_utf8(binary_contents).map_err(|e| e.into_bytes())
+}
+
+/// 서버를 멈추지 않고 설정 파일, 스템프 목록, 미션 목록, 정적 파일 대응표를 다시 불러오는 함수입니다.
+/// HTML 템플릿은 요청마다 파일에서 읽으므로 따로 불러올 필요가 없습니다.
+/// 알림 설정과 서버 바인딩, 작업자 수 같은 항목은 재시작해야 적용됩니다.
+///
+/// # Arguments
+///
+/// * `config` - 교체할 설정입니다.
+/// * `stamp_list` - 교체할 스템프 목록입니다.
+/// * `stamp_history` - 새로 추가된 스템프의 빈 기록을 만들 스템프 기록입니다.
+/// * `assets` - 교체할 정적 파일 대응표입니다.
+/// * `stamp_pages` - 템플릿이 바뀌었을 수 있으므로 비울 스템프 페이지 캐시입니다.
+/// * `missions` - 교체할 미션 목록입니다.
+///
+/// # Returns
+///
+/// 성공하면 새 스템프 개수를, 설정이나 스템프 목록, 미션 목록을 읽지 못하면 오류 메시지를 반환합니다. 실패하면 아무것도 교체하지 않습니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let stamp_count = reload_state(&state.config, &state.stamp_list, &state.stamp_history, &state.assets, &state.stamp_pages, &state.missions)?;
+/// ```
+pub fn reload_state(
+    config: &Reloadable<Config>,
+    stamp_list: &Reloadable<StampIdList>,
+    stamp_history: &StampHistory,
+    assets: &Reloadable<AssetManifest>,
+    stamp_pages: &StampPageCache,
+    missions: &Reloadable<MissionList>,
+) -> Result<usize, String> {
+    let new_config = read_config().map_err(|e| format!("config.json: {}", e))?;
+    let new_stamp_list = read_stamp_list().map_err(|e| format!("stampList.json: {}", e))?;
+    let new_missions = read_missions().map_err(|e| format!("missions.json: {}", e))?;
+    let stamp_count = new_stamp_list.stamp_id_list.len();
+
+    for stamp_id in new_stamp_list.stamp_id_list.keys() {
+        stamp_history.insert_stamp(stamp_id);
+    }
+    config.replace(new_config);
+    stamp_list.replace(new_stamp_list);
+    missions.replace(new_missions);
+    assets.replace(AssetManifest::build(&resources_dir()));
+    stamp_pages.clear();
+
+    info!("Reloaded config and {} stamps", stamp_count);
+    Ok(stamp_count)
+}
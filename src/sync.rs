@@ -0,0 +1,307 @@
+use actix_web::{
+    web::{Data, Json},
+    HttpResponse,
+};
+use chrono::{DateTime, Duration, Utc};
+use log::{info, warn};
+use openssl::memcmp;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeSet, sync::Mutex};
+
+use crate::backup::{hmac_sha256, to_hex};
+use crate::booth::booth_key;
+use crate::config::Config;
+use crate::error::AppError;
+use crate::lock::MutexExt;
+use crate::notifier::{Event, Notifier};
+use crate::persistence::{Dataset, Persister};
+use crate::progress::record_progress;
+use crate::report::parse_timestamp;
+use crate::state::{
+    CompletionList, Reloadable, StampHistory, StampIdList, StampUserInfo, TeamList, UserList,
+};
+
+/// 한 번에 동기화할 수 있는 최대 기록 수입니다.
+pub const MAX_SYNC_EVENTS: usize = 1000;
+
+/// `/api/v1/sync` 요청 본문의 최대 크기(바이트)입니다. 기록이 많이 쌓인 기기를 위해 일반 요청보다 크게 잡습니다.
+pub const SYNC_BODY_LIMIT: usize = 256 * 1024;
+
+/// 기기 시계가 서버보다 빠른 경우를 위해 허용하는 오차입니다.
+const CLOCK_SKEW_MINUTES: i64 = 5;
+
+/// 연결이 끊긴 동안 직원 기기에 쌓아둔 스템프 기록 하나입니다.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SyncEvent {
+    /// 방문객의 6자리 유저 코드입니다.
+    pub user_code: String,
+    pub stamp_id: String,
+    /// 기기에서 스템프를 찍은 시각(RFC 3339)입니다.
+    pub timestamp: String,
+    /// `event_signature`로 계산한 기기 서명입니다.
+    pub signature: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SyncRequest {
+    pub events: Vec<SyncEvent>,
+}
+
+/// 동기화한 기록 하나의 처리 결과입니다.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncStatus {
+    Accepted,
+    /// 이미 반영된 기록입니다. 기기가 같은 묶음을 다시 보낸 경우입니다.
+    Duplicate,
+    Rejected,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SyncResult {
+    pub status: SyncStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// 반영되었거나 이미 반영된 기록의 서버 순번입니다. 기기는 이 값으로 보낸 기록을 확인하고 지울 수 있습니다.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seq: Option<u64>,
+}
+
+/// `/api/v1/sync` 응답입니다. `results`는 요청한 기록과 같은 순서입니다.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncResponse {
+    pub accepted: usize,
+    pub duplicates: usize,
+    pub rejected: usize,
+    pub results: Vec<SyncResult>,
+}
+
+/// 직원 기기가 기록마다 붙이는 서명을 계산하는 함수입니다.
+/// 부스별 접근 키(`booth_key`)로 `{user_code}:{stamp_id}:{timestamp}`의 HMAC-SHA256을 계산한 16진수 문자열입니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let key = booth_key(staff_token, "a");
+/// let signature = event_signature(&key, "ABC234", "a", "2024-10-05T10:00:00+09:00");
+/// ```
+pub fn event_signature(key: &str, user_code: &str, stamp_id: &str, timestamp: &str) -> String {
+    to_hex(&hmac_sha256(
+        key.as_bytes(),
+        &format!("{}:{}:{}", user_code, stamp_id, timestamp),
+    ))
+}
+
+/// 기록 하나를 확인하여 스템프 기록에 넣을 `StampUserInfo`를 만듭니다.
+fn validate(
+    event: &SyncEvent,
+    staff_token: &str,
+    closes_at: Option<DateTime<Utc>>,
+    stamp_id_list: &StampIdList,
+    user_list: &UserList,
+    now: DateTime<Utc>,
+) -> Result<StampUserInfo, String> {
+    if !stamp_id_list.stamp_id_list.contains_key(&event.stamp_id) {
+        return Err(format!("Unknown stamp {}", event.stamp_id));
+    }
+    let key = booth_key(staff_token, &event.stamp_id);
+    let expected = event_signature(&key, &event.user_code, &event.stamp_id, &event.timestamp);
+    // 서명을 한 글자씩 맞춰보지 못하도록 같은 시간에 비교
+    if event.signature.len() != expected.len()
+        || !memcmp::eq(event.signature.as_bytes(), expected.as_bytes())
+    {
+        return Err("Invalid signature".to_string());
+    }
+    let user_id = user_list
+        .find_by_code(&event.user_code)
+        .ok_or_else(|| format!("Unknown user code {}", event.user_code))?;
+    let timestamp = DateTime::parse_from_rfc3339(&event.timestamp)
+        .map_err(|_| format!("Invalid timestamp {}", event.timestamp))?
+        .with_timezone(&Utc);
+    if timestamp > now + Duration::minutes(CLOCK_SKEW_MINUTES) {
+        return Err("Timestamp is in the future".to_string());
+    }
+    if closes_at.is_some_and(|closes_at| timestamp >= closes_at) {
+        return Err("Timestamp is after the tour closed".to_string());
+    }
+
+    Ok(StampUserInfo {
+        user_name: user_list.users.get(user_id).cloned().unwrap_or_default(),
+        user_id: user_id.clone(),
+        timestamp: timestamp.to_string(),
+        seq: 0, // 합칠 때 순번 발급
+    })
+}
+
+/// 직원 기기에 쌓인 기록을 확인하고 중복을 제거하여 스템프 기록에 합치는 함수입니다.
+/// 합친 기록은 시각 순서가 유지되도록 제자리에 넣지만 순번은 반영할 때 새로 발급하므로,
+/// 스템프 기록 목록 안에서 순번은 정렬되어 있지 않을 수 있습니다. 반영 순서가 필요하면 `seq`로 정렬해야 합니다.
+///
+/// # Arguments
+///
+/// * `events` - 기기가 보낸 기록입니다.
+/// * `staff_token` - 기기 서명 확인에 사용할 직원 토큰입니다.
+/// * `now` - 미래 시각 확인의 기준 시각입니다.
+///
+/// # Returns
+///
+/// 기록별 처리 결과를 담은 `SyncResponse`와 새 기록이 추가된 유저 ID 목록을 반환합니다.
+pub fn merge_events(
+    events: &[SyncEvent],
+    staff_token: &str,
+    closes_at: Option<DateTime<Utc>>,
+    stamp_id_list: &StampIdList,
+    user_list: &UserList,
+    stamp_history: &StampHistory,
+    now: DateTime<Utc>,
+) -> (SyncResponse, BTreeSet<String>) {
+    let mut response = SyncResponse::default();
+    let mut updated_users = BTreeSet::new();
+
+    for event in events {
+        let result = match validate(event, staff_token, closes_at, stamp_id_list, user_list, now) {
+            Err(reason) => SyncResult {
+                status: SyncStatus::Rejected,
+                reason: Some(reason),
+                seq: None,
+            },
+            Ok(entry) => {
+                let stamp = &stamp_id_list.stamp_id_list[&event.stamp_id];
+                stamp_history.insert_stamp(&event.stamp_id);
+                stamp_history
+                    .update(&event.stamp_id, |entries| {
+                        if let Some(existing) = entries.iter().find(|existing| {
+                            existing.user_id == entry.user_id
+                                && existing.timestamp == entry.timestamp
+                        }) {
+                            SyncResult {
+                                status: SyncStatus::Duplicate,
+                                reason: None,
+                                seq: Some(existing.seq),
+                            }
+                        } else if stamp.is_sold_out(entries.len()) {
+                            SyncResult {
+                                status: SyncStatus::Rejected,
+                                reason: Some("Stamp is sold out".to_string()),
+                                seq: None,
+                            }
+                        } else {
+                            // 온라인으로 찍힌 기록 사이에 시각 순서대로 끼워 넣음 (순번은 앞 기록보다 클 수 있음)
+                            let at = parse_timestamp(&entry.timestamp);
+                            let index = entries.partition_point(|existing| {
+                                parse_timestamp(&existing.timestamp) <= at
+                            });
+                            updated_users.insert(entry.user_id.clone());
+                            let seq = stamp_history.next_seq();
+                            entries.insert(index, StampUserInfo { seq, ..entry });
+                            SyncResult {
+                                status: SyncStatus::Accepted,
+                                reason: None,
+                                seq: Some(seq),
+                            }
+                        }
+                    })
+                    .unwrap_or_else(|| SyncResult {
+                        status: SyncStatus::Rejected,
+                        reason: Some("Stamp not found".to_string()),
+                        seq: None,
+                    })
+            }
+        };
+
+        match result.status {
+            SyncStatus::Accepted => response.accepted += 1,
+            SyncStatus::Duplicate => response.duplicates += 1,
+            SyncStatus::Rejected => response.rejected += 1,
+        }
+        response.results.push(result);
+    }
+    (response, updated_users)
+}
+
+/// 연결이 끊긴 부스의 직원 기기가 쌓아둔 스템프 기록을 한 번에 반영하는 비동기 함수입니다.
+/// 기록마다 부스별 접근 키로 만든 서명이 필요하며, 이미 반영된 기록은 다시 보내도 한 번만 반영됩니다.
+///
+/// # Returns
+///
+/// 기록별 처리 결과를 담은 `SyncResponse`가 200 OK 응답으로 반환됩니다.
+/// `staff_token`이 설정되지 않았으면 503 Service Unavailable 응답이,
+/// 기록이 `MAX_SYNC_EVENTS`개보다 많으면 400 Bad Request 응답이 반환됩니다.
+#[allow(clippy::too_many_arguments)]
+pub async fn handle_sync(
+    request: Json<SyncRequest>,
+    user_list: Data<Mutex<UserList>>,
+    stamp_id_list: Data<Reloadable<StampIdList>>,
+    stamp_history: Data<StampHistory>,
+    completions: Data<Mutex<CompletionList>>,
+    teams: Data<Mutex<TeamList>>,
+    notifier: Data<Notifier>,
+    config: Data<Reloadable<Config>>,
+    persister: Data<Persister>,
+) -> Result<HttpResponse, AppError> {
+    let config = config.get();
+    let stamp_id_list = stamp_id_list.get();
+    let Some(staff_token) = config.staff_token.as_deref() else {
+        warn!("Offline sync was requested but staff_token is not configured.");
+        return Err(AppError::Disabled(
+            "staff_token is not configured".to_string(),
+        ));
+    };
+    if request.events.len() > MAX_SYNC_EVENTS {
+        return Err(AppError::Validation(format!(
+            "At most {} events can be synced at once",
+            MAX_SYNC_EVENTS
+        )));
+    }
+    let closes_at = config
+        .closes_at
+        .as_deref()
+        .and_then(|closes_at| DateTime::parse_from_rfc3339(closes_at).ok())
+        .map(|closes_at| closes_at.with_timezone(&Utc));
+
+    let (response, updated_users) = merge_events(
+        &request.events,
+        staff_token,
+        closes_at,
+        &stamp_id_list,
+        &user_list.lock_or_recover(),
+        &stamp_history,
+        Utc::now(),
+    );
+    info!(
+        "Offline sync: {} accepted, {} duplicates, {} rejected.",
+        response.accepted, response.duplicates, response.rejected
+    );
+
+    // 새로 반영된 기록으로 달성한 완주 등급 기록
+    let milestones: Vec<Event> = {
+        let mut completions = completions.lock_or_recover();
+        let mut teams = teams.lock_or_recover();
+        updated_users
+            .iter()
+            .flat_map(|user_id| {
+                record_progress(
+                    &config,
+                    &stamp_id_list,
+                    &stamp_history,
+                    &mut completions,
+                    &mut teams,
+                    &notifier,
+                    user_id,
+                )
+            })
+            .collect()
+    };
+    for event in milestones {
+        notifier.notify(event);
+    }
+    if response.accepted > 0 {
+        persister.mark(&[
+            Dataset::StampStatus,
+            Dataset::CompletionStatus,
+            Dataset::TeamStatus,
+        ]);
+    }
+
+    Ok(HttpResponse::Ok().json(response))
+}
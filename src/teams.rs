@@ -0,0 +1,238 @@
+use actix_web::{
+    routes,
+    web::{Data, Path, Query},
+    HttpRequest, HttpResponse,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeSet, sync::Mutex};
+
+use crate::config::Config;
+use crate::error::AppError;
+use crate::features::{require_feature, Feature};
+use crate::handlers::LeaderboardQuery;
+use crate::lock::MutexExt;
+use crate::state::{
+    generate_code, CompletionRecord, Reloadable, StampHistory, StampIdList, Team, TeamList,
+};
+
+/// `/api/v1/teams/{team_code}`로 반환되는 팀 진행 상황입니다.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TeamProgress {
+    pub team_code: String,
+    pub team_name: String,
+    pub member_count: usize,
+    /// 팀원 중 누구라도 모은 스템프 ID 목록입니다.
+    pub collected: Vec<String>,
+    pub collected_count: usize,
+    pub total_count: usize,
+    pub points: u32,
+    /// 현재 달성한 가장 높은 팀 완주 등급입니다.
+    pub tier: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TeamLeaderboardEntry {
+    pub rank: usize,
+    pub team_name: String,
+    pub member_count: usize,
+    pub points: u32,
+    pub collected_count: usize,
+}
+
+/// 새로운 팀을 만들고 중복되지 않는 팀 코드를 반환하는 함수입니다.
+pub fn create_team(teams: &mut TeamList, team_name: &str) -> String {
+    let mut team_code = generate_code(6);
+    while teams.teams.contains_key(&team_code) {
+        team_code = generate_code(6);
+    }
+
+    teams.teams.insert(
+        team_code.clone(),
+        Team {
+            team_name: team_name.to_string(),
+            ..Default::default()
+        },
+    );
+    team_code
+}
+
+/// 팀원 모두가 모은 스템프 ID의 합집합을 반환합니다. 현재 스템프 목록에 없는 스템프와 숨은 스템프는 제외합니다.
+pub fn team_collected(
+    stamp_id_list: &StampIdList,
+    stamp_history: &StampHistory,
+    team: &Team,
+) -> BTreeSet<String> {
+    stamp_history
+        .to_map()
+        .iter()
+        .filter(|(stamp_id, _)| stamp_id_list.stamp_id_list.contains_key(*stamp_id))
+        .filter(|(stamp_id, _)| !stamp_id_list.is_hidden(stamp_id))
+        .filter(|(_, entries)| {
+            entries
+                .iter()
+                .any(|entry| team.members.contains(&entry.user_id))
+        })
+        .map(|(stamp_id, _)| stamp_id.clone())
+        .collect()
+}
+
+/// 팀의 진행 상황과 팀 완주 등급을 계산하는 함수입니다.
+///
+/// # Arguments
+///
+/// * `config` - 팀 완주 등급이 정의된 행사 설정입니다.
+/// * `stamp_id_list` - 전체 스템프 목록입니다.
+/// * `stamp_history` - 스템프 기록입니다.
+/// * `team_code` - 팀 코드입니다.
+/// * `team` - 진행 상황을 계산할 팀입니다.
+///
+/// # Returns
+///
+/// 팀원들이 모은 스템프의 합집합과 점수, 현재 등급을 담은 `TeamProgress`가 반환됩니다.
+pub fn team_progress(
+    config: &Config,
+    stamp_id_list: &StampIdList,
+    stamp_history: &StampHistory,
+    team_code: &str,
+    team: &Team,
+) -> TeamProgress {
+    let collected = team_collected(stamp_id_list, stamp_history, team);
+    let total_count = stamp_id_list.visible_count();
+    let tier = config
+        .team_tiers(total_count)
+        .into_iter()
+        .filter(|(_, required)| collected.len() >= *required)
+        .map(|(tier_name, _)| tier_name)
+        .next_back();
+
+    TeamProgress {
+        team_code: team_code.to_string(),
+        team_name: team.team_name.clone(),
+        member_count: team.members.len(),
+        collected_count: collected.len(),
+        points: stamp_id_list.points(&collected),
+        collected: collected.into_iter().collect(),
+        total_count,
+        tier,
+    }
+}
+
+/// 팀원이 스템프를 찍었을 때 새로 달성한 팀 완주 등급을 팀 기록에 추가하는 함수입니다.
+///
+/// # Returns
+///
+/// 이번에 새로 달성한 팀 등급 이름 목록을 반환합니다.
+pub fn record_team_completions(
+    config: &Config,
+    stamp_id_list: &StampIdList,
+    stamp_history: &StampHistory,
+    team: &mut Team,
+) -> Vec<String> {
+    let collected = team_collected(stamp_id_list, stamp_history, team).len();
+    let timestamp = chrono::prelude::Utc::now().to_string();
+
+    let mut reached = Vec::new();
+    for (tier_name, required) in config.team_tiers(stamp_id_list.visible_count()) {
+        if collected >= required
+            && !team
+                .completions
+                .iter()
+                .any(|record| record.tier_name == tier_name)
+        {
+            team.completions.push(CompletionRecord {
+                tier_name: tier_name.clone(),
+                timestamp: timestamp.clone(),
+            });
+            reached.push(tier_name);
+        }
+    }
+    reached
+}
+
+/// 팀 진행 상황을 JSON으로 반환하는 비동기 함수입니다.
+///
+/// # Returns
+///
+/// 팀이 존재하면 `TeamProgress`를 담은 200 OK 응답이, 존재하지 않으면 404 Not Found 응답이 반환됩니다.
+#[routes]
+#[get("/api/v1/teams/{team_code}")]
+#[get("/api/teams/{team_code}")]
+pub async fn handle_team_progress(
+    team_code: Path<String>,
+    teams: Data<Mutex<TeamList>>,
+    stamp_id_list: Data<Reloadable<StampIdList>>,
+    stamp_history: Data<StampHistory>,
+    config: Data<Reloadable<Config>>,
+) -> Result<HttpResponse, AppError> {
+    let config = config.get();
+    let stamp_id_list = stamp_id_list.get();
+    let team_code = team_code.into_inner().to_uppercase();
+    let Some(team) = teams.lock_or_recover().teams.get(&team_code).cloned() else {
+        return Err(AppError::Missing("Team not found".to_string()));
+    };
+
+    Ok(HttpResponse::Ok().json(team_progress(
+        &config,
+        &stamp_id_list,
+        &stamp_history,
+        &team_code,
+        &team,
+    )))
+}
+
+/// 팀 점수 순위표를 JSON으로 반환하는 비동기 함수입니다. 팀 코드는 공개하지 않습니다.
+///
+/// # Arguments
+///
+/// * `query` - 반환할 최대 팀 수(`limit`, 기본 10팀, 최대 100팀)를 담은 쿼리입니다.
+///
+/// # Returns
+///
+/// 순위표 기능(`features.leaderboard`)이 꺼져 있으면 503 응답이 반환됩니다.
+#[routes]
+#[get("/api/v1/teams/leaderboard")]
+#[get("/api/teams/leaderboard")]
+pub async fn handle_team_leaderboard(
+    query: Query<LeaderboardQuery>,
+    teams: Data<Mutex<TeamList>>,
+    stamp_id_list: Data<Reloadable<StampIdList>>,
+    stamp_history: Data<StampHistory>,
+    config: Data<Reloadable<Config>>,
+    req: HttpRequest,
+) -> Result<HttpResponse, AppError> {
+    require_feature(&req, Feature::Leaderboard)?;
+    let config = config.get();
+    let stamp_id_list = stamp_id_list.get();
+    let limit = query.limit.unwrap_or(10).min(100);
+    let teams = teams.lock_or_recover().clone();
+
+    let mut scores: Vec<TeamProgress> = teams
+        .teams
+        .iter()
+        .map(|(team_code, team)| {
+            team_progress(&config, &stamp_id_list, &stamp_history, team_code, team)
+        })
+        .collect();
+    scores.sort_by_key(|team| std::cmp::Reverse((team.points, team.collected_count)));
+
+    let mut entries: Vec<TeamLeaderboardEntry> = Vec::new();
+    for (index, team) in scores.into_iter().take(limit).enumerate() {
+        let rank = match entries.last() {
+            Some(last)
+                if (last.points, last.collected_count) == (team.points, team.collected_count) =>
+            {
+                last.rank
+            }
+            _ => index + 1,
+        };
+        entries.push(TeamLeaderboardEntry {
+            rank,
+            team_name: team.team_name,
+            member_count: team.member_count,
+            points: team.points,
+            collected_count: team.collected_count,
+        });
+    }
+
+    Ok(HttpResponse::Ok().json(entries))
+}
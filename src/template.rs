@@ -0,0 +1,42 @@
+/// 템플릿 안의 `%KEY%` 형태의 자리표시자를 주어진 값으로 치환하는 함수입니다.
+///
+/// # Arguments
+///
+/// * `source` - 치환할 템플릿 문자열입니다.
+/// * `vars` - `(KEY, 값)` 쌍의 목록입니다. `KEY`는 `%` 없이 전달합니다.
+///
+/// # Returns
+///
+/// 모든 자리표시자가 치환된 문자열을 반환합니다. 목록에 없는 자리표시자는 그대로 남습니다.
+///
+/// # Example
+///
+/// ```rust
+/// use gj_stamptour::template::render;
+///
+/// let html = render("<h1>%STAMP_NAME%</h1>", &[("STAMP_NAME", "과학관")]);
+/// assert_eq!(html, "<h1>과학관</h1>");
+/// ```
+pub fn render(source: &str, vars: &[(&str, &str)]) -> String {
+    vars.iter().fold(source.to_string(), |html, (key, value)| {
+        html.replace(&format!("%{}%", key), value)
+    })
+}
+
+/// 사용자가 입력한 문자열을 HTML에 안전하게 삽입할 수 있도록 이스케이프합니다.
+///
+/// # Example
+///
+/// ```rust
+/// use gj_stamptour::template::escape_html;
+///
+/// assert_eq!(escape_html("<b>\"A&B\"</b>"), "&lt;b&gt;&quot;A&amp;B&quot;&lt;/b&gt;");
+/// ```
+pub fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
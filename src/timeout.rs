@@ -0,0 +1,47 @@
+use actix_rt::time::timeout;
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    middleware::Next,
+    web::Data,
+};
+use log::warn;
+
+use crate::config::Config;
+use crate::error::AppError;
+use crate::state::Reloadable;
+
+/// 경로별 처리 제한 시간(`server.api_timeout_secs` 등)이 지나도록 끝나지 않은 요청을 멈추고
+/// `error503.html`을 담은 503 응답을 보내는 미들웨어입니다.
+/// 디스크 읽기 하나가 멈추더라도 작업자에 요청이 쌓이지 않도록 합니다.
+///
+/// 라우팅 전에는 `HttpRequest`를 복제할 수 없으므로 503 응답은 오류로 반환하며, actix가 그 응답을 그대로 보냅니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// App::new()
+///     .wrap(actix_web::middleware::from_fn(apply_timeout))
+///     .configure(|cfg| state.register(cfg))
+///     .configure(routes);
+/// ```
+pub async fn apply_timeout(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let limit = req
+        .app_data::<Data<Reloadable<Config>>>()
+        .and_then(|config| config.get().server.request_timeout(req.path()));
+    let Some(limit) = limit else {
+        return next.call(req).await;
+    };
+
+    let request_line = format!("{} {}", req.method(), req.path());
+    match timeout(limit, next.call(req)).await {
+        Ok(res) => res,
+        Err(_) => {
+            warn!("{} timed out after {}s", request_line, limit.as_secs());
+            Err(AppError::Unavailable.into())
+        }
+    }
+}
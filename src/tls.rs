@@ -0,0 +1,118 @@
+use rustls::{
+    crypto::ring,
+    server::{ClientHello, ResolvesServerCert},
+    sign::CertifiedKey,
+    ServerConfig,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    sync::{Arc, RwLock},
+};
+
+use crate::acme::AcmeConfig;
+use crate::lock::RwLockExt;
+
+/// 공개 서버가 ALPN으로 협상하는 프로토콜입니다. 브라우저가 HTTP/2를 지원하면 `h2`를 고릅니다.
+pub const ALPN_PROTOCOLS: [&[u8]; 2] = [b"h2", b"http/1.1"];
+
+/// `resources/config.json`의 `tls` 항목입니다. 설정하면 공개 서버가 TLS를 직접 처리하고,
+/// ALPN으로 HTTP/2를 협상하여 스템프 페이지의 작은 정적 파일 요청들을 연결 하나로 처리합니다.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct TlsConfig {
+    /// HTTPS 요청을 받을 포트입니다. HTTP 포트(`-p`)도 그대로 열립니다.
+    pub port: u16,
+    /// PEM 형식의 인증서 체인 파일입니다.
+    pub cert_file: String,
+    /// PEM 형식의 개인 키 파일입니다.
+    pub key_file: String,
+    /// 설정하면 ACME로 인증서를 직접 발급받고 갱신하여 `cert_file`과 `key_file`에 저장합니다.
+    pub acme: Option<AcmeConfig>,
+}
+
+impl Default for TlsConfig {
+    fn default() -> Self {
+        TlsConfig {
+            port: 443,
+            cert_file: "resources/tls/cert.pem".to_string(),
+            key_file: "resources/tls/key.pem".to_string(),
+            acme: None,
+        }
+    }
+}
+
+/// TLS 연결마다 현재 인증서를 돌려주는 `rustls` 인증서 선택기입니다.
+/// 서버를 다시 시작하지 않고 `set`으로 새 인증서로 바꿀 수 있으며, 바꾼 뒤의 연결부터 새 인증서를 사용합니다.
+#[derive(Debug, Default)]
+pub struct CertResolver {
+    current: RwLock<Option<Arc<CertifiedKey>>>,
+}
+
+impl CertResolver {
+    /// 이후의 TLS 연결에 사용할 인증서를 바꿉니다.
+    pub fn set(&self, certified_key: CertifiedKey) {
+        *self.current.write_or_recover() = Some(Arc::new(certified_key));
+    }
+
+    /// 현재 인증서를 반환합니다. 아직 인증서가 없으면 `None`을 반환합니다.
+    pub fn current(&self) -> Option<Arc<CertifiedKey>> {
+        self.current.read_or_recover().clone()
+    }
+}
+
+impl ResolvesServerCert for CertResolver {
+    fn resolve(&self, _client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        self.current()
+    }
+}
+
+/// PEM 형식의 인증서 체인과 개인 키로 `rustls` 인증서를 만드는 함수입니다.
+///
+/// # Returns
+///
+/// 인증서나 개인 키가 없거나 읽을 수 없으면 오류 메시지를 반환합니다.
+pub fn certified_key(cert_pem: &[u8], key_pem: &[u8]) -> Result<CertifiedKey, String> {
+    let certs = rustls_pemfile::certs(&mut &cert_pem[..])
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    if certs.is_empty() {
+        return Err("No certificate found".to_string());
+    }
+    let key = rustls_pemfile::private_key(&mut &key_pem[..])
+        .map_err(|e| e.to_string())?
+        .ok_or("No private key found")?;
+    let signing_key = ring::sign::any_supported_type(&key).map_err(|e| e.to_string())?;
+    Ok(CertifiedKey::new(certs, signing_key))
+}
+
+/// 설정의 인증서와 개인 키 파일을 읽어 `rustls` 인증서를 만듭니다.
+pub fn load_certified_key(config: &TlsConfig) -> Result<CertifiedKey, String> {
+    let cert_pem =
+        fs::read(&config.cert_file).map_err(|e| format!("{}: {}", config.cert_file, e))?;
+    let key_pem = fs::read(&config.key_file).map_err(|e| format!("{}: {}", config.key_file, e))?;
+    certified_key(&cert_pem, &key_pem)
+}
+
+/// 공개 서버의 `rustls` 설정을 만드는 함수입니다. 인증서는 `resolver`에서 연결마다 가져오며,
+/// ALPN으로 `h2`와 `http/1.1`을 협상합니다.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let resolver = Arc::new(CertResolver::default());
+/// resolver.set(load_certified_key(&tls)?);
+/// HttpServer::new(...).bind_rustls_0_23(("0.0.0.0", tls.port), rustls_config(resolver))?
+/// ```
+pub fn rustls_config(resolver: Arc<CertResolver>) -> ServerConfig {
+    let mut config = ServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .expect("ring provider supports the default TLS versions")
+        .with_no_client_auth()
+        .with_cert_resolver(resolver);
+    config.alpn_protocols = ALPN_PROTOCOLS
+        .iter()
+        .map(|protocol| protocol.to_vec())
+        .collect();
+    config
+}
@@ -0,0 +1,35 @@
+use actix_web::{HttpRequest, HttpResponse};
+use chrono::{DateTime, Utc};
+
+use crate::announcement::inject;
+use crate::config::Config;
+use crate::error::AppError;
+use crate::state::TourStatus;
+use crate::storage::html_template;
+
+/// 투어가 종료되어 로그인과 스템프 찍기를 받지 않는 상태인지 확인하는 함수입니다.
+///
+/// # Arguments
+///
+/// * `config` - 예약 종료 시각(`closes_at`)이 정의된 행사 설정입니다.
+/// * `status` - 관리자가 설정한 투어 운영 상태입니다.
+/// * `now` - 예약 종료 시각과 비교할 현재 시각입니다.
+///
+/// # Returns
+///
+/// 관리자가 운영 상태를 고정했다면 그 값을, 아니면 예약 종료 시각이 지났는지를 반환합니다.
+pub fn is_closed(config: &Config, status: &TourStatus, now: DateTime<Utc>) -> bool {
+    status.closed.unwrap_or_else(|| {
+        config
+            .closes_at
+            .as_deref()
+            .and_then(|closes_at| DateTime::parse_from_rfc3339(closes_at).ok())
+            .is_some_and(|closes_at| now >= closes_at)
+    })
+}
+
+/// 투어 종료 페이지를 반환하는 비동기 함수입니다. 'ended.html' 파일을 읽어 403 Forbidden 응답으로 반환합니다.
+pub async fn handle_tour_ended(req: &HttpRequest) -> Result<HttpResponse, AppError> {
+    let template = html_template("ended.html").await?;
+    Ok(HttpResponse::Forbidden().body(inject(req, template)))
+}
@@ -0,0 +1,148 @@
+use chrono::Utc;
+
+use crate::report::parse_timestamp;
+use crate::retention::{restore_user, PersonalData};
+use crate::state::{
+    AdminHistory, AdminOperation, AuditRecord, CompletionList, StampHistory, StampUserInfo,
+    UndoAction,
+};
+
+/// 되돌릴 수 있도록 기억하는 최근 관리자 작업 수입니다. 넘으면 오래된 작업부터 잊습니다.
+pub const MAX_UNDO: usize = 20;
+
+impl AdminHistory {
+    /// 되돌릴 수 있는 관리자 작업을 기록합니다.
+    pub fn push(&mut self, command: &str, undo: UndoAction) {
+        self.entries.push(AdminOperation {
+            command: command.to_string(),
+            timestamp: Utc::now().to_string(),
+            undo,
+        });
+        if self.entries.len() > MAX_UNDO {
+            let excess = self.entries.len() - MAX_UNDO;
+            self.entries.drain(..excess);
+        }
+    }
+}
+
+/// 유저의 진행 상황을 초기화하기 전에 되돌리는 데 필요한 값을 모으는 함수입니다.
+pub fn capture_progress(
+    stamp_history: &StampHistory,
+    completions: &CompletionList,
+    user_id: &str,
+) -> UndoAction {
+    let stamps = stamp_history
+        .to_map()
+        .into_iter()
+        .flat_map(|(stamp_id, entries)| {
+            entries
+                .into_iter()
+                .filter(|entry| entry.user_id == user_id)
+                .map(move |entry| (stamp_id.clone(), entry))
+        })
+        .collect();
+    UndoAction::ResetProgress {
+        user_id: user_id.to_string(),
+        stamps,
+        completions: completions
+            .completions
+            .get(user_id)
+            .cloned()
+            .unwrap_or_default(),
+        achievements: completions
+            .achievements
+            .get(user_id)
+            .cloned()
+            .unwrap_or_default(),
+        completion_code: completions.codes.get(user_id).cloned(),
+    }
+}
+
+/// 스템프 기록을 다시 넣고 찍은 시각 순서로 정렬합니다.
+fn restore_entries(stamp_history: &StampHistory, stamps: Vec<(String, StampUserInfo)>) {
+    for (stamp_id, entry) in stamps {
+        stamp_history.push(&stamp_id, entry);
+        stamp_history.update(&stamp_id, |entries| {
+            entries.sort_by_key(|entry| parse_timestamp(&entry.timestamp))
+        });
+    }
+}
+
+/// 마지막으로 기록한 관리자 작업을 되돌리는 함수입니다.
+///
+/// # Arguments
+///
+/// * `history` - 되돌릴 수 있는 관리자 작업 목록입니다.
+/// * `data` - 잠근 참가자 데이터 묶음입니다.
+/// * `actor` - 감사 기록에 남길 작업자 이름입니다.
+///
+/// # Returns
+///
+/// 되돌린 작업의 명령과 유저 ID를 반환합니다. 되돌릴 작업이 없거나 이미 영구 삭제되어 되돌릴 수 없으면 오류 메시지를 반환합니다.
+/// 되돌리지 못한 작업도 목록에서 빠집니다.
+pub fn undo_last(
+    history: &mut AdminHistory,
+    data: PersonalData,
+    actor: &str,
+) -> Result<(String, String), String> {
+    let operation = history
+        .entries
+        .pop()
+        .ok_or_else(|| "Nothing to undo".to_string())?;
+    let user_id = match operation.undo {
+        UndoAction::DeleteUser { user_id } => {
+            // restore_user가 감사 기록을 남기므로 여기서는 결과만 반환
+            return match restore_user(data, &user_id, actor) {
+                Some(user_id) => Ok((operation.command, user_id)),
+                None => Err(format!(
+                    "Cannot undo `{}`: user {} is no longer deleted",
+                    operation.command, user_id
+                )),
+            };
+        }
+        UndoAction::RevokeStamp { stamp_id, entry } => {
+            let user_id = entry.user_id.clone();
+            restore_entries(data.stamp_history, vec![(stamp_id, entry)]);
+            user_id
+        }
+        UndoAction::ResetProgress {
+            user_id,
+            stamps,
+            completions,
+            achievements,
+            completion_code,
+        } => {
+            restore_entries(data.stamp_history, stamps);
+            // 초기화한 뒤 다시 달성한 기록이 있으면 그대로 둠
+            if !completions.is_empty() {
+                data.completions
+                    .completions
+                    .entry(user_id.clone())
+                    .or_insert(completions);
+            }
+            if !achievements.is_empty() {
+                data.completions
+                    .achievements
+                    .entry(user_id.clone())
+                    .or_insert(achievements);
+            }
+            if let Some(completion_code) = completion_code {
+                data.completions
+                    .codes
+                    .entry(user_id.clone())
+                    .or_insert(completion_code);
+            }
+            user_id
+        }
+    };
+
+    data.audit_log.entries.push(AuditRecord {
+        action: "undo".to_string(),
+        user_id: user_id.clone(),
+        stamp_id: None,
+        actor: actor.to_string(),
+        reason: operation.command.clone(),
+        timestamp: Utc::now().to_string(),
+    });
+    Ok((operation.command, user_id))
+}
@@ -0,0 +1,148 @@
+use actix_web::{get, web::Data, HttpRequest, HttpResponse};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use svg::node::element::tag::Type;
+use svg::node::Value;
+use svg::parser::Event;
+
+use crate::bundle::event_path;
+use crate::config::Config;
+use crate::error::AppError;
+use crate::session::CurrentUser;
+use crate::state::{Reloadable, StampHistory};
+
+/// 지도에서 부스 표시 요소에 스템프 ID를 적는 속성입니다. 예: `<circle data-stamp-id="booth-a" .../>`
+pub const STAMP_ATTRIBUTE: &str = "data-stamp-id";
+
+/// `resources/config.json`의 `map` 항목입니다. `/map.svg`에서 부스 표시 요소를 칠하는 색입니다.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct MapConfig {
+    /// 방문한 부스의 색입니다.
+    pub visited_color: String,
+    /// 아직 방문하지 않은 부스의 색입니다.
+    pub unvisited_color: String,
+}
+
+impl Default for MapConfig {
+    fn default() -> Self {
+        MapConfig {
+            visited_color: "#16a34a".to_string(),
+            unvisited_color: "#9ca3af".to_string(),
+        }
+    }
+}
+
+/// 태그 하나를 SVG 문자열로 씁니다. 속성 값은 원본 파일에 적힌 그대로 씁니다.
+fn write_tag(output: &mut String, name: &str, kind: Type, attributes: &HashMap<String, Value>) {
+    if let Type::End = kind {
+        output.push_str(&format!("</{}>", name));
+        return;
+    }
+    output.push('<');
+    output.push_str(name);
+    let mut attributes: Vec<_> = attributes.iter().collect();
+    attributes.sort_by_key(|(name, _)| name.as_str());
+    for (name, value) in attributes {
+        match value.contains('"') {
+            true => output.push_str(&format!(" {}='{}'", name, value)),
+            false => output.push_str(&format!(" {}=\"{}\"", name, value)),
+        }
+    }
+    output.push_str(match kind {
+        Type::Empty => "/>",
+        _ => ">",
+    });
+}
+
+/// 기본 지도 SVG의 부스 표시 요소를 방문 여부에 따라 다시 칠하는 함수입니다.
+///
+/// `data-stamp-id` 속성이 있는 요소의 `fill`을 바꾸고 `class`에 `visited` 또는 `unvisited`를 더합니다.
+/// 그 외의 요소는 그대로 둡니다.
+///
+/// # Arguments
+///
+/// * `source` - 기본 지도 SVG 내용입니다.
+/// * `collected` - 유저가 모은 스템프 ID 목록입니다.
+/// * `config` - 칠할 색 설정입니다.
+///
+/// # Returns
+///
+/// 다시 칠한 SVG를 반환합니다. SVG를 해석하지 못하면 오류 메시지를 반환합니다.
+///
+/// # Example
+///
+/// ```rust
+/// use gj_stamptour::venue_map::{highlight_map, MapConfig};
+/// use std::collections::BTreeSet;
+///
+/// let source = r#"<svg><circle data-stamp-id="a" fill="red"/><circle data-stamp-id="b"/></svg>"#;
+/// let collected = BTreeSet::from(["a".to_string()]);
+/// let map = highlight_map(source, &collected, &MapConfig::default()).unwrap();
+/// assert!(map.contains(r##"<circle class="visited" data-stamp-id="a" fill="#16a34a"/>"##));
+/// assert!(map.contains(r##"<circle class="unvisited" data-stamp-id="b" fill="#9ca3af"/>"##));
+/// ```
+pub fn highlight_map(
+    source: &str,
+    collected: &BTreeSet<String>,
+    config: &MapConfig,
+) -> Result<String, String> {
+    let mut output = String::with_capacity(source.len());
+    for event in svg::read(source).map_err(|e| e.to_string())? {
+        match event {
+            Event::Tag(name, kind, mut attributes) => {
+                let stamp_id = attributes.get(STAMP_ATTRIBUTE).map(|id| id.to_string());
+                if let Some(stamp_id) = stamp_id {
+                    let (class, color) = match collected.contains(&stamp_id) {
+                        true => ("visited", &config.visited_color),
+                        false => ("unvisited", &config.unvisited_color),
+                    };
+                    let class = match attributes.get("class") {
+                        Some(existing) => format!("{} {}", existing, class),
+                        None => class.to_string(),
+                    };
+                    attributes.insert("class".to_string(), class.into());
+                    attributes.insert("fill".to_string(), color.clone().into());
+                }
+                write_tag(&mut output, name, kind, &attributes);
+            }
+            Event::Text(text) => output.push_str(text),
+            Event::Comment(markup) | Event::Declaration(markup) | Event::Instruction(markup) => {
+                output.push_str(markup)
+            }
+            Event::Error(e) => return Err(e.to_string()),
+        }
+    }
+    Ok(output)
+}
+
+/// 행사장 지도에 방문한 부스를 표시하여 반환하는 비동기 함수입니다. `resources/map.svg`를 기본 지도로 사용합니다.
+/// 로그인하지 않은 요청에는 모든 부스를 방문하지 않은 색으로 칠한 지도를 반환합니다.
+///
+/// # Returns
+///
+/// 다시 칠한 지도가 200 OK 응답으로 반환됩니다. 기본 지도가 없거나 해석하지 못하면 404 Not Found 응답이 반환됩니다.
+#[get("/map.svg")]
+pub async fn handle_map(
+    req: HttpRequest,
+    stamp_history: Data<StampHistory>,
+    config: Data<Reloadable<Config>>,
+) -> Result<HttpResponse, AppError> {
+    let source = async_std::fs::read_to_string(event_path("map.svg"))
+        .await
+        .map_err(|_| AppError::NotFound)?;
+    let collected = CurrentUser::from_cookie(&req)
+        .map(|user| stamp_history.collected_by(&user.user_id))
+        .unwrap_or_default();
+
+    let map = highlight_map(&source, &collected, &config.get().map).map_err(|e| {
+        log::error!("map.svg is invalid : {}", e);
+        AppError::NotFound
+    })?;
+    // 유저마다 다른 지도이므로 이미지 캐시 정책 대신 매번 확인하도록 지정
+    Ok(HttpResponse::Ok()
+        .content_type("image/svg+xml")
+        .insert_header(("Cache-Control", "private, no-cache"))
+        .insert_header(("Vary", "Cookie"))
+        .body(map))
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use actix_web::{middleware::from_fn, test, App};
+use gj_stamptour::{
+    access_log::{client_ip, log_access},
+    config::Config,
+    handlers::routes,
+    state::AppState,
+};
+use serde_json::json;
+use std::{fs, net::IpAddr};
+
+#[actix_web::test]
+async fn client_ip_trusts_only_configured_proxies() {
+    let proxy: IpAddr = "10.0.0.2".parse().unwrap();
+    let req = test::TestRequest::get()
+        .peer_addr("10.0.0.2:5000".parse().unwrap())
+        .insert_header(("X-Forwarded-For", "198.51.100.1, 203.0.113.7, 10.0.0.2"))
+        .to_http_request();
+    assert_eq!(client_ip(&req, &[proxy]), "203.0.113.7");
+    // 프록시를 설정하지 않으면 방문객이 보낸 헤더는 무시
+    assert_eq!(client_ip(&req, &[]), "10.0.0.2");
+
+    let req = test::TestRequest::get()
+        .peer_addr("203.0.113.9:5000".parse().unwrap())
+        .insert_header(("X-Forwarded-For", "1.2.3.4"))
+        .to_http_request();
+    assert_eq!(client_ip(&req, &[proxy]), "203.0.113.9");
+}
+
+#[actix_web::test]
+async fn requests_are_written_in_combined_format() {
+    let dir = common::setup();
+    let path = dir.join("logs/access.log");
+    let config: Config =
+        serde_json::from_value(json!({ "log": { "access_file": path.display().to_string() } }))
+            .unwrap();
+    let state = AppState::new(config, common::stamp_list(vec![common::stamp("a")]));
+    let app = test::init_service(
+        App::new()
+            .wrap(from_fn(log_access))
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    test::call_service(
+        &app,
+        test::TestRequest::get()
+            .uri("/api/v1/stats/public?x=1")
+            .peer_addr("203.0.113.7:5000".parse().unwrap())
+            .insert_header(("User-Agent", "Mozilla/5.0 \"test\""))
+            .to_request(),
+    )
+    .await;
+
+    let contents = fs::read_to_string(&path).unwrap();
+    let line = contents.lines().last().unwrap();
+    assert!(line.starts_with("203.0.113.7 - - ["));
+    assert!(line.contains("] \"GET /api/v1/stats/public?x=1 HTTP/1.1\" 200 "));
+    assert!(line.ends_with(" \"-\" \"Mozilla/5.0 \\\"test\\\"\""));
+}
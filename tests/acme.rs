@@ -0,0 +1,407 @@
+mod common;
+
+use actix_web::{
+    http::StatusCode,
+    test,
+    web::{self, Bytes, Data},
+    App, HttpResponse, HttpServer,
+};
+use gj_stamptour::{
+    acme::{needs_renewal, renew, AcmeConfig, CHALLENGE_FOLDER},
+    crypto::{decode_base64_url, encode_base64_url},
+    handlers::routes,
+    storage::resource_path,
+    tls::{certified_key, rustls_config, CertResolver, TlsConfig},
+};
+use openssl::{
+    asn1::Asn1Time,
+    bn::BigNum,
+    ec::{EcGroup, EcKey},
+    ecdsa::EcdsaSig,
+    hash::MessageDigest,
+    nid::Nid,
+    pkey::{PKey, Private},
+    sha::sha256,
+    x509::{X509Builder, X509Req, X509},
+};
+use serde_json::{json, Value};
+use std::{
+    fs,
+    sync::{Arc, Mutex},
+};
+
+#[actix_web::test]
+async fn acme_challenge_tokens_are_served() {
+    let state = common::test_state();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    let token = format!("token_{}-abc", std::process::id());
+    let token_path = resource_path(CHALLENGE_FOLDER, &token);
+    fs::create_dir_all(token_path.parent().unwrap()).unwrap();
+    fs::write(&token_path, format!("{}.thumbprint", token)).unwrap();
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/.well-known/acme-challenge/{}", token))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(test::read_body(resp).await, format!("{}.thumbprint", token));
+    fs::remove_file(&token_path).unwrap();
+
+    for token in ["missing", "..%2F..%2Fdatabase"] {
+        let req = test::TestRequest::get()
+            .uri(&format!("/.well-known/acme-challenge/{}", token))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND, "{}", token);
+    }
+}
+
+/// 테스트용 ACME 서버의 상태입니다. 인증서는 `ca` 키로 서명합니다.
+struct FakeAcme {
+    base: String,
+    app_port: u16,
+    ca: (X509, PKey<Private>),
+    jwk: Option<Value>,
+    nonce: u32,
+    validated: bool,
+    certificate: Option<Vec<u8>>,
+}
+
+type Fake = Data<Mutex<FakeAcme>>;
+
+impl FakeAcme {
+    fn next_nonce(&mut self) -> String {
+        self.nonce += 1;
+        format!("nonce-{}", self.nonce)
+    }
+
+    /// JWS 서명을 계정의 공개 키로 검증하고 payload를 반환합니다.
+    fn verify(&mut self, body: &Bytes, url: &str) -> Value {
+        let jws: Value = serde_json::from_slice(body).unwrap();
+        let protected: Value =
+            serde_json::from_slice(&decode_base64_url(jws["protected"].as_str().unwrap()).unwrap())
+                .unwrap();
+        assert_eq!(protected["alg"], "ES256");
+        assert_eq!(protected["url"], format!("{}{}", self.base, url));
+        assert!(protected["nonce"].as_str().unwrap().starts_with("nonce-"));
+        if self.jwk.is_none() {
+            self.jwk = Some(protected["jwk"].clone());
+        } else {
+            assert_eq!(protected["kid"], format!("{}/account/1", self.base));
+        }
+
+        let jwk = self.jwk.as_ref().unwrap();
+        let coordinate = |name: &str| {
+            BigNum::from_slice(&decode_base64_url(jwk[name].as_str().unwrap()).unwrap()).unwrap()
+        };
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let key =
+            EcKey::from_public_key_affine_coordinates(&group, &coordinate("x"), &coordinate("y"))
+                .unwrap();
+        let signature = decode_base64_url(jws["signature"].as_str().unwrap()).unwrap();
+        let signature = EcdsaSig::from_private_components(
+            BigNum::from_slice(&signature[..32]).unwrap(),
+            BigNum::from_slice(&signature[32..]).unwrap(),
+        )
+        .unwrap();
+        let signed = format!(
+            "{}.{}",
+            jws["protected"].as_str().unwrap(),
+            jws["payload"].as_str().unwrap()
+        );
+        assert!(signature.verify(&sha256(signed.as_bytes()), &key).unwrap());
+
+        match jws["payload"].as_str().unwrap() {
+            "" => Value::Null,
+            payload => serde_json::from_slice(&decode_base64_url(payload).unwrap()).unwrap(),
+        }
+    }
+
+    fn respond(&mut self, status: StatusCode, location: Option<&str>, body: Value) -> HttpResponse {
+        let mut response = HttpResponse::build(status);
+        response.insert_header(("Replay-Nonce", self.next_nonce()));
+        if let Some(location) = location {
+            response.insert_header(("Location", format!("{}{}", self.base, location)));
+        }
+        response.json(body)
+    }
+
+    fn order(&self) -> Value {
+        let mut order = json!({
+            "status": if self.certificate.is_some() { "valid" } else { "pending" },
+            "authorizations": [format!("{}/authz/1", self.base)],
+            "finalize": format!("{}/finalize/1", self.base),
+        });
+        if self.certificate.is_some() {
+            order["certificate"] = json!(format!("{}/cert/1", self.base));
+        }
+        order
+    }
+}
+
+async fn directory(fake: Fake) -> HttpResponse {
+    let base = fake.lock().unwrap().base.clone();
+    HttpResponse::Ok().json(json!({
+        "newNonce": format!("{}/nonce", base),
+        "newAccount": format!("{}/account", base),
+        "newOrder": format!("{}/order", base),
+    }))
+}
+
+async fn new_nonce(fake: Fake) -> HttpResponse {
+    let nonce = fake.lock().unwrap().next_nonce();
+    HttpResponse::Ok()
+        .insert_header(("Replay-Nonce", nonce))
+        .finish()
+}
+
+async fn new_account(fake: Fake, body: Bytes) -> HttpResponse {
+    let mut fake = fake.lock().unwrap();
+    let payload = fake.verify(&body, "/account");
+    assert_eq!(payload["termsOfServiceAgreed"], true);
+    assert_eq!(payload["contact"], json!(["mailto:staff@example.com"]));
+    fake.respond(
+        StatusCode::CREATED,
+        Some("/account/1"),
+        json!({ "status": "valid" }),
+    )
+}
+
+async fn new_order(fake: Fake, body: Bytes) -> HttpResponse {
+    let mut fake = fake.lock().unwrap();
+    let payload = fake.verify(&body, "/order");
+    assert_eq!(
+        payload["identifiers"],
+        json!([{ "type": "dns", "value": "localhost" }])
+    );
+    let order = fake.order();
+    fake.respond(StatusCode::CREATED, Some("/order/1"), order)
+}
+
+async fn authorization(fake: Fake, body: Bytes) -> HttpResponse {
+    let mut fake = fake.lock().unwrap();
+    assert_eq!(fake.verify(&body, "/authz/1"), Value::Null);
+    let authorization = json!({
+        "status": if fake.validated { "valid" } else { "pending" },
+        "identifier": { "type": "dns", "value": "localhost" },
+        "challenges": [
+            { "type": "dns-01", "url": format!("{}/chall/2", fake.base), "token": "dnsToken" },
+            { "type": "http-01", "url": format!("{}/chall/1", fake.base), "token": "httpToken_1" },
+        ],
+    });
+    fake.respond(StatusCode::OK, None, authorization)
+}
+
+/// 실제 ACME 서버처럼 서버의 인증 경로에서 토큰 파일을 가져와 키 인증 값을 확인합니다.
+async fn challenge(fake: Fake, body: Bytes) -> HttpResponse {
+    let (app_port, thumbprint) = {
+        let mut fake = fake.lock().unwrap();
+        assert_eq!(fake.verify(&body, "/chall/1"), json!({}));
+        // RFC 7638의 JWK 지문은 필수 항목을 이름 순으로 적은 JSON의 SHA-256
+        let jwk = fake.jwk.as_ref().unwrap();
+        let canonical = format!(
+            r#"{{"crv":"P-256","kty":"EC","x":"{}","y":"{}"}}"#,
+            jwk["x"].as_str().unwrap(),
+            jwk["y"].as_str().unwrap()
+        );
+        (
+            fake.app_port,
+            encode_base64_url(&sha256(canonical.as_bytes())),
+        )
+    };
+    let url = format!(
+        "http://127.0.0.1:{}/.well-known/acme-challenge/httpToken_1",
+        app_port
+    );
+    let key_authorization = reqwest::get(&url).await.unwrap().text().await.unwrap();
+    assert_eq!(key_authorization, format!("httpToken_1.{}", thumbprint));
+
+    let mut fake = fake.lock().unwrap();
+    fake.validated = true;
+    fake.respond(
+        StatusCode::OK,
+        None,
+        json!({ "type": "http-01", "status": "valid" }),
+    )
+}
+
+async fn finalize(fake: Fake, body: Bytes) -> HttpResponse {
+    let mut fake = fake.lock().unwrap();
+    let payload = fake.verify(&body, "/finalize/1");
+    assert!(fake.validated);
+    let csr =
+        X509Req::from_der(&decode_base64_url(payload["csr"].as_str().unwrap()).unwrap()).unwrap();
+    let public_key = csr.public_key().unwrap();
+    assert!(csr.verify(&public_key).unwrap());
+
+    let (ca, ca_key) = &fake.ca;
+    let mut builder = X509Builder::new().unwrap();
+    builder.set_version(2).unwrap();
+    builder.set_subject_name(csr.subject_name()).unwrap();
+    builder.set_issuer_name(ca.subject_name()).unwrap();
+    builder.set_pubkey(&public_key).unwrap();
+    builder
+        .set_not_before(&Asn1Time::days_from_now(0).unwrap())
+        .unwrap();
+    builder
+        .set_not_after(&Asn1Time::days_from_now(90).unwrap())
+        .unwrap();
+    builder.sign(ca_key, MessageDigest::sha256()).unwrap();
+    let mut chain = builder.build().to_pem().unwrap();
+    chain.extend(ca.to_pem().unwrap());
+    fake.certificate = Some(chain);
+
+    let order = fake.order();
+    fake.respond(StatusCode::OK, None, order)
+}
+
+async fn order(fake: Fake, body: Bytes) -> HttpResponse {
+    let mut fake = fake.lock().unwrap();
+    assert_eq!(fake.verify(&body, "/order/1"), Value::Null);
+    let order = fake.order();
+    fake.respond(StatusCode::OK, None, order)
+}
+
+async fn certificate(fake: Fake, body: Bytes) -> HttpResponse {
+    let mut fake = fake.lock().unwrap();
+    assert_eq!(fake.verify(&body, "/cert/1"), Value::Null);
+    let certificate = fake.certificate.clone().unwrap();
+    HttpResponse::Ok()
+        .insert_header(("Replay-Nonce", fake.next_nonce()))
+        .content_type("application/pem-certificate-chain")
+        .body(certificate)
+}
+
+/// 접속한 TLS 서버가 보여주는 인증서를 반환합니다.
+async fn served_certificate(port: u16) -> X509 {
+    actix_web::rt::task::spawn_blocking(move || {
+        common::tls_connect(port, b"\x08http/1.1")
+            .ssl()
+            .peer_certificate()
+            .unwrap()
+    })
+    .await
+    .unwrap()
+}
+
+#[actix_web::test]
+async fn certificates_are_issued_over_acme_and_swapped_without_restart() {
+    let dir = common::setup().join(format!("acme-{}", std::process::id()));
+    let state = common::test_state();
+
+    // HTTP-01 인증을 받을 서버
+    let app_state = state.clone();
+    let app = HttpServer::new(move || {
+        App::new()
+            .configure(|cfg| app_state.register(cfg))
+            .configure(routes)
+    })
+    .workers(1)
+    .disable_signals()
+    .bind(("127.0.0.1", 0))
+    .unwrap();
+    let app_port = app.addrs()[0].port();
+    let app = app.run();
+    let app_handle = app.handle();
+    actix_rt::spawn(app);
+
+    // 테스트용 ACME 서버
+    let (ca_pem, ca_key_pem) = common::self_signed("Fake ACME CA", 365);
+    let ca = (
+        X509::from_pem(&ca_pem).unwrap(),
+        PKey::private_key_from_pem(&ca_key_pem).unwrap(),
+    );
+    let fake = Data::new(Mutex::new(FakeAcme {
+        base: String::new(),
+        app_port,
+        ca,
+        jwk: None,
+        nonce: 0,
+        validated: false,
+        certificate: None,
+    }));
+    let fake_data = fake.clone();
+    let acme = HttpServer::new(move || {
+        App::new()
+            .app_data(fake_data.clone())
+            .route("/directory", web::get().to(directory))
+            .route("/nonce", web::head().to(new_nonce))
+            .route("/account", web::post().to(new_account))
+            .route("/order", web::post().to(new_order))
+            .route("/authz/1", web::post().to(authorization))
+            .route("/chall/1", web::post().to(challenge))
+            .route("/finalize/1", web::post().to(finalize))
+            .route("/order/1", web::post().to(order))
+            .route("/cert/1", web::post().to(certificate))
+    })
+    .workers(1)
+    .disable_signals()
+    .bind(("127.0.0.1", 0))
+    .unwrap();
+    let acme_port = acme.addrs()[0].port();
+    fake.lock().unwrap().base = format!("http://127.0.0.1:{}", acme_port);
+    let acme = acme.run();
+    let acme_handle = acme.handle();
+    actix_rt::spawn(acme);
+
+    // 만료가 가까운 인증서로 TLS 서버 시작
+    let tls = TlsConfig {
+        port: 0,
+        cert_file: dir.join("cert.pem").to_string_lossy().into_owned(),
+        key_file: dir.join("key.pem").to_string_lossy().into_owned(),
+        acme: Some(AcmeConfig {
+            directory_url: format!("http://127.0.0.1:{}/directory", acme_port),
+            domains: vec!["localhost".to_string()],
+            contact_email: Some("staff@example.com".to_string()),
+            account_key_file: dir.join("account.pem").to_string_lossy().into_owned(),
+            renew_before_days: 30,
+        }),
+    };
+    let (old_cert, old_key) = common::self_signed("localhost", 10);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(&tls.cert_file, &old_cert).unwrap();
+    fs::write(&tls.key_file, &old_key).unwrap();
+    assert!(needs_renewal(&tls.cert_file, 30));
+
+    let resolver = Arc::new(CertResolver::default());
+    resolver.set(certified_key(&old_cert, &old_key).unwrap());
+    let server = HttpServer::new(|| App::new().configure(routes))
+        .workers(1)
+        .disable_signals()
+        .bind_rustls_0_23(("127.0.0.1", 0), rustls_config(resolver.clone()))
+        .unwrap();
+    let tls_port = server.addrs()[0].port();
+    let server = server.run();
+    let server_handle = server.handle();
+    actix_rt::spawn(server);
+    let before = served_certificate(tls_port).await;
+    assert_eq!(before.to_pem().unwrap(), old_cert);
+
+    assert_eq!(renew(&tls, &resolver).await, Ok(true));
+
+    // 서버를 다시 시작하지 않아도 새 연결부터 발급받은 인증서 사용
+    let after = served_certificate(tls_port).await;
+    assert_ne!(after.to_der().unwrap(), before.to_der().unwrap());
+    let issuer = fake.lock().unwrap().ca.0.subject_name().to_der().unwrap();
+    assert_eq!(after.issuer_name().to_der().unwrap(), issuer);
+    assert_eq!(
+        fs::read(&tls.cert_file).unwrap(),
+        fake.lock().unwrap().certificate.clone().unwrap()
+    );
+    assert!(!resource_path(CHALLENGE_FOLDER, "httpToken_1").exists());
+
+    // 새 인증서는 만료까지 충분히 남아 다시 발급받지 않음
+    assert!(!needs_renewal(&tls.cert_file, 30));
+    assert_eq!(renew(&tls, &resolver).await, Ok(false));
+
+    server_handle.stop(false).await;
+    acme_handle.stop(false).await;
+    app_handle.stop(false).await;
+    fs::remove_dir_all(&dir).unwrap();
+}
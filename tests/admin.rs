@@ -0,0 +1,763 @@
+mod common;
+
+use actix_web::{cookie::Cookie, http::StatusCode, test, App};
+use gj_stamptour::{
+    announcement::inject,
+    bundle::event_path,
+    config::Config,
+    handlers::{admin_routes, routes},
+    logging::recent_logs,
+    persistence,
+    report::DailyReport,
+    state::{Announcement, Command, Reloadable, StampList, Team},
+};
+use serde_json::json;
+use std::fs;
+
+#[actix_web::test]
+async fn public_routes_do_not_expose_admin_apis() {
+    let state = common::test_state();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/admin")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .set_json(json!({ "command": "save all", "output": "" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    let req = test::TestRequest::get()
+        .uri("/api/staff/users/ABCDEF")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    // `/metrics`는 정적 파일 요청으로 처리되므로 메트릭 내용이 없어야 함
+    let req = test::TestRequest::get()
+        .uri("/metrics")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .to_request();
+    let body = test::call_and_read_body(&app, req).await;
+    assert!(!String::from_utf8_lossy(&body).contains("stamptour_"));
+}
+
+#[actix_web::test]
+async fn admin_rejects_remote_peer() {
+    let state = common::test_state();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes)
+            .configure(routes),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/admin")
+        .peer_addr("10.0.0.5:40000".parse().unwrap())
+        .set_json(json!({ "command": "save all", "output": "" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn admin_without_shared_state_returns_internal_error() {
+    // 공유 상태를 등록하지 않으면 패닉 대신 500 응답
+    let app = test::init_service(App::new().configure(admin_routes)).await;
+
+    let req = test::TestRequest::post()
+        .uri("/admin")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .set_json(json!({ "command": "short list", "output": "" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[actix_web::test]
+async fn admin_save_all_writes_databases() {
+    let dir = common::setup();
+    let state = common::test_state();
+    state
+        .user_list
+        .lock()
+        .unwrap()
+        .users
+        .insert("u1".to_string(), "visitor".to_string());
+    persistence::start(&state);
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes)
+            .configure(routes),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/admin")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .set_json(json!({ "command": "save all", "output": "" }))
+        .to_request();
+    let output: Command = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(output.output, "All databases saved");
+
+    let saved = std::fs::read_to_string(dir.join("resources/database/user_status.json")).unwrap();
+    assert!(saved.contains("visitor"));
+}
+
+#[actix_web::test]
+async fn admin_unknown_command() {
+    let state = common::test_state();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes)
+            .configure(routes),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/admin")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .set_json(json!({ "command": "launch rockets", "output": "" }))
+        .to_request();
+    let output: Command = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(output.output, "Command not found");
+}
+
+#[actix_web::test]
+async fn admin_exports_users_csv() {
+    let dir = common::setup();
+    let state = common::test_state();
+    common::register(&state, "u1", "visitor");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes)
+            .configure(routes),
+    )
+    .await;
+    common::collect(&app, "u1", "a").await;
+
+    let req = test::TestRequest::post()
+        .uri("/admin")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .set_json(json!({ "command": "export users", "output": "" }))
+        .to_request();
+    let output: Command = test::call_and_read_body_json(&app, req).await;
+    assert!(output.output.starts_with("Users exported"));
+
+    let csv = std::fs::read_to_string(dir.join("resources/exports/users.csv")).unwrap();
+    assert!(csv.starts_with("user_id,user_name,collected_count,points,tier"));
+    assert!(csv.contains("u1,visitor,1,1,"));
+}
+
+#[actix_web::test]
+async fn admin_exports_anonymized_history() {
+    let dir = common::setup();
+    let state = common::test_state();
+    common::register(&state, "u1", "홍길동");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes)
+            .configure(routes),
+    )
+    .await;
+    common::collect(&app, "u1", "a").await;
+    common::collect(&app, "u1", "b").await;
+
+    let req = test::TestRequest::post()
+        .uri("/admin")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .set_json(json!({ "command": "export anonymized", "output": "" }))
+        .to_request();
+    let output: Command = test::call_and_read_body_json(&app, req).await;
+    assert!(output.output.starts_with("Anonymized history exported"));
+
+    let csv =
+        std::fs::read_to_string(dir.join("resources/exports/anonymized_history.csv")).unwrap();
+    let rows: Vec<&str> = csv.lines().collect();
+    assert_eq!(rows[0], "visitor,stamp_id,timestamp,seq");
+    assert_eq!(rows.len(), 3);
+    // 서버에 반영된 순서대로 정렬됨
+    assert!(rows[1].ends_with(",1") && rows[2].ends_with(",2"));
+    assert!(!csv.contains("u1") && !csv.contains("홍길동"));
+    // 같은 방문자는 같은 가명으로 연결됨
+    assert_eq!(rows[1].split(',').next(), rows[2].split(',').next());
+}
+
+#[actix_web::test]
+async fn admin_sets_and_clears_announcement() {
+    let state = common::test_state();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes)
+            .configure(routes),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/admin")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .set_json(json!({ "command": "announce 30 7번 부스는 <5시>에 종료됩니다", "output": "" }))
+        .to_request();
+    let output: Command = test::call_and_read_body_json(&app, req).await;
+    assert!(output.output.starts_with("Announcement set until"));
+
+    // 만료 시각을 표현할 수 없을 만큼 긴 공지는 형식 오류로 처리
+    let req = test::TestRequest::post()
+        .uri("/admin")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .set_json(json!({ "command": "announce 9223372036854775807 공지", "output": "" }))
+        .to_request();
+    let output: Command = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(output.output, "Usage: announce <minutes> <message>");
+
+    let req = test::TestRequest::get()
+        .uri("/api/announcement")
+        .to_request();
+    let announcement: Option<Announcement> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(
+        announcement.unwrap().message,
+        "7번 부스는 <5시>에 종료됩니다"
+    );
+
+    let req = test::TestRequest::post()
+        .uri("/admin")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .set_json(json!({ "command": "clear announcement", "output": "" }))
+        .to_request();
+    test::call_service(&app, req).await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/announcement")
+        .to_request();
+    let announcement: Option<Announcement> = test::call_and_read_body_json(&app, req).await;
+    assert!(announcement.is_none());
+}
+
+#[actix_web::test]
+async fn expired_announcement_is_hidden() {
+    let state = common::test_state();
+    *state.announcement.lock().unwrap() = Some(Announcement {
+        message: "지난 공지".to_string(),
+        expires_at: "2000-01-01T00:00:00+00:00".to_string(),
+    });
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes)
+            .configure(routes),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/announcement")
+        .to_request();
+    let announcement: Option<Announcement> = test::call_and_read_body_json(&app, req).await;
+    assert!(announcement.is_none());
+
+    let req = test::TestRequest::get()
+        .app_data(state.announcement.clone())
+        .to_http_request();
+    assert_eq!(inject(&req, "<p>%ANNOUNCEMENT%</p>".to_string()), "<p></p>");
+}
+
+#[actix_web::test]
+async fn admin_writes_daily_report() {
+    let dir = common::setup();
+    let state = common::test_state();
+    common::register(&state, "u1", "visitor");
+    common::register(&state, "u2", "guest");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes)
+            .configure(routes),
+    )
+    .await;
+    common::collect(&app, "u1", "a").await;
+    common::collect(&app, "u1", "b").await;
+    common::collect(&app, "u2", "b").await;
+
+    let req = test::TestRequest::post()
+        .uri("/admin")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .set_json(json!({ "command": "daily report", "output": "" }))
+        .to_request();
+    let output: Command = test::call_and_read_body_json(&app, req).await;
+    assert!(output.output.starts_with("Daily report saved"));
+
+    let date = chrono::Local::now().date_naive();
+    let report: DailyReport = serde_json::from_str(
+        &std::fs::read_to_string(dir.join(format!("resources/reports/{}.json", date))).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(report.registered_users, 2);
+    assert_eq!(report.active_users, 2);
+    assert_eq!(report.total_stamps, 3);
+    assert_eq!(report.top_booths[0].stamp_id, "b");
+    assert_eq!(report.top_booths[0].count, 2);
+    assert!(dir
+        .join(format!("resources/reports/{}.html", date))
+        .exists());
+}
+
+#[actix_web::test]
+async fn admin_exports_results_xlsx() {
+    let dir = common::setup();
+    let state = common::test_state();
+    common::register(&state, "u1", "visitor");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes)
+            .configure(routes),
+    )
+    .await;
+    common::collect(&app, "u1", "a").await;
+
+    let req = test::TestRequest::post()
+        .uri("/admin")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .set_json(json!({ "command": "export xlsx", "output": "" }))
+        .to_request();
+    let output: Command = test::call_and_read_body_json(&app, req).await;
+    assert!(output.output.starts_with("Results exported"));
+
+    // xlsx는 zip 파일이므로 zip 시그니처로 시작
+    let xlsx = std::fs::read(dir.join("resources/exports/results.xlsx")).unwrap();
+    assert!(xlsx.starts_with(b"PK"));
+}
+
+#[actix_web::test]
+async fn admin_imports_users_from_csv() {
+    let dir = common::setup();
+    std::fs::create_dir_all(dir.join("resources/imports")).unwrap();
+    std::fs::write(
+        dir.join("resources/imports/groups.csv"),
+        "user_name,team_name\n김철수,3학년 2반\n이영희,3학년 2반\n박민수,\n",
+    )
+    .unwrap();
+    let state = common::test_state();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes)
+            .configure(routes),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/admin")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .set_json(json!({ "command": "import users groups.csv", "output": "" }))
+        .to_request();
+    let output: Command = test::call_and_read_body_json(&app, req).await;
+    assert!(output.output.starts_with("3 users imported"));
+
+    assert_eq!(state.user_list.lock().unwrap().users.len(), 3);
+    let teams = state.teams.lock().unwrap().teams.clone();
+    assert_eq!(teams.len(), 1);
+    assert_eq!(teams.values().next().unwrap().members.len(), 2);
+
+    let mapping =
+        std::fs::read_to_string(dir.join("resources/exports/imported_groups.csv")).unwrap();
+    assert!(mapping.starts_with("user_id,user_code,user_name,team_code"));
+    assert_eq!(mapping.lines().count(), 4);
+
+    let req = test::TestRequest::post()
+        .uri("/admin")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .set_json(json!({ "command": "import users ../database/user_status.json", "output": "" }))
+        .to_request();
+    let output: Command = test::call_and_read_body_json(&app, req).await;
+    assert!(output.output.ends_with("not found"));
+}
+
+#[actix_web::test]
+async fn admin_resets_single_user_progress() {
+    let state = common::test_state();
+    common::register(&state, "u1", "tester");
+    common::register(&state, "u2", "visitor");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes)
+            .configure(routes),
+    )
+    .await;
+    for stamp_id in ["a", "b", "c"] {
+        common::collect(&app, "u1", stamp_id).await;
+    }
+    common::collect(&app, "u2", "a").await;
+    assert!(state
+        .completions
+        .lock()
+        .unwrap()
+        .completions
+        .contains_key("u1"));
+
+    let req = test::TestRequest::post()
+        .uri("/admin")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .set_json(json!({ "command": "reset user u1 테스트 기기 초기화", "output": "" }))
+        .to_request();
+    let output: Command = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(output.output, "Reset user u1 (3 stamps removed)");
+
+    let history = &state.stamp_history;
+    assert!(history.collected_by("u1").is_empty());
+    assert_eq!(history.collected_by("u2").len(), 1);
+    assert!(state.user_list.lock().unwrap().users.contains_key("u1"));
+    assert!(!state
+        .completions
+        .lock()
+        .unwrap()
+        .completions
+        .contains_key("u1"));
+    assert_eq!(state.audit_log.lock().unwrap().entries[0].action, "reset");
+}
+
+#[actix_web::test]
+async fn reload_replaces_config_and_stamp_list() {
+    let state = common::test_state();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes),
+    )
+    .await;
+    let stamps = StampList {
+        stampList: ["a", "b", "c", "d"]
+            .into_iter()
+            .map(common::stamp)
+            .collect(),
+    };
+    fs::create_dir_all(event_path("api")).unwrap();
+    fs::write(
+        event_path("api/stampList.json"),
+        serde_json::to_string(&stamps).unwrap(),
+    )
+    .unwrap();
+    fs::write(event_path("config.json"), r#"{ "staff_token": "reloaded" }"#).unwrap();
+
+    let reload = || {
+        test::TestRequest::post()
+            .uri("/admin")
+            .peer_addr("127.0.0.1:40000".parse().unwrap())
+            .set_json(json!({ "command": "reload", "output": "" }))
+            .to_request()
+    };
+    let output: Command = test::call_and_read_body_json(&app, reload()).await;
+    assert_eq!(output.output, "Reloaded config and 4 stamps");
+    assert_eq!(state.stamp_list.get().stamp_id_list.len(), 4);
+    assert!(state.stamp_history.contains("d"));
+    assert_eq!(state.config.get().staff_token.as_deref(), Some("reloaded"));
+
+    // 설정 파일이 깨져 있으면 이전 값을 유지
+    fs::write(event_path("config.json"), "{ broken").unwrap();
+    let output: Command = test::call_and_read_body_json(&app, reload()).await;
+    fs::remove_file(event_path("config.json")).unwrap();
+    fs::remove_file(event_path("api/stampList.json")).unwrap();
+    assert!(output.output.starts_with("Reload failed: config.json"));
+    assert_eq!(state.config.get().staff_token.as_deref(), Some("reloaded"));
+}
+
+#[actix_web::test]
+async fn admin_merges_duplicate_users() {
+    let state = common::test_state();
+    common::register(&state, "u1", "visitor");
+    common::register(&state, "u2", "visitor (phone)");
+    state.teams.lock().unwrap().teams.insert(
+        "TEAM42".to_string(),
+        Team {
+            team_name: "1반".to_string(),
+            members: vec!["u2".to_string(), "u3".to_string()],
+            completions: Vec::new(),
+        },
+    );
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes)
+            .configure(routes),
+    )
+    .await;
+    common::collect(&app, "u1", "a").await;
+    common::collect(&app, "u2", "a").await;
+    common::collect(&app, "u2", "b").await;
+    common::collect(&app, "u2", "c").await;
+    let admin = |command: &str| {
+        test::TestRequest::post()
+            .uri("/admin")
+            .peer_addr("127.0.0.1:40000".parse().unwrap())
+            .set_json(json!({ "command": command, "output": "" }))
+            .to_request()
+    };
+
+    let output: Command = test::call_and_read_body_json(&app, admin("merge u1 u1")).await;
+    assert_eq!(output.output, "Cannot merge a user into itself");
+    let output: Command = test::call_and_read_body_json(&app, admin("merge u1 nobody")).await;
+    assert_eq!(output.output, "User nobody not found");
+
+    // 미리보기는 아무것도 바꾸지 않음
+    let output: Command = test::call_and_read_body_json(&app, admin("merge --dry-run u1 u2")).await;
+    assert_eq!(
+        output.output,
+        "Dry run: merging u2 into u1 would move 3 history entries, new stamps: b, c, joins team TEAM42"
+    );
+    assert!(state.user_list.lock().unwrap().users.contains_key("u2"));
+    assert_eq!(state.stamp_history.collected_by("u1").len(), 1);
+
+    let output: Command = test::call_and_read_body_json(&app, admin("merge u1 u2")).await;
+    assert_eq!(
+        output.output,
+        "Merged u2 into u1: moved 3 history entries, new stamps: b, c, joins team TEAM42"
+    );
+    assert!(!state.user_list.lock().unwrap().users.contains_key("u2"));
+    assert!(state.user_list.lock().unwrap().code_of("u2").is_none());
+    assert_eq!(state.stamp_history.collected_by("u1").len(), 3);
+    assert!(state.stamp_history.collected_by("u2").is_empty());
+    // 부스별 발급 수는 그대로
+    assert_eq!(state.stamp_history.issued("a"), 2);
+    assert_eq!(
+        state.teams.lock().unwrap().teams["TEAM42"].members,
+        vec!["u1", "u3"]
+    );
+    {
+        let completions = state.completions.lock().unwrap();
+        assert!(completions.completions.contains_key("u1"));
+        assert!(!completions.completions.contains_key("u2"));
+        assert!(completions.codes.contains_key("u1"));
+    }
+    let audit_log = state.audit_log.lock().unwrap();
+    let record = audit_log.entries.last().unwrap();
+    assert_eq!(record.action, "merge");
+    assert_eq!(record.user_id, "u1");
+}
+
+#[actix_web::test]
+async fn admin_soft_deletes_restores_and_purges_users() {
+    let state = common::test_state();
+    common::register(&state, "u1", "visitor");
+    common::register(&state, "u2", "other");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes)
+            .configure(routes),
+    )
+    .await;
+    common::collect(&app, "u1", "a").await;
+    common::collect(&app, "u1", "b").await;
+    common::collect(&app, "u2", "a").await;
+    let admin = |command: &str| {
+        test::TestRequest::post()
+            .uri("/admin")
+            .peer_addr("127.0.0.1:40000".parse().unwrap())
+            .set_json(json!({ "command": command, "output": "" }))
+            .to_request()
+    };
+
+    let output: Command = test::call_and_read_body_json(&app, admin("delete user u1")).await;
+    assert_eq!(output.output, "Usage: delete user <user> <reason>");
+    let output: Command =
+        test::call_and_read_body_json(&app, admin("delete user u1 잘못 삭제 시험")).await;
+    assert_eq!(output.output, "Deleted user u1 (undelete user u1 to undo)");
+    assert!(!state.user_list.lock().unwrap().users.contains_key("u1"));
+    assert!(state.stamp_history.collected_by("u1").is_empty());
+    assert_eq!(state.stamp_history.issued("a"), 1);
+    let req = test::TestRequest::get()
+        .uri("/progress")
+        .cookie(Cookie::new("user_id", "u1"))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::UNAUTHORIZED
+    );
+    let output: Command = test::call_and_read_body_json(&app, admin("deleted users")).await;
+    assert!(output.output.starts_with("u1 (2 stamps) deleted at "));
+    assert!(output.output.ends_with(": 잘못 삭제 시험"));
+
+    let output: Command = test::call_and_read_body_json(&app, admin("undelete user u1")).await;
+    assert!(output.output.starts_with("Restored user u1 (code "));
+    assert_eq!(state.user_list.lock().unwrap().users["u1"], "visitor");
+    assert_eq!(state.stamp_history.collected_by("u1").len(), 2);
+    assert_eq!(state.stamp_history.issued("a"), 2);
+    let output: Command = test::call_and_read_body_json(&app, admin("undelete user u1")).await;
+    assert_eq!(output.output, "Deleted user u1 not found");
+
+    test::call_service(&app, admin("delete user u2 중복 등록")).await;
+    let output: Command = test::call_and_read_body_json(&app, admin("purge deleted")).await;
+    assert_eq!(output.output, "Purged 1 deleted users");
+    let output: Command = test::call_and_read_body_json(&app, admin("undelete user u2")).await;
+    assert_eq!(output.output, "Deleted user u2 not found");
+    let audit_log = state.audit_log.lock().unwrap();
+    assert!(audit_log
+        .entries
+        .iter()
+        .all(|record| record.user_id != "u2"));
+    assert_eq!(
+        audit_log
+            .entries
+            .iter()
+            .filter(|record| record.action == "undelete")
+            .count(),
+        1
+    );
+}
+
+#[actix_web::test]
+async fn admin_undoes_destructive_commands() {
+    let state = common::test_state();
+    common::register(&state, "u1", "visitor");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes)
+            .configure(routes),
+    )
+    .await;
+    common::collect(&app, "u1", "a").await;
+    common::collect(&app, "u1", "b").await;
+    let admin = |command: &str| {
+        test::TestRequest::post()
+            .uri("/admin")
+            .peer_addr("127.0.0.1:40000".parse().unwrap())
+            .set_json(json!({ "command": command, "output": "" }))
+            .to_request()
+    };
+
+    let output: Command = test::call_and_read_body_json(&app, admin("undo")).await;
+    assert_eq!(output.output, "Nothing to undo");
+
+    test::call_service(&app, admin("revoke u1 a 잘못 찍음")).await;
+    test::call_service(&app, admin("reset user u1 시험 기기")).await;
+    test::call_service(&app, admin("delete user u1 실수")).await;
+    assert!(!state.user_list.lock().unwrap().users.contains_key("u1"));
+    let output: Command = test::call_and_read_body_json(&app, admin("undo list")).await;
+    let commands: Vec<&str> = output
+        .output
+        .lines()
+        .map(|line| line.split_once(" UTC ").unwrap().1)
+        .collect();
+    assert_eq!(
+        commands,
+        vec![
+            "delete user u1 실수",
+            "reset user u1 시험 기기",
+            "revoke u1 a 잘못 찍음"
+        ]
+    );
+
+    let output: Command = test::call_and_read_body_json(&app, admin("undo")).await;
+    assert_eq!(output.output, "Undid `delete user u1 실수`");
+    assert!(state.user_list.lock().unwrap().users.contains_key("u1"));
+    assert!(state.stamp_history.collected_by("u1").is_empty());
+
+    let output: Command = test::call_and_read_body_json(&app, admin("undo")).await;
+    assert_eq!(output.output, "Undid `reset user u1 시험 기기`");
+    assert_eq!(
+        state
+            .stamp_history
+            .collected_by("u1")
+            .into_iter()
+            .collect::<Vec<_>>(),
+        vec!["b"]
+    );
+
+    let output: Command = test::call_and_read_body_json(&app, admin("undo")).await;
+    assert_eq!(output.output, "Undid `revoke u1 a 잘못 찍음`");
+    assert_eq!(state.stamp_history.collected_by("u1").len(), 2);
+    assert_eq!(state.stamp_history.issued("a"), 1);
+
+    let output: Command = test::call_and_read_body_json(&app, admin("undo list")).await;
+    assert_eq!(output.output, "Nothing to undo");
+    let audit_log = state.audit_log.lock().unwrap();
+    assert_eq!(
+        audit_log
+            .entries
+            .iter()
+            .filter(|record| record.action == "undo")
+            .count(),
+        2
+    );
+}
+
+#[actix_web::test]
+async fn admin_logs_return_recent_lines() {
+    let state = common::test_state();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes),
+    )
+    .await;
+    recent_logs().push("[2024-10-05T05:00:00Z INFO  gj_stamptour] log-tail first\n");
+    recent_logs().push("[2024-10-05T05:00:01Z WARN  gj_stamptour] log-tail slow save\n");
+    recent_logs()
+        .push("[2024-10-05T05:00:02Z ERROR gj_stamptour] log-tail failed\n  caused by disk full\n");
+
+    let req = test::TestRequest::get()
+        .uri("/admin/logs?lines=3&level=warn")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+    assert_eq!(
+        body,
+        "[2024-10-05T05:00:01Z WARN  gj_stamptour] log-tail slow save\n\
+         [2024-10-05T05:00:02Z ERROR gj_stamptour] log-tail failed\n  caused by disk full\n"
+    );
+
+    let req = test::TestRequest::get()
+        .uri("/admin/logs?level=loud")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::BAD_REQUEST
+    );
+}
+
+#[actix_web::test]
+async fn admin_logs_require_staff_token() {
+    let state = common::test_state();
+    let config = Config {
+        staff_token: Some("secret".to_string()),
+        ..Default::default()
+    };
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .app_data(actix_web::web::Data::new(Reloadable::new(config)))
+            .configure(admin_routes),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/admin/logs")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::UNAUTHORIZED
+    );
+    let req = test::TestRequest::get()
+        .uri("/admin/logs")
+        .insert_header(("Authorization", "Bearer secret"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use actix_web::{test, App};
+use gj_stamptour::{handlers::routes, state::AppState};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+/// 현재 스레드의 메모리 할당 횟수를 세는 할당자입니다.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+/// 유저 `users`명이 등록되고 그중 절반이 확인 대기 중인 상태에서 스템프 하나를 찍는 동안의 할당 횟수를 반환합니다.
+async fn allocations_per_stamp(users: usize) -> usize {
+    let state = common::test_state();
+    for i in 0..users {
+        common::register(&state, &format!("user-{}", i), "visitor");
+    }
+    populate_pending(&state, users / 2);
+    common::register(&state, "visitor", "visitor");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    // 첫 요청의 초기화 비용은 제외
+    common::collect(&app, "visitor", "a").await;
+    let before = allocations();
+    common::collect(&app, "visitor", "b").await;
+    allocations() - before
+}
+
+fn populate_pending(state: &AppState, count: usize) {
+    let mut user_stamp_list = state.user_stamp_list.lock().unwrap();
+    for i in 0..count {
+        user_stamp_list
+            .user_stamp_list
+            .insert(format!("user-{}", i), "c".to_string());
+    }
+}
+
+#[actix_web::test]
+async fn stamping_does_not_copy_whole_user_lists() {
+    let small = allocations_per_stamp(10).await;
+    let large = allocations_per_stamp(10_000).await;
+
+    // 유저 목록이나 확인 대기 목록을 복사하면 유저 수에 비례하여 할당이 늘어남
+    assert!(
+        large < small + 100,
+        "allocations per stamp grew from {} to {} with more users",
+        small,
+        large
+    );
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use actix_web::{http::StatusCode, middleware::from_fn, test, App};
+use gj_stamptour::{
+    api_version::mark_legacy_path, handlers::routes, state::User, stats::PublicStats,
+};
+use serde_json::json;
+
+#[actix_web::test]
+async fn legacy_paths_point_to_v1() {
+    let state = common::test_state();
+    let app = test::init_service(
+        App::new()
+            .wrap(from_fn(mark_legacy_path))
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    let res = test::call_service(
+        &app,
+        test::TestRequest::post()
+            .uri("/api/v1/login")
+            .set_json(json!({ "user_name": "visitor" }))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert!(res.headers().get("deprecation").is_none());
+    let user: User = test::read_body_json(res).await;
+    assert_eq!(user.user_name, "visitor");
+
+    // 이전 경로도 같은 응답을 반환하며 새 경로를 알려줌
+    let res = test::call_service(
+        &app,
+        test::TestRequest::get()
+            .uri("/api/stats/public")
+            .to_request(),
+    )
+    .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers().get("deprecation").unwrap(), "true");
+    assert_eq!(
+        res.headers().get("link").unwrap(),
+        "</api/v1/stats/public>; rel=\"successor-version\""
+    );
+    let stats: PublicStats = test::read_body_json(res).await;
+    assert_eq!(stats.participants, 1);
+
+    let res = test::call_service(
+        &app,
+        test::TestRequest::get()
+            .uri("/api/v1/stats/public")
+            .to_request(),
+    )
+    .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert!(res.headers().get("link").is_none());
+}
@@ -0,0 +1,204 @@
+mod common;
+
+use actix_web::web::Data;
+use actix_web::{http::StatusCode, test, App};
+use gj_stamptour::{
+    assets::{rewrite_asset_urls, AssetManifest, IMMUTABLE_CACHE},
+    handlers::routes,
+    state::Reloadable,
+    storage::resource_path,
+};
+use std::fs;
+
+#[actix_web::test]
+async fn binary_assets_are_streamed_with_length() {
+    let state = common::test_state();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    let file_name = format!("stream-test-{}.png", std::process::id());
+    let file_path = resource_path("img", &file_name);
+    fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+    // 한 번에 읽는 크기보다 큰 파일
+    let contents: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+    fs::write(&file_path, &contents).unwrap();
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/img/{}", file_name))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = test::read_body(resp).await;
+    fs::remove_file(&file_path).unwrap();
+    assert_eq!(body.len(), contents.len());
+    assert_eq!(&body[..], &contents[..]);
+
+    let req = test::TestRequest::get()
+        .uri("/img/missing-image.png")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn asset_placeholders_use_fingerprinted_urls() {
+    let mut manifest = AssetManifest::default();
+    manifest.insert("css", "style.css", b"body { color: red; }");
+    let url = manifest.url("css/style.css");
+    let hash = url
+        .strip_prefix("/css/style.")
+        .and_then(|rest| rest.strip_suffix(".css"))
+        .unwrap();
+    assert_eq!(hash.len(), 8);
+    assert_eq!(
+        manifest.original(&format!("css/style.{}.css", hash)),
+        Some("css/style.css")
+    );
+
+    let html = rewrite_asset_urls(
+        "<link href=\"%ASSET:css/style.css%\"><script src=\"%ASSET:js/none.js%\"></script>",
+        &manifest,
+        "",
+    );
+    assert_eq!(
+        html,
+        format!(
+            "<link href=\"{}\"><script src=\"/js/none.js\"></script>",
+            url
+        )
+    );
+
+    // 내용이 바뀌면 지문도 바뀜
+    manifest.insert("css", "style.css", b"body { color: blue; }");
+    assert_ne!(manifest.url("css/style.css"), url);
+}
+
+#[actix_web::test]
+async fn fingerprinted_assets_are_cached_immutably() {
+    let mut state = common::test_state();
+    let file_name = format!("fingerprint-{}.css", std::process::id());
+    let file_path = resource_path("css", &file_name);
+    fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+    fs::write(&file_path, "h1 { margin: 0; }").unwrap();
+
+    let mut manifest = AssetManifest::default();
+    manifest.insert("css", &file_name, b"h1 { margin: 0; }");
+    let url = manifest.url(&format!("css/{}", file_name));
+    state.assets = Data::new(Reloadable::new(manifest));
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    let req = test::TestRequest::get().uri(&url).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        resp.headers().get("cache-control").unwrap(),
+        IMMUTABLE_CACHE
+    );
+    assert_eq!(test::read_body(resp).await, "h1 { margin: 0; }");
+
+    // 원래 이름으로 요청하면 장기 캐시하지 않음
+    let req = test::TestRequest::get()
+        .uri(&format!("/css/{}", file_name))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    fs::remove_file(&file_path).unwrap();
+    assert!(resp.headers().get("cache-control").is_none());
+}
+
+#[actix_web::test]
+async fn nested_assets_resolve_inside_resources_only() {
+    let state = common::test_state();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    let dir_name = format!("nested-{}", std::process::id());
+    let file_path = resource_path("img", &format!("{}/icons/star.svg.txt", dir_name));
+    fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+    fs::write(&file_path, "star").unwrap();
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/img/{}/icons/star.svg.txt", dir_name))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(test::read_body(resp).await, "star");
+    fs::remove_dir_all(resource_path("img", &dir_name)).unwrap();
+
+    for uri in [
+        "/img/../database/user_status.json",
+        "/img/%2e%2e/%2e%2e/etc/passwd",
+        "/database/user_status.json",
+        "/img/missing/icon.txt",
+        "/%2e%2e",
+    ] {
+        let req = test::TestRequest::get().uri(uri).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND, "{}", uri);
+    }
+}
+
+#[actix_web::test]
+async fn exports_are_not_served() {
+    let state = common::test_state();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    // 내보내기 파일에는 세션 쿠키로 쓰이는 유저 ID가 담겨 있음
+    let file_path = resource_path("exports", "users.csv");
+    fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+    fs::write(&file_path, "user_id,user_name\nu1,visitor\n").unwrap();
+
+    let req = test::TestRequest::get()
+        .uri("/exports/users.csv")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    fs::remove_file(&file_path).unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn only_public_folders_are_served() {
+    let state = common::test_state();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    // 데이터를 담는 폴더는 목록에 없으므로 모두 404 응답
+    for (folder, file) in [
+        ("backups", "user_status-20241005-130000.json"),
+        ("staging", "user_status.json"),
+        ("reports", "daily-2024-10-05.md"),
+        ("imports", "users.csv"),
+    ] {
+        let file_path = resource_path(folder, file);
+        fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+        fs::write(&file_path, "user_id\nu1\n").unwrap();
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/{}/{}", folder, file))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        fs::remove_file(&file_path).unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND, "{}/{}", folder, file);
+    }
+}
@@ -0,0 +1,377 @@
+mod common;
+
+use actix_web::{test, App};
+use chrono::{Local, TimeZone, Utc};
+use gj_stamptour::{
+    backup::{
+        download_missing_snapshots, download_snapshots, list_snapshots, read_snapshot, sign_put,
+        signing_key, write_snapshot, BackupConfig, S3Config,
+    },
+    config::Config,
+    handlers::{admin_routes, routes},
+    state::{Command, Reloadable},
+    storage::{checksum, load_database, to_database, DatabaseFormat},
+};
+use serde_json::{json, Value};
+use std::{
+    net::TcpListener,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+#[actix_web::test]
+async fn snapshots_keep_newest() {
+    let dir = common::setup().join("backups-retention");
+    let config = BackupConfig {
+        dir: dir.to_string_lossy().to_string(),
+        keep: 2,
+        s3: None,
+    };
+
+    for hour in [10, 11, 12] {
+        let now = Local.with_ymd_and_hms(2024, 10, 5, hour, 0, 0).unwrap();
+        write_snapshot(&config, &[("stamp_status", b"{}".to_vec())], now).unwrap();
+    }
+
+    assert_eq!(
+        list_snapshots(&dir, "stamp_status"),
+        vec!["2024-10-05T11:00", "2024-10-05T12:00"]
+    );
+    assert!(dir.join("stamp_status-2024-10-05T12:00.json").exists());
+    assert!(dir
+        .join("stamp_status-2024-10-05T12:00.json.sha256")
+        .exists());
+    assert!(!dir
+        .join("stamp_status-2024-10-05T10:00.json.sha256")
+        .exists());
+}
+
+#[actix_web::test]
+async fn derives_sigv4_signing_key() {
+    // AWS 문서의 서명 키 계산 예시
+    let key = signing_key(
+        "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+        "20120215",
+        "us-east-1",
+        "iam",
+    );
+    let hex: String = key.iter().map(|byte| format!("{:02x}", byte)).collect();
+    assert_eq!(
+        hex,
+        "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+    );
+
+    let s3 = S3Config {
+        endpoint: "http://localhost:9000".to_string(),
+        bucket: "stamptour".to_string(),
+        region: "us-east-1".to_string(),
+        access_key: "minio".to_string(),
+        secret_key: "minio123".to_string(),
+        prefix: "backups/".to_string(),
+    };
+    let now = Utc.with_ymd_and_hms(2024, 10, 5, 5, 0, 0).unwrap();
+    let (url, headers) = sign_put(&s3, "stamp_status-2024-10-05T14:00.json", b"{}", now).unwrap();
+    assert_eq!(
+        url,
+        "http://localhost:9000/stamptour/backups/stamp_status-2024-10-05T14%3A00.json"
+    );
+    assert!(headers[0]
+        .1
+        .starts_with("AWS4-HMAC-SHA256 Credential=minio/20241005/us-east-1/s3/aws4_request"));
+    assert_eq!(headers[2], ("x-amz-date", "20241005T050000Z".to_string()));
+}
+
+#[actix_web::test]
+async fn admin_backup_writes_all_databases() {
+    let state = common::test_state();
+    let dir = common::setup().join("backups-admin");
+    let mut config = Config::default();
+    config.backup.dir = dir.to_string_lossy().to_string();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .app_data(actix_web::web::Data::new(Reloadable::new(config)))
+            .configure(admin_routes),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/admin")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .set_json(json!({ "command": "backup", "output": "" }))
+        .to_request();
+    let output: Command = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(
+        output.output,
+        "Backup saved (7 files, 0 old snapshots removed)"
+    );
+    assert_eq!(list_snapshots(Path::new(&dir), "user_status").len(), 1);
+    assert_eq!(list_snapshots(Path::new(&dir), "stamp_status").len(), 1);
+}
+
+#[actix_web::test]
+async fn restore_undoes_accidental_reset() {
+    let state = common::test_state();
+    common::register(&state, "u1", "tester");
+    let dir = common::setup().join("backups-restore");
+    let mut config = Config::default();
+    config.backup.dir = dir.to_string_lossy().to_string();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .app_data(actix_web::web::Data::new(Reloadable::new(config)))
+            .configure(admin_routes)
+            .configure(routes),
+    )
+    .await;
+    common::collect(&app, "u1", "a").await;
+    common::collect(&app, "u1", "b").await;
+
+    for command in ["backup", "reset user u1 실수"] {
+        let req = test::TestRequest::post()
+            .uri("/admin")
+            .peer_addr("127.0.0.1:40000".parse().unwrap())
+            .set_json(json!({ "command": command, "output": "" }))
+            .to_request();
+        test::call_service(&app, req).await;
+    }
+    assert!(state.stamp_history.collected_by("u1").is_empty());
+
+    let req = test::TestRequest::post()
+        .uri("/admin")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .set_json(json!({ "command": "backups", "output": "" }))
+        .to_request();
+    let timestamp: Command = test::call_and_read_body_json(&app, req).await;
+
+    let req = test::TestRequest::post()
+        .uri("/admin")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .set_json(json!({ "command": format!("restore {}", timestamp.output), "output": "" }))
+        .to_request();
+    let output: Command = test::call_and_read_body_json(&app, req).await;
+    assert!(
+        output.output.starts_with("Restored snapshot"),
+        "{}",
+        output.output
+    );
+    assert_eq!(state.stamp_history.collected_by("u1").len(), 2);
+    assert_eq!(
+        state
+            .audit_log
+            .lock()
+            .unwrap()
+            .entries
+            .last()
+            .unwrap()
+            .action,
+        "restore"
+    );
+
+    let req = test::TestRequest::post()
+        .uri("/admin")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .set_json(json!({ "command": "restore ../../etc/passwd", "output": "" }))
+        .to_request();
+    let output: Command = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(
+        output.output,
+        "Restore failed: Invalid snapshot name ../../etc/passwd"
+    );
+}
+
+/// S3 대역 서버가 받은 `(경로, 본문)`입니다.
+type Upload = (String, Vec<u8>);
+
+#[actix_web::test]
+async fn exports_are_uploaded_to_s3() {
+    // PUT 요청의 경로와 본문을 기록하는 S3 대역 서버
+    let uploads: Arc<Mutex<Vec<Upload>>> = Arc::default();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let received = Arc::clone(&uploads);
+    let server = actix_web::HttpServer::new(move || {
+        let received = Arc::clone(&received);
+        App::new().default_service(actix_web::web::to(
+            move |req: actix_web::HttpRequest, body: actix_web::web::Bytes| {
+                let received = Arc::clone(&received);
+                async move {
+                    assert!(req.headers().contains_key("authorization"));
+                    received
+                        .lock()
+                        .unwrap()
+                        .push((req.path().to_string(), body.to_vec()));
+                    actix_web::HttpResponse::Ok().finish()
+                }
+            },
+        ))
+    })
+    .workers(1)
+    .listen(listener)
+    .unwrap()
+    .run();
+    let handle = server.handle();
+    actix_rt::spawn(server);
+
+    let state = common::test_state();
+    common::setup();
+    let mut config = Config::default();
+    config.backup.s3 = Some(S3Config {
+        endpoint: format!("http://{}", address),
+        bucket: "stamptour".to_string(),
+        region: "us-east-1".to_string(),
+        access_key: "minio".to_string(),
+        secret_key: "minio123".to_string(),
+        prefix: "festival/".to_string(),
+    });
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .app_data(actix_web::web::Data::new(Reloadable::new(config)))
+            .configure(admin_routes),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/admin")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .set_json(json!({ "command": "export feedback", "output": "" }))
+        .to_request();
+    let output: Command = test::call_and_read_body_json(&app, req).await;
+    assert!(output.output.starts_with("Feedback exported to"));
+
+    for _ in 0..50 {
+        if !uploads.lock().unwrap().is_empty() {
+            break;
+        }
+        actix_rt::time::sleep(Duration::from_millis(100)).await;
+    }
+    handle.stop(true).await;
+    let uploads = uploads.lock().unwrap();
+    assert_eq!(uploads.len(), 1);
+    assert_eq!(uploads[0].0, "/stamptour/festival/exports/feedback.csv");
+    assert!(String::from_utf8_lossy(&uploads[0].1).starts_with("rating,comment,timestamp"));
+}
+
+#[actix_web::test]
+async fn snapshots_are_downloaded_from_s3() {
+    // 버킷 목록과 객체를 돌려주는 S3 대역 서버 (목록은 두 쪽으로 나누어 보냄)
+    let snapshot = br#"{"stamp_history": {"library": []}}"#.to_vec();
+    let checksum_line = format!(
+        "{}  stamp_status-2024-10-05T14:00.json\n",
+        checksum(&snapshot)
+    );
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let server = actix_web::HttpServer::new(move || {
+        let (snapshot, checksum_line) = (snapshot.clone(), checksum_line.clone());
+        App::new().default_service(actix_web::web::to(move |req: actix_web::HttpRequest| {
+            let (snapshot, checksum_line) = (snapshot.clone(), checksum_line.clone());
+            async move {
+                assert!(req.headers().contains_key("authorization"));
+                // 객체 키의 `:`는 인코딩되어 들어옴
+                let path = req.path().replace("%3A", ":");
+                let body = match (path.as_str(), req.query_string()) {
+                    ("/stamptour", query) if query.contains("continuation-token=next") => {
+                        "<ListBucketResult><IsTruncated>false</IsTruncated>\
+                         <Contents><Key>festival/stamp_status-2024-10-05T14:00.json.sha256</Key></Contents>\
+                         <Contents><Key>festival/exports/users.csv</Key></Contents>\
+                         </ListBucketResult>"
+                            .as_bytes()
+                            .to_vec()
+                    }
+                    ("/stamptour", query) => {
+                        assert!(query.contains("list-type=2&prefix=festival%2F"));
+                        "<ListBucketResult><IsTruncated>true</IsTruncated>\
+                         <NextContinuationToken>next</NextContinuationToken>\
+                         <Contents><Key>festival/stamp_status-2024-10-05T14:00.json</Key></Contents>\
+                         <Contents><Key>festival/audit_log.json</Key></Contents>\
+                         </ListBucketResult>"
+                            .as_bytes()
+                            .to_vec()
+                    }
+                    ("/stamptour/festival/stamp_status-2024-10-05T14:00.json", _) => snapshot,
+                    ("/stamptour/festival/stamp_status-2024-10-05T14:00.json.sha256", _) => {
+                        checksum_line.into_bytes()
+                    }
+                    (path, _) => panic!("unexpected request {}", path),
+                };
+                actix_web::HttpResponse::Ok().body(body)
+            }
+        }))
+    })
+    .workers(1)
+    .listen(listener)
+    .unwrap()
+    .run();
+    let handle = server.handle();
+    actix_rt::spawn(server);
+
+    let dir = common::setup().join("backups-s3");
+    let backup = BackupConfig {
+        dir: dir.to_string_lossy().to_string(),
+        s3: Some(S3Config {
+            endpoint: format!("http://{}", address),
+            bucket: "stamptour".to_string(),
+            region: "us-east-1".to_string(),
+            access_key: "minio".to_string(),
+            secret_key: "minio123".to_string(),
+            prefix: "festival/".to_string(),
+        }),
+        ..Default::default()
+    };
+    download_missing_snapshots(&backup).await;
+    assert_eq!(
+        list_snapshots(&dir, "stamp_status"),
+        vec!["2024-10-05T14:00"]
+    );
+    assert!(dir
+        .join("stamp_status-2024-10-05T14:00.json.sha256")
+        .exists());
+    // 스냅샷이 아닌 객체는 내려받지 않음
+    assert!(!dir.join("audit_log.json").exists());
+    assert!(!dir.join("exports").exists());
+    assert_eq!(
+        read_snapshot(&backup, "2024-10-05T14:00")
+            .unwrap()
+            .stamp_history
+            .to_map()
+            .keys()
+            .collect::<Vec<_>>(),
+        vec!["library"]
+    );
+
+    // 이미 있는 파일은 다시 받지 않음
+    assert!(download_snapshots(&backup).await.unwrap().is_empty());
+    handle.stop(true).await;
+}
+
+#[actix_web::test]
+async fn missing_database_is_loaded_from_latest_snapshot() {
+    let dir = common::setup().join("backups-missing");
+    let config = BackupConfig {
+        dir: dir.to_string_lossy().to_string(),
+        ..Default::default()
+    };
+    assert_eq!(load_database::<Value>("missing_test", &config), None);
+
+    for (hour, saved) in [(13, 1), (14, 2)] {
+        let contents = to_database(
+            "missing_test",
+            json!({ "saved": saved }),
+            DatabaseFormat::Json,
+        )
+        .unwrap();
+        let now = Local.with_ymd_and_hms(2024, 10, 5, hour, 0, 0).unwrap();
+        write_snapshot(&config, &[("missing_test", contents)], now).unwrap();
+    }
+    // 데이터베이스 파일이 없으면 빈 데이터 대신 가장 최근 스냅샷을 불러옴
+    assert!(!common::setup()
+        .join("resources/database/missing_test.json")
+        .exists());
+    assert_eq!(
+        load_database::<Value>("missing_test", &config),
+        Some(json!({ "saved": 2 }))
+    );
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use actix_web::{http::StatusCode, middleware::from_fn, test, App};
+use gj_stamptour::{
+    base_path::{normalize, strip_base_path},
+    config::Config,
+    handlers::routes,
+    state::AppState,
+};
+use serde_json::json;
+
+#[actix_web::test]
+async fn routes_are_mounted_under_base_path() {
+    common::setup();
+    let config: Config = serde_json::from_value(json!({ "base_path": "/stamptour/" })).unwrap();
+    let state = AppState::new(
+        config,
+        common::stamp_list(vec![common::stamp("a"), common::stamp("b")]),
+    );
+    let app = test::init_service(
+        App::new()
+            .wrap(from_fn(strip_base_path))
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/stamptour/api/leaderboard?limit=3")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    for uri in ["/api/leaderboard", "/stamptourx/api/leaderboard"] {
+        let req = test::TestRequest::get().uri(uri).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND, "{}", uri);
+    }
+
+    // 리다이렉션 주소에도 접두사가 붙음
+    let req = test::TestRequest::get()
+        .uri("/stamptour/check?s=a")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    let location = resp.headers().get("location").unwrap().to_str().unwrap();
+    assert!(
+        location.starts_with("/stamptour/stamp/?random="),
+        "{}",
+        location
+    );
+}
+
+#[actix_web::test]
+async fn base_path_is_normalized() {
+    assert_eq!(normalize(""), "");
+    assert_eq!(normalize(" / "), "");
+    assert_eq!(normalize("festival/stamptour/"), "/festival/stamptour");
+}
@@ -0,0 +1,127 @@
+mod common;
+
+use actix_web::{cookie::Cookie, http::StatusCode, test, App};
+use chrono::Utc;
+use gj_stamptour::{
+    booth::{booth_key, qr_token},
+    config::Config,
+    handlers::routes,
+    state::AppState,
+};
+use serde_json::json;
+
+fn booth_state() -> AppState {
+    common::setup();
+    let config: Config = serde_json::from_value(json!({
+        "staff_token": "secret",
+        "booth": { "qr_rotation_secs": 60 }
+    }))
+    .unwrap();
+    AppState::new(
+        config,
+        common::stamp_list(vec![common::stamp("a"), common::stamp("b")]),
+    )
+}
+
+#[actix_web::test]
+async fn booth_page_requires_booth_key() {
+    let state = booth_state();
+    common::register(&state, "u1", "<visitor>");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+    let token = qr_token(&state.config.get().booth, "a", Utc::now()).unwrap();
+    let req = test::TestRequest::get()
+        .uri(&format!("/check?t={}&s=a", token))
+        .cookie(Cookie::new("user_id", "u1"))
+        .to_request();
+    test::call_service(&app, req).await;
+    let req = test::TestRequest::get()
+        .uri("/stamp/")
+        .cookie(Cookie::new("user_id", "u1"))
+        .to_request();
+    test::call_service(&app, req).await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/staff/booth/a?key={}", booth_key("secret", "a")))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = String::from_utf8(test::read_body(res).await.to_vec()).unwrap();
+    assert!(body.contains("스템프 a"));
+    assert!(body.contains("<strong>1</strong>"));
+    assert!(body.contains("&lt;visitor&gt;"));
+    assert!(body.contains("<svg"));
+    assert!(body.contains("content=\"60\""));
+
+    // 다른 부스의 키로는 볼 수 없음
+    let req = test::TestRequest::get()
+        .uri(&format!("/staff/booth/a?key={}", booth_key("secret", "b")))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    let req = test::TestRequest::get()
+        .uri("/staff/booth/unknown")
+        .insert_header(("Authorization", "Bearer secret"))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn rotating_qr_rejects_missing_token() {
+    let state = booth_state();
+    common::register(&state, "u1", "visitor");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    // 토큰 없이 인쇄된 QR 코드로는 찍히지 않음
+    common::collect(&app, "u1", "b").await;
+    let req = test::TestRequest::get()
+        .uri("/check?t=000000000000&s=b")
+        .cookie(Cookie::new("user_id", "u1"))
+        .to_request();
+    test::call_service(&app, req).await;
+    common::collect(&app, "u1", "b").await;
+    assert!(state.stamp_history.entries("b").is_empty());
+}
+
+#[actix_web::test]
+async fn kiosk_serves_current_qr_code() {
+    let state = booth_state();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+    let key = booth_key("secret", "a");
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/kiosk/a?key={}", key))
+        .to_request();
+    let body = test::call_and_read_body(&app, req).await;
+    let body = String::from_utf8(body.to_vec()).unwrap();
+    assert!(body.contains("<svg"));
+    // QR 코드가 바뀌는 간격의 절반마다 다시 받아옴
+    assert!(body.contains("30000"));
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/kiosk/a/qr?key={}", key))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers().get("Content-Type").unwrap(), "image/svg+xml");
+
+    let req = test::TestRequest::get().uri("/kiosk/a/qr").to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,164 @@
+mod common;
+
+use actix_web::{http::StatusCode, test, App};
+use gj_stamptour::{
+    bot::{line_signature, nearest_uncollected, reply_for},
+    config::Config,
+    handlers::routes,
+    state::{AppState, Coordinates, Stamp, StampUserInfo},
+};
+use serde_json::{json, Value};
+
+fn at(stamp_id: &str, latitude: f64, longitude: f64) -> Stamp {
+    let mut stamp = common::stamp(stamp_id);
+    stamp.stampCoordinates = Some(Coordinates {
+        latitude,
+        longitude,
+    });
+    stamp
+}
+
+fn collect(state: &AppState, user_id: &str, stamp_id: &str, timestamp: &str) {
+    state.stamp_history.push(
+        stamp_id,
+        StampUserInfo {
+            user_name: "visitor".to_string(),
+            user_id: user_id.to_string(),
+            timestamp: timestamp.to_string(),
+            seq: 0,
+        },
+    );
+}
+
+#[actix_web::test]
+async fn nearest_booth_follows_last_stamp() {
+    common::setup();
+    let state = common::state_with(common::stamp_list(vec![
+        at("a", 35.1000, 126.9000),
+        at("b", 35.1001, 126.9001),
+        at("c", 35.2000, 127.0000),
+        at("d", 35.2001, 127.0001),
+    ]));
+    common::register(&state, "u1", "visitor");
+
+    // 아무것도 찍지 않았으면 스템프 ID 순서로 안내
+    let stamp_id_list = state.stamp_list.get();
+    let nearest = |state: &AppState| {
+        nearest_uncollected(&stamp_id_list, &state.stamp_history, "u1")
+            .map(|stamp| stamp.stampId.clone())
+    };
+    assert_eq!(nearest(&state).as_deref(), Some("a"));
+
+    collect(&state, "u1", "a", "2024-10-05 01:00:00 UTC");
+    collect(&state, "u1", "c", "2024-10-05 02:00:00 UTC");
+    assert_eq!(nearest(&state).as_deref(), Some("d"));
+
+    collect(&state, "u1", "b", "2024-10-05 03:00:00 UTC");
+    collect(&state, "u1", "d", "2024-10-05 04:00:00 UTC");
+    assert_eq!(nearest(&state), None);
+}
+
+#[actix_web::test]
+async fn replies_with_progress_for_user_codes() {
+    let state = common::test_state();
+    common::register(&state, "u1", "visitor");
+    state.user_list.lock().unwrap().assign_missing_codes();
+    collect(&state, "u1", "a", "2024-10-05 01:00:00 UTC");
+    let user_code = state.user_list.lock().unwrap().codes.keys().next().cloned();
+
+    let reply = |text: &str| {
+        reply_for(
+            &Config::default(),
+            &state.stamp_list.get(),
+            &state.stamp_history,
+            &state.user_list.lock().unwrap(),
+            text,
+        )
+    };
+    let progress = reply(&user_code.unwrap().to_lowercase());
+    assert!(progress.contains("1/3"));
+    assert!(progress.contains("스템프 b"));
+    assert!(!progress.contains("visitor"));
+    assert!(reply("000000").contains("찾을 수 없습니다"));
+    assert!(reply("/start").contains("6자리 유저 코드"));
+}
+
+#[actix_web::test]
+async fn webhooks_require_their_secrets() {
+    common::setup();
+    let config: Config = serde_json::from_value(json!({
+        "bot": {
+            "telegram": { "secret_token": "tg-secret" },
+            "line": { "channel_secret": "line-secret", "channel_access_token": "token" }
+        }
+    }))
+    .unwrap();
+    let state = AppState::new(
+        config,
+        common::stamp_list(vec![common::stamp("a"), common::stamp("b")]),
+    );
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    let update = json!({ "update_id": 1, "message": { "chat": { "id": 42 }, "text": "hello" } });
+    let res = test::call_service(
+        &app,
+        test::TestRequest::post()
+            .uri("/api/v1/bot/telegram")
+            .insert_header(("X-Telegram-Bot-Api-Secret-Token", "wrong"))
+            .set_json(&update)
+            .to_request(),
+    )
+    .await;
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    let res = test::call_service(
+        &app,
+        test::TestRequest::post()
+            .uri("/api/v1/bot/telegram")
+            .insert_header(("X-Telegram-Bot-Api-Secret-Token", "tg-secret"))
+            .set_json(&update)
+            .to_request(),
+    )
+    .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let body: Value = test::read_body_json(res).await;
+    assert_eq!(body["method"], "sendMessage");
+    assert_eq!(body["chat_id"], 42);
+
+    // 메시지가 없는 이벤트는 답장하지 않으므로 외부 API를 호출하지 않음
+    let webhook = json!({ "destination": "U1", "events": [] }).to_string();
+    let res = test::call_service(
+        &app,
+        test::TestRequest::post()
+            .uri("/api/v1/bot/line")
+            .insert_header((
+                "X-Line-Signature",
+                line_signature("other", webhook.as_bytes()),
+            ))
+            .insert_header(("Content-Type", "application/json"))
+            .set_payload(webhook.clone())
+            .to_request(),
+    )
+    .await;
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    let res = test::call_service(
+        &app,
+        test::TestRequest::post()
+            .uri("/api/v1/bot/line")
+            .insert_header((
+                "X-Line-Signature",
+                line_signature("line-secret", webhook.as_bytes()),
+            ))
+            .insert_header(("Content-Type", "application/json"))
+            .set_payload(webhook)
+            .to_request(),
+    )
+    .await;
+    assert_eq!(res.status(), StatusCode::OK);
+}
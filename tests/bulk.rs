@@ -0,0 +1,148 @@
+mod common;
+
+use actix_web::{http::StatusCode, test, App};
+use gj_stamptour::{
+    bulk::{BulkError, BulkResult},
+    config::Config,
+    handlers::admin_routes,
+    state::Reloadable,
+};
+use serde_json::json;
+
+#[actix_web::test]
+async fn bulk_operations_apply_together() {
+    let state = common::test_state();
+    common::register(&state, "u1", "tester");
+    common::register(&state, "u2", "tester 2");
+    for (user_id, stamp_id) in [("u1", "a"), ("u1", "b"), ("u2", "a")] {
+        state.stamp_history.push(
+            stamp_id,
+            gj_stamptour::state::StampUserInfo {
+                user_name: "tester".to_string(),
+                user_id: user_id.to_string(),
+                timestamp: "2024-10-05 01:00:00 UTC".to_string(),
+                seq: 0,
+            },
+        );
+    }
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes),
+    )
+    .await;
+    let bulk = |operations: serde_json::Value| {
+        test::TestRequest::post()
+            .uri("/api/v1/admin/bulk")
+            .peer_addr("127.0.0.1:40000".parse().unwrap())
+            .set_json(json!({ "operations": operations, "reason": "시험 운영일 정리" }))
+            .to_request()
+    };
+
+    // 하나라도 실행할 수 없으면 아무것도 바꾸지 않음
+    let resp = test::call_service(
+        &app,
+        bulk(json!([
+            { "op": "revoke_stamp", "user": "u1", "stamp_id": "a" },
+            { "op": "revoke_stamp", "user": "u1", "stamp_id": "a" },
+            { "op": "add_stamp", "user": "u1", "stamp_id": "b" },
+            { "op": "delete_user", "user": "nobody" },
+        ])),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let result: BulkResult = test::read_body_json(resp).await;
+    assert!(!result.applied);
+    assert_eq!(
+        result.errors,
+        vec![
+            BulkError {
+                index: 1,
+                error: "User u1 has no record for stamp a".to_string()
+            },
+            BulkError {
+                index: 2,
+                error: "User u1 already has stamp b".to_string()
+            },
+            BulkError {
+                index: 3,
+                error: "User nobody not found".to_string()
+            },
+        ]
+    );
+    assert_eq!(state.stamp_history.collected_by("u1").len(), 2);
+
+    let resp = test::call_service(
+        &app,
+        bulk(json!([
+            { "op": "revoke_stamp", "user": "u1", "stamp_id": "a" },
+            { "op": "add_stamp", "user": "u1", "stamp_id": "c" },
+            { "op": "delete_user", "user": "u2" },
+        ])),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let result: BulkResult = test::read_body_json(resp).await;
+    assert_eq!(
+        result,
+        BulkResult {
+            applied: true,
+            deleted_users: 1,
+            revoked_stamps: 1,
+            added_stamps: 1,
+            errors: Vec::new(),
+        }
+    );
+    let collected: Vec<String> = state.stamp_history.collected_by("u1").into_iter().collect();
+    assert_eq!(collected, vec!["b", "c"]);
+    assert!(state.user_list.lock().unwrap().deleted.contains_key("u2"));
+    assert_eq!(state.stamp_history.issued("a"), 0);
+    let audit_log = state.audit_log.lock().unwrap();
+    let actions: Vec<&str> = audit_log
+        .entries
+        .iter()
+        .map(|record| record.action.as_str())
+        .collect();
+    assert_eq!(actions, vec!["revoke", "add stamp", "delete"]);
+    assert!(audit_log
+        .entries
+        .iter()
+        .all(|record| record.actor == "admin" && record.reason == "시험 운영일 정리"));
+}
+
+#[actix_web::test]
+async fn bulk_operations_require_staff() {
+    let state = common::test_state();
+    let config = Config {
+        staff_token: Some("secret".to_string()),
+        ..Default::default()
+    };
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .app_data(actix_web::web::Data::new(Reloadable::new(config)))
+            .configure(admin_routes),
+    )
+    .await;
+    let body = json!({ "operations": [{ "op": "delete_user", "user": "u1" }], "reason": "x" });
+
+    let req = test::TestRequest::post()
+        .uri("/api/admin/bulk")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .set_json(&body)
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::UNAUTHORIZED
+    );
+
+    let req = test::TestRequest::post()
+        .uri("/api/admin/bulk")
+        .insert_header(("Authorization", "Bearer secret"))
+        .set_json(json!({ "operations": [], "reason": "x" }))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::BAD_REQUEST
+    );
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use gj_stamptour::{
+    bundle::{event_path, select_event_dir, validate_bundle, REQUIRED_TEMPLATES},
+    config::read_config,
+    storage::{read_stamp_list, resources_dir},
+};
+use std::{fs, path::PathBuf};
+
+const STAMP_LIST: &str = r#"{"stampList": [
+    {"stampId": "library", "stampName": "도서관", "stampLocation": "본관 1층", "stampDesc": ""}
+]}"#;
+
+/// 스템프 목록과 필수 템플릿을 갖춘 행사 폴더를 만듭니다.
+fn bundle(name: &str) -> PathBuf {
+    let dir = common::setup().join(name);
+    fs::create_dir_all(dir.join("api")).unwrap();
+    fs::create_dir_all(dir.join("html")).unwrap();
+    fs::write(dir.join("api/stampList.json"), STAMP_LIST).unwrap();
+    for template in REQUIRED_TEMPLATES {
+        fs::write(dir.join("html").join(template), "<html></html>").unwrap();
+    }
+    dir
+}
+
+#[test]
+fn complete_bundle_is_valid() {
+    let dir = bundle("event-complete");
+    assert_eq!(validate_bundle(&dir), Ok(()));
+
+    fs::write(dir.join("config.json"), r#"{"closes_at": null}"#).unwrap();
+    assert_eq!(validate_bundle(&dir), Ok(()));
+}
+
+#[test]
+fn broken_bundle_reports_every_problem() {
+    let dir = bundle("event-broken");
+    fs::write(dir.join("config.json"), "{ not json").unwrap();
+    fs::write(dir.join("api/stampList.json"), r#"{"stampList": []}"#).unwrap();
+    fs::remove_file(dir.join("html/index.html")).unwrap();
+
+    let problems = validate_bundle(&dir).unwrap_err();
+    assert_eq!(problems.len(), 3);
+    assert!(problems[0].starts_with("config.json:"));
+    assert_eq!(problems[1], "api/stampList.json: Stamp list is empty");
+    assert_eq!(problems[2], "html/index.html is missing");
+}
+
+#[test]
+fn missing_directory_is_rejected() {
+    let dir = common::setup().join("event-missing");
+    let problems = validate_bundle(&dir).unwrap_err();
+    assert_eq!(
+        problems,
+        vec![format!("{} is not a directory", dir.display())]
+    );
+}
+
+#[test]
+fn event_dir_replaces_resources() {
+    let dir = bundle("event-2024");
+    fs::write(
+        dir.join("config.json"),
+        r#"{"share": {"festival_name": "2024 축제"}}"#,
+    )
+    .unwrap();
+    let args = ["GJ_StampTour", "-p", "8080", "--event-dir"]
+        .iter()
+        .map(|arg| arg.to_string())
+        .chain([dir.to_string_lossy().to_string()])
+        .collect::<Vec<_>>();
+
+    assert_eq!(select_event_dir(&args), Some(dir.as_path()));
+    assert_eq!(event_path("missions.json"), dir.join("missions.json"));
+    assert_eq!(resources_dir(), dir);
+    assert_eq!(read_config().unwrap().share.festival_name, "2024 축제");
+    assert!(read_stamp_list()
+        .unwrap()
+        .stamp_id_list
+        .contains_key("library"));
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use actix_web::{cookie::Cookie, http::header::CACHE_CONTROL, middleware::from_fn, test, App};
+use gj_stamptour::{
+    cache_control::{apply_admin_cache_policy, apply_cache_policy, ASSET_CACHE},
+    handlers::{admin_routes, routes},
+    storage::resource_path,
+};
+use serde_json::json;
+use std::fs;
+
+#[actix_web::test]
+async fn public_responses_get_policy_by_content_class() {
+    let state = common::test_state();
+    common::register(&state, "u1", "visitor");
+    let app = test::init_service(
+        App::new()
+            .wrap(from_fn(apply_cache_policy))
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    let file_name = format!("cache-{}.png", std::process::id());
+    let file_path = resource_path("img", &file_name);
+    fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+    fs::write(&file_path, [0u8; 8]).unwrap();
+
+    let cases = [
+        (
+            test::TestRequest::get().uri(&format!("/img/{}", file_name)),
+            ASSET_CACHE,
+        ),
+        (test::TestRequest::get().uri("/img/missing.png"), "no-cache"),
+        (
+            test::TestRequest::get()
+                .uri("/api/v1/progress")
+                .cookie(Cookie::new("user_id", "u1")),
+            "no-cache",
+        ),
+        (test::TestRequest::get().uri("/stamp/"), "no-cache"),
+        (
+            test::TestRequest::post()
+                .uri("/api/v1/login")
+                .set_json(json!({ "user_name": "visitor2" })),
+            "no-store",
+        ),
+        (
+            test::TestRequest::get().uri("/api/stats/public"),
+            "public, max-age=10",
+        ),
+    ];
+    for (req, expected) in cases {
+        let req = req
+            .peer_addr("127.0.0.1:40000".parse().unwrap())
+            .to_request();
+        let uri = req.uri().to_string();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(
+            resp.headers().get(CACHE_CONTROL).unwrap(),
+            expected,
+            "{}",
+            uri
+        );
+    }
+    fs::remove_file(&file_path).unwrap();
+}
+
+#[actix_web::test]
+async fn admin_responses_are_never_stored() {
+    let state = common::test_state();
+    let app = test::init_service(
+        App::new()
+            .wrap(from_fn(apply_admin_cache_policy))
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes),
+    )
+    .await;
+
+    for req in [
+        test::TestRequest::post()
+            .uri("/admin")
+            .set_json(json!({ "command": "stamp status", "output": "" })),
+        test::TestRequest::get().uri("/metrics"),
+    ] {
+        let req = req
+            .peer_addr("127.0.0.1:40000".parse().unwrap())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.headers().get(CACHE_CONTROL).unwrap(), "no-store");
+    }
+}
@@ -0,0 +1,105 @@
+mod common;
+
+use chrono::{Local, TimeZone};
+use gj_stamptour::{
+    backup::{write_snapshot, BackupConfig},
+    storage::{
+        checksum, load_database, read_database, to_database, write_database, DatabaseFormat,
+    },
+};
+use serde_json::{json, Value};
+use std::fs;
+
+fn backup_config(name: &str) -> BackupConfig {
+    BackupConfig {
+        dir: common::setup().join(name).to_string_lossy().to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn database_files_are_written_with_checksums() {
+    let dir = common::setup().join("resources/database");
+    let contents = to_database("checksum_test", json!({ "a": 1 }), DatabaseFormat::Json).unwrap();
+    write_database("checksum_test", DatabaseFormat::Json, &contents).unwrap();
+
+    let line = fs::read_to_string(dir.join("checksum_test.json.sha256")).unwrap();
+    assert_eq!(
+        line,
+        format!("{}  checksum_test.json\n", checksum(&contents))
+    );
+    assert_eq!(read_database("checksum_test").unwrap().unwrap(), contents);
+}
+
+#[test]
+fn damaged_database_falls_back_to_newest_valid_backup() {
+    let dir = common::setup().join("resources/database");
+    let backup = backup_config("backups-checksum");
+    let snapshot = |value: Value, hour| {
+        let contents = to_database("damaged_test", value, DatabaseFormat::Json).unwrap();
+        let now = Local.with_ymd_and_hms(2024, 10, 5, hour, 0, 0).unwrap();
+        write_snapshot(&backup, &[("damaged_test", contents)], now).unwrap();
+    };
+    snapshot(json!({ "saved": 10 }), 10);
+    snapshot(json!({ "saved": 11 }), 11);
+    // 가장 최근 스냅샷이 잘렸으면 그 이전 스냅샷을 사용
+    let newest = std::path::Path::new(&backup.dir).join("damaged_test-2024-10-05T11:00.json");
+    fs::write(&newest, b"{\"saved\": 1").unwrap();
+
+    let contents =
+        to_database("damaged_test", json!({ "saved": 12 }), DatabaseFormat::Json).unwrap();
+    write_database("damaged_test", DatabaseFormat::Json, &contents).unwrap();
+    fs::write(
+        dir.join("damaged_test.json"),
+        &contents[..contents.len() - 1],
+    )
+    .unwrap();
+
+    assert!(read_database("damaged_test").unwrap().is_err());
+    assert_eq!(
+        load_database::<Value>("damaged_test", &backup),
+        Some(json!({ "saved": 10 }))
+    );
+}
+
+#[test]
+fn files_saved_before_checksums_still_load() {
+    let dir = common::setup().join("resources/database");
+    fs::write(dir.join("legacy_test.json"), b"{\"legacy\": true}").unwrap();
+    assert_eq!(
+        load_database::<Value>("legacy_test", &backup_config("backups-legacy")),
+        Some(json!({ "legacy": true }))
+    );
+}
+
+#[test]
+fn crash_between_renames_keeps_database_readable() {
+    let dir = common::setup().join("resources/database");
+    let old = to_database("rename_test", json!({ "saved": 1 }), DatabaseFormat::Json).unwrap();
+    let new = to_database("rename_test", json!({ "saved": 2 }), DatabaseFormat::Json).unwrap();
+    write_database("rename_test", DatabaseFormat::Json, &old).unwrap();
+
+    // 체크섬 파일만 바뀌고 데이터 파일은 아직 이전 내용인 상태
+    let line = |contents: &[u8]| format!("{}  rename_test.json\n", checksum(contents));
+    fs::write(
+        dir.join("rename_test.json.sha256"),
+        format!("{}{}", line(&new), line(&old)),
+    )
+    .unwrap();
+    assert_eq!(read_database("rename_test").unwrap().unwrap(), old);
+
+    // 데이터 파일도 바뀌었지만 체크섬 파일을 정리하기 전인 상태
+    fs::write(dir.join("rename_test.json"), &new).unwrap();
+    assert_eq!(read_database("rename_test").unwrap().unwrap(), new);
+
+    // 어느 쪽과도 맞지 않는 내용은 거절
+    fs::write(dir.join("rename_test.json"), b"{\"saved\": 3}").unwrap();
+    assert!(read_database("rename_test").unwrap().is_err());
+
+    // 저장을 마치면 새 체크섬만 남음
+    write_database("rename_test", DatabaseFormat::Json, &new).unwrap();
+    assert_eq!(
+        fs::read_to_string(dir.join("rename_test.json.sha256")).unwrap(),
+        line(&new)
+    );
+}
@@ -0,0 +1,135 @@
+#![allow(dead_code)]
+
+use actix_http::Request;
+use actix_web::{
+    body::MessageBody,
+    cookie::Cookie,
+    dev::{Service, ServiceResponse},
+    test,
+};
+use gj_stamptour::{
+    config::Config,
+    state::{AppState, Stamp, StampIdList},
+};
+use std::{env, fs, path::PathBuf, sync::Once};
+
+static INIT: Once = Once::new();
+
+/// 테스트가 저장하는 데이터베이스 파일이 저장소를 더럽히지 않도록 임시 디렉터리로 이동합니다.
+pub fn setup() -> PathBuf {
+    let dir = env::temp_dir().join(format!("stamptour-test-{}", std::process::id()));
+    INIT.call_once(|| {
+        fs::create_dir_all(dir.join("resources/database")).unwrap();
+        env::set_current_dir(&dir).unwrap();
+    });
+    dir
+}
+
+pub fn stamp(stamp_id: &str) -> Stamp {
+    Stamp {
+        stampId: stamp_id.to_string(),
+        stampLocation: format!("{} 부스", stamp_id),
+        stampName: format!("스템프 {}", stamp_id),
+        stampDesc: String::new(),
+        ..Default::default()
+    }
+}
+
+pub fn stamp_list(stamps: Vec<Stamp>) -> StampIdList {
+    StampIdList {
+        stamp_id_list: stamps
+            .into_iter()
+            .map(|stamp| (stamp.stampId.clone(), stamp))
+            .collect(),
+    }
+}
+
+/// `a`, `b`, `c` 세 개의 스템프를 가진 빈 상태를 생성합니다.
+pub fn test_state() -> AppState {
+    setup();
+    state_with(stamp_list(vec![stamp("a"), stamp("b"), stamp("c")]))
+}
+
+pub fn state_with(stamps: StampIdList) -> AppState {
+    AppState::new(Config::default(), stamps)
+}
+
+/// 유저를 등록된 상태로 추가합니다.
+pub fn register(state: &AppState, user_id: &str, user_name: &str) {
+    state
+        .user_list
+        .lock()
+        .unwrap()
+        .users
+        .insert(user_id.to_string(), user_name.to_string());
+}
+
+/// `/check`와 `/stamp/`를 차례로 호출하여 스템프를 찍고 `/stamp/`의 응답 상태를 반환합니다.
+pub async fn collect<S, B>(app: &S, user_id: &str, stamp_id: &str) -> actix_web::http::StatusCode
+where
+    S: Service<Request, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    B: MessageBody,
+{
+    let req = test::TestRequest::get()
+        .uri(&format!("/check?s={}", stamp_id))
+        .cookie(Cookie::new("user_id", user_id.to_string()))
+        .to_request();
+    test::call_service(app, req).await;
+
+    let req = test::TestRequest::get()
+        .uri("/stamp/")
+        .cookie(Cookie::new("user_id", user_id.to_string()))
+        .to_request();
+    test::call_service(app, req).await.status()
+}
+
+/// `domain`에 대한 자체 서명 인증서와 개인 키를 PEM 형식으로 만듭니다. 인증서는 `days`일 뒤에 만료됩니다.
+pub fn self_signed(domain: &str, days: u32) -> (Vec<u8>, Vec<u8>) {
+    use openssl::{
+        asn1::Asn1Time,
+        bn::{BigNum, MsbOption},
+        ec::{EcGroup, EcKey},
+        hash::MessageDigest,
+        nid::Nid,
+        pkey::PKey,
+        x509::{X509Builder, X509NameBuilder},
+    };
+
+    let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+    let key = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+    let mut name = X509NameBuilder::new().unwrap();
+    name.append_entry_by_text("CN", domain).unwrap();
+    let name = name.build();
+    let mut builder = X509Builder::new().unwrap();
+    builder.set_version(2).unwrap();
+    let mut serial = BigNum::new().unwrap();
+    serial.rand(64, MsbOption::MAYBE_ZERO, false).unwrap();
+    builder
+        .set_serial_number(&serial.to_asn1_integer().unwrap())
+        .unwrap();
+    builder.set_subject_name(&name).unwrap();
+    builder.set_issuer_name(&name).unwrap();
+    builder.set_pubkey(&key).unwrap();
+    builder
+        .set_not_before(&Asn1Time::days_from_now(0).unwrap())
+        .unwrap();
+    builder
+        .set_not_after(&Asn1Time::days_from_now(days).unwrap())
+        .unwrap();
+    builder.sign(&key, MessageDigest::sha256()).unwrap();
+    (
+        builder.build().to_pem().unwrap(),
+        key.private_key_to_pem_pkcs8().unwrap(),
+    )
+}
+
+/// 인증서를 검증하지 않고 `127.0.0.1:port`에 TLS로 접속합니다. `protocols`는 제안할 ALPN 프로토콜입니다.
+pub fn tls_connect(port: u16, protocols: &[u8]) -> openssl::ssl::SslStream<std::net::TcpStream> {
+    use openssl::ssl::{SslConnector, SslMethod, SslVerifyMode};
+
+    let mut connector = SslConnector::builder(SslMethod::tls()).unwrap();
+    connector.set_verify(SslVerifyMode::NONE);
+    connector.set_alpn_protos(protocols).unwrap();
+    let stream = std::net::TcpStream::connect(("127.0.0.1", port)).unwrap();
+    connector.build().connect("localhost", stream).unwrap()
+}
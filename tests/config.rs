@@ -0,0 +1,69 @@
+use gj_stamptour::config::{handle_args, Config, ServerConfig};
+use serde_json::json;
+use std::time::Duration;
+
+#[test]
+fn server_config_defaults_match_actix() {
+    let config: Config = serde_json::from_value(json!({})).unwrap();
+    assert_eq!(config.server, ServerConfig::default());
+    assert_eq!(config.server.workers, None);
+    assert_eq!(config.server.keep_alive(), Duration::from_secs(5));
+    assert_eq!(config.server.shutdown_timeout_secs, 30);
+    assert!(!config.server.http2);
+    assert_eq!(
+        config.server.request_timeout("/img/map.svg"),
+        Some(Duration::from_secs(30))
+    );
+}
+
+#[test]
+fn server_config_overrides_are_partial() {
+    let config: Config = serde_json::from_value(json!({
+        "server": { "workers": 2, "client_disconnect_timeout_secs": 30 }
+    }))
+    .unwrap();
+    assert_eq!(config.server.workers, Some(2));
+    assert_eq!(
+        config.server.client_disconnect_timeout(),
+        Duration::from_secs(30)
+    );
+    assert_eq!(
+        config.server.client_request_timeout(),
+        Duration::from_secs(5)
+    );
+}
+
+#[test]
+fn zero_request_timeout_disables_limit() {
+    let config: Config = serde_json::from_value(json!({
+        "server": { "page_timeout_secs": 0 }
+    }))
+    .unwrap();
+    assert_eq!(config.server.request_timeout("/stamp/"), None);
+    assert_eq!(
+        config.server.request_timeout("/api/v1/login"),
+        Some(Duration::from_secs(5))
+    );
+}
+
+#[test]
+fn valueless_flags_do_not_shift_args() {
+    let args: Vec<String> = [
+        "server",
+        "--dry-run",
+        "-p",
+        "8080",
+        "--check",
+        "--admin-port",
+        "9090",
+        "-a",
+        "0.0.0.0",
+    ]
+    .iter()
+    .map(|arg| arg.to_string())
+    .collect();
+    let address = handle_args(args.clone(), args.len());
+    assert_eq!(address.port, 8080);
+    assert_eq!(address.admin_port, 9090);
+    assert_eq!(address.address, "0.0.0.0");
+}
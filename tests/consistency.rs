@@ -0,0 +1,103 @@
+mod common;
+
+use actix_web::{test, App};
+use gj_stamptour::{
+    consistency::{check, repair},
+    handlers::admin_routes,
+    state::{Command, StampUserInfo},
+};
+use serde_json::json;
+
+fn entry(user_id: &str, timestamp: &str) -> StampUserInfo {
+    StampUserInfo {
+        user_name: user_id.to_string(),
+        user_id: user_id.to_string(),
+        timestamp: timestamp.to_string(),
+        seq: 0,
+    }
+}
+
+#[actix_web::test]
+async fn finds_and_repairs_broken_references() {
+    let state = common::test_state();
+    common::register(&state, "u1", "tester");
+    let stamp_list = state.stamp_list.get();
+    let user_list = state.user_list.lock().unwrap().clone();
+    let history = state.stamp_history.get_ref().clone();
+    let mut pending = state.user_stamp_list.lock().unwrap().clone();
+
+    history.push("a", entry("u1", "2024-10-05 10:00:00 UTC"));
+    history.push("a", entry("u1", "2024-10-05 10:05:00 UTC"));
+    history.push("a", entry("ghost", "2024-10-05 10:10:00 UTC"));
+    history.remove_stamp("b");
+    history.push("removed", entry("u1", "2024-10-05 11:00:00 UTC"));
+    pending
+        .user_stamp_list
+        .insert("ghost".to_string(), "a".to_string());
+
+    let report = check(&stamp_list, &user_list, &history, &pending);
+    assert_eq!(
+        report.orphan_entries,
+        vec![("a".to_string(), "ghost".to_string())]
+    );
+    assert_eq!(
+        report.duplicate_entries,
+        vec![("a".to_string(), "u1".to_string(), 2)]
+    );
+    assert_eq!(report.unknown_stamps, vec![("removed".to_string(), 1)]);
+    assert_eq!(report.missing_stamps, vec!["b".to_string()]);
+    assert_eq!(report.orphan_pending, vec!["ghost".to_string()]);
+
+    assert_eq!(
+        repair(&stamp_list, &user_list, &history, &mut pending),
+        report
+    );
+    assert_eq!(history.entries("a").len(), 1);
+    assert_eq!(history.entries("a")[0].timestamp, "2024-10-05 10:00:00 UTC");
+    assert!(history.contains("b") && history.entries("b").is_empty());
+    assert!(pending.user_stamp_list.is_empty());
+
+    let remaining = check(&stamp_list, &user_list, &history, &pending);
+    assert_eq!(remaining.unknown_stamps, report.unknown_stamps);
+    assert!(remaining.orphan_entries.is_empty() && remaining.duplicate_entries.is_empty());
+}
+
+#[actix_web::test]
+async fn admin_checks_and_repairs_state() {
+    let state = common::test_state();
+    common::register(&state, "u1", "tester");
+    state
+        .stamp_history
+        .push("c", entry("ghost", "2024-10-05 10:00:00 UTC"));
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/admin")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .set_json(json!({ "command": "check state", "output": "" }))
+        .to_request();
+    let output: Command = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(output.output, "orphan entry: unknown user ghost on stamp c");
+    assert_eq!(state.stamp_history.entries("c").len(), 1);
+
+    let req = test::TestRequest::post()
+        .uri("/admin")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .set_json(json!({ "command": "repair state", "output": "" }))
+        .to_request();
+    test::call_service(&app, req).await;
+    assert!(state.stamp_history.entries("c").is_empty());
+
+    let req = test::TestRequest::post()
+        .uri("/admin")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .set_json(json!({ "command": "check state", "output": "" }))
+        .to_request();
+    let output: Command = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(output.output, "No inconsistencies found");
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use actix_web::{cookie::Cookie, http::StatusCode, test, App};
+use gj_stamptour::{config::Config, handlers::routes, state::AppState};
+use serde_json::json;
+
+fn cooldown_state(secs: u64) -> AppState {
+    common::setup();
+    let config: Config = serde_json::from_value(json!({ "scan_cooldown_secs": secs })).unwrap();
+    AppState::new(
+        config,
+        common::stamp_list(vec![common::stamp("a"), common::stamp("b")]),
+    )
+}
+
+fn check(stamp_id: &str, user_id: &str) -> actix_http::Request {
+    test::TestRequest::get()
+        .uri(&format!("/check?s={}", stamp_id))
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .cookie(Cookie::new("user_id", user_id.to_string()))
+        .to_request()
+}
+
+#[actix_web::test]
+async fn rapid_scans_are_rejected_within_cooldown() {
+    let state = cooldown_state(60);
+    common::register(&state, "u1", "visitor");
+    common::register(&state, "u2", "friend");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    let res = test::call_service(&app, check("a", "u1")).await;
+    assert_eq!(res.status(), StatusCode::TEMPORARY_REDIRECT);
+
+    // 간격 안에 같은 스템프나 다른 스템프를 다시 확인하면 429와 남은 시간을 반환
+    for stamp_id in ["a", "b"] {
+        let res = test::call_service(&app, check(stamp_id, "u1")).await;
+        assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+        let retry_after: u64 = res
+            .headers()
+            .get("Retry-After")
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!((1..=60).contains(&retry_after));
+    }
+    // 거절된 확인은 확인 대기 스템프를 바꾸지 않음
+    assert_eq!(
+        state.user_stamp_list.lock().unwrap().user_stamp_list["u1"],
+        "a"
+    );
+
+    // 다른 유저는 영향을 받지 않음
+    let res = test::call_service(&app, check("a", "u2")).await;
+    assert_eq!(res.status(), StatusCode::TEMPORARY_REDIRECT);
+
+    // 등록되지 않은 스템프는 간격과 관계없이 리다이렉션
+    let res = test::call_service(&app, check("missing", "u1")).await;
+    assert_eq!(res.status(), StatusCode::TEMPORARY_REDIRECT);
+}
+
+#[actix_web::test]
+async fn zero_cooldown_accepts_every_scan() {
+    let state = cooldown_state(0);
+    common::register(&state, "u1", "visitor");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    for stamp_id in ["a", "a", "b"] {
+        let res = test::call_service(&app, check(stamp_id, "u1")).await;
+        assert_eq!(res.status(), StatusCode::TEMPORARY_REDIRECT);
+    }
+}
@@ -0,0 +1,164 @@
+mod common;
+
+use actix_web::{http::StatusCode, middleware::from_fn, test, App};
+use gj_stamptour::{
+    config::Config,
+    cors::{apply_cors, CorsConfig},
+    handlers::routes,
+    state::AppState,
+};
+
+const PWA: &str = "https://pwa.example.com";
+
+fn cors_state() -> AppState {
+    common::setup();
+    let config = Config {
+        cors: CorsConfig {
+            allowed_origins: vec![PWA.to_string()],
+            allow_credentials: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    AppState::new(
+        config,
+        common::stamp_list(vec![common::stamp("a"), common::stamp("b")]),
+    )
+}
+
+#[actix_web::test]
+async fn api_routes_allow_configured_origin() {
+    let state = cors_state();
+    let app = test::init_service(
+        App::new()
+            .wrap(from_fn(apply_cors))
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/v1/event")
+        .insert_header(("Origin", PWA))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(
+        resp.headers().get("access-control-allow-origin").unwrap(),
+        PWA
+    );
+    assert_eq!(
+        resp.headers()
+            .get("access-control-allow-credentials")
+            .unwrap(),
+        "true"
+    );
+    assert_eq!(resp.headers().get("vary").unwrap(), "Origin");
+
+    // 허용하지 않은 출처
+    let req = test::TestRequest::get()
+        .uri("/api/v1/event")
+        .insert_header(("Origin", "https://evil.example.com"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(!resp.headers().contains_key("access-control-allow-origin"));
+
+    // HTML 경로는 같은 출처에서만 사용
+    let req = test::TestRequest::get()
+        .uri("/check")
+        .insert_header(("Origin", PWA))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(!resp.headers().contains_key("access-control-allow-origin"));
+}
+
+#[actix_web::test]
+async fn preflight_requests_are_answered_without_handler() {
+    let state = cors_state();
+    let app = test::init_service(
+        App::new()
+            .wrap(from_fn(apply_cors))
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    let req = test::TestRequest::default()
+        .method(actix_web::http::Method::OPTIONS)
+        .uri("/api/v1/login")
+        .insert_header(("Origin", PWA))
+        .insert_header(("Access-Control-Request-Method", "POST"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    assert_eq!(
+        resp.headers().get("access-control-allow-origin").unwrap(),
+        PWA
+    );
+    assert_eq!(
+        resp.headers().get("access-control-allow-methods").unwrap(),
+        "GET, POST"
+    );
+    assert_eq!(resp.headers().get("access-control-max-age").unwrap(), "600");
+
+    // 허용하지 않은 메서드는 CORS 헤더 없이 응답
+    let req = test::TestRequest::default()
+        .method(actix_web::http::Method::OPTIONS)
+        .uri("/api/v1/login")
+        .insert_header(("Origin", PWA))
+        .insert_header(("Access-Control-Request-Method", "DELETE"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(!resp.headers().contains_key("access-control-allow-origin"));
+}
+
+#[actix_web::test]
+async fn wildcard_origin_never_allows_credentials() {
+    common::setup();
+    let config = Config {
+        cors: CorsConfig {
+            allowed_origins: vec!["*".to_string(), PWA.to_string()],
+            allow_credentials: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let state = AppState::new(config, common::stamp_list(vec![common::stamp("a")]));
+    let app = test::init_service(
+        App::new()
+            .wrap(from_fn(apply_cors))
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    // `*`로만 허용된 출처는 쿠키 없이 읽기만 허용
+    let req = test::TestRequest::get()
+        .uri("/api/v1/event")
+        .insert_header(("Origin", "https://evil.example.com"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(
+        resp.headers().get("access-control-allow-origin").unwrap(),
+        "*"
+    );
+    assert!(!resp
+        .headers()
+        .contains_key("access-control-allow-credentials"));
+
+    // 목록에 직접 적은 출처는 쿠키를 허용
+    let req = test::TestRequest::get()
+        .uri("/api/v1/event")
+        .insert_header(("Origin", PWA))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(
+        resp.headers().get("access-control-allow-origin").unwrap(),
+        PWA
+    );
+    assert_eq!(
+        resp.headers()
+            .get("access-control-allow-credentials")
+            .unwrap(),
+        "true"
+    );
+}
@@ -0,0 +1,97 @@
+use gj_stamptour::{
+    config::Config,
+    crypto::{configure, parse_key, seal, unseal, ENCRYPTED_PREFIX},
+    state::{Guestbook, StampHistory, StampUserInfo, UserList},
+    storage::{from_database, to_database, DatabaseFormat},
+};
+use serde_json::{json, to_value};
+use std::collections::{BTreeMap, HashMap};
+
+const KEY: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+
+#[test]
+fn user_status_is_encrypted_as_a_whole() {
+    let key = parse_key(KEY).unwrap();
+    let user_list = UserList {
+        users: BTreeMap::from([("u1".to_string(), "홍길동".to_string())]),
+        ..Default::default()
+    };
+
+    let sealed = seal("user_status", to_value(&user_list).unwrap(), &key);
+    assert_eq!(sealed["encrypted"], "aes-256-gcm");
+    assert!(!sealed.to_string().contains("홍길동"));
+
+    let opened: UserList =
+        serde_json::from_value(unseal(sealed.clone(), Some(&key)).unwrap()).unwrap();
+    assert_eq!(opened.users["u1"], "홍길동");
+
+    let other_key = parse_key(&"11".repeat(32)).unwrap();
+    assert!(unseal(sealed.clone(), Some(&other_key)).is_err());
+    assert!(unseal(sealed, None)
+        .unwrap_err()
+        .contains("STAMPTOUR_ENCRYPTION_KEY"));
+}
+
+#[test]
+fn stamp_status_keeps_stamp_ids_readable() {
+    let key = parse_key(KEY).unwrap();
+    let history = StampHistory::from(HashMap::from([(
+        "booth-1".to_string(),
+        vec![StampUserInfo {
+            user_name: "홍길동".to_string(),
+            user_id: "u1".to_string(),
+            timestamp: "2024-10-05 10:00:00 UTC".to_string(),
+            seq: 0,
+        }],
+    )]));
+
+    let sealed = seal("stamp_status", to_value(&history).unwrap(), &key);
+    let entry = &sealed["stamp_history"]["booth-1"][0];
+    assert!(entry["user_name"]
+        .as_str()
+        .unwrap()
+        .starts_with(ENCRYPTED_PREFIX));
+    assert!(entry["user_id"]
+        .as_str()
+        .unwrap()
+        .starts_with(ENCRYPTED_PREFIX));
+    assert_eq!(entry["timestamp"], "2024-10-05 10:00:00 UTC");
+
+    let opened: StampHistory = serde_json::from_value(unseal(sealed, Some(&key)).unwrap()).unwrap();
+    assert!(opened.collected_by("u1").contains("booth-1"));
+
+    // 암호화를 켜기 전에 저장한 파일도 그대로 읽음
+    let plain = to_value(&history).unwrap();
+    assert_eq!(unseal(plain.clone(), Some(&key)).unwrap(), plain);
+}
+
+#[test]
+fn rejects_short_keys() {
+    assert!(parse_key("c2hvcnQ=").is_err());
+    assert!(parse_key("AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=").is_ok());
+}
+
+#[test]
+fn binary_files_are_encrypted_after_compression() {
+    let user_list = UserList {
+        users: BTreeMap::from([("u1".to_string(), "홍길동".to_string())]),
+        ..Default::default()
+    };
+    let config: Config = serde_json::from_value(json!({ "encryption_key": KEY })).unwrap();
+    configure(&config).unwrap();
+
+    let contents = to_database("user_status", &user_list, DatabaseFormat::Binary).unwrap();
+    assert!(contents.starts_with(b"GJE1"));
+    assert_eq!(DatabaseFormat::detect(&contents), DatabaseFormat::Binary);
+    let opened: UserList = from_database(&contents).unwrap();
+    assert_eq!(opened.users["u1"], "홍길동");
+
+    // 개인정보가 없는 파일은 압축만 함
+    let guestbook = to_database("guestbook", Guestbook::default(), DatabaseFormat::Binary).unwrap();
+    assert!(!guestbook.starts_with(b"GJE1"));
+
+    configure(&Config::default()).unwrap();
+    assert!(from_database::<UserList>(&contents)
+        .unwrap_err()
+        .contains("STAMPTOUR_ENCRYPTION_KEY"));
+}
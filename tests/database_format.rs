@@ -0,0 +1,207 @@
+mod common;
+
+use actix_web::{test, App};
+use gj_stamptour::{
+    backup::{list_snapshots, read_snapshot, BackupConfig},
+    config::Config,
+    handlers::admin_routes,
+    state::{
+        AdminHistory, AdminOperation, AuditLog, AuditRecord, Command, CompletionList, DeletedUser,
+        Reloadable, StampHistory, StampUserInfo, UndoAction, UserList,
+    },
+    storage::{from_database, load_database, to_database, write_database, DatabaseFormat},
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, to_value};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+    time::Instant,
+};
+
+/// 비어 있는 필드와 채워진 필드, 모든 되돌리기 종류를 담은 관리자 작업 기록입니다.
+fn admin_history() -> AdminHistory {
+    let entry = StampUserInfo {
+        user_name: "홍길동".to_string(),
+        user_id: "u1".to_string(),
+        timestamp: "2024-10-05 10:00:00 UTC".to_string(),
+        seq: 3,
+    };
+    let operation = |command: &str, undo| AdminOperation {
+        command: command.to_string(),
+        timestamp: "2024-10-05T10:00:00+09:00".to_string(),
+        undo,
+    };
+    AdminHistory {
+        entries: vec![
+            operation(
+                "delete user u2",
+                UndoAction::DeleteUser {
+                    user_id: "u2".to_string(),
+                },
+            ),
+            operation(
+                "revoke u1 booth-1",
+                UndoAction::RevokeStamp {
+                    stamp_id: "booth-1".to_string(),
+                    entry: entry.clone(),
+                },
+            ),
+            operation(
+                "reset user u1",
+                UndoAction::ResetProgress {
+                    user_id: "u1".to_string(),
+                    stamps: vec![("booth-1".to_string(), entry)],
+                    completions: vec![],
+                    achievements: vec![],
+                    completion_code: None,
+                },
+            ),
+        ],
+    }
+}
+
+/// 저장하고 다시 읽은 값이 같은지 JSON으로 비교합니다.
+fn round_trip<T: Serialize + DeserializeOwned>(file_name: &str, data: &T, format: DatabaseFormat) {
+    let contents = to_database(file_name, data, format).unwrap();
+    assert_eq!(DatabaseFormat::detect(&contents), format);
+    write_database(file_name, format, &contents).unwrap();
+    let loaded: T = load_database(file_name, &BackupConfig::default()).unwrap();
+    assert_eq!(to_value(&loaded).unwrap(), to_value(data).unwrap());
+}
+
+#[actix_web::test]
+async fn binary_database_round_trips_and_replaces_json() {
+    let dir = common::setup().join("resources/database");
+    let user_list = UserList {
+        users: BTreeMap::from([("u1".to_string(), "홍길동".to_string())]),
+        codes: BTreeMap::new(),
+        deleted: BTreeMap::from([(
+            "u2".to_string(),
+            DeletedUser {
+                user_name: "".to_string(),
+                user_code: None,
+                reason: "중복 등록".to_string(),
+                deleted_at: "2024-10-05T10:00:00+09:00".to_string(),
+                completion_code: Some("ABC123".to_string()),
+                ..Default::default()
+            },
+        )]),
+    };
+    let audit_log = AuditLog {
+        entries: vec![
+            AuditRecord {
+                action: "revoke".to_string(),
+                user_id: "u1".to_string(),
+                stamp_id: Some("booth-1".to_string()),
+                actor: "staff".to_string(),
+                reason: "".to_string(),
+                timestamp: "2024-10-05T10:00:00+09:00".to_string(),
+            },
+            AuditRecord {
+                action: "reset".to_string(),
+                user_id: "u1".to_string(),
+                stamp_id: None,
+                actor: "admin".to_string(),
+                reason: "요청".to_string(),
+                timestamp: "2024-10-05T11:00:00+09:00".to_string(),
+            },
+        ],
+    };
+
+    round_trip("format_users", &user_list, DatabaseFormat::Json);
+    // 바이너리로 바꾸어 저장하면 JSON 파일은 지우고, 불러올 때 형식을 판별함
+    round_trip("format_users", &user_list, DatabaseFormat::Binary);
+    assert!(dir.join("format_users.bin").exists());
+    assert!(!dir.join("format_users.json").exists());
+    assert!(!dir.join("format_users.json.sha256").exists());
+
+    // JSON에서 생략하는 빈 필드와 `kind`로 구분하는 되돌리기 작업도 바이너리 형식으로 읽을 수 있음
+    round_trip("format_audit", &audit_log, DatabaseFormat::Binary);
+    round_trip("format_admin", &admin_history(), DatabaseFormat::Binary);
+    round_trip(
+        "format_completions",
+        &CompletionList::default(),
+        DatabaseFormat::Binary,
+    );
+    let json = to_database("format_audit", &audit_log, DatabaseFormat::Json).unwrap();
+    assert_eq!(
+        String::from_utf8(json).unwrap().matches("stamp_id").count(),
+        1
+    );
+}
+
+#[actix_web::test]
+async fn binary_database_is_smaller_and_faster_than_json() {
+    let stamp_history = StampHistory::from(
+        (0..100)
+            .map(|stamp| {
+                let entries = (0..200)
+                    .map(|user| StampUserInfo {
+                        user_name: format!("참가자 {}", user),
+                        user_id: format!("{:08x}-0000-4000-8000-{:012x}", user, stamp),
+                        timestamp: format!("2024-10-05 10:{:02}:{:02} UTC", user % 60, stamp % 60),
+                        seq: 0,
+                    })
+                    .collect();
+                (format!("booth-{}", stamp), entries)
+            })
+            .collect::<HashMap<_, _>>(),
+    );
+
+    let measure = |format| {
+        let started = Instant::now();
+        let contents = to_database("stamp_status", &stamp_history, format).unwrap();
+        let loaded: StampHistory = from_database(&contents).unwrap();
+        assert_eq!(
+            loaded
+                .collected_by("00000007-0000-4000-8000-000000000063")
+                .len(),
+            1
+        );
+        (contents.len(), started.elapsed())
+    };
+    let (json_size, json_time) = measure(DatabaseFormat::Json);
+    let (binary_size, binary_time) = measure(DatabaseFormat::Binary);
+    println!(
+        "20000 stamp records: json {} bytes in {:?}, binary {} bytes in {:?}",
+        json_size, json_time, binary_size, binary_time
+    );
+    assert!(binary_size * 5 < json_size);
+    assert!(binary_time < json_time);
+}
+
+#[actix_web::test]
+async fn backups_follow_configured_format() {
+    let state = common::test_state();
+    common::register(&state, "u1", "visitor");
+    let dir = common::setup().join("backups-binary");
+    let config: Config = serde_json::from_value(json!({
+        "backup": { "dir": dir.to_string_lossy() },
+        "persistence": { "format": "binary" }
+    }))
+    .unwrap();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .app_data(actix_web::web::Data::new(Reloadable::new(config.clone())))
+            .configure(admin_routes),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/admin")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .set_json(json!({ "command": "backup", "output": "" }))
+        .to_request();
+    let output: Command = test::call_and_read_body_json(&app, req).await;
+    assert!(output.output.starts_with("Backup saved (7 files"));
+
+    let timestamps = list_snapshots(Path::new(&dir), "user_status");
+    assert_eq!(timestamps.len(), 1);
+    assert!(dir
+        .join(format!("user_status-{}.bin", timestamps[0]))
+        .exists());
+    let snapshot = read_snapshot(&config.backup, &timestamps[0]).unwrap();
+    assert_eq!(snapshot.user_list.unwrap().users["u1"], "visitor");
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use actix_web::{
+    body::to_bytes,
+    http::{
+        header::{ALLOW, RETRY_AFTER},
+        StatusCode,
+    },
+    test, App, ResponseError,
+};
+use gj_stamptour::{error::AppError, handlers::routes};
+
+#[actix_web::test]
+async fn page_errors_map_to_status_and_headers() {
+    common::setup();
+    assert_eq!(AppError::NotFound.status_code(), StatusCode::NOT_FOUND);
+    assert_eq!(
+        AppError::Unauthorized.status_code(),
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        AppError::Template("missing".to_string()).status_code(),
+        StatusCode::INTERNAL_SERVER_ERROR
+    );
+
+    let resp = AppError::MethodNotAllowed("GET").error_response();
+    assert_eq!(resp.status(), StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(resp.headers().get(ALLOW).unwrap(), "GET");
+
+    let resp = AppError::TooManyRequests(7).error_response();
+    assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(resp.headers().get(RETRY_AFTER).unwrap(), "7");
+
+    let resp = AppError::Unavailable.error_response();
+    assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert!(resp.headers().contains_key(RETRY_AFTER));
+}
+
+#[actix_web::test]
+async fn api_errors_are_json() {
+    common::setup();
+    let resp = AppError::Missing("Team not found".to_string()).error_response();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    let body = to_bytes(resp.into_body()).await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body, serde_json::json!({ "error": "Team not found" }));
+
+    let resp = AppError::Validation("Invalid".to_string()).error_response();
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn internal_errors_do_not_leak_details() {
+    common::setup();
+    let resp = AppError::Storage("resources/user_list.json: permission denied".to_string())
+        .error_response();
+    assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let body = to_bytes(resp.into_body()).await.unwrap();
+    assert!(!String::from_utf8_lossy(&body).contains("user_list"));
+}
+
+#[actix_web::test]
+async fn missing_pages_get_404() {
+    common::setup();
+    let state = common::test_state();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    let req = test::TestRequest::get().uri("/no-such-page").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    let req = test::TestRequest::get()
+        .uri("/api/v1/teams/NOPE")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["error"], "Team not found");
+}
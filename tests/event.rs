@@ -0,0 +1,69 @@
+mod common;
+
+use actix_web::{test, App};
+use chrono::{Duration, Utc};
+use gj_stamptour::{
+    config::Config,
+    event::EventInfo,
+    handlers::{admin_routes, routes},
+    state::AppState,
+};
+use serde_json::json;
+
+#[actix_web::test]
+async fn event_info_follows_server_gating() {
+    common::setup();
+    let mut config = Config::default();
+    config.share.festival_name = "가을 축제".to_string();
+    config.closes_at = Some((Utc::now() + Duration::hours(2)).to_rfc3339());
+    config.event.dates = vec!["2024-10-18".to_string()];
+    config.event.booth_hours = Some("10:00-17:00".to_string());
+    let mut short = common::stamp("a");
+    short.stampHours = Some("10:00-12:00".to_string());
+    let mut secret = common::stamp("s");
+    secret.stampHidden = true;
+    let state = AppState::new(
+        config,
+        common::stamp_list(vec![short, common::stamp("b"), secret]),
+    );
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes)
+            .configure(routes),
+    )
+    .await;
+
+    let req = test::TestRequest::get().uri("/api/v1/event").to_request();
+    let event: EventInfo = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(event.name, "가을 축제");
+    assert_eq!(event.dates, vec!["2024-10-18"]);
+    assert!(!event.closed);
+    let remaining = event.remaining_secs.unwrap();
+    assert!(remaining > 7000 && remaining <= 7200);
+    assert_eq!(
+        event.booth_hours,
+        [
+            ("a".to_string(), "10:00-12:00".to_string()),
+            ("b".to_string(), "10:00-17:00".to_string()),
+        ]
+        .into()
+    );
+    assert!(event.announcement.is_none());
+
+    for command in ["announce 30 정문 혼잡", "close tour"] {
+        let req = test::TestRequest::post()
+            .uri("/admin")
+            .peer_addr("127.0.0.1:40000".parse().unwrap())
+            .set_json(json!({ "command": command, "output": "" }))
+            .to_request();
+        test::call_service(&app, req).await;
+    }
+
+    // 관리자가 일찍 마감하면 마감 시각 전이라도 마감으로 표시
+    let req = test::TestRequest::get().uri("/api/event").to_request();
+    let event: EventInfo = test::call_and_read_body_json(&app, req).await;
+    assert!(event.closed);
+    assert_eq!(event.remaining_secs, Some(0));
+    assert_eq!(event.announcement.unwrap().message, "정문 혼잡");
+}
@@ -0,0 +1,130 @@
+mod common;
+
+use actix_web::{cookie::Cookie, http::StatusCode, test, App};
+use gj_stamptour::{
+    config::Config,
+    event::EventInfo,
+    features::Feature,
+    handlers::{admin_routes, routes},
+    state::{AppState, Command, Coordinates},
+};
+use serde_json::{json, Value};
+
+async fn admin<S, B>(app: &S, command: &str) -> String
+where
+    S: actix_web::dev::Service<
+        actix_http::Request,
+        Response = actix_web::dev::ServiceResponse<B>,
+        Error = actix_web::Error,
+    >,
+    B: actix_web::body::MessageBody,
+{
+    let req = test::TestRequest::post()
+        .uri("/admin")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .set_json(json!({ "command": command, "output": "" }))
+        .to_request();
+    let output: Command = test::call_and_read_body_json(app, req).await;
+    output.output
+}
+
+#[actix_web::test]
+async fn admin_toggle_overrides_config() {
+    common::setup();
+    let config: Config = serde_json::from_value(json!({
+        "features": { "leaderboard": false }
+    }))
+    .unwrap();
+    let state = AppState::new(config, common::stamp_list(vec![common::stamp("a")]));
+    common::register(&state, "u1", "visitor");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes)
+            .configure(routes),
+    )
+    .await;
+
+    for uri in ["/api/v1/leaderboard", "/api/v1/teams/leaderboard"] {
+        let req = test::TestRequest::get().uri(uri).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["error"], "leaderboard is disabled");
+    }
+    assert_eq!(
+        admin(&app, "features").await,
+        "leaderboard: off\nmissions: on\ngeofencing: on"
+    );
+
+    assert_eq!(
+        admin(&app, "feature leaderboard on").await,
+        "Feature leaderboard turned on"
+    );
+    assert_eq!(
+        admin(&app, "feature missions off").await,
+        "Feature missions turned off"
+    );
+    let req = test::TestRequest::get()
+        .uri("/api/v1/leaderboard")
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+    let req = test::TestRequest::get()
+        .uri("/api/v1/missions")
+        .cookie(Cookie::new("user_id", "u1"))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::SERVICE_UNAVAILABLE
+    );
+
+    let req = test::TestRequest::get().uri("/api/v1/event").to_request();
+    let event: EventInfo = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(
+        event.features,
+        [
+            (Feature::Leaderboard, true),
+            (Feature::Missions, false),
+            (Feature::Geofencing, true),
+        ]
+        .into()
+    );
+
+    // default는 관리자가 바꾼 값을 지우고 설정을 따름
+    assert_eq!(
+        admin(&app, "feature leaderboard default").await,
+        "Feature leaderboard follows config (off)"
+    );
+    assert!(admin(&app, "feature chat on").await.starts_with("Usage:"));
+    assert!(admin(&app, "feature missions").await.starts_with("Usage:"));
+}
+
+#[actix_web::test]
+async fn geofencing_can_be_switched_off() {
+    common::setup();
+    let config: Config = serde_json::from_value(json!({
+        "geofence": { "enabled": true, "require_location": true }
+    }))
+    .unwrap();
+    let mut fenced = common::stamp("a");
+    fenced.stampCoordinates = Some(Coordinates {
+        latitude: 35.15,
+        longitude: 126.85,
+    });
+    fenced.stampRadius = Some(50);
+    let state = AppState::new(config, common::stamp_list(vec![fenced]));
+    common::register(&state, "u1", "visitor");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes)
+            .configure(routes),
+    )
+    .await;
+
+    // 위치를 보내지 않은 확인은 거절
+    assert_ne!(common::collect(&app, "u1", "a").await, StatusCode::OK);
+
+    admin(&app, "feature geofencing off").await;
+    assert_eq!(common::collect(&app, "u1", "a").await, StatusCode::OK);
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use actix_web::{cookie::Cookie, http::StatusCode, test, App};
+use gj_stamptour::{
+    handlers::{admin_routes, routes},
+    state::Command,
+};
+use serde_json::{json, Value};
+
+#[actix_web::test]
+async fn feedback_is_stored_per_user_and_exported() {
+    let dir = common::setup();
+    let state = common::test_state();
+    common::register(&state, "u1", "홍길동");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes)
+            .configure(routes),
+    )
+    .await;
+
+    // 로그인하지 않은 후기는 거절
+    let req = test::TestRequest::post()
+        .uri("/feedback")
+        .set_json(json!({ "rating": 5 }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    for body in [
+        json!({ "rating": 0 }),
+        json!({ "rating": 6 }),
+        json!({ "rating": 3, "comment": "가".repeat(1001) }),
+    ] {
+        let req = test::TestRequest::post()
+            .uri("/feedback")
+            .cookie(Cookie::new("user_id", "u1"))
+            .set_json(body)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+    assert!(state.feedback.lock().unwrap().entries.is_empty());
+
+    let req = test::TestRequest::post()
+        .uri("/feedback")
+        .cookie(Cookie::new("user_id", "u1"))
+        .set_json(json!({ "rating": 3 }))
+        .to_request();
+    let resp: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(resp, json!({ "saved": true, "replaced": false }));
+
+    // 다시 보내면 이전 후기를 바꿈
+    let req = test::TestRequest::post()
+        .uri("/api/v1/feedback")
+        .cookie(Cookie::new("user_id", "u1"))
+        .set_json(json!({ "rating": 5, "comment": " 재밌었어요, 또 올게요 " }))
+        .to_request();
+    let resp: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(resp["replaced"], true);
+    {
+        let feedback = state.feedback.lock().unwrap();
+        assert_eq!(feedback.entries.len(), 1);
+        assert_eq!(feedback.entries["u1"].rating, 5);
+        assert_eq!(feedback.entries["u1"].comment, "재밌었어요, 또 올게요");
+    }
+
+    let req = test::TestRequest::get()
+        .uri("/feedback")
+        .cookie(Cookie::new("user_id", "u1"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+    let req = test::TestRequest::post()
+        .uri("/admin")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .set_json(json!({ "command": "export feedback", "output": "" }))
+        .to_request();
+    let output: Command = test::call_and_read_body_json(&app, req).await;
+    assert!(output.output.starts_with("Feedback exported"));
+
+    let csv = std::fs::read_to_string(dir.join("resources/exports/feedback.csv")).unwrap();
+    let rows: Vec<&str> = csv.lines().collect();
+    assert_eq!(rows[0], "rating,comment,timestamp");
+    assert!(rows[1].starts_with("5,\"재밌었어요, 또 올게요\","));
+    assert_eq!(rows.len(), 2);
+    assert!(!csv.contains("u1") && !csv.contains("홍길동"));
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use actix_web::{cookie::Cookie, http::StatusCode, test, App};
+use gj_stamptour::{
+    config::Config,
+    handlers::routes,
+    state::{AppState, Coordinates},
+};
+use serde_json::json;
+
+fn geofence_state(require_location: bool) -> AppState {
+    common::setup();
+    let config: Config = serde_json::from_value(json!({
+        "geofence": { "enabled": true, "require_location": require_location }
+    }))
+    .unwrap();
+    let mut fenced = common::stamp("a");
+    fenced.stampCoordinates = Some(Coordinates {
+        latitude: 35.15,
+        longitude: 126.85,
+    });
+    fenced.stampRadius = Some(50);
+    let state = AppState::new(config, common::stamp_list(vec![fenced, common::stamp("b")]));
+    common::register(&state, "u1", "visitor");
+    state
+}
+
+fn pending(state: &AppState) -> Option<String> {
+    state
+        .user_stamp_list
+        .lock()
+        .unwrap()
+        .user_stamp_list
+        .remove("u1")
+}
+
+fn check(query: &str) -> actix_http::Request {
+    test::TestRequest::get()
+        .uri(&format!("/check?{}", query))
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .cookie(Cookie::new("user_id", "u1"))
+        .to_request()
+}
+
+#[actix_web::test]
+async fn scans_far_from_booth_are_rejected() {
+    let state = geofence_state(false);
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    for (query, expected) in [
+        // 부스에서 약 22m
+        ("s=a&lat=35.1502&lng=126.85&acc=5", Some("a")),
+        // 부스에서 약 1.1km
+        ("s=a&lat=35.16&lng=126.85&acc=5", None),
+        // 오차가 커도 반경의 두 배까지만 허용
+        ("s=a&lat=35.16&lng=126.85&acc=5000", None),
+        // 위치를 보내지 않으면 허용
+        ("s=a", Some("a")),
+        // 반경이 없는 스템프는 확인하지 않음
+        ("s=b&lat=0&lng=0", Some("b")),
+    ] {
+        let res = test::call_service(&app, check(query)).await;
+        assert_eq!(res.status(), StatusCode::TEMPORARY_REDIRECT);
+        assert_eq!(pending(&state).as_deref(), expected, "{}", query);
+    }
+}
+
+#[actix_web::test]
+async fn missing_location_is_rejected_when_required() {
+    let state = geofence_state(true);
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    for (query, expected) in [
+        ("s=a", None),
+        ("s=a&lat=abc&lng=126.85", None),
+        ("s=a&lat=35.15&lng=126.85", Some("a")),
+        ("s=b", Some("b")),
+    ] {
+        test::call_service(&app, check(query)).await;
+        assert_eq!(pending(&state).as_deref(), expected, "{}", query);
+    }
+}
@@ -0,0 +1,87 @@
+mod common;
+
+use actix_web::{http::StatusCode, test, App};
+use gj_stamptour::handlers::admin_routes;
+use serde_json::{json, Value};
+
+#[actix_web::test]
+async fn graphql_filters_users_and_history() {
+    let state = common::test_state();
+    common::register(&state, "u1", "Alice");
+    common::register(&state, "u2", "Bob");
+    state.user_list.lock().unwrap().assign_missing_codes();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes),
+    )
+    .await;
+    {
+        let stamp_history = &state.stamp_history;
+        for (stamp_id, user_id, user_name, timestamp) in [
+            ("a", "u1", "Alice", "2024-10-05 01:00:00 UTC"),
+            ("b", "u1", "Alice", "2024-10-05 03:00:00 UTC"),
+            ("a", "u2", "Bob", "2024-10-05 02:00:00 UTC"),
+        ] {
+            stamp_history.push(
+                stamp_id,
+                gj_stamptour::state::StampUserInfo {
+                    user_id: user_id.to_string(),
+                    user_name: user_name.to_string(),
+                    timestamp: timestamp.to_string(),
+                    seq: 0,
+                },
+            );
+        }
+    }
+
+    let query = |query: &str| {
+        test::TestRequest::post()
+            .uri("/graphql")
+            .peer_addr("127.0.0.1:40000".parse().unwrap())
+            .set_json(json!({ "query": query }))
+            .to_request()
+    };
+    let body: Value = test::call_and_read_body_json(
+        &app,
+        query("{ users(minCollected: 2) { userName collectedCount } stamps(ids: [\"a\"]) { stampId issued } }"),
+    )
+    .await;
+    assert_eq!(
+        body["data"],
+        json!({
+            "users": [{ "userName": "Alice", "collectedCount": 2 }],
+            "stamps": [{ "stampId": "a", "issued": 2 }],
+        })
+    );
+
+    // 기록은 시각 순서로 반환하고 기간으로 거를 수 있음
+    let body: Value = test::call_and_read_body_json(
+        &app,
+        query("{ history(since: \"2024-10-05T01:30:00Z\") { stampId userId } }"),
+    )
+    .await;
+    assert_eq!(
+        body["data"]["history"],
+        json!([{ "stampId": "a", "userId": "u2" }, { "stampId": "b", "userId": "u1" }])
+    );
+
+    let body: Value =
+        test::call_and_read_body_json(&app, query("{ timeseries(bucket: \"15x\") { buckets } }"))
+            .await;
+    assert!(body["errors"][0]["message"]
+        .as_str()
+        .unwrap()
+        .contains("bucket"));
+
+    let res = test::call_service(
+        &app,
+        test::TestRequest::post()
+            .uri("/graphql")
+            .peer_addr("10.0.0.5:40000".parse().unwrap())
+            .set_json(json!({ "query": "{ stats { participants } }" }))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+}
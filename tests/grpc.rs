@@ -0,0 +1,104 @@
+mod common;
+
+use gj_stamptour::{
+    config::Config,
+    grpc::{
+        proto::{stamp_tour_server::StampTour, RecordStampRequest, RegisterRequest, UserRequest},
+        StampTourService,
+    },
+};
+use serde_json::json;
+use tonic::{Code, Request};
+
+/// 직원 토큰을 메타데이터에 담은 요청을 만듭니다.
+fn authorized<T>(message: T) -> Request<T> {
+    let mut request = Request::new(message);
+    request
+        .metadata_mut()
+        .insert("authorization", "Bearer secret".parse().unwrap());
+    request
+}
+
+#[actix_web::test]
+async fn turnstile_registers_and_records_stamps() {
+    let state = common::test_state();
+    let config: Config = serde_json::from_value(json!({ "staff_token": "secret" })).unwrap();
+    state.config.replace(config);
+    let service = StampTourService::new(state.clone());
+
+    let user = service
+        .register(authorized(RegisterRequest {
+            user_name: "visitor".to_string(),
+            team_code: String::new(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(user.user_code.len(), 6);
+    assert!(state
+        .user_list
+        .lock()
+        .unwrap()
+        .users
+        .contains_key(&user.user_id));
+
+    let recorded = service
+        .record_stamp(authorized(RecordStampRequest {
+            user_code: user.user_code.to_lowercase(),
+            stamp_id: "b".to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(recorded.user_code, user.user_code);
+    assert_eq!(recorded.progress.unwrap().collected, vec!["b".to_string()]);
+    service
+        .record_stamp(authorized(RecordStampRequest {
+            user_code: user.user_code.clone(),
+            stamp_id: "a".to_string(),
+        }))
+        .await
+        .unwrap();
+    assert_eq!(state.stamp_history.entries("b")[0].user_id, user.user_id);
+
+    let progress = service
+        .get_progress(authorized(UserRequest {
+            user_code: user.user_code.clone(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(progress.collected_count, 2);
+    assert_eq!(progress.total_count, 3);
+
+    // 기록은 찍은 순서대로 반환
+    let history = service
+        .get_history(authorized(UserRequest {
+            user_code: user.user_code.clone(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    let stamp_ids: Vec<&str> = history
+        .entries
+        .iter()
+        .map(|entry| entry.stamp_id.as_str())
+        .collect();
+    assert_eq!(stamp_ids, ["b", "a"]);
+
+    let missing = service
+        .record_stamp(authorized(RecordStampRequest {
+            user_code: user.user_code.clone(),
+            stamp_id: "x".to_string(),
+        }))
+        .await
+        .unwrap_err();
+    assert_eq!(missing.code(), Code::NotFound);
+    let unauthorized = service
+        .get_progress(Request::new(UserRequest {
+            user_code: user.user_code.clone(),
+        }))
+        .await
+        .unwrap_err();
+    assert_eq!(unauthorized.code(), Code::Unauthenticated);
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use actix_web::{cookie::Cookie, http::StatusCode, test, App};
+use gj_stamptour::{
+    handlers::{admin_routes, routes},
+    state::Command,
+};
+use serde_json::{json, Value};
+
+#[actix_web::test]
+async fn guestbook_entries_show_after_moderation() {
+    let state = common::test_state();
+    common::register(&state, "u1", "<홍길동>");
+    common::register(&state, "u2", "visitor");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes)
+            .configure(routes),
+    )
+    .await;
+
+    let post = |user_id: &str, message: String| {
+        test::TestRequest::post()
+            .uri("/guestbook")
+            .cookie(Cookie::new("user_id", user_id.to_string()))
+            .set_json(json!({ "message": message }))
+            .to_request()
+    };
+    let admin = |command: &str| {
+        test::TestRequest::post()
+            .uri("/admin")
+            .peer_addr("127.0.0.1:40000".parse().unwrap())
+            .set_json(json!({ "command": command, "output": "" }))
+            .to_request()
+    };
+
+    let resp = test::call_service(&app, post("u1", "  ".to_string())).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let resp = test::call_service(&app, post("u1", "가".repeat(201))).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let resp: Value =
+        test::call_and_read_body_json(&app, post("u1", "즐거운 <축제>".to_string())).await;
+    assert_eq!(resp, json!({ "entry_id": 1, "status": "pending" }));
+
+    // 간격 안에 다시 남기면 429
+    let resp = test::call_service(&app, post("u1", "또 왔어요".to_string())).await;
+    assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert!(resp.headers().contains_key("retry-after"));
+
+    let resp: Value = test::call_and_read_body_json(&app, post("u2", "최고".to_string())).await;
+    assert_eq!(resp["entry_id"], 2);
+
+    // 승인 전에는 화면에 나타나지 않음
+    let req = test::TestRequest::get().uri("/guestbook").to_request();
+    let html = String::from_utf8(test::call_and_read_body(&app, req).await.to_vec()).unwrap();
+    assert!(!html.contains("축제") && !html.contains("최고"));
+
+    let output: Command = test::call_and_read_body_json(&app, admin("guestbook queue")).await;
+    assert_eq!(
+        output.output,
+        "#1 <홍길동>: 즐거운 <축제>\n#2 visitor: 최고"
+    );
+    let output: Command = test::call_and_read_body_json(&app, admin("guestbook approve 1")).await;
+    assert_eq!(output.output, "Guestbook entry #1 approved");
+    let output: Command = test::call_and_read_body_json(&app, admin("guestbook hide 2")).await;
+    assert_eq!(output.output, "Guestbook entry #2 hidden");
+    let output: Command = test::call_and_read_body_json(&app, admin("guestbook hide 9")).await;
+    assert_eq!(output.output, "Guestbook entry #9 not found");
+    let output: Command = test::call_and_read_body_json(&app, admin("guestbook queue")).await;
+    assert_eq!(output.output, "No pending guestbook entries");
+
+    let req = test::TestRequest::get().uri("/guestbook").to_request();
+    let html = String::from_utf8(test::call_and_read_body(&app, req).await.to_vec()).unwrap();
+    assert!(html.contains("<strong>&lt;홍길동&gt;</strong> 즐거운 &lt;축제&gt;"));
+    assert!(!html.contains("최고"));
+
+    let req = test::TestRequest::delete().uri("/guestbook").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(resp.headers().get("allow").unwrap(), "GET, POST");
+
+    // 로그인하지 않으면 남길 수 없음
+    let req = test::TestRequest::post()
+        .uri("/api/v1/guestbook")
+        .set_json(json!({ "message": "hi" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+}
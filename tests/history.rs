@@ -0,0 +1,189 @@
+use gj_stamptour::{
+    staff::revoke_stamp,
+    state::{AuditLog, StampHistory, StampUserInfo},
+};
+use serde_json::json;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    thread,
+};
+
+fn entry(user_id: &str) -> StampUserInfo {
+    StampUserInfo {
+        user_name: "visitor".to_string(),
+        user_id: user_id.to_string(),
+        timestamp: "2024-10-05 10:00:00 UTC".to_string(),
+        seq: 0,
+    }
+}
+
+#[test]
+fn stamps_are_recorded_per_booth_concurrently() {
+    let history = Arc::new(StampHistory::from(HashMap::from([
+        ("a".to_string(), Vec::new()),
+        ("b".to_string(), Vec::new()),
+    ])));
+
+    // 한 부스의 기록을 잠가도 다른 부스의 기록은 기다리지 않음
+    history.with_shard("a", |shard| {
+        let _held = shard.lock().unwrap();
+        let other = Arc::clone(&history);
+        thread::spawn(move || other.push("b", entry("u1")))
+            .join()
+            .unwrap();
+    });
+    assert_eq!(history.issued("b"), 1);
+
+    let handles: Vec<_> = (0..8)
+        .map(|i| {
+            let history = Arc::clone(&history);
+            thread::spawn(move || {
+                let stamp_id = if i % 2 == 0 { "a" } else { "c" };
+                for n in 0..50 {
+                    history.push(stamp_id, entry(&format!("u{}-{}", i, n)));
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert_eq!(history.issued("a"), 200);
+    assert_eq!(history.issued("c"), 200);
+    assert_eq!(history.stamp_ids(), vec!["a", "b", "c"]);
+
+    // 한정 수량은 확인과 기록을 같은 잠금 안에서 함
+    let handles: Vec<_> = (0..8)
+        .map(|i| {
+            let history = Arc::clone(&history);
+            thread::spawn(move || history.push_if("d", entry(&format!("u{}", i)), |n| n < 3))
+        })
+        .collect();
+    let issued = handles
+        .into_iter()
+        .map(|handle| handle.join().unwrap())
+        .filter(|issued| *issued)
+        .count();
+    assert_eq!(issued, 3);
+    assert_eq!(history.issued("d"), 3);
+}
+
+#[test]
+fn stamp_status_format_is_unchanged() {
+    let history = StampHistory::default();
+    history.push("a", entry("u1"));
+    history.insert_stamp("b");
+
+    let value = serde_json::to_value(&history).unwrap();
+    assert_eq!(
+        value,
+        json!({
+            "stamp_history": {
+                "a": [{
+                    "user_name": "visitor",
+                    "user_id": "u1",
+                    "timestamp": "2024-10-05 10:00:00 UTC",
+                    "seq": 1
+                }],
+                "b": []
+            },
+            "last_seq": 1
+        })
+    );
+    let loaded: StampHistory = serde_json::from_value(value.clone()).unwrap();
+    assert_eq!(serde_json::to_value(&loaded).unwrap(), value);
+}
+
+#[test]
+fn sequence_numbers_continue_after_reload() {
+    let history = StampHistory::default();
+    history.push("a", entry("u1"));
+    history.push("b", entry("u2"));
+    assert_eq!(history.entries("b")[0].seq, 2);
+
+    // 다시 불러와도 가장 큰 순번 다음부터 발급
+    let loaded: StampHistory =
+        serde_json::from_value(serde_json::to_value(&history).unwrap()).unwrap();
+    assert_eq!(loaded.last_seq(), 2);
+    loaded.push("a", entry("u3"));
+    assert_eq!(loaded.entries("a")[1].seq, 3);
+
+    // 되돌리기 등으로 다시 넣는 기록은 원래 순번을 유지
+    loaded.push(
+        "c",
+        StampUserInfo {
+            seq: 2,
+            ..entry("u2")
+        },
+    );
+    assert_eq!(loaded.entries("c")[0].seq, 2);
+    assert_eq!(loaded.last_seq(), 3);
+}
+
+#[test]
+fn sequence_numbers_are_not_reused_after_revoke_and_reload() {
+    let history = StampHistory::default();
+    history.push("a", entry("u1"));
+    history.push("a", entry("u2"));
+    let mut audit_log = AuditLog::default();
+    let revoked = revoke_stamp(&history, &mut audit_log, "u2", "a", "admin", "test").unwrap();
+    assert_eq!(revoked.seq, 2);
+
+    // 가장 최근 기록이 지워져도 저장된 마지막 순번부터 이어서 발급
+    let loaded: StampHistory =
+        serde_json::from_value(serde_json::to_value(&history).unwrap()).unwrap();
+    assert!(loaded.next_seq() > revoked.seq);
+    assert!(history.clone().next_seq() > revoked.seq);
+}
+
+#[test]
+fn legacy_entries_get_sequence_numbers_in_time_order() {
+    let legacy = json!({
+        "stamp_history": {
+            "a": [{ "user_name": "v", "user_id": "u2", "timestamp": "2024-10-05 11:00:00 UTC" }],
+            "b": [{ "user_name": "v", "user_id": "u1", "timestamp": "2024-10-05 10:00:00 UTC" }]
+        }
+    });
+    let loaded: StampHistory = serde_json::from_value(legacy).unwrap();
+    assert_eq!(loaded.entries("b")[0].seq, 1);
+    assert_eq!(loaded.entries("a")[0].seq, 2);
+    assert_eq!(loaded.next_seq(), 3);
+}
+
+#[test]
+fn replace_is_never_seen_half_done() {
+    let snapshot = |user_id: &str| {
+        StampHistory::from(
+            (0..16)
+                .map(|stamp| (format!("booth-{}", stamp), vec![entry(user_id)]))
+                .collect::<HashMap<_, _>>(),
+        )
+    };
+    let history = Arc::new(snapshot("before"));
+
+    // 복원하는 동안 읽은 기록은 모두 바꾸기 전이거나 모두 바꾼 뒤여야 함
+    let readers: Vec<_> = (0..4)
+        .map(|_| {
+            let history = Arc::clone(&history);
+            thread::spawn(move || {
+                for _ in 0..500 {
+                    let map = history.to_map();
+                    assert_eq!(map.len(), 16);
+                    let users: HashSet<&str> = map
+                        .values()
+                        .flatten()
+                        .map(|entry| entry.user_id.as_str())
+                        .collect();
+                    assert_eq!(users.len(), 1, "mixed history: {:?}", users);
+                }
+            })
+        })
+        .collect();
+    for round in 0..500 {
+        history.replace(snapshot(if round % 2 == 0 { "after" } else { "before" }));
+    }
+    for reader in readers {
+        reader.join().unwrap();
+    }
+}
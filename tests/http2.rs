@@ -0,0 +1,96 @@
+mod common;
+
+use actix_web::{App, HttpServer};
+use gj_stamptour::{
+    handlers::routes,
+    tls::{certified_key, rustls_config, CertResolver},
+};
+use std::sync::Arc;
+
+#[actix_web::test]
+async fn public_routes_are_served_over_h2c() {
+    let state = common::test_state();
+    let app_state = state.clone();
+    let server = HttpServer::new(move || {
+        App::new()
+            .configure(|cfg| app_state.register(cfg))
+            .configure(routes)
+    })
+    .workers(1)
+    .disable_signals()
+    .bind_auto_h2c(("127.0.0.1", 0))
+    .unwrap();
+    let port = server.addrs()[0].port();
+    let server = server.run();
+    let handle = server.handle();
+    actix_rt::spawn(server);
+
+    let client = reqwest::Client::builder()
+        .http2_prior_knowledge()
+        .build()
+        .unwrap();
+    let url = format!("http://127.0.0.1:{}/check", port);
+    let resp = client.get(&url).send().await.unwrap();
+    assert_eq!(resp.version(), reqwest::Version::HTTP_2);
+
+    // 같은 포트에서 HTTP/1.1 클라이언트도 그대로 접속
+    let resp = reqwest::get(&url).await.unwrap();
+    assert_eq!(resp.version(), reqwest::Version::HTTP_11);
+
+    handle.stop(false).await;
+}
+
+/// 클라이언트가 제안한 ALPN 프로토콜로 TLS 연결을 맺고 서버가 고른 프로토콜을 반환합니다.
+fn negotiate(port: u16, protocols: &[u8]) -> Option<Vec<u8>> {
+    let stream = common::tls_connect(port, protocols);
+    stream.ssl().selected_alpn_protocol().map(|p| p.to_vec())
+}
+
+#[actix_web::test]
+async fn tls_negotiates_http2_with_alpn() {
+    let state = common::test_state();
+    let app_state = state.clone();
+    let (cert_pem, key_pem) = common::self_signed("localhost", 90);
+    let resolver = Arc::new(CertResolver::default());
+    resolver.set(certified_key(&cert_pem, &key_pem).unwrap());
+    let server = HttpServer::new(move || {
+        App::new()
+            .configure(|cfg| app_state.register(cfg))
+            .configure(routes)
+    })
+    .workers(1)
+    .disable_signals()
+    .bind_rustls_0_23(("127.0.0.1", 0), rustls_config(resolver))
+    .unwrap();
+    let port = server.addrs()[0].port();
+    let server = server.run();
+    let handle = server.handle();
+    actix_rt::spawn(server);
+
+    let selected = actix_web::rt::task::spawn_blocking(move || {
+        (
+            negotiate(port, b"\x02h2\x08http/1.1"),
+            negotiate(port, b"\x08http/1.1"),
+        )
+    })
+    .await
+    .unwrap();
+    assert_eq!(selected.0.as_deref(), Some(&b"h2"[..]));
+    // HTTP/2를 지원하지 않는 클라이언트는 HTTP/1.1로 접속
+    assert_eq!(selected.1.as_deref(), Some(&b"http/1.1"[..]));
+
+    // TLS 연결에서도 공개 경로를 그대로 처리 (로그인하지 않은 요청은 스템프 페이지로 이동)
+    let client = reqwest::Client::builder()
+        .danger_accept_invalid_certs(true)
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap();
+    let resp = client
+        .get(format!("https://127.0.0.1:{}/check", port))
+        .send()
+        .await
+        .unwrap();
+    assert!(resp.status().is_redirection());
+
+    handle.stop(false).await;
+}
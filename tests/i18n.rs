@@ -0,0 +1,121 @@
+mod common;
+
+use actix_web::{cookie::Cookie, middleware::from_fn, test, App};
+use gj_stamptour::{
+    handlers::routes,
+    i18n::{from_accept_language, negotiate, select_locale},
+    state::StampInfo,
+    storage::resource_path,
+};
+use std::{collections::BTreeMap, fs};
+
+#[actix_web::test]
+async fn negotiates_locale() {
+    assert_eq!(from_accept_language("ja-JP,ja;q=0.9,en;q=0.8"), Some("ja"));
+    assert_eq!(
+        from_accept_language("fr-FR, en-US;q=0.7, ko;q=0.9"),
+        Some("ko")
+    );
+    assert_eq!(from_accept_language("fr-FR, de;q=0.5"), None);
+    assert_eq!(negotiate(Some("en"), Some("ja"), Some("ko")), "en");
+    assert_eq!(negotiate(Some("xx"), Some("ja"), Some("en")), "ja");
+    assert_eq!(negotiate(None, None, Some("zh-CN")), "ko");
+}
+
+#[actix_web::test]
+async fn serves_localized_templates_and_strings() {
+    let page = format!("i18n-{}", std::process::id());
+    let html = format!("{}.html", page);
+    fs::create_dir_all(resource_path("html", "en")).unwrap();
+    fs::create_dir_all(resource_path("i18n", "")).unwrap();
+    fs::write(
+        resource_path("html", &html),
+        "<html lang=\"%LANG%\">%T:{page}%</html>".replace("{page}", &page),
+    )
+    .unwrap();
+    fs::write(
+        resource_path("html", &format!("en/{}", html)),
+        "English page",
+    )
+    .unwrap();
+    fs::write(
+        resource_path("i18n", "ja.json"),
+        format!("{{\"{}\": \"ようこそ\"}}", page),
+    )
+    .unwrap();
+
+    let state = common::test_state();
+    let app = test::init_service(
+        App::new()
+            .wrap(from_fn(select_locale))
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/{}", page))
+        .insert_header(("Accept-Language", "en-US,en;q=0.9"))
+        .to_request();
+    assert_eq!(test::call_and_read_body(&app, req).await, "English page");
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/{}?lang=ja", page))
+        .insert_header(("Accept-Language", "en-US,en;q=0.9"))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    let cookie = res
+        .response()
+        .cookies()
+        .find(|cookie| cookie.name() == "lang")
+        .unwrap();
+    assert_eq!(cookie.value(), "ja");
+    assert_eq!(
+        test::read_body(res).await,
+        "<html lang=\"ja\">ようこそ</html>"
+    );
+
+    // 언어를 고른 뒤에는 쿠키로 유지
+    let req = test::TestRequest::get()
+        .uri(&format!("/{}", page))
+        .cookie(Cookie::new("lang", "ja"))
+        .to_request();
+    assert_eq!(
+        test::call_and_read_body(&app, req).await,
+        "<html lang=\"ja\">ようこそ</html>"
+    );
+
+    fs::remove_file(resource_path("html", &html)).unwrap();
+    fs::remove_file(resource_path("html", &format!("en/{}", html))).unwrap();
+    fs::remove_file(resource_path("i18n", "ja.json")).unwrap();
+}
+
+#[actix_web::test]
+async fn serves_localized_stamp_list() {
+    common::setup();
+    let mut stamp = common::stamp("a");
+    stamp.stampNames = BTreeMap::from([("en".to_string(), "Library".to_string())]);
+    stamp.stampDescs = BTreeMap::from([("en".to_string(), "Second floor".to_string())]);
+    let state = common::state_with(common::stamp_list(vec![stamp, common::stamp("b")]));
+    let app = test::init_service(
+        App::new()
+            .wrap(from_fn(select_locale))
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/stamps")
+        .insert_header(("Accept-Language", "en-US,en;q=0.9"))
+        .to_request();
+    let stamps: Vec<StampInfo> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(stamps[0].stampName, "Library");
+    assert_eq!(stamps[0].stampDesc, "Second floor");
+    // 번역이 없는 스템프는 기본 이름을 사용
+    assert_eq!(stamps[1].stampName, "스템프 b");
+
+    let req = test::TestRequest::get().uri("/api/stamps").to_request();
+    let stamps: Vec<StampInfo> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(stamps[0].stampName, "스템프 a");
+}
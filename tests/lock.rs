@@ -0,0 +1,36 @@
+mod common;
+
+use actix_web::{test, App};
+use gj_stamptour::{handlers::routes, lock::MutexExt, state::User};
+use serde_json::json;
+
+#[actix_web::test]
+async fn login_works_after_a_panic_poisoned_the_user_list() {
+    let state = common::test_state();
+    let user_list = state.user_list.clone();
+    let _ = std::thread::spawn(move || {
+        let _guard = user_list.lock().unwrap();
+        panic!("handler bug while holding the user list");
+    })
+    .join();
+    assert!(state.user_list.is_poisoned());
+
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/login")
+        .set_json(json!({ "user_name": "홍길동" }))
+        .to_request();
+    let user: User = test::call_and_read_body_json(&app, req).await;
+
+    assert_eq!(
+        state.user_list.lock_or_recover().users.get(&user.user_id),
+        Some(&"홍길동".to_string())
+    );
+    assert!(!state.user_list.is_poisoned());
+}
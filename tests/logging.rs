@@ -0,0 +1,41 @@
+use chrono::{Duration, Utc};
+use gj_stamptour::logging::{LogConfig, RotatingFile};
+use std::{env, fs};
+
+#[test]
+fn log_files_rotate_by_size_and_date() {
+    let dir = env::temp_dir().join(format!("stamptour-log-test-{}", std::process::id()));
+    let path = dir.join("logs/stamptour.log");
+    let config = LogConfig {
+        file: Some(path.display().to_string()),
+        keep: 2,
+        ..Default::default()
+    };
+    let mut file = RotatingFile::open(&config, &path)
+        .unwrap()
+        .with_max_bytes(20);
+
+    // 크기 제한을 넘으면 새 파일
+    let today = Utc::now();
+    file.write_at(b"first line\n", today).unwrap();
+    file.write_at(b"second line\n", today).unwrap();
+    assert_eq!(file.rotated_files().len(), 1);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "second line\n");
+
+    // 날짜가 바뀌면 크기와 관계없이 새 파일
+    let tomorrow = today + Duration::days(1);
+    file.write_at(b"a\n", tomorrow).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "a\n");
+
+    // 보관 개수를 넘는 오래된 파일은 지움
+    file.write_at(b"0123456789012345678\n", tomorrow).unwrap();
+    file.write_at(b"last\n", tomorrow + Duration::days(1))
+        .unwrap();
+    let rotated = file.rotated_files();
+    assert_eq!(rotated.len(), 2);
+    assert!(!rotated
+        .iter()
+        .any(|path| fs::read_to_string(path).unwrap() == "first line\n"));
+
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use actix_web::{http::StatusCode, test, App};
+use gj_stamptour::{
+    handlers::{admin_routes, routes},
+    state::User,
+};
+use serde_json::json;
+
+#[actix_web::test]
+async fn login_registers_user() {
+    let state = common::test_state();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/login")
+        .set_json(json!({ "user_name": "홍길동" }))
+        .to_request();
+    let user: User = test::call_and_read_body_json(&app, req).await;
+
+    assert_eq!(user.user_name, "홍길동");
+    assert_eq!(
+        state.user_list.lock().unwrap().users.get(&user.user_id),
+        Some(&"홍길동".to_string())
+    );
+}
+
+#[actix_web::test]
+async fn login_rejects_missing_name() {
+    let state = common::test_state();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/login")
+        .set_json(json!({}))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert!(resp.status().is_client_error());
+    assert!(state.user_list.lock().unwrap().users.is_empty());
+}
+
+#[actix_web::test]
+async fn malformed_body_returns_json_error() {
+    let state = common::test_state();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes)
+            .configure(routes),
+    )
+    .await;
+
+    for uri in ["/login", "/admin"] {
+        let req = test::TestRequest::post()
+            .uri(uri)
+            .peer_addr("127.0.0.1:40000".parse().unwrap())
+            .insert_header(("content-type", "application/json"))
+            .set_payload("{\"user_name\": ")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["error"], "Invalid JSON body");
+    }
+    assert!(state.user_list.lock().unwrap().users.is_empty());
+}
+
+#[actix_web::test]
+async fn oversized_body_is_rejected_with_413() {
+    let state = common::test_state();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/login")
+        .set_json(json!({ "user_name": "a".repeat(10_000) }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["error"], "Request body too large");
+    assert!(state.user_list.lock().unwrap().users.is_empty());
+}
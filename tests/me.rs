@@ -0,0 +1,66 @@
+mod common;
+
+use actix_web::{cookie::Cookie, http::StatusCode, test, App};
+use gj_stamptour::handlers::routes;
+use serde_json::json;
+
+#[actix_web::test]
+async fn deletes_own_data_after_confirmation() {
+    let state = common::test_state();
+    common::register(&state, "u1", "홍길동");
+    common::register(&state, "u2", "김철수");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+    common::collect(&app, "u1", "a").await;
+    common::collect(&app, "u2", "a").await;
+
+    let req = test::TestRequest::post()
+        .uri("/me/delete")
+        .cookie(Cookie::new("user_id", "u1"))
+        .set_json(json!({}))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::BAD_REQUEST
+    );
+    assert!(state.user_list.lock().unwrap().users.contains_key("u1"));
+
+    let req = test::TestRequest::post()
+        .uri("/me/delete")
+        .cookie(Cookie::new("user_id", "u1"))
+        .set_json(json!({ "confirm": true }))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let removal = res
+        .response()
+        .cookies()
+        .find(|cookie| cookie.name() == "user_id")
+        .unwrap();
+    assert_eq!(removal.value(), "");
+
+    assert!(!state.user_list.lock().unwrap().users.contains_key("u1"));
+    let history = &state.stamp_history;
+    assert!(history.collected_by("u1").is_empty());
+    assert_eq!(history.entries("a").len(), 2);
+    assert!(history
+        .entries("a")
+        .iter()
+        .all(|entry| entry.user_name != "홍길동"));
+    assert_eq!(history.collected_by("u2").len(), 1);
+
+    // 이미 삭제된 유저는 다시 삭제할 수 없음
+    let req = test::TestRequest::post()
+        .uri("/me/delete")
+        .cookie(Cookie::new("user_id", "u1"))
+        .set_json(json!({ "confirm": true }))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::UNAUTHORIZED
+    );
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use actix_web::{cookie::Cookie, http::StatusCode, test, App};
+use gj_stamptour::{handlers::routes, state::StampContent};
+
+#[actix_web::test]
+async fn me_page_lists_collected_and_remaining_booths() {
+    common::setup();
+    let mut library = common::stamp("library");
+    library.stampContent = Some(StampContent {
+        image: "/img/library.png".to_string(),
+        ..Default::default()
+    });
+    let mut secret = common::stamp("secret");
+    secret.stampHidden = true;
+    let state = common::state_with(common::stamp_list(vec![
+        library,
+        common::stamp("gym"),
+        secret,
+    ]));
+    common::register(&state, "u1", "<홍길동>");
+    state
+        .user_list
+        .lock()
+        .unwrap()
+        .codes
+        .insert("ABC234".to_string(), "u1".to_string());
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+    common::collect(&app, "u1", "library").await;
+
+    let req = test::TestRequest::get().uri("/me").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    let req = test::TestRequest::get()
+        .uri("/me")
+        .cookie(Cookie::new("user_id", "u1"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let html = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+    assert!(html.contains("&lt;홍길동&gt;님의 스템프"));
+    assert!(html.contains("1 / 2"));
+    assert!(html.contains("<strong>ABC234</strong>"));
+    let (collected, remaining) = html.split_once("남은 부스").unwrap();
+    assert!(
+        collected.contains("<img src=\"/img/library.png\" alt=\"\"><span>스템프 library</span>")
+    );
+    assert!(remaining.contains("<span>스템프 gym</span>"));
+    assert!(!html.contains("스템프 secret"));
+}
@@ -0,0 +1,229 @@
+mod common;
+
+use actix_web::{
+    http::{header::ALLOW, Method, StatusCode},
+    test, App,
+};
+use gj_stamptour::handlers::{admin_routes, routes, ADMIN_METHODS, PUBLIC_METHODS};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs,
+    path::Path,
+};
+
+/// 경로별 허용 메서드입니다.
+type Methods = BTreeMap<String, BTreeSet<String>>;
+
+/// `src` 아래 파일의 `#[get("/경로")]` 같은 라우트 매크로를 읽어 핸들러 이름별 `(경로, 메서드)` 목록을 만듭니다.
+fn macro_routes() -> HashMap<String, Vec<(String, String)>> {
+    let mut handlers = HashMap::new();
+    let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+    for entry in fs::read_dir(src).unwrap().flatten() {
+        let source = fs::read_to_string(entry.path()).unwrap();
+        let mut pending = Vec::new();
+        for line in source.lines().map(str::trim) {
+            let route = ["get", "post", "put", "patch", "delete"]
+                .into_iter()
+                .find_map(|method| {
+                    let path = line.strip_prefix(&format!("#[{}(\"", method))?;
+                    let (path, _) = path.split_once("\")]")?;
+                    Some((path.to_string(), method.to_uppercase()))
+                });
+            if let Some(route) = route {
+                pending.push(route);
+            } else if let Some((_, rest)) = line.split_once("fn ") {
+                let name = rest.split(['(', '<']).next().unwrap().to_string();
+                if !pending.is_empty() {
+                    handlers.insert(name, std::mem::take(&mut pending));
+                }
+            } else if !line.starts_with("#[") {
+                pending.clear();
+            }
+        }
+    }
+    handlers
+}
+
+/// `handlers.rs`의 `function` 함수에서 405 응답을 등록하기 전까지 `.service(핸들러)`로 등록한 핸들러의 라우트를 모읍니다.
+fn registered_methods(function: &str, list: &str) -> Methods {
+    let source =
+        fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("src/handlers.rs")).unwrap();
+    let body = source
+        .split_once(&format!("pub fn {}(", function))
+        .unwrap()
+        .1
+        .split_once(&format!("in {} {{", list))
+        .unwrap()
+        .0;
+    let handlers = macro_routes();
+    let mut methods = Methods::new();
+    for name in body.split(".service(").skip(1).filter_map(|rest| {
+        let name = rest.split(')').next()?;
+        name.chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+            .then_some(name)
+    }) {
+        for (path, method) in handlers
+            .get(name)
+            .unwrap_or_else(|| panic!("{} has no route macro", name))
+        {
+            methods
+                .entry(path.clone())
+                .or_default()
+                .insert(method.clone());
+        }
+    }
+    methods
+}
+
+/// `PUBLIC_METHODS` 같은 목록을 같은 형식으로 바꿉니다.
+fn listed_methods(list: &[(&str, &str)]) -> Methods {
+    let mut methods = Methods::new();
+    for (path, allow) in list {
+        let previous = methods.insert(
+            path.to_string(),
+            allow.split(", ").map(str::to_string).collect(),
+        );
+        assert!(previous.is_none(), "{} is listed twice", path);
+    }
+    methods
+}
+
+#[actix_web::test]
+async fn method_lists_match_registered_routes() {
+    assert_eq!(
+        listed_methods(&PUBLIC_METHODS),
+        registered_methods("routes", "PUBLIC_METHODS")
+    );
+    assert_eq!(
+        listed_methods(&ADMIN_METHODS),
+        registered_methods("admin_routes", "ADMIN_METHODS")
+    );
+}
+
+#[actix_web::test]
+async fn every_listed_route_reports_its_methods() {
+    let state = common::test_state();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    for (path, allow) in PUBLIC_METHODS {
+        // 경로 변수는 아무 값으로 채움
+        let uri = path
+            .split('{')
+            .enumerate()
+            .map(|(index, part)| match index {
+                0 => part,
+                _ => part.split_once('}').unwrap().1,
+            })
+            .collect::<Vec<_>>()
+            .join("x");
+        let req = test::TestRequest::default()
+            .method(Method::PATCH)
+            .uri(&uri)
+            .peer_addr("127.0.0.1:40000".parse().unwrap())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::METHOD_NOT_ALLOWED, "{}", uri);
+        assert_eq!(resp.headers().get(ALLOW).unwrap(), allow, "{}", uri);
+    }
+}
+
+#[actix_web::test]
+async fn wrong_method_returns_405_with_allow_header() {
+    let state = common::test_state();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    for (method, uri, allow) in [
+        (Method::POST, "/check?s=a", "GET"),
+        (Method::POST, "/stamp/", "GET"),
+        (Method::GET, "/login", "POST"),
+        (Method::GET, "/api/v1/login", "POST"),
+        (Method::GET, "/api/v1/sync", "POST"),
+        (Method::DELETE, "/api/v1/teams/team-1", "GET"),
+        (Method::GET, "/api/v1/partner/stamp", "POST"),
+    ] {
+        let req = test::TestRequest::default()
+            .method(method.clone())
+            .uri(uri)
+            .peer_addr("127.0.0.1:40000".parse().unwrap())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(
+            resp.status(),
+            StatusCode::METHOD_NOT_ALLOWED,
+            "{} {}",
+            method,
+            uri
+        );
+        assert_eq!(
+            resp.headers().get(ALLOW).unwrap(),
+            allow,
+            "{} {}",
+            method,
+            uri
+        );
+    }
+
+    // 올바른 메서드는 그대로 처리하고, 없는 경로는 여전히 404
+    let req = test::TestRequest::get()
+        .uri("/check?s=a")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::TEMPORARY_REDIRECT);
+
+    let req = test::TestRequest::post()
+        .uri("/no/such/page")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn admin_server_reports_allowed_methods() {
+    let state = common::test_state();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes),
+    )
+    .await;
+
+    for (method, uri, allow) in [
+        (Method::GET, "/admin", "POST"),
+        (Method::PUT, "/graphql", "GET, POST"),
+        (Method::POST, "/metrics", "GET"),
+    ] {
+        let req = test::TestRequest::default()
+            .method(method.clone())
+            .uri(uri)
+            .peer_addr("127.0.0.1:40000".parse().unwrap())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(
+            resp.status(),
+            StatusCode::METHOD_NOT_ALLOWED,
+            "{} {}",
+            method,
+            uri
+        );
+        assert_eq!(
+            resp.headers().get(ALLOW).unwrap(),
+            allow,
+            "{} {}",
+            method,
+            uri
+        );
+    }
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use actix_web::{middleware::from_fn, test, App};
+use gj_stamptour::{
+    handlers::{admin_routes, routes},
+    metrics::record_latency,
+    persistence::{self, Dataset},
+};
+
+#[actix_web::test]
+async fn records_route_latency_and_lock_waits() {
+    let state = common::test_state();
+    common::register(&state, "u1", "visitor");
+    let app = test::init_service(
+        App::new()
+            .wrap(from_fn(record_latency))
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes)
+            .configure(routes),
+    )
+    .await;
+    common::collect(&app, "u1", "a").await;
+    common::collect(&app, "u1", "b").await;
+
+    let histogram = state.metrics.request_histogram("GET", "/stamp/").unwrap();
+    assert_eq!(histogram.count(), 2);
+
+    let req = test::TestRequest::get()
+        .uri("/metrics")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .to_request();
+    let body = String::from_utf8(test::call_and_read_body(&app, req).await.to_vec()).unwrap();
+    assert!(body.contains(
+        "stamptour_http_request_duration_seconds_count{method=\"GET\",route=\"/check\"} 2"
+    ));
+    assert!(body.contains("stamptour_lock_wait_seconds_bucket{lock=\"stamp_history\",le=\"+Inf\"}"));
+    assert!(body.contains("stamptour_lock_hold_seconds_bucket{lock=\"stamp_history\",le=\"+Inf\"}"));
+}
+
+#[actix_web::test]
+async fn records_save_durations_and_bytes() {
+    let state = common::test_state();
+    persistence::start(&state);
+    common::register(&state, "u1", "visitor");
+    state.persister.mark(&[Dataset::UserStatus]);
+    assert_eq!(state.persister.flush().await, Ok(()));
+
+    let saved = std::fs::metadata("resources/database/user_status.json").unwrap();
+    let stats = state.metrics.save_stats("user_status").unwrap();
+    assert!(stats.serialize.count() >= 1);
+    assert!(stats.write.count() >= 1);
+    assert!(stats.bytes_written >= saved.len());
+
+    let body = state.metrics.render();
+    assert!(body.contains("stamptour_save_write_seconds_count{file=\"user_status\"}"));
+    assert!(body.contains("stamptour_save_bytes_total{file=\"user_status\"}"));
+}
+
+#[actix_web::test]
+async fn metrics_are_local_only() {
+    let state = common::test_state();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes)
+            .configure(routes),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/metrics")
+        .peer_addr("10.0.0.5:40000".parse().unwrap())
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), actix_web::http::StatusCode::UNAUTHORIZED);
+}
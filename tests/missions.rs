@@ -0,0 +1,66 @@
+mod common;
+
+use actix_web::{cookie::Cookie, test, App};
+use chrono::{Days, Local};
+use gj_stamptour::{
+    config::Config,
+    handlers::routes,
+    missions::{MissionList, MissionStatus},
+    state::AppState,
+};
+use serde_json::json;
+
+#[actix_web::test]
+async fn missions_report_todays_progress() {
+    common::setup();
+    let state = AppState::new(
+        Config::default(),
+        common::stamp_list(vec![
+            common::stamp("a"),
+            common::stamp("b"),
+            common::stamp("food"),
+        ]),
+    );
+    let today = Local::now().date_naive();
+    let tomorrow = today.checked_add_days(Days::new(1)).unwrap();
+    let missions: MissionList = serde_json::from_value(json!({
+        "missions": [
+            { "mission_id": "two", "name": "오늘 스템프 2개", "dates": [today.to_string()], "required": 2 },
+            { "mission_id": "food", "name": "먹거리 부스", "stamp_ids": ["food"] },
+            { "mission_id": "later", "name": "내일 미션", "dates": [tomorrow.to_string()], "required": 1 }
+        ]
+    }))
+    .unwrap();
+    state.missions.replace(missions);
+    common::register(&state, "u1", "visitor");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    let missions = || {
+        test::TestRequest::get()
+            .uri("/api/v1/missions")
+            .cookie(Cookie::new("user_id", "u1"))
+            .to_request()
+    };
+    let status: Vec<MissionStatus> = test::call_and_read_body_json(&app, missions()).await;
+    let ids: Vec<&str> = status.iter().map(|m| m.mission_id.as_str()).collect();
+    assert_eq!(ids, vec!["two", "food"]);
+    assert!(status.iter().all(|m| m.collected == 0 && !m.completed));
+
+    // 같은 스템프를 다시 찍어도 한 번만 셈
+    common::collect(&app, "u1", "a").await;
+    common::collect(&app, "u1", "a").await;
+    let status: Vec<MissionStatus> = test::call_and_read_body_json(&app, missions()).await;
+    assert_eq!(status[0].collected, 1);
+    assert!(!status[0].completed);
+
+    common::collect(&app, "u1", "food").await;
+    let status: Vec<MissionStatus> = test::call_and_read_body_json(&app, missions()).await;
+    assert!(status[0].completed);
+    assert!(status[1].completed);
+    assert_eq!(status[1].required, 1);
+}
@@ -0,0 +1,102 @@
+mod common;
+
+use actix_web::{cookie::Cookie, http::StatusCode, test, App};
+use gj_stamptour::{
+    config::Config,
+    handlers::routes,
+    nfc::{tag_mac, verify_tag},
+    state::AppState,
+};
+use serde_json::json;
+
+const UID: &str = "04A1B2C3D4E5F6";
+
+fn nfc_config() -> Config {
+    serde_json::from_value(json!({
+        "nfc": { "secret": "tag-secret", "tags": { UID: "a" } }
+    }))
+    .unwrap()
+}
+
+fn tag_query(uid: &str, counter: u32) -> String {
+    format!(
+        "uid={}&ctr={:06X}&mac={}",
+        uid,
+        counter,
+        tag_mac("tag-secret", uid, counter)
+    )
+}
+
+#[actix_web::test]
+async fn verifies_tag_payload() {
+    let config = nfc_config();
+    assert_eq!(
+        verify_tag(&config.nfc, &tag_query(UID, 42)),
+        Ok((UID.to_string(), "a", 42))
+    );
+    // UID는 소문자로 적어도 같은 태그
+    assert_eq!(
+        verify_tag(&config.nfc, &tag_query(&UID.to_lowercase(), 7)),
+        Ok((UID.to_string(), "a", 7))
+    );
+
+    let wrong_mac = format!("uid={}&ctr=00002A&mac={}", UID, tag_mac("other", UID, 42));
+    assert!(verify_tag(&config.nfc, &wrong_mac).is_err());
+    assert!(verify_tag(&config.nfc, &tag_query("04FFFFFFFFFFFF", 42)).is_err());
+    assert!(verify_tag(&config.nfc, &format!("uid={}&ctr=2A&mac=00", UID)).is_err());
+    assert!(verify_tag(&config.nfc, "uid=zz&ctr=00002A&mac=00").is_err());
+    assert!(verify_tag(&Config::default().nfc, &tag_query(UID, 42)).is_err());
+}
+
+#[actix_web::test]
+async fn nfc_tag_checks_stamp_once_per_counter() {
+    common::setup();
+    let state = AppState::new(
+        nfc_config(),
+        common::stamp_list(vec![common::stamp("a"), common::stamp("b")]),
+    );
+    common::register(&state, "u1", "visitor");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+    let pending = |state: &AppState| {
+        state
+            .user_stamp_list
+            .lock()
+            .unwrap()
+            .user_stamp_list
+            .remove("u1")
+    };
+    let tap = |query: String| {
+        test::TestRequest::get()
+            .uri(&format!("/nfc/check?{}", query))
+            .peer_addr("127.0.0.1:40000".parse().unwrap())
+            .cookie(Cookie::new("user_id", "u1"))
+            .to_request()
+    };
+
+    let res = test::call_service(&app, tap(tag_query(UID, 5))).await;
+    assert_eq!(res.status(), StatusCode::TEMPORARY_REDIRECT);
+    assert_eq!(pending(&state).as_deref(), Some("a"));
+    assert_eq!(state.nfc_counters.lock().unwrap().counters[UID], 5);
+
+    // 같은 카운터나 이전 카운터의 주소는 기록하지 않음
+    for counter in [5, 4] {
+        let res = test::call_service(&app, tap(tag_query(UID, counter))).await;
+        assert_eq!(res.status(), StatusCode::TEMPORARY_REDIRECT);
+        assert_eq!(pending(&state), None);
+    }
+
+    let res = test::call_service(&app, tap(tag_query(UID, 6))).await;
+    assert_eq!(res.status(), StatusCode::TEMPORARY_REDIRECT);
+    assert_eq!(pending(&state).as_deref(), Some("a"));
+
+    // 잘못된 MAC은 카운터를 바꾸지 않음
+    let forged = format!("uid={}&ctr=0000FF&mac=0000000000000000", UID);
+    test::call_service(&app, tap(forged)).await;
+    assert_eq!(pending(&state), None);
+    assert_eq!(state.nfc_counters.lock().unwrap().counters[UID], 6);
+}
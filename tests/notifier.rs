@@ -0,0 +1,40 @@
+use gj_stamptour::{
+    config::Config,
+    notifier::{payload, Event, Notifier, NotifierService},
+};
+use serde_json::json;
+
+#[test]
+fn payload_matches_service() {
+    let message = Event::CompletionMilestone {
+        tier_name: "complete".to_string(),
+        count: 100,
+    }
+    .message();
+    assert!(message.contains("complete"));
+    assert!(message.contains("100"));
+
+    assert_eq!(
+        payload(NotifierService::Discord, "hello"),
+        json!({ "content": "hello" })
+    );
+    assert_eq!(
+        payload(NotifierService::Slack, "hello"),
+        json!({ "text": "hello" })
+    );
+}
+
+#[test]
+fn milestone_uses_configured_interval() {
+    let config: Config = serde_json::from_value(json!({
+        "notifier": { "service": "slack", "webhook_url": "http://127.0.0.1:9/hook", "completion_milestone": 50 }
+    }))
+    .unwrap();
+    let notifier = Notifier::new(config.notifier);
+    assert!(!notifier.is_milestone(49));
+    assert!(notifier.is_milestone(50));
+    assert!(notifier.is_milestone(100));
+
+    // 설정이 없으면 알림을 보내지 않음
+    assert!(!Notifier::new(None).is_milestone(100));
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use actix_web::{cookie::Cookie, test, App};
+use gj_stamptour::{handlers::routes, state::Announcement};
+
+#[actix_web::test]
+async fn stamp_pages_are_served_from_cache() {
+    let state = common::test_state();
+    common::register(&state, "u1", "visitor");
+    state
+        .stamp_pages
+        .insert("a", "ko", "<p>A %ANNOUNCEMENT%</p>".to_string());
+    state
+        .stamp_pages
+        .insert("b", "ko", "<p>B %ANNOUNCEMENT%</p>".to_string());
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    // `/check`와 `/stamp/` 요청을 차례로 만듦
+    let stamp = |stamp_id: &str| {
+        (
+            test::TestRequest::get()
+                .uri(&format!("/check?s={}", stamp_id))
+                .peer_addr("127.0.0.1:40000".parse().unwrap())
+                .cookie(Cookie::new("user_id", "u1"))
+                .to_request(),
+            test::TestRequest::get()
+                .uri("/stamp/")
+                .peer_addr("127.0.0.1:40000".parse().unwrap())
+                .cookie(Cookie::new("user_id", "u1"))
+                .to_request(),
+        )
+    };
+
+    // 캐시에 있는 페이지는 템플릿 파일을 읽지 않고 그대로 반환
+    let (check, req) = stamp("a");
+    test::call_service(&app, check).await;
+    let body = test::call_and_read_body(&app, req).await;
+    assert_eq!(body, "<p>A </p>");
+
+    // 공지가 있으면 캐시한 페이지에 공지만 채워 넣음
+    *state.announcement.lock().unwrap() = Some(Announcement {
+        message: "<점심시간>".to_string(),
+        expires_at: "2999-01-01T00:00:00+00:00".to_string(),
+    });
+    let (check, req) = stamp("b");
+    test::call_service(&app, check).await;
+    let body = test::call_and_read_body(&app, req).await;
+    assert_eq!(body, "<p>B &lt;점심시간&gt;</p>");
+
+    // 템플릿을 읽지 못한 페이지는 저장하지 않음 (테스트 환경에는 템플릿 파일이 없음)
+    let (check, req) = stamp("c");
+    test::call_service(&app, check).await;
+    let body = test::call_and_read_body(&app, req).await;
+    assert!(body.is_empty());
+    assert_eq!(state.stamp_pages.len(), 2);
+    assert!(state.stamp_pages.get("c", "ko").is_none());
+
+    state.stamp_pages.clear();
+    assert!(state.stamp_pages.is_empty());
+}
@@ -0,0 +1,44 @@
+mod common;
+
+use actix_web::{http::StatusCode, middleware::from_fn, test, web, App, HttpResponse};
+use gj_stamptour::{handlers::routes, panic::catch_panic, request_id::assign_request_id};
+
+#[actix_web::test]
+async fn panicking_handlers_get_500_and_server_keeps_serving() {
+    common::setup();
+    let state = common::test_state();
+    let app = test::init_service(
+        App::new()
+            .wrap(from_fn(catch_panic))
+            .wrap(from_fn(assign_request_id))
+            .configure(|cfg| state.register(cfg))
+            .route(
+                "/api/v1/panic",
+                web::get().to(|| async {
+                    if true {
+                        panic!("handler bug");
+                    }
+                    HttpResponse::Ok().finish()
+                }),
+            )
+            .configure(routes),
+    )
+    .await;
+
+    // 500 응답은 오류로 반환되며 바깥 미들웨어가 요청 ID를 붙임
+    let req = test::TestRequest::get()
+        .uri("/api/v1/panic")
+        .insert_header(("x-request-id", "panic-test"))
+        .to_request();
+    let Err(err) = test::try_call_service(&app, req).await else {
+        panic!("panic was not converted into an error");
+    };
+    assert_eq!(
+        err.error_response().status(),
+        StatusCode::INTERNAL_SERVER_ERROR
+    );
+
+    let req = test::TestRequest::get().uri("/api/v1/event").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+}
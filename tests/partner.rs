@@ -0,0 +1,89 @@
+mod common;
+
+use actix_web::{http::StatusCode, test, App};
+use gj_stamptour::{
+    handlers::{admin_routes, routes},
+    partner::PartnerStampResponse,
+    persistence,
+    state::Command,
+};
+use serde_json::json;
+
+#[actix_web::test]
+async fn partner_keys_are_scoped_and_revocable() {
+    let state = common::test_state();
+    persistence::start(&state);
+    common::register(&state, "u1", "visitor");
+    let user_code = {
+        let mut user_list = state.user_list.lock().unwrap();
+        user_list.assign_missing_codes();
+        user_list.codes.keys().next().cloned().unwrap()
+    };
+    let admin = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes),
+    )
+    .await;
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    let admin_command = |command: &str| {
+        test::TestRequest::post()
+            .uri("/admin")
+            .peer_addr("127.0.0.1:40000".parse().unwrap())
+            .set_json(json!({ "command": command, "output": "" }))
+            .to_request()
+    };
+    let output: Command =
+        test::call_and_read_body_json(&admin, admin_command("partner create acme a,x")).await;
+    assert_eq!(output.output, "Stamp x not found");
+    let output: Command =
+        test::call_and_read_body_json(&admin, admin_command("partner create acme a")).await;
+    let key = output
+        .output
+        .split_whitespace()
+        .find(|word| word.starts_with("pk_"))
+        .unwrap()
+        .to_string();
+    // 키 원문은 저장하지 않음
+    assert!(!serde_json::to_string(&*state.partner_keys.lock().unwrap())
+        .unwrap()
+        .contains(&key));
+
+    let stamp = |stamp_id: &str, key: &str| {
+        test::TestRequest::post()
+            .uri("/api/partner/stamp")
+            .insert_header(("X-API-Key", key.to_string()))
+            .set_json(json!({ "user_code": user_code.to_lowercase(), "stamp_id": stamp_id }))
+            .to_request()
+    };
+    let res = test::call_service(&app, stamp("a", &key)).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let recorded: PartnerStampResponse = test::read_body_json(res).await;
+    assert_eq!(recorded.user_code, user_code);
+    assert_eq!(state.stamp_history.entries("a")[0].user_id, "u1");
+    assert_eq!(
+        state.audit_log.lock().unwrap().entries[0].actor,
+        "partner:acme"
+    );
+
+    let res = test::call_service(&app, stamp("b", &key)).await;
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+    let res = test::call_service(&app, stamp("a", "pk_wrong")).await;
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    let output: Command =
+        test::call_and_read_body_json(&admin, admin_command("partner revoke acme")).await;
+    assert_eq!(output.output, "Partner key for acme revoked");
+    let res = test::call_service(&app, stamp("a", &key)).await;
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    let output: Command = test::call_and_read_body_json(&admin, admin_command("partners")).await;
+    assert_eq!(output.output, "acme: a (revoked)");
+
+    std::fs::remove_file("resources/database/partner_keys.json").ok();
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use actix_web::{test, App};
+use gj_stamptour::{
+    config::Config,
+    handlers::routes,
+    persistence::{self, Dataset},
+    state::AppState,
+};
+use serde_json::json;
+use std::{fs, time::Duration};
+
+fn state_with_debounce(debounce_ms: u64) -> AppState {
+    common::setup();
+    let config: Config = serde_json::from_value(json!({
+        "persistence": { "debounce_ms": debounce_ms, "max_delay_ms": 5000 }
+    }))
+    .unwrap();
+    AppState::new(
+        config,
+        common::stamp_list(vec![common::stamp("a"), common::stamp("b")]),
+    )
+}
+
+#[actix_web::test]
+async fn changes_are_saved_after_requests_settle() {
+    let dir = common::setup();
+    let path = dir.join("resources/database/user_status.json");
+    fs::remove_file(&path).ok();
+    let state = state_with_debounce(200);
+    persistence::start(&state);
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    for name in ["홍길동", "김철수"] {
+        let req = test::TestRequest::post()
+            .uri("/login")
+            .peer_addr("127.0.0.1:40000".parse().unwrap())
+            .set_json(json!({ "user_name": name }))
+            .to_request();
+        assert!(test::call_service(&app, req).await.status().is_success());
+    }
+
+    // 요청을 처리하는 동안에는 저장하지 않고, 변경이 잠잠해진 뒤에 한 번에 저장
+    assert!(!path.exists());
+    actix_rt::time::sleep(Duration::from_millis(600)).await;
+    let saved = fs::read_to_string(&path).unwrap();
+    assert!(saved.contains("홍길동"));
+    assert!(saved.contains("김철수"));
+}
+
+#[actix_web::test]
+async fn flush_saves_every_database() {
+    let dir = common::setup();
+    let state = state_with_debounce(60_000);
+
+    // 작업자가 실행되지 않았으면 저장할 수 없음
+    let failed = state.persister.flush().await.unwrap_err();
+    assert_eq!(failed.len(), Dataset::ALL.len());
+
+    persistence::start(&state);
+    common::register(&state, "u1", "visitor");
+    state.persister.mark(&[Dataset::UserStatus]);
+    assert_eq!(state.persister.flush().await, Ok(()));
+
+    for dataset in Dataset::ALL {
+        let path = dir.join(format!("resources/database/{}.json", dataset.file_name()));
+        assert!(path.exists(), "{} was not saved", dataset.file_name());
+    }
+    let saved = fs::read_to_string(dir.join("resources/database/user_status.json")).unwrap();
+    assert!(saved.contains("visitor"));
+}
+
+#[actix_web::test]
+async fn interval_saves_wait_unless_mutations_pile_up() {
+    common::setup();
+    let config: Config = serde_json::from_value(json!({
+        "persistence": { "autosave_interval_minutes": 10, "save_after_mutations": 3 }
+    }))
+    .unwrap();
+    let state = AppState::new(config, common::stamp_list(vec![common::stamp("a")]));
+    persistence::start(&state);
+    // 다른 테스트도 같은 파일을 쓰므로 이 상태의 저장 기록으로 확인
+    let saved = || state.metrics.save_stats("team_status").is_some();
+
+    // 주기 저장에서는 변경이 잠잠해져도 바로 저장하지 않음
+    state.persister.mark(&[Dataset::TeamStatus]);
+    state.persister.mark(&[Dataset::TeamStatus]);
+    actix_rt::time::sleep(Duration::from_millis(1500)).await;
+    assert!(!saved());
+
+    // 저장하지 않은 변경이 `save_after_mutations`만큼 쌓이면 바로 저장
+    state.persister.mark(&[Dataset::TeamStatus]);
+    actix_rt::time::sleep(Duration::from_millis(300)).await;
+    assert!(saved());
+}
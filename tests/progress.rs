@@ -0,0 +1,286 @@
+mod common;
+
+use actix_web::{cookie::Cookie, http::StatusCode, test, App};
+use gj_stamptour::{
+    config::Config,
+    handlers::routes,
+    progress::{LeaderboardEntry, NextTier, Progress},
+    state::AppState,
+    storage::resource_path,
+};
+use serde_json::json;
+use std::fs;
+
+fn tiered_state() -> AppState {
+    common::setup();
+    let config: Config = serde_json::from_value(json!({
+        "completion_tiers": [
+            { "tier_name": "grand" },
+            { "tier_name": "small", "required": 2 }
+        ]
+    }))
+    .unwrap();
+    AppState::new(
+        config,
+        common::stamp_list(vec![
+            common::stamp("a"),
+            common::stamp("b"),
+            common::stamp("c"),
+        ]),
+    )
+}
+
+#[actix_web::test]
+async fn progress_reports_tiers() {
+    let state = tiered_state();
+    common::register(&state, "u1", "visitor");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    common::collect(&app, "u1", "a").await;
+    let req = test::TestRequest::get()
+        .uri("/progress")
+        .cookie(Cookie::new("user_id", "u1"))
+        .to_request();
+    let progress: Progress = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(progress.collected, vec!["a".to_string()]);
+    assert_eq!(progress.total_count, 3);
+    assert_eq!(progress.tier, None);
+    assert_eq!(
+        progress.next_tier,
+        Some(NextTier {
+            tier_name: "small".to_string(),
+            remaining: 1
+        })
+    );
+
+    // 같은 스템프를 다시 찍어도 개수는 늘지 않음
+    common::collect(&app, "u1", "a").await;
+    common::collect(&app, "u1", "b").await;
+    let req = test::TestRequest::get()
+        .uri("/progress")
+        .cookie(Cookie::new("user_id", "u1"))
+        .to_request();
+    let progress: Progress = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(progress.collected_count, 2);
+    assert_eq!(progress.tier.as_deref(), Some("small"));
+
+    common::collect(&app, "u1", "c").await;
+    let completions = state.completions.lock().unwrap();
+    let tiers: Vec<&str> = completions.completions["u1"]
+        .iter()
+        .map(|record| record.tier_name.as_str())
+        .collect();
+    assert_eq!(tiers, vec!["small", "grand"]);
+}
+
+#[actix_web::test]
+async fn progress_requires_registered_user() {
+    let state = tiered_state();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    let req = test::TestRequest::get().uri("/progress").to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::UNAUTHORIZED
+    );
+
+    let req = test::TestRequest::get()
+        .uri("/progress")
+        .cookie(Cookie::new("user_id", "ghost"))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::UNAUTHORIZED
+    );
+}
+
+#[actix_web::test]
+async fn leaderboard_orders_by_points() {
+    common::setup();
+    let mut flagship = common::stamp("flagship");
+    flagship.stampPoints = Some(5);
+    let state = common::state_with(common::stamp_list(vec![
+        flagship,
+        common::stamp("a"),
+        common::stamp("b"),
+    ]));
+    common::register(&state, "u1", "small booths");
+    common::register(&state, "u2", "flagship");
+    common::register(&state, "u3", "nothing yet");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    common::collect(&app, "u1", "a").await;
+    common::collect(&app, "u1", "b").await;
+    common::collect(&app, "u2", "flagship").await;
+
+    let req = test::TestRequest::get()
+        .uri("/progress")
+        .cookie(Cookie::new("user_id", "u2"))
+        .to_request();
+    let progress: Progress = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(progress.points, 5);
+
+    let req = test::TestRequest::get()
+        .uri("/api/leaderboard")
+        .to_request();
+    let entries: Vec<LeaderboardEntry> = test::call_and_read_body_json(&app, req).await;
+    let ranking: Vec<(usize, &str, u32)> = entries
+        .iter()
+        .map(|entry| (entry.rank, entry.user_name.as_str(), entry.points))
+        .collect();
+    assert_eq!(ranking, vec![(1, "flagship", 5), (2, "small booths", 2)]);
+
+    let req = test::TestRequest::get()
+        .uri("/api/leaderboard?limit=1")
+        .to_request();
+    let entries: Vec<LeaderboardEntry> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(entries.len(), 1);
+}
+
+#[actix_web::test]
+async fn hidden_stamps_appear_only_after_discovery() {
+    common::setup();
+    let mut secret = common::stamp("secret");
+    secret.stampHidden = true;
+    secret.stampPoints = Some(5);
+    let state = AppState::new(
+        Config::default(),
+        common::stamp_list(vec![common::stamp("a"), secret]),
+    );
+    common::register(&state, "u1", "visitor");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    // 공개 스템프 목록에 숨은 스템프는 나오지 않음
+    let req = test::TestRequest::get().uri("/api/v1/stamps").to_request();
+    let stamps: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(stamps.as_array().unwrap().len(), 1);
+
+    // 원본 스템프 목록 파일 주소로도 숨은 스템프와 별칭, 위치 정보는 나오지 않음
+    let raw = resource_path("api", "stampList.json");
+    fs::create_dir_all(raw.parent().unwrap()).unwrap();
+    fs::write(
+        &raw,
+        r#"{"stampList": [{"stampId": "secret", "stampHidden": true, "stampAliases": ["s-1"]}]}"#,
+    )
+    .unwrap();
+    let req = test::TestRequest::get()
+        .uri("/api/stampList.json")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+    let list: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(list["stampList"].as_array().unwrap().len(), 1);
+    assert_eq!(list["stampList"][0]["stampId"], "a");
+    assert!(!body.contains("secret"));
+    assert!(!body.contains("stampAliases"));
+    fs::remove_file(&raw).unwrap();
+
+    let req = test::TestRequest::get()
+        .uri("/progress")
+        .cookie(Cookie::new("user_id", "u1"))
+        .to_request();
+    let progress: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(progress["total_count"], 1);
+    assert!(progress.get("secret_badges").is_none());
+
+    // 숨은 스템프는 개수와 완주 등급에 포함되지 않고 비밀 배지와 보너스 점수로만 나타남
+    assert_eq!(common::collect(&app, "u1", "secret").await, StatusCode::OK);
+    let req = test::TestRequest::get()
+        .uri("/progress")
+        .cookie(Cookie::new("user_id", "u1"))
+        .to_request();
+    let progress: Progress = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(progress.collected_count, 0);
+    assert_eq!(progress.points, 5);
+    assert_eq!(progress.tier, None);
+    assert_eq!(progress.secret_badges.len(), 1);
+    assert_eq!(progress.secret_badges[0].stampId, "secret");
+
+    common::collect(&app, "u1", "a").await;
+    let req = test::TestRequest::get()
+        .uri("/progress")
+        .cookie(Cookie::new("user_id", "u1"))
+        .to_request();
+    let progress: Progress = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(progress.collected, vec!["a".to_string()]);
+    assert_eq!(progress.tier.as_deref(), Some("complete"));
+    assert_eq!(progress.points, 6);
+}
+
+#[actix_web::test]
+async fn achievements_are_recorded_once_and_reported() {
+    common::setup();
+    let config: Config = serde_json::from_value(json!({
+        "achievements": [
+            { "achievement_id": "first", "name": "선착순 완주", "rule": "first_finishers", "limit": 1 },
+            { "achievement_id": "foodie", "name": "미식가", "rule": "all_of", "stamp_ids": ["a", "b"] }
+        ]
+    }))
+    .unwrap();
+    let state = AppState::new(
+        config,
+        common::stamp_list(vec![common::stamp("a"), common::stamp("b")]),
+    );
+    common::register(&state, "u1", "first");
+    common::register(&state, "u2", "second");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    for user_id in ["u1", "u2"] {
+        common::collect(&app, user_id, "a").await;
+        common::collect(&app, user_id, "b").await;
+        common::collect(&app, user_id, "a").await;
+    }
+
+    let achievements = |user_id: &'static str| {
+        test::TestRequest::get()
+            .uri("/progress")
+            .cookie(Cookie::new("user_id", user_id))
+            .to_request()
+    };
+    let progress: Progress = test::call_and_read_body_json(&app, achievements("u1")).await;
+    let names: Vec<&str> = progress
+        .achievements
+        .iter()
+        .map(|a| a.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["선착순 완주", "미식가"]);
+
+    // 선착순 업적은 한 명에게만 주어짐
+    let progress: Progress = test::call_and_read_body_json(&app, achievements("u2")).await;
+    let ids: Vec<&str> = progress
+        .achievements
+        .iter()
+        .map(|a| a.achievement_id.as_str())
+        .collect();
+    assert_eq!(ids, vec!["foodie"]);
+    assert_eq!(
+        state.completions.lock().unwrap().achievements["u1"].len(),
+        2
+    );
+}
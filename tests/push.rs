@@ -0,0 +1,234 @@
+mod common;
+
+use actix_web::{cookie::Cookie, http::StatusCode, test, App};
+use chrono::{TimeZone, Utc};
+use gj_stamptour::{
+    config::Config,
+    crypto::{decode_base64_url, encode_base64_url},
+    handlers::routes,
+    push::{
+        encrypt_with, generate_vapid_keys, new_completions, newly_low_stock, public_key,
+        vapid_authorization, vapid_key,
+    },
+    state::{AppState, CompletionList, CompletionRecord},
+};
+use openssl::{
+    bn::BigNum,
+    ec::{EcGroup, EcKey, EcPoint},
+    ecdsa::EcdsaSig,
+    nid::Nid,
+    sha::sha256,
+};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+
+/// RFC 8291 부록 A의 예시를 그대로 암호화합니다.
+#[actix_web::test]
+async fn encryption_matches_rfc8291_example() {
+    let as_key = vapid_key("yfWPiYE-n46HLnH0KqZOF1fJJU3MYrct3AELtAQ-oRw").unwrap();
+    let ua_public = decode_base64_url(
+        "BCVxsr7N_eNgVRqvHtD0zTZsEc6-VV-JvLexhqUzORcxaOzi6-AYWXvTBHm4bjyPjs7Vd8pZGH6SRpkNtoIAiw4",
+    )
+    .unwrap();
+    let auth_secret = decode_base64_url("BTBZMqHH6r4Tts7J_aSIgg").unwrap();
+    let salt: [u8; 16] = decode_base64_url("DGv6ra1nlYgDCS1FRnbzlw")
+        .unwrap()
+        .try_into()
+        .unwrap();
+
+    let body = encrypt_with(
+        b"When I grow up, I want to be a watermelon",
+        &ua_public,
+        &auth_secret,
+        &as_key,
+        &salt,
+    )
+    .unwrap();
+    assert_eq!(
+        encode_base64_url(&body),
+        "DGv6ra1nlYgDCS1FRnbzlwAAEABBBP4z9KsN6nGRTbVYI_c7VJSPQTBtkgcy27mlmlMoZIIgDll6e3vCYLocInmYWAmS6TlzAC8wEqKK6PBru3jl7A_yl95bQpu6cVPTpK4Mqgkf1CXztLVBSt2Ks3oZwbuwXPXLWyouBWLVWGNWQexSgSxsj_Qulcy4a-fN"
+    );
+}
+
+#[actix_web::test]
+async fn vapid_token_is_signed_with_es256() {
+    let (private_key, public) = generate_vapid_keys();
+    let key = vapid_key(&private_key).unwrap();
+    assert_eq!(encode_base64_url(&public_key(&key)), public);
+
+    let header = vapid_authorization(
+        &key,
+        "https://push.example.net/send/abc",
+        "mailto:staff@example.com",
+        1_700_000_000,
+    )
+    .unwrap();
+    let (token, k) = header
+        .strip_prefix("vapid t=")
+        .and_then(|rest| rest.split_once(", k="))
+        .unwrap();
+    assert_eq!(k, public);
+
+    let (unsigned, signature) = token.rsplit_once('.').unwrap();
+    let claims: Value =
+        serde_json::from_slice(&decode_base64_url(unsigned.split('.').nth(1).unwrap()).unwrap())
+            .unwrap();
+    assert_eq!(claims["aud"], "https://push.example.net");
+    assert_eq!(claims["exp"], 1_700_000_000 + 12 * 60 * 60);
+    assert_eq!(claims["sub"], "mailto:staff@example.com");
+
+    let signature = decode_base64_url(signature).unwrap();
+    assert_eq!(signature.len(), 64);
+    let signature = EcdsaSig::from_private_components(
+        BigNum::from_slice(&signature[..32]).unwrap(),
+        BigNum::from_slice(&signature[32..]).unwrap(),
+    )
+    .unwrap();
+    let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+    let mut ctx = openssl::bn::BigNumContext::new().unwrap();
+    let point = EcPoint::from_bytes(&group, &decode_base64_url(k).unwrap(), &mut ctx).unwrap();
+    let verifying_key = EcKey::from_public_key(&group, &point).unwrap();
+    assert!(signature
+        .verify(&sha256(unsigned.as_bytes()), &verifying_key)
+        .unwrap());
+}
+
+#[actix_web::test]
+async fn notifications_are_detected_once() {
+    let before = HashMap::from([("a".to_string(), 11), ("b".to_string(), 3)]);
+    let after = HashMap::from([
+        ("a".to_string(), 10),
+        ("b".to_string(), 2),
+        ("c".to_string(), 0),
+    ]);
+    // 기준 이하로 새로 줄어든 스템프만 알리고, 이미 알린 스템프와 소진된 스템프는 제외
+    assert_eq!(
+        newly_low_stock(&before, &after, 10),
+        [("a".to_string(), 10)]
+    );
+
+    let completions = CompletionList {
+        completions: BTreeMap::from([(
+            "u1".to_string(),
+            vec![
+                CompletionRecord {
+                    tier_name: "Bronze".to_string(),
+                    timestamp: "2024-10-05 01:00:00 UTC".to_string(),
+                },
+                CompletionRecord {
+                    tier_name: "Gold".to_string(),
+                    timestamp: "2024-10-05 02:00:00 UTC".to_string(),
+                },
+            ],
+        )]),
+        ..Default::default()
+    };
+    let since = Utc.with_ymd_and_hms(2024, 10, 5, 1, 30, 0).unwrap();
+    let until = Utc.with_ymd_and_hms(2024, 10, 5, 3, 0, 0).unwrap();
+    assert_eq!(
+        new_completions(&completions, since, until),
+        [("u1".to_string(), "Gold".to_string())]
+    );
+}
+
+#[actix_web::test]
+async fn visitors_manage_their_subscriptions() {
+    common::setup();
+    let (private_key, public) = generate_vapid_keys();
+    let config: Config = serde_json::from_value(json!({
+        "web_push": { "vapid_private_key": private_key, "subject": "mailto:staff@example.com" }
+    }))
+    .unwrap();
+    let state = AppState::new(
+        config,
+        common::stamp_list(vec![common::stamp("a"), common::stamp("b")]),
+    );
+    common::register(&state, "u1", "visitor");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    let res = test::call_service(
+        &app,
+        test::TestRequest::get()
+            .uri("/api/v1/push/key")
+            .to_request(),
+    )
+    .await;
+    let body: Value = test::read_body_json(res).await;
+    assert_eq!(body["public_key"], public);
+
+    let ua_key =
+        EcKey::generate(&EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap()).unwrap();
+    let subscription = json!({
+        "endpoint": "https://push.example.net/send/abc",
+        "keys": {
+            "p256dh": encode_base64_url(&public_key(&ua_key)),
+            "auth": encode_base64_url(&[7; 16]),
+        },
+    });
+
+    // 등록되지 않은 유저와 잘못된 구독은 거부
+    let res = test::call_service(
+        &app,
+        test::TestRequest::post()
+            .uri("/api/v1/push/subscribe")
+            .cookie(Cookie::new("user_id", "stranger"))
+            .set_json(&subscription)
+            .to_request(),
+    )
+    .await;
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    let res = test::call_service(
+        &app,
+        test::TestRequest::post()
+            .uri("/api/v1/push/subscribe")
+            .cookie(Cookie::new("user_id", "u1"))
+            .set_json(json!({
+                "endpoint": "http://push.example.net/send/abc",
+                "keys": subscription["keys"],
+            }))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    // 같은 구독을 다시 보내도 하나만 저장
+    for _ in 0..2 {
+        let res = test::call_service(
+            &app,
+            test::TestRequest::post()
+                .uri("/api/v1/push/subscribe")
+                .cookie(Cookie::new("user_id", "u1"))
+                .set_json(&subscription)
+                .to_request(),
+        )
+        .await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+    assert_eq!(
+        state.push_subscriptions.lock().unwrap().subscriptions["u1"].len(),
+        1
+    );
+
+    let res = test::call_service(
+        &app,
+        test::TestRequest::post()
+            .uri("/api/v1/push/unsubscribe")
+            .cookie(Cookie::new("user_id", "u1"))
+            .set_json(json!({ "endpoint": "https://push.example.net/send/abc" }))
+            .to_request(),
+    )
+    .await;
+    let body: Value = test::read_body_json(res).await;
+    assert_eq!(body["unsubscribed"], true);
+    assert!(state
+        .push_subscriptions
+        .lock()
+        .unwrap()
+        .subscriptions
+        .is_empty());
+}
@@ -0,0 +1,55 @@
+use actix_web::{middleware::from_fn, test, web, App, HttpMessage, HttpRequest, HttpResponse};
+use gj_stamptour::request_id::{assign_request_id, current, RequestId};
+
+async fn echo(req: HttpRequest) -> HttpResponse {
+    let from_extension = req.extensions().get::<RequestId>().cloned().unwrap().0;
+    assert_eq!(current(), Some(from_extension.clone()));
+    HttpResponse::Ok().body(from_extension)
+}
+
+#[actix_web::test]
+async fn honors_valid_request_id() {
+    let app = test::init_service(
+        App::new()
+            .wrap(from_fn(assign_request_id))
+            .route("/", web::get().to(echo)),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/")
+        .insert_header(("X-Request-Id", "visitor-report-42"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(
+        resp.headers().get("x-request-id").unwrap(),
+        "visitor-report-42"
+    );
+    assert_eq!(test::read_body(resp).await, "visitor-report-42");
+}
+
+#[actix_web::test]
+async fn replaces_invalid_request_id() {
+    let app = test::init_service(
+        App::new()
+            .wrap(from_fn(assign_request_id))
+            .route("/", web::get().to(echo)),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/")
+        .insert_header(("X-Request-Id", "bad id; DROP"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    let request_id = resp
+        .headers()
+        .get("x-request-id")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+    assert_eq!(request_id.len(), 36);
+    assert_eq!(test::read_body(resp).await, request_id);
+    assert_eq!(current(), None);
+}
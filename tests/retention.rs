@@ -0,0 +1,101 @@
+mod common;
+
+use actix_web::{test, App};
+use chrono::{TimeZone, Utc};
+use gj_stamptour::{
+    config::Config,
+    handlers::routes,
+    retention::{purge_if_due, PurgeMode, RetentionConfig},
+    state::Reloadable,
+};
+
+fn retention_config(mode: PurgeMode) -> Config {
+    Config {
+        closes_at: Some("2024-10-05T18:00:00+09:00".to_string()),
+        retention: RetentionConfig {
+            days: Some(30),
+            event_end: None,
+            mode,
+        },
+        ..Default::default()
+    }
+}
+
+#[actix_web::test]
+async fn purge_waits_for_retention_period() {
+    let config = retention_config(PurgeMode::Anonymize);
+    assert_eq!(
+        config.retention.purge_at(config.closes_at.as_deref()),
+        Some(Utc.with_ymd_and_hms(2024, 11, 4, 9, 0, 0).unwrap())
+    );
+    assert_eq!(
+        RetentionConfig::default().purge_at(Some("2024-10-05T18:00:00Z")),
+        None
+    );
+}
+
+#[actix_web::test]
+async fn purges_personal_data_after_retention_period() {
+    let state = common::test_state();
+    state.config.replace(retention_config(PurgeMode::Anonymize));
+    common::register(&state, "u1", "홍길동");
+    common::register(&state, "u2", "김철수");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .app_data(actix_web::web::Data::new(
+                Reloadable::new(Config::default()),
+            ))
+            .configure(routes),
+    )
+    .await;
+    common::collect(&app, "u1", "a").await;
+    common::collect(&app, "u1", "b").await;
+    common::collect(&app, "u2", "a").await;
+
+    let before = Utc.with_ymd_and_hms(2024, 11, 1, 0, 0, 0).unwrap();
+    assert_eq!(purge_if_due(&state, before), None);
+
+    let after = Utc.with_ymd_and_hms(2024, 11, 5, 0, 0, 0).unwrap();
+    assert_eq!(purge_if_due(&state, after), Some(2));
+    assert!(state.user_list.lock().unwrap().users.is_empty());
+
+    let history = &state.stamp_history;
+    assert_eq!(history.entries("a").len(), 2);
+    assert!(history.collected_by("u1").is_empty());
+    assert_eq!(history.collected_by("anon-1").len(), 2);
+    assert!(history
+        .to_map()
+        .values()
+        .flatten()
+        .all(|entry| entry.user_name.is_empty()));
+
+    // 이미 처리한 뒤에는 다시 처리하지 않음
+    assert_eq!(purge_if_due(&state, after), None);
+
+    // 집계 통계 파일을 함께 쓰므로 삭제 모드도 같은 테스트에서 이어서 확인
+    let state = common::test_state();
+    state.config.replace(retention_config(PurgeMode::Delete));
+    common::register(&state, "u1", "홍길동");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .app_data(actix_web::web::Data::new(
+                Reloadable::new(Config::default()),
+            ))
+            .configure(routes),
+    )
+    .await;
+    common::collect(&app, "u1", "a").await;
+
+    let after = Utc.with_ymd_and_hms(2024, 12, 1, 0, 0, 0).unwrap();
+    assert_eq!(purge_if_due(&state, after), Some(1));
+    assert!(state.stamp_history.entries("a").is_empty());
+
+    let stats: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string("resources/database/aggregate_stats.json").unwrap(),
+    )
+    .unwrap();
+    assert_eq!(stats["registered_users"], 1);
+    assert_eq!(stats["stamps_by_booth"]["a"], 1);
+}
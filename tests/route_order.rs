@@ -0,0 +1,60 @@
+mod common;
+
+use actix_web::{http::StatusCode, test, App};
+use gj_stamptour::{config::Config, handlers::routes, state::AppState};
+use serde_json::json;
+
+#[actix_web::test]
+async fn stamps_must_follow_route_stages() {
+    common::setup();
+    let config: Config = serde_json::from_value(json!({
+        "route": { "stages": [["a"], ["b", "c"], ["d"]] }
+    }))
+    .unwrap();
+    let state = AppState::new(
+        config,
+        common::stamp_list(
+            ["a", "b", "c", "d", "free"]
+                .into_iter()
+                .map(common::stamp)
+                .collect(),
+        ),
+    );
+    common::register(&state, "u1", "visitor");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    // 앞 구역을 방문하지 않으면 `/check`가 안내 페이지를 보내고 확인 대기 스템프를 등록하지 않음
+    for stamp_id in ["b", "d"] {
+        let req = test::TestRequest::get()
+            .uri(&format!("/check?s={}", stamp_id))
+            .cookie(actix_web::cookie::Cookie::new("user_id", "u1"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(state
+            .user_stamp_list
+            .lock()
+            .unwrap()
+            .user_stamp_list
+            .is_empty());
+    }
+
+    // 구역에 없는 스템프는 언제든 찍을 수 있음
+    assert_eq!(common::collect(&app, "u1", "free").await, StatusCode::OK);
+
+    assert_eq!(common::collect(&app, "u1", "a").await, StatusCode::OK);
+    assert_eq!(common::collect(&app, "u1", "c").await, StatusCode::OK);
+    // 같은 구역의 스템프를 모두 모아야 다음 구역으로 넘어감
+    assert_eq!(
+        common::collect(&app, "u1", "d").await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(common::collect(&app, "u1", "b").await, StatusCode::OK);
+    assert_eq!(common::collect(&app, "u1", "d").await, StatusCode::OK);
+    assert_eq!(state.stamp_history.collected_by("u1").len(), 5);
+}
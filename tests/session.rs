@@ -0,0 +1,64 @@
+mod common;
+
+use actix_web::{cookie::Cookie, http::StatusCode, test, web, App, HttpResponse};
+use gj_stamptour::{handlers::routes, session::CurrentUser};
+
+async fn whoami(user: CurrentUser) -> HttpResponse {
+    HttpResponse::Ok().body(format!("{} {}", user.user_id, user.user_name))
+}
+
+#[actix_web::test]
+async fn current_user_requires_registered_cookie() {
+    let state = common::test_state();
+    common::register(&state, "u1", "visitor");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .route("/whoami", web::get().to(whoami)),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/whoami")
+        .cookie(Cookie::new("user_id", "u1"))
+        .to_request();
+    assert_eq!(test::call_and_read_body(&app, req).await, "u1 visitor");
+
+    // 쿠키가 없거나 등록되지 않은 유저는 핸들러를 실행하지 않고 401 응답
+    for req in [
+        test::TestRequest::get().uri("/whoami"),
+        test::TestRequest::get()
+            .uri("/whoami")
+            .cookie(Cookie::new("user_id", "forged")),
+    ] {
+        let resp = test::call_service(&app, req.to_request()).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+}
+
+#[actix_web::test]
+async fn user_endpoints_reject_unknown_sessions() {
+    let state = common::test_state();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    for (method, uri) in [
+        (actix_web::http::Method::GET, "/stamp/"),
+        (actix_web::http::Method::GET, "/api/v1/progress"),
+        (actix_web::http::Method::GET, "/complete"),
+        (actix_web::http::Method::POST, "/api/v1/push/unsubscribe"),
+    ] {
+        let req = test::TestRequest::default()
+            .method(method)
+            .uri(uri)
+            .cookie(Cookie::new("user_id", "forged"))
+            .set_json(serde_json::json!({ "endpoint": "https://push.example/1" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED, "{}", uri);
+    }
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use actix_web::{http::StatusCode, test, App};
+use gj_stamptour::{handlers::routes, state::User};
+
+#[actix_web::test]
+async fn share_image_and_page_use_user_code() {
+    let state = common::test_state();
+    let mut user = User {
+        user_name: "<visitor>".to_string(),
+        user_id: "u1".to_string(),
+        team_code: None,
+        user_code: None,
+    };
+    state.user_list.lock().unwrap().insert(&mut user);
+    let user_code = user.user_code.unwrap();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+    common::collect(&app, "u1", "a").await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/share/{}.png", user_code.to_lowercase()))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers().get("content-type").unwrap(), "image/png");
+    let png = test::read_body(resp).await;
+    assert!(png.starts_with(b"\x89PNG"));
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/share/{}", user_code))
+        .insert_header(("Host", "stamp.example.com"))
+        .to_request();
+    let html = String::from_utf8(test::call_and_read_body(&app, req).await.to_vec()).unwrap();
+    assert!(html.contains(&format!(
+        "<meta property=\"og:image\" content=\"http://stamp.example.com/share/{}.png\">",
+        user_code
+    )));
+    assert!(html.contains("&lt;visitor&gt;"));
+    assert!(html.contains("3개 중 1개"));
+
+    let req = test::TestRequest::get()
+        .uri("/share/ZZZZZZ.png")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use gj_stamptour::{
+    config::Config,
+    sheets::{registration_rows, signed_jwt, total_rows, ServiceAccount},
+    state::StampUserInfo,
+};
+use openssl::{base64::decode_block, hash::MessageDigest, pkey::PKey, rsa::Rsa, sign::Verifier};
+use serde_json::{json, Value};
+
+#[actix_web::test]
+async fn sheet_rows_summarize_results() {
+    let state = common::test_state();
+    common::register(&state, "u1", "visitor");
+    state.user_list.lock().unwrap().assign_missing_codes();
+    state.stamp_history.push(
+        "b",
+        StampUserInfo {
+            user_name: "visitor".to_string(),
+            user_id: "u1".to_string(),
+            timestamp: "2024-10-05 01:00:00 UTC".to_string(),
+            seq: 0,
+        },
+    );
+
+    let stamp_id_list = state.stamp_list.get();
+    let users = registration_rows(
+        &Config::default(),
+        &stamp_id_list,
+        &state.stamp_history,
+        &state.user_list.lock().unwrap(),
+        &state.teams.lock().unwrap(),
+    );
+    let user_code = state.user_list.lock().unwrap().codes.keys().next().cloned();
+    assert_eq!(users.len(), 2);
+    assert_eq!(
+        users[1],
+        [
+            json!(user_code),
+            json!("visitor"),
+            json!(""),
+            json!(1),
+            json!(1),
+            json!("")
+        ]
+    );
+
+    let totals = total_rows(&stamp_id_list, &state.stamp_history);
+    assert_eq!(totals[0][0], "stamp_id");
+    let issued: Vec<&Value> = totals[1..].iter().map(|row| &row[2]).collect();
+    assert_eq!(issued, [&json!(0), &json!(1), &json!(0)]);
+}
+
+#[test]
+fn service_account_jwt_is_signed_with_rs256() {
+    let rsa = Rsa::generate(2048).unwrap();
+    let account = ServiceAccount {
+        client_email: "sync@example.iam.gserviceaccount.com".to_string(),
+        private_key: String::from_utf8(rsa.private_key_to_pem().unwrap()).unwrap(),
+        token_uri: "https://oauth2.googleapis.com/token".to_string(),
+    };
+    let jwt = signed_jwt(&account, 1_700_000_000).unwrap();
+    let parts: Vec<&str> = jwt.split('.').collect();
+    assert_eq!(parts.len(), 3);
+
+    let decode = |part: &str| {
+        let mut base64 = part.replace('-', "+").replace('_', "/");
+        while !base64.len().is_multiple_of(4) {
+            base64.push('=');
+        }
+        decode_block(&base64).unwrap()
+    };
+    let claims: Value = serde_json::from_slice(&decode(parts[1])).unwrap();
+    assert_eq!(claims["iss"], account.client_email);
+    assert_eq!(claims["exp"], 1_700_003_600);
+
+    let key = PKey::from_rsa(rsa).unwrap();
+    let mut verifier = Verifier::new(MessageDigest::sha256(), &key).unwrap();
+    verifier
+        .update(format!("{}.{}", parts[0], parts[1]).as_bytes())
+        .unwrap();
+    assert!(verifier.verify(&decode(parts[2])).unwrap());
+}
@@ -0,0 +1,123 @@
+mod common;
+
+use actix_web::{http::StatusCode, test, App};
+use chrono::Utc;
+use gj_stamptour::{
+    booth::is_valid_qr_token,
+    config::Config,
+    handlers::{admin_routes, routes},
+    state::{AppState, Command},
+};
+use serde_json::json;
+
+fn short_link_state(config: Config) -> AppState {
+    common::setup();
+    let mut library = common::stamp("library");
+    library.stampAliases = vec!["library-gate".to_string()];
+    AppState::new(config, common::stamp_list(vec![library]))
+}
+
+fn admin_command(command: &str) -> actix_http::Request {
+    test::TestRequest::post()
+        .uri("/admin")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .set_json(json!({ "command": command, "output": "" }))
+        .to_request()
+}
+
+#[actix_web::test]
+async fn short_links_redirect_until_revoked() {
+    let state = short_link_state(Config::default());
+    let admin = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes),
+    )
+    .await;
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    let output: Command =
+        test::call_and_read_body_json(&admin, admin_command("short create gym")).await;
+    assert_eq!(output.output, "Stamp gym not found");
+    let output: Command =
+        test::call_and_read_body_json(&admin, admin_command("short create library-gate")).await;
+    let short = output
+        .output
+        .strip_prefix("/s/")
+        .and_then(|rest| rest.split_once(' '))
+        .map(|(short, _)| short.to_string())
+        .unwrap();
+    assert_eq!(
+        output.output,
+        format!("/s/{} -> /check?s=library-gate", short)
+    );
+
+    // 받아 적은 소문자 코드도 같은 주소로 이동
+    for path in [
+        format!("/s/{}", short),
+        format!("/s/{}", short.to_lowercase()),
+    ] {
+        let req = test::TestRequest::get().uri(&path).to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::TEMPORARY_REDIRECT);
+        assert_eq!(
+            res.headers().get("Location").unwrap(),
+            "/check?s=library-gate"
+        );
+    }
+
+    let output: Command =
+        test::call_and_read_body_json(&admin, admin_command(&format!("short revoke {}", short)))
+            .await;
+    assert_eq!(output.output, format!("Short link {} revoked", short));
+    let req = test::TestRequest::get()
+        .uri(&format!("/s/{}", short))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+    let output: Command = test::call_and_read_body_json(&admin, admin_command("short list")).await;
+    assert_eq!(
+        output.output,
+        format!("/s/{} -> library-gate (revoked)", short)
+    );
+    let req = test::TestRequest::get().uri("/s/ZZZZZ").to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn short_links_carry_current_qr_token() {
+    let config: Config =
+        serde_json::from_value(json!({ "booth": { "qr_rotation_secs": 60 } })).unwrap();
+    let state = short_link_state(config);
+    let short = state.short_links.lock().unwrap().create("library");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/s/{}", short))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::TEMPORARY_REDIRECT);
+    let location = res.headers().get("Location").unwrap().to_str().unwrap();
+    let token = location
+        .strip_prefix("/check?t=")
+        .and_then(|rest| rest.strip_suffix("&s=library"))
+        .unwrap();
+    assert!(is_valid_qr_token(
+        &state.config.get().booth,
+        "library",
+        Some(token),
+        Utc::now()
+    ));
+}
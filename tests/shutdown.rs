@@ -0,0 +1,70 @@
+mod common;
+
+use actix_web::{
+    middleware::from_fn,
+    web::{self, Data},
+    App, HttpResponse, HttpServer,
+};
+use gj_stamptour::{
+    metrics::record_latency,
+    persistence::{self, Dataset},
+    shutdown::drain,
+    state::StampUserInfo,
+};
+use std::{fs, time::Duration};
+
+#[actix_web::test]
+async fn in_flight_requests_finish_and_are_saved_on_shutdown() {
+    let state = common::test_state();
+    persistence::start(&state);
+
+    // 기록을 남기기 전에 잠시 기다리는 느린 스템프 요청
+    let slow_state = state.clone();
+    let server = HttpServer::new(move || {
+        let state = slow_state.clone();
+        App::new()
+            .wrap(from_fn(record_latency))
+            .app_data(Data::clone(&state.metrics))
+            .route(
+                "/stamp/",
+                web::get().to(move || {
+                    let state = state.clone();
+                    async move {
+                        actix_rt::time::sleep(Duration::from_millis(300)).await;
+                        state.stamp_history.push(
+                            "a",
+                            StampUserInfo {
+                                user_name: "visitor".to_string(),
+                                user_id: "u1".to_string(),
+                                timestamp: "2024-10-05 01:00:00 UTC".to_string(),
+                                seq: 0,
+                            },
+                        );
+                        state.persister.mark(&[Dataset::StampStatus]);
+                        HttpResponse::Ok().finish()
+                    }
+                }),
+            )
+    })
+    .workers(1)
+    .shutdown_timeout(5)
+    .disable_signals()
+    .bind(("127.0.0.1", 0))
+    .unwrap();
+    let port = server.addrs()[0].port();
+    let server = server.run();
+    let handle = server.handle();
+    actix_rt::spawn(server);
+
+    let request = actix_rt::spawn(reqwest::get(format!("http://127.0.0.1:{}/stamp/", port)));
+    actix_rt::time::sleep(Duration::from_millis(100)).await;
+
+    // 요청을 처리하는 도중에 종료해도 응답을 보낸 뒤 멈춤
+    drain(vec![handle], &state.metrics, Duration::from_secs(5)).await;
+    assert!(request.await.unwrap().unwrap().status().is_success());
+    assert_eq!(state.metrics.in_flight(), 0);
+
+    assert_eq!(state.persister.flush().await, Ok(()));
+    let saved = fs::read_to_string("resources/database/stamp_status.json").unwrap();
+    assert!(saved.contains("u1"));
+}
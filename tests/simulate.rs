@@ -0,0 +1,57 @@
+mod common;
+
+use actix_web::{App, HttpServer};
+use gj_stamptour::{
+    handlers::routes,
+    simulate::{simulate, Step},
+};
+use std::net::TcpListener;
+
+#[actix_web::test]
+async fn simulated_visitors_complete_the_tour() {
+    let state = common::test_state();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let server_state = state.clone();
+    let server = HttpServer::new(move || {
+        App::new()
+            .configure(|cfg| server_state.register(cfg))
+            .configure(routes)
+    })
+    .workers(1)
+    .listen(listener)
+    .unwrap()
+    .run();
+    let handle = server.handle();
+    actix_rt::spawn(server);
+
+    let report = simulate(&format!("http://{}/", address), 4).await.unwrap();
+    handle.stop(true).await;
+
+    // 방문객마다 한 번 등록하고, 스템프마다 확인과 찍기를 한 번씩 요청
+    assert_eq!(report.failures(), 0, "{}", report);
+    assert_eq!(report.steps[&Step::Login].latencies.len(), 4);
+    assert_eq!(report.steps[&Step::Check].latencies.len(), 12);
+    assert_eq!(report.steps[&Step::Stamp].latencies.len(), 12);
+    assert_eq!(report.requests(), 28);
+    assert!(report.throughput() > 0.0);
+    assert!(report
+        .to_string()
+        .contains("4 visitors, 28 requests (0 failed)"));
+
+    // 스템프마다 가상 방문객 네 명의 발급 기록이 남음
+    for stamp_id in ["a", "b", "c"] {
+        assert_eq!(state.stamp_history.entries(stamp_id).len(), 4);
+    }
+}
+
+#[actix_web::test]
+async fn unreachable_target_is_reported() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    drop(listener);
+    let error = simulate(&format!("http://{}", address), 1)
+        .await
+        .unwrap_err();
+    assert!(error.starts_with("Cannot fetch stamp list"));
+}
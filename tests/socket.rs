@@ -0,0 +1,76 @@
+#![cfg(unix)]
+
+use gj_stamptour::{
+    config::handle_args,
+    socket::{listen_fds, notify, remove_stale_socket, set_socket_mode},
+};
+use std::{
+    env, fs,
+    os::unix::{
+        fs::PermissionsExt,
+        net::{UnixDatagram, UnixListener},
+    },
+};
+
+#[test]
+fn bind_unix_args_are_parsed() {
+    let args: Vec<String> = [
+        "server",
+        "--bind-unix",
+        "/run/stamptour.sock",
+        "--unix-mode",
+        "600",
+    ]
+    .iter()
+    .map(|arg| arg.to_string())
+    .collect();
+    let address = handle_args(args.clone(), args.len());
+    assert_eq!(address.unix_socket.as_deref(), Some("/run/stamptour.sock"));
+    assert_eq!(address.unix_socket_mode, 0o600);
+}
+
+#[test]
+fn stale_socket_is_replaced_but_regular_file_is_kept() {
+    let dir = env::temp_dir().join(format!("stamptour-socket-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let socket_path = dir.join("stamptour.sock");
+    drop(UnixListener::bind(&socket_path).unwrap());
+    remove_stale_socket(&socket_path).unwrap();
+    assert!(!socket_path.exists());
+
+    UnixListener::bind(&socket_path).unwrap();
+    set_socket_mode(&socket_path, 0o660).unwrap();
+    let mode = fs::metadata(&socket_path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o660);
+
+    let file_path = dir.join("not-a-socket");
+    fs::write(&file_path, "keep").unwrap();
+    assert!(remove_stale_socket(&file_path).is_err());
+    assert!(file_path.exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn systemd_listen_fds_require_matching_pid() {
+    assert_eq!(listen_fds(Some("42"), Some("2"), 42), 2);
+    assert_eq!(listen_fds(Some("41"), Some("2"), 42), 0);
+    assert_eq!(listen_fds(None, Some("2"), 42), 0);
+    assert_eq!(listen_fds(Some("42"), Some("x"), 42), 0);
+}
+
+#[test]
+fn readiness_is_sent_to_notify_socket() {
+    let dir = env::temp_dir().join(format!("stamptour-notify-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let socket_path = dir.join("notify.sock");
+    let receiver = UnixDatagram::bind(&socket_path).unwrap();
+
+    notify(socket_path.to_str().unwrap(), "READY=1").unwrap();
+    let mut buffer = [0; 16];
+    let read = receiver.recv(&mut buffer).unwrap();
+    assert_eq!(&buffer[..read], b"READY=1");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
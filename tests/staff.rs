@@ -0,0 +1,189 @@
+mod common;
+
+use actix_web::{http::StatusCode, test, App};
+use gj_stamptour::{
+    config::Config,
+    handlers::{admin_routes, routes},
+    progress::Progress,
+    staff::StaffUser,
+    state::{AppState, RedemptionRecord, StampUserInfo, User},
+};
+use serde_json::json;
+
+#[actix_web::test]
+async fn staff_finds_user_by_short_code() {
+    let state = common::test_state();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes)
+            .configure(routes),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/login")
+        .set_json(json!({ "user_name": "visitor" }))
+        .to_request();
+    let user: User = test::call_and_read_body_json(&app, req).await;
+    let user_code = user.user_code.unwrap();
+    assert_eq!(user_code.len(), 6);
+    common::collect(&app, &user.user_id, "a").await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/staff/users/{}", user_code.to_lowercase()))
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .to_request();
+    let found: StaffUser = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(found.user_code, user_code);
+    assert_eq!(found.progress.user_id, user.user_id);
+    assert_eq!(found.progress.collected_count, 1);
+
+    let req = test::TestRequest::get()
+        .uri("/api/staff/users/ZZZZZZ")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn staff_token_is_required_when_configured() {
+    common::setup();
+    let config: Config = serde_json::from_value(json!({ "staff_token": "booth-secret" })).unwrap();
+    let state = AppState::new(config, common::stamp_list(vec![common::stamp("a")]));
+    let mut user = User {
+        user_name: "visitor".to_string(),
+        user_id: "u1".to_string(),
+        team_code: None,
+        user_code: None,
+    };
+    state.user_list.lock().unwrap().insert(&mut user);
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes)
+            .configure(routes),
+    )
+    .await;
+    let uri = format!("/api/staff/users/{}", user.user_code.unwrap());
+
+    let req = test::TestRequest::get()
+        .uri(&uri)
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    let req = test::TestRequest::get()
+        .uri(&uri)
+        .peer_addr("10.0.0.5:40000".parse().unwrap())
+        .insert_header(("Authorization", "Bearer booth-secret"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn staff_revokes_stamp_with_audit_record() {
+    let state = common::test_state();
+    common::register(&state, "u1", "visitor");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes)
+            .configure(routes),
+    )
+    .await;
+    common::collect(&app, "u1", "a").await;
+    common::collect(&app, "u1", "b").await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/staff/revoke")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .set_json(json!({
+            "user": "u1",
+            "stamp_id": "a",
+            "revoked_by": "김선생",
+            "reason": "다른 방문객에게 잘못 찍음"
+        }))
+        .to_request();
+    let revoked: StampUserInfo = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(revoked.user_id, "u1");
+
+    let collected = state.stamp_history.collected_by("u1");
+    assert_eq!(
+        collected.into_iter().collect::<Vec<_>>(),
+        vec!["b".to_string()]
+    );
+
+    let audit = state.audit_log.lock().unwrap().entries.clone();
+    assert_eq!(audit.len(), 1);
+    assert_eq!(audit[0].action, "revoke");
+    assert_eq!(audit[0].actor, "김선생");
+    assert_eq!(audit[0].stamp_id.as_deref(), Some("a"));
+
+    // 이미 취소된 기록은 다시 취소할 수 없음
+    let req = test::TestRequest::post()
+        .uri("/api/staff/revoke")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .set_json(
+            json!({ "user": "u1", "stamp_id": "a", "revoked_by": "김선생", "reason": "중복" }),
+        )
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    assert_eq!(state.audit_log.lock().unwrap().entries.len(), 1);
+}
+
+#[actix_web::test]
+async fn completion_code_redeems_prize_only_once() {
+    let state = common::test_state();
+    common::register(&state, "u1", "visitor");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes)
+            .configure(routes),
+    )
+    .await;
+    let progress = || {
+        test::TestRequest::get()
+            .uri("/progress")
+            .cookie(actix_web::cookie::Cookie::new("user_id", "u1"))
+            .to_request()
+    };
+    let redeem = |code: &str| {
+        test::TestRequest::post()
+            .uri("/staff/redeem")
+            .peer_addr("127.0.0.1:40000".parse().unwrap())
+            .set_json(json!({ "code": code, "redeemed_by": "김선생" }))
+            .to_request()
+    };
+
+    // 완주하기 전에는 완주 코드가 없음
+    common::collect(&app, "u1", "a").await;
+    let before: Progress = test::call_and_read_body_json(&app, progress()).await;
+    assert_eq!(before.completion_code, None);
+
+    common::collect(&app, "u1", "b").await;
+    common::collect(&app, "u1", "c").await;
+    let after: Progress = test::call_and_read_body_json(&app, progress()).await;
+    let code = after.completion_code.unwrap();
+    assert!(!after.redeemed);
+
+    let record: RedemptionRecord =
+        test::call_and_read_body_json(&app, redeem(&code.to_lowercase())).await;
+    assert_eq!(record.user_id, "u1");
+    assert_eq!(record.tier_name, "complete");
+
+    // 같은 코드로 다시 받을 수 없음
+    let resp = test::call_service(&app, redeem(&code)).await;
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+    let resp = test::call_service(&app, redeem("NOTACODE")).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    let after: Progress = test::call_and_read_body_json(&app, progress()).await;
+    assert!(after.redeemed);
+    assert_eq!(state.completions.lock().unwrap().redemptions.len(), 1);
+}
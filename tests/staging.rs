@@ -0,0 +1,85 @@
+mod common;
+
+use actix_web::{cookie::Cookie, http::StatusCode, test, App};
+use gj_stamptour::{
+    config::Config,
+    event::EventInfo,
+    handlers::routes,
+    staging::{self, is_staging, BANNER},
+    state::AppState,
+    storage::{write_database, DatabaseFormat},
+};
+use serde_json::json;
+
+#[actix_web::test]
+async fn staging_saves_separately_and_shows_banner() {
+    let dir = common::setup();
+    let mut config: Config = serde_json::from_value(json!({
+        "staging": true,
+        "backup": {
+            "dir": "resources/backups",
+            "s3": { "endpoint": "http://localhost:9000", "bucket": "stamptour", "access_key": "a", "secret_key": "b" }
+        },
+        "sheets": { "spreadsheet_id": "sheet", "credentials_file": "key.json" }
+    }))
+    .unwrap();
+    assert!(!is_staging());
+    staging::apply(&mut config);
+
+    // 시험 운영 중에는 실제 백업과 외부 저장소, 스프레드시트를 건드리지 않음
+    assert!(is_staging());
+    assert_eq!(config.backup.dir, "resources/backups/staging");
+    assert!(config.backup.s3.is_none());
+    assert!(config.sheets.is_none());
+
+    // 한 번 켜면 설정에서 빠져도 꺼지지 않음
+    let mut reloaded = Config::default();
+    staging::apply(&mut reloaded);
+    assert!(reloaded.staging);
+
+    write_database("staging_test", DatabaseFormat::Json, b"{}").unwrap();
+    assert!(dir.join("resources/staging/staging_test.json").exists());
+    assert!(!dir.join("resources/database/staging_test.json").exists());
+
+    let state = AppState::new(
+        config,
+        common::stamp_list(vec![common::stamp("a"), common::stamp("b")]),
+    );
+    common::register(&state, "u1", "홍길동");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+    assert_eq!(common::collect(&app, "u1", "a").await, StatusCode::OK);
+
+    let req = test::TestRequest::get()
+        .uri("/me")
+        .cookie(Cookie::new("user_id", "u1"))
+        .to_request();
+    let html = String::from_utf8(test::call_and_read_body(&app, req).await.to_vec()).unwrap();
+    assert!(html.contains(BANNER));
+    assert!(html.contains("1 / 2"));
+
+    // 스템프를 찍은 뒤의 페이지도 캐시 여부와 관계없이 띠를 보여줌
+    for _ in 0..2 {
+        let req = test::TestRequest::get()
+            .uri("/check?s=b")
+            .cookie(Cookie::new("user_id", "u1"))
+            .to_request();
+        test::call_service(&app, req).await;
+        let req = test::TestRequest::get()
+            .uri("/stamp/")
+            .cookie(Cookie::new("user_id", "u1"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let html = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        assert!(html.contains(BANNER));
+    }
+
+    let req = test::TestRequest::get().uri("/api/v1/event").to_request();
+    let event: EventInfo = test::call_and_read_body_json(&app, req).await;
+    assert!(event.staging);
+}
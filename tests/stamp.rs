@@ -0,0 +1,335 @@
+mod common;
+
+use actix_web::{cookie::Cookie, http::StatusCode, test, App};
+use gj_stamptour::{
+    config::Config,
+    handlers::{admin_routes, routes},
+    progress::Progress,
+    state::{TourStatus, User},
+    tour::is_closed,
+};
+use serde_json::json;
+
+#[actix_web::test]
+async fn check_then_stamp_records_history() {
+    let state = common::test_state();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/login")
+        .set_json(json!({ "user_name": "visitor" }))
+        .to_request();
+    let user: User = test::call_and_read_body_json(&app, req).await;
+
+    let req = test::TestRequest::get()
+        .uri("/check?s=b")
+        .cookie(Cookie::new("user_id", user.user_id.clone()))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::TEMPORARY_REDIRECT);
+    assert_eq!(
+        state
+            .user_stamp_list
+            .lock()
+            .unwrap()
+            .user_stamp_list
+            .get(&user.user_id),
+        Some(&"b".to_string())
+    );
+
+    let req = test::TestRequest::get()
+        .uri("/stamp/?random=1")
+        .cookie(Cookie::new("user_id", user.user_id.clone()))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let entries = state.stamp_history.entries("b");
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].user_id, user.user_id);
+    assert_eq!(entries[0].user_name, "visitor");
+    assert!(state
+        .user_stamp_list
+        .lock()
+        .unwrap()
+        .user_stamp_list
+        .is_empty());
+}
+
+#[actix_web::test]
+async fn check_ignores_unknown_user_and_stamp() {
+    let state = common::test_state();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    // 등록되지 않은 쿠키
+    let req = test::TestRequest::get()
+        .uri("/check?s=a")
+        .cookie(Cookie::new("user_id", "forged"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::TEMPORARY_REDIRECT);
+
+    // 존재하지 않는 스템프
+    state
+        .user_list
+        .lock()
+        .unwrap()
+        .users
+        .insert("u1".to_string(), "visitor".to_string());
+    let req = test::TestRequest::get()
+        .uri("/check?s=zzz")
+        .cookie(Cookie::new("user_id", "u1"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::TEMPORARY_REDIRECT);
+
+    assert!(state
+        .user_stamp_list
+        .lock()
+        .unwrap()
+        .user_stamp_list
+        .is_empty());
+}
+
+#[actix_web::test]
+async fn check_reads_stamp_id_regardless_of_parameter_order() {
+    let state = common::test_state();
+    common::register(&state, "u1", "visitor");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    // 다른 매개변수 뒤에 오거나 앞에 와도 스템프 ID만 읽음
+    for (query, expected) in [
+        ("s=b&lang=en", Some("b")),
+        ("lang=en&s=c", Some("c")),
+        ("lists=a", None),
+    ] {
+        state
+            .user_stamp_list
+            .lock()
+            .unwrap()
+            .user_stamp_list
+            .clear();
+        let req = test::TestRequest::get()
+            .uri(&format!("/check?{}", query))
+            .cookie(Cookie::new("user_id", "u1"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::TEMPORARY_REDIRECT);
+        assert_eq!(
+            state
+                .user_stamp_list
+                .lock()
+                .unwrap()
+                .user_stamp_list
+                .get("u1")
+                .map(String::as_str),
+            expected,
+            "{}",
+            query
+        );
+    }
+}
+
+#[actix_web::test]
+async fn stamp_without_pending_check_is_unauthorized() {
+    let state = common::test_state();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    let req = test::TestRequest::get().uri("/stamp/").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    let req = test::TestRequest::get()
+        .uri("/stamp/")
+        .cookie(Cookie::new("user_id", "u1"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn alias_codes_record_the_canonical_stamp() {
+    common::setup();
+    let mut library = common::stamp("library");
+    library.stampAliases = vec!["poster-1".to_string(), "poster-2".to_string()];
+    let state = common::state_with(common::stamp_list(vec![library, common::stamp("gym")]));
+    common::register(&state, "u1", "visitor");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/check?s=poster-2")
+        .cookie(Cookie::new("user_id", "u1"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::TEMPORARY_REDIRECT);
+    let req = test::TestRequest::get()
+        .uri("/stamp/")
+        .cookie(Cookie::new("user_id", "u1"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(state.stamp_history.entries("library").len(), 1);
+
+    // 목록에서 지운 별칭은 더 이상 받지 않음
+    let mut stamps = state.stamp_list.get().as_ref().clone();
+    stamps
+        .stamp_id_list
+        .get_mut("library")
+        .unwrap()
+        .stampAliases = vec!["poster-1".to_string()];
+    state.stamp_list.replace(stamps);
+    let req = test::TestRequest::get()
+        .uri("/check?s=poster-2")
+        .cookie(Cookie::new("user_id", "u1"))
+        .to_request();
+    test::call_service(&app, req).await;
+    assert!(state
+        .user_stamp_list
+        .lock()
+        .unwrap()
+        .user_stamp_list
+        .is_empty());
+}
+
+#[actix_web::test]
+async fn limited_stamp_sells_out() {
+    common::setup();
+    let mut limited = common::stamp("limited");
+    limited.maxIssued = Some(1);
+    let state = common::state_with(common::stamp_list(vec![limited]));
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    for user_id in ["u1", "u2"] {
+        state
+            .user_list
+            .lock()
+            .unwrap()
+            .users
+            .insert(user_id.to_string(), user_id.to_string());
+    }
+
+    // 두 유저가 동시에 확인을 마친 경우
+    for user_id in ["u1", "u2"] {
+        let req = test::TestRequest::get()
+            .uri("/check?s=limited")
+            .cookie(Cookie::new("user_id", user_id))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::TEMPORARY_REDIRECT);
+    }
+
+    // 먼저 찍은 유저만 기록되고 나중 유저는 품절 페이지를 받음
+    for user_id in ["u1", "u2"] {
+        let req = test::TestRequest::get()
+            .uri("/stamp/")
+            .cookie(Cookie::new("user_id", user_id))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+    assert_eq!(state.stamp_history.entries("limited").len(), 1);
+
+    // 품절 이후의 확인은 리다이렉션 없이 바로 품절 페이지
+    let req = test::TestRequest::get()
+        .uri("/check?s=limited")
+        .cookie(Cookie::new("user_id", "u2"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(state
+        .user_stamp_list
+        .lock()
+        .unwrap()
+        .user_stamp_list
+        .is_empty());
+}
+
+#[actix_web::test]
+async fn closed_tour_rejects_login_and_stamping() {
+    let state = common::test_state();
+    common::register(&state, "u1", "visitor");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes)
+            .configure(routes),
+    )
+    .await;
+    assert_eq!(common::collect(&app, "u1", "a").await, StatusCode::OK);
+
+    let req = test::TestRequest::post()
+        .uri("/admin")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .set_json(json!({ "command": "close tour", "output": "" }))
+        .to_request();
+    test::call_service(&app, req).await;
+
+    assert_eq!(
+        common::collect(&app, "u1", "b").await,
+        StatusCode::FORBIDDEN
+    );
+
+    let req = test::TestRequest::post()
+        .uri("/login")
+        .set_json(json!({ "user_name": "late" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    // 결과 조회는 종료 이후에도 가능
+    let req = test::TestRequest::get()
+        .uri("/progress")
+        .cookie(Cookie::new("user_id", "u1"))
+        .to_request();
+    let progress: Progress = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(progress.collected, vec!["a".to_string()]);
+}
+
+#[actix_web::test]
+async fn scheduled_close_can_be_overridden() {
+    let config: Config =
+        serde_json::from_value(json!({ "closes_at": "2024-10-26T18:00:00+09:00" })).unwrap();
+    let before = "2024-10-26T08:00:00Z".parse().unwrap();
+    let after = "2024-10-26T09:00:00Z".parse().unwrap();
+
+    assert!(!is_closed(&config, &TourStatus::default(), before));
+    assert!(is_closed(&config, &TourStatus::default(), after));
+    assert!(!is_closed(
+        &config,
+        &TourStatus {
+            closed: Some(false),
+            ..Default::default()
+        },
+        after
+    ));
+}
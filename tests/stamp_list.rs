@@ -0,0 +1,55 @@
+use gj_stamptour::storage::parse_stamp_list;
+
+#[test]
+fn reports_syntax_error_location() {
+    let error =
+        parse_stamp_list("{\n  \"stampList\": [\n    {\"stampId\": \"a\",}\n  ]\n}").unwrap_err();
+    assert!(error.contains("line 3 column 21"), "{}", error);
+    assert!(error.contains("{\"stampId\": \"a\",}"), "{}", error);
+}
+
+#[test]
+fn reports_duplicate_ids_and_empty_fields() {
+    let error = parse_stamp_list(
+        r#"{"stampList": [
+            {"stampId": "a", "stampLocation": "1층", "stampName": "A", "stampDesc": ""},
+            {"stampId": "b", "stampLocation": "", "stampName": "B", "stampDesc": ""},
+            {"stampId": "a", "stampLocation": "2층", "stampName": "A2", "stampDesc": ""}
+        ]}"#,
+    )
+    .unwrap_err();
+    assert_eq!(
+        error,
+        "stampList[1]: stampLocation is empty\nstampList[2]: duplicate stampId \"a\" (first defined at stampList[0])"
+    );
+}
+
+#[test]
+fn reports_conflicting_aliases() {
+    let error = parse_stamp_list(
+        r#"{"stampList": [
+            {"stampId": "a", "stampLocation": "1층", "stampName": "A", "stampDesc": "", "stampAliases": ["a-1", "b"]},
+            {"stampId": "b", "stampLocation": "2층", "stampName": "B", "stampDesc": "", "stampAliases": ["a-1", ""]}
+        ]}"#,
+    )
+    .unwrap_err();
+    assert_eq!(
+        error,
+        "stampList[0]: alias \"b\" is the stampId of stampList[1]\nstampList[1]: duplicate alias \"a-1\" (first defined at stampList[0])\nstampList[1]: stampAliases has an empty code"
+    );
+}
+
+#[test]
+fn accepts_valid_list() {
+    let stamps = parse_stamp_list(
+        r#"{"stampList": [
+            {"stampId": "a", "stampLocation": "1층", "stampName": "A", "stampDesc": ""}
+        ]}"#,
+    )
+    .unwrap();
+    assert_eq!(stamps.stamp_id_list["a"].stampName, "A");
+    assert_eq!(
+        parse_stamp_list(r#"{"stampList": []}"#).unwrap_err(),
+        "Stamp list is empty"
+    );
+}
@@ -0,0 +1,122 @@
+mod common;
+
+use actix_web::{http::StatusCode, test, App};
+use chrono::{Duration, Utc};
+use gj_stamptour::{
+    handlers::{admin_routes, routes},
+    state::{CompletionRecord, StampUserInfo},
+    stats::{parse_bucket, PublicStats, Timeseries},
+};
+
+fn entry(user_id: &str, timestamp: String) -> StampUserInfo {
+    StampUserInfo {
+        user_name: String::new(),
+        user_id: user_id.to_string(),
+        timestamp,
+        seq: 0,
+    }
+}
+
+#[actix_web::test]
+async fn public_stats_are_aggregated_and_cached() {
+    let state = common::test_state();
+    common::register(&state, "u1", "visitor");
+    common::register(&state, "u2", "visitor");
+    {
+        let history = &state.stamp_history;
+        history.push("a", entry("u1", Utc::now().to_string()));
+        history.push(
+            "a",
+            entry("u2", (Utc::now() - Duration::days(2)).to_string()),
+        );
+        history.push("b", entry("u1", Utc::now().to_string()));
+    }
+    state.completions.lock().unwrap().completions.insert(
+        "u1".to_string(),
+        vec![CompletionRecord {
+            tier_name: "grand".to_string(),
+            timestamp: Utc::now().to_string(),
+        }],
+    );
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/stats/public")
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(
+        res.headers().get("Cache-Control").unwrap(),
+        "public, max-age=10"
+    );
+    let stats: PublicStats = test::read_body_json(res).await;
+    assert_eq!(
+        stats,
+        PublicStats {
+            participants: 2,
+            stamps_today: 2,
+            completions: 1,
+        }
+    );
+
+    // 캐시 유지 시간 안에는 새 유저가 반영되지 않음
+    common::register(&state, "u3", "visitor");
+    let req = test::TestRequest::get()
+        .uri("/api/stats/public")
+        .to_request();
+    let stats: PublicStats = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(stats.participants, 2);
+}
+
+#[actix_web::test]
+async fn timeseries_buckets_stamps() {
+    assert_eq!(parse_bucket("15m"), Some(Duration::minutes(15)));
+    assert_eq!(parse_bucket("2h"), Some(Duration::hours(2)));
+    assert_eq!(parse_bucket("0m"), None);
+    assert_eq!(parse_bucket("15분"), None);
+
+    let state = common::test_state();
+    {
+        let history = &state.stamp_history;
+        history.push("a", entry("u1", "2024-10-05 01:02:00 UTC".to_string()));
+        history.push("a", entry("u2", "2024-10-05 01:14:59 UTC".to_string()));
+        history.push("a", entry("u3", "2024-10-05 01:45:00 UTC".to_string()));
+        history.push("b", entry("u1", "2024-10-05 01:20:00 UTC".to_string()));
+    }
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(admin_routes),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/stats/timeseries?bucket=15m")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .to_request();
+    let series: Timeseries = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(series.bucket_seconds, 900);
+    assert_eq!(series.buckets.len(), 4);
+    assert_eq!(series.buckets[0], "2024-10-05T01:00:00+00:00");
+    assert_eq!(series.stamps["a"], vec![2, 0, 0, 1]);
+    assert_eq!(series.stamps["b"], vec![0, 1, 0, 0]);
+    assert_eq!(series.stamps["c"], vec![0, 0, 0, 0]);
+
+    let req = test::TestRequest::get()
+        .uri("/api/stats/timeseries?bucket=15x")
+        .peer_addr("127.0.0.1:40000".parse().unwrap())
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let req = test::TestRequest::get()
+        .uri("/api/stats/timeseries")
+        .peer_addr("10.0.0.5:40000".parse().unwrap())
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,151 @@
+mod common;
+
+use actix_web::{test, App};
+use chrono::{Duration, Utc};
+use gj_stamptour::{
+    booth::booth_key,
+    config::Config,
+    handlers::routes,
+    state::{AppState, User},
+    sync::{event_signature, SyncEvent, SyncResponse, SyncStatus},
+};
+use serde_json::json;
+
+fn event(user_code: &str, stamp_id: &str, timestamp: &str) -> SyncEvent {
+    SyncEvent {
+        user_code: user_code.to_string(),
+        stamp_id: stamp_id.to_string(),
+        timestamp: timestamp.to_string(),
+        signature: event_signature(
+            &booth_key("secret", stamp_id),
+            user_code,
+            stamp_id,
+            timestamp,
+        ),
+    }
+}
+
+#[actix_web::test]
+async fn sync_merges_signed_events_once() {
+    common::setup();
+    let config: Config = serde_json::from_value(json!({ "staff_token": "secret" })).unwrap();
+    let state = AppState::new(
+        config,
+        common::stamp_list(vec![common::stamp("a"), common::stamp("b")]),
+    );
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/login")
+        .set_json(json!({ "user_name": "visitor" }))
+        .to_request();
+    let user: User = test::call_and_read_body_json(&app, req).await;
+    let user_code = user.user_code.unwrap();
+
+    let earlier = (Utc::now() - Duration::minutes(30)).to_rfc3339();
+    let later = (Utc::now() - Duration::minutes(10)).to_rfc3339();
+    let mut forged = event(&user_code, "b", &earlier);
+    forged.signature = event_signature(&booth_key("secret", "a"), &user_code, "b", &earlier);
+    let future = (Utc::now() + Duration::hours(1)).to_rfc3339();
+    // 길이가 다른 서명도 비교 도중 멈추지 않고 거절됨
+    let mut truncated = event(&user_code, "b", &earlier);
+    truncated.signature.truncate(8);
+    let events = vec![
+        event(&user_code, "a", &later),
+        event(&user_code, "a", &earlier),
+        event(&user_code, "a", &later),
+        forged,
+        event("ZZZZZZ", "a", &earlier),
+        event(&user_code, "b", &future),
+        truncated,
+    ];
+
+    let req = test::TestRequest::post()
+        .uri("/api/sync")
+        .set_json(json!({ "events": events }))
+        .to_request();
+    let response: SyncResponse = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(
+        (response.accepted, response.duplicates, response.rejected),
+        (2, 1, 4)
+    );
+    assert_eq!(response.results[2].status, SyncStatus::Duplicate);
+    // 중복 기록은 처음 반영된 기록의 순번을 돌려줌
+    assert_eq!(response.results[2].seq, response.results[0].seq);
+    assert_eq!(response.results[3].seq, None);
+    assert_eq!(
+        response.results[3].reason.as_deref(),
+        Some("Invalid signature")
+    );
+    assert_eq!(
+        response.results[6].reason.as_deref(),
+        Some("Invalid signature")
+    );
+
+    // 오프라인 기록은 시각 순서대로 들어감
+    {
+        let entries = state.stamp_history.entries("a");
+        assert_eq!(entries.len(), 2);
+        assert!(entries[0].timestamp < entries[1].timestamp);
+        // 시각이 더 이른 기록이 나중에 반영되었으므로 순번은 더 큼
+        assert!(entries[0].seq > entries[1].seq);
+        assert_eq!(entries[0].user_id, user.user_id);
+    }
+
+    // 같은 묶음을 다시 보내도 한 번만 반영됨
+    let req = test::TestRequest::post()
+        .uri("/api/sync")
+        .set_json(json!({ "events": events[..2] }))
+        .to_request();
+    let response: SyncResponse = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(response.duplicates, 2);
+    assert_eq!(state.stamp_history.entries("a").len(), 2);
+}
+
+#[actix_web::test]
+async fn offline_event_is_inserted_before_online_entries_with_a_larger_seq() {
+    common::setup();
+    let config: Config = serde_json::from_value(json!({ "staff_token": "secret" })).unwrap();
+    let state = AppState::new(config, common::stamp_list(vec![common::stamp("a")]));
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    let mut users = Vec::new();
+    for name in ["offline", "online"] {
+        let req = test::TestRequest::post()
+            .uri("/login")
+            .set_json(json!({ "user_name": name }))
+            .to_request();
+        users.push(test::call_and_read_body_json::<_, _, User>(&app, req).await);
+    }
+    common::collect(&app, &users[1].user_id, "a").await;
+    let online_seq = state.stamp_history.entries("a")[0].seq;
+
+    // 온라인 기록보다 먼저 찍혔지만 나중에 동기화된 기록
+    let earlier = (Utc::now() - Duration::minutes(30)).to_rfc3339();
+    let events = vec![event(users[0].user_code.as_deref().unwrap(), "a", &earlier)];
+    let req = test::TestRequest::post()
+        .uri("/api/sync")
+        .set_json(json!({ "events": events }))
+        .to_request();
+    let response: SyncResponse = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(response.accepted, 1);
+
+    // 목록은 시각 순서, 순번은 반영 순서
+    let mut entries = state.stamp_history.entries("a");
+    assert_eq!(entries[0].user_id, users[0].user_id);
+    assert_eq!(entries[1].user_id, users[1].user_id);
+    assert_eq!(entries[0].seq, response.results[0].seq.unwrap());
+    assert!(entries[0].seq > online_seq);
+    entries.sort_by_key(|entry| entry.seq);
+    assert_eq!(entries[0].user_id, users[1].user_id);
+}
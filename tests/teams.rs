@@ -0,0 +1,87 @@
+mod common;
+
+use actix_web::{http::StatusCode, test, App};
+use gj_stamptour::{
+    handlers::routes,
+    state::User,
+    teams::{TeamLeaderboardEntry, TeamProgress},
+};
+use serde_json::json;
+
+#[actix_web::test]
+async fn team_members_share_progress() {
+    let state = common::test_state();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/login")
+        .set_json(json!({ "user_name": "leader", "team_name": "3학년 2반" }))
+        .to_request();
+    let leader: User = test::call_and_read_body_json(&app, req).await;
+    let team_code = leader.team_code.clone().unwrap();
+    assert_eq!(team_code.len(), 6);
+
+    let req = test::TestRequest::post()
+        .uri("/login")
+        .set_json(json!({ "user_name": "member", "team_code": team_code.to_lowercase() }))
+        .to_request();
+    let member: User = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(member.team_code.as_ref(), Some(&team_code));
+
+    common::collect(&app, &leader.user_id, "a").await;
+    common::collect(&app, &member.user_id, "b").await;
+    common::collect(&app, &member.user_id, "c").await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/teams/{}", team_code))
+        .to_request();
+    let progress: TeamProgress = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(progress.team_name, "3학년 2반");
+    assert_eq!(progress.member_count, 2);
+    assert_eq!(progress.collected_count, 3);
+    assert_eq!(progress.tier.as_deref(), Some("complete"));
+
+    assert_eq!(
+        state.teams.lock().unwrap().teams[&team_code]
+            .completions
+            .len(),
+        1
+    );
+
+    let req = test::TestRequest::get()
+        .uri("/api/teams/leaderboard")
+        .to_request();
+    let entries: Vec<TeamLeaderboardEntry> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].points, 3);
+}
+
+#[actix_web::test]
+async fn unknown_team_code_is_rejected() {
+    let state = common::test_state();
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/login")
+        .set_json(json!({ "user_name": "lost", "team_code": "NOPE42" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    assert!(state.user_list.lock().unwrap().users.is_empty());
+
+    let req = test::TestRequest::get()
+        .uri("/api/teams/NOPE42")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
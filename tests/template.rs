@@ -0,0 +1,39 @@
+use gj_stamptour::{state::StampList, template::render};
+
+#[test]
+fn stamp_list_accepts_optional_content() {
+    let stamp_list: StampList = serde_json::from_str(
+        r#"{"stampList": [
+            {"stampId": "a", "stampLocation": "1층", "stampName": "A", "stampDesc": ""},
+            {"stampId": "b", "stampLocation": "2층", "stampName": "B", "stampDesc": "",
+             "stampTemplate": "sponsor.html",
+             "stampContent": {"image": "/img/b.png", "sponsorMessage": "감사합니다", "nextHint": "3층으로"}}
+        ]}"#,
+    )
+    .unwrap();
+
+    let b = stamp_list
+        .stampList
+        .iter()
+        .find(|s| s.stampId == "b")
+        .unwrap();
+    assert_eq!(b.stampTemplate.as_deref(), Some("sponsor.html"));
+    assert_eq!(b.stampContent.as_ref().unwrap().nextHint, "3층으로");
+
+    let a = stamp_list
+        .stampList
+        .iter()
+        .find(|s| s.stampId == "a")
+        .unwrap();
+    assert!(a.stampTemplate.is_none());
+    assert!(a.stampContent.is_none());
+}
+
+#[test]
+fn render_replaces_every_occurrence() {
+    let html = render(
+        "%STAMP_ID% / %STAMP_ID% / %NEXT_HINT% / %UNKNOWN%",
+        &[("STAMP_ID", "a"), ("NEXT_HINT", "")],
+    );
+    assert_eq!(html, "a / a /  / %UNKNOWN%");
+}
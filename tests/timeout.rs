@@ -0,0 +1,117 @@
+mod common;
+
+use actix_web::{http::StatusCode, middleware::from_fn, test, web, App, HttpResponse};
+use gj_stamptour::{
+    config::{Config, ServerConfig},
+    handlers::routes,
+    state::AppState,
+    storage::read_file,
+    timeout::apply_timeout,
+};
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    process::Command,
+    thread,
+    time::{Duration, Instant},
+};
+
+#[actix_web::test]
+async fn slow_handlers_get_503() {
+    common::setup();
+    let config = Config {
+        server: ServerConfig {
+            api_timeout_secs: 1,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let state = AppState::new(config, common::stamp_list(vec![common::stamp("a")]));
+    let app = test::init_service(
+        App::new()
+            .wrap(from_fn(apply_timeout))
+            .configure(|cfg| state.register(cfg))
+            .route(
+                "/api/v1/slow",
+                web::get().to(|| async {
+                    actix_rt::time::sleep(Duration::from_secs(3)).await;
+                    HttpResponse::Ok().finish()
+                }),
+            )
+            .configure(routes),
+    )
+    .await;
+
+    // 503 응답은 오류로 반환되며 actix가 그 응답을 그대로 보냄
+    let req = test::TestRequest::get().uri("/api/v1/slow").to_request();
+    let Err(err) = test::try_call_service(&app, req).await else {
+        panic!("slow request did not time out");
+    };
+    let resp = err.error_response();
+    assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert!(resp.headers().contains_key("retry-after"));
+
+    let req = test::TestRequest::get().uri("/api/v1/event").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn slow_file_reads_do_not_block_the_worker() {
+    // 쓰는 쪽이 열 때까지 읽기가 멈추는 named pipe로 멈춘 디스크를 흉내 냄
+    let fifo = common::setup().join("slow-read.html");
+    let _ = std::fs::remove_file(&fifo);
+    assert!(Command::new("mkfifo")
+        .arg(&fifo)
+        .status()
+        .unwrap()
+        .success());
+    let writer = {
+        let fifo = fifo.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_secs(3));
+            let mut pipe = OpenOptions::new().write(true).open(&fifo).unwrap();
+            pipe.write_all(b"late").unwrap();
+        })
+    };
+
+    let config = Config {
+        server: ServerConfig {
+            api_timeout_secs: 1,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let state = AppState::new(config, common::stamp_list(vec![common::stamp("a")]));
+    let app = test::init_service(
+        App::new()
+            .wrap(from_fn(apply_timeout))
+            .configure(|cfg| state.register(cfg))
+            .route(
+                "/api/v1/slow-file",
+                web::get().to(move || {
+                    let fifo = fifo.clone();
+                    async move {
+                        let contents = read_file(&fifo).await.unwrap_or_default();
+                        HttpResponse::Ok().body(contents)
+                    }
+                }),
+            ),
+    )
+    .await;
+
+    // 작업자 스레드가 읽기를 기다리지 않으므로 제한 시간에 503 응답을 보냄
+    let started = Instant::now();
+    let req = test::TestRequest::get()
+        .uri("/api/v1/slow-file")
+        .to_request();
+    let Err(err) = test::try_call_service(&app, req).await else {
+        panic!("slow read did not time out");
+    };
+    assert_eq!(
+        err.error_response().status(),
+        StatusCode::SERVICE_UNAVAILABLE
+    );
+    assert!(started.elapsed() < Duration::from_secs(3));
+    writer.join().unwrap();
+}
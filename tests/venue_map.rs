@@ -0,0 +1,61 @@
+mod common;
+
+use actix_web::{cookie::Cookie, http::StatusCode, test, App};
+use gj_stamptour::{bundle::event_path, handlers::routes, storage::resources_dir};
+use std::fs;
+
+#[actix_web::test]
+async fn map_highlights_collected_booths() {
+    let state = common::test_state();
+    common::register(&state, "u1", "visitor");
+    let app = test::init_service(
+        App::new()
+            .configure(|cfg| state.register(cfg))
+            .configure(routes),
+    )
+    .await;
+
+    // 기본 지도가 없으면 404
+    let _ = fs::remove_file(event_path("map.svg"));
+    let req = test::TestRequest::get().uri("/map.svg").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    fs::create_dir_all(resources_dir()).unwrap();
+    fs::write(
+        event_path("map.svg"),
+        r#"<?xml version="1.0"?>
+<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 100 100">
+<rect width="100" height="100" fill="white"/>
+<circle data-stamp-id="a" class="booth" cx="10" cy="10" r="5"/>
+<circle data-stamp-id="b" cx="30" cy="10" r="5"/>
+<text x="10" y="50">A &amp; B</text>
+</svg>"#,
+    )
+    .unwrap();
+    common::collect(&app, "u1", "a").await;
+
+    let req = test::TestRequest::get()
+        .uri("/map.svg")
+        .cookie(Cookie::new("user_id", "u1"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers().get("content-type").unwrap(), "image/svg+xml");
+    assert_eq!(
+        resp.headers().get("cache-control").unwrap(),
+        "private, no-cache"
+    );
+    let map = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+    assert!(
+        map.contains(r##"class="booth visited" cx="10" cy="10" data-stamp-id="a" fill="#16a34a""##)
+    );
+    assert!(map.contains(r##"class="unvisited" cx="30" cy="10" data-stamp-id="b" fill="#9ca3af""##));
+    assert!(map.contains(r#"<rect fill="white" height="100" width="100"/>"#));
+    assert!(map.contains("A &amp; B</text>"));
+
+    // 로그인하지 않으면 모든 부스를 방문하지 않은 색으로 표시
+    let req = test::TestRequest::get().uri("/map.svg").to_request();
+    let map = String::from_utf8(test::call_and_read_body(&app, req).await.to_vec()).unwrap();
+    assert!(!map.contains("\"visited\"") && !map.contains(" visited\""));
+}